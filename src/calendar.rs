@@ -0,0 +1,190 @@
+//! Calendar feed (iCalendar) of focus blocks, so that they show up inside calendar apps.
+//!
+//! Recorded focus blocks are taken from the history. Planned focus blocks are projected from the
+//! running timer and the configured timer lengths until the end of the current cycle - they are
+//! marked as tentative, because they move as soon as the timer is paused or skipped.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, Utc};
+use zentime_rs_timer::config::PomodoroTimerConfig;
+use zentime_rs_timer::pomodoro_timer::ViewState;
+use zentime_rs_timer::util::time_to_seconds;
+
+use crate::history::{SessionKind, SessionOutcome, SessionRecord};
+
+/// State of a focus block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FocusBlockStatus {
+    /// The interval has run out
+    Completed,
+
+    /// The interval has been skipped or reset before it ran out
+    Cut,
+
+    /// The interval is ahead (or currently running)
+    Planned,
+}
+
+/// Time span during which the user has been (or will be) focusing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FocusBlock {
+    /// Start of the block
+    pub start: SystemTime,
+
+    /// End of the block
+    pub end: SystemTime,
+
+    /// Pomodoro round of the block
+    pub round: u64,
+
+    /// State of the block
+    pub status: FocusBlockStatus,
+}
+
+impl FocusBlock {
+    /// Title of the calendar event
+    fn summary(&self) -> &'static str {
+        match self.status {
+            FocusBlockStatus::Completed => "Focus",
+            FocusBlockStatus::Cut => "Focus (cut short)",
+            FocusBlockStatus::Planned => "Focus (planned)",
+        }
+    }
+}
+
+/// Focus blocks of all recorded intervals.
+/// Blocks are placed by the time the interval has ended, therefore pauses are not part of them.
+pub fn recorded_blocks<'a>(
+    records: impl IntoIterator<Item = &'a SessionRecord>,
+) -> Vec<FocusBlock> {
+    records
+        .into_iter()
+        .filter(|record| record.kind == SessionKind::Interval && record.elapsed_seconds > 0)
+        .map(|record| {
+            let end = UNIX_EPOCH + Duration::from_secs(record.ended_at);
+
+            FocusBlock {
+                start: end - Duration::from_secs(record.elapsed_seconds),
+                end,
+                round: record.round,
+                status: match record.outcome {
+                    SessionOutcome::Completed => FocusBlockStatus::Completed,
+                    _ => FocusBlockStatus::Cut,
+                },
+            }
+        })
+        .collect()
+}
+
+/// Focus blocks, which lie ahead until the end of the current cycle, if the timer keeps running.
+/// A paused timer (or a cycle on hold) has no plan.
+pub fn planned_blocks(
+    state: &ViewState,
+    config: &PomodoroTimerConfig,
+    now: SystemTime,
+) -> Vec<FocusBlock> {
+    let Some(remaining) = time_to_seconds(&state.time) else {
+        return vec![];
+    };
+
+    if state.is_paused || state.hold.is_some() {
+        return vec![];
+    }
+
+    let last_round = config.last_round_of_cycle(state.round);
+    let mut blocks = vec![];
+    let mut time = now + Duration::from_secs(remaining);
+
+    if state.is_break {
+        // The cycle ends with its long break
+        if state.round == last_round {
+            return blocks;
+        }
+    } else {
+        // Postponed breaks extend the current interval
+        blocks.push(FocusBlock {
+            start: now,
+            end: time,
+            round: state.round,
+            status: FocusBlockStatus::Planned,
+        });
+
+        if state.round == last_round {
+            return blocks;
+        }
+
+        time += Duration::from_secs(config.minor_break);
+    }
+
+    for round in state.round + 1..=last_round {
+        let start = time;
+        time += Duration::from_secs(config.timer);
+
+        blocks.push(FocusBlock {
+            start,
+            end: time,
+            round,
+            status: FocusBlockStatus::Planned,
+        });
+
+        time += Duration::from_secs(config.minor_break);
+    }
+
+    blocks
+}
+
+/// Renders the given blocks as iCalendar document (RFC 5545)
+pub fn to_ics(blocks: &[FocusBlock], now: SystemTime) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//zentime//focus blocks//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "X-WR-CALNAME:Zentime focus".to_string(),
+    ];
+
+    for block in blocks {
+        let status = match block.status {
+            FocusBlockStatus::Planned => "TENTATIVE",
+            FocusBlockStatus::Completed | FocusBlockStatus::Cut => "CONFIRMED",
+        };
+
+        lines.extend([
+            "BEGIN:VEVENT".to_string(),
+            // Planned blocks get a new identity whenever the plan changes
+            format!(
+                "UID:{}-{}-{}@zentime",
+                if block.status == FocusBlockStatus::Planned {
+                    "planned"
+                } else {
+                    "focus"
+                },
+                utc_timestamp(block.start),
+                block.round
+            ),
+            format!("DTSTAMP:{}", utc_timestamp(now)),
+            format!("DTSTART:{}", utc_timestamp(block.start)),
+            format!("DTEND:{}", utc_timestamp(block.end)),
+            format!("SUMMARY:{}", block.summary()),
+            format!("DESCRIPTION:Round {}", block.round),
+            format!("STATUS:{}", status),
+            "TRANSP:OPAQUE".to_string(),
+            "END:VEVENT".to_string(),
+        ]);
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+
+    // Lines are separated by CRLF, including the last one
+    let mut ics = lines.join("\r\n");
+    ics.push_str("\r\n");
+    ics
+}
+
+/// Formats a point in time as UTC date-time (e.g. `20240517T083000Z`)
+fn utc_timestamp(time: SystemTime) -> String {
+    DateTime::<Utc>::from(time)
+        .format("%Y%m%dT%H%M%SZ")
+        .to_string()
+}
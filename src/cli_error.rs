@@ -0,0 +1,97 @@
+//! Errors of the zentime command line, which are reported to the user together with a hint how
+//! to fix them (instead of panicking with a backtrace)
+
+use std::process;
+
+use thiserror::Error;
+
+use crate::subcommands::server::daemon_output_paths;
+
+/// Error which ends a zentime command
+#[derive(Debug, Error)]
+pub enum CliError {
+    /// The config file could not be read or contains invalid values
+    #[error("Could not load the config {path}")]
+    Config {
+        /// Path of the config file
+        path: String,
+
+        /// What is wrong with the config
+        #[source]
+        source: Box<figment::Error>,
+    },
+
+    /// The zentime server could not be started
+    #[error("Could not start the zentime server")]
+    ServerSpawn(#[source] anyhow::Error),
+
+    /// No connection to the zentime server could be established
+    #[error("Could not connect to the zentime server")]
+    Connect(#[source] anyhow::Error),
+
+    /// A message could not be exchanged with the zentime server
+    #[error("Could not communicate with the zentime server")]
+    Communication(#[source] anyhow::Error),
+
+    /// Anything else, which has no specific hint
+    #[error("{0}")]
+    Other(String),
+}
+
+impl CliError {
+    /// Error for a config file, which could not be loaded
+    pub fn config(path: &str, source: figment::Error) -> Self {
+        Self::Config {
+            path: path.to_string(),
+            source: Box::new(source),
+        }
+    }
+
+    /// Suggests how the user might fix the error
+    pub fn hint(&self) -> Option<String> {
+        match self {
+            CliError::Config { .. } => Some(
+                "Check the key mentioned above for typos - all available options are listed \
+                inside zentime.example.toml"
+                    .to_string(),
+            ),
+            CliError::ServerSpawn(_) => {
+                let (_, stderr_path) = daemon_output_paths();
+
+                Some(format!(
+                    "The server writes its errors to {} - `zentime log` shows its log",
+                    stderr_path
+                ))
+            }
+            CliError::Connect(_) => Some(
+                "Check if the server is running via `zentime server status` - if it is stuck, \
+                restart it via `zentime server stop`"
+                    .to_string(),
+            ),
+            CliError::Communication(_) => Some(
+                "The server might run a different version of zentime - restart it via \
+                `zentime server stop`"
+                    .to_string(),
+            ),
+            CliError::Other(_) => None,
+        }
+    }
+
+    /// Prints the error, its causes and a hint to stderr and exits with an error code
+    pub fn exit(&self) -> ! {
+        eprintln!("error: {}", self);
+
+        let mut source = std::error::Error::source(self);
+
+        while let Some(cause) = source {
+            eprintln!("  caused by: {}", cause);
+            source = cause.source();
+        }
+
+        if let Some(hint) = self.hint() {
+            eprintln!("\nhint: {}", hint);
+        }
+
+        process::exit(1)
+    }
+}
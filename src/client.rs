@@ -1,9 +1,24 @@
 //! Code related to zentime terminal clients (e.g. async connection handling, terminal io etc.)
+//! The terminal interface itself is only available with the `tui` feature.
 
+#[cfg(feature = "tui")]
 mod connection;
+#[cfg(feature = "tui")]
+mod event_commands;
+#[cfg(feature = "tui")]
+mod optimistic_view;
 
+pub mod interpolation;
 pub mod one_shot_connection;
+pub mod overlay;
+pub mod pause;
+#[cfg(feature = "tui")]
 pub mod start;
+#[cfg(feature = "tui")]
 pub mod terminal_io;
+pub mod timer_event;
+#[cfg(all(feature = "tray", target_os = "linux"))]
+pub mod tray;
 
-pub use start::start;
+#[cfg(feature = "tui")]
+pub use start::{start, start_at};
@@ -1,21 +1,33 @@
 use crate::client::terminal_io::input::ClientInputAction;
-use std::thread::sleep;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
+use crate::config::{Config, EventCommands};
+use crate::ipc::ClientPreferences;
 use crate::ipc::ClientToServerMsg;
-use crate::ipc::InterProcessCommunication;
 use crate::ipc::ServerToClientMsg;
+use crate::ipc::{
+    heartbeat_interval, Connection, ConnectionWriter, IncompatibleServer, IpcWriter, Role,
+    ServerAddress,
+};
 use anyhow::Context;
-use interprocess::local_socket::tokio::OwnedWriteHalf;
+use log::info;
 
-use crate::ipc::get_socket_name;
-use futures::io::BufReader;
-use interprocess::local_socket::tokio::LocalSocketStream;
+use futures::future::pending;
 use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
 use tokio::task::JoinHandle;
+use tokio::time::{sleep, sleep_until};
 use tokio::{select, task::yield_now};
 
-use super::terminal_io::terminal_event::TerminalEvent;
+use super::event_commands::EventCommandRunner;
+use super::interpolation::Interpolation;
+use super::optimistic_view::OptimisticView;
+use super::terminal_io::terminal_event::{ConnectionStatus, Prompt, PromptKind, TerminalEvent};
+
+/// Delay before the first attempt to reconnect after the connection to the server has been lost
+const INITIAL_RECONNECT_DELAY: Duration = Duration::from_millis(100);
+
+/// Upper limit of the (exponentially increasing) delay between two reconnection attempts
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(5);
 
 /// Tokio task handling the connection between the client and the zentime server
 pub struct ClientConnectionTask {}
@@ -24,9 +36,10 @@ impl ClientConnectionTask {
     pub async fn spawn(
         terminal_in_rx: UnboundedReceiver<ClientInputAction>,
         terminal_out_tx: UnboundedSender<TerminalEvent>,
+        config: Config,
+        event_commands: EventCommands,
+        address: ServerAddress,
     ) -> JoinHandle<()> {
-        let socket_name = get_socket_name();
-
         let mut connection_tries = 0;
 
         // Try to receive a connection to the server (will timeout after the third attempt)
@@ -42,70 +55,324 @@ impl ClientConnectionTask {
                     .expect("Could not send to terminal out");
             }
 
-            let result = LocalSocketStream::connect(socket_name).await;
+            let result = address.connect().await;
 
-            if let Ok(conn) = result {
-                break conn;
-            } else {
-                sleep(Duration::from_millis(200));
+            match result {
+                Ok(conn) => break conn,
+                // Retrying would not help, because the server stays the same until it is restarted
+                Err(error) if error.is::<IncompatibleServer>() => {
+                    terminal_out_tx
+                        .send(incompatible_server(&error))
+                        .expect("Could not send to terminal out");
+
+                    return tokio::spawn(async {});
+                }
+                Err(error) => {
+                    info!("Could not connect to server: {:#}", error);
+                    // The server might still be starting up - without blocking the runtime, so
+                    // that the terminal keeps responding in the meantime
+                    sleep(Duration::from_millis(200)).await;
+                }
             }
         };
 
-        tokio::spawn(async move {
-            if let Err(error) =
-                handle_connection(connection, terminal_out_tx.clone(), terminal_in_rx).await
-            {
+        let update_interval = config.view.update_interval;
+
+        tokio::spawn(supervise(
+            connection,
+            update_interval,
+            address,
+            terminal_in_rx,
+            terminal_out_tx,
+            OptimisticView::new(config),
+            EventCommandRunner::new(event_commands),
+        ))
+    }
+}
+
+/// Handles the connection to the server and reconnects (with exponential backoff), whenever
+/// the connection is lost.
+async fn supervise(
+    mut connection: Connection,
+    update_interval: u64,
+    address: ServerAddress,
+    mut terminal_in_rx: UnboundedReceiver<ClientInputAction>,
+    terminal_out_tx: UnboundedSender<TerminalEvent>,
+    mut view: OptimisticView,
+    mut event_commands: EventCommandRunner,
+) {
+    let mut interpolation = Interpolation::new();
+
+    loop {
+        // This only returns once the connection has been lost or the server has shut down,
+        // because a quitting client terminates the whole process
+        if let Err(error) = handle_connection(
+            connection,
+            update_interval,
+            &terminal_out_tx,
+            &mut terminal_in_rx,
+            &mut view,
+            &mut interpolation,
+            &mut event_commands,
+        )
+        .await
+        {
+            info!("Lost connection to server: {}", error);
+        }
+
+        connection = match reconnect(&address, &terminal_out_tx, &mut terminal_in_rx).await {
+            Some(connection) => connection,
+            None => return,
+        };
+
+        terminal_out_tx
+            .send(TerminalEvent::Connection(ConnectionStatus::Connected))
+            .expect("Could not send to terminal out");
+    }
+}
+
+/// Tries to reconnect to the server until it succeeds or the user quits the client.
+/// Returns [None] if the user has quit the client.
+async fn reconnect(
+    address: &ServerAddress,
+    terminal_out_tx: &UnboundedSender<TerminalEvent>,
+    terminal_in_rx: &mut UnboundedReceiver<ClientInputAction>,
+) -> Option<Connection> {
+    let mut delay = INITIAL_RECONNECT_DELAY;
+    let mut attempt = 1;
+
+    loop {
+        terminal_out_tx
+            .send(TerminalEvent::Connection(ConnectionStatus::Reconnecting {
+                attempt,
+            }))
+            .expect("Could not send to terminal out");
+
+        select! {
+            _ = sleep(delay) => {},
+            action = terminal_in_rx.recv() => {
+                // Without a server there is nothing to control, but the user should still be
+                // able to suspend or leave the client
+                let event = match action {
+                    Some(ClientInputAction::Suspend) => Some(TerminalEvent::Suspend),
+                    Some(ClientInputAction::Resume) => Some(TerminalEvent::Resume),
+                    _ => None,
+                };
+
+                if let Some(event) = event {
+                    terminal_out_tx.send(event).expect("Could not send to terminal out");
+                }
+
+                if let Some(ClientInputAction::Quit | ClientInputAction::Detach) | None = action {
+                    terminal_out_tx
+                        .send(TerminalEvent::Quit {
+                            msg: Some(String::from("Server connection closed.")),
+                            error: false,
+                        })
+                        .expect("Could not send to terminal out");
+
+                    return None;
+                }
+            }
+        }
+
+        match address.connect().await {
+            Ok(connection) => return Some(connection),
+            // The server has been replaced by another version of zentime in the meantime
+            Err(error) if error.is::<IncompatibleServer>() => {
                 terminal_out_tx
-                    .send(TerminalEvent::Quit {
-                        msg: Some(format!("{}.\nServer connection closed.", error)),
-                        error: true,
-                    })
+                    .send(incompatible_server(&error))
                     .expect("Could not send to terminal out");
+
+                return None;
             }
-        })
+            Err(_) => {}
+        }
+
+        delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+        attempt += 1;
+    }
+}
+
+/// Quits the client, because the server speaks another version of the protocol
+fn incompatible_server(error: &anyhow::Error) -> TerminalEvent {
+    TerminalEvent::Quit {
+        msg: Some(format!(
+            "\n{}\nRestart the server via `zentime server restart`",
+            error
+        )),
+        error: true,
     }
 }
 
 /// Continously handle the connection to the server by reacting to incoming
 /// [ServerToClientMsg] and terminal input events.
 async fn handle_connection(
-    connection: LocalSocketStream,
-    terminal_out_tx: UnboundedSender<TerminalEvent>,
-    mut terminal_in_rx: UnboundedReceiver<ClientInputAction>,
+    connection: Connection,
+    update_interval: u64,
+    terminal_out_tx: &UnboundedSender<TerminalEvent>,
+    terminal_in_rx: &mut UnboundedReceiver<ClientInputAction>,
+    view: &mut OptimisticView,
+    interpolation: &mut Interpolation,
+    event_commands: &mut EventCommandRunner,
 ) -> anyhow::Result<()> {
-    // This consumes our connection and splits it into two halves,
-    // so that we could concurrently act on both.
-    let (reader, mut writer) = connection.into_split();
-    let mut reader = BufReader::new(reader);
+    let Connection {
+        mut reader,
+        mut writer,
+        role,
+        ..
+    } = connection;
+
+    // The remaining time is counted down locally in between two updates of the server
+    if update_interval > 0 {
+        let preferences = ClientPreferences {
+            resync_seconds: update_interval,
+            ..ClientPreferences::default()
+        };
+
+        writer
+            .send(&ClientToServerMsg::Hello(preferences))
+            .await
+            .context("Could not send client preferences")?;
+    }
+
+    // Request the current state, so that the display is restored after a reconnect
+    writer
+        .send(&ClientToServerMsg::Sync)
+        .await
+        .context("Could not sync with server")?;
+
+    let mut heartbeat = heartbeat_interval();
+
+    // Action which gives up the current interval and waits for the user to confirm it
+    let mut contract_breach: Option<ClientInputAction> = None;
 
     loop {
         select! {
-            msg = InterProcessCommunication::recv_ipc_message::<ServerToClientMsg>(&mut reader) => {
+            msg = reader.recv::<ServerToClientMsg>() => {
                 let msg = msg.context("Could not receive message from socket")?;
-                handle_server_to_client_msg(msg, &terminal_out_tx).context("Could not handle server to client message")?;
+
+                // The server closes the connection right afterwards, which is no error
+                if let ServerToClientMsg::ShuttingDown = msg {
+                    info!("Server is shutting down");
+                    return Ok(());
+                }
+
+                handle_server_to_client_msg(msg, terminal_out_tx, view, interpolation, event_commands).context("Could not handle server to client message")?;
+            },
+            _ = until(view.deadline()) => {
+                // The server did not confirm our prediction, therefore we fall back to its state
+                if let Some(state) = view.expire() {
+                    terminal_out_tx
+                        .send(TerminalEvent::View(state))
+                        .context("Could not send to terminal out")?;
+                }
+            },
+            // Predictions are displayed as they are until the server has confirmed them
+            _ = until(interpolation.deadline().filter(|_| view.deadline().is_none())) => {
+                if let Some(state) = interpolation.tick(Instant::now()) {
+                    terminal_out_tx
+                        .send(TerminalEvent::View(state))
+                        .context("Could not send to terminal out")?;
+                }
+            },
+            _ = heartbeat.tick() => {
+                writer
+                    .send(&ClientToServerMsg::Heartbeat)
+                    .await
+                    .context("Could not send heartbeat")?;
             },
             value = terminal_in_rx.recv() => {
                 if let Some(action) = value {
-                    handle_client_input_action(action, &terminal_out_tx, &mut writer).await.context("Could not handle input action")?;
+                    // Only the owner may shut down the server - team participants just leave
+                    let action = match action {
+                        ClientInputAction::Quit if role != Role::Owner => ClientInputAction::Detach,
+                        // A paused timer stays paused, while the reason is being typed
+                        ClientInputAction::Interrupt if view.is_running() => ClientInputAction::PlayPause,
+                        ClientInputAction::Interrupt => ClientInputAction::None,
+                        ClientInputAction::NextProfile => match view.next_profile() {
+                            Some(profile) => ClientInputAction::SwitchProfile(profile),
+                            None => ClientInputAction::None,
+                        },
+                        ClientInputAction::NextTimer => match view.next_timer() {
+                            Some(timer) => ClientInputAction::SelectTimer(timer),
+                            None => ClientInputAction::None,
+                        },
+                        // An enforced break has to be taken in full
+                        ClientInputAction::Skip | ClientInputAction::PostPone | ClientInputAction::Previous if view.is_break_enforced() => ClientInputAction::None,
+                        ClientInputAction::AdjustTime(seconds) if seconds < 0 && view.is_break_enforced() => ClientInputAction::None,
+                        action => action,
+                    };
+
+                    let action = match (contract_breach.take(), action) {
+                        // Any key other than `y` keeps the interval running
+                        (Some(breach), action) => {
+                            terminal_out_tx
+                                .send(TerminalEvent::Prompt(None))
+                                .context("Could not send to terminal out")?;
+
+                            match action {
+                                ClientInputAction::Confirm => breach,
+                                ClientInputAction::Suspend | ClientInputAction::Resume => action,
+                                _ => ClientInputAction::None,
+                            }
+                        }
+                        (None, action) if view.breaks_focus_contract(&action) => {
+                            terminal_out_tx
+                                .send(TerminalEvent::Prompt(Some(Prompt::new(PromptKind::FocusContract))))
+                                .context("Could not send to terminal out")?;
+
+                            contract_breach = Some(action);
+                            ClientInputAction::None
+                        }
+                        (None, action) => action,
+                    };
+
+                    // Display the expected outcome right away instead of waiting for the server.
+                    // Viewers can't change the timer, therefore there is nothing to predict.
+                    if role != Role::Viewer {
+                        if let Some(predicted) = view.predict(&action) {
+                            terminal_out_tx
+                                .send(TerminalEvent::View(predicted))
+                                .context("Could not send to terminal out")?;
+                        }
+                    }
+
+                    handle_client_input_action(action, terminal_out_tx, &mut writer).await.context("Could not handle input action")?;
                 }
             }
         };
 
+        if let Some(enforced) = view.enforcement_change() {
+            terminal_out_tx
+                .send(TerminalEvent::EnforcedBreak(enforced))
+                .context("Could not send to terminal out")?;
+        }
+
         yield_now().await;
     }
 }
 
+/// Waits until the given deadline has been reached (or forever, if there is none)
+async fn until(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => sleep_until(deadline.into()).await,
+        None => pending().await,
+    }
+}
+
 /// Handle incoming [ClientInputAction]s
 async fn handle_client_input_action(
     action: ClientInputAction,
     terminal_out_tx: &UnboundedSender<TerminalEvent>,
-    writer: &mut OwnedWriteHalf,
+    writer: &mut IpcWriter<ConnectionWriter>,
 ) -> anyhow::Result<()> {
     match action {
         // Command server to shutdown and quit the current client
         ClientInputAction::Quit => {
             let msg = ClientToServerMsg::Quit;
-            InterProcessCommunication::send_ipc_message(msg, writer)
+            writer
+                .send(&msg)
                 .await
                 .context("Could not send IPC message")?;
 
@@ -121,7 +388,8 @@ async fn handle_client_input_action(
         // Quit the current client (but keep the server running)
         ClientInputAction::Detach => {
             let msg = ClientToServerMsg::Detach;
-            InterProcessCommunication::send_ipc_message(msg, writer)
+            writer
+                .send(&msg)
                 .await
                 .context("Could not send IPC message")?;
 
@@ -137,19 +405,80 @@ async fn handle_client_input_action(
         // Postpone the current break, if possible (validation happens inside the
         // [PomodoroTimer] run by the server itself)
         ClientInputAction::PostPone => {
-            let msg = ClientToServerMsg::PostPone;
-            InterProcessCommunication::send_ipc_message(msg, writer)
+            let msg = ClientToServerMsg::PostPone { seconds: None };
+            writer
+                .send(&msg)
                 .await
                 .context("Could not send IPC message")?;
         }
 
-        // NoOp
-        ClientInputAction::None => return Ok(()),
+        // Extend or shorten the current interval or break
+        ClientInputAction::AdjustTime(seconds) => {
+            let msg = ClientToServerMsg::AdjustTime(seconds);
+            writer
+                .send(&msg)
+                .await
+                .context("Could not send IPC message")?;
+        }
+
+        // NoOp (interrupts have already been converted into play/pause, task prompts are
+        // handled by the terminal input, the next profile/timer has already been looked up and
+        // confirmations have already been resolved)
+        ClientInputAction::None
+        | ClientInputAction::Confirm
+        | ClientInputAction::Interrupt
+        | ClientInputAction::LabelTask
+        | ClientInputAction::NextProfile
+        | ClientInputAction::NextTimer => return Ok(()),
+
+        // Command the server to display and control another timer
+        ClientInputAction::SelectTimer(timer) => {
+            let msg = ClientToServerMsg::SelectTimer(timer);
+            writer
+                .send(&msg)
+                .await
+                .context("Could not send IPC message")?;
+        }
+
+        // Command the server to switch to another timer profile
+        ClientInputAction::SwitchProfile(profile) => {
+            let msg = ClientToServerMsg::SwitchProfile(profile);
+            writer
+                .send(&msg)
+                .await
+                .context("Could not send IPC message")?;
+        }
+
+        // Display the text, which is currently being typed
+        ClientInputAction::Prompt(prompt) => {
+            terminal_out_tx
+                .send(TerminalEvent::Prompt(prompt))
+                .context("Could not send to terminal out")?;
+        }
+
+        // Record why the timer has been paused
+        ClientInputAction::PauseReason(reason) => {
+            let msg = ClientToServerMsg::PauseReason { reason };
+            writer
+                .send(&msg)
+                .await
+                .context("Could not send IPC message")?;
+        }
+
+        // Label the current session
+        ClientInputAction::SetTask(task) => {
+            let msg = ClientToServerMsg::SetTask { task };
+            writer
+                .send(&msg)
+                .await
+                .context("Could not send IPC message")?;
+        }
 
         // Command the server to pause or play the timer
         ClientInputAction::PlayPause => {
             let msg = ClientToServerMsg::PlayPause;
-            InterProcessCommunication::send_ipc_message(msg, writer)
+            writer
+                .send(&msg)
                 .await
                 .context("Could not send IPC message")?;
         }
@@ -157,17 +486,64 @@ async fn handle_client_input_action(
         // Command the server to skip to the next interval
         ClientInputAction::Skip => {
             let msg = ClientToServerMsg::Skip;
-            InterProcessCommunication::send_ipc_message(msg, writer)
+            writer
+                .send(&msg)
                 .await
                 .context("Could not send IPC message")?;
         }
 
         ClientInputAction::Reset => {
             let msg = ClientToServerMsg::Reset;
-            InterProcessCommunication::send_ipc_message(msg, writer)
+            writer
+                .send(&msg)
                 .await
                 .context("Could not send IPC message")?;
         }
+
+        // Command the server to go back to the previous interval or break
+        ClientInputAction::Previous => {
+            let msg = ClientToServerMsg::Previous;
+            writer
+                .send(&msg)
+                .await
+                .context("Could not send IPC message")?;
+        }
+
+        ClientInputAction::RestartCurrent => {
+            let msg = ClientToServerMsg::RestartCurrent;
+            writer
+                .send(&msg)
+                .await
+                .context("Could not send IPC message")?;
+        }
+
+        // Command the server to start the long break right away
+        ClientInputAction::LongBreakNow => {
+            let msg = ClientToServerMsg::LongBreakNow;
+            writer
+                .send(&msg)
+                .await
+                .context("Could not send IPC message")?;
+        }
+
+        ClientInputAction::Suspend => {
+            terminal_out_tx
+                .send(TerminalEvent::Suspend)
+                .context("Could not send to terminal out")?;
+        }
+
+        // The countdown has continued while the client was stopped, therefore the current
+        // state is requested after the terminal has been set up again
+        ClientInputAction::Resume => {
+            terminal_out_tx
+                .send(TerminalEvent::Resume)
+                .context("Could not send to terminal out")?;
+
+            writer
+                .send(&ClientToServerMsg::Sync)
+                .await
+                .context("Could not sync with server")?;
+        }
     }
 
     Ok(())
@@ -178,13 +554,50 @@ async fn handle_client_input_action(
 fn handle_server_to_client_msg(
     msg: ServerToClientMsg,
     terminal_out_tx: &UnboundedSender<TerminalEvent>,
+    view: &mut OptimisticView,
+    interpolation: &mut Interpolation,
+    event_commands: &mut EventCommandRunner,
 ) -> anyhow::Result<()> {
     match msg {
         ServerToClientMsg::Timer(state) => {
+            // Only states of the server are considered, so that events are never run for
+            // predictions which don't come true
+            event_commands.handle(&state);
+            interpolation.receive(&state, Instant::now());
+
+            // Outdated states are dropped, while a prediction is waiting for confirmation
+            if let Some(state) = view.reconcile(state) {
+                terminal_out_tx
+                    .send(TerminalEvent::View(state))
+                    .context("Could not send to terminal out")?;
+            }
+        }
+        ServerToClientMsg::Snapshot(snapshot) => {
+            event_commands.handle(&snapshot.state);
+            interpolation.receive(&snapshot.state, Instant::now());
+
+            if let Some(state) = view.sync(snapshot) {
+                terminal_out_tx
+                    .send(TerminalEvent::View(state))
+                    .context("Could not send to terminal out")?;
+            }
+        }
+        ServerToClientMsg::Error { message, .. } => {
+            terminal_out_tx
+                .send(TerminalEvent::ServerError(message))
+                .context("Could not send to terminal out")?;
+        }
+        ServerToClientMsg::Bell { message } => {
             terminal_out_tx
-                .send(TerminalEvent::View(state))
+                .send(TerminalEvent::Bell(message))
                 .context("Could not send to terminal out")?;
         }
+        ServerToClientMsg::Pong
+        | ServerToClientMsg::Joined { .. }
+        | ServerToClientMsg::ConfigReloaded
+        | ServerToClientMsg::Clients(_)
+        | ServerToClientMsg::Welcome(_)
+        | ServerToClientMsg::ShuttingDown => {}
     }
 
     Ok(())
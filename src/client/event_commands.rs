@@ -0,0 +1,71 @@
+//! Runs the shell commands, which have been configured via `--exec-on-*` (see [EventCommands]),
+//! whenever the attached client observes the matching [TimerEvent]
+
+use std::process::Stdio;
+
+use log::{error, info};
+use zentime_rs_timer::pomodoro_timer::ViewState;
+
+use crate::client::timer_event::{TimerEvent, TimerEventDetector};
+use crate::config::EventCommands;
+use crate::hooks::HookEnv;
+
+/// Detects timer events inside the states received from the server and runs the configured
+/// commands for them
+#[derive(Debug, Clone, Default)]
+pub struct EventCommandRunner {
+    commands: EventCommands,
+    detector: TimerEventDetector,
+}
+
+impl EventCommandRunner {
+    /// Creates a runner for the given commands
+    pub fn new(commands: EventCommands) -> Self {
+        Self {
+            commands,
+            detector: TimerEventDetector::default(),
+        }
+    }
+
+    /// Runs the commands of all events, which happened since the previously received state
+    pub fn handle(&mut self, state: &ViewState) {
+        for event in self.detector.detect(state) {
+            if let Some(command) = self.command(event) {
+                run_command(command, event, state);
+            }
+        }
+    }
+
+    /// Command which has been configured for the given event
+    fn command(&self, event: TimerEvent) -> Option<&str> {
+        let command = match event {
+            TimerEvent::IntervalStart => &self.commands.interval_start,
+            TimerEvent::IntervalEnd => &self.commands.interval_end,
+            TimerEvent::BreakStart => &self.commands.break_start,
+            TimerEvent::BreakEnd => &self.commands.break_end,
+            TimerEvent::Postpone | TimerEvent::Pause | TimerEvent::Resume => &None,
+        };
+
+        command.as_deref()
+    }
+}
+
+/// Spawns the command without waiting for it, so that a long running command does not block
+/// the client. Its output is discarded, because it would garble the terminal interface.
+fn run_command(command: &str, event: TimerEvent, state: &ViewState) {
+    info!("Running command for {}", event);
+
+    let child = HookEnv::from_view_state(event, state)
+        .command(command)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+
+    match child {
+        Ok(mut child) => {
+            tokio::spawn(async move { child.wait().await.ok() });
+        }
+        Err(error) => error!("Could not run command for {}: {}", event, error),
+    }
+}
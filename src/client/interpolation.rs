@@ -0,0 +1,79 @@
+//! Local countdown of clients between two states of the server.
+//!
+//! Clients which have asked the server to send the remaining time less often (see
+//! [ClientPreferences::resync_seconds](crate::ipc::ClientPreferences::resync_seconds)) count
+//! down on their own, starting from the [progress](ViewState::progress) of the last state they
+//! have received. Each new state of the server replaces the local countdown, so that it can never
+//! drift apart for long.
+
+use std::time::{Duration, Instant};
+
+use zentime_rs_timer::pomodoro_timer::ViewState;
+use zentime_rs_timer::util::seconds_to_time;
+
+/// Delay after a second has been crossed, so that the countdown is never displayed early
+const TICK_DELAY: Duration = Duration::from_millis(1);
+
+/// Counts down the remaining time of the last running state received from the server
+#[derive(Debug, Clone, Default)]
+pub struct Interpolation {
+    /// Last running state of the server, the time left at that point and when it was received
+    base: Option<(ViewState, Duration, Instant)>,
+
+    /// Whole seconds which are currently displayed
+    displayed: u64,
+}
+
+impl Interpolation {
+    /// Creates an interpolation which does not count down until a state has been received
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts counting down from a state of the server.
+    /// Paused states, holds and states without progress stop the countdown.
+    pub fn receive(&mut self, state: &ViewState, now: Instant) {
+        self.base = state
+            .progress
+            .filter(|_| !state.is_paused && state.hold.is_none())
+            .map(|progress| (state.clone(), progress.remaining(), now));
+
+        if let Some((_, remaining, _)) = &self.base {
+            self.displayed = remaining.as_secs();
+        }
+    }
+
+    /// Point in time at which the displayed time changes next - [None] if the countdown has
+    /// stopped or reached zero (the server announces what follows)
+    pub fn deadline(&self) -> Option<Instant> {
+        let (_, remaining, received_at) = self.base.as_ref()?;
+
+        if self.displayed == 0 {
+            return None;
+        }
+
+        let until_change = remaining.saturating_sub(Duration::from_secs(self.displayed));
+
+        Some(*received_at + until_change + TICK_DELAY)
+    }
+
+    /// Returns the state with the interpolated remaining time, if it has changed since it was
+    /// last displayed
+    pub fn tick(&mut self, now: Instant) -> Option<ViewState> {
+        let (state, remaining, received_at) = self.base.as_ref()?;
+        let seconds = remaining
+            .saturating_sub(now.saturating_duration_since(*received_at))
+            .as_secs();
+
+        if seconds == self.displayed {
+            return None;
+        }
+
+        self.displayed = seconds;
+
+        Some(ViewState {
+            time: seconds_to_time(seconds),
+            ..state.clone()
+        })
+    }
+}
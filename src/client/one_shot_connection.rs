@@ -1,8 +1,7 @@
 //! Creates a connection for single reads/writes from/to the server
-use crate::ipc::get_socket_name;
+use crate::ipc::connect;
 use crate::server::status::server_status;
 use crate::server::status::ServerStatus;
-use interprocess::local_socket::tokio::LocalSocketStream;
 use interprocess::local_socket::tokio::OwnedReadHalf;
 use interprocess::local_socket::tokio::OwnedWriteHalf;
 
@@ -19,14 +18,13 @@ use interprocess::local_socket::tokio::OwnedWriteHalf;
 /// Also make sure to send a detach message to the server as well
 pub async fn one_shot_connection() -> anyhow::Result<(OwnedReadHalf, OwnedWriteHalf)> {
     // check if server is running -> if not, quit
-    if server_status() == ServerStatus::Stopped {
+    if server_status().await == ServerStatus::Stopped {
         println!("No zentime server running");
         std::process::exit(0);
     }
 
     // connect to server
-    let socket_name = get_socket_name();
-    let connection = LocalSocketStream::connect(socket_name).await?;
+    let connection = connect().await?;
 
     Ok(connection.into_split())
 }
@@ -0,0 +1,308 @@
+//! Optimistic display of client actions, which have not yet been confirmed by the server
+
+use std::time::{Duration, Instant};
+
+use zentime_rs_timer::config::PomodoroTimerConfig;
+use zentime_rs_timer::pomodoro_timer::ViewState;
+use zentime_rs_timer::util::seconds_to_time;
+
+use crate::client::terminal_io::input::ClientInputAction;
+use crate::config::{BreakEnforcement, Config};
+use crate::ipc::{Snapshot, MAIN_TIMER};
+use crate::wall_clock::WallClock;
+
+/// Time after which a prediction is discarded, if the server did not confirm it.
+/// This happens, if the server rejects an action (e.g. because it could not be applied).
+const PREDICTION_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Keeps track of the state which should be displayed by a client.
+///
+/// When the user triggers an action, the expected outcome is displayed right away instead of
+/// waiting for the next broadcast of the server. States received from the server are
+/// authoritative: as soon as the server confirms a prediction (or the prediction times out),
+/// the server state replaces it.
+#[derive(Debug, Clone)]
+pub struct OptimisticView {
+    config: Config,
+    /// Timer config the server has reported for a profile, which takes precedence over the
+    /// local config (the server may have been started with a different one)
+    synced_timers: Option<(Option<String>, PomodoroTimerConfig)>,
+    confirmed: Option<ViewState>,
+    pending: Option<(ViewState, Instant)>,
+    /// Enforcement of the break as it has last been reported to the terminal
+    reported_enforcement: bool,
+}
+
+impl OptimisticView {
+    /// Creates a new view. The timer config (of the active profile) is used to predict the time
+    /// of the next phase when skipping.
+    pub fn new(config: Config) -> Self {
+        Self {
+            config,
+            synced_timers: None,
+            confirmed: None,
+            pending: None,
+            reported_enforcement: false,
+        }
+    }
+
+    /// Denotes if the displayed timer is running
+    pub fn is_running(&self) -> bool {
+        self.current().is_some_and(|state| !state.is_paused)
+    }
+
+    /// Denotes if the displayed break is enforced (see `view.enforce_breaks`), which means
+    /// that it may neither be skipped nor postponed
+    pub fn is_break_enforced(&self) -> bool {
+        let Some(current) = self.current().filter(|state| state.is_break) else {
+            return false;
+        };
+
+        match self.config.view.enforce_breaks {
+            BreakEnforcement::Off => false,
+            BreakEnforcement::Always => true,
+            BreakEnforcement::AfterPostpones => {
+                current.postpone_count >= self.timers(current).postpone_limit
+            }
+        }
+    }
+
+    /// Denotes if the given action would give up the displayed interval, although the focus
+    /// contract is in effect and the user wants to confirm this (see `focus_contract.confirm`)
+    pub fn breaks_focus_contract(&self, action: &ClientInputAction) -> bool {
+        let contract = self.config.focus_contract;
+        let is_interval = self.current().is_some_and(|state| {
+            !state.is_break
+                && !state.is_postponed
+                && state.stopwatch.is_none()
+                && state.selected_timer().is_none()
+        });
+
+        contract.enabled
+            && contract.confirm
+            && is_interval
+            && matches!(
+                action,
+                ClientInputAction::Skip
+                    | ClientInputAction::Reset
+                    | ClientInputAction::LongBreakNow
+                    | ClientInputAction::Previous
+                    | ClientInputAction::RestartCurrent
+            )
+    }
+
+    /// Reports whether the enforcement of the break has started or ended since the last call -
+    /// [None] if nothing has changed
+    pub fn enforcement_change(&mut self) -> Option<bool> {
+        let is_enforced = self.is_break_enforced();
+
+        if is_enforced == self.reported_enforcement {
+            return None;
+        }
+
+        self.reported_enforcement = is_enforced;
+        Some(is_enforced)
+    }
+
+    /// Predicts the outcome of a user action.
+    /// Returns the predicted state, if the action changes the displayed state.
+    pub fn predict(&mut self, action: &ClientInputAction) -> Option<ViewState> {
+        let current = match &self.pending {
+            Some((state, _)) => state,
+            None => self.confirmed.as_ref()?,
+        };
+
+        // Actions apply to the selected named timer, whose outcome is not predicted
+        if current.selected_timer().is_some() {
+            return None;
+        }
+
+        let predicted = match action {
+            // Play/Pause also ends a hold or an automatic pause (and starting the next cycle
+            // removes its banner)
+            ClientInputAction::PlayPause => ViewState {
+                is_paused: !current.is_paused,
+                hold: None,
+                banner: None,
+                pause_reason: None,
+                ..current.clone()
+            },
+            // A stopwatch only takes a lap
+            ClientInputAction::Skip if current.stopwatch.is_none() => self.skipped(current),
+            _ => return None,
+        };
+
+        self.pending = Some((predicted.clone(), Instant::now()));
+
+        Some(predicted)
+    }
+
+    /// Handles a state received from the server.
+    /// Returns the state which should be displayed, or [None] if the server state is outdated
+    /// compared to a pending prediction.
+    pub fn reconcile(&mut self, state: ViewState) -> Option<ViewState> {
+        self.confirmed = Some(state.clone());
+
+        if let Some((predicted, predicted_at)) = &self.pending {
+            let is_confirmed = is_same_phase(predicted, &state);
+
+            if !is_confirmed && predicted_at.elapsed() < PREDICTION_TIMEOUT {
+                return None;
+            }
+
+            self.pending = None;
+        }
+
+        Some(state)
+    }
+
+    /// Handles a snapshot the server has answered a sync with (see [Self::reconcile])
+    pub fn sync(&mut self, snapshot: Snapshot) -> Option<ViewState> {
+        self.synced_timers = Some((snapshot.state.profile.clone(), snapshot.timers));
+        self.reconcile(snapshot.state)
+    }
+
+    /// Point in time at which the pending prediction expires (if there is one)
+    pub fn deadline(&self) -> Option<Instant> {
+        self.pending
+            .as_ref()
+            .map(|(_, predicted_at)| *predicted_at + PREDICTION_TIMEOUT)
+    }
+
+    /// Discards the pending prediction, because the server did not confirm it in time.
+    /// Returns the last state received from the server, which should be displayed instead.
+    pub fn expire(&mut self) -> Option<ViewState> {
+        self.pending = None;
+        self.confirmed.clone()
+    }
+
+    /// Timer profile which follows the active one (in alphabetical order) - [None] if no
+    /// profiles have been configured
+    pub fn next_profile(&self) -> Option<String> {
+        let names = self.config.profile_names();
+        let active = self.confirmed.as_ref()?.profile.as_ref()?;
+
+        if names.len() < 2 {
+            return None;
+        }
+
+        let next = names
+            .iter()
+            .position(|name| name == active)
+            .map_or(0, |index| (index + 1) % names.len());
+
+        Some(names[next].clone())
+    }
+
+    /// Timer which follows the selected one (the pomodoro timer comes first) - [None] if the
+    /// server does not run any named timers
+    pub fn next_timer(&self) -> Option<String> {
+        let timers = &self.confirmed.as_ref()?.timers;
+
+        if timers.is_empty() {
+            return None;
+        }
+
+        let next = match timers.iter().position(|timer| timer.is_selected) {
+            None => timers.first(),
+            Some(index) => timers.get(index + 1),
+        };
+
+        Some(next.map_or_else(|| MAIN_TIMER.to_string(), |timer| timer.name.clone()))
+    }
+
+    /// State which is displayed right now - the pending prediction, if there is one
+    fn current(&self) -> Option<&ViewState> {
+        match &self.pending {
+            Some((state, _)) => Some(state),
+            None => self.confirmed.as_ref(),
+        }
+    }
+
+    /// Timer config of the profile the given state belongs to
+    fn timers(&self, state: &ViewState) -> PomodoroTimerConfig {
+        match &self.synced_timers {
+            Some((profile, timers)) if *profile == state.profile => *timers,
+            _ => {
+                let now = WallClock::from_config(&self.config.clock).now();
+
+                state
+                    .profile
+                    .as_deref()
+                    .and_then(|profile| self.config.profile_timers(profile, now))
+                    .unwrap_or(self.config.timers)
+            }
+        }
+    }
+
+    /// Predicts the state of the phase following the given one, which is paused unless it
+    /// starts automatically (see `timers.auto_start_break` and `timers.auto_start_work`)
+    fn skipped(&self, current: &ViewState) -> ViewState {
+        let timers = self.timers(current);
+        let next_is_major_break = current.round.is_multiple_of(timers.intervals);
+        let break_time = if next_is_major_break {
+            timers.major_break
+        } else {
+            timers.minor_break
+        };
+
+        if current.is_postponed {
+            ViewState {
+                is_break: true,
+                is_postponed: false,
+                time: seconds_to_time(break_time),
+                is_paused: !timers.auto_start_break,
+                ..current.clone()
+            }
+        } else if current.is_break {
+            ViewState {
+                is_break: false,
+                is_postponed: false,
+                postpone_count: current.postpone_count,
+                round: current.round + 1,
+                time: seconds_to_time(timers.timer),
+                is_paused: !timers.auto_start_work,
+                hold: current.hold.clone(),
+                reminder: None,
+                task: current.task.clone(),
+                profile: current.profile.clone(),
+                banner: None,
+                today: current.today,
+                progress: None,
+                stopwatch: None,
+                pause_reason: None,
+                timers: current.timers.clone(),
+                countdowns: current.countdowns.clone(),
+            }
+        } else {
+            ViewState {
+                is_break: true,
+                is_postponed: false,
+                postpone_count: 0,
+                round: current.round,
+                time: seconds_to_time(break_time),
+                is_paused: !timers.auto_start_break,
+                hold: current.hold.clone(),
+                reminder: None,
+                task: current.task.clone(),
+                profile: current.profile.clone(),
+                banner: None,
+                today: current.today,
+                progress: None,
+                stopwatch: None,
+                pause_reason: None,
+                timers: current.timers.clone(),
+                countdowns: current.countdowns.clone(),
+            }
+        }
+    }
+}
+
+/// Denotes if both states belong to the same phase and have the same play/pause state.
+/// The time is ignored, because it keeps changing while the timer is running.
+fn is_same_phase(a: &ViewState, b: &ViewState) -> bool {
+    a.is_break == b.is_break
+        && a.is_postponed == b.is_postponed
+        && a.round == b.round
+        && a.is_paused == b.is_paused
+}
@@ -0,0 +1,27 @@
+//! Plain text representation of the timer for streaming overlays (e.g. OBS text sources) and
+//! terminal titles
+
+use zentime_rs_timer::pomodoro_timer::ViewState;
+
+/// Formats the remaining time and phase of the timer (e.g. `24:59 Focus`)
+pub fn overlay_text(state: &ViewState) -> String {
+    let phase = if state.hold.is_some() {
+        "On hold"
+    } else if state.stopwatch.is_some() {
+        "Stopwatch"
+    } else if state.is_break {
+        "Break"
+    } else if state.is_postponed {
+        "Postponed"
+    } else {
+        "Focus"
+    };
+
+    if let Some(reason) = state.pause_reason.as_ref().filter(|_| state.is_paused) {
+        format!("{} {} (paused: {})", state.time, phase, reason)
+    } else if state.is_paused && state.hold.is_none() {
+        format!("{} {} (paused)", state.time, phase)
+    } else {
+        format!("{} {}", state.time, phase)
+    }
+}
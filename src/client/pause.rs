@@ -0,0 +1,120 @@
+//! Pauses which end at a time of day or after a while (see `zentime pause-until`)
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::Context;
+use chrono::{Days, NaiveTime};
+use zentime_rs_timer::clock;
+
+use crate::ipc::ClientToServerMsg;
+use crate::wall_clock::WallClock;
+
+/// Point at which a pause ends
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PauseEnd {
+    /// Time of day (tomorrow, if it has already passed today)
+    At(NaiveTime),
+
+    /// Duration from now on
+    After(Duration),
+}
+
+/// Pause of the timer, after which the server resumes it and welcomes the user back
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pause {
+    /// Point in time at which the timer resumes
+    pub until: SystemTime,
+
+    /// Reason which is shown by all clients, e.g. `back at 13:30`
+    pub reason: String,
+}
+
+impl Pause {
+    /// Pause from now (as told by [clock::system_now]) until the given end.
+    /// Fails if the pause would end beyond the range of the system clock.
+    pub fn new(end: PauseEnd, wall_clock: WallClock) -> anyhow::Result<Self> {
+        let until = match end {
+            PauseEnd::At(time) => next_occurrence(time, wall_clock),
+            PauseEnd::After(duration) => clock::system_now()
+                .checked_add(duration)
+                .with_context(|| format!("Can't pause for {}s", duration.as_secs()))?,
+        };
+
+        Ok(Self {
+            until,
+            reason: format!("back at {}", wall_clock.local_time(until).format("%H:%M")),
+        })
+    }
+
+    /// Message which asks the server to pause the timer
+    pub fn msg(&self) -> ClientToServerMsg {
+        let until = self
+            .until
+            .duration_since(UNIX_EPOCH)
+            .map(|until| until.as_secs())
+            .unwrap_or_default();
+
+        ClientToServerMsg::PauseUntil {
+            reason: self.reason.clone(),
+            until,
+        }
+    }
+}
+
+/// Next point in time at which the wall clock shows the given time - tomorrow, if it has
+/// already passed today
+fn next_occurrence(time: NaiveTime, wall_clock: WallClock) -> SystemTime {
+    let now = wall_clock.now();
+    let mut date = now.date();
+
+    if now.time() >= time {
+        date = date + Days::new(1);
+    }
+
+    wall_clock.system_time(date.and_time(time))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ClockConfig;
+
+    #[test]
+    fn pauses_until_a_time_of_day_or_for_a_while() {
+        let wall_clock = WallClock::from_config(&ClockConfig {
+            time_zone: Some(chrono_tz::Europe::Berlin),
+        });
+
+        // Other tests may advance the mock clock in the meantime
+        let before = clock::system_now();
+        let pause = Pause::new(PauseEnd::After(Duration::from_secs(45 * 60)), wall_clock).unwrap();
+        let after = clock::system_now();
+
+        assert!(pause.until >= before + Duration::from_secs(45 * 60));
+        assert!(pause.until <= after + Duration::from_secs(45 * 60));
+        assert_eq!(
+            pause.reason,
+            format!(
+                "back at {}",
+                wall_clock.local_time(pause.until).format("%H:%M")
+            )
+        );
+
+        let lunch = NaiveTime::from_hms_opt(13, 30, 0).unwrap();
+        let pause = Pause::new(PauseEnd::At(lunch), wall_clock).unwrap();
+
+        assert!(pause.until > before);
+        assert_eq!(wall_clock.local_time(pause.until).time(), lunch);
+        assert_eq!(pause.reason, "back at 13:30");
+
+        let ClientToServerMsg::PauseUntil { reason, until } = pause.msg() else {
+            panic!("Pause has to be sent as PauseUntil");
+        };
+        assert_eq!(reason, "back at 13:30");
+        assert!(Pause::new(PauseEnd::After(Duration::from_secs(u64::MAX)), wall_clock).is_err());
+        assert_eq!(
+            until,
+            pause.until.duration_since(UNIX_EPOCH).unwrap().as_secs()
+        );
+    }
+}
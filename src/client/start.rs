@@ -6,13 +6,16 @@ use std::sync::Arc;
 
 use crate::client::terminal_io::input::TerminalInputTask;
 use crate::client::terminal_io::output::TerminalOut;
-use crate::config::Config;
+use crate::config::{Config, TuiLayout, ViewConfig};
+use crate::ipc::ServerAddress;
 use futures::future::FutureExt;
 use futures::lock::Mutex;
 use tokio::sync::mpsc::unbounded_channel;
 use tokio::try_join;
+use zentime_tui::InputHandler;
 
 use super::connection::ClientConnectionTask;
+use crate::client::terminal_io::accessible_interface::AccessibleInterface;
 use crate::client::terminal_io::output::DefaultInterface;
 use crate::client::terminal_io::output::MinimalInterface;
 
@@ -38,18 +41,33 @@ use crate::client::terminal_io::output::MinimalInterface;
 /// }
 /// ```
 pub async fn start(config: Config) {
+    start_at(config, ServerAddress::Local).await
+}
+
+/// Start a single zentime client and connect it to the server at the given address
+/// (e.g. to join a shared team session).
+/// See [start].
+pub async fn start_at(config: Config, address: ServerAddress) {
     let (terminal_in_tx, terminal_in_rx) = unbounded_channel();
     let (terminal_out_tx, terminal_out_rx) = unbounded_channel();
 
-    let interface_type = config.view.interface.clone();
-
-    let terminal_out: Box<dyn TerminalOut + Send> = init_interface(interface_type);
+    let terminal_out: Box<dyn TerminalOut + Send> = init_interface(&config.view);
 
     let thread_safe_terminal_out = Arc::new(Mutex::new(terminal_out));
 
-    let input_handler = TerminalInputTask::spawn(terminal_in_tx);
-    let view_handler = TerminalOutputTask::spawn(thread_safe_terminal_out.clone(), terminal_out_rx);
-    let connection_handler = ClientConnectionTask::spawn(terminal_in_rx, terminal_out_tx);
+    let input_handler = TerminalInputTask::spawn(terminal_in_tx, init_input_handler(&config.view));
+    let view_handler = TerminalOutputTask::spawn(
+        thread_safe_terminal_out.clone(),
+        terminal_out_rx,
+        config.view.terminal_title,
+    );
+    let connection_handler = ClientConnectionTask::spawn(
+        terminal_in_rx,
+        terminal_out_tx,
+        config.clone(),
+        config.view.exec_on,
+        address,
+    );
 
     let join_result = try_join! {
         connection_handler.flatten(),
@@ -65,21 +83,35 @@ pub async fn start(config: Config) {
     }
 }
 
+/// Popups are closed instead of quit, so that they can't shut down the server by accident
+fn init_input_handler(view: &ViewConfig) -> InputHandler {
+    match view.layout {
+        TuiLayout::Popup => InputHandler::popup(),
+        TuiLayout::Default | TuiLayout::Compact => InputHandler::new(),
+    }
+}
+
 /// Determine which terminal interface should be used.
-fn init_interface(interface_type: String) -> Box<dyn TerminalOut + Send> {
-    match interface_type.as_str() {
-        "minimal" => match MinimalInterface::new() {
+fn init_interface(view: &ViewConfig) -> Box<dyn TerminalOut + Send> {
+    match view.interface.as_str() {
+        "accessible" => match AccessibleInterface::new() {
+            Ok(interface) => Box::new(interface),
+            Err(error) => {
+                panic!("Could not initialize interface: {}", error);
+            }
+        },
+        "minimal" => match MinimalInterface::new(view) {
             Ok(interface) => {
                 // We move up one line to replace the initial prompt ending with our timer
                 let ansi_move_line_up_escape = "\x1B[A";
                 print!("{}", ansi_move_line_up_escape);
                 Box::new(interface)
-            },
+            }
             Err(error) => {
                 panic!("Could not initialize interface: {}", error);
             }
         },
-        _ => match DefaultInterface::new() {
+        _ => match DefaultInterface::new(view) {
             Ok(interface) => Box::new(interface),
             Err(error) => {
                 panic!("Could not initialize interface: {}", error);
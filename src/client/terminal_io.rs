@@ -1,6 +1,8 @@
 //! Zentime client terminal io
 
+pub mod accessible_interface;
 mod default_interface;
 pub mod input;
+pub mod job_control;
 pub mod output;
 pub mod terminal_event;
@@ -0,0 +1,191 @@
+//! Interface for screen readers and braille displays.
+//!
+//! Instead of redrawing the screen, each change is announced as a plain line of text, which
+//! never relies on colors. The remaining time is only announced once a minute, so that screen
+//! readers are not flooded with updates. Phase changes are additionally signaled by the
+//! terminal bell (one ring for focus, two for breaks and three for postponed breaks).
+
+use std::io::Write;
+use std::process;
+
+use anyhow::Context;
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
+use zentime_rs_timer::pomodoro_timer::ViewState;
+
+use super::output::TerminalOut;
+use super::terminal_event::{ClientStatus, ConnectionStatus, PromptKind};
+
+/// Terminal bell
+const BELL: &str = "\x07";
+
+/// Moves the cursor to the start of the line and erases it
+const CLEAR_LINE: &str = "\r\x1B[2K";
+
+/// Raw mode does not translate line feeds, therefore each line ends with both
+const LINE_END: &str = "\r\n";
+
+/// Announces the timer as plain lines of text (see module documentation)
+#[derive(Debug, Clone, Default)]
+pub struct AccessibleInterface {
+    announced: Option<ViewState>,
+    status: ClientStatus,
+}
+
+impl AccessibleInterface {
+    /// Creates a new accessible interface and enables raw mode, so that keyboard shortcuts
+    /// work without pressing enter
+    pub fn new() -> anyhow::Result<Self> {
+        enable_raw_mode().context("Can't run in raw mode")?;
+        Ok(Self::default())
+    }
+
+    /// Lines which announce the changes between the last announced and the given state
+    fn announcements(&self, state: &ViewState, status: &ClientStatus) -> Vec<String> {
+        let mut lines = vec![];
+
+        if status.connection != self.status.connection {
+            lines.push(match status.connection {
+                ConnectionStatus::Reconnecting { attempt } => {
+                    format!("Connection lost, reconnecting (attempt {})", attempt)
+                }
+                ConnectionStatus::Connected => "Connected".to_string(),
+            });
+        }
+
+        if let Some(error) = &status.error {
+            if self.status.error.as_ref() != Some(error) {
+                lines.push(format!("Error: {}", error));
+            }
+        }
+
+        if self.status.prompt.is_none() {
+            match status.prompt.as_ref().map(|prompt| prompt.kind) {
+                Some(PromptKind::Reason) => lines.push(
+                    "Why did you pause? Type a reason, then press Enter (Esc cancels)".to_string(),
+                ),
+                Some(PromptKind::Task) => lines.push(
+                    "What are you working on? Type the task, then press Enter (an empty task \
+                    removes the label, Esc cancels)"
+                        .to_string(),
+                ),
+                Some(PromptKind::FocusContract) => lines.push(
+                    "You promised to stay focused. Press y to give up this interval anyway, \
+                    any other key keeps it running"
+                        .to_string(),
+                ),
+                None => {}
+            }
+        }
+
+        match &self.announced {
+            Some(announced) if announced.differs_only_in_time(state) => {
+                if announced.time != state.time && state.time.ends_with(":00") {
+                    lines.push(format!("{} remaining", state.time));
+                }
+            }
+            _ => lines.push(describe(state)),
+        }
+
+        lines
+    }
+}
+
+impl TerminalOut for AccessibleInterface {
+    fn render(&mut self, state: ViewState, status: &ClientStatus) -> anyhow::Result<()> {
+        let mut output = String::new();
+
+        // The prompt is typed inside its own line, which is rewritten on each key press
+        if self.status.prompt.is_some() {
+            output.push_str(CLEAR_LINE);
+        }
+
+        if let Some(announced) = &self.announced {
+            if phase_rings(announced) != phase_rings(&state) || announced.round != state.round {
+                output.push_str(&BELL.repeat(phase_rings(&state)));
+            }
+        }
+
+        for line in self.announcements(&state, status) {
+            output.push_str(&line);
+            output.push_str(LINE_END);
+        }
+
+        if let Some(prompt) = &status.prompt {
+            output.push_str(&prompt.to_string());
+        }
+
+        print!("{}", output);
+        std::io::stdout().flush()?;
+
+        self.announced = Some(state);
+        self.status = status.clone();
+
+        Ok(())
+    }
+
+    fn quit(&mut self, msg: Option<String>, is_error: bool) {
+        disable_raw_mode().expect("Could not disable raw mode");
+
+        println!("\r\n{}", msg.unwrap_or_else(|| String::from("")));
+
+        process::exit(i32::from(is_error))
+    }
+
+    fn suspend(&mut self) -> anyhow::Result<()> {
+        disable_raw_mode().context("Could not disable raw mode")?;
+        print!("Suspended{}", LINE_END);
+        Ok(std::io::stdout().flush()?)
+    }
+
+    /// The whole state is announced again, because time has passed in the meantime
+    fn resume(&mut self) -> anyhow::Result<()> {
+        enable_raw_mode().context("Can't run in raw mode")?;
+        self.announced = None;
+        self.status = ClientStatus::default();
+        Ok(())
+    }
+}
+
+/// Describes the whole state in words (e.g. `Focus, round 1, 24:59 remaining, paused`)
+fn describe(state: &ViewState) -> String {
+    let phase = if let Some(hold) = &state.hold {
+        hold.to_string()
+    } else if let Some(reminder) = &state.reminder {
+        format!("Reminder: {}", reminder)
+    } else if state.is_break {
+        "Break".to_string()
+    } else if state.is_postponed {
+        format!("Postponed break ({} times)", state.postpone_count)
+    } else {
+        "Focus".to_string()
+    };
+
+    let mut description = format!("{}, round {}, {} remaining", phase, state.round, state.time);
+
+    if let Some(reason) = state.pause_reason.as_ref().filter(|_| state.is_paused) {
+        description.push_str(&format!(", paused ({})", reason));
+    } else if state.is_paused && state.hold.is_none() {
+        description.push_str(", paused");
+    }
+
+    if let Some(task) = &state.task {
+        description.push_str(&format!(", working on {}", task));
+    }
+
+    if let Some(banner) = &state.banner {
+        description = format!("{}. {}", banner, description);
+    }
+
+    description
+}
+
+/// Number of bell rings which announce the phase of the given state
+fn phase_rings(state: &ViewState) -> usize {
+    if state.is_postponed {
+        3
+    } else if state.is_break {
+        2
+    } else {
+        1
+    }
+}
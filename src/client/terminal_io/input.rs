@@ -1,53 +1,49 @@
 //! Code related to async client terminal input handling
 
-use crossterm::event::{EventStream, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::EventStream;
+use futures::future::pending;
+use log::warn;
+use tokio::select;
 use tokio::sync::mpsc::UnboundedSender;
 use tokio::task::yield_now;
 use tokio::{spawn, task::JoinHandle};
 use tokio_stream::StreamExt;
+use zentime_tui::input::InputHandler;
 
-use crossterm::event::Event;
+pub use zentime_tui::input::ClientInputAction;
 
-/// Actions triggered by user terminal input on a client
-#[derive(Copy, Clone, Debug)]
-pub enum ClientInputAction {
-    /// Quit Timer and terminate server
-    Quit,
-
-    /// Detach current client without terminating server
-    Detach,
-
-    /// NoOp
-    None,
-
-    /// Either start or pause the current timer
-    PlayPause,
-
-    /// Skip to the next timer (break or focus)
-    Skip,
-
-    /// Resets the timer back to the first interval
-    Reset,
-
-    /// Postpones the current break, if possible (see [PomodoroTimerConfig])
-    PostPone,
-}
+use super::job_control::JobControlSignals;
 
 /// Tokio task handling terminal input events
 #[derive(Copy, Clone, Debug)]
 pub struct TerminalInputTask {}
 
 impl TerminalInputTask {
-    /// Spanws the task and converts incoming terminal input events into [ClientInputAction]s and
-    /// sends them to the client.
-    pub async fn spawn(input_worker_tx: UnboundedSender<ClientInputAction>) -> JoinHandle<()> {
+    /// Spanws the task and converts incoming terminal input events into [ClientInputAction]s
+    /// (via the given handler) and sends them to the client.
+    /// Once the terminal is gone (e.g. a tmux popup has been closed), the client detaches.
+    pub async fn spawn(
+        input_worker_tx: UnboundedSender<ClientInputAction>,
+        mut input_handler: InputHandler,
+    ) -> JoinHandle<()> {
         spawn(async move {
             let mut stream = EventStream::new();
+            let mut signals = JobControlSignals::new()
+                .map_err(|error| warn!("Suspending the client is not supported: {:#}", error))
+                .ok();
 
             loop {
-                let result = stream.next().await;
-                if let Some(Ok(event)) = result {
-                    if let Err(error) = input_worker_tx.send(handle_input(event)) {
+                let actions = select! {
+                    result = stream.next() => match result {
+                        Some(Ok(event)) => input_handler.handle(event),
+                        Some(Err(_)) => vec![],
+                        None => vec![ClientInputAction::Detach],
+                    },
+                    action = job_control_signal(&mut signals) => vec![action],
+                };
+
+                for action in actions {
+                    if let Err(error) = input_worker_tx.send(action) {
                         // TODO: handle this more gracefully
                         panic!("Could not send ClientInputAction: {}", error)
                     };
@@ -59,60 +55,10 @@ impl TerminalInputTask {
     }
 }
 
-/// Keymap from terminal input events to [ClientInputAction]
-fn handle_input(event: Event) -> ClientInputAction {
-    if let Event::Key(key_event) = event {
-        match key_event {
-            KeyEvent {
-                code: KeyCode::Char('q'),
-                ..
-            }
-            | KeyEvent {
-                code: KeyCode::Char('c'),
-                modifiers: KeyModifiers::CONTROL,
-                ..
-            } => {
-                return ClientInputAction::Quit;
-            }
-
-            KeyEvent {
-                code: KeyCode::Char('d'),
-                ..
-            } => {
-                return ClientInputAction::Detach;
-            }
-
-            KeyEvent {
-                code: KeyCode::Char(' '),
-                ..
-            } => {
-                return ClientInputAction::PlayPause;
-            }
-
-            KeyEvent {
-                code: KeyCode::Char('s'),
-                ..
-            } => {
-                return ClientInputAction::Skip;
-            }
-
-            KeyEvent {
-                code: KeyCode::Char('p'),
-                ..
-            } => {
-                return ClientInputAction::PostPone
-            }
-
-            KeyEvent {
-                code: KeyCode::Char('r'),
-                ..
-            } => {
-                return ClientInputAction::Reset;
-            }
-
-            _ => {}
-        }
+/// Waits for the next job control signal (or forever, if signals can't be handled)
+async fn job_control_signal(signals: &mut Option<JobControlSignals>) -> ClientInputAction {
+    match signals {
+        Some(signals) => signals.recv().await,
+        None => pending().await,
     }
-
-    ClientInputAction::None
 }
@@ -0,0 +1,77 @@
+//! Job control of terminal clients (suspending them via Ctrl-Z and continuing them via `fg`).
+//!
+//! Raw mode keeps the terminal from sending SIGTSTP on Ctrl-Z, therefore the client handles the
+//! key press itself: the terminal is restored and the process is stopped. As soon as the process
+//! has been continued (no matter who stopped it), the terminal is set up again and the client
+//! resyncs with the server, so that no stale countdown is shown.
+//!
+//! A hangup (e.g. the terminal window or a tmux popup has been closed) detaches the client, so
+//! that the server isn't left with a dead connection.
+
+use super::input::ClientInputAction;
+
+/// Signals, which suspend, continue or detach the client
+#[derive(Debug)]
+pub struct JobControlSignals {
+    #[cfg(unix)]
+    stop: tokio::signal::unix::Signal,
+
+    #[cfg(unix)]
+    cont: tokio::signal::unix::Signal,
+
+    #[cfg(unix)]
+    hangup: tokio::signal::unix::Signal,
+}
+
+impl JobControlSignals {
+    /// Listens for SIGTSTP (e.g. `kill -TSTP`), SIGCONT and SIGHUP
+    #[cfg(unix)]
+    pub fn new() -> anyhow::Result<Self> {
+        use anyhow::Context;
+        use tokio::signal::unix::{signal, SignalKind};
+
+        Ok(Self {
+            stop: signal(SignalKind::from_raw(libc::SIGTSTP))
+                .context("Could not handle SIGTSTP")?,
+            cont: signal(SignalKind::from_raw(libc::SIGCONT))
+                .context("Could not handle SIGCONT")?,
+            hangup: signal(SignalKind::hangup()).context("Could not handle SIGHUP")?,
+        })
+    }
+
+    /// There is no job control outside of unix
+    #[cfg(not(unix))]
+    pub fn new() -> anyhow::Result<Self> {
+        Ok(Self {})
+    }
+
+    /// Waits for the next signal and returns the action it triggers
+    #[cfg(unix)]
+    pub async fn recv(&mut self) -> ClientInputAction {
+        tokio::select! {
+            _ = self.stop.recv() => ClientInputAction::Suspend,
+            _ = self.cont.recv() => ClientInputAction::Resume,
+            _ = self.hangup.recv() => ClientInputAction::Detach,
+        }
+    }
+
+    /// There is no job control outside of unix
+    #[cfg(not(unix))]
+    pub async fn recv(&mut self) -> ClientInputAction {
+        futures::future::pending().await
+    }
+}
+
+/// Stops the whole process, until it is continued (e.g. via `fg`).
+/// SIGSTOP is used instead of SIGTSTP, because the latter is handled by [JobControlSignals].
+#[cfg(unix)]
+pub fn stop_process() {
+    // SAFETY: raise only sends a signal to the calling thread
+    unsafe {
+        libc::raise(libc::SIGSTOP);
+    }
+}
+
+/// There is no job control outside of unix
+#[cfg(not(unix))]
+pub fn stop_process() {}
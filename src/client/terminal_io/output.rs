@@ -1,61 +1,182 @@
 //! Code related to client async terminal output handling
 
-use crate::client::terminal_io::default_interface::render;
+use crate::client::overlay::overlay_text;
+use crate::client::terminal_io::default_interface::view_options;
+use crate::config::ViewConfig;
 use anyhow::Context;
 use crossterm::cursor::Hide;
 use crossterm::style::Stylize;
-use crossterm::terminal::{enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::terminal::{enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen, SetTitle};
 use crossterm::{cursor::Show, event::DisableMouseCapture, execute, terminal::disable_raw_mode};
 use futures::lock::Mutex;
-use zentime_rs_timer::pomodoro_timer::ViewState;
 use std::io::Write;
+use std::iter;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use std::{io::Stdout, process};
 use tokio::sync::mpsc::UnboundedReceiver;
 use tokio::task::{spawn, JoinHandle};
 use tui::{backend::CrosstermBackend, Terminal as TuiTerminal};
+use zentime_rs_timer::pomodoro_timer::ViewState;
+use zentime_rs_timer::status_template::StatusTemplate;
+use zentime_tui::{render, ViewOptions};
+
+use super::job_control::stop_process;
+use super::terminal_event::{ClientStatus, ConnectionStatus, TerminalEvent};
 
-use super::terminal_event::TerminalEvent;
+/// Time an error reported by the server stays visible
+const ERROR_DISPLAY_DURATION: Duration = Duration::from_secs(5);
 
 /// Tokio task which continouusly renders the current view state to the terminal output.
 #[derive(Copy, Clone, Debug)]
 pub struct TerminalOutputTask {}
 
 impl TerminalOutputTask {
-    /// Spawns a tokio task which continously handles terminal output
+    /// Spawns a tokio task which continously handles terminal output.
+    /// If `show_in_title` is set, the remaining time is also shown inside the terminal title.
     pub async fn spawn(
         terminal_out: Arc<Mutex<Box<dyn TerminalOut + Send>>>,
         mut out_rx: UnboundedReceiver<TerminalEvent>,
+        show_in_title: bool,
     ) -> JoinHandle<()> {
         spawn(async move {
+            let mut state: Option<ViewState> = None;
+            let mut status = ClientStatus::default();
+            let mut error_received_at = Instant::now();
+            let mut rendered: Option<(ViewState, ClientStatus)> = None;
+            let mut title: Option<String> = None;
+            let mut is_suspended = false;
+
             loop {
-                match out_rx.recv().await {
-                    Some(TerminalEvent::View(state)) => {
-                        if let Err(error) = terminal_out.lock().await.render(state) {
-                            return terminal_out
-                                .lock()
-                                .await
-                                .quit(Some(format!("ERROR: {}", error)), true);
+                let event = match out_rx.recv().await {
+                    Some(event) => event,
+                    None => continue,
+                };
+
+                // Apply all events which have queued up in the meantime, so that intermediate
+                // frames are dropped and only the latest state is rendered
+                for event in iter::once(event).chain(iter::from_fn(|| out_rx.try_recv().ok())) {
+                    match event {
+                        TerminalEvent::View(view_state) => state = Some(view_state),
+                        TerminalEvent::Connection(connection) => status.connection = connection,
+                        TerminalEvent::Prompt(prompt) => status.prompt = prompt,
+                        TerminalEvent::EnforcedBreak(enforced) => status.enforced_break = enforced,
+                        TerminalEvent::ServerError(error) => {
+                            status.error = Some(error);
+                            error_received_at = Instant::now();
+                        }
+                        TerminalEvent::Bell(message) => {
+                            // A terminal without a bell simply ignores the control character
+                            ring_terminal_bell().ok();
+                            status.error = Some(message);
+                            error_received_at = Instant::now();
+                        }
+                        TerminalEvent::Suspend => {
+                            if let Err(error) = terminal_out.lock().await.suspend() {
+                                return terminal_out
+                                    .lock()
+                                    .await
+                                    .quit(Some(format!("ERROR: {}", error)), true);
+                            }
+
+                            // The process stops right here, until it is continued
+                            is_suspended = true;
+                            stop_process();
+                        }
+                        TerminalEvent::Resume => {
+                            if let Err(error) = terminal_out.lock().await.resume() {
+                                return terminal_out
+                                    .lock()
+                                    .await
+                                    .quit(Some(format!("ERROR: {}", error)), true);
+                            }
+
+                            // The screen has been cleared, therefore everything is redrawn
+                            is_suspended = false;
+                            rendered = None;
+                            title = None;
+                        }
+                        TerminalEvent::Quit { msg, error } => {
+                            if title.is_some() {
+                                set_terminal_title("").ok();
+                            }
+
+                            return terminal_out.lock().await.quit(msg, error);
                         }
                     }
-                    Some(TerminalEvent::Quit { msg, error }) => {
-                        return terminal_out.lock().await.quit(msg, error);
-                    }
+                }
+
+                // Nothing is drawn until the terminal has been set up again
+                if is_suspended {
+                    continue;
+                }
+
+                if error_received_at.elapsed() > ERROR_DISPLAY_DURATION {
+                    status.error = None;
+                }
+
+                let state = match &state {
+                    Some(state) => state.clone(),
                     None => continue,
+                };
+
+                // Only redraw if the screen would actually change
+                let next = (state, status.clone());
+                if rendered.as_ref() == Some(&next) {
+                    continue;
                 }
+
+                if let Err(error) = terminal_out.lock().await.render(next.0.clone(), &status) {
+                    return terminal_out
+                        .lock()
+                        .await
+                        .quit(Some(format!("ERROR: {}", error)), true);
+                }
+
+                if show_in_title {
+                    let next_title = format!("{} - zentime", overlay_text(&next.0));
+
+                    if title.as_ref() != Some(&next_title) {
+                        // A terminal without title support simply ignores the escape sequence
+                        set_terminal_title(&next_title).ok();
+                        title = Some(next_title);
+                    }
+                }
+
+                rendered = Some(next);
             }
         })
     }
 }
 
+/// Sets the title of the terminal window/tab via an OSC escape sequence
+fn set_terminal_title(title: &str) -> anyhow::Result<()> {
+    execute!(std::io::stdout(), SetTitle(title)).context("Could not set terminal title")
+}
+
+/// Rings the bell of the terminal
+fn ring_terminal_bell() -> anyhow::Result<()> {
+    let mut stdout = std::io::stdout();
+    write!(stdout, "\x07").context("Could not ring terminal bell")?;
+    stdout.flush().context("Could not ring terminal bell")
+}
+
 /// Trait representing a terminal output
 pub trait TerminalOut {
-    /// Renders the current [ViewState]
-    fn render(&mut self, state: ViewState) -> anyhow::Result<()>;
+    /// Renders the current [ViewState] and the status of the client
+    fn render(&mut self, state: ViewState, status: &ClientStatus) -> anyhow::Result<()>;
 
     /// Gracefully quits the [Self] so that raw-mode, alternate screens etc.
     /// are restored to their default.
     fn quit(&mut self, msg: Option<String>, is_error: bool);
+
+    /// Restores raw-mode, alternate screens etc. to their default before the client is
+    /// suspended
+    fn suspend(&mut self) -> anyhow::Result<()>;
+
+    /// Sets up the terminal again after the client has been continued.
+    /// The next [TerminalOut::render] has to draw the whole screen.
+    fn resume(&mut self) -> anyhow::Result<()>;
 }
 
 /// Implementation of a [TerminalOut]
@@ -64,11 +185,12 @@ pub trait TerminalOut {
 #[derive()]
 pub struct DefaultInterface {
     tui_terminal: TuiTerminal<CrosstermBackend<Stdout>>,
+    options: ViewOptions,
 }
 
 impl DefaultInterface {
-    /// Creates a new default interface
-    pub fn new() -> anyhow::Result<Self> {
+    /// Creates a new default interface, which is laid out and colored as configured
+    pub fn new(view: &ViewConfig) -> anyhow::Result<Self> {
         let backend = CrosstermBackend::new(std::io::stdout());
         execute!(std::io::stdout(), EnterAlternateScreen)
             .context("Can't execute crossterm macros")?;
@@ -80,74 +202,143 @@ impl DefaultInterface {
 
         Ok(Self {
             tui_terminal: terminal,
+            options: view_options(view),
         })
     }
 }
 
 impl TerminalOut for DefaultInterface {
-    fn render(&mut self, state: ViewState) -> anyhow::Result<()> {
-        render(&mut self.tui_terminal, state)
+    fn render(&mut self, state: ViewState, status: &ClientStatus) -> anyhow::Result<()> {
+        render(&mut self.tui_terminal, &state, status, &self.options)
+            .context("Could not render to terminal")
     }
 
     fn quit(&mut self, msg: Option<String>, is_error: bool) {
-        disable_raw_mode().expect("Could not disable raw mode");
-        self.tui_terminal
-            .show_cursor()
-            .expect("Could not show cursor");
-        self.tui_terminal.clear().expect("Could not clear terminal");
-        execute!(std::io::stdout(), DisableMouseCapture, LeaveAlternateScreen)
-            .expect("Could not execute crossterm macros");
+        // The terminal might already be gone (e.g. a closed tmux popup), in which case there is
+        // nothing left to restore
+        disable_raw_mode().ok();
+        self.tui_terminal.show_cursor().ok();
+        self.tui_terminal.clear().ok();
+        execute!(std::io::stdout(), DisableMouseCapture, LeaveAlternateScreen).ok();
 
-        println!("\n{}", msg.unwrap_or_else(|| String::from("")));
+        writeln!(std::io::stdout(), "\n{}", msg.unwrap_or_default()).ok();
 
         process::exit(i32::from(is_error))
     }
+
+    fn suspend(&mut self) -> anyhow::Result<()> {
+        disable_raw_mode().context("Could not disable raw mode")?;
+        self.tui_terminal
+            .show_cursor()
+            .context("Could not show cursor")?;
+        execute!(std::io::stdout(), LeaveAlternateScreen)
+            .context("Could not execute crossterm macros")
+    }
+
+    fn resume(&mut self) -> anyhow::Result<()> {
+        execute!(std::io::stdout(), EnterAlternateScreen)
+            .context("Could not execute crossterm macros")?;
+        enable_raw_mode().context("Can't run in raw mode")?;
+        self.tui_terminal
+            .hide_cursor()
+            .context("Could not hide cursor")?;
+        self.tui_terminal
+            .clear()
+            .context("Terminal could not be cleared")
+    }
 }
 
 /// Minimal interface which uses a [Crossterm] to display colors, hide the cursor and enable raw mode.
 /// The actual rendering happens with simple `print!`-macro-calls.
-#[derive(Debug, Copy, Clone)]
-pub struct MinimalInterface {}
+#[derive(Debug, Clone)]
+pub struct MinimalInterface {
+    show_round: bool,
+    show_postpone_count: bool,
+    format: Option<StatusTemplate>,
+}
 
 impl MinimalInterface {
     /// Creates a new minimal interface and also enables raw mode and hides the cursor.
-    pub fn new() -> anyhow::Result<Self> {
+    /// The round counter and the postpone count are left out, if they have been turned off
+    /// inside the view config, and a configured format replaces the whole line.
+    pub fn new(view: &ViewConfig) -> anyhow::Result<Self> {
         enable_raw_mode().context("Can't run in raw mode")?;
 
         execute!(std::io::stdout(), Hide).context("Could not execute crossterm macros")?;
-        Ok(Self {})
+        Ok(Self {
+            show_round: view.show_round,
+            show_postpone_count: view.show_postpone_count,
+            format: view.format.clone(),
+        })
     }
 }
 
 impl TerminalOut for MinimalInterface {
-    fn render(&mut self, state: ViewState) -> anyhow::Result<()> {
+    fn render(&mut self, state: ViewState, status: &ClientStatus) -> anyhow::Result<()> {
+        let ansi_erase_line_escape = "\x1B[2K";
+        let ansi_move_cursor_to_start_of_line_escape = "\r";
+
+        let status_info = match (&status.connection, &status.error) {
+            (ConnectionStatus::Reconnecting { attempt }, _) => {
+                format!(" Reconnecting ({})...", attempt).red()
+            }
+            (ConnectionStatus::Connected, Some(error)) => format!(" {}", error).red(),
+            (ConnectionStatus::Connected, None) => "".to_string().white(),
+        };
+
+        if let (Some(format), None) = (&self.format, &status.prompt) {
+            print!(
+                "{}{}{}{}",
+                ansi_move_cursor_to_start_of_line_escape,
+                ansi_erase_line_escape,
+                format.render(&state),
+                status_info
+            );
+
+            return Ok(std::io::stdout().flush()?);
+        }
+
         let timer = format!(" {} ", state.time.white());
-        let round = format!("Round: {}", state.round);
-        let timer_kind = if state.is_break {
-                "Break".yellow()
-            } else if state.is_postponed {
-                "Postpone".red()
-            } else {
-                "Focus".blue()
-            };
+        let round = match &state.task {
+            _ if !self.show_round => String::new(),
+            Some(task) => format!("Round: {} - {} ", state.round, task),
+            None => format!("Round: {} ", state.round),
+        };
+        let timer_kind = if let Some(prompt) = &status.prompt {
+            format!("{}_", prompt).yellow()
+        } else if let Some(hold) = &state.hold {
+            hold.to_string().magenta()
+        } else if let Some(reminder) = &state.reminder {
+            reminder.to_string().cyan()
+        } else if let Some(banner) = &state.banner {
+            banner.clone().green()
+        } else if state.is_break {
+            "Break".to_string().yellow()
+        } else if state.is_postponed {
+            "Postpone".to_string().red()
+        } else {
+            "Focus".to_string().blue()
+        };
 
-        let postponed_count = if state.is_postponed {
+        let postponed_count = if state.is_postponed && self.show_postpone_count {
             format!(" ({})", state.postpone_count).dark_grey()
         } else {
             "".to_string().white()
         };
 
-        let ansi_erase_line_escape = "\x1B[2K";
-        let ansi_move_cursor_to_start_of_line_escape = "\r";
-
         print!(
-            "{}{}{} {} {}{}",
+            "{}{}{} {}{}{}{}",
             ansi_move_cursor_to_start_of_line_escape,
             ansi_erase_line_escape,
-            if state.is_paused { timer.on_dark_green() } else { timer.on_dark_red() },
+            if state.is_paused {
+                timer.on_dark_green()
+            } else {
+                timer.on_dark_red()
+            },
             round.green(),
             timer_kind,
-            postponed_count
+            postponed_count,
+            status_info
         );
 
         Ok(std::io::stdout().flush()?)
@@ -162,4 +353,18 @@ impl TerminalOut for MinimalInterface {
 
         process::exit(i32::from(is_error))
     }
+
+    fn suspend(&mut self) -> anyhow::Result<()> {
+        disable_raw_mode().context("Could not disable raw mode")?;
+        execute!(std::io::stdout(), Show).context("Could not execute crossterm macros")?;
+
+        // The shell reports the suspended job inside its own line
+        println!();
+        Ok(())
+    }
+
+    fn resume(&mut self) -> anyhow::Result<()> {
+        enable_raw_mode().context("Can't run in raw mode")?;
+        execute!(std::io::stdout(), Hide).context("Could not execute crossterm macros")
+    }
 }
@@ -2,12 +2,40 @@
 
 use zentime_rs_timer::pomodoro_timer::ViewState;
 
+pub use zentime_tui::status::{ClientStatus, ConnectionStatus, Prompt, PromptKind};
+
 /// Describes a message passed from a connection to the [TerminalOutputTask]
+// Views are by far the most frequent event, therefore they are not boxed
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug)]
 pub enum TerminalEvent {
     /// Rendering information with a [ViewState]
     View(ViewState),
 
+    /// The status of the connection to the server has changed
+    Connection(ConnectionStatus),
+
+    /// The server reported an error
+    ServerError(String),
+
+    /// The server could not deliver a notification, therefore the terminal bell is rung and
+    /// its text is shown instead
+    Bell(String),
+
+    /// The text of a prompt, which is being typed, has changed ([None] once the prompt closes)
+    Prompt(Option<Prompt>),
+
+    /// The enforcement of the current break has started (or ended)
+    EnforcedBreak(bool),
+
+    /// The terminal should be restored and the client suspended (see [job_control])
+    ///
+    /// [job_control]: super::job_control
+    Suspend,
+
+    /// The client has been continued, therefore the terminal should be set up and redrawn
+    Resume,
+
     /// The timer received an [AppAction::Quit] and forwards
     /// this information to the view
     Quit {
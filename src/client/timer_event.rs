@@ -0,0 +1,123 @@
+//! Derives discrete timer events (e.g. "break started") from the continuous stream of
+//! [ViewState]s a client receives from the server.
+
+use std::fmt::Display;
+use zentime_rs_timer::pomodoro_timer::ViewState;
+
+/// Event which happened between two consecutive [ViewState]s
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimerEvent {
+    /// A focus interval has started
+    IntervalStart,
+
+    /// A focus interval has ended
+    IntervalEnd,
+
+    /// A break has started (this also happens, when a postponed break resumes)
+    BreakStart,
+
+    /// A break has ended
+    BreakEnd,
+
+    /// A break has been postponed
+    Postpone,
+
+    /// The timer has been paused
+    Pause,
+
+    /// The timer has been resumed
+    Resume,
+}
+
+impl Display for TimerEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            TimerEvent::IntervalStart => "interval_start",
+            TimerEvent::IntervalEnd => "interval_end",
+            TimerEvent::BreakStart => "break_start",
+            TimerEvent::BreakEnd => "break_end",
+            TimerEvent::Postpone => "postpone",
+            TimerEvent::Pause => "pause",
+            TimerEvent::Resume => "resume",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// Phase of the timer as seen by a client
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// Focus interval
+    Focus,
+
+    /// Short or long break
+    Break,
+
+    /// Postponed break
+    Postponed,
+}
+
+impl Phase {
+    /// Determines the phase of a [ViewState]
+    pub fn of(state: &ViewState) -> Self {
+        if state.is_break {
+            Phase::Break
+        } else if state.is_postponed {
+            Phase::Postponed
+        } else {
+            Phase::Focus
+        }
+    }
+}
+
+impl Display for Phase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Phase::Focus => "focus",
+            Phase::Break => "break",
+            Phase::Postponed => "postponed",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// Keeps track of the last received [ViewState] and detects [TimerEvent]s
+#[derive(Debug, Default, Clone)]
+pub struct TimerEventDetector {
+    last_state: Option<ViewState>,
+}
+
+impl TimerEventDetector {
+    /// Compares the given state to the previously received one and returns all events that
+    /// happened in between. The very first state never produces any events.
+    pub fn detect(&mut self, state: &ViewState) -> Vec<TimerEvent> {
+        let Some(last_state) = self.last_state.replace(state.clone()) else {
+            return vec![];
+        };
+
+        let previous_phase = Phase::of(&last_state);
+        let phase = Phase::of(state);
+
+        // A phase change implies a new (paused) timer, therefore we don't report
+        // pause/resume in that case
+        if previous_phase == phase {
+            return match (last_state.is_paused, state.is_paused) {
+                (false, true) => vec![TimerEvent::Pause],
+                (true, false) => vec![TimerEvent::Resume],
+                _ => vec![],
+            };
+        }
+
+        match (previous_phase, phase) {
+            (Phase::Focus, Phase::Break) => vec![TimerEvent::IntervalEnd, TimerEvent::BreakStart],
+            (Phase::Break, Phase::Postponed) => vec![TimerEvent::Postpone],
+            (Phase::Postponed, Phase::Break) => vec![TimerEvent::BreakStart],
+            (Phase::Break | Phase::Postponed, Phase::Focus) => {
+                vec![TimerEvent::BreakEnd, TimerEvent::IntervalStart]
+            }
+            _ => vec![],
+        }
+    }
+}
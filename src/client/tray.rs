@@ -0,0 +1,133 @@
+//! Tray icon of `zentime tray`, which shows the state of the timer inside the system tray (or
+//! menu bar) of the desktop and offers the most common actions in its menu.
+//!
+//! The icon is a StatusNotifierItem, which is shown by KDE, most bars of tiling window managers
+//! (e.g. waybar) and GNOME with the AppIndicator extension. It does not talk to the server
+//! itself: menu entries are sent as [ClientToServerMsg]s to whoever drives the tray, and that
+//! one hands each state it receives to [TimerTray::set_state].
+
+use ksni::menu::StandardItem;
+use ksni::{MenuItem, ToolTip, Tray};
+use tokio::sync::mpsc::UnboundedSender;
+use zentime_rs_timer::pomodoro_timer::ViewState;
+
+use crate::client::overlay::overlay_text;
+use crate::ipc::ClientToServerMsg;
+
+/// Tray icon, which reflects the last state of the server
+#[derive(Debug)]
+pub struct TimerTray {
+    state: Option<ViewState>,
+    actions: UnboundedSender<ClientToServerMsg>,
+}
+
+impl TimerTray {
+    /// Creates a tray without a state (i.e. not connected yet), whose menu entries are sent
+    /// to the given channel. Closing the tray sends [ClientToServerMsg::Detach].
+    pub fn new(actions: UnboundedSender<ClientToServerMsg>) -> Self {
+        Self {
+            state: None,
+            actions,
+        }
+    }
+
+    /// Shows the given state - [None] while there is no connection to the server
+    pub fn set_state(&mut self, state: Option<ViewState>) {
+        self.state = state;
+    }
+
+    /// Text of the tray, e.g. `25m Focus (paused)`
+    pub fn text(&self) -> String {
+        self.state
+            .as_ref()
+            .map_or_else(|| "Not connected".to_string(), overlay_text)
+    }
+
+    fn send(&self, msg: ClientToServerMsg) {
+        // The receiver only goes away once the tray is being shut down
+        self.actions.send(msg).ok();
+    }
+}
+
+impl Tray for TimerTray {
+    fn id(&self) -> String {
+        "zentime".to_string()
+    }
+
+    fn title(&self) -> String {
+        format!("zentime: {}", self.text())
+    }
+
+    /// Icon of the freedesktop icon theme, which tells the phase at a glance
+    fn icon_name(&self) -> String {
+        let name = match &self.state {
+            None => "network-offline",
+            Some(state) if state.is_paused => "media-playback-pause",
+            Some(state) if state.is_break => "face-smile",
+            Some(_) => "appointment-soon",
+        };
+
+        name.to_string()
+    }
+
+    fn tool_tip(&self) -> ToolTip {
+        ToolTip {
+            title: "zentime".to_string(),
+            description: self.text(),
+            ..ToolTip::default()
+        }
+    }
+
+    /// Clicking the icon starts or pauses the timer
+    fn activate(&mut self, _x: i32, _y: i32) {
+        if self.state.is_some() {
+            self.send(ClientToServerMsg::PlayPause);
+        }
+    }
+
+    fn menu(&self) -> Vec<MenuItem<Self>> {
+        let is_connected = self.state.is_some();
+        let is_paused = self.state.as_ref().is_none_or(|state| state.is_paused);
+        let is_break = self.state.as_ref().is_some_and(|state| state.is_break);
+
+        vec![
+            StandardItem {
+                label: self.text(),
+                enabled: false,
+                ..StandardItem::default()
+            }
+            .into(),
+            MenuItem::Separator,
+            StandardItem {
+                label: if is_paused { "Start" } else { "Pause" }.to_string(),
+                enabled: is_connected,
+                activate: Box::new(|tray: &mut Self| tray.send(ClientToServerMsg::PlayPause)),
+                ..StandardItem::default()
+            }
+            .into(),
+            StandardItem {
+                label: "Skip".to_string(),
+                enabled: is_connected,
+                activate: Box::new(|tray: &mut Self| tray.send(ClientToServerMsg::Skip)),
+                ..StandardItem::default()
+            }
+            .into(),
+            StandardItem {
+                label: "Postpone break".to_string(),
+                enabled: is_break,
+                activate: Box::new(|tray: &mut Self| {
+                    tray.send(ClientToServerMsg::PostPone { seconds: None })
+                }),
+                ..StandardItem::default()
+            }
+            .into(),
+            MenuItem::Separator,
+            StandardItem {
+                label: "Close tray icon".to_string(),
+                activate: Box::new(|tray: &mut Self| tray.send(ClientToServerMsg::Detach)),
+                ..StandardItem::default()
+            }
+            .into(),
+        ]
+    }
+}
@@ -0,0 +1,215 @@
+//! Typed async client for the IPC protocol of zentime, so that other Rust applications (e.g.
+//! editors, status bars or GUIs) can integrate with a running server without reimplementing
+//! the socket protocol.
+//!
+//! A [ZentimeClient] takes care of the connection (incl. the heartbeats the server expects from
+//! long-lived clients) and turns the states the server broadcasts into [TimerEvent]s.
+//! Actions are fire-and-forget: the server broadcasts the resulting state to all clients and
+//! reports actions which it has rejected via [ServerToClientMsg::Error].
+//!
+//! ## Example
+//!
+//! ```no_run
+//! use zentime_rs::client_api::ZentimeClient;
+//!
+//! #[tokio::main]
+//! async fn main() -> anyhow::Result<()> {
+//!     let mut client = ZentimeClient::connect().await?;
+//!
+//!     let snapshot = client.state().await?;
+//!     println!("Round {}: {}", snapshot.state.round, snapshot.state.time);
+//!
+//!     client.play_pause().await?;
+//!
+//!     loop {
+//!         let (event, state) = client.next_event().await?;
+//!         println!("{} in round {}", event, state.round);
+//!     }
+//! }
+//! ```
+
+use std::collections::VecDeque;
+use std::fmt::Debug;
+
+use anyhow::Context;
+use tokio::select;
+use tokio::time::Interval;
+use zentime_rs_timer::pomodoro_timer::ViewState;
+
+use crate::client::timer_event::{TimerEvent, TimerEventDetector};
+use crate::ipc::{
+    heartbeat_interval, ClientPreferences, ClientToServerMsg, Connection, Role, ServerAddress,
+    ServerToClientMsg, Snapshot,
+};
+
+/// Connection to a zentime server
+pub struct ZentimeClient {
+    connection: Connection,
+    heartbeat: Interval,
+
+    /// Messages which have been received while waiting for an answer (see [ZentimeClient::state])
+    received: VecDeque<ServerToClientMsg>,
+
+    detector: TimerEventDetector,
+
+    /// Events which have been detected, but not returned yet (together with their state)
+    events: VecDeque<(TimerEvent, ViewState)>,
+}
+
+impl Debug for ZentimeClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ZentimeClient")
+            .field("connection", &self.connection)
+            .finish_non_exhaustive()
+    }
+}
+
+impl From<Connection> for ZentimeClient {
+    fn from(connection: Connection) -> Self {
+        Self {
+            connection,
+            heartbeat: heartbeat_interval(),
+            received: VecDeque::new(),
+            detector: TimerEventDetector::default(),
+            events: VecDeque::new(),
+        }
+    }
+}
+
+impl ZentimeClient {
+    /// Connects to the server of the current user on this machine
+    pub async fn connect() -> anyhow::Result<Self> {
+        Self::connect_to(&ServerAddress::Local).await
+    }
+
+    /// Connects to the given server (e.g. joins a team session)
+    pub async fn connect_to(address: &ServerAddress) -> anyhow::Result<Self> {
+        let connection = address
+            .connect()
+            .await
+            .context("Could not connect to zentime server")?;
+
+        Ok(Self::from(connection))
+    }
+
+    /// Role the client has been granted by the server
+    pub fn role(&self) -> Role {
+        self.connection.role
+    }
+
+    /// Declares what the client wants to receive (e.g. only states which change the phase via
+    /// [crate::ipc::UpdateMode::Events])
+    pub async fn hello(&mut self, preferences: ClientPreferences) -> anyhow::Result<()> {
+        self.send(&ClientToServerMsg::Hello(preferences)).await
+    }
+
+    /// Sends a message to the server
+    pub async fn send(&mut self, msg: &ClientToServerMsg) -> anyhow::Result<()> {
+        self.connection
+            .writer
+            .send(msg)
+            .await
+            .context("Could not send message to zentime server")
+    }
+
+    /// Starts or pauses the timer
+    pub async fn play_pause(&mut self) -> anyhow::Result<()> {
+        self.send(&ClientToServerMsg::PlayPause).await
+    }
+
+    /// Skips to the next interval or break
+    pub async fn skip(&mut self) -> anyhow::Result<()> {
+        self.send(&ClientToServerMsg::Skip).await
+    }
+
+    /// Resets the timer back to the first interval
+    pub async fn reset(&mut self) -> anyhow::Result<()> {
+        self.send(&ClientToServerMsg::Reset).await
+    }
+
+    /// Postpones the current break - [None] uses the configured `postpone_timer`
+    pub async fn postpone(&mut self, seconds: Option<u64>) -> anyhow::Result<()> {
+        self.send(&ClientToServerMsg::PostPone { seconds }).await
+    }
+
+    /// Labels the current session - [None] removes the current label
+    pub async fn set_task(&mut self, task: Option<String>) -> anyhow::Result<()> {
+        self.send(&ClientToServerMsg::SetTask { task }).await
+    }
+
+    /// Queries the current state of the server, from which on [ZentimeClient::next_event]
+    /// detects events.
+    /// States which arrive in the meantime are superseded by the answer, other messages are
+    /// kept for [ZentimeClient::recv].
+    pub async fn state(&mut self) -> anyhow::Result<Snapshot> {
+        self.send(&ClientToServerMsg::Sync).await?;
+
+        let snapshot = loop {
+            match self.recv_from_server().await? {
+                ServerToClientMsg::Snapshot(snapshot) => break snapshot,
+                ServerToClientMsg::Timer(_) => continue,
+                msg => self.received.push_back(msg),
+            }
+        };
+
+        self.received.retain(|msg| {
+            !matches!(
+                msg,
+                ServerToClientMsg::Timer(_) | ServerToClientMsg::Snapshot(_)
+            )
+        });
+
+        for event in self.detector.detect(&snapshot.state) {
+            self.events.push_back((event, snapshot.state.clone()));
+        }
+
+        Ok(snapshot)
+    }
+
+    /// Waits for the next message of the server and keeps the connection alive in the meantime
+    pub async fn recv(&mut self) -> anyhow::Result<ServerToClientMsg> {
+        match self.received.pop_front() {
+            Some(msg) => Ok(msg),
+            None => self.recv_from_server().await,
+        }
+    }
+
+    /// Waits for the next [TimerEvent] and returns it together with the state in which it has
+    /// happened. The first state the client receives never produces any events.
+    pub async fn next_event(&mut self) -> anyhow::Result<(TimerEvent, ViewState)> {
+        loop {
+            if let Some(event) = self.events.pop_front() {
+                return Ok(event);
+            }
+
+            let state = match self.recv().await? {
+                ServerToClientMsg::Timer(state)
+                | ServerToClientMsg::Snapshot(Snapshot { state, .. }) => state,
+                _ => continue,
+            };
+
+            for event in self.detector.detect(&state) {
+                self.events.push_back((event, state.clone()));
+            }
+        }
+    }
+
+    /// Detaches from the server and closes the connection
+    pub async fn detach(mut self) -> anyhow::Result<()> {
+        self.send(&ClientToServerMsg::Detach).await
+    }
+
+    /// Reads the next message from the connection, while sending heartbeats
+    async fn recv_from_server(&mut self) -> anyhow::Result<ServerToClientMsg> {
+        loop {
+            select! {
+                msg = self.connection.reader.recv::<ServerToClientMsg>() => {
+                    return msg.context("Connection to zentime server has been closed");
+                },
+                _ = self.heartbeat.tick() => {
+                    self.send(&ClientToServerMsg::Heartbeat).await?;
+                }
+            }
+        }
+    }
+}
@@ -1,8 +1,26 @@
 //! Code related to the runtime configuration of zentime
 
+mod draft;
+
+pub use draft::ConfigDraft;
+
+use crate::client::timer_event::TimerEvent;
+use crate::server::sound::SoundChannel;
+use chrono::{Datelike, NaiveDateTime, NaiveTime, Weekday};
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fmt::Display;
 use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
 use zentime_rs_timer::config::PomodoroTimerConfig;
+use zentime_rs_timer::duration::HumanDuration;
+use zentime_rs_timer::sequence::{Schedule, TimerSequence};
+use zentime_rs_timer::status_template::StatusTemplate;
+use zentime_rs_timer::ticking_pomodoro_timer::PomodoroPhase;
 
 use figment::{
     providers::{Format, Serialized, Toml},
@@ -20,15 +38,78 @@ pub struct NotificationConfig {
     /// Will default to a bell sound, if `None`
     pub sound_file: Option<String>,
 
+    /// Sounds (and volumes) of the end of intervals, short breaks, the long break and
+    /// postponements, which replace [Self::sound_file]
+    pub sounds: EventSounds,
+
     /// Notification bell volume
     pub volume: f32,
 
     /// Show OS-notification
     pub show_notification: bool,
 
+    /// Adds buttons to the OS-notification at the end of intervals and breaks, which start,
+    /// skip or postpone the next interval/break right from the notification
+    pub actions: bool,
+
+    /// Keep a single OS-notification updated with the remaining time of the current break
+    /// (every 30 seconds), so that the countdown is visible inside the notification shade
+    pub break_countdown: bool,
+
     /// A random suggestion will be picked on each break and shown inside the
-    /// notification text.
+    /// notification text. These replace the exercises of the built-in suggestion provider.
     pub break_suggestions: Option<Vec<String>>,
+
+    /// Provider of the suggestion which is shown at the start of each break: `"builtin"` picks
+    /// one of the stretching exercises shipped with zentime (or one of [Self::break_suggestions]),
+    /// any other value is run as shell command, which prints the suggestion as JSON
+    /// (see [BreakSuggestion](crate::server::notification::BreakSuggestion)).
+    /// Defaults to `"builtin"`, if [Self::break_suggestions] are set.
+    pub suggestion_provider: Option<String>,
+
+    /// URL which receives a JSON `POST` request on each interval/break end.
+    /// This may be a secret reference (e.g. `keyring:zentime/webhook`), see [crate::secrets].
+    pub webhook_url: Option<String>,
+
+    /// Shell commands which are run on each interval/break end
+    pub hooks: Vec<String>,
+
+    /// Number of bell rings per event, so that events can be told apart by ear
+    pub bell_patterns: BellPatterns,
+
+    /// Lowering the volume of other applications while the bell plays
+    pub ducking: DuckingConfig,
+
+    /// Volume and priority of the sounds, which decide what is played back if sounds overlap
+    pub channels: SoundChannels,
+
+    /// What happens if a notification could not be delivered (e.g. because no notification
+    /// daemon is running)
+    pub on_failure: NotificationFailurePolicy,
+
+    /// Attempts to deliver a notification again with `on_failure = "retry"`, before the failure
+    /// is reported
+    pub retries: u8,
+}
+
+/// What happens if a notifier (e.g. OS notifications or a webhook) fails to deliver a
+/// notification
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationFailurePolicy {
+    /// The failure is only logged
+    Silent,
+
+    /// The notification is sent again (with an increasing delay) and the failure is reported,
+    /// once all retries have failed
+    Retry,
+
+    /// Attached clients ring the terminal bell and show the notification text instead
+    Bell,
+
+    /// Attached clients show the error
+    #[default]
+    Report,
 }
 
 impl Default for NotificationConfig {
@@ -36,9 +117,198 @@ impl Default for NotificationConfig {
         NotificationConfig {
             volume: 0.5,
             sound_file: None,
+            sounds: EventSounds::default(),
             enable_bell: true,
             show_notification: true,
+            actions: true,
+            break_countdown: false,
             break_suggestions: None,
+            suggestion_provider: None,
+            webhook_url: None,
+            hooks: vec![],
+            bell_patterns: BellPatterns::default(),
+            ducking: DuckingConfig::default(),
+            channels: SoundChannels::default(),
+            on_failure: NotificationFailurePolicy::default(),
+            retries: 3,
+        }
+    }
+}
+
+/// Configuration of sound ducking, which temporarily lowers the volume of other applications
+/// (e.g. music) while the bell plays, so that it stays audible.
+/// This requires PulseAudio or PipeWire (`pactl`) and is therefore only supported on Linux.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug)]
+pub struct DuckingConfig {
+    /// Lower the volume of other applications while the bell plays
+    pub enabled: bool,
+
+    /// Share of their original volume other applications are lowered to (0.0 - 1.0)
+    pub volume: f32,
+}
+
+impl Default for DuckingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            volume: 0.2,
+        }
+    }
+}
+
+/// Number of times the notification bell rings for each event.
+/// A value of 0 keeps the bell silent for that event.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug)]
+pub struct BellPatterns {
+    /// Rings at the end of a focus interval (i.e. when a break starts)
+    pub interval_end: u8,
+
+    /// Rings at the end of a break
+    pub break_end: u8,
+
+    /// Rings for each reminder
+    pub reminder: u8,
+
+    /// Rings when a pause has lasted for too long (see [PauseConfig])
+    pub pause_timeout: u8,
+
+    /// Rings at the end of a full cycle (i.e. after the long break, which already rings
+    /// for [BellPatterns::break_end])
+    pub cycle_end: u8,
+
+    /// Rings when the timer resumes after `zentime pause-until`
+    pub welcome_back: u8,
+
+    /// Rings for the weekly summary of the focus time goal (see [GoalConfig])
+    pub weekly_goal: u8,
+
+    /// Rings once the daily goal has been reached (see [GoalConfig::daily])
+    pub daily_goal: u8,
+
+    /// Rings when a named timer (e.g. `zentime timer new tea 5m`) or a countdown has run out
+    pub countdown_end: u8,
+
+    /// Silence between two rings in milliseconds
+    pub gap_millis: u64,
+}
+
+impl BellPatterns {
+    /// Silence between two rings
+    pub fn gap(&self) -> Duration {
+        Duration::from_millis(self.gap_millis)
+    }
+}
+
+impl Default for BellPatterns {
+    fn default() -> Self {
+        Self {
+            interval_end: 1,
+            break_end: 3,
+            reminder: 1,
+            pause_timeout: 1,
+            cycle_end: 0,
+            welcome_back: 1,
+            weekly_goal: 0,
+            daily_goal: 0,
+            countdown_end: 2,
+            gap_millis: 250,
+        }
+    }
+}
+
+/// Sound of a single timer event
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct EventSound {
+    /// Sound file of the event - [NotificationConfig::sound_file] is played, if this is `None`
+    pub sound_file: Option<String>,
+
+    /// Volume relative to [NotificationConfig::volume]
+    pub volume: f32,
+}
+
+impl Default for EventSound {
+    fn default() -> Self {
+        Self {
+            sound_file: None,
+            volume: 1.0,
+        }
+    }
+}
+
+/// Sounds of the end of each kind of timer, so that e.g. the end of the long break can be told
+/// apart from the end of a short break
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct EventSounds {
+    /// End of a focus interval
+    pub interval_end: EventSound,
+
+    /// End of a short break
+    pub short_break_end: EventSound,
+
+    /// End of the long break
+    pub long_break_end: EventSound,
+
+    /// End of a postponement (i.e. the postponed break starts)
+    pub postpone: EventSound,
+}
+
+impl EventSounds {
+    /// Sound which is played once the given phase has ended
+    pub fn get(&self, phase: PomodoroPhase) -> &EventSound {
+        match phase {
+            PomodoroPhase::Interval => &self.interval_end,
+            PomodoroPhase::ShortBreak => &self.short_break_end,
+            PomodoroPhase::LongBreak => &self.long_break_end,
+            PomodoroPhase::PostponedShortBreak { .. }
+            | PomodoroPhase::PostponedLongBreak { .. } => &self.postpone,
+        }
+    }
+}
+
+/// Volume and priority of a single sound channel
+#[derive(Deserialize, Serialize, Clone, Copy, Debug)]
+pub struct SoundChannelConfig {
+    /// Volume of the channel relative to [NotificationConfig::volume] (e.g. 0.5 plays it at
+    /// half the volume)
+    pub volume: f32,
+
+    /// If sounds overlap, one with a higher priority interrupts the current sound - all
+    /// others wait until it has finished
+    pub priority: u8,
+}
+
+/// Sound channels, each of which has its own volume and priority.
+/// By default the bell interrupts a reminder chime, but not the other way round.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug)]
+pub struct SoundChannels {
+    /// Notification bell at the end of intervals and breaks (and other timer events)
+    pub bell: SoundChannelConfig,
+
+    /// Chime of reminders (incl. micro-breaks)
+    pub reminder: SoundChannelConfig,
+}
+
+impl SoundChannels {
+    /// Configuration of the given channel
+    pub fn get(&self, channel: SoundChannel) -> SoundChannelConfig {
+        match channel {
+            SoundChannel::Bell => self.bell,
+            SoundChannel::Reminder => self.reminder,
+        }
+    }
+}
+
+impl Default for SoundChannels {
+    fn default() -> Self {
+        Self {
+            bell: SoundChannelConfig {
+                volume: 1.0,
+                priority: 2,
+            },
+            reminder: SoundChannelConfig {
+                volume: 1.0,
+                priority: 1,
+            },
         }
     }
 }
@@ -52,6 +322,39 @@ pub struct ViewConfig {
     /// Suppresses the output of one-shot commands
     /// (e.g. `zentime skip` or `zentime toggle-timer`)
     pub silent: bool,
+
+    /// Shows the remaining time and phase inside the title of the terminal window/tab, so
+    /// that the timer stays visible while the client is in a background tab
+    pub terminal_title: bool,
+
+    /// Shell commands which are run by attached clients on timer events
+    pub exec_on: EventCommands,
+
+    /// Layout of the default interface
+    pub layout: TuiLayout,
+
+    /// Shows the round counter (and the active profile and task)
+    pub show_round: bool,
+
+    /// Shows how often a break has been postponed
+    pub show_postpone_count: bool,
+
+    /// Colors of the default interface
+    pub colors: ThemeColors,
+
+    /// Lets the default interface take over the whole terminal during breaks, with skipping
+    /// and postponing disabled
+    pub enforce_breaks: BreakEnforcement,
+
+    /// Seconds between two updates of the remaining time by the server (0 = every second).
+    /// Attached clients count down on their own in between, which saves IPC traffic.
+    #[serde(deserialize_with = "zentime_rs_timer::duration::deserialize_seconds")]
+    pub update_interval: u64,
+
+    /// Template of single-line status output (e.g. `"{round} {time} {state_icon}"`), which is
+    /// used by `zentime status`, the compact layout and the minimal interface instead of
+    /// their built-in line. See [StatusTemplate] for the available placeholders.
+    pub format: Option<StatusTemplate>,
 }
 
 impl Default for ViewConfig {
@@ -59,10 +362,1146 @@ impl Default for ViewConfig {
         Self {
             interface: "default".to_string(),
             silent: false,
+            terminal_title: false,
+            exec_on: EventCommands::default(),
+            layout: TuiLayout::default(),
+            show_round: true,
+            show_postpone_count: true,
+            colors: ThemeColors::default(),
+            enforce_breaks: BreakEnforcement::default(),
+            update_interval: 0,
+            format: None,
+        }
+    }
+}
+
+/// Layout of the default interface
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TuiLayout {
+    /// Timer information and the timer side by side inside boxes, with the key bindings below
+    #[default]
+    Default,
+
+    /// A single line with the timer, the phase and the round - without any boxes or key bindings
+    Compact,
+
+    /// The compact line plus a line of key bindings inside a small fixed area, in which `q`
+    /// only closes the client (used by `zentime popup`)
+    Popup,
+}
+
+/// When the default interface enforces a break by taking over the whole terminal with a large
+/// countdown. While a break is enforced, it can't be skipped, postponed or shortened.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakEnforcement {
+    /// Breaks are never enforced
+    #[default]
+    Off,
+
+    /// Every break is enforced
+    Always,
+
+    /// Breaks are enforced once they can't be postponed anymore (see
+    /// [PomodoroTimerConfig::postpone_limit])
+    AfterPostpones,
+}
+
+/// Colors of the default interface, so that it can be matched to the theme of the terminal
+/// (e.g. `text = "reset"` keeps the default text color of a light terminal theme)
+#[derive(Deserialize, Serialize, Clone, Copy, Debug)]
+pub struct ThemeColors {
+    /// Phase label during focus intervals
+    pub focus: ThemeColor,
+
+    /// Phase label during breaks
+    #[serde(rename = "break")]
+    pub break_phase: ThemeColor,
+
+    /// Phase label while a break is postponed
+    pub postponed: ThemeColor,
+
+    /// Remaining time
+    pub timer: ThemeColor,
+
+    /// Plain text (e.g. the title)
+    pub text: ThemeColor,
+
+    /// Round counter
+    pub round: ThemeColor,
+
+    /// Key bindings and the postpone count
+    pub hints: ThemeColor,
+
+    /// Errors and the connection status
+    pub error: ThemeColor,
+}
+
+impl Default for ThemeColors {
+    fn default() -> Self {
+        Self {
+            focus: ThemeColor::Blue,
+            break_phase: ThemeColor::Yellow,
+            postponed: ThemeColor::Red,
+            timer: ThemeColor::Cyan,
+            text: ThemeColor::White,
+            round: ThemeColor::Gray,
+            hints: ThemeColor::DarkGray,
+            error: ThemeColor::Red,
+        }
+    }
+}
+
+/// Terminal color, which is either a name of the 16 standard colors (e.g. `"dark_gray"`),
+/// `"reset"` for the default color of the terminal or a hex code (e.g. `"#1e90ff"`)
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(try_from = "String", into = "String")]
+#[allow(missing_docs)]
+pub enum ThemeColor {
+    Reset,
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    Gray,
+    DarkGray,
+    LightRed,
+    LightGreen,
+    LightYellow,
+    LightBlue,
+    LightMagenta,
+    LightCyan,
+    White,
+    Rgb(u8, u8, u8),
+}
+
+/// Names of the standard colors
+const THEME_COLOR_NAMES: &[(&str, ThemeColor)] = &[
+    ("reset", ThemeColor::Reset),
+    ("black", ThemeColor::Black),
+    ("red", ThemeColor::Red),
+    ("green", ThemeColor::Green),
+    ("yellow", ThemeColor::Yellow),
+    ("blue", ThemeColor::Blue),
+    ("magenta", ThemeColor::Magenta),
+    ("cyan", ThemeColor::Cyan),
+    ("gray", ThemeColor::Gray),
+    ("dark_gray", ThemeColor::DarkGray),
+    ("light_red", ThemeColor::LightRed),
+    ("light_green", ThemeColor::LightGreen),
+    ("light_yellow", ThemeColor::LightYellow),
+    ("light_blue", ThemeColor::LightBlue),
+    ("light_magenta", ThemeColor::LightMagenta),
+    ("light_cyan", ThemeColor::LightCyan),
+    ("white", ThemeColor::White),
+];
+
+impl FromStr for ThemeColor {
+    type Err = String;
+
+    fn from_str(color: &str) -> Result<Self, Self::Err> {
+        let color = color.trim().to_lowercase().replace(['-', ' '], "_");
+
+        if let Some(hex) = color.strip_prefix('#') {
+            let channel = |range| {
+                hex.get(range)
+                    .and_then(|channel| u8::from_str_radix(channel, 16).ok())
+            };
+
+            if hex.len() == 6 {
+                if let (Some(r), Some(g), Some(b)) = (channel(0..2), channel(2..4), channel(4..6)) {
+                    return Ok(ThemeColor::Rgb(r, g, b));
+                }
+            }
+        }
+
+        THEME_COLOR_NAMES
+            .iter()
+            .chain(&[
+                ("grey", ThemeColor::Gray),
+                ("dark_grey", ThemeColor::DarkGray),
+            ])
+            .find(|(name, _)| *name == color)
+            .map(|(_, theme_color)| *theme_color)
+            .ok_or_else(|| {
+                format!(
+                    "Unknown color '{}' - expected a name (e.g. 'dark_gray'), 'reset' or a hex \
+                    code (e.g. '#1e90ff')",
+                    color
+                )
+            })
+    }
+}
+
+impl TryFrom<String> for ThemeColor {
+    type Error = String;
+
+    fn try_from(color: String) -> Result<Self, Self::Error> {
+        color.parse()
+    }
+}
+
+impl From<ThemeColor> for String {
+    fn from(color: ThemeColor) -> Self {
+        color.to_string()
+    }
+}
+
+impl Display for ThemeColor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let ThemeColor::Rgb(r, g, b) = self {
+            return write!(f, "#{:02x}{:02x}{:02x}", r, g, b);
+        }
+
+        let name = THEME_COLOR_NAMES
+            .iter()
+            .find(|(_, color)| color == self)
+            .map_or("reset", |(name, _)| name);
+
+        write!(f, "{}", name)
+    }
+}
+
+/// Shell commands which an attached client runs on timer events.
+/// The commands receive the same environment variables as hooks (see [crate::hooks]).
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct EventCommands {
+    /// Run whenever a focus interval starts
+    pub interval_start: Option<String>,
+
+    /// Run whenever a focus interval ends
+    pub interval_end: Option<String>,
+
+    /// Run whenever a break starts
+    pub break_start: Option<String>,
+
+    /// Run whenever a break ends
+    pub break_end: Option<String>,
+}
+
+/// Shell commands which the server runs on timer events (e.g. to toggle do-not-disturb whenever
+/// an interval starts). The commands receive the same environment variables as hooks (see
+/// [crate::hooks]).
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct HooksConfig {
+    /// Run whenever a focus interval starts
+    pub on_interval_start: Option<String>,
+
+    /// Run whenever a focus interval ends
+    pub on_interval_end: Option<String>,
+
+    /// Run whenever a break starts (this also happens, when a postponed break resumes)
+    pub on_break_start: Option<String>,
+
+    /// Run whenever a break ends
+    pub on_break_end: Option<String>,
+
+    /// Run whenever a break is postponed
+    pub on_postpone: Option<String>,
+
+    /// Run whenever the timer is paused
+    pub on_pause: Option<String>,
+
+    /// Run whenever the timer is resumed
+    pub on_resume: Option<String>,
+
+    /// Run whenever a named timer or a one-off countdown has run out (`ZENTIME_REMINDER` holds
+    /// its name or label)
+    pub on_countdown_end: Option<String>,
+}
+
+impl HooksConfig {
+    /// Denotes if no command has been configured at all
+    pub fn is_empty(&self) -> bool {
+        self.commands().all(|(_, command)| command.is_none()) && self.on_countdown_end.is_none()
+    }
+
+    /// Command which has been configured for the given event
+    pub fn command(&self, event: TimerEvent) -> Option<&str> {
+        self.commands()
+            .find_map(|(known, command)| (known == event).then_some(command)?)
+    }
+
+    /// All timer events and the commands which have been configured for them
+    /// ([Self::on_countdown_end] is run for a notification instead of a timer event)
+    pub fn commands(&self) -> impl Iterator<Item = (TimerEvent, Option<&str>)> {
+        [
+            (TimerEvent::IntervalStart, &self.on_interval_start),
+            (TimerEvent::IntervalEnd, &self.on_interval_end),
+            (TimerEvent::BreakStart, &self.on_break_start),
+            (TimerEvent::BreakEnd, &self.on_break_end),
+            (TimerEvent::Postpone, &self.on_postpone),
+            (TimerEvent::Pause, &self.on_pause),
+            (TimerEvent::Resume, &self.on_resume),
+        ]
+        .into_iter()
+        .map(|(event, command)| (event, command.as_deref()))
+    }
+}
+
+/// Configuration of battery-aware behavior
+#[derive(Deserialize, Serialize, Clone, Copy, Debug)]
+pub struct PowerConfig {
+    /// Reduce the timer tick and broadcast frequency while running on low battery
+    pub battery_saver: bool,
+
+    /// Battery percentage below which zentime starts to save power
+    pub battery_threshold: u8,
+
+    /// Disable sound playback while running on low battery
+    pub mute_on_low_battery: bool,
+}
+
+impl Default for PowerConfig {
+    fn default() -> Self {
+        Self {
+            battery_saver: true,
+            battery_threshold: 20,
+            mute_on_low_battery: false,
+        }
+    }
+}
+
+/// What happens to a timer, which has been paused for longer than
+/// [PauseConfig::max_pause_minutes]
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PauseTimeoutAction {
+    /// Resume the timer
+    #[default]
+    Resume,
+
+    /// Restart the current interval/break from the beginning (the timer stays paused)
+    Reset,
+}
+
+/// Configuration of pauses, which prevents that a timer stays paused forever after an
+/// interruption
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default)]
+pub struct PauseConfig {
+    /// Minutes after which a paused interval/break is resumed or reset (see
+    /// [PauseConfig::on_timeout]). A value of 0 disables this.
+    pub max_pause_minutes: u64,
+
+    /// What happens once the pause has lasted for [PauseConfig::max_pause_minutes]
+    pub on_timeout: PauseTimeoutAction,
+}
+
+impl PauseConfig {
+    /// Maximum length of a pause - [None] if pauses may last forever
+    pub fn max_pause(&self) -> Option<Duration> {
+        (self.max_pause_minutes > 0).then(|| Duration::from_secs(self.max_pause_minutes * 60))
+    }
+}
+
+/// Configuration of the alignment of intervals to wall-clock boundaries, e.g. for workplaces
+/// which schedule around half-hours
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default)]
+pub struct AlignmentConfig {
+    /// Intervals start at multiples of this many seconds after midnight (e.g. "30m" for :00 and
+    /// :30) - the server waits for the next boundary, before it runs a started interval.
+    /// A value of 0 disables this.
+    #[serde(deserialize_with = "zentime_rs_timer::duration::deserialize_seconds")]
+    pub interval: u64,
+}
+
+impl AlignmentConfig {
+    /// Distance between two boundaries - [None] if intervals are not aligned
+    pub fn boundary(&self) -> Option<Duration> {
+        (self.interval > 0).then(|| Duration::from_secs(self.interval))
+    }
+}
+
+/// Configuration of a reminder channel, which sends a recurring nudge (e.g. to drink some water)
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct ReminderConfig {
+    /// Name of the reminder, which is shown inside notifications
+    pub name: String,
+
+    /// Running time of the timer between two reminders in seconds
+    #[serde(deserialize_with = "zentime_rs_timer::duration::deserialize_seconds")]
+    pub interval: u64,
+
+    /// Text which is shown for each reminder
+    pub message: String,
+
+    /// Seconds during which the reminder is displayed by clients (0 = not displayed)
+    #[serde(
+        default,
+        deserialize_with = "zentime_rs_timer::duration::deserialize_seconds"
+    )]
+    pub duration: u64,
+
+    /// Play the notification bell on each reminder
+    #[serde(default)]
+    pub enable_bell: bool,
+
+    /// Only count focus time and restart the reminder after each break
+    #[serde(default)]
+    pub focus_only: bool,
+}
+
+/// Configuration of micro-breaks (20-20-20 rule): every `interval` seconds of focus time, look at
+/// something 20 feet away for `duration` seconds
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct MicroBreakConfig {
+    /// Enable/Disable micro-breaks
+    pub enabled: bool,
+
+    /// Focus time between two micro-breaks in seconds
+    #[serde(deserialize_with = "zentime_rs_timer::duration::deserialize_seconds")]
+    pub interval: u64,
+
+    /// Length of a micro-break in seconds
+    #[serde(deserialize_with = "zentime_rs_timer::duration::deserialize_seconds")]
+    pub duration: u64,
+
+    /// Text which is shown during a micro-break
+    pub message: String,
+
+    /// Play the notification bell on each micro-break
+    pub enable_bell: bool,
+}
+
+impl MicroBreakConfig {
+    /// Reminder channel which raises the micro-breaks, if they have been enabled
+    pub fn reminder(&self) -> Option<ReminderConfig> {
+        self.enabled.then(|| ReminderConfig {
+            name: "micro-break".to_string(),
+            interval: self.interval,
+            message: self.message.clone(),
+            duration: self.duration,
+            enable_bell: self.enable_bell,
+            focus_only: true,
+        })
+    }
+}
+
+impl Default for MicroBreakConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval: 20 * 60,
+            duration: 20,
+            message: "Look at something 20 feet away for 20 seconds".to_string(),
+            enable_bell: false,
+        }
+    }
+}
+
+/// Configuration of the focus contract: once enabled, every interval is a promise to stay
+/// focused until it has run out. Skipping or resetting an interval which has already started
+/// (incl. a reset after a long pause, see [PauseConfig]) is recorded as a broken contract in the
+/// history (see `zentime stats`).
+#[derive(Deserialize, Serialize, Clone, Copy, Debug)]
+pub struct FocusContractConfig {
+    /// Enable/Disable the focus contract
+    pub enabled: bool,
+
+    /// Ask for confirmation inside the terminal interface, before an interval is given up
+    pub confirm: bool,
+}
+
+impl Default for FocusContractConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            confirm: true,
+        }
+    }
+}
+
+/// Configuration of break enforcement: while a break is running (or waiting to be started), its
+/// notification is raised again and again. The longer the break is ignored, the more urgent the
+/// notification gets and the screen is dimmed briefly.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct BreakNudgeConfig {
+    /// Enable/Disable break enforcement
+    pub enabled: bool,
+
+    /// Time between two nudges in seconds
+    #[serde(deserialize_with = "zentime_rs_timer::duration::deserialize_seconds")]
+    pub interval: u64,
+
+    /// Dim the screen briefly from the second nudge on
+    pub dim: bool,
+
+    /// Seconds the screen stays dimmed
+    #[serde(deserialize_with = "zentime_rs_timer::duration::deserialize_seconds")]
+    pub dim_duration: u64,
+
+    /// Shell command which sets the brightness of the screen. `{level}` is replaced by a factor
+    /// between 0 and 1 (e.g. `0.6`) and `{percent}` by the same value in percent (e.g. `60`),
+    /// a level of 1 restores the screen. Defaults to `xgamma` on Linux (X11).
+    pub dim_command: Option<String>,
+}
+
+impl Default for BreakNudgeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval: 60,
+            dim: true,
+            dim_duration: 2,
+            dim_command: None,
+        }
+    }
+}
+
+/// Configuration of idle detection: a running focus interval is paused, once there hasn't been
+/// any keyboard or mouse input for a while
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct IdleConfig {
+    /// Enable/Disable idle detection
+    pub enabled: bool,
+
+    /// Time without any input in seconds, after which the interval is paused
+    #[serde(deserialize_with = "zentime_rs_timer::duration::deserialize_seconds")]
+    pub threshold: u64,
+
+    /// Resume the interval as soon as there is input again
+    pub resume: bool,
+
+    /// Shell command which prints the idle time in milliseconds. Defaults to the idle monitor of
+    /// GNOME on Wayland, `xprintidle` on X11 and to the event source of the window server on
+    /// macOS.
+    pub command: Option<String>,
+}
+
+impl Default for IdleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            threshold: 300,
+            resume: false,
+            command: None,
+        }
+    }
+}
+
+/// How do-not-disturb is switched (see [DndConfig])
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DndBackend {
+    /// Dunst on Linux and the do-not-disturb setting of the notification center on macOS
+    #[default]
+    Auto,
+
+    /// Pauses the notifications of dunst via `dunstctl`
+    Dunst,
+
+    /// Do-not-disturb setting of the notification center of macOS (until macOS 11)
+    Macos,
+
+    /// Runs `enable_command` and `disable_command`
+    Command,
+}
+
+/// Configuration of do-not-disturb: while a focus interval is running, notifications of other
+/// applications are held back. Afterwards do-not-disturb is set back to what it has been before.
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct DndConfig {
+    /// Enable/Disable do-not-disturb during focus intervals
+    pub enabled: bool,
+
+    /// How do-not-disturb is switched
+    #[serde(default)]
+    pub backend: DndBackend,
+
+    /// Shell command which enables do-not-disturb (see [DndBackend::Command])
+    #[serde(default)]
+    pub enable_command: Option<String>,
+
+    /// Shell command which disables do-not-disturb (see [DndBackend::Command])
+    #[serde(default)]
+    pub disable_command: Option<String>,
+}
+
+/// Storage format of the session history
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryBackend {
+    /// Plain text file with one JSON object per session
+    #[default]
+    JsonLines,
+
+    /// SQLite database, which can be queried with any SQLite client
+    /// (requires the `sqlite` feature)
+    Sqlite,
+}
+
+/// Configuration of the session history
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct HistoryConfig {
+    /// Record each interval and break, so that they can be evaluated by `zentime stats`
+    pub enabled: bool,
+
+    /// Storage format of the history
+    #[serde(default)]
+    pub backend: HistoryBackend,
+
+    /// Path of the history file (defaults to a file inside `~/.local/share/zentime`, which
+    /// depends on the backend)
+    #[serde(default)]
+    pub path: Option<String>,
+}
+
+impl HistoryConfig {
+    /// Path of the history file (`~` is not expanded yet)
+    pub fn path(&self) -> &str {
+        match (&self.path, self.backend) {
+            (Some(path), _) => path,
+            (None, HistoryBackend::JsonLines) => "~/.local/share/zentime/history.jsonl",
+            (None, HistoryBackend::Sqlite) => "~/.local/share/zentime/history.sqlite",
+        }
+    }
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            backend: HistoryBackend::default(),
+            path: None,
+        }
+    }
+}
+
+/// Context which is captured at the start of an interval
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ContextSource {
+    /// Title of the active window (tagged as `window:<title>`)
+    Window,
+
+    /// Name of the git repository (tagged as `git:<name>`)
+    Git,
+}
+
+/// Configuration of context capture, which tags each interval with what has been worked on at
+/// its start, so that `zentime stats` can show which project got the focus.
+/// Tags are only stored inside the local history.
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct ContextConfig {
+    /// Context which is captured - nothing is captured, if this is empty
+    #[serde(default)]
+    pub capture: Vec<ContextSource>,
+
+    /// Shell command which prints the title of the active window.
+    /// Defaults to `xdotool` on Linux (X11) and to AppleScript on macOS.
+    #[serde(default)]
+    pub window_command: Option<String>,
+
+    /// Directory inside the git repository (defaults to the directory the server has been
+    /// started in)
+    #[serde(default)]
+    pub git_directory: Option<String>,
+}
+
+/// Configuration of the plan of the day (see `zentime plan`)
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct PlanConfig {
+    /// File the plan is kept in
+    pub path: String,
+}
+
+impl PlanConfig {
+    /// Path of the plan file (with `~` expanded)
+    pub fn path(&self) -> PathBuf {
+        PathBuf::from(shellexpand::tilde(&self.path).as_ref())
+    }
+}
+
+impl Default for PlanConfig {
+    fn default() -> Self {
+        Self {
+            path: "~/.local/share/zentime/plan.json".to_string(),
+        }
+    }
+}
+
+/// Configuration of the state file, which the server keeps its timer in (see `--resume`)
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct StateConfig {
+    /// Continue the timer of the previous server (round, phase, remaining time and postpone
+    /// count) instead of starting over with the first interval
+    pub resume: bool,
+
+    /// File the state of the timer is kept in
+    pub path: String,
+}
+
+impl StateConfig {
+    /// Path of the state file (with `~` expanded)
+    pub fn path(&self) -> PathBuf {
+        PathBuf::from(shellexpand::tilde(&self.path).as_ref())
+    }
+}
+
+impl Default for StateConfig {
+    fn default() -> Self {
+        Self {
+            resume: false,
+            path: "~/.local/share/zentime/state.json".to_string(),
+        }
+    }
+}
+
+/// Configuration of the weekly report, which the server writes as markdown file (and optionally
+/// sends via email)
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct ReportConfig {
+    /// Write a report of the past week once a week
+    pub enabled: bool,
+
+    /// Day of the week on which the report is written
+    pub weekday: Weekday,
+
+    /// Local time at which the report is written
+    pub time: NaiveTime,
+
+    /// Directory the reports are written to (one file per week)
+    pub directory: String,
+
+    /// Address the report is sent to - no email is sent, if this is not set
+    pub email: Option<String>,
+
+    /// Shell command which reads the email (including its headers) from stdin and sends it
+    pub sendmail: String,
+}
+
+impl Default for ReportConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            weekday: Weekday::Sun,
+            time: NaiveTime::from_hms_opt(18, 0, 0).unwrap_or(NaiveTime::MIN),
+            directory: "~/.local/share/zentime/reports".to_string(),
+            email: None,
+            sendmail: "sendmail -t".to_string(),
+        }
+    }
+}
+
+/// Focus time goals per week and month, whose progress is shown by `zentime stats`, and a
+/// goal of completed intervals per day, whose progress is shown by all clients
+#[derive(Deserialize, Serialize, Clone, Copy, Debug)]
+pub struct GoalConfig {
+    /// Intervals which should run out per calendar day (e.g. `8`) - there is no daily goal, if
+    /// this is not set
+    pub daily: Option<u16>,
+
+    /// Show a notification as soon as the daily goal has been reached
+    pub daily_notification: bool,
+
+    /// Focus time per (ISO) calendar week (e.g. `20h`) - there is no weekly goal, if this is
+    /// not set
+    pub weekly: Option<HumanDuration>,
+
+    /// Focus time per calendar month (e.g. `80h`)
+    pub monthly: Option<HumanDuration>,
+
+    /// Adds the focus time, which has been missing to reach the goal of the previous
+    /// week/month, to the current goal
+    pub carry_over: bool,
+
+    /// Show a notification at the end of each week, which tells whether the weekly goal has
+    /// been met
+    pub weekly_summary: bool,
+
+    /// Local time on sunday at which the weekly summary is shown
+    pub summary_time: NaiveTime,
+}
+
+impl Default for GoalConfig {
+    fn default() -> Self {
+        Self {
+            daily: None,
+            daily_notification: true,
+            weekly: None,
+            monthly: None,
+            carry_over: true,
+            weekly_summary: false,
+            summary_time: NaiveTime::from_hms_opt(18, 0, 0).unwrap_or(NaiveTime::MIN),
+        }
+    }
+}
+
+/// Configuration of team mode, in which participants on other machines join the timer of this
+/// server via `zentime join`
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct TeamConfig {
+    /// Address on which the server accepts participants (e.g. "0.0.0.0:7878").
+    /// Team mode is disabled, if this is not set.
+    pub listen: Option<String>,
+
+    /// Token which allows participants to control the timer (may be a secret reference)
+    pub control_token: Option<String>,
+
+    /// Token which allows participants to watch the timer (may be a secret reference)
+    pub viewer_token: Option<String>,
+}
+
+/// Configuration of the optional HTTP server, which serves a spectator page showing the live
+/// timer
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct HttpConfig {
+    /// Address the HTTP server listens on (e.g. "127.0.0.1:7879").
+    /// The HTTP server is disabled, if this is not set.
+    pub listen: Option<String>,
+
+    /// Serve the recorded and planned focus blocks as calendar feed (`/focus.ics`)
+    #[serde(default)]
+    pub calendar: bool,
+}
+
+/// Configuration of the JSON API, which lets other applications (e.g. browser widgets, Stream
+/// Deck plugins or home automation) control the timer via HTTP. It only listens on localhost and
+/// only answers requests which carry the token as `Authorization: Bearer <token>` header.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct ApiConfig {
+    /// Enable/Disable the API (requires the `http` feature)
+    pub enabled: bool,
+
+    /// Port on localhost the API listens on
+    pub port: u16,
+
+    /// Token which requests have to send. Might be a secret reference
+    /// (e.g. `keyring:zentime/api`, see [crate::secrets]).
+    /// The API is not served without a token.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+
+    /// Origin of a web page (e.g. `https://widgets.example.com`), which may read the responses of
+    /// the API inside the browser. Pages of other origins (except for localhost) are rejected.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_origin: Option<String>,
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 8080,
+            token: None,
+            allowed_origin: None,
+        }
+    }
+}
+
+/// Name of the timer profile, which uses [Config::timers] as they are (unless it has been
+/// overridden via `[profiles.default]`)
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Named set of timer lengths (e.g. "deep-work" with 50 minute intervals and 10 minute breaks),
+/// which can be switched to at runtime. Lengths which are left out are taken from
+/// [Config::timers].
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct TimerProfile {
+    /// Length of an interval (e.g. `50m`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timer: Option<HumanDuration>,
+
+    /// Length of a minor break
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub minor_break: Option<HumanDuration>,
+
+    /// Length of a major break
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub major_break: Option<HumanDuration>,
+
+    /// Intervals before a major break
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub intervals: Option<u64>,
+
+    /// How often a break may be postponed (0 disables postponing)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub postpone_limit: Option<u16>,
+
+    /// Length of a postponed break
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub postpone_timer: Option<HumanDuration>,
+}
+
+impl TimerProfile {
+    /// Overrides the lengths of the given timer config with the ones of this profile
+    pub fn apply(&self, timers: PomodoroTimerConfig) -> PomodoroTimerConfig {
+        PomodoroTimerConfig {
+            timer: self.timer.map_or(timers.timer, |timer| timer.as_secs()),
+            minor_break: self
+                .minor_break
+                .map_or(timers.minor_break, |minor_break| minor_break.as_secs()),
+            major_break: self
+                .major_break
+                .map_or(timers.major_break, |major_break| major_break.as_secs()),
+            intervals: self.intervals.unwrap_or(timers.intervals),
+            postpone_limit: self.postpone_limit.unwrap_or(timers.postpone_limit),
+            postpone_timer: self
+                .postpone_timer
+                .map_or(timers.postpone_timer, |postpone_timer| {
+                    postpone_timer.as_secs()
+                }),
+            ..timers
         }
     }
 }
 
+/// Configuration of client connections to the server
+#[derive(Deserialize, Serialize, Clone, Copy, Debug)]
+pub struct ConnectionConfig {
+    /// Seconds after which a client, which has not sent any message (incl. heartbeats), is
+    /// considered dead and its connection is closed. 0 keeps connections open until the client
+    /// closes them.
+    #[serde(deserialize_with = "zentime_rs_timer::duration::deserialize_seconds")]
+    pub heartbeat_timeout: u64,
+}
+
+impl ConnectionConfig {
+    /// [Self::heartbeat_timeout] as [Duration] - [None] if connections never time out
+    pub fn heartbeat_timeout(&self) -> Option<Duration> {
+        (self.heartbeat_timeout > 0).then(|| Duration::from_secs(self.heartbeat_timeout))
+    }
+}
+
+impl Default for ConnectionConfig {
+    fn default() -> Self {
+        Self {
+            heartbeat_timeout: 30,
+        }
+    }
+}
+
+/// Minimum level of log entries which are written
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LogLevel {
+    /// Nothing is logged
+    Off,
+
+    /// Only errors
+    Error,
+
+    /// Errors and warnings
+    #[default]
+    Warn,
+
+    /// Errors, warnings and what the server is doing (e.g. connections, notifications)
+    Info,
+
+    /// Additionally details which help to debug zentime
+    Debug,
+
+    /// Everything
+    Trace,
+}
+
+impl From<LogLevel> for log::LevelFilter {
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::Off => log::LevelFilter::Off,
+            LogLevel::Error => log::LevelFilter::Error,
+            LogLevel::Warn => log::LevelFilter::Warn,
+            LogLevel::Info => log::LevelFilter::Info,
+            LogLevel::Debug => log::LevelFilter::Debug,
+            LogLevel::Trace => log::LevelFilter::Trace,
+        }
+    }
+}
+
+/// Configuration of the log files of the server and of clients
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct LogConfig {
+    /// Minimum level of log entries (`RUST_LOG` takes precedence)
+    pub level: LogLevel,
+
+    /// File the server logs to
+    pub file: String,
+
+    /// File clients log to
+    pub client_file: String,
+
+    /// Write one JSON object per entry instead of plain text lines
+    pub json: bool,
+
+    /// Size in KiB after which a log file is rotated
+    pub max_size: u64,
+
+    /// Number of rotated files which are kept (e.g. `server.log.1`, `server.log.2`, ...)
+    pub max_files: usize,
+}
+
+impl LogConfig {
+    /// Path of the server log (with `~` expanded)
+    pub fn file(&self) -> PathBuf {
+        PathBuf::from(shellexpand::tilde(&self.file).as_ref())
+    }
+
+    /// Path of the client log (with `~` expanded)
+    pub fn client_file(&self) -> PathBuf {
+        PathBuf::from(shellexpand::tilde(&self.client_file).as_ref())
+    }
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self {
+            level: LogLevel::default(),
+            file: "~/.local/state/zentime/server.log".to_string(),
+            client_file: "~/.local/state/zentime/client.log".to_string(),
+            json: false,
+            max_size: 1024,
+            max_files: 3,
+        }
+    }
+}
+
+/// Configuration of the wall clock, which is used by features based on the time of day
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default)]
+pub struct ClockConfig {
+    /// IANA name of the time zone (e.g. "Europe/Berlin"), which overrides the time zone of the
+    /// operating system (e.g. while traveling)
+    pub time_zone: Option<Tz>,
+}
+
+/// Timer lengths which differ by the day of the week and quiet hours, both of which the server
+/// follows by the wall clock (see [ClockConfig]) without being restarted
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct ScheduleConfig {
+    /// Lengths on mondays (e.g. `[schedule.monday]`), which override [Config::timers] - the
+    /// active timer profile still takes precedence
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub monday: Option<TimerProfile>,
+
+    /// Lengths on tuesdays
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tuesday: Option<TimerProfile>,
+
+    /// Lengths on wednesdays
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wednesday: Option<TimerProfile>,
+
+    /// Lengths on thursdays
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thursday: Option<TimerProfile>,
+
+    /// Lengths on fridays
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub friday: Option<TimerProfile>,
+
+    /// Lengths on saturdays
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub saturday: Option<TimerProfile>,
+
+    /// Lengths on sundays
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sunday: Option<TimerProfile>,
+
+    /// Times of the day during which notifications are silent and/or the timer does not start
+    /// on its own (e.g. `[[schedule.quiet_hours]]`)
+    pub quiet_hours: Vec<QuietHours>,
+}
+
+impl ScheduleConfig {
+    /// Lengths of the given day of the week (if they differ from [Config::timers])
+    pub fn day(&self, weekday: Weekday) -> Option<&TimerProfile> {
+        match weekday {
+            Weekday::Mon => self.monday.as_ref(),
+            Weekday::Tue => self.tuesday.as_ref(),
+            Weekday::Wed => self.wednesday.as_ref(),
+            Weekday::Thu => self.thursday.as_ref(),
+            Weekday::Fri => self.friday.as_ref(),
+            Weekday::Sat => self.saturday.as_ref(),
+            Weekday::Sun => self.sunday.as_ref(),
+        }
+    }
+
+    /// Denotes if notifications are silent at the given local time
+    pub fn is_silent(&self, time: NaiveDateTime) -> bool {
+        self.quiet_hours
+            .iter()
+            .any(|quiet_hours| quiet_hours.silent && quiet_hours.contains(time))
+    }
+
+    /// Denotes if intervals and breaks may start on their own at the given local time (see
+    /// `timers.auto_start_break` and `timers.auto_start_work`)
+    pub fn allows_auto_start(&self, time: NaiveDateTime) -> bool {
+        !self
+            .quiet_hours
+            .iter()
+            .any(|quiet_hours| !quiet_hours.auto_start && quiet_hours.contains(time))
+    }
+}
+
+/// Time of the day during which the timer keeps quiet (e.g. in the evening)
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(default)]
+pub struct QuietHours {
+    /// Local time at which the quiet hours start (e.g. `"22:00"`)
+    pub from: NaiveTime,
+
+    /// Local time at which the quiet hours end. Quiet hours which end before they start last
+    /// past midnight, the same time as [Self::from] means the whole day.
+    pub to: NaiveTime,
+
+    /// Days of the week on which the quiet hours start (e.g. `["sat", "sun"]`) - every day, if
+    /// this is empty
+    pub weekdays: Vec<Weekday>,
+
+    /// Notifications are shown without any sound
+    pub silent: bool,
+
+    /// Intervals and breaks may still start on their own
+    pub auto_start: bool,
+}
+
+impl QuietHours {
+    /// Denotes if the given local time lies within the quiet hours
+    pub fn contains(&self, time: NaiveDateTime) -> bool {
+        let time_of_day = time.time();
+
+        // Day on which the quiet hours containing the time have started
+        let start_day = match self.from.cmp(&self.to) {
+            Ordering::Equal => time.weekday(),
+            Ordering::Less if (self.from..self.to).contains(&time_of_day) => time.weekday(),
+            Ordering::Greater if time_of_day >= self.from => time.weekday(),
+            Ordering::Greater if time_of_day < self.to => time.weekday().pred(),
+            _ => return false,
+        };
+
+        self.weekdays.is_empty() || self.weekdays.contains(&start_day)
+    }
+}
+
+impl Default for QuietHours {
+    fn default() -> Self {
+        Self {
+            from: NaiveTime::from_hms_opt(22, 0, 0).unwrap_or(NaiveTime::MIN),
+            to: NaiveTime::from_hms_opt(7, 0, 0).unwrap_or(NaiveTime::MIN),
+            weekdays: vec![],
+            silent: true,
+            auto_start: false,
+        }
+    }
+}
+
+/// What the timer of the server does
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TimerMode {
+    /// Intervals and breaks (the classic pomodoro cycle or a custom sequence)
+    #[default]
+    Pomodoro,
+
+    /// Count-up timer without intervals, which can be split into laps via skip
+    Stopwatch,
+}
+
 /// Zentime configuration
 #[derive(Deserialize, Serialize, Clone, Default, Debug)]
 pub struct Config {
@@ -72,10 +1511,146 @@ pub struct Config {
     /// Configuration of the timer itself
     pub timers: PomodoroTimerConfig,
 
+    /// Custom sequence of intervals and breaks (e.g. `["work:50", "break:10"]`), which
+    /// replaces the classic pomodoro cycle and decides the lengths of all intervals and breaks
+    pub sequence: Option<TimerSequence>,
+
+    /// Whether the server runs pomodoro intervals or a stopwatch, which simply counts the
+    /// elapsed time upwards
+    pub mode: TimerMode,
+
+    /// Timer profiles, which can be switched between (e.g. `[profiles.deep-work]`)
+    pub profiles: BTreeMap<String, TimerProfile>,
+
+    /// Timer profile which is active once the server has started (defaults to
+    /// [DEFAULT_PROFILE])
+    pub profile: Option<String>,
+
     /// Configuration for OS notifications
     pub notifications: NotificationConfig,
+
+    /// Shell commands which are run on timer events
+    pub hooks: HooksConfig,
+
+    /// Configuration of battery-aware behavior
+    pub power: PowerConfig,
+
+    /// Configuration of the session history
+    pub history: HistoryConfig,
+
+    /// Configuration of context capture
+    pub context: ContextConfig,
+
+    /// Configuration of the plan of the day
+    pub plan: PlanConfig,
+
+    /// Configuration of the state file, from which a restarted server can resume its timer
+    pub state: StateConfig,
+
+    /// Configuration of the weekly report
+    pub report: ReportConfig,
+
+    /// Daily, weekly and monthly focus goals
+    pub goals: GoalConfig,
+
+    /// Configuration of pauses
+    pub pause: PauseConfig,
+
+    /// Alignment of intervals to wall-clock boundaries
+    pub alignment: AlignmentConfig,
+
+    /// Configuration of micro-breaks
+    pub micro_breaks: MicroBreakConfig,
+
+    /// Configuration of break enforcement
+    pub break_nudge: BreakNudgeConfig,
+
+    /// Configuration of idle detection
+    pub idle: IdleConfig,
+
+    /// Configuration of do-not-disturb during focus intervals
+    pub dnd: DndConfig,
+
+    /// Configuration of the focus contract
+    pub focus_contract: FocusContractConfig,
+
+    /// Recurring reminders (e.g. to drink some water or to check the posture)
+    pub reminders: Vec<ReminderConfig>,
+
+    /// Configuration of shared team sessions
+    pub team: TeamConfig,
+
+    /// Configuration of the HTTP server
+    pub http: HttpConfig,
+
+    /// Configuration of the JSON API
+    pub api: ApiConfig,
+
+    /// Configuration of the wall clock
+    pub clock: ClockConfig,
+
+    /// Timer lengths per day of the week and quiet hours
+    pub schedule: ScheduleConfig,
+
+    /// Configuration of client connections
+    pub connections: ConnectionConfig,
+
+    /// Configuration of the log files
+    pub log: LogConfig,
 }
 
+impl Config {
+    /// Names of all timer profiles (incl. [DEFAULT_PROFILE]) in alphabetical order
+    pub fn profile_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.profiles.keys().cloned().collect();
+
+        if !self.profiles.contains_key(DEFAULT_PROFILE) {
+            names.push(DEFAULT_PROFILE.to_string());
+            names.sort();
+        }
+
+        names
+    }
+
+    /// Timer config of the given profile at the given local time (adapted to the lengths of
+    /// that day, quiet hours and the custom sequence, if there is one) - [None] if there is no
+    /// such profile
+    pub fn profile_timers(&self, name: &str, time: NaiveDateTime) -> Option<PomodoroTimerConfig> {
+        let day_timers = match self.schedule.day(time.weekday()) {
+            Some(day) => day.apply(self.timers),
+            None => self.timers,
+        };
+
+        let timers = match self.profiles.get(name) {
+            Some(profile) => profile.apply(day_timers),
+            None if name == DEFAULT_PROFILE => day_timers,
+            None => return None,
+        };
+
+        let timers = if self.schedule.allows_auto_start(time) {
+            timers
+        } else {
+            PomodoroTimerConfig {
+                auto_start_break: false,
+                auto_start_work: false,
+                ..timers
+            }
+        };
+
+        Some(self.schedule().apply(timers))
+    }
+
+    /// Schedule the timer runs - the classic pomodoro cycle, unless a custom sequence has
+    /// been configured
+    pub fn schedule(&self) -> Schedule {
+        Schedule::from(self.sequence.clone())
+    }
+}
+
+/// Reads the configuration again (e.g. from the config file the server has been started with),
+/// whenever a client requests the server to reload it
+pub type ConfigLoader = Arc<dyn Fn() -> anyhow::Result<Config> + Send + Sync>;
+
 /// Creates a base configuration [Figment] by trying to open a configuration file
 /// from a given path and merging its configuration with the zentime default configuration.
 pub fn create_base_config(config_path: &str) -> Figment {
@@ -84,3 +1659,103 @@ pub fn create_base_config(config_path: &str) -> Figment {
 
     Figment::from(Serialized::defaults(Config::default())).merge(Toml::file(path_buffer))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_config_drafts() -> anyhow::Result<()> {
+        let draft = ConfigDraft {
+            timer: 50 * 60,
+            major_break: 20 * 60 + 30,
+            sound_file: Some("C:\\Sounds\\\"gong\".wav".to_string()),
+            volume: 0.8,
+            layout: TuiLayout::Compact,
+            colors: ThemeColors {
+                timer: ThemeColor::Rgb(0x1e, 0x90, 0xff),
+                ..ThemeColors::default()
+            },
+            ..ConfigDraft::default()
+        };
+        let config: Config = Figment::from(Serialized::defaults(Config::default()))
+            .merge(Toml::string(&draft.to_toml()))
+            .extract()?;
+
+        assert_eq!(config.timers.timer, 50 * 60);
+        assert_eq!(config.timers.major_break, 20 * 60 + 30);
+        assert_eq!(config.timers.intervals, 4);
+        assert_eq!(
+            config.notifications.sound_file.as_deref(),
+            Some("C:\\Sounds\\\"gong\".wav")
+        );
+        assert_eq!(config.notifications.volume, 0.8);
+        assert_eq!(config.view.layout, TuiLayout::Compact);
+        assert_eq!(config.view.colors.timer, ThemeColor::Rgb(0x1e, 0x90, 0xff));
+        assert_eq!(config.view.colors.break_phase, ThemeColor::Yellow);
+
+        Ok(())
+    }
+
+    #[test]
+    fn reads_event_sounds_from_config() -> anyhow::Result<()> {
+        let toml = r#"
+            [notifications]
+            sound_file = "bell.wav"
+
+            [notifications.sounds.long_break_end]
+            sound_file = "gong.wav"
+            volume = 0.5
+
+            [notifications.sounds.postpone]
+            volume = 0.2
+        "#;
+        let config: Config = Figment::from(Serialized::defaults(Config::default()))
+            .merge(Toml::string(toml))
+            .extract()?;
+        let sounds = &config.notifications.sounds;
+
+        let long_break_end = sounds.get(PomodoroPhase::LongBreak);
+        assert_eq!(long_break_end.sound_file.as_deref(), Some("gong.wav"));
+        assert_eq!(long_break_end.volume, 0.5);
+
+        let postpone = sounds.get(PomodoroPhase::PostponedShortBreak { seconds: 300 });
+        assert_eq!(postpone.sound_file, None);
+        assert_eq!(postpone.volume, 0.2);
+
+        assert_eq!(sounds.get(PomodoroPhase::ShortBreak).volume, 1.0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn reads_interface_theme_from_config() -> anyhow::Result<()> {
+        let toml = r##"
+            [view]
+            layout = "compact"
+            show_postpone_count = false
+
+            [view.colors]
+            text = "reset"
+            timer = "#1E90FF"
+            hints = "dark-grey"
+        "##;
+        let config: Config = Figment::from(Serialized::defaults(Config::default()))
+            .merge(Toml::string(toml))
+            .extract()?;
+
+        assert_eq!(config.view.layout, TuiLayout::Compact);
+        assert!(config.view.show_round);
+        assert!(!config.view.show_postpone_count);
+        assert_eq!(config.view.colors.text, ThemeColor::Reset);
+        assert_eq!(config.view.colors.timer, ThemeColor::Rgb(0x1e, 0x90, 0xff));
+        assert_eq!(config.view.colors.hints, ThemeColor::DarkGray);
+        assert_eq!(config.view.colors.focus, ThemeColor::Blue);
+        assert_eq!(config.view.colors.timer.to_string(), "#1e90ff");
+
+        assert!("#1e90f".parse::<ThemeColor>().is_err());
+        assert!("purple".parse::<ThemeColor>().is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,128 @@
+//! Drafts of new config files (see `zentime init`), which only contain the settings a new user
+//! has chosen. Everything else keeps its default and can be looked up inside
+//! `zentime.example.toml`.
+
+use std::fmt::Write;
+
+use serde::Serialize;
+use zentime_rs_timer::duration::HumanDuration;
+
+use super::{Config, NotificationConfig, ThemeColors, TuiLayout};
+
+/// Settings of a new config file
+#[derive(Debug, Clone)]
+pub struct ConfigDraft {
+    /// Length of focus intervals in seconds
+    pub timer: u64,
+
+    /// Length of short breaks in seconds
+    pub minor_break: u64,
+
+    /// Length of the long break in seconds
+    pub major_break: u64,
+
+    /// Intervals before the long break
+    pub intervals: u64,
+
+    /// Play a sound at the end of intervals and breaks
+    pub enable_bell: bool,
+
+    /// Show OS-notifications
+    pub show_notification: bool,
+
+    /// Sound file which replaces the default bell
+    pub sound_file: Option<String>,
+
+    /// Volume of the bell (0.0 - 1.0)
+    pub volume: f32,
+
+    /// Layout of the default interface
+    pub layout: TuiLayout,
+
+    /// Colors of the default interface
+    pub colors: ThemeColors,
+}
+
+impl Default for ConfigDraft {
+    fn default() -> Self {
+        let Config {
+            timers,
+            notifications,
+            view,
+            ..
+        } = Config::default();
+
+        Self {
+            timer: timers.timer,
+            minor_break: timers.minor_break,
+            major_break: timers.major_break,
+            intervals: timers.intervals,
+            enable_bell: notifications.enable_bell,
+            show_notification: notifications.show_notification,
+            sound_file: notifications.sound_file,
+            volume: notifications.volume,
+            layout: view.layout,
+            colors: view.colors,
+        }
+    }
+}
+
+impl ConfigDraft {
+    /// Notification settings of the draft, e.g. to try them out before the config is written
+    pub fn notifications(&self) -> NotificationConfig {
+        NotificationConfig {
+            enable_bell: self.enable_bell,
+            show_notification: self.show_notification,
+            sound_file: self.sound_file.clone(),
+            volume: self.volume,
+            ..NotificationConfig::default()
+        }
+    }
+
+    /// Contents of the config file
+    pub fn to_toml(&self) -> String {
+        let mut toml = String::from(
+            "# Created by `zentime init` - all available options are listed inside\n\
+            # zentime.example.toml\n",
+        );
+
+        // Writing into a String can't fail
+        writeln!(toml, "\n[timers]").ok();
+        writeln!(toml, "timer = {}", duration(self.timer)).ok();
+        writeln!(toml, "minor_break = {}", duration(self.minor_break)).ok();
+        writeln!(toml, "major_break = {}", duration(self.major_break)).ok();
+        writeln!(toml, "intervals = {}", self.intervals).ok();
+
+        writeln!(toml, "\n[notifications]").ok();
+        writeln!(toml, "enable_bell = {}", self.enable_bell).ok();
+        writeln!(toml, "show_notification = {}", self.show_notification).ok();
+        writeln!(toml, "volume = {:?}", self.volume).ok();
+
+        if let Some(sound_file) = &self.sound_file {
+            writeln!(toml, "sound_file = {}", toml_value(sound_file)).ok();
+        }
+
+        writeln!(toml, "\n[view]").ok();
+        writeln!(toml, "layout = {}", toml_value(&self.layout)).ok();
+
+        writeln!(toml, "\n[view.colors]").ok();
+
+        if let Ok(serde_json::Value::Object(colors)) = serde_json::to_value(self.colors) {
+            for (name, color) in colors {
+                writeln!(toml, "{} = {}", name, color).ok();
+            }
+        }
+
+        toml
+    }
+}
+
+/// Duration in a human readable form (e.g. `"25m"`)
+fn duration(seconds: u64) -> String {
+    toml_value(&HumanDuration::from_secs(seconds).to_string())
+}
+
+/// Value as TOML - JSON strings, numbers and booleans are valid TOML as well
+fn toml_value(value: &impl Serialize) -> String {
+    serde_json::to_string(value).expect("Config values can always be serialized")
+}
@@ -0,0 +1,244 @@
+//! Export of completed focus intervals, so that the pomodoro log can be reconciled with time
+//! trackers or billing tools:
+//!
+//! * CSV with the columns of Toggl's CSV import (`Description`, `Start date`, `Start time`,
+//!   `Duration`, ...) in local time
+//! * JSON with one object per interval and UTC timestamps
+//! * the JSON format of timewarrior, which `timew import` reads
+//!
+//! Intervals are placed by the time they have ended (like in the calendar feed), therefore
+//! pauses are not part of them.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::Serialize;
+
+use crate::history::{SessionKind, SessionOutcome, SessionRecord};
+use crate::wall_clock::WallClock;
+
+/// Tag of all intervals inside timewarrior, which tells them apart from other tracked time
+pub const TIMEW_TAG: &str = "pomodoro";
+
+/// Description of intervals without task
+const DEFAULT_DESCRIPTION: &str = "Focus";
+
+/// Focus interval which has run out
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportedInterval {
+    /// Start of the interval
+    pub start: SystemTime,
+
+    /// End of the interval
+    pub end: SystemTime,
+
+    /// Pomodoro round of the interval
+    pub round: u64,
+
+    /// Task the interval has been labeled with
+    pub task: Option<String>,
+
+    /// Captured context of the interval (e.g. `git:zentime-rs`)
+    pub tags: Vec<String>,
+}
+
+impl ExportedInterval {
+    /// Time which has been focused
+    pub fn duration(&self) -> Duration {
+        self.end.duration_since(self.start).unwrap_or_default()
+    }
+}
+
+/// Interval as it is exported to JSON
+#[derive(Debug, Serialize)]
+struct JsonInterval<'a> {
+    start: String,
+    end: String,
+    duration_seconds: u64,
+    round: u64,
+    task: Option<&'a str>,
+    tags: &'a [String],
+}
+
+/// Interval in the format of `timew export`/`timew import`
+#[derive(Debug, Serialize)]
+struct TimewInterval<'a> {
+    start: String,
+    end: String,
+    tags: Vec<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    annotation: Option<&'a str>,
+}
+
+/// All intervals of the records which have run out - skipped and reset intervals are left out
+pub fn completed_intervals<'a>(
+    records: impl IntoIterator<Item = &'a SessionRecord>,
+) -> Vec<ExportedInterval> {
+    records
+        .into_iter()
+        .filter(|record| {
+            record.kind == SessionKind::Interval && record.outcome == SessionOutcome::Completed
+        })
+        .map(|record| {
+            let end = UNIX_EPOCH + Duration::from_secs(record.ended_at);
+
+            ExportedInterval {
+                start: end - Duration::from_secs(record.elapsed_seconds),
+                end,
+                round: record.round,
+                task: record.task.clone(),
+                tags: record.tags.clone(),
+            }
+        })
+        .collect()
+}
+
+/// CSV document with one line per interval (with a header line), whose dates and times are
+/// given in the local time of the wall clock
+pub fn to_csv(intervals: &[ExportedInterval], wall_clock: WallClock) -> String {
+    let mut csv =
+        String::from("Description,Start date,Start time,End date,End time,Duration,Tags\r\n");
+
+    for interval in intervals {
+        let (start, end) = (
+            wall_clock.local_time(interval.start),
+            wall_clock.local_time(interval.end),
+        );
+        let seconds = interval.duration().as_secs();
+
+        let fields = [
+            interval
+                .task
+                .clone()
+                .unwrap_or_else(|| DEFAULT_DESCRIPTION.to_string()),
+            start.format("%Y-%m-%d").to_string(),
+            start.format("%H:%M:%S").to_string(),
+            end.format("%Y-%m-%d").to_string(),
+            end.format("%H:%M:%S").to_string(),
+            format!(
+                "{:02}:{:02}:{:02}",
+                seconds / 3600,
+                seconds % 3600 / 60,
+                seconds % 60
+            ),
+            interval.tags.join(", "),
+        ];
+
+        let line: Vec<_> = fields.iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&line.join(","));
+        csv.push_str("\r\n");
+    }
+
+    csv
+}
+
+/// JSON array with one object per interval
+pub fn to_json(intervals: &[ExportedInterval]) -> serde_json::Result<String> {
+    let utc_time =
+        |time: SystemTime| DateTime::<Utc>::from(time).to_rfc3339_opts(SecondsFormat::Secs, true);
+
+    let intervals: Vec<_> = intervals
+        .iter()
+        .map(|interval| JsonInterval {
+            start: utc_time(interval.start),
+            end: utc_time(interval.end),
+            duration_seconds: interval.duration().as_secs(),
+            round: interval.round,
+            task: interval.task.as_deref(),
+            tags: &interval.tags,
+        })
+        .collect();
+
+    serde_json::to_string_pretty(&intervals)
+}
+
+/// JSON array in the format of timewarrior. Each interval is tagged with [TIMEW_TAG] and its
+/// context, its task becomes the annotation.
+pub fn to_timew(intervals: &[ExportedInterval]) -> serde_json::Result<String> {
+    let timew_time = |time: SystemTime| DateTime::<Utc>::from(time).format("%Y%m%dT%H%M%SZ");
+
+    let intervals: Vec<_> = intervals
+        .iter()
+        .map(|interval| TimewInterval {
+            start: timew_time(interval.start).to_string(),
+            end: timew_time(interval.end).to_string(),
+            tags: std::iter::once(TIMEW_TAG)
+                .chain(interval.tags.iter().map(String::as_str))
+                .collect(),
+            annotation: interval.task.as_deref(),
+        })
+        .collect();
+
+    serde_json::to_string(&intervals)
+}
+
+/// Quotes a CSV field, if it contains a separator, a quote or a line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ClockConfig;
+
+    #[test]
+    fn exports_completed_intervals() -> anyhow::Result<()> {
+        let wall_clock = WallClock::from_config(&ClockConfig {
+            time_zone: Some(chrono_tz::Europe::Berlin),
+        });
+        let interval =
+            |outcome: SessionOutcome, task: Option<&str>, tags: Vec<String>| SessionRecord {
+                kind: SessionKind::Interval,
+                outcome,
+                round: 2,
+                // 2026-10-16 09:25:00 UTC
+                ended_at: 1_792_142_700,
+                planned_seconds: 25 * 60,
+                elapsed_seconds: 25 * 60,
+                pause_reasons: vec![],
+                tags,
+                task: task.map(str::to_string),
+                broken_contract: false,
+            };
+        let records = [
+            interval(
+                SessionOutcome::Completed,
+                Some("Review, \"export\""),
+                vec!["git:zentime-rs".to_string()],
+            ),
+            interval(SessionOutcome::Skipped, Some("Skipped"), vec![]),
+            SessionRecord {
+                kind: SessionKind::ShortBreak,
+                ..interval(SessionOutcome::Completed, None, vec![])
+            },
+            interval(SessionOutcome::Completed, None, vec![]),
+        ];
+
+        let intervals = completed_intervals(&records);
+        assert_eq!(intervals.len(), 2);
+
+        assert_eq!(
+            to_csv(&intervals, wall_clock),
+            "Description,Start date,Start time,End date,End time,Duration,Tags\r\n\
+            \"Review, \"\"export\"\"\",2026-10-16,11:00:00,2026-10-16,11:25:00,00:25:00,git:zentime-rs\r\n\
+            Focus,2026-10-16,11:00:00,2026-10-16,11:25:00,00:25:00,\r\n"
+        );
+
+        let json: serde_json::Value = serde_json::from_str(&to_json(&intervals)?)?;
+        assert_eq!(json[0]["start"], "2026-10-16T09:00:00Z");
+        assert_eq!(json[0]["duration_seconds"], 1500);
+        assert_eq!(json[1]["task"], serde_json::Value::Null);
+
+        assert_eq!(
+            to_timew(&intervals)?,
+            r#"[{"start":"20261016T090000Z","end":"20261016T092500Z","tags":["pomodoro","git:zentime-rs"],"annotation":"Review, \"export\""},{"start":"20261016T090000Z","end":"20261016T092500Z","tags":["pomodoro"]}]"#
+        );
+
+        Ok(())
+    }
+}
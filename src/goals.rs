@@ -0,0 +1,336 @@
+//! Progress towards the weekly and monthly focus time goals (see [GoalConfig]), e.g.
+//!
+//! ```text
+//! Weekly goal:      12h30m of 22h (2h carried over) - 56%
+//! ```
+//!
+//! With carry-over, the focus time which has been missing to reach the goal of the previous
+//! week/month is added to the current goal. Only the plain goal of the previous period counts,
+//! so that a single bad week doesn't pile up forever.
+
+use std::fmt::Display;
+use std::time::{Duration, UNIX_EPOCH};
+
+use chrono::{Datelike, Months, NaiveDate};
+
+use crate::config::{GoalConfig, HistoryConfig};
+use crate::history::{format_minutes, CycleSummary, History, SessionRecord};
+use crate::wall_clock::WallClock;
+
+/// Period a goal is set for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GoalPeriod {
+    /// (ISO) calendar week, which starts on monday
+    Week,
+
+    /// Calendar month
+    Month,
+}
+
+impl GoalPeriod {
+    /// Focus time (in seconds) which has been set as goal for this period
+    fn goal_seconds(self, config: &GoalConfig) -> Option<u64> {
+        let goal = match self {
+            GoalPeriod::Week => config.weekly,
+            GoalPeriod::Month => config.monthly,
+        };
+
+        goal.map(|goal| goal.as_secs())
+            .filter(|seconds| *seconds > 0)
+    }
+
+    /// First day of the period the given date belongs to and first day of the following period
+    fn bounds(self, date: NaiveDate) -> (NaiveDate, NaiveDate) {
+        match self {
+            GoalPeriod::Week => {
+                let start = date.week(chrono::Weekday::Mon).first_day();
+                (start, start + chrono::Days::new(7))
+            }
+            GoalPeriod::Month => {
+                let start = date.with_day(1).unwrap_or(date);
+                (start, start + Months::new(1))
+            }
+        }
+    }
+}
+
+impl Display for GoalPeriod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GoalPeriod::Week => write!(f, "Weekly goal"),
+            GoalPeriod::Month => write!(f, "Monthly goal"),
+        }
+    }
+}
+
+/// Focus time of a week/month compared to its goal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GoalProgress {
+    /// Period the goal has been set for
+    pub period: GoalPeriod,
+
+    /// Focus time which has been set as goal (in seconds)
+    pub goal_seconds: u64,
+
+    /// Focus time which has been missing to reach the goal of the previous period (in seconds)
+    pub carried_over_seconds: u64,
+
+    /// Focus time of the period so far (in seconds)
+    pub focused_seconds: u64,
+}
+
+impl GoalProgress {
+    /// Reads the sessions of the current and the previous week/month from the history and
+    /// evaluates the goal of the current one - [None] if no goal has been set for the period
+    pub fn load(
+        config: &GoalConfig,
+        history: &HistoryConfig,
+        wall_clock: WallClock,
+        period: GoalPeriod,
+    ) -> anyhow::Result<Option<Self>> {
+        let today = wall_clock.today();
+        let (start, _) = period.bounds(today);
+        let (previous_start, _) = period.bounds(start - chrono::Days::new(1));
+
+        let since = wall_clock.start_of_day(previous_start)
+            ..wall_clock.start_of_day(today + chrono::Days::new(1));
+        let records = History::open(history)?.records_between(since)?;
+
+        Ok(Self::from_records(
+            config, &records, wall_clock, period, today,
+        ))
+    }
+
+    /// Evaluates the goal of the week/month the given date belongs to.
+    /// The records have to cover the previous period as well, so that the focus time which has
+    /// been missing back then can be carried over.
+    pub fn from_records(
+        config: &GoalConfig,
+        records: &[SessionRecord],
+        wall_clock: WallClock,
+        period: GoalPeriod,
+        date: NaiveDate,
+    ) -> Option<Self> {
+        let goal_seconds = period.goal_seconds(config)?;
+        let (start, end) = period.bounds(date);
+        let (previous_start, _) = period.bounds(start - chrono::Days::new(1));
+
+        let focused_between = |from: NaiveDate, to: NaiveDate| {
+            let records = records.iter().filter(|record| {
+                let date = wall_clock
+                    .local_time(UNIX_EPOCH + Duration::from_secs(record.ended_at))
+                    .date();
+
+                from <= date && date < to
+            });
+
+            CycleSummary::from_records(records).focused_seconds
+        };
+
+        let carried_over_seconds = if config.carry_over {
+            goal_seconds.saturating_sub(focused_between(previous_start, start))
+        } else {
+            0
+        };
+
+        Some(Self {
+            period,
+            goal_seconds,
+            carried_over_seconds,
+            focused_seconds: focused_between(start, end),
+        })
+    }
+
+    /// Focus time which has to be reached in this period (incl. the carried over time)
+    pub fn target_seconds(&self) -> u64 {
+        self.goal_seconds + self.carried_over_seconds
+    }
+
+    /// Denotes if the target has been reached
+    pub fn is_met(&self) -> bool {
+        self.focused_seconds >= self.target_seconds()
+    }
+
+    /// Share of the target which has been reached (may exceed 100)
+    pub fn percentage(&self) -> u64 {
+        self.focused_seconds * 100 / self.target_seconds().max(1)
+    }
+}
+
+impl Display for GoalProgress {
+    /// e.g. `12h30m of 22h (2h carried over) - 56%`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} of {}",
+            format_minutes(self.focused_seconds),
+            format_minutes(self.target_seconds())
+        )?;
+
+        if self.carried_over_seconds > 0 {
+            write!(
+                f,
+                " ({} carried over)",
+                format_minutes(self.carried_over_seconds)
+            )?;
+        }
+
+        write!(f, " - {}%", self.percentage())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDateTime;
+    use zentime_rs_timer::duration::HumanDuration;
+
+    use super::*;
+    use crate::config::ClockConfig;
+    use crate::history::{SessionKind, SessionOutcome};
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    fn focused(ended_at: NaiveDateTime, hours: u64) -> SessionRecord {
+        SessionRecord {
+            kind: SessionKind::Interval,
+            outcome: SessionOutcome::Completed,
+            round: 1,
+            ended_at: ended_at.and_utc().timestamp() as u64,
+            planned_seconds: hours * 60 * 60,
+            elapsed_seconds: hours * 60 * 60,
+            pause_reasons: vec![],
+            tags: vec![],
+            task: None,
+            broken_contract: false,
+        }
+    }
+
+    fn focused_at_noon(day: &str, hours: u64) -> SessionRecord {
+        focused(date(day).and_hms_opt(12, 0, 0).unwrap(), hours)
+    }
+
+    fn goals() -> GoalConfig {
+        GoalConfig {
+            weekly: Some(HumanDuration::from_secs(20 * 60 * 60)),
+            monthly: Some(HumanDuration::from_secs(80 * 60 * 60)),
+            ..GoalConfig::default()
+        }
+    }
+
+    fn utc() -> WallClock {
+        WallClock::from_config(&ClockConfig {
+            time_zone: Some(chrono_tz::UTC),
+        })
+    }
+
+    #[test]
+    fn carries_over_missed_focus_goals() {
+        let records = [
+            focused_at_noon("2026-09-30", 30),
+            focused_at_noon("2026-10-08", 12),
+            focused_at_noon("2026-10-12", 9),
+            focused_at_noon("2026-10-16", 6),
+        ];
+        let config = goals();
+        let today = date("2026-10-16");
+
+        // 8h have been missing last week
+        let week =
+            GoalProgress::from_records(&config, &records, utc(), GoalPeriod::Week, today).unwrap();
+        assert_eq!(week.carried_over_seconds, 8 * 60 * 60);
+        assert_eq!(week.focused_seconds, 15 * 60 * 60);
+        assert_eq!(week.percentage(), 53);
+        assert!(!week.is_met());
+        assert_eq!(week.to_string(), "15h of 28h (8h carried over) - 53%");
+
+        // Focus time of september only counts towards its own goal
+        let month =
+            GoalProgress::from_records(&config, &records, utc(), GoalPeriod::Month, today).unwrap();
+        assert_eq!(month.carried_over_seconds, 50 * 60 * 60);
+        assert_eq!(month.focused_seconds, 27 * 60 * 60);
+
+        let without_carry_over = GoalConfig {
+            carry_over: false,
+            ..config
+        };
+        let week = GoalProgress::from_records(
+            &without_carry_over,
+            &records,
+            utc(),
+            GoalPeriod::Week,
+            today,
+        )
+        .unwrap();
+        assert_eq!(week.target_seconds(), 20 * 60 * 60);
+    }
+
+    #[test]
+    fn weeks_start_on_monday_and_months_on_their_first_day() {
+        // Weeks may span two months or years
+        assert_eq!(
+            GoalPeriod::Week.bounds(date("2026-01-01")),
+            (date("2025-12-29"), date("2026-01-05"))
+        );
+        assert_eq!(
+            GoalPeriod::Week.bounds(date("2026-10-12")),
+            (date("2026-10-12"), date("2026-10-19"))
+        );
+        assert_eq!(
+            GoalPeriod::Week.bounds(date("2026-10-18")),
+            (date("2026-10-12"), date("2026-10-19"))
+        );
+
+        assert_eq!(
+            GoalPeriod::Month.bounds(date("2026-01-31")),
+            (date("2026-01-01"), date("2026-02-01"))
+        );
+        assert_eq!(
+            GoalPeriod::Month.bounds(date("2026-12-15")),
+            (date("2026-12-01"), date("2027-01-01"))
+        );
+    }
+
+    #[test]
+    fn assigns_sessions_to_the_period_of_their_local_date() {
+        // 23:30 UTC on sunday is already monday in Berlin
+        let sunday_night = date("2026-10-11").and_hms_opt(23, 30, 0).unwrap();
+        let records = [focused(sunday_night, 2)];
+        let berlin = WallClock::from_config(&ClockConfig {
+            time_zone: Some(chrono_tz::Europe::Berlin),
+        });
+        let monday = date("2026-10-12");
+
+        let week = GoalProgress::from_records(&goals(), &records, utc(), GoalPeriod::Week, monday)
+            .unwrap();
+        assert_eq!(week.focused_seconds, 0);
+        assert_eq!(week.carried_over_seconds, 18 * 60 * 60);
+
+        let week = GoalProgress::from_records(&goals(), &records, berlin, GoalPeriod::Week, monday)
+            .unwrap();
+        assert_eq!(week.focused_seconds, 2 * 60 * 60);
+        assert_eq!(week.carried_over_seconds, 20 * 60 * 60);
+    }
+
+    #[test]
+    fn ignores_periods_without_a_goal() {
+        let config = GoalConfig {
+            monthly: Some(HumanDuration::from_secs(0)),
+            ..goals()
+        };
+        let today = date("2026-10-16");
+
+        assert!(
+            GoalProgress::from_records(&config, &[], utc(), GoalPeriod::Month, today).is_none()
+        );
+        assert!(GoalProgress::from_records(
+            &GoalConfig::default(),
+            &[],
+            utc(),
+            GoalPeriod::Week,
+            today
+        )
+        .is_none());
+    }
+}
@@ -0,0 +1,466 @@
+//! Persistent history of all intervals and breaks.
+//!
+//! The server appends a [SessionRecord] to the history, whenever an interval or break ends -
+//! no matter if it has run out, has been skipped or postponed.
+//! The history is evaluated by `zentime stats`.
+//!
+//! Records are kept by a [HistoryStore] - either a JSON-lines file or (with the `sqlite`
+//! feature) a SQLite database (see [HistoryBackend]).
+//! Without the `history` feature, nothing is stored and only the evaluation of records (e.g.
+//! the summary of a cycle) is available.
+
+#[cfg(feature = "history")]
+mod json_lines;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+
+#[cfg(feature = "history")]
+pub use json_lines::JsonLinesStore;
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteStore;
+
+use std::fmt::{Debug, Display};
+#[cfg(feature = "history")]
+use std::ops::Range;
+#[cfg(feature = "history")]
+use std::path::{Path, PathBuf};
+#[cfg(feature = "history")]
+use std::sync::Arc;
+#[cfg(feature = "history")]
+use std::time::Duration;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[cfg(feature = "history")]
+use anyhow::bail;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "history")]
+use crate::config::{HistoryBackend, HistoryConfig};
+
+/// Kind of a recorded session
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionKind {
+    /// Focus interval
+    Interval,
+
+    /// Short break
+    ShortBreak,
+
+    /// Long break
+    LongBreak,
+}
+
+impl SessionKind {
+    /// Denotes if the session is a (short or long) break
+    pub fn is_break(&self) -> bool {
+        matches!(self, SessionKind::ShortBreak | SessionKind::LongBreak)
+    }
+}
+
+/// How a session has ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionOutcome {
+    /// The timer has run out (i.e. the break has been taken)
+    Completed,
+
+    /// The session has been skipped
+    Skipped,
+
+    /// The break has been postponed
+    Postponed,
+
+    /// The timer has been reset
+    Reset,
+}
+
+/// A single interval or break
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionRecord {
+    /// Kind of the session
+    pub kind: SessionKind,
+
+    /// How the session has ended
+    pub outcome: SessionOutcome,
+
+    /// Pomodoro round of the session
+    pub round: u64,
+
+    /// Unix timestamp (in seconds) at which the session has ended
+    pub ended_at: u64,
+
+    /// Configured length of the session in seconds
+    pub planned_seconds: u64,
+
+    /// Seconds of the session which have elapsed before it ended
+    pub elapsed_seconds: u64,
+
+    /// Reasons the session has been paused for (e.g. "phone call")
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pause_reasons: Vec<String>,
+
+    /// Context of an interval, which has been captured at its start (e.g. `git:zentime-rs`,
+    /// see [ContextConfig](crate::config::ContextConfig))
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
+    /// Task the session has been labeled with (see [ClientToServerMsg::SetTask]) or which has
+    /// been worked on according to the plan
+    ///
+    /// [ClientToServerMsg::SetTask]: crate::ipc::ClientToServerMsg::SetTask
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task: Option<String>,
+
+    /// Set if an interval has been given up early, although the focus contract was in effect
+    /// (see [FocusContractConfig](crate::config::FocusContractConfig))
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub broken_contract: bool,
+}
+
+/// Storage the session records are kept in (see [HistoryBackend])
+#[cfg(feature = "history")]
+pub trait HistoryStore: Debug + Send + Sync {
+    /// Stores a record
+    fn append(&self, record: &SessionRecord) -> anyhow::Result<()>;
+
+    /// Reads all records which have ended at or after the given unix timestamp (in seconds),
+    /// in the order they have been appended
+    fn records_since(&self, timestamp: u64) -> anyhow::Result<Vec<SessionRecord>>;
+}
+
+/// Append-only history, which is stored by the configured [HistoryStore]
+#[cfg(feature = "history")]
+#[derive(Debug, Clone)]
+pub struct History {
+    path: PathBuf,
+    store: Arc<dyn HistoryStore>,
+}
+
+#[cfg(feature = "history")]
+impl History {
+    /// Opens the history with the configured backend.
+    /// The history file is created as soon as the first record is appended.
+    pub fn open(config: &HistoryConfig) -> anyhow::Result<Self> {
+        let path = PathBuf::from(shellexpand::tilde(config.path()).as_ref());
+
+        let store: Arc<dyn HistoryStore> = match config.backend {
+            HistoryBackend::JsonLines => Arc::new(JsonLinesStore::new(path.clone())),
+            #[cfg(feature = "sqlite")]
+            HistoryBackend::Sqlite => Arc::new(SqliteStore::new(path.clone())),
+            #[cfg(not(feature = "sqlite"))]
+            HistoryBackend::Sqlite => bail!(
+                "zentime has been built without SQLite support - \
+                rebuild it with `--features sqlite` or use the json_lines history backend"
+            ),
+        };
+
+        Ok(Self { path, store })
+    }
+
+    /// Path of the history file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Appends a record to the history
+    pub fn append(&self, record: &SessionRecord) -> anyhow::Result<()> {
+        self.store.append(record)
+    }
+
+    /// Reads all records
+    pub fn records(&self) -> anyhow::Result<Vec<SessionRecord>> {
+        self.store.records_since(0)
+    }
+
+    /// Reads all records which have ended within the given time span before now
+    pub fn records_since(&self, span: Duration) -> anyhow::Result<Vec<SessionRecord>> {
+        let since = unix_timestamp(SystemTime::now()).saturating_sub(span.as_secs());
+
+        self.store.records_since(since)
+    }
+
+    /// Reads all records which have ended within the last `days` days (e.g. of `--days`)
+    pub fn records_of_last_days(&self, days: u64) -> anyhow::Result<Vec<SessionRecord>> {
+        let Some(seconds) = days.checked_mul(24 * 60 * 60) else {
+            bail!(
+                "Can't look back {} days - the number of days is too large",
+                days
+            );
+        };
+
+        self.records_since(Duration::from_secs(seconds))
+    }
+
+    /// Reads all records which have ended inside the given time range
+    pub fn records_between(&self, range: Range<SystemTime>) -> anyhow::Result<Vec<SessionRecord>> {
+        let (start, end) = (unix_timestamp(range.start), unix_timestamp(range.end));
+
+        Ok(self
+            .store
+            .records_since(start)?
+            .into_iter()
+            .filter(|record| record.ended_at < end)
+            .collect())
+    }
+}
+
+/// Converts a [SystemTime] into a unix timestamp in seconds
+pub fn unix_timestamp(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+/// Counts how often each pause reason occurs inside the given records.
+/// The most common reasons come first.
+pub fn pause_reason_counts<'a>(
+    records: impl IntoIterator<Item = &'a SessionRecord>,
+) -> Vec<(String, u64)> {
+    let mut counts: Vec<(String, u64)> = vec![];
+
+    for reason in records.into_iter().flat_map(|record| &record.pause_reasons) {
+        match counts.iter_mut().find(|(known, _)| known == reason) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((reason.clone(), 1)),
+        }
+    }
+
+    // Stable sort, so that equally common reasons stay in the order they first occurred
+    counts.sort_by(|(_, a), (_, b)| b.cmp(a));
+    counts
+}
+
+/// Sums up the focus time (in seconds) of the intervals of each tag inside the given records.
+/// The tags with the most focus time come first.
+pub fn focus_by_tag<'a>(
+    records: impl IntoIterator<Item = &'a SessionRecord>,
+) -> Vec<(String, u64)> {
+    let mut focus: Vec<(String, u64)> = vec![];
+
+    for record in records
+        .into_iter()
+        .filter(|record| record.kind == SessionKind::Interval)
+    {
+        let focused_seconds = record.elapsed_seconds.min(record.planned_seconds);
+
+        for tag in &record.tags {
+            match focus.iter_mut().find(|(known, _)| known == tag) {
+                Some((_, seconds)) => *seconds += focused_seconds,
+                None => focus.push((tag.clone(), focused_seconds)),
+            }
+        }
+    }
+
+    // Stable sort, so that tags with equal focus time stay in the order they first occurred
+    focus.sort_by(|(_, a), (_, b)| b.cmp(a));
+    focus
+}
+
+/// Summary of how consistently breaks have been taken
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BreakCompliance {
+    /// Breaks which have run out
+    pub taken: u64,
+
+    /// Breaks which have been skipped (or reset)
+    pub skipped: u64,
+
+    /// Times a break has been postponed
+    pub postponed: u64,
+
+    /// Sum of the configured lengths of all taken and skipped breaks
+    pub planned_seconds: u64,
+
+    /// Break time which has actually elapsed
+    pub elapsed_seconds: u64,
+}
+
+impl BreakCompliance {
+    /// Evaluates all breaks of the given records.
+    /// A postponed break is recorded again as soon as it has been taken or skipped, therefore
+    /// postponements only count towards [BreakCompliance::postponed].
+    pub fn from_records<'a>(records: impl IntoIterator<Item = &'a SessionRecord>) -> Self {
+        let mut compliance = Self::default();
+
+        for record in records.into_iter().filter(|record| record.kind.is_break()) {
+            match record.outcome {
+                SessionOutcome::Postponed => {
+                    compliance.postponed += 1;
+                    continue;
+                }
+                SessionOutcome::Completed => compliance.taken += 1,
+                SessionOutcome::Skipped | SessionOutcome::Reset => compliance.skipped += 1,
+            }
+
+            compliance.planned_seconds += record.planned_seconds;
+            compliance.elapsed_seconds += record.elapsed_seconds.min(record.planned_seconds);
+        }
+
+        compliance
+    }
+
+    /// Share of the planned break time which has actually been taken (0 - 100).
+    /// Returns `None` if there haven't been any breaks.
+    pub fn percentage(&self) -> Option<u64> {
+        if self.planned_seconds == 0 {
+            return None;
+        }
+
+        Some(self.elapsed_seconds * 100 / self.planned_seconds)
+    }
+}
+
+/// Track record of the focus contract (see
+/// [FocusContractConfig](crate::config::FocusContractConfig))
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ContractRecord {
+    /// Intervals which have been given up while the contract was in effect
+    pub broken: u64,
+
+    /// Intervals which have run out since the contract has last been broken
+    pub kept_since_broken: u64,
+}
+
+impl ContractRecord {
+    /// Evaluates all intervals of the given records (which have to be in chronological order)
+    pub fn from_records<'a>(records: impl IntoIterator<Item = &'a SessionRecord>) -> Self {
+        let mut contract = Self::default();
+
+        for record in records {
+            if record.broken_contract {
+                contract.broken += 1;
+                contract.kept_since_broken = 0;
+            } else if record.kind == SessionKind::Interval
+                && record.outcome == SessionOutcome::Completed
+            {
+                contract.kept_since_broken += 1;
+            }
+        }
+
+        contract
+    }
+}
+
+/// Simple score of how focused a period (e.g. a week) has been, which combines how many of the
+/// started intervals have been completed, how consistently breaks have been taken and how often
+/// the timer has been interrupted
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FocusScore {
+    /// Intervals which have run out
+    pub completed_intervals: u64,
+
+    /// Intervals which have run out, been skipped or been reset
+    pub started_intervals: u64,
+
+    /// How consistently breaks have been taken
+    pub compliance: BreakCompliance,
+
+    /// Times the timer has been paused for a reason (see [SessionRecord::pause_reasons])
+    pub interruptions: u64,
+}
+
+impl FocusScore {
+    /// Evaluates all records of a period
+    pub fn from_records<'a>(records: impl IntoIterator<Item = &'a SessionRecord> + Clone) -> Self {
+        let mut score = Self {
+            compliance: BreakCompliance::from_records(records.clone()),
+            ..Self::default()
+        };
+
+        for record in records {
+            score.interruptions += record.pause_reasons.len() as u64;
+
+            if record.kind == SessionKind::Interval {
+                score.started_intervals += 1;
+
+                if record.outcome == SessionOutcome::Completed {
+                    score.completed_intervals += 1;
+                }
+            }
+        }
+
+        score
+    }
+
+    /// Score from 0 to 100, which weighs completed intervals by 50%, break compliance by 30%
+    /// and the share of completed intervals among completed intervals and interruptions by 20%.
+    /// Returns `None` if no interval has been started.
+    pub fn value(&self) -> Option<u64> {
+        if self.started_intervals == 0 {
+            return None;
+        }
+
+        let completion = self.completed_intervals * 100 / self.started_intervals;
+        let compliance = self.compliance.percentage().unwrap_or(100);
+        let calmness = match self.completed_intervals + self.interruptions {
+            0 => 0,
+            total => self.completed_intervals * 100 / total,
+        };
+
+        Some((completion * 50 + compliance * 30 + calmness * 20) / 100)
+    }
+}
+
+/// Summary of a full pomodoro cycle (all intervals and breaks up to and including the long
+/// break)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CycleSummary {
+    /// Intervals which have run out
+    pub intervals: u64,
+
+    /// Focus time which has elapsed during all intervals (including skipped ones)
+    pub focused_seconds: u64,
+
+    /// Times the timer has been paused for a reason (see [SessionRecord::pause_reasons])
+    pub interruptions: u64,
+}
+
+impl CycleSummary {
+    /// Evaluates all records of a single cycle
+    pub fn from_records<'a>(records: impl IntoIterator<Item = &'a SessionRecord>) -> Self {
+        let mut summary = Self::default();
+
+        for record in records {
+            summary.interruptions += record.pause_reasons.len() as u64;
+
+            if record.kind != SessionKind::Interval {
+                continue;
+            }
+
+            if record.outcome == SessionOutcome::Completed {
+                summary.intervals += 1;
+            }
+
+            summary.focused_seconds += record.elapsed_seconds.min(record.planned_seconds);
+        }
+
+        summary
+    }
+}
+
+impl Display for CycleSummary {
+    /// e.g. `Cycle done: 4 intervals, 1h40m focused, 2 interruptions`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Cycle done: {} interval{}, {} focused, {} interruption{}",
+            self.intervals,
+            if self.intervals == 1 { "" } else { "s" },
+            format_minutes(self.focused_seconds),
+            self.interruptions,
+            if self.interruptions == 1 { "" } else { "s" }
+        )
+    }
+}
+
+/// Formats a time span in whole minutes (e.g. `1h40m`, `2h` or `0m`)
+pub fn format_minutes(seconds: u64) -> String {
+    let minutes = seconds / 60;
+
+    match (minutes / 60, minutes % 60) {
+        (0, minutes) => format!("{}m", minutes),
+        (hours, 0) => format!("{}h", hours),
+        (hours, minutes) => format!("{}h{}m", hours, minutes),
+    }
+}
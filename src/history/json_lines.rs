@@ -0,0 +1,70 @@
+//! History file with one JSON object per line
+
+use std::fs::{create_dir_all, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+
+use anyhow::Context;
+use log::warn;
+
+use super::{HistoryStore, SessionRecord};
+
+/// Append-only text file, which stores each record as a single line of JSON
+#[derive(Debug, Clone)]
+pub struct JsonLinesStore {
+    path: PathBuf,
+}
+
+impl JsonLinesStore {
+    /// Store which writes to the given file (it is created along with its parent directories
+    /// as soon as the first record is appended)
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+}
+
+impl HistoryStore for JsonLinesStore {
+    fn append(&self, record: &SessionRecord) -> anyhow::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            create_dir_all(parent).context(format!("Could not create {}", parent.display()))?;
+        }
+
+        let mut line = serde_json::to_string(record).context("Could not serialize record")?;
+        line.push('\n');
+
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .context(format!("Could not write {}", self.path.display()))
+    }
+
+    /// Lines which can't be parsed are skipped
+    fn records_since(&self, timestamp: u64) -> anyhow::Result<Vec<SessionRecord>> {
+        if !self.path.exists() {
+            return Ok(vec![]);
+        }
+
+        let file =
+            File::open(&self.path).context(format!("Could not open {}", self.path.display()))?;
+
+        let mut records = vec![];
+
+        for (index, line) in BufReader::new(file).lines().enumerate() {
+            let line = line.context(format!("Could not read {}", self.path.display()))?;
+
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            match serde_json::from_str::<SessionRecord>(&line) {
+                Ok(record) if record.ended_at >= timestamp => records.push(record),
+                Ok(_) => {}
+                Err(error) => warn!("Skipping invalid history line {}: {}", index + 1, error),
+            }
+        }
+
+        Ok(records)
+    }
+}
@@ -0,0 +1,256 @@
+//! History which is stored inside a SQLite database, so that it can be queried with any SQLite
+//! client, e.g.
+//!
+//! ```sql
+//! SELECT date(ended_at, 'unixepoch'), count(*) FROM sessions
+//! WHERE kind = 'interval' AND outcome = 'completed' GROUP BY 1;
+//! ```
+
+use std::collections::HashMap;
+use std::fs::create_dir_all;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use anyhow::{anyhow, bail, Context};
+use rusqlite::{params, Connection};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+use super::{HistoryStore, SessionRecord};
+
+/// Creates the tables, unless they already exist
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS sessions (
+        id INTEGER PRIMARY KEY,
+        kind TEXT NOT NULL,
+        outcome TEXT NOT NULL,
+        round INTEGER NOT NULL,
+        ended_at INTEGER NOT NULL,
+        planned_seconds INTEGER NOT NULL,
+        elapsed_seconds INTEGER NOT NULL,
+        task TEXT,
+        broken_contract INTEGER NOT NULL DEFAULT 0
+    );
+    CREATE INDEX IF NOT EXISTS sessions_ended_at ON sessions (ended_at);
+    CREATE TABLE IF NOT EXISTS pause_reasons (
+        session_id INTEGER NOT NULL REFERENCES sessions (id),
+        reason TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS pause_reasons_session_id ON pause_reasons (session_id);
+    CREATE TABLE IF NOT EXISTS session_tags (
+        session_id INTEGER NOT NULL REFERENCES sessions (id),
+        tag TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS session_tags_session_id ON session_tags (session_id);
+";
+
+/// Columns which have been added to the `sessions` table after its first release, so that they
+/// are added to existing databases as well (name, type)
+const ADDED_SESSION_COLUMNS: &[(&str, &str)] = &[
+    ("task", "TEXT"),
+    ("broken_contract", "INTEGER NOT NULL DEFAULT 0"),
+];
+
+/// SQLite database with a row per session (table `sessions`), a row per pause reason
+/// (table `pause_reasons`) and a row per tag (table `session_tags`)
+#[derive(Debug)]
+pub struct SqliteStore {
+    path: PathBuf,
+
+    /// Opened on first use, so that the database is only created once something is recorded
+    connection: Mutex<Option<Connection>>,
+}
+
+impl SqliteStore {
+    /// Store which writes to the given database (it is created along with its parent
+    /// directories as soon as the first record is appended)
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            connection: Mutex::new(None),
+        }
+    }
+
+    /// Runs the given function with the database connection (which is opened if necessary)
+    fn with_connection<T>(
+        &self,
+        f: impl FnOnce(&mut Connection) -> anyhow::Result<T>,
+    ) -> anyhow::Result<T> {
+        let mut guard = self
+            .connection
+            .lock()
+            .map_err(|_| anyhow!("History database is poisoned"))?;
+
+        let mut connection = match guard.take() {
+            Some(connection) => connection,
+            None => self.open()?,
+        };
+
+        let result = f(&mut connection);
+        *guard = Some(connection);
+
+        result
+    }
+
+    /// Opens the database and creates its tables
+    fn open(&self) -> anyhow::Result<Connection> {
+        if let Some(parent) = self.path.parent() {
+            create_dir_all(parent).context(format!("Could not create {}", parent.display()))?;
+        }
+
+        Connection::open(&self.path)
+            .and_then(|connection| connection.execute_batch(SCHEMA).map(|_| connection))
+            .and_then(|connection| add_missing_columns(&connection).map(|_| connection))
+            .context(format!("Could not open {}", self.path.display()))
+    }
+}
+
+impl HistoryStore for SqliteStore {
+    fn append(&self, record: &SessionRecord) -> anyhow::Result<()> {
+        self.with_connection(|connection| {
+            let transaction = connection.transaction()?;
+
+            transaction.execute(
+                "INSERT INTO sessions
+                    (kind, outcome, round, ended_at, planned_seconds, elapsed_seconds, task,
+                    broken_contract)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    to_text(&record.kind)?,
+                    to_text(&record.outcome)?,
+                    record.round,
+                    record.ended_at,
+                    record.planned_seconds,
+                    record.elapsed_seconds,
+                    record.task,
+                    record.broken_contract,
+                ],
+            )?;
+
+            let session_id = transaction.last_insert_rowid();
+
+            for reason in &record.pause_reasons {
+                transaction.execute(
+                    "INSERT INTO pause_reasons (session_id, reason) VALUES (?1, ?2)",
+                    params![session_id, reason],
+                )?;
+            }
+
+            for tag in &record.tags {
+                transaction.execute(
+                    "INSERT INTO session_tags (session_id, tag) VALUES (?1, ?2)",
+                    params![session_id, tag],
+                )?;
+            }
+
+            transaction.commit()?;
+            Ok(())
+        })
+        .context(format!("Could not write {}", self.path.display()))
+    }
+
+    fn records_since(&self, timestamp: u64) -> anyhow::Result<Vec<SessionRecord>> {
+        if !self.path.exists() {
+            return Ok(vec![]);
+        }
+
+        self.with_connection(|connection| {
+            let mut pause_reasons = values_by_session(
+                connection,
+                "SELECT pause_reasons.session_id, pause_reasons.reason FROM pause_reasons
+                JOIN sessions ON sessions.id = pause_reasons.session_id
+                WHERE sessions.ended_at >= ?1
+                ORDER BY pause_reasons.rowid",
+                timestamp,
+            )?;
+            let mut tags = values_by_session(
+                connection,
+                "SELECT session_tags.session_id, session_tags.tag FROM session_tags
+                JOIN sessions ON sessions.id = session_tags.session_id
+                WHERE sessions.ended_at >= ?1
+                ORDER BY session_tags.rowid",
+                timestamp,
+            )?;
+
+            let mut statement = connection.prepare(
+                "SELECT id, kind, outcome, round, ended_at, planned_seconds, elapsed_seconds, task,
+                    broken_contract
+                FROM sessions WHERE ended_at >= ?1 ORDER BY id",
+            )?;
+            let mut rows = statement.query(params![timestamp])?;
+            let mut records = vec![];
+
+            while let Some(row) = rows.next()? {
+                let id: i64 = row.get(0)?;
+
+                records.push(SessionRecord {
+                    kind: from_text(row.get(1)?)?,
+                    outcome: from_text(row.get(2)?)?,
+                    round: row.get(3)?,
+                    ended_at: row.get(4)?,
+                    planned_seconds: row.get(5)?,
+                    elapsed_seconds: row.get(6)?,
+                    pause_reasons: pause_reasons.remove(&id).unwrap_or_default(),
+                    tags: tags.remove(&id).unwrap_or_default(),
+                    task: row.get(7)?,
+                    broken_contract: row.get(8)?,
+                });
+            }
+
+            Ok(records)
+        })
+        .context(format!("Could not read {}", self.path.display()))
+    }
+}
+
+/// Adds the [ADDED_SESSION_COLUMNS] to a `sessions` table, which has been created before them
+fn add_missing_columns(connection: &Connection) -> rusqlite::Result<()> {
+    let mut statement = connection.prepare("SELECT name FROM pragma_table_info('sessions')")?;
+    let columns = statement
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    for (name, kind) in ADDED_SESSION_COLUMNS {
+        if !columns.iter().any(|column| column == name) {
+            connection.execute(
+                &format!("ALTER TABLE sessions ADD COLUMN {} {}", name, kind),
+                [],
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads the values of a table with a row per value of a session (e.g. `pause_reasons`) via the
+/// given query, which selects the session id and the value of sessions ended at or after `?1`
+fn values_by_session(
+    connection: &Connection,
+    query: &str,
+    timestamp: u64,
+) -> anyhow::Result<HashMap<i64, Vec<String>>> {
+    let mut values: HashMap<i64, Vec<String>> = HashMap::new();
+    let mut statement = connection.prepare(query)?;
+    let mut rows = statement.query(params![timestamp])?;
+
+    while let Some(row) = rows.next()? {
+        values.entry(row.get(0)?).or_default().push(row.get(1)?);
+    }
+
+    Ok(values)
+}
+
+/// Stores enums by their serialized name (e.g. `short_break`), which is readable inside queries
+fn to_text<T: Serialize>(value: &T) -> anyhow::Result<String> {
+    match serde_json::to_value(value)? {
+        Value::String(text) => Ok(text),
+        other => bail!("Expected a unit variant, got {}", other),
+    }
+}
+
+/// Reads an enum stored via [to_text]
+fn from_text<T: DeserializeOwned>(text: String) -> anyhow::Result<T> {
+    serde_json::from_value(Value::String(text.clone()))
+        .with_context(|| format!("Unknown value '{}'", text))
+}
@@ -0,0 +1,137 @@
+//! Environment contract of hook commands.
+//!
+//! Hook commands (configured via `notifications.hooks` and the `[hooks]` section) and
+//! `zentime listen --exec` handlers receive information about the timer via the following
+//! environment variables:
+//!
+//! * `ZENTIME_EVENT` - name of the [TimerEvent](crate::client::timer_event::TimerEvent)
+//!   (e.g. `interval_end`)
+//! * `ZENTIME_PHASE` - `focus`, `break` or `postponed`
+//! * `ZENTIME_ROUND` - the current round
+//! * `ZENTIME_REMAINING` - the remaining time of the current timer (e.g. `04:59`)
+//! * `ZENTIME_TASK` - the current task (empty if there is none)
+//! * `ZENTIME_REMINDER` - name of the reminder for `reminder` events, name of the timer (or label
+//!   of the countdown) for `countdown_end` events (empty otherwise)
+//! * `ZENTIME_SUGGESTION` - what to do during the break which is about to start (only set for
+//!   notifications at the end of an interval, if break suggestions are enabled - empty otherwise)
+//!
+//! These variables are always set, so that hooks can rely on them.
+
+use std::process::ExitStatus;
+
+use anyhow::Context;
+use tokio::process::Command;
+use zentime_rs_timer::pomodoro_timer::ViewState;
+
+use crate::client::timer_event::Phase;
+
+/// Name of the event which triggered the hook
+pub const ENV_EVENT: &str = "ZENTIME_EVENT";
+
+/// Phase of the timer
+pub const ENV_PHASE: &str = "ZENTIME_PHASE";
+
+/// Current pomodoro round
+pub const ENV_ROUND: &str = "ZENTIME_ROUND";
+
+/// Remaining time of the current timer
+pub const ENV_REMAINING: &str = "ZENTIME_REMAINING";
+
+/// Current task
+pub const ENV_TASK: &str = "ZENTIME_TASK";
+
+/// Name of the reminder which is due
+pub const ENV_REMINDER: &str = "ZENTIME_REMINDER";
+
+/// Suggestion for the upcoming break
+pub const ENV_SUGGESTION: &str = "ZENTIME_SUGGESTION";
+
+/// Values which are passed to a hook command as environment variables
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HookEnv {
+    /// Name of the event which triggered the hook
+    pub event: String,
+
+    /// Phase of the timer
+    pub phase: Phase,
+
+    /// Current pomodoro round
+    pub round: u64,
+
+    /// Remaining time of the current timer
+    pub remaining: String,
+
+    /// Current task
+    pub task: Option<String>,
+
+    /// Name of the reminder which is due
+    pub reminder: Option<String>,
+
+    /// Suggestion for the upcoming break
+    pub suggestion: Option<String>,
+}
+
+impl HookEnv {
+    /// Creates the environment for an event which has been derived from the given state
+    pub fn from_view_state(event: impl ToString, state: &ViewState) -> Self {
+        Self {
+            event: event.to_string(),
+            phase: Phase::of(state),
+            round: state.round,
+            remaining: state.time.clone(),
+            task: state.task.clone(),
+            reminder: None,
+            suggestion: None,
+        }
+    }
+
+    /// Sample data, which is used to test hooks
+    pub fn sample() -> Self {
+        Self {
+            event: "interval_end".to_string(),
+            phase: Phase::Focus,
+            round: 1,
+            remaining: "00:00".to_string(),
+            task: Some("Sample task".to_string()),
+            reminder: None,
+            suggestion: Some("Neck rolls".to_string()),
+        }
+    }
+
+    /// All environment variables and their values
+    pub fn vars(&self) -> [(&'static str, String); 7] {
+        [
+            (ENV_EVENT, self.event.clone()),
+            (ENV_PHASE, self.phase.to_string()),
+            (ENV_ROUND, self.round.to_string()),
+            (ENV_REMAINING, self.remaining.clone()),
+            (ENV_TASK, self.task.clone().unwrap_or_default()),
+            (ENV_REMINDER, self.reminder.clone().unwrap_or_default()),
+            (ENV_SUGGESTION, self.suggestion.clone().unwrap_or_default()),
+        ]
+    }
+
+    /// Creates a command which runs the given shell command with this environment
+    pub fn command(&self, shell_command: &str) -> Command {
+        let mut command = if cfg!(windows) {
+            let mut shell = Command::new("cmd");
+            shell.arg("/C");
+            shell
+        } else {
+            let mut shell = Command::new("sh");
+            shell.arg("-c");
+            shell
+        };
+
+        command.arg(shell_command).envs(self.vars());
+        command
+    }
+
+    /// Runs the given shell command with this environment and waits until it has finished
+    pub async fn run(&self, shell_command: &str) -> anyhow::Result<ExitStatus> {
+        self.command(shell_command)
+            .status()
+            .await
+            .context(format!("Could not run '{}'", shell_command))
+    }
+}
@@ -2,47 +2,580 @@
 
 use anyhow::{bail, Context};
 use futures::io::BufReader;
-use futures::{AsyncReadExt, AsyncWriteExt};
-use interprocess::local_socket::tokio::{OwnedReadHalf, OwnedWriteHalf};
+use futures::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use interprocess::local_socket::tokio::{LocalSocketStream, OwnedReadHalf, OwnedWriteHalf};
+#[cfg(not(windows))]
 use interprocess::local_socket::NameTypeSupport;
+use schemars::gen::SchemaSettings;
+use schemars::schema::{RootSchema, SchemaObject, SubschemaValidation};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Display};
+use std::sync::OnceLock;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::net::TcpStream;
+use tokio::time::{interval_at, timeout, Instant, Interval, MissedTickBehavior};
+use tokio_util::compat::{TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt};
+use zentime_rs_timer::config::PomodoroTimerConfig;
 use zentime_rs_timer::pomodoro_timer::ViewState;
-use std::fmt::Debug;
 
-const DEFAULT_SOCKET_PATH: &str = "/tmp/zentime.sock";
-const DEFAULT_SOCKET_NAMESPACE: &str = "@zentime.sock";
-const DEBUG_SOCKET_PATH: &str = "/tmp/zentime_debug.sock";
-const DEBUG_SOCKET_NAMESPACE: &str = "@zentime_debug.sock";
+pub mod codec;
 
-/// Get zentime socket name over which server and clients may connect
+pub use codec::{FRAME_HEADER_LENGTH, MAX_FRAME_LENGTH};
+
+#[cfg(not(windows))]
+const DEFAULT_SOCKET_PATH_PREFIX: &str = "/tmp/zentime";
+#[cfg(not(windows))]
+const DEFAULT_SOCKET_NAMESPACE_PREFIX: &str = "@zentime";
+#[cfg(not(windows))]
+const DEBUG_SOCKET_PATH_PREFIX: &str = "/tmp/zentime_debug";
+#[cfg(not(windows))]
+const DEBUG_SOCKET_NAMESPACE_PREFIX: &str = "@zentime_debug";
+#[cfg(target_os = "linux")]
+const DEFAULT_SYSTEMD_SOCKET_FILE_NAME: &str = "zentime.sock";
+#[cfg(target_os = "linux")]
+const DEBUG_SYSTEMD_SOCKET_FILE_NAME: &str = "zentime_debug.sock";
+#[cfg(windows)]
+const DEFAULT_PIPE_NAME_PREFIX: &str = "zentime";
+#[cfg(windows)]
+const DEBUG_PIPE_NAME_PREFIX: &str = "zentime_debug";
+
+static SOCKET_NAME: OnceLock<String> = OnceLock::new();
+
+/// Get zentime socket name over which server and clients may connect.
+/// The name contains the id of the current user, so that servers of multiple users on the same
+/// machine never collide.
+/// If systemd listens on the zentime socket (see `zentime server install-service`), its socket
+/// file is used instead.
+#[cfg(not(windows))]
+pub fn get_socket_name() -> &'static str {
+    SOCKET_NAME.get_or_init(|| {
+        #[cfg(target_os = "linux")]
+        if let Some(path) = systemd_socket_path().filter(|path| is_unix_socket(path)) {
+            return path;
+        }
+
+        // This scoping trick allows us to nicely contain the import inside the `match`, so that if
+        // any imports of variants named `Both` happen down the line, they won't collide with the
+        // enum we're working with here. Maybe someone should make a macro for this.
+        use NameTypeSupport::*;
+
+        let prefix = if cfg!(debug_assertions) {
+            match NameTypeSupport::query() {
+                OnlyPaths => DEBUG_SOCKET_PATH_PREFIX,
+                OnlyNamespaced | Both => DEBUG_SOCKET_NAMESPACE_PREFIX,
+            }
+        } else {
+            match NameTypeSupport::query() {
+                OnlyPaths => DEFAULT_SOCKET_PATH_PREFIX,
+                OnlyNamespaced | Both => DEFAULT_SOCKET_NAMESPACE_PREFIX,
+            }
+        };
+
+        format!("{}-{}.sock", prefix, user_id())
+    })
+}
+
+/// Get zentime socket name over which server and clients may connect.
+/// On Windows this is the name of a named pipe (i.e. `\\.\pipe\zentime-<user>`), which
+/// contains the name of the current user, so that servers of multiple users on the same machine
+/// never collide.
+#[cfg(windows)]
 pub fn get_socket_name() -> &'static str {
-    // This scoping trick allows us to nicely contain the import inside the `match`, so that if
-    // any imports of variants named `Both` happen down the line, they won't collide with the
-    // enum we're working with here. Maybe someone should make a macro for this.
-    use NameTypeSupport::*;
+    SOCKET_NAME.get_or_init(|| {
+        let prefix = if cfg!(debug_assertions) {
+            DEBUG_PIPE_NAME_PREFIX
+        } else {
+            DEFAULT_PIPE_NAME_PREFIX
+        };
 
+        format!("{}-{}", prefix, user_id())
+    })
+}
+
+/// Name of the socket file, which systemd listens on (inside `$XDG_RUNTIME_DIR`, i.e. `%t` of
+/// the socket unit) once the server has been installed as service
+#[cfg(target_os = "linux")]
+pub fn systemd_socket_file_name() -> &'static str {
     if cfg!(debug_assertions) {
-        match NameTypeSupport::query() {
-            OnlyPaths => DEBUG_SOCKET_PATH,
-            OnlyNamespaced | Both => DEBUG_SOCKET_NAMESPACE,
-        }
+        DEBUG_SYSTEMD_SOCKET_FILE_NAME
     } else {
-        match NameTypeSupport::query() {
-            OnlyPaths => DEFAULT_SOCKET_PATH,
-            OnlyNamespaced | Both => DEFAULT_SOCKET_NAMESPACE,
+        DEFAULT_SYSTEMD_SOCKET_FILE_NAME
+    }
+}
+
+/// Path of the socket file, which systemd listens on once the server has been installed as
+/// service ([None] without runtime directory)
+#[cfg(target_os = "linux")]
+pub fn systemd_socket_path() -> Option<String> {
+    let runtime_dir = std::env::var("XDG_RUNTIME_DIR").ok()?;
+
+    Some(format!("{}/{}", runtime_dir, systemd_socket_file_name()))
+}
+
+/// Denotes if the zentime socket is the one of systemd, which starts a server as soon as a
+/// client connects to it
+pub fn socket_is_systemd_managed() -> bool {
+    #[cfg(target_os = "linux")]
+    {
+        systemd_socket_path().as_deref() == Some(get_socket_name())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        false
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn is_unix_socket(path: &str) -> bool {
+    use std::os::unix::fs::FileTypeExt;
+
+    std::fs::metadata(path).is_ok_and(|metadata| metadata.file_type().is_socket())
+}
+
+/// Denotes if the zentime socket is a file on the file system (instead of a namespaced socket
+/// or a named pipe)
+pub fn socket_is_file() -> bool {
+    is_socket_file(get_socket_name())
+}
+
+/// Denotes if the given socket name refers to a file on the file system.
+/// Names starting with `@` are namespaced sockets and on Windows every name refers to a named
+/// pipe - both of them vanish together with the server.
+pub fn is_socket_file(socket_name: &str) -> bool {
+    cfg!(not(windows)) && !socket_name.starts_with('@')
+}
+
+/// Removes the socket file of the given socket name, if there is one (e.g. a dangling file of a
+/// server which has crashed)
+pub async fn remove_socket_file(socket_name: &str) -> anyhow::Result<()> {
+    if !is_socket_file(socket_name) || tokio::fs::metadata(socket_name).await.is_err() {
+        return Ok(());
+    }
+
+    tokio::fs::remove_file(socket_name)
+        .await
+        .context("Could not remove existing socket file")
+}
+
+/// Id of the user running the current process
+#[cfg(unix)]
+pub fn user_id() -> String {
+    // SAFETY: getuid() is always successful
+    unsafe { libc::getuid() }.to_string()
+}
+
+/// Id of the user running the current process
+#[cfg(not(unix))]
+pub fn user_id() -> String {
+    std::env::var("USERNAME").unwrap_or_else(|_| String::from("default"))
+}
+
+/// Connects to the zentime socket and makes sure, that the server on the other end is owned
+/// by the current user.
+pub async fn connect() -> anyhow::Result<LocalSocketStream> {
+    let socket_name = get_socket_name();
+
+    if socket_is_file() {
+        verify_socket_file_owner(socket_name)?;
+    }
+
+    let connection = LocalSocketStream::connect(socket_name)
+        .await
+        .context("Could not connect to socket")?;
+
+    verify_peer(&connection)?;
+
+    Ok(connection)
+}
+
+/// Read half of a connection to a zentime server (via local socket or TCP)
+pub type ConnectionReader = Box<dyn AsyncRead + Send + Unpin>;
+
+/// Write half of a connection to a zentime server (via local socket or TCP)
+pub type ConnectionWriter = Box<dyn AsyncWrite + Send + Unpin>;
+
+/// Time a team server has to answer a [ClientToServerMsg::Join]
+const JOIN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Time a server has to answer a [ClientToServerMsg::Handshake]
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Version of the IPC protocol, which is exchanged via [ClientToServerMsg::Handshake].
+/// It has to be increased whenever a message changes in a way, which the other side can't
+/// decode anymore (e.g. a new field without default or a renamed variant).
+pub const PROTOCOL_VERSION: u32 = 3;
+
+/// Name of the pomodoro timer, which runs next to the named timers of a server
+/// (see [ClientToServerMsg::SelectTimer])
+pub const MAIN_TIMER: &str = "main";
+
+/// Interval in which long-lived clients send a [ClientToServerMsg::Heartbeat].
+/// This is well below the default heartbeat timeout of the server, so that a single delayed
+/// heartbeat does not close the connection.
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Ticks once per [HEARTBEAT_INTERVAL] (starting one interval from now), so that clients can
+/// send their heartbeats from inside their event loop
+pub fn heartbeat_interval() -> Interval {
+    let mut heartbeat = interval_at(Instant::now() + HEARTBEAT_INTERVAL, HEARTBEAT_INTERVAL);
+    heartbeat.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    heartbeat
+}
+
+/// Server a client attaches to
+#[derive(Clone)]
+pub enum ServerAddress {
+    /// Server of the current user on this machine
+    Local,
+
+    /// Shared team session of a (usually remote) server, which is joined via TCP
+    Team {
+        /// Host and port of the server (e.g. `192.168.0.2:7878`)
+        address: String,
+
+        /// Token of the team session
+        token: String,
+    },
+}
+
+impl Debug for ServerAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ServerAddress::Local => write!(f, "Local"),
+            // The token is a secret and should never end up in logs
+            ServerAddress::Team { address, .. } => f
+                .debug_struct("Team")
+                .field("address", address)
+                .finish_non_exhaustive(),
+        }
+    }
+}
+
+impl ServerAddress {
+    /// Connects to the server. Team sessions are joined right away, so that an invalid token
+    /// leads to an error.
+    pub async fn connect(&self) -> anyhow::Result<Connection> {
+        match self {
+            ServerAddress::Local => {
+                let (reader, writer) = connect().await?.into_split();
+                let mut reader =
+                    IpcReader::new(BufReader::new(Box::new(reader) as ConnectionReader));
+                let mut writer = IpcWriter::new(Box::new(writer) as ConnectionWriter);
+                let server = handshake(&mut reader, &mut writer).await?;
+
+                Ok(Connection {
+                    reader,
+                    writer,
+                    role: Role::Owner,
+                    server,
+                })
+            }
+            ServerAddress::Team { address, token } => join(address, token).await,
+        }
+    }
+}
+
+/// The server speaks another version of the protocol than this client (see
+/// [ClientToServerMsg::Handshake]), which is usually fixed by restarting the server
+#[derive(Debug, Error)]
+#[error("{message}")]
+pub struct IncompatibleServer {
+    /// What does not match
+    pub message: String,
+}
+
+/// Exchanges the protocol versions with the server, right after the connection has been
+/// established (or the team session has been joined).
+/// Fails with [IncompatibleServer] if the server speaks another version of the protocol.
+pub async fn handshake<R, W>(
+    reader: &mut IpcReader<R>,
+    writer: &mut IpcWriter<W>,
+) -> anyhow::Result<ServerInfo>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    writer
+        .send(&ClientToServerMsg::Handshake {
+            version: PROTOCOL_VERSION,
+        })
+        .await
+        .context("Could not send handshake")?;
+
+    let answer = timeout(HANDSHAKE_TIMEOUT, reader.recv::<ServerToClientMsg>())
+        .await
+        .context("Server did not answer the handshake in time")?;
+
+    match answer {
+        Ok(ServerToClientMsg::Welcome(server)) => Ok(server),
+        Ok(ServerToClientMsg::Error {
+            code: ErrorCode::VersionMismatch,
+            message,
+        }) => Err(IncompatibleServer { message }.into()),
+        Ok(ServerToClientMsg::Error { message, .. }) => bail!("Handshake failed: {}", message),
+        Ok(msg) => bail!("Unexpected answer to handshake {:?}", msg),
+        // Older servers close the connection instead of answering
+        Err(_) => Err(IncompatibleServer {
+            message: format!(
+                "The server did not answer the handshake of protocol version {} - it probably \
+                runs another version of zentime than this client ({})",
+                PROTOCOL_VERSION,
+                env!("CARGO_PKG_VERSION")
+            ),
         }
+        .into()),
+    }
+}
+
+/// Established connection to a zentime server
+pub struct Connection {
+    /// Reads messages from the server
+    pub reader: IpcReader<BufReader<ConnectionReader>>,
+
+    /// Writes messages to the server
+    pub writer: IpcWriter<ConnectionWriter>,
+
+    /// Role the client has been granted by the server
+    pub role: Role,
+
+    /// Server on the other end, as it has introduced itself during the handshake
+    pub server: ServerInfo,
+}
+
+impl Debug for Connection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Connection")
+            .field("role", &self.role)
+            .field("server", &self.server)
+            .finish_non_exhaustive()
     }
 }
 
+/// Connects to a team server via TCP and joins its session
+async fn join(address: &str, token: &str) -> anyhow::Result<Connection> {
+    let stream = TcpStream::connect(address)
+        .await
+        .context(format!("Could not connect to {}", address))?;
+    stream.set_nodelay(true).ok();
+
+    let (reader, writer) = stream.into_split();
+    let mut reader = IpcReader::new(BufReader::new(Box::new(reader.compat()) as ConnectionReader));
+    let mut writer = IpcWriter::new(Box::new(writer.compat_write()) as ConnectionWriter);
+
+    writer
+        .send(&ClientToServerMsg::Join {
+            token: token.to_string(),
+        })
+        .await?;
+
+    let role = timeout(JOIN_TIMEOUT, async {
+        match reader.recv::<ServerToClientMsg>().await? {
+            ServerToClientMsg::Joined { role } => Ok(role),
+            ServerToClientMsg::Error { message, .. } => bail!("{}", message),
+            msg => bail!("Unexpected answer {:?}", msg),
+        }
+    })
+    .await
+    .context("Server did not answer in time")?
+    .context("Could not join team session")?;
+
+    let server = handshake(&mut reader, &mut writer).await?;
+
+    Ok(Connection {
+        reader,
+        writer,
+        role,
+        server,
+    })
+}
+
+/// Makes sure that the socket file is owned by the current user
+#[cfg(unix)]
+fn verify_socket_file_owner(socket_name: &str) -> anyhow::Result<()> {
+    use std::os::unix::fs::MetadataExt;
+
+    let metadata = std::fs::metadata(socket_name).context("Could not read socket file")?;
+
+    // SAFETY: getuid() is always successful
+    if metadata.uid() != unsafe { libc::getuid() } {
+        bail!("Socket {} is owned by another user", socket_name);
+    }
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn verify_socket_file_owner(_socket_name: &str) -> anyhow::Result<()> {
+    Ok(())
+}
+
+/// Makes sure that the process on the other end of the connection is run by the current user.
+/// This is necessary for namespaced sockets, which (unlike socket files) are accessible by
+/// every user of the machine.
+#[cfg(target_os = "linux")]
+pub fn verify_peer(connection: &LocalSocketStream) -> anyhow::Result<()> {
+    use std::os::unix::fs::MetadataExt;
+
+    let peer_pid = connection
+        .peer_pid()
+        .context("Could not determine peer process")?;
+    let metadata = std::fs::metadata(format!("/proc/{}", peer_pid))
+        .context("Could not read peer process information")?;
+
+    // SAFETY: getuid() is always successful
+    if metadata.uid() != unsafe { libc::getuid() } {
+        bail!("Peer process {} is owned by another user", peer_pid);
+    }
+
+    Ok(())
+}
+
+/// Makes sure that the process on the other end of the connection is run by the current user.
+/// On platforms other than linux, we only use socket files, which are protected by their file
+/// permissions instead.
+#[cfg(not(target_os = "linux"))]
+pub fn verify_peer(_connection: &LocalSocketStream) -> anyhow::Result<()> {
+    Ok(())
+}
+
 /// A message from the zentime server to the client
-#[derive(Debug, Clone, Serialize, Deserialize)]
+// Timer states make up nearly all messages - boxing them would add an allocation per message
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub enum ServerToClientMsg {
     /// Aggregated state of the timer which a client can display
     Timer(ViewState),
+
+    /// Answer to a [ClientToServerMsg::Sync], which is only sent to the client which has synced
+    Snapshot(Snapshot),
+
+    /// Answer to a [ClientToServerMsg::Ping]
+    Pong,
+
+    /// Answer to a [ClientToServerMsg::Join], which has been accepted
+    Joined {
+        /// Role the client has been granted
+        role: Role,
+    },
+
+    /// The server has read its config file again (see [ClientToServerMsg::ReloadConfig])
+    ConfigReloaded,
+
+    /// Answer to a [ClientToServerMsg::ListClients]
+    Clients(Vec<ClientInfo>),
+
+    /// Something went wrong on the server
+    Error {
+        /// Category of the error
+        code: ErrorCode,
+
+        /// Human readable description of the error
+        message: String,
+    },
+
+    /// A notification could not be delivered (see `notifications.on_failure = "bell"`),
+    /// therefore attached clients should ring the terminal bell and show its text instead
+    Bell {
+        /// Text of the notification
+        message: String,
+    },
+
+    /// Answer to a [ClientToServerMsg::Handshake], whose protocol version the server speaks
+    Welcome(ServerInfo),
+
+    /// The server is shutting down (e.g. because it has been told to quit or has received
+    /// SIGTERM) and closes the connection right after this message
+    ShuttingDown,
+}
+
+/// Describes the server a client has shaken hands with (see [ClientToServerMsg::Handshake])
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ServerInfo {
+    /// Version of the IPC protocol (see [PROTOCOL_VERSION])
+    pub protocol_version: u32,
+
+    /// Version of zentime the server runs
+    pub zentime_version: String,
+
+    /// Optional features the server has been built with (e.g. `http` or `history`), so that
+    /// clients can leave out what the server can't do
+    pub capabilities: Vec<String>,
+}
+
+impl ServerInfo {
+    /// Describes the server of the current process
+    pub fn current() -> Self {
+        let features = [
+            ("audio", cfg!(feature = "audio")),
+            ("notifications", cfg!(feature = "notifications")),
+            ("http", cfg!(feature = "http")),
+            ("history", cfg!(feature = "history")),
+            ("sqlite", cfg!(feature = "sqlite")),
+        ];
+
+        Self {
+            protocol_version: PROTOCOL_VERSION,
+            zentime_version: env!("CARGO_PKG_VERSION").to_string(),
+            capabilities: features
+                .into_iter()
+                .filter(|(_, enabled)| *enabled)
+                .map(|(feature, _)| feature.to_string())
+                .collect(),
+        }
+    }
+}
+
+/// Complete current state of the server, so that a client which has just attached can render
+/// right away instead of waiting for the next broadcast
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Snapshot {
+    /// Current state of the timer
+    pub state: ViewState,
+
+    /// Timer config of the active profile (lengths of intervals and breaks, postpone limit, ...)
+    pub timers: PomodoroTimerConfig,
+}
+
+/// Category of an error reported by the server via [ServerToClientMsg::Error]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum ErrorCode {
+    /// A notification (sound or OS notification) could not be dispatched
+    Notification,
+
+    /// A client action could not be applied to the timer (e.g. postponing outside of a break)
+    ActionRejected,
+
+    /// The server configuration is invalid
+    Config,
+
+    /// The client is not allowed to do this (e.g. a team viewer trying to skip)
+    Unauthorized,
+
+    /// The client speaks another version of the protocol than the server
+    VersionMismatch,
+
+    /// Any other error
+    Internal,
+}
+
+impl Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ErrorCode::Notification => write!(f, "notification"),
+            ErrorCode::ActionRejected => write!(f, "action rejected"),
+            ErrorCode::Config => write!(f, "config"),
+            ErrorCode::Unauthorized => write!(f, "unauthorized"),
+            ErrorCode::VersionMismatch => write!(f, "version mismatch"),
+            ErrorCode::Internal => write!(f, "internal"),
+        }
+    }
 }
 
 /// A message from a client to the zentime server
-#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub enum ClientToServerMsg {
     /// Command the server to shutdown and close all connections
     Quit,
@@ -59,18 +592,442 @@ pub enum ClientToServerMsg {
     /// Command the server to reset the timer back to interval 1
     Reset,
 
-    /// Currently it's necessary for a client to write at least once to a socket
-    /// connection to synchronize with the server.
-    /// For one-shot zentime commands we therefore use this sync msg to synchronize
-    /// with the server.
+    /// Command the server to go back to the interval or break before the current one (e.g.
+    /// after skipping by accident)
+    Previous,
+
+    /// Command the server to start the current interval or break over
+    RestartCurrent,
+
+    /// Command the server to end the current interval or break and to start the long break
+    /// right away. A full cycle follows the long break.
+    LongBreakNow,
+
+    /// Requests the current state of the server, which is answered right away with
+    /// [ServerToClientMsg::Snapshot].
+    /// Clients send this after attaching (and one-shot zentime commands instead of an action),
+    /// so that they don't have to wait for the next broadcast.
     Sync,
 
     /// Command the server to postpone the current break, if possible
-    PostPone,
+    PostPone {
+        /// Length of the postponed break in seconds - [None] uses the configured
+        /// `postpone_timer`
+        #[serde(default)]
+        seconds: Option<u64>,
+    },
+
+    /// Command the server to add (positive) or remove (negative) the given number of seconds
+    /// to/from the remaining time of the current interval or break
+    AdjustTime(i64),
+
+    /// Check if the server is alive. The server answers with [ServerToClientMsg::Pong].
+    Ping,
+
+    /// Keeps the connection alive without an answer. The server closes connections which
+    /// have not sent any message within its heartbeat timeout (`connections.heartbeat_timeout`),
+    /// therefore long-lived clients send this every [HEARTBEAT_INTERVAL].
+    Heartbeat,
+
+    /// Lists all connected clients. The server answers with [ServerToClientMsg::Clients].
+    ListClients,
+
+    /// Puts the pomodoro cycle on hold (e.g. during a meeting): the timer is paused and no
+    /// notifications are sent, until the hold expires or the timer is resumed manually.
+    Hold {
+        /// Reason of the hold, which is displayed by all clients
+        reason: String,
+
+        /// Unix timestamp (in seconds) at which the timer automatically resumes
+        until: u64,
+    },
+
+    /// Pauses the timer (e.g. for lunch) and resumes it at the given point in time, where the
+    /// user is welcomed back with a notification. In the meantime the pomodoro cycle is on hold,
+    /// just like with [ClientToServerMsg::Hold].
+    PauseUntil {
+        /// Reason of the pause, which is displayed by all clients (e.g. "back at 13:30")
+        reason: String,
+
+        /// Unix timestamp (in seconds) at which the timer resumes
+        until: u64,
+    },
+
+    /// Records why the timer has been paused (e.g. "phone call"), so that common interruptions
+    /// show up inside the statistics. Ignored while the timer is running.
+    PauseReason {
+        /// Reason of the current pause
+        reason: String,
+    },
+
+    /// Labels the current session (e.g. with the task which is worked on), so that the label
+    /// is shown by all clients and passed to notifications and hooks
+    SetTask {
+        /// Label of the session - [None] removes the current label
+        task: Option<String>,
+    },
+
+    /// Adds a task to the plan of the day. Completed intervals are attributed to the planned
+    /// tasks in order, and the task which is currently worked on labels the session (unless
+    /// it has been labeled via [ClientToServerMsg::SetTask]).
+    AddToPlan {
+        /// Task which is worked on
+        task: String,
+
+        /// Intervals which are planned for the task (at least one)
+        pomodoros: u16,
+    },
+
+    /// Removes all tasks from the plan of the day
+    ClearPlan,
+
+    /// Switches to the timer profile with the given name (see `[profiles.<name>]` inside the
+    /// config). Like a reloaded config, the new lengths apply from the next interval/break on,
+    /// unless the current one has not been started yet.
+    SwitchProfile(String),
+
+    /// Declares what the client wants to receive, so that the server can tailor the states
+    /// it sends. Clients should send this right after connecting.
+    Hello(ClientPreferences),
+
+    /// Joins a shared team session. Clients connecting via TCP have to send this first and
+    /// receive [ServerToClientMsg::Joined] or an error, if the token is invalid.
+    Join {
+        /// Token of the team session, which determines the [Role] of the client
+        token: String,
+    },
+
+    /// Command the server to read its config file again. New timer lengths apply from the next
+    /// interval/break on, notification settings apply right away.
+    /// The server answers with [ServerToClientMsg::ConfigReloaded] or an error.
+    ReloadConfig,
+
+    /// Declares the protocol version of the client. Clients send this as their first message
+    /// (team participants right after [ClientToServerMsg::Join]) and receive
+    /// [ServerToClientMsg::Welcome] - or [ErrorCode::VersionMismatch], after which the server
+    /// closes the connection.
+    /// Servers which predate the handshake can't decode it and close the connection right away.
+    Handshake {
+        /// Protocol version of the client (see [PROTOCOL_VERSION])
+        version: u32,
+    },
+
+    /// Starts a named countdown next to the pomodoro timer (e.g. "tea" for 5 minutes), which
+    /// disappears once it has run out. Its state is part of each broadcast state
+    /// ([ViewState::timers]).
+    NewTimer {
+        /// Name the timer is addressed by, which has to be unique
+        name: String,
+
+        /// Length of the countdown in seconds
+        seconds: u64,
+    },
+
+    /// Selects the timer, which all clients display and control from now on: play/pause,
+    /// skip, reset and time adjustments apply to the selected timer only. Skipping a named
+    /// timer removes it.
+    /// [MAIN_TIMER] selects the pomodoro timer again.
+    SelectTimer(String),
+
+    /// Starts a one-off countdown (e.g. "standup" in 15 minutes), which runs independently of
+    /// the pomodoro cycle and notifies once it has run out. Pending countdowns are part of each
+    /// broadcast state ([ViewState::countdowns]).
+    Countdown {
+        /// Length of the countdown in seconds
+        seconds: u64,
+
+        /// What the countdown is for
+        label: Option<String>,
+    },
+}
+
+/// Preferences of a client, which are declared via [ClientToServerMsg::Hello]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ClientPreferences {
+    /// Which timer states the client wants to receive
+    #[serde(default)]
+    pub updates: UpdateMode,
+
+    /// Precision of the remaining time inside each state
+    #[serde(default)]
+    pub precision: TimePrecision,
+
+    /// Seconds between two states in which only the remaining time has changed (0 sends all of
+    /// them). Clients which count down on their own (see [ViewState::progress]) only need such
+    /// a state every now and then to stay in sync with the server.
+    #[serde(default)]
+    pub resync_seconds: u64,
+}
+
+/// Which timer states a client wants to receive
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum UpdateMode {
+    /// Every state in which the (displayed) remaining time has changed
+    #[default]
+    Ticks,
+
+    /// Only states in which something other than the remaining time has changed
+    /// (e.g. a break has started or the timer has been paused)
+    Events,
+}
+
+/// Precision of the remaining time inside [ViewState::time]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum TimePrecision {
+    /// Minutes and seconds (e.g. `24:59`)
+    #[default]
+    Seconds,
+
+    /// Started minutes only (e.g. `25m` for `24:59`), which change at most once a minute
+    Minutes,
+}
+
+/// Client which is connected to the server, as listed via [ClientToServerMsg::ListClients]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ClientInfo {
+    /// Number of the connection, which is unique until the server restarts
+    pub id: u64,
+
+    /// Role of the client
+    pub role: Role,
+
+    /// How the client is connected
+    pub transport: Transport,
+
+    /// Process id of a client on this machine (only known on Linux)
+    pub pid: Option<u32>,
+
+    /// Address of a team participant connected via TCP
+    pub address: Option<String>,
+
+    /// Unix timestamp (in seconds) at which the client has connected
+    pub connected_at: u64,
+
+    /// Unix timestamp (in seconds) of the last message the client has sent
+    pub last_seen: u64,
+}
+
+/// How a client is connected to the server
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum Transport {
+    /// Message frames on the zentime socket
+    Socket,
+
+    /// Plain-text commands on the zentime socket (e.g. via `zentime repl`)
+    PlainText,
+
+    /// Message frames via TCP (team participants)
+    Tcp,
+}
+
+impl Display for Transport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Transport::Socket => write!(f, "socket"),
+            Transport::PlainText => write!(f, "plain-text"),
+            Transport::Tcp => write!(f, "tcp"),
+        }
+    }
+}
+
+/// Role of a client, which determines the messages it may send
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum Role {
+    /// Client of the user running the server, which may do anything (incl. shutting it down)
+    Owner,
+
+    /// Team participant, who may control the timer
+    Controller,
+
+    /// Team participant, who may only watch the timer
+    Viewer,
+}
+
+impl Role {
+    /// Denotes if a client with this role may send the given message
+    pub fn permits(&self, msg: &ClientToServerMsg) -> bool {
+        match self {
+            Role::Owner => true,
+            Role::Controller => !matches!(
+                msg,
+                ClientToServerMsg::Quit
+                    | ClientToServerMsg::ReloadConfig
+                    | ClientToServerMsg::ListClients
+            ),
+            Role::Viewer => matches!(
+                msg,
+                ClientToServerMsg::Sync
+                    | ClientToServerMsg::Ping
+                    | ClientToServerMsg::Heartbeat
+                    | ClientToServerMsg::Detach
+                    | ClientToServerMsg::Hello(_)
+                    | ClientToServerMsg::Join { .. }
+                    | ClientToServerMsg::Handshake { .. }
+            ),
+        }
+    }
+}
+
+impl Display for Role {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Role::Owner => write!(f, "owner"),
+            Role::Controller => write!(f, "controller"),
+            Role::Viewer => write!(f, "viewer"),
+        }
+    }
+}
+
+/// JSON schema of all messages which are exchanged between clients and the server, so that
+/// third-party clients can be written without reading the source.
+/// [ClientToServerMsg] is sent by clients, [ServerToClientMsg] by the server.
+pub fn protocol_schema() -> RootSchema {
+    let mut generator = SchemaSettings::draft07().into_generator();
+    let messages = vec![
+        generator.subschema_for::<ClientToServerMsg>(),
+        generator.subschema_for::<ServerToClientMsg>(),
+    ];
+
+    let mut schema = SchemaObject {
+        subschemas: Some(Box::new(SubschemaValidation {
+            any_of: Some(messages),
+            ..Default::default()
+        })),
+        ..Default::default()
+    };
+
+    let metadata = schema.metadata();
+    metadata.title = Some("zentime IPC protocol".to_string());
+    metadata.description = Some(format!(
+        "Messages are encoded via MessagePack (structs as arrays of their fields in declaration \
+        order, enums as maps from variant name to content or as plain variant names) and each \
+        message is prefixed with its length as little endian u32 (at most {} bytes). \
+        ClientToServerMsg is sent by clients, ServerToClientMsg by the server. \
+        Connections which start with a line of text (e.g. `STATUS`) use the plain-text \
+        protocol instead (see `zentime repl`).",
+        MAX_FRAME_LENGTH
+    ));
+
+    RootSchema {
+        meta_schema: generator.settings().meta_schema.clone(),
+        schema,
+        definitions: generator.take_definitions(),
+    }
+}
+
+/// Reads length-prefixed message frames from a stream.
+///
+/// The internal buffer is reused for all messages, so that receiving a message does not
+/// allocate (once the buffer has grown to the size of the largest message).
+/// Partially read frames are kept inside the buffer, which makes [Self::recv] cancel safe:
+/// it can be used inside `select!` without losing data if another branch completes first.
+#[derive(Debug)]
+pub struct IpcReader<R> {
+    reader: R,
+    buffer: Vec<u8>,
+    filled: usize,
+}
+
+impl<R: AsyncRead + Unpin> IpcReader<R> {
+    /// Creates a new reader reading frames from the given stream
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buffer: vec![0; FRAME_HEADER_LENGTH],
+            filled: 0,
+        }
+    }
+
+    /// Reads the next message from the stream.
+    /// The frame is decoded via [codec::decode] once it has been read completely.
+    pub async fn recv<M>(&mut self) -> anyhow::Result<M>
+    where
+        M: for<'a> Deserialize<'a>,
+    {
+        // Read message length, so that we can make an exact read of the actual message afterwards
+        self.fill(FRAME_HEADER_LENGTH).await?;
+
+        let msg_length = codec::frame_length(&self.buffer[..FRAME_HEADER_LENGTH])?;
+        let frame_length = FRAME_HEADER_LENGTH + msg_length;
+
+        if self.buffer.len() < frame_length {
+            self.buffer.resize(frame_length, 0);
+        }
+
+        // Read message of previously determined length, decode and return it
+        self.fill(frame_length).await?;
+        self.filled = 0;
+
+        codec::decode(&self.buffer[..frame_length])
+    }
+
+    /// Reads from the stream until the buffer contains at least `length` bytes
+    async fn fill(&mut self, length: usize) -> anyhow::Result<()> {
+        while self.filled < length {
+            let read = self
+                .reader
+                .read(&mut self.buffer[self.filled..length])
+                .await
+                .context("Could not read from stream")?;
+
+            if read == 0 {
+                bail!("Stream has been closed before the frame was complete");
+            }
+
+            self.filled += read;
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes length-prefixed message frames to a stream.
+///
+/// Messages are encoded into an internal buffer, which is reused for all messages, and are
+/// then written together with their length prefix in a single write.
+#[derive(Debug)]
+pub struct IpcWriter<W> {
+    writer: W,
+    buffer: Vec<u8>,
+}
+
+impl<W: AsyncWrite + Unpin> IpcWriter<W> {
+    /// Creates a new writer writing frames to the given stream
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Writes a message to the stream.
+    /// The message is encoded via [codec::encode].
+    pub async fn send<M>(&mut self, msg: &M) -> anyhow::Result<()>
+    where
+        M: Serialize + Debug,
+    {
+        codec::encode(msg, &mut self.buffer)?;
+
+        self.writer
+            .write_all(&self.buffer)
+            .await
+            .context(format!("Could not write {:?} to stream", msg))?;
+
+        Ok(())
+    }
+
+    /// Mutable access to the underlying stream
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
 }
 
 /// Service handling communication between processes over the zentime socket.
 /// Multiple clients may exist alongside a single (usually daemonized) zentime server instance.
+///
+/// NOTE:
+/// These functions set up a new [IpcReader]/[IpcWriter] on each call and are therefore meant for
+/// single messages. Long-lived connections should use an [IpcReader] and [IpcWriter] directly.
 #[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 pub struct InterProcessCommunication {}
 
@@ -81,66 +1038,15 @@ impl InterProcessCommunication {
     where
         M: Serialize + for<'a> Deserialize<'a> + Debug,
     {
-        let encoded_msg =
-            rmp_serde::encode::to_vec::<M>(&msg).context(format!("Could not encode {:?}", msg))?;
-        let msg_length =
-            u32::try_from(encoded_msg.len()).context("Could not cast msg length to u32")?;
-        let msg_length = msg_length.to_le_bytes();
-
-        // Write msg with length to the stream (see [Self::recv_ipc_message] for why this is needed)
-        writer
-            .write_all(&msg_length)
-            .await
-            .context("Could not write message length to stream")?;
-
-        // Write actual msg to the stream
-        writer
-            .write_all(&encoded_msg)
-            .await
-            .context(format!("Could not write {:?} to stream", msg))?;
-
-        Ok(())
+        IpcWriter::new(writer).send(&msg).await
     }
 
-    /// Writes a message to the zentime socket.
+    /// Reads a message from the zentime socket.
     /// The message is decoded via [rmp_serde::decode] which uses [Messagepack](https://msgpack.org/) to decode type information.
     pub async fn recv_ipc_message<M>(reader: &mut BufReader<OwnedReadHalf>) -> anyhow::Result<M>
     where
         M: Serialize + for<'a> Deserialize<'a> + Debug,
     {
-        // Read message length, so that we can make an exact read of the actual message afterwards
-        let mut buffer = [0_u8; 4];
-
-        reader
-            .read_exact(&mut buffer)
-            .await
-            .context("Could not read msg length")?;
-        let msg_length = u32::from_le_bytes(buffer);
-
-        let mut buffer = [0_u8; 1024];
-
-        // Read message of previously determined length, decode and return it
-        if let Err(error) = reader
-            .read_exact(
-                &mut buffer[0..usize::try_from(msg_length)
-                    .context("Could not convert msg length to usize")?],
-            )
-            .await
-        {
-            match error.kind() {
-                std::io::ErrorKind::UnexpectedEof => {
-                    bail!("Buffer slice has not been filled entirely: {:?}", error)
-                }
-                _ => bail!("Could not read into buffer: {:?}", error),
-            }
-        };
-
-        match rmp_serde::from_slice::<M>(
-            &buffer[0..usize::try_from(msg_length)
-                .context("Could not convert msg length to usize")?],
-        ) {
-            Ok(msg) => Ok(msg),
-            Err(error) => bail!("Could not decode msg: {:?}", error),
-        }
+        IpcReader::new(reader).recv().await
     }
 }
@@ -0,0 +1,289 @@
+//! Encoding of IPC messages into frames.
+//!
+//! A frame consists of a header, which contains the length of the encoded message as little
+//! endian u32, followed by the message encoded via [MessagePack](https://msgpack.org/)
+//! ([rmp_serde]). The length tells readers up front how many bytes belong to the message, so
+//! that they can keep reading until the frame is complete instead of relying on a single read.
+//!
+//! [IpcReader](super::IpcReader) and [IpcWriter](super::IpcWriter) take care of the streams -
+//! these functions work on buffers and can be used by clients which handle the IO themselves.
+
+use std::fmt::Debug;
+
+use anyhow::{bail, Context};
+use serde::{Deserialize, Serialize};
+
+/// Maximum length of a single encoded message.
+/// Frames announcing a larger length are rejected instead of allocating arbitrary amounts of
+/// memory for them.
+pub const MAX_FRAME_LENGTH: usize = 64 * 1024;
+
+/// Length of the frame header, which contains the length of the encoded message as
+/// little endian u32
+pub const FRAME_HEADER_LENGTH: usize = 4;
+
+/// Encodes the message as frame into the given buffer (replacing its content), so that the
+/// buffer can be reused for the next message
+pub fn encode<M>(msg: &M, frame: &mut Vec<u8>) -> anyhow::Result<()>
+where
+    M: Serialize + Debug,
+{
+    frame.clear();
+    // Placeholder for the frame header, which is filled in as soon as we know the
+    // length of the encoded message
+    frame.extend_from_slice(&[0; FRAME_HEADER_LENGTH]);
+
+    rmp_serde::encode::write(frame, msg).context(format!("Could not encode {:?}", msg))?;
+
+    let msg_length = frame.len() - FRAME_HEADER_LENGTH;
+    if msg_length > MAX_FRAME_LENGTH {
+        bail!("Encoded {:?} exceeds the maximum frame length", msg);
+    }
+
+    let msg_length = u32::try_from(msg_length).context("Could not cast msg length to u32")?;
+    frame[..FRAME_HEADER_LENGTH].copy_from_slice(&msg_length.to_le_bytes());
+
+    Ok(())
+}
+
+/// Decodes a complete frame (header and message)
+pub fn decode<M>(frame: &[u8]) -> anyhow::Result<M>
+where
+    M: for<'a> Deserialize<'a>,
+{
+    let Some((header, msg)) = frame.split_at_checked(FRAME_HEADER_LENGTH) else {
+        bail!("Frame of {} bytes is missing its header", frame.len());
+    };
+
+    let msg_length = frame_length(header)?;
+    if msg.len() != msg_length {
+        bail!(
+            "Frame announces {} bytes, but contains {}",
+            msg_length,
+            msg.len()
+        );
+    }
+
+    rmp_serde::from_slice::<M>(msg).context("Could not decode msg")
+}
+
+/// Decodes the frame header and validates the announced message length
+pub fn frame_length(header: &[u8]) -> anyhow::Result<usize> {
+    let header: [u8; FRAME_HEADER_LENGTH] = header
+        .try_into()
+        .context("Frame header has an invalid length")?;
+    let msg_length = usize::try_from(u32::from_le_bytes(header))
+        .context("Could not convert msg length to usize")?;
+
+    if msg_length > MAX_FRAME_LENGTH {
+        bail!(
+            "Frame of {} bytes exceeds the maximum frame length",
+            msg_length
+        );
+    }
+
+    Ok(msg_length)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ipc::{
+        ClientInfo, ClientPreferences, ClientToServerMsg, ErrorCode, Role, ServerInfo,
+        ServerToClientMsg, Snapshot, TimePrecision, Transport, UpdateMode, MAIN_TIMER,
+        PROTOCOL_VERSION,
+    };
+    use zentime_rs_timer::config::PomodoroTimerConfig;
+    use zentime_rs_timer::pomodoro_timer_action::PomodoroTimerAction;
+    use zentime_rs_timer::ticking_pomodoro_timer::TickingPomodoroTimer;
+
+    /// Encodes the message as frame and decodes it again. Messages don't implement [PartialEq],
+    /// therefore the round trip is compared via their debug representation.
+    fn assert_round_trip<M>(msg: &M) -> anyhow::Result<()>
+    where
+        M: Serialize + for<'a> Deserialize<'a> + Debug,
+    {
+        let mut frame = vec![];
+        encode(msg, &mut frame)?;
+
+        let length = frame_length(&frame[..FRAME_HEADER_LENGTH])?;
+        assert_eq!(length, frame.len() - FRAME_HEADER_LENGTH);
+
+        let decoded: M = decode(&frame)?;
+        assert_eq!(format!("{:?}", decoded), format!("{:?}", msg));
+
+        Ok(())
+    }
+
+    #[test]
+    fn encodes_and_decodes_all_messages() -> anyhow::Result<()> {
+        let client_msgs = vec![
+            ClientToServerMsg::Quit,
+            ClientToServerMsg::Detach,
+            ClientToServerMsg::PlayPause,
+            ClientToServerMsg::Skip,
+            ClientToServerMsg::Reset,
+            ClientToServerMsg::Previous,
+            ClientToServerMsg::RestartCurrent,
+            ClientToServerMsg::LongBreakNow,
+            ClientToServerMsg::Sync,
+            ClientToServerMsg::PostPone { seconds: None },
+            ClientToServerMsg::PostPone { seconds: Some(600) },
+            ClientToServerMsg::AdjustTime(-300),
+            ClientToServerMsg::Ping,
+            ClientToServerMsg::Heartbeat,
+            ClientToServerMsg::ListClients,
+            ClientToServerMsg::Hold {
+                reason: "meeting".to_string(),
+                until: 1_700_000_000,
+            },
+            ClientToServerMsg::PauseUntil {
+                reason: "back at 13:30".to_string(),
+                until: 1_700_000_000,
+            },
+            ClientToServerMsg::PauseReason {
+                reason: "phone call".to_string(),
+            },
+            ClientToServerMsg::SetTask {
+                task: Some("Write docs".to_string()),
+            },
+            ClientToServerMsg::SetTask { task: None },
+            ClientToServerMsg::AddToPlan {
+                task: "Review".to_string(),
+                pomodoros: 2,
+            },
+            ClientToServerMsg::ClearPlan,
+            ClientToServerMsg::SwitchProfile("deep-work".to_string()),
+            ClientToServerMsg::Hello(ClientPreferences {
+                updates: UpdateMode::Events,
+                precision: TimePrecision::Minutes,
+                resync_seconds: 30,
+            }),
+            ClientToServerMsg::Join {
+                token: "secret".to_string(),
+            },
+            ClientToServerMsg::ReloadConfig,
+            ClientToServerMsg::Handshake {
+                version: PROTOCOL_VERSION,
+            },
+            ClientToServerMsg::NewTimer {
+                name: "tea".to_string(),
+                seconds: 300,
+            },
+            ClientToServerMsg::SelectTimer(MAIN_TIMER.to_string()),
+            ClientToServerMsg::Countdown {
+                seconds: 900,
+                label: Some("standup".to_string()),
+            },
+        ];
+
+        for msg in &client_msgs {
+            // Adding a variant fails to compile here, until it is added to the messages above
+            match msg {
+                ClientToServerMsg::Quit
+                | ClientToServerMsg::Detach
+                | ClientToServerMsg::PlayPause
+                | ClientToServerMsg::Skip
+                | ClientToServerMsg::Reset
+                | ClientToServerMsg::Previous
+                | ClientToServerMsg::RestartCurrent
+                | ClientToServerMsg::LongBreakNow
+                | ClientToServerMsg::Sync
+                | ClientToServerMsg::PostPone { .. }
+                | ClientToServerMsg::AdjustTime(_)
+                | ClientToServerMsg::Ping
+                | ClientToServerMsg::Heartbeat
+                | ClientToServerMsg::ListClients
+                | ClientToServerMsg::Hold { .. }
+                | ClientToServerMsg::PauseUntil { .. }
+                | ClientToServerMsg::PauseReason { .. }
+                | ClientToServerMsg::SetTask { .. }
+                | ClientToServerMsg::AddToPlan { .. }
+                | ClientToServerMsg::ClearPlan
+                | ClientToServerMsg::SwitchProfile(_)
+                | ClientToServerMsg::Hello(_)
+                | ClientToServerMsg::Join { .. }
+                | ClientToServerMsg::ReloadConfig
+                | ClientToServerMsg::Handshake { .. }
+                | ClientToServerMsg::NewTimer { .. }
+                | ClientToServerMsg::SelectTimer(_)
+                | ClientToServerMsg::Countdown { .. } => assert_round_trip(msg)?,
+            }
+        }
+
+        let mut timer = TickingPomodoroTimer::new(PomodoroTimerConfig::default());
+        timer.handle_action(PomodoroTimerAction::PlayPause)?;
+        let state = timer.view_state();
+
+        let server_msgs = vec![
+            ServerToClientMsg::Timer(state.clone()),
+            ServerToClientMsg::Snapshot(Snapshot {
+                state,
+                timers: PomodoroTimerConfig::default(),
+            }),
+            ServerToClientMsg::Pong,
+            ServerToClientMsg::Joined {
+                role: Role::Controller,
+            },
+            ServerToClientMsg::ConfigReloaded,
+            ServerToClientMsg::Clients(vec![ClientInfo {
+                id: 7,
+                role: Role::Viewer,
+                transport: Transport::Tcp,
+                pid: None,
+                address: Some("192.168.0.2:7878".to_string()),
+                connected_at: 1_700_000_000,
+                last_seen: 1_700_000_060,
+            }]),
+            ServerToClientMsg::Error {
+                code: ErrorCode::ActionRejected,
+                message: "Can't postpone outside of a break".to_string(),
+            },
+            ServerToClientMsg::Bell {
+                message: "Time for a break".to_string(),
+            },
+            ServerToClientMsg::Welcome(ServerInfo::current()),
+            ServerToClientMsg::ShuttingDown,
+        ];
+
+        for msg in &server_msgs {
+            match msg {
+                ServerToClientMsg::Timer(_)
+                | ServerToClientMsg::Snapshot(_)
+                | ServerToClientMsg::Pong
+                | ServerToClientMsg::Joined { .. }
+                | ServerToClientMsg::ConfigReloaded
+                | ServerToClientMsg::Clients(_)
+                | ServerToClientMsg::Error { .. }
+                | ServerToClientMsg::Bell { .. }
+                | ServerToClientMsg::Welcome(_)
+                | ServerToClientMsg::ShuttingDown => assert_round_trip(msg)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_invalid_frames() -> anyhow::Result<()> {
+        let mut frame = vec![];
+        encode(
+            &ClientToServerMsg::SwitchProfile("deep-work".to_string()),
+            &mut frame,
+        )?;
+
+        // Truncated message and missing header
+        assert!(decode::<ClientToServerMsg>(&frame[..frame.len() - 1]).is_err());
+        assert!(decode::<ClientToServerMsg>(&frame[..2]).is_err());
+
+        // Announced length beyond the maximum
+        let oversized = u32::try_from(MAX_FRAME_LENGTH + 1)?.to_le_bytes();
+        assert!(frame_length(&oversized).is_err());
+
+        // Messages beyond the maximum are not sent at all
+        let task = "x".repeat(MAX_FRAME_LENGTH);
+        assert!(encode(&ClientToServerMsg::SetTask { task: Some(task) }, &mut frame).is_err());
+
+        Ok(())
+    }
+}
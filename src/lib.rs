@@ -10,7 +10,22 @@
 //!
 #![doc = include_str!("../README.md")]
 
+pub mod calendar;
 pub mod client;
+pub mod client_api;
 pub mod config;
+pub mod export;
+#[cfg(feature = "history")]
+pub mod goals;
+pub mod history;
+pub mod hooks;
 pub mod ipc;
+pub mod logging;
+pub mod plan;
+#[cfg(feature = "history")]
+pub mod report;
+pub mod secrets;
 pub mod server;
+#[cfg(feature = "test-support")]
+pub mod test_support;
+pub mod wall_clock;
@@ -0,0 +1,192 @@
+//! Log files of the server and of clients (see `[log]` inside the config).
+//!
+//! Each process writes into its own file (`server.log` or `client.log` inside
+//! `~/.local/state/zentime` by default), which is rotated once it has grown beyond
+//! `log.max_size`. Entries are either plain text lines (like `[<time> WARN  zentime] ...`) or
+//! JSON objects with the fields `time`, `level`, `target` and `message`.
+
+use std::fs::{create_dir_all, rename, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use env_logger::{Builder, Env, Target, WriteStyle};
+use log::LevelFilter;
+use serde_json::json;
+
+use crate::config::LogConfig;
+
+/// Process whose log entries are written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogTarget {
+    /// The zentime server (writes into [LogConfig::file])
+    Server,
+
+    /// A client, e.g. the default interface or `zentime status` (writes into
+    /// [LogConfig::client_file])
+    Client,
+}
+
+/// Sets up the global logger of the current process according to the config.
+/// `RUST_LOG` takes precedence over the configured level.
+///
+/// If the log file can't be opened, entries are written to stderr instead and an error is
+/// returned, so that the caller can report it.
+pub fn init(config: &LogConfig, target: LogTarget) -> anyhow::Result<()> {
+    let path = match target {
+        LogTarget::Server => config.file(),
+        LogTarget::Client => config.client_file(),
+    };
+
+    let mut builder = Builder::new();
+    builder.filter_level(LevelFilter::from(config.level));
+    builder.parse_env(Env::default());
+
+    if config.json {
+        builder.format(|buf, record| {
+            let entry = json!({
+                "time": buf.timestamp().to_string(),
+                "level": record.level().as_str(),
+                "target": record.target(),
+                "message": record.args().to_string(),
+            });
+
+            writeln!(buf, "{}", entry)
+        });
+    }
+
+    builder.write_style(WriteStyle::Never);
+
+    let result = RotatingFile::open(&path, config.max_size * 1024, config.max_files);
+
+    match result {
+        Ok(file) => {
+            builder.target(Target::Pipe(Box::new(file)));
+            // Another logger might already have been set up (e.g. by an embedding application)
+            builder.try_init().ok();
+            Ok(())
+        }
+        Err(error) => {
+            builder.target(Target::Stderr);
+            builder.try_init().ok();
+            Err(error)
+        }
+    }
+}
+
+/// Log file, which is moved to `<file>.1` (and older ones to `<file>.2` and so on) once it has
+/// grown beyond its maximum size
+#[derive(Debug)]
+pub struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    max_size: u64,
+    max_files: usize,
+}
+
+impl RotatingFile {
+    /// Opens the file for appending (and creates it with its directory, if necessary).
+    /// A maximum size of 0 never rotates the file.
+    pub fn open(path: &Path, max_size: u64, max_files: usize) -> anyhow::Result<Self> {
+        if let Some(directory) = path.parent() {
+            create_dir_all(directory)
+                .with_context(|| format!("Could not create {}", directory.display()))?;
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            file: open_append(path)?,
+            max_size,
+            max_files,
+        })
+    }
+
+    /// Path of a rotated file, e.g. `server.log.2`
+    pub fn rotated_path(&self, index: usize) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(format!(".{}", index));
+        PathBuf::from(path)
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        if self.max_files > 0 {
+            for index in (1..self.max_files).rev() {
+                let older = self.rotated_path(index);
+
+                if older.exists() {
+                    rename(older, self.rotated_path(index + 1))?;
+                }
+            }
+
+            rename(&self.path, self.rotated_path(1))?;
+            self.file = open_append(&self.path).map_err(io::Error::other)?;
+        } else {
+            self.file.set_len(0)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // All clients append to the same file, therefore we ask the file system for its size
+        // instead of counting what we have written
+        let size = self.file.metadata()?.len();
+
+        if self.max_size > 0 && size > 0 && size + buf.len() as u64 > self.max_size {
+            self.rotate()?;
+        }
+
+        self.file.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+fn open_append(path: &Path) -> anyhow::Result<File> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Could not open {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotates_log_files_beyond_their_maximum_size() -> anyhow::Result<()> {
+        let directory =
+            std::env::temp_dir().join(format!("zentime-test-log-{}", std::process::id()));
+        let path = directory.join("server.log");
+        let mut log = RotatingFile::open(&path, 16, 2)?;
+
+        for entry in [
+            "first entry\n",
+            "second entry\n",
+            "third entry\n",
+            "fourth entry\n",
+        ] {
+            log.write_all(entry.as_bytes())?;
+        }
+
+        assert_eq!(std::fs::read_to_string(&path)?, "fourth entry\n");
+        assert_eq!(
+            std::fs::read_to_string(log.rotated_path(1))?,
+            "third entry\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(log.rotated_path(2))?,
+            "second entry\n"
+        );
+        assert!(!log.rotated_path(3).exists());
+
+        std::fs::remove_dir_all(directory)?;
+
+        Ok(())
+    }
+}
@@ -1,20 +1,62 @@
+use crate::cli_error::CliError;
+#[cfg(feature = "tui")]
 use crate::default_cmd::default_cmd;
+#[cfg(feature = "history")]
+use chrono::NaiveDate;
 use clap::{Parser, Subcommand};
-use env_logger::Env;
+use log::LevelFilter;
 
+mod cli_error;
+#[cfg(feature = "tui")]
 mod default_cmd;
 mod subcommands;
 use figment::providers::Serialized;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
+#[cfg(all(feature = "tray", target_os = "linux"))]
+use subcommands::tray::tray;
+#[cfg(feature = "history")]
 use subcommands::{
+    calendar::calendar,
+    export::{export, parse_day, Day, ExportFormat},
+    replay::replay,
+    report::report,
+    stats::{stats, StatsPeriod},
+};
+use subcommands::{
+    countdown::countdown,
+    hold::{hold, parse_duration},
+    hooks::test_hooks,
+    init::init,
+    install_autostart::install_autostart,
+    ipc_schema::ipc_schema,
+    listen::listen,
+    log::log,
+    long_break::long_break,
+    overlay::overlay,
+    pause_until::{parse_time_of_day, pause_until},
+    plan::{add_to_plan, clear_plan, show_plan},
     postpone::postpone,
+    profile::{list_profiles, switch_profile},
     query_server_once::query_server_once,
+    reload::reload,
+    repl::repl,
     reset_timer::reset_timer,
-    server::{start_daemonized, status, stop},
+    secrets::{remove_secret, set_secret},
+    server::{clients, install_service, restart, run_service, start_daemonized, status, stop},
     skip_timer::skip_timer,
+    status::{print_status, StatusFormat},
+    task::task,
+    timer::{list_timers, new_timer, select_timer},
     toggle_timer::toggle_timer,
 };
-use zentime_rs::config::{create_base_config, Config};
+#[cfg(feature = "tui")]
+use subcommands::{join::join, popup::popup};
+use zentime_rs::config::{create_base_config, Config, LogConfig};
+use zentime_rs::logging::{self, LogTarget};
+use zentime_rs::wall_clock::WallClock;
+use zentime_rs_timer::duration::HumanDuration;
+use zentime_rs_timer::status_template::StatusTemplate;
 
 #[derive(clap::Args)]
 pub struct CommonArgs {
@@ -59,20 +101,20 @@ struct ClapNotificationConfig {
 #[derive(clap::Args, Serialize, Deserialize, Copy, Clone, Debug)]
 #[serde(rename(serialize = "TimerConfig"))]
 struct ClapTimerConfig {
-    /// Timer in seconds
+    /// Timer (e.g. `25m`, `90s` or `1h15m` - plain numbers are seconds)
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[arg(long)]
-    pub timer: Option<u64>,
+    #[arg(long, value_name = "DURATION")]
+    pub timer: Option<HumanDuration>,
 
-    /// Minor break time in seconds
+    /// Minor break time (e.g. `25m`, `90s` or `1h15m` - plain numbers are seconds)
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[arg(long)]
-    pub minor_break: Option<u64>,
+    #[arg(long, value_name = "DURATION")]
+    pub minor_break: Option<HumanDuration>,
 
-    /// Major break time in seconds
+    /// Major break time (e.g. `25m`, `90s` or `1h15m` - plain numbers are seconds)
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[arg(long)]
-    pub major_break: Option<u64>,
+    #[arg(long, value_name = "DURATION")]
+    pub major_break: Option<HumanDuration>,
 
     /// Intervals before major break
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -86,10 +128,20 @@ struct ClapTimerConfig {
     #[arg(long)]
     pub postpone_limit: Option<u16>,
 
-    /// Determines how long each postpone timer runs (in seconds)
+    /// Determines how long each postpone timer runs (e.g. `5m` - plain numbers are seconds)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[arg(long, value_name = "DURATION")]
+    pub postpone_timer: Option<HumanDuration>,
+
+    /// Starts each break right away once an interval has ended
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[arg(long)]
+    pub auto_start_break: Option<bool>,
+
+    /// Starts the next interval right away once a break has ended
     #[serde(skip_serializing_if = "Option::is_none")]
     #[arg(long)]
-    pub postpone_timer: Option<u64>,
+    pub auto_start_work: Option<bool>,
 }
 
 #[derive(clap::Args, Serialize, Deserialize, Clone, Debug)]
@@ -100,6 +152,28 @@ pub struct ServerConfig {
 
     #[command(flatten)]
     notifications: ClapNotificationConfig,
+
+    /// Timer profile which is active once the server has started
+    /// (see `[profiles.<name>]` inside the config)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[arg(long, value_name = "NAME")]
+    profile: Option<String>,
+
+    #[command(flatten)]
+    state: ClapStateConfig,
+}
+
+/// This should match [Config::StateConfig], but makes fields optional, so that they are not
+/// required by clap. If no value is provided and therefore the `Option` is `None`, we skip
+/// serializing the value.
+#[derive(clap::Args, Serialize, Deserialize, Copy, Clone, Debug)]
+#[serde(rename(serialize = "StateConfig"))]
+struct ClapStateConfig {
+    /// Continues the timer of the previous server (round, phase, remaining time and postpone
+    /// count) instead of starting over with the first interval
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+    pub resume: Option<bool>,
 }
 
 /// This should match [Config::ViewConfig], but makes fields optional, so that they are not
@@ -116,6 +190,40 @@ struct ClapViewConfig {
     /// (e.g. `zentime skip` or `zentime toggle-timer`)
     #[arg(long, short = 's')]
     pub silent: bool,
+
+    /// Shows the remaining time and phase inside the title of the terminal window/tab
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    #[arg(long)]
+    pub terminal_title: bool,
+
+    #[command(flatten)]
+    pub exec_on: ClapEventCommands,
+}
+
+/// This should match [Config::EventCommands], but is flattened into the client arguments
+#[derive(clap::Args, Serialize, Deserialize, Clone, Debug)]
+#[serde(rename(serialize = "EventCommands"))]
+struct ClapEventCommands {
+    /// Shell command which is run whenever a focus interval starts (see `zentime listen` for
+    /// the available environment variables)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[arg(long = "exec-on-interval-start", value_name = "CMD")]
+    pub interval_start: Option<String>,
+
+    /// Shell command which is run whenever a focus interval ends
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[arg(long = "exec-on-interval-end", value_name = "CMD")]
+    pub interval_end: Option<String>,
+
+    /// Shell command which is run whenever a break starts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[arg(long = "exec-on-break-start", value_name = "CMD")]
+    pub break_start: Option<String>,
+
+    /// Shell command which is run whenever a break ends
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[arg(long = "exec-on-break-end", value_name = "CMD")]
+    pub break_end: Option<String>,
 }
 
 #[derive(clap::Args, Serialize, Deserialize, Clone, Debug)]
@@ -153,8 +261,28 @@ enum Commands {
     /// zentime into a status bar etc.
     Once,
 
+    /// Prints the current state of the timer once and detaches right away (e.g. for polybar,
+    /// waybar or tmux)
+    Status {
+        /// Output format
+        #[arg(long, value_enum, default_value_t = StatusFormat::Plain)]
+        format: StatusFormat,
+
+        /// Template of the status line (e.g. `"{round} {time} {state_icon}"`), which overrides
+        /// `view.format`. Placeholders: {time}, {round}, {phase}, {break}, {postpone_count},
+        /// {paused}, {task} and {state_icon}.
+        #[arg(long)]
+        template: Option<StatusTemplate>,
+    },
+
     /// Toggles between timer play/pause
-    ToggleTimer,
+    #[command(alias = "toggle")]
+    ToggleTimer {
+        /// Reason of the pause (e.g. `--reason "phone call"`), which is recorded in the
+        /// history and summarized by `zentime stats`
+        #[arg(long)]
+        reason: Option<String>,
+    },
 
     /// Skips to next timer interval
     Skip,
@@ -162,14 +290,330 @@ enum Commands {
     /// Resets the timer to the first interval
     Reset,
 
-    /// Postpones the current break (if possible)
-    Postpone,
+    /// Ends the current interval or break and starts the long break right away.
+    /// Unlike `skip`, this ends the current cycle early, so that a full cycle follows.
+    LongBreak,
+
+    /// Postpones the current break (if possible), e.g. `zentime postpone 10m`
+    Postpone {
+        /// Length of the postponed break (e.g. `3m` or `90s` - plain numbers are seconds).
+        /// Defaults to the configured `postpone_timer`.
+        #[arg(value_parser = parse_duration)]
+        duration: Option<Duration>,
+    },
+
+    /// Puts the pomodoro cycle on hold (e.g. `zentime hold 45m "meeting"`).
+    /// The timer is paused and no notifications are sent, until the hold expires or the timer
+    /// is resumed manually.
+    Hold {
+        /// Duration of the hold (e.g. `45m`, `1h30m` or `90s` - plain numbers are seconds)
+        #[arg(value_parser = parse_duration)]
+        duration: Duration,
+
+        /// Reason of the hold, which is displayed by all clients
+        reason: Option<String>,
+    },
+
+    /// Pauses the timer until the given time of day (e.g. `zentime pause-until 13:30`) or for the
+    /// given duration (e.g. `zentime pause-until --for 45m`). Afterwards the timer is resumed
+    /// automatically and a notification welcomes you back.
+    PauseUntil {
+        /// Time of day at which the timer is resumed (tomorrow, if it has already passed today)
+        #[arg(
+            value_parser = parse_time_of_day,
+            required_unless_present = "duration",
+            conflicts_with = "duration"
+        )]
+        time: Option<chrono::NaiveTime>,
+
+        /// Pauses for this long instead (e.g. `45m`, `1h30m` or `90s` - plain numbers are
+        /// seconds)
+        #[arg(long = "for", value_name = "DURATION", value_parser = parse_duration)]
+        duration: Option<Duration>,
+    },
+
+    /// Labels the current session with the task which is worked on (e.g.
+    /// `zentime task "write docs"`). The label is shown by all clients and passed to
+    /// notifications and hooks. Omitting the task removes the label.
+    Task {
+        /// Task which is worked on
+        task: Option<String>,
+    },
+
+    /// Plans the day as queue of tasks with an estimate of pomodoros (e.g.
+    /// `zentime plan add "review PRs" 2`). Completed intervals are attributed to the planned
+    /// tasks in order, and the current task labels the session. Shows the plan of today, if no
+    /// subcommand is given.
+    Plan {
+        #[command(subcommand)]
+        command: Option<PlanCommands>,
+    },
+
+    /// Switches the server to another timer profile (see `[profiles.<name>]` inside the config,
+    /// e.g. `zentime profile deep-work`). The new lengths apply from the next interval or break
+    /// on. Lists the available profiles, if no profile is given.
+    Profile {
+        /// Name of the profile
+        name: Option<String>,
+    },
+
+    /// Runs further named timers next to the pomodoro timer (e.g.
+    /// `zentime timer new tea 5m`). The selected timer is displayed and controlled by all
+    /// clients. Lists the timers, if no subcommand is given.
+    Timer {
+        #[command(subcommand)]
+        command: Option<TimerCommands>,
+    },
+
+    /// Starts a one-off countdown (e.g. `zentime countdown 15m standup`), which runs
+    /// independently of the pomodoro cycle and notifies once it has run out
+    Countdown {
+        /// Length of the countdown (e.g. `15m` or `90s` - plain numbers are seconds)
+        #[arg(value_parser = parse_duration)]
+        duration: Duration,
+
+        /// What the countdown is for, which is shown by the notification
+        label: Option<String>,
+    },
+
+    /// Makes the server read its config file again. New timer lengths apply from the next
+    /// interval or break on and notification settings right away - all other settings require
+    /// a restart of the server.
+    Reload,
+
+    /// Prints statistics about recorded intervals and breaks (e.g. how consistently breaks
+    /// have been taken)
+    #[cfg(feature = "history")]
+    Stats {
+        /// Number of days to summarize
+        #[arg(long, default_value_t = 7)]
+        days: u64,
+
+        /// Additionally lists the intervals and focus time of each day or week
+        #[arg(long, value_enum)]
+        by: Option<StatsPeriod>,
+    },
+
+    /// Shows the recorded sessions of a single day as timeline, to review how focus and breaks
+    /// have been distributed
+    #[cfg(feature = "history")]
+    Replay {
+        /// Day to replay (e.g. `2024-05-17`) - defaults to today
+        #[arg(long)]
+        date: Option<NaiveDate>,
+
+        /// Plays the day back accelerated by this factor (e.g. `60` plays one minute per
+        /// second) instead of printing it at once
+        #[arg(long)]
+        speed: Option<u32>,
+    },
+
+    /// Exports the recorded focus blocks as iCalendar feed, so that they show up inside calendar
+    /// apps (the HTTP server additionally serves planned focus blocks, see `http.calendar`)
+    #[cfg(feature = "history")]
+    Calendar {
+        /// Number of days to export
+        #[arg(long, default_value_t = 30)]
+        days: u64,
+
+        /// File the feed is written to - defaults to stdout
+        #[arg(long, short)]
+        output: Option<String>,
+    },
+
+    /// Exports the completed intervals with their start, end, duration and task (e.g. to
+    /// reconcile them with a time tracker or billing tool)
+    #[cfg(feature = "history")]
+    Export {
+        /// First day to export (e.g. `2024-01-01` or `yesterday`) - defaults to the start of the
+        /// history
+        #[arg(long, value_parser = parse_day)]
+        from: Option<Day>,
+
+        /// Last day to export (e.g. `2024-01-31`)
+        #[arg(long, value_parser = parse_day, default_value = "today")]
+        to: Day,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = ExportFormat::Csv)]
+        format: ExportFormat,
+
+        /// File the export is written to - defaults to stdout
+        #[arg(long, short)]
+        output: Option<String>,
+    },
+
+    /// Prints the weekly report (focus score, intervals, break compliance and interruptions) of
+    /// the last seven days as markdown
+    #[cfg(feature = "history")]
+    Report {
+        /// Also writes the report into the report directory (see `report.directory`)
+        #[arg(long)]
+        write: bool,
+
+        /// Also sends the report via email (see `report.email` and `report.sendmail`)
+        #[arg(long)]
+        send: bool,
+    },
+
+    /// Attaches to the server without an interface and runs a shell command on each timer event
+    /// (e.g. when a break starts). Information about the event is passed via the environment
+    /// variables ZENTIME_EVENT, ZENTIME_PHASE, ZENTIME_ROUND, ZENTIME_REMAINING and ZENTIME_TASK.
+    Listen {
+        /// Shell command which is run on each event
+        #[arg(long)]
+        exec: String,
+    },
+
+    /// Controls the timer via plain-text commands (STATUS, TOGGLE, SKIP and HELP), which are
+    /// read line by line from stdin - e.g. to control a remote server via
+    /// `ssh host zentime repl`
+    Repl,
+
+    /// Prints the log of the zentime server (e.g. to debug notifications or integrations).
+    /// The server only logs what its log level permits - set `log.level = "info"` (or start it
+    /// with e.g. `RUST_LOG=info`) to see more.
+    Log {
+        /// Only shows entries of this level or above (error, warn, info, debug, trace)
+        #[arg(long, default_value_t = LevelFilter::Trace)]
+        level: LevelFilter,
+
+        /// Number of recent lines to print
+        #[arg(short = 'n', long, default_value_t = 50)]
+        lines: usize,
+
+        /// Keeps printing new log entries as they are written
+        #[arg(short, long)]
+        follow: bool,
+    },
+
+    /// Joins the shared team session of a zentime server on another machine (see `team` inside
+    /// the config) and attaches a client to it
+    #[cfg(feature = "tui")]
+    Join {
+        /// Address of the server (e.g. `192.168.0.2:7878`)
+        address: String,
+
+        /// Token of the team session, which is prompted for if omitted.
+        /// May also be a secret reference (e.g. `keyring:zentime/team`).
+        #[arg(long)]
+        token: Option<String>,
+    },
+
+    /// Attaches a small interactive client, which fits into a tmux popup (e.g.
+    /// `bind-key z display-popup -E -w 50 -h 4 zentime popup`). Keys act right away and quitting
+    /// (or closing the popup) only detaches the client.
+    #[cfg(feature = "tui")]
+    Popup,
+
+    /// Attaches to the server without an interface and keeps writing the remaining time into a
+    /// file (e.g. for an OBS text source)
+    Overlay {
+        /// File the remaining time is written to
+        #[arg(long)]
+        file: String,
+    },
+
+    /// Shows the remaining time as tray icon (StatusNotifierItem), whose menu starts, pauses,
+    /// skips and postpones the timer. Reconnects whenever the server has been restarted.
+    #[cfg(all(feature = "tray", target_os = "linux"))]
+    Tray,
+
+    /// Prints a JSON schema of all messages exchanged between clients and the server (incl.
+    /// their encoding), so that third-party clients can be written against the protocol
+    IpcSchema,
+
+    /// Walks through the most important settings (timer lengths, notifications, sound and
+    /// colors), tries out the notifications and writes a new config file
+    Init,
+
+    /// Installs an autostart entry (systemd user unit, launchd agent or scheduled task),
+    /// so that the zentime server is started on login
+    InstallAutostart {
+        /// Removes a previously installed autostart entry
+        #[arg(long)]
+        uninstall: bool,
+    },
 
     /// Interact with the zentime server
     Server {
         #[command(subcommand)]
         command: ServerCommands,
     },
+
+    /// Manage the hook commands, which are run on each interval/break end
+    Hooks {
+        #[command(subcommand)]
+        command: HooksCommands,
+    },
+
+    /// Manage secrets (e.g. tokens), which can be referenced inside the config as
+    /// `keyring:<service>/<name>`
+    Secrets {
+        #[command(subcommand)]
+        command: SecretsCommands,
+    },
+}
+
+#[derive(Subcommand)]
+enum PlanCommands {
+    /// Adds a task to the end of the plan
+    Add {
+        /// Task which is worked on
+        task: String,
+
+        /// Number of pomodoros (intervals) the task is estimated to take
+        #[arg(value_parser = clap::value_parser!(u16).range(1..))]
+        pomodoros: u16,
+    },
+
+    /// Removes all tasks from the plan
+    Clear,
+}
+
+#[derive(Subcommand)]
+enum TimerCommands {
+    /// Starts a countdown, which is selected right away and disappears once it has run out
+    New {
+        /// Name the timer is addressed by (e.g. `tea`)
+        name: String,
+
+        /// Length of the countdown (e.g. `5m` or `90s` - plain numbers are seconds)
+        #[arg(value_parser = parse_duration)]
+        duration: Duration,
+    },
+
+    /// Lists all timers with their remaining time - the selected one is marked
+    List,
+
+    /// Selects the timer, which all clients display and control (`main` selects the pomodoro
+    /// timer again)
+    Select {
+        /// Name of the timer
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum HooksCommands {
+    /// Runs each configured hook once with sample data
+    Test,
+}
+
+#[derive(Subcommand)]
+enum SecretsCommands {
+    /// Stores a secret inside the OS keyring (or an encrypted file, if no keyring is available).
+    /// The secret is prompted for or read from stdin.
+    Set {
+        /// Name of the secret (e.g. `zentime/slack`)
+        name: String,
+    },
+
+    /// Removes a stored secret
+    Remove {
+        /// Name of the secret (e.g. `zentime/slack`)
+        name: String,
+    },
 }
 
 #[derive(Subcommand)]
@@ -178,28 +622,72 @@ enum ServerCommands {
     Start {
         #[command(flatten)]
         common_args: CommonArgs,
+
+        /// Plays back the states of a JSON scenario file instead of running a timer
+        /// (e.g. to work on client interfaces without waiting for real time to pass).
+        /// See `mock-scenario.example.json`.
+        #[arg(long, value_name = "SCENARIO")]
+        mock: Option<String>,
     },
 
     /// Stop the zentime server and close all client connections
     Stop,
 
-    /// Check if the zentime server is currently running
+    /// Stop the zentime server (if it is running) and start it again,
+    /// e.g. to apply settings which can't be reloaded
+    Restart {
+        #[command(flatten)]
+        common_args: CommonArgs,
+    },
+
+    /// Check if the zentime server is running and show the state of its timer
     Status,
+
+    /// List the clients which are connected to the zentime server
+    Clients,
+
+    /// Register the zentime server as a Windows service, which is started automatically, or
+    /// as a systemd user service, which is started as soon as a client connects to its socket
+    InstallService {
+        #[command(flatten)]
+        common_args: CommonArgs,
+
+        /// Removes a previously installed service
+        #[arg(long)]
+        uninstall: bool,
+    },
+
+    /// Run the zentime server as Windows service.
+    /// This is invoked by the Windows service control manager.
+    #[command(hide = true)]
+    RunService {
+        #[command(flatten)]
+        common_args: CommonArgs,
+    },
 }
 
 fn main() {
-    env_logger::Builder::from_env(Env::default().default_filter_or("warn"))
-        .target(env_logger::Target::Stdout)
-        .init();
     let cli = Cli::parse();
 
+    // A server sets up its log once it has read its own config, while a Windows service logs
+    // to the event log instead
+    if !matches!(
+        &cli.command,
+        Some(Commands::Server {
+            command: ServerCommands::Start { .. } | ServerCommands::RunService { .. }
+        })
+    ) {
+        init_client_log(&cli.common_args.config);
+    }
+
     if let Some(Commands::Server { command }) = &cli.command {
-        match command {
-            ServerCommands::Start { common_args } => start_daemonized(common_args),
-            ServerCommands::Stop => stop(),
-            ServerCommands::Status => status(),
-        }
+        run_server_command(command);
+        return;
+    }
 
+    // The wizard writes a new config, therefore an existing one does not have to be readable
+    if let Some(Commands::Init) = &cli.command {
+        init(&cli.common_args.config);
         return;
     }
 
@@ -207,22 +695,72 @@ fn main() {
     let config: Config = get_client_config(config_path, &cli.client_config);
 
     match &cli.command {
-        Some(Commands::Server { command }) => match command {
-            ServerCommands::Start { common_args } => start_daemonized(common_args),
-            ServerCommands::Stop => stop(),
-            ServerCommands::Status => status(),
+        Some(Commands::Server { command }) => run_server_command(command),
+
+        Some(Commands::Postpone { duration }) => {
+            postpone(*duration, config.view.silent);
+        }
+
+        Some(Commands::Hold { duration, reason }) => {
+            hold(
+                *duration,
+                reason.clone().unwrap_or_default(),
+                config.view.silent,
+            );
+        }
+
+        Some(Commands::PauseUntil { time, duration }) => {
+            pause_until(
+                *time,
+                *duration,
+                WallClock::from_config(&config.clock),
+                config.view.silent,
+            );
+        }
+
+        Some(Commands::Task { task: label }) => {
+            task(label.clone(), config.view.silent);
+        }
+
+        Some(Commands::Plan { command }) => match command {
+            Some(PlanCommands::Add { task, pomodoros }) => {
+                add_to_plan(task.clone(), *pomodoros, config.view.silent)
+            }
+            Some(PlanCommands::Clear) => clear_plan(config.view.silent),
+            None => show_plan(&config.plan, WallClock::from_config(&config.clock)),
+        },
+
+        Some(Commands::Profile { name }) => match name {
+            Some(name) => switch_profile(name.clone(), config.view.silent),
+            None => list_profiles(&config),
         },
 
-        Some(Commands::Postpone) => {
-            postpone(config.view.silent);
+        Some(Commands::Timer { command }) => match command {
+            Some(TimerCommands::New { name, duration }) => {
+                new_timer(name.clone(), *duration, config.view.silent)
+            }
+            Some(TimerCommands::Select { name }) => select_timer(name.clone(), config.view.silent),
+            Some(TimerCommands::List) | None => list_timers(),
+        },
+
+        Some(Commands::Countdown { duration, label }) => {
+            countdown(*duration, label.clone(), config.view.silent)
+        }
+
+        Some(Commands::Reload) => {
+            reload(config.view.silent);
         }
 
         Some(Commands::Once) => {
             query_server_once();
         }
 
-        Some(Commands::ToggleTimer) => {
-            toggle_timer(config.view.silent);
+        Some(Commands::Status { format, template }) => {
+            print_status(*format, template.clone().or(config.view.format.clone()));
+        }
+
+        Some(Commands::ToggleTimer { reason }) => {
+            toggle_timer(reason.clone(), config.view.silent);
         }
 
         Some(Commands::Skip) => {
@@ -233,7 +771,145 @@ fn main() {
             reset_timer(config.view.silent);
         }
 
+        Some(Commands::LongBreak) => {
+            long_break(config.view.silent);
+        }
+
+        Some(Commands::IpcSchema) => ipc_schema(),
+
+        #[cfg(feature = "history")]
+        Some(Commands::Stats { days, by }) => {
+            stats(
+                &config.history,
+                &config.plan,
+                &config.goals,
+                &config.focus_contract,
+                WallClock::from_config(&config.clock),
+                *days,
+                *by,
+            );
+        }
+
+        #[cfg(feature = "history")]
+        Some(Commands::Replay { date, speed }) => {
+            replay(
+                &config.history,
+                WallClock::from_config(&config.clock),
+                *date,
+                *speed,
+            );
+        }
+
+        #[cfg(feature = "history")]
+        Some(Commands::Calendar { days, output }) => {
+            calendar(&config.history, *days, output.as_deref());
+        }
+
+        #[cfg(feature = "history")]
+        Some(Commands::Export {
+            from,
+            to,
+            format,
+            output,
+        }) => {
+            export(
+                &config.history,
+                WallClock::from_config(&config.clock),
+                *from,
+                *to,
+                *format,
+                output.as_deref(),
+            );
+        }
+
+        #[cfg(feature = "history")]
+        Some(Commands::Report { write, send }) => {
+            report(&config, *write, *send);
+        }
+
+        Some(Commands::Listen { exec }) => {
+            listen(exec);
+        }
+
+        Some(Commands::Repl) => repl(),
+
+        Some(Commands::Log {
+            level,
+            lines,
+            follow,
+        }) => {
+            log(&config.log.file(), *level, *lines, *follow);
+        }
+
+        #[cfg(feature = "tui")]
+        Some(Commands::Popup) => popup(&cli.common_args, config),
+
+        Some(Commands::Overlay { file }) => {
+            overlay(file);
+        }
+
+        #[cfg(all(feature = "tray", target_os = "linux"))]
+        Some(Commands::Tray) => tray(),
+
+        #[cfg(feature = "tui")]
+        Some(Commands::Join { address, token }) => {
+            join(address, token.as_deref(), config);
+        }
+
+        Some(Commands::Init) => init(config_path),
+
+        Some(Commands::InstallAutostart { uninstall }) => {
+            install_autostart(config_path, *uninstall);
+        }
+
+        Some(Commands::Hooks { command }) => match command {
+            HooksCommands::Test => test_hooks(&config.notifications.hooks, &config.hooks),
+        },
+
+        Some(Commands::Secrets { command }) => match command {
+            SecretsCommands::Set { name } => set_secret(name),
+            SecretsCommands::Remove { name } => remove_secret(name),
+        },
+
+        #[cfg(feature = "tui")]
         None => default_cmd(&cli.common_args, config),
+
+        #[cfg(not(feature = "tui"))]
+        None => CliError::Other(
+            "zentime has been built without its terminal interface - rebuild it with \
+            `--features tui` or use one of its subcommands (see `zentime --help`)"
+                .to_string(),
+        )
+        .exit(),
+    }
+}
+
+fn run_server_command(command: &ServerCommands) {
+    match command {
+        ServerCommands::Start { common_args, mock } => {
+            start_daemonized(common_args, mock.as_deref())
+        }
+        ServerCommands::Stop => stop(),
+        ServerCommands::Restart { common_args } => restart(common_args),
+        ServerCommands::Status => status(),
+        ServerCommands::Clients => clients(),
+        ServerCommands::InstallService {
+            common_args,
+            uninstall,
+        } => install_service(common_args, *uninstall),
+        ServerCommands::RunService { common_args } => run_service(common_args),
+    }
+}
+
+/// Sets up the client log - an invalid config is reported by the command itself, therefore its
+/// log config falls back to the defaults
+fn init_client_log(config_path: &str) {
+    let log_config = create_base_config(config_path)
+        .extract_inner::<LogConfig>("log")
+        .unwrap_or_default();
+
+    if let Err(error) = logging::init(&log_config, LogTarget::Client) {
+        eprintln!("{:#}", error);
     }
 }
 
@@ -242,5 +918,5 @@ fn get_client_config(config_path: &str, client_config: &ClientConfig) -> Config
     create_base_config(config_path)
         .merge(Serialized::defaults(client_config))
         .extract()
-        .expect("Could not create config")
+        .unwrap_or_else(|error| CliError::config(config_path, error).exit())
 }
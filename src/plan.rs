@@ -0,0 +1,156 @@
+//! Plan of the day: tasks with an estimate of pomodoros, which the server works through one
+//! after another (see `zentime plan`).
+//!
+//! The plan is kept inside a JSON file, so that it survives restarts of the server and can be
+//! read by `zentime plan` and `zentime stats` without asking the server. A plan of another day
+//! is outdated and treated like an empty plan.
+
+use std::fmt::Display;
+use std::fs::{create_dir_all, read_to_string, write};
+use std::path::Path;
+
+use anyhow::Context;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// Task of the plan
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlanItem {
+    /// Task which is worked on (e.g. "review PRs")
+    pub task: String,
+
+    /// Intervals which have been planned for the task
+    pub estimate: u16,
+
+    /// Intervals which have run out while working on the task
+    pub completed: u16,
+}
+
+impl PlanItem {
+    /// Denotes if all planned intervals have been completed
+    pub fn is_done(&self) -> bool {
+        self.completed >= self.estimate
+    }
+}
+
+/// Tasks planned for a single day, which are worked on in the order they have been added
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Plan {
+    /// Day the plan has been made for
+    pub date: NaiveDate,
+
+    /// Planned tasks
+    pub items: Vec<PlanItem>,
+}
+
+impl Plan {
+    /// Empty plan of the given day
+    pub fn new(date: NaiveDate) -> Self {
+        Self {
+            date,
+            items: vec![],
+        }
+    }
+
+    /// Reads the plan of the given day from the given file.
+    /// If the file does not exist or contains the plan of another day, the plan is empty.
+    pub fn load(path: &Path, date: NaiveDate) -> anyhow::Result<Self> {
+        if !path.exists() {
+            return Ok(Self::new(date));
+        }
+
+        let content = read_to_string(path).context(format!("Could not read {}", path.display()))?;
+        let plan: Plan = serde_json::from_str(&content)
+            .context(format!("Could not parse {}", path.display()))?;
+
+        if plan.date == date {
+            Ok(plan)
+        } else {
+            Ok(Self::new(date))
+        }
+    }
+
+    /// Writes the plan into the given file (which is created along with its parent directories)
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            create_dir_all(parent).context(format!("Could not create {}", parent.display()))?;
+        }
+
+        let content = serde_json::to_string_pretty(self).context("Could not serialize plan")?;
+
+        write(path, content).context(format!("Could not write {}", path.display()))
+    }
+
+    /// Adds a task to the end of the plan
+    pub fn add(&mut self, task: String, estimate: u16) {
+        self.items.push(PlanItem {
+            task,
+            estimate,
+            completed: 0,
+        });
+    }
+
+    /// Task which is currently worked on, i.e. the first one which is not done yet
+    pub fn current(&self) -> Option<&PlanItem> {
+        self.items.iter().find(|item| !item.is_done())
+    }
+
+    /// Attributes a completed interval to the current task and returns it
+    pub fn complete_interval(&mut self) -> Option<&PlanItem> {
+        let item = self.items.iter_mut().find(|item| !item.is_done())?;
+        item.completed += 1;
+
+        Some(item)
+    }
+
+    /// Intervals which have been completed for all planned tasks
+    pub fn completed(&self) -> u16 {
+        self.items
+            .iter()
+            .map(|item| item.completed.min(item.estimate))
+            .sum()
+    }
+
+    /// Intervals which have been planned for all tasks
+    pub fn estimate(&self) -> u16 {
+        self.items.iter().map(|item| item.estimate).sum()
+    }
+}
+
+impl Display for Plan {
+    /// e.g.
+    ///
+    /// ```text
+    /// Plan: 3/5 pomodoros
+    ///   [x] review PRs 2/2
+    ///   [>] write docs 1/3
+    /// ```
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Plan: {}/{} pomodoros",
+            self.completed(),
+            self.estimate()
+        )?;
+
+        let current = self.current();
+
+        for item in &self.items {
+            let marker = if item.is_done() {
+                "x"
+            } else if current.is_some_and(|current| std::ptr::eq(current, item)) {
+                ">"
+            } else {
+                " "
+            };
+
+            write!(
+                f,
+                "\n  [{}] {} {}/{}",
+                marker, item.task, item.completed, item.estimate
+            )?;
+        }
+
+        Ok(())
+    }
+}
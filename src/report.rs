@@ -0,0 +1,237 @@
+//! Weekly report, which summarizes the recorded sessions of a week as markdown, e.g.
+//!
+//! ```markdown
+//! # Zentime weekly report 2026-W42
+//!
+//! **Focus score: 82/100**
+//!
+//! | | |
+//! |---|---|
+//! | Intervals | 20 of 24 completed |
+//! | Focus time | 8h20m |
+//! ...
+//! ```
+
+use std::fs::{create_dir_all, write};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::time::{Duration, UNIX_EPOCH};
+
+use anyhow::{bail, Context};
+use chrono::{Datelike, NaiveDateTime};
+
+use crate::config::{HistoryConfig, ReportConfig};
+use crate::history::{
+    format_minutes, pause_reason_counts, BreakCompliance, CycleSummary, FocusScore, History,
+    SessionOutcome, SessionRecord,
+};
+use crate::wall_clock::WallClock;
+
+/// Length of the period a report covers
+const REPORT_PERIOD: chrono::Duration = chrono::Duration::days(7);
+
+/// Number of the most common pause reasons which are listed
+const MAX_LISTED_INTERRUPTIONS: usize = 5;
+
+/// Summary of the sessions, which have been recorded during the seven days before a point in time
+#[derive(Debug, Clone)]
+pub struct WeeklyReport {
+    /// Local time at which the covered period ends
+    end: NaiveDateTime,
+
+    records: Vec<SessionRecord>,
+    wall_clock: WallClock,
+}
+
+impl WeeklyReport {
+    /// Reads the sessions of the seven days before the given local time from the history
+    pub fn load(
+        config: &HistoryConfig,
+        wall_clock: WallClock,
+        end: NaiveDateTime,
+    ) -> anyhow::Result<Self> {
+        let period = wall_clock.system_time(end - REPORT_PERIOD)..wall_clock.system_time(end);
+        let records = History::open(config)?.records_between(period)?;
+
+        Ok(Self {
+            end,
+            records,
+            wall_clock,
+        })
+    }
+
+    /// ISO calendar week of the end of the report (e.g. `2026-W42`)
+    pub fn week(&self) -> String {
+        let week = self.end.date().iso_week();
+        format!("{}-W{:02}", week.year(), week.week())
+    }
+
+    /// Title of the report (e.g. `Zentime weekly report 2026-W42`)
+    pub fn title(&self) -> String {
+        format!("Zentime weekly report {}", self.week())
+    }
+
+    /// The report as markdown document
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::new();
+        let start = self.end - REPORT_PERIOD;
+
+        markdown.push_str(&format!("# {}\n\n", self.title()));
+        markdown.push_str(&format!(
+            "{} - {}\n\n",
+            start.format("%Y-%m-%d %H:%M"),
+            self.end.format("%Y-%m-%d %H:%M")
+        ));
+
+        // Postponements are recorded once more, when the break is actually taken
+        let sessions: Vec<_> = self
+            .records
+            .iter()
+            .filter(|record| record.outcome != SessionOutcome::Postponed)
+            .collect();
+
+        if sessions.is_empty() {
+            markdown.push_str("No sessions have been recorded during this week.\n");
+            return markdown;
+        }
+
+        let score = FocusScore::from_records(&self.records);
+        let compliance = BreakCompliance::from_records(&self.records);
+        let summary = CycleSummary::from_records(sessions.iter().copied());
+
+        markdown.push_str(&format!(
+            "**Focus score: {}/100**\n\n",
+            score
+                .value()
+                .map_or("-".to_string(), |value| value.to_string())
+        ));
+        markdown.push_str("| | |\n|---|---|\n");
+        markdown.push_str(&format!(
+            "| Intervals | {} of {} completed |\n",
+            score.completed_intervals, score.started_intervals
+        ));
+        markdown.push_str(&format!(
+            "| Focus time | {} |\n",
+            format_minutes(summary.focused_seconds)
+        ));
+        markdown.push_str(&format!(
+            "| Breaks | {} taken, {} skipped, {} postponed |\n",
+            compliance.taken, compliance.skipped, compliance.postponed
+        ));
+        markdown.push_str(&format!(
+            "| Break compliance | {} |\n",
+            compliance
+                .percentage()
+                .map_or("-".to_string(), |percentage| format!("{}%", percentage))
+        ));
+        markdown.push_str(&format!("| Interruptions | {} |\n", score.interruptions));
+
+        markdown.push_str("\n## Days\n\n| Day | Intervals | Focus time |\n|---|---:|---:|\n");
+
+        for (day, records) in self.days(&sessions) {
+            // Days are evaluated just like cycles
+            let summary = CycleSummary::from_records(records);
+
+            markdown.push_str(&format!(
+                "| {} | {} | {} |\n",
+                day,
+                summary.intervals,
+                format_minutes(summary.focused_seconds)
+            ));
+        }
+
+        let interruptions = pause_reason_counts(&self.records);
+
+        if !interruptions.is_empty() {
+            markdown.push_str("\n## Interruptions\n\n");
+
+            for (reason, count) in interruptions.iter().take(MAX_LISTED_INTERRUPTIONS) {
+                markdown.push_str(&format!("- {}x {}\n", count, reason));
+            }
+        }
+
+        markdown
+    }
+
+    /// Groups the given sessions by the local day they've ended on
+    fn days<'a>(&self, sessions: &[&'a SessionRecord]) -> Vec<(String, Vec<&'a SessionRecord>)> {
+        let mut days: Vec<(String, Vec<&SessionRecord>)> = vec![];
+
+        for record in sessions {
+            let day = self
+                .wall_clock
+                .local_time(UNIX_EPOCH + Duration::from_secs(record.ended_at))
+                .format("%a %Y-%m-%d")
+                .to_string();
+
+            // Records are stored in the order they have ended
+            match days.last_mut() {
+                Some((last, records)) if *last == day => records.push(record),
+                _ => days.push((day, vec![record])),
+            }
+        }
+
+        days
+    }
+
+    /// Writes the report into the configured directory (e.g. `2026-W42.md`) and returns the path
+    /// of the written file
+    pub fn write(&self, config: &ReportConfig) -> anyhow::Result<PathBuf> {
+        let directory = PathBuf::from(shellexpand::tilde(&config.directory).as_ref());
+        create_dir_all(&directory).context(format!("Could not create {}", directory.display()))?;
+
+        let path = directory.join(format!("{}.md", self.week()));
+        write(&path, self.to_markdown()).context(format!("Could not write {}", path.display()))?;
+
+        Ok(path)
+    }
+
+    /// Sends the report to the configured address via the configured sendmail command.
+    /// Nothing is sent, if no address has been configured.
+    pub fn send(&self, config: &ReportConfig) -> anyhow::Result<()> {
+        let Some(email) = &config.email else {
+            return Ok(());
+        };
+
+        let message = format!(
+            "To: {}\nSubject: {}\nContent-Type: text/plain; charset=utf-8\n\n{}",
+            email,
+            self.title(),
+            self.to_markdown()
+        );
+
+        let mut command = if cfg!(windows) {
+            let mut shell = Command::new("cmd");
+            shell.arg("/C");
+            shell
+        } else {
+            let mut shell = Command::new("sh");
+            shell.arg("-c");
+            shell
+        };
+
+        let mut child = command
+            .arg(&config.sendmail)
+            .stdin(Stdio::piped())
+            .spawn()
+            .context(format!("Could not run '{}'", config.sendmail))?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(message.as_bytes()).context(format!(
+                "Could not pass the report to '{}'",
+                config.sendmail
+            ))?;
+        }
+
+        let status = child
+            .wait()
+            .context(format!("Could not run '{}'", config.sendmail))?;
+
+        if !status.success() {
+            bail!("'{}' has failed with {}", config.sendmail, status);
+        }
+
+        Ok(())
+    }
+}
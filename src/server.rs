@@ -1,9 +1,52 @@
 //! Zentime server utilities
 
+mod action_queue;
+mod alignment;
+#[cfg(feature = "notifications")]
+mod break_countdown;
+#[cfg(feature = "notifications")]
+mod break_nudge;
+mod clients;
+mod context_capture;
+mod dnd;
+#[cfg(feature = "audio")]
+mod ducking;
+mod event_hooks;
+#[cfg(feature = "history")]
+mod goal_summary;
+mod hold;
+#[cfg(feature = "http")]
+mod http;
+mod idle;
+mod listener;
+pub mod mock;
+mod named_timers;
 pub mod notification;
+mod pause_timeout;
+mod planner;
+pub mod power;
+mod profiles;
+mod reminder;
+mod session_recorder;
+mod shutdown;
 pub mod sound;
 mod start;
+mod state_file;
 pub mod status;
+mod stopwatch;
+#[cfg(target_os = "linux")]
+pub mod systemd;
+mod team;
+mod text_protocol;
 mod timer_output;
+mod view_filter;
+#[cfg(feature = "history")]
+mod weekly_report;
+#[cfg(windows)]
+pub mod windows_service;
 
-pub use start::start;
+#[cfg(feature = "test-support")]
+pub(crate) use listener::SocketListener;
+#[cfg(feature = "test-support")]
+pub(crate) use start::listen;
+pub use start::{start, start_mock};
@@ -0,0 +1,404 @@
+//! Bounded queue which hands client actions over to the timer task
+
+use log::warn;
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt::Display;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use tokio::sync::{oneshot, Notify};
+use zentime_rs_timer::pomodoro_timer::ViewState;
+use zentime_rs_timer::pomodoro_timer_action::PomodoroTimerAction;
+
+/// Input of the timer task
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TimerInput {
+    /// Action which is applied to the timer itself
+    Action(PomodoroTimerAction),
+
+    /// Action which has been triggered by a button of an OS-notification. It only applies as long
+    /// as the timer is still in the round and phase the notification has been sent for, because
+    /// the button may be clicked long after the notification has been shown.
+    NotificationAction {
+        action: PomodoroTimerAction,
+        round: u64,
+        is_break: bool,
+    },
+
+    /// Action of a client, which waits for its own outcome (instead of reading it from the
+    /// broadcast, which might carry the outcome of another client's action)
+    Request {
+        action: PomodoroTimerAction,
+        replier: Replier,
+    },
+
+    /// Puts the pomodoro cycle on hold until the given point in time. If `welcome_back` is set,
+    /// the timer is resumed afterwards in any case and the user is welcomed back.
+    Hold {
+        reason: String,
+        until: SystemTime,
+        welcome_back: bool,
+    },
+
+    /// Records why the timer is currently paused
+    PauseReason(String),
+
+    /// Pauses a running focus interval on behalf of the server (e.g. because the user is idle).
+    /// The reason is shown by the clients, until the timer is resumed.
+    AutoPause { reason: String },
+
+    /// Resumes the timer, if it is still paused because of an [TimerInput::AutoPause]
+    AutoResume,
+
+    /// Labels the current session (or removes its label)
+    Task(Option<String>),
+
+    /// Adds a task to the plan of the day
+    AddToPlan { task: String, estimate: u16 },
+
+    /// Removes all tasks from the plan of the day
+    ClearPlan,
+
+    /// Switches to the timer profile with the given name
+    SwitchProfile(String),
+
+    /// Tags the current interval with its context (see [crate::config::ContextConfig])
+    Tags(Vec<String>),
+
+    /// Notifies about the weekly focus time goal with the given summary
+    /// (see [crate::config::GoalConfig])
+    GoalSummary(String),
+
+    /// Reads the config file again
+    ReloadConfig,
+
+    /// Starts a named countdown next to the pomodoro timer
+    NewTimer { name: String, duration: Duration },
+
+    /// Selects the timer, to which actions apply from now on
+    SelectTimer(String),
+
+    /// Starts a one-off countdown
+    Countdown {
+        duration: Duration,
+        label: Option<String>,
+    },
+
+    /// Shuts the server down, once the timer has saved its state
+    Shutdown,
+}
+
+impl TimerInput {
+    /// Whether the input is a plain timer action, which may be dropped while the queue is full.
+    /// The next state broadcast tells the clients where the timer stands anyway.
+    fn is_coalescable(&self) -> bool {
+        matches!(
+            self,
+            TimerInput::Action(_) | TimerInput::NotificationAction { .. }
+        )
+    }
+}
+
+/// Outcome of a [TimerInput::Request] - the state which is broadcast after the action has been
+/// applied, or the reason why the timer has rejected it
+pub type ActionReply = Result<ViewState, String>;
+
+/// Answers a single [TimerInput::Request]. It is shared (instead of owned) by the input, so that
+/// inputs can still be cloned and compared.
+#[derive(Clone)]
+pub struct Replier(Arc<Mutex<Option<oneshot::Sender<ActionReply>>>>);
+
+impl Replier {
+    /// Creates a replier and the receiving end of its reply
+    pub fn new() -> (Self, oneshot::Receiver<ActionReply>) {
+        let (sender, receiver) = oneshot::channel();
+
+        (Self(Arc::new(Mutex::new(Some(sender)))), receiver)
+    }
+
+    /// Sends the reply, unless it has been sent before (or the requester has gone away)
+    pub fn reply(&self, reply: ActionReply) {
+        let sender = self.0.lock().expect("Replier has been poisoned").take();
+
+        if let Some(sender) = sender {
+            sender.send(reply).ok();
+        }
+    }
+}
+
+impl PartialEq for Replier {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for Replier {}
+
+impl std::fmt::Debug for Replier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Replier")
+    }
+}
+
+/// Maximum number of timer actions waiting for the timer.
+/// This should never be reached as long as the timer keeps up with its clients. Control inputs
+/// are queued beyond it, because they are rare and must not get lost.
+const ACTION_QUEUE_CAPACITY: usize = 32;
+
+/// Maximum number of control inputs waiting for the timer, beyond which they are rejected (so
+/// that a flooding client can't grow the queue without limit). [TimerInput::Shutdown] is exempt.
+const CONTROL_QUEUE_CAPACITY: usize = 32;
+
+/// Error of a control input, which has been rejected because too many are queued up already
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueueFull(pub TimerInput);
+
+impl Display for QueueFull {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "The timer is busy - try again later (rejected {:?})",
+            self.0
+        )
+    }
+}
+
+impl Error for QueueFull {}
+
+#[derive(Debug, Default)]
+struct Shared {
+    actions: Mutex<VecDeque<TimerInput>>,
+    notify: Notify,
+}
+
+/// Creates a bounded action queue and returns its sending and receiving end
+pub fn action_queue() -> (ActionSender, ActionReceiver) {
+    let shared = Arc::new(Shared::default());
+
+    (
+        ActionSender {
+            shared: shared.clone(),
+        },
+        ActionReceiver { shared },
+    )
+}
+
+/// Sending end of the action queue, which can be cloned for each client connection
+#[derive(Debug, Clone)]
+pub struct ActionSender {
+    shared: Arc<Shared>,
+}
+
+impl ActionSender {
+    /// Queues up an action for the timer
+    pub fn send(&self, action: PomodoroTimerAction) {
+        self.send_input(TimerInput::Action(action));
+    }
+
+    /// Queues up an input for the timer task and logs it, if it has been rejected (see
+    /// [Self::try_send_input()])
+    pub fn send_input(&self, input: TimerInput) {
+        if let Err(error) = self.try_send_input(input) {
+            warn!("{}", error);
+        }
+    }
+
+    /// Queues up an input for the timer task.
+    ///
+    /// To keep the queue bounded, inputs are coalesced on the way in:
+    /// * consecutive [PomodoroTimerAction::PlayPause]s cancel each other out - a
+    ///   [PomodoroTimerAction::None] is queued instead, so that the sender still receives a state
+    /// * a [PomodoroTimerAction::None] (which just requests a state broadcast) and
+    ///   [TimerInput::Shutdown] are only queued once
+    /// * if the queue is full nonetheless, the oldest (most stale) timer action is dropped.
+    ///   Control inputs (e.g. [TimerInput::Hold]) are never dropped, but rejected once
+    ///   [CONTROL_QUEUE_CAPACITY] of them are queued up.
+    pub fn try_send_input(&self, input: TimerInput) -> Result<(), QueueFull> {
+        {
+            let mut actions = self
+                .shared
+                .actions
+                .lock()
+                .expect("Action queue has been poisoned");
+
+            let is_limited = !input.is_coalescable() && input != TimerInput::Shutdown;
+            if is_limited
+                && actions
+                    .iter()
+                    .filter(|queued| !queued.is_coalescable())
+                    .count()
+                    >= CONTROL_QUEUE_CAPACITY
+            {
+                return Err(QueueFull(input));
+            }
+
+            match input {
+                TimerInput::Action(PomodoroTimerAction::PlayPause)
+                    if actions.back() == Some(&input) =>
+                {
+                    actions.pop_back();
+
+                    let acknowledgement = TimerInput::Action(PomodoroTimerAction::None);
+                    if !actions.contains(&acknowledgement) {
+                        actions.push_back(acknowledgement);
+                    }
+                }
+                TimerInput::Action(PomodoroTimerAction::None) | TimerInput::Shutdown
+                    if actions.contains(&input) => {}
+                _ if actions.len() >= ACTION_QUEUE_CAPACITY => {
+                    match actions.iter().position(TimerInput::is_coalescable) {
+                        Some(index) => {
+                            let dropped = actions.remove(index);
+                            warn!("Timer action queue is full - dropping {:?}", dropped);
+                            actions.push_back(input);
+                        }
+                        None if input.is_coalescable() => {
+                            warn!("Timer action queue is full - dropping {:?}", input);
+                        }
+                        None => actions.push_back(input),
+                    }
+                }
+                _ => actions.push_back(input),
+            }
+        }
+
+        self.shared.notify.notify_one();
+
+        Ok(())
+    }
+}
+
+/// Receiving end of the action queue, which is owned by the timer task
+#[derive(Debug)]
+pub struct ActionReceiver {
+    shared: Arc<Shared>,
+}
+
+impl ActionReceiver {
+    /// Waits until at least one input is queued up and returns all queued inputs in order
+    pub async fn recv(&self) -> Vec<TimerInput> {
+        loop {
+            let actions = self.drain();
+
+            if !actions.is_empty() {
+                return actions;
+            }
+
+            self.shared.notify.notified().await;
+        }
+    }
+
+    /// Returns all inputs which are currently queued up, without waiting for new ones
+    pub fn drain(&self) -> Vec<TimerInput> {
+        self.shared
+            .actions
+            .lock()
+            .expect("Action queue has been poisoned")
+            .drain(..)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zentime_rs_timer::ticking_pomodoro_timer::TickingPomodoroTimer;
+
+    fn queue_is_full(sender: &ActionSender) {
+        for _ in 0..ACTION_QUEUE_CAPACITY / 2 {
+            sender.send(PomodoroTimerAction::Skip);
+            sender.send(PomodoroTimerAction::ResetTimer);
+        }
+    }
+
+    #[test]
+    fn acknowledges_cancelled_play_pauses() {
+        let (sender, receiver) = action_queue();
+
+        sender.send(PomodoroTimerAction::PlayPause);
+        sender.send(PomodoroTimerAction::PlayPause);
+        sender.send(PomodoroTimerAction::PlayPause);
+        sender.send(PomodoroTimerAction::PlayPause);
+
+        assert_eq!(
+            receiver.drain(),
+            vec![TimerInput::Action(PomodoroTimerAction::None)]
+        );
+    }
+
+    #[test]
+    fn drops_the_oldest_timer_action_while_full() {
+        let (sender, receiver) = action_queue();
+
+        sender.send_input(TimerInput::ReloadConfig);
+        queue_is_full(&sender);
+        sender.send(PomodoroTimerAction::PlayPause);
+
+        let actions = receiver.drain();
+        assert_eq!(actions.len(), ACTION_QUEUE_CAPACITY);
+        assert_eq!(actions[0], TimerInput::ReloadConfig);
+        assert_eq!(actions[1], TimerInput::Action(PomodoroTimerAction::Skip));
+        assert_eq!(
+            actions.last(),
+            Some(&TimerInput::Action(PomodoroTimerAction::PlayPause))
+        );
+    }
+
+    #[test]
+    fn never_drops_control_inputs() {
+        let (sender, receiver) = action_queue();
+
+        for index in 0..ACTION_QUEUE_CAPACITY {
+            sender.send_input(TimerInput::Task(Some(index.to_string())));
+        }
+        sender.send(PomodoroTimerAction::Skip);
+        sender.send_input(TimerInput::Shutdown);
+
+        let actions = receiver.drain();
+        assert_eq!(actions.len(), ACTION_QUEUE_CAPACITY + 1);
+        assert_eq!(actions[0], TimerInput::Task(Some("0".to_string())));
+        assert_eq!(actions.last(), Some(&TimerInput::Shutdown));
+        assert!(!actions.contains(&TimerInput::Action(PomodoroTimerAction::Skip)));
+    }
+
+    #[test]
+    fn rejects_control_inputs_beyond_their_capacity() {
+        let (sender, receiver) = action_queue();
+
+        let rejected = (0..CONTROL_QUEUE_CAPACITY * 4)
+            .filter_map(|index| {
+                sender
+                    .try_send_input(TimerInput::Task(Some(index.to_string())))
+                    .err()
+            })
+            .count();
+        sender.send(PomodoroTimerAction::Skip);
+        sender.send_input(TimerInput::Shutdown);
+        sender.send_input(TimerInput::Shutdown);
+
+        assert_eq!(rejected, CONTROL_QUEUE_CAPACITY * 3);
+        assert_eq!(
+            sender.try_send_input(TimerInput::ReloadConfig),
+            Err(QueueFull(TimerInput::ReloadConfig))
+        );
+
+        let actions = receiver.drain();
+        assert_eq!(actions.len(), CONTROL_QUEUE_CAPACITY + 1);
+        assert_eq!(actions.last(), Some(&TimerInput::Shutdown));
+
+        // Once the timer has caught up, control inputs are accepted again
+        assert!(sender.try_send_input(TimerInput::ReloadConfig).is_ok());
+    }
+
+    #[test]
+    fn answers_each_request_once() {
+        let (replier, mut reply) = Replier::new();
+
+        replier.reply(Err("Rejected".to_string()));
+        let state = TickingPomodoroTimer::new(Default::default()).view_state();
+        replier.clone().reply(Ok(state));
+
+        assert_eq!(reply.try_recv(), Ok(Err("Rejected".to_string())));
+        assert_ne!(replier, Replier::new().0);
+    }
+}
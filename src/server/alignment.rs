@@ -0,0 +1,86 @@
+//! Aligns the start of intervals to wall-clock boundaries (see [AlignmentConfig]), e.g. so that
+//! intervals always start at :00 or :30.
+//!
+//! Once an interval is started (by the user or automatically after a break), the cycle is put on
+//! hold until the next boundary has been reached. Like any other hold, it can be cut short by
+//! pressing play.
+
+use std::time::{Duration, SystemTime};
+
+use chrono::Timelike;
+use zentime_rs_timer::clock;
+use zentime_rs_timer::sequence::Schedule;
+use zentime_rs_timer::ticking_pomodoro_timer::{PomodoroPhase, TickingPomodoroTimer};
+
+use crate::config::AlignmentConfig;
+use crate::wall_clock::WallClock;
+
+/// Intervals which have been running for longer than this have been resumed after a pause
+/// instead of being started
+const START_TOLERANCE: Duration = Duration::from_secs(1);
+
+/// Keeps track of the intervals which have already been aligned
+#[derive(Debug, Clone)]
+pub struct IntervalAlignment {
+    boundary: Option<Duration>,
+    wall_clock: WallClock,
+
+    /// Round of the interval which has last been aligned, so that it is not aligned again once
+    /// the hold is over
+    aligned_round: Option<u64>,
+}
+
+impl IntervalAlignment {
+    /// Creates a new alignment, which does nothing if it has been disabled
+    pub fn new(config: &AlignmentConfig, wall_clock: WallClock) -> Self {
+        Self {
+            boundary: config.boundary(),
+            wall_clock,
+            aligned_round: None,
+        }
+    }
+
+    /// Checks whether an interval has just been started.
+    /// Returns the reason and the end of the hold, which delays the interval until the next
+    /// boundary - [None] if the interval may run right away.
+    pub fn tick(
+        &mut self,
+        timer: &TickingPomodoroTimer<Schedule>,
+        is_on_hold: bool,
+    ) -> Option<(String, SystemTime)> {
+        let boundary = self.boundary?;
+
+        if timer.phase() != PomodoroPhase::Interval {
+            self.aligned_round = None;
+            return None;
+        }
+
+        let round = timer.shared_state().round;
+        let has_started = !timer.timer().is_paused()
+            && !is_on_hold
+            && timer.timer().elapsed_time() < START_TOLERANCE;
+
+        if !has_started || self.aligned_round == Some(round) {
+            return None;
+        }
+
+        self.aligned_round = Some(round);
+
+        let now = clock::system_now();
+        let time = self.wall_clock.local_time(now).time();
+        let since_midnight = u64::from(time.num_seconds_from_midnight()) * 1000
+            + u64::from(time.nanosecond() / 1_000_000).min(999);
+        let boundary_millis = boundary.as_millis() as u64;
+        let wait = boundary_millis - since_midnight % boundary_millis;
+
+        // The interval has been started right at the boundary
+        if wait == boundary_millis {
+            return None;
+        }
+
+        let until = now + Duration::from_millis(wait);
+        let starts_at = self.wall_clock.local_time(until).format("%H:%M");
+
+        Some((format!("waiting for {}", starts_at), until))
+    }
+}
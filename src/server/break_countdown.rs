@@ -0,0 +1,163 @@
+//! Persistent OS-notification, which shows the remaining time of the current break, so that
+//! the countdown stays visible inside the notification shade without a terminal.
+//!
+//! Instead of sending a new notification on every update, a single notification is replaced
+//! (this requires a notification daemon implementing the freedesktop specification). On other
+//! platforms the notification is only shown once at the start of each break.
+
+use std::sync::Arc;
+
+use anyhow::Context;
+use log::{info, warn};
+use notify_rust::{Notification as OsNotification, Timeout};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::broadcast::Sender as BroadcastSender;
+use tokio::task::spawn_blocking;
+use zentime_rs_timer::pomodoro_timer::ViewState;
+use zentime_rs_timer::util::{seconds_to_time, time_to_seconds};
+
+use super::timer_output::TimerOutputAction;
+
+/// The remaining time inside the notification is updated in steps of this many seconds
+const UPDATE_STEP: u64 = 30;
+
+/// Handle of the shown notification, which is needed to replace or close it
+#[cfg(all(unix, not(target_os = "macos")))]
+type CountdownHandle = notify_rust::NotificationHandle;
+
+/// Notifications can't be replaced on this platform, therefore there is nothing to keep
+#[cfg(not(all(unix, not(target_os = "macos"))))]
+type CountdownHandle = ();
+
+/// Keeps the break countdown notification in sync with the timer, until the server shuts down
+pub async fn run(timer_output_sender: Arc<BroadcastSender<TimerOutputAction>>) {
+    let mut timer_output_receiver = timer_output_sender.subscribe();
+    let mut countdown = BreakCountdown::default();
+
+    loop {
+        match timer_output_receiver.recv().await {
+            Ok(TimerOutputAction::Timer(state)) => {
+                if let Err(error) = countdown.update(&state).await {
+                    warn!("Could not update break countdown: {:#}", error);
+                }
+            }
+            // The next state brings the countdown up to date
+            Ok(_) | Err(RecvError::Lagged(_)) => continue,
+            Err(RecvError::Closed) => break,
+        }
+    }
+
+    countdown.close().await;
+}
+
+/// Notification which counts down the remaining time of a break
+#[derive(Default)]
+struct BreakCountdown {
+    handle: Option<CountdownHandle>,
+    body: Option<String>,
+}
+
+impl BreakCountdown {
+    /// Shows, replaces or closes the notification depending on the given state.
+    /// Nothing happens, if the displayed text would not change.
+    async fn update(&mut self, state: &ViewState) -> anyhow::Result<()> {
+        let body = countdown_text(state);
+
+        if body == self.body {
+            return Ok(());
+        }
+
+        match &body {
+            Some(body) => self.show(body.clone()).await?,
+            None => self.close().await,
+        }
+
+        self.body = body;
+
+        Ok(())
+    }
+
+    /// Shows the notification or replaces the text of the shown one
+    #[cfg(all(unix, not(target_os = "macos")))]
+    async fn show(&mut self, body: String) -> anyhow::Result<()> {
+        let handle = self.handle.take();
+
+        // Talking to the notification daemon blocks
+        let handle = spawn_blocking(move || match handle {
+            Some(mut handle) => {
+                handle.body(&body);
+                handle.update().map(|()| handle)
+            }
+            None => countdown_notification(&body).show(),
+        })
+        .await
+        .context("Notification task failed")?
+        .context("Could not show notification")?;
+
+        self.handle = Some(handle);
+
+        Ok(())
+    }
+
+    /// Shows the notification, unless it has already been shown during the current break
+    #[cfg(not(all(unix, not(target_os = "macos"))))]
+    async fn show(&mut self, body: String) -> anyhow::Result<()> {
+        if self.handle.is_some() {
+            return Ok(());
+        }
+
+        spawn_blocking(move || countdown_notification(&body).show().map(|_| ()))
+            .await
+            .context("Notification task failed")?
+            .context("Could not show notification")?;
+
+        self.handle = Some(());
+
+        Ok(())
+    }
+
+    /// Removes the notification (e.g. because the break has ended)
+    #[cfg(all(unix, not(target_os = "macos")))]
+    async fn close(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            info!("Closing break countdown");
+            spawn_blocking(move || handle.close()).await.ok();
+        }
+    }
+
+    /// Forgets the notification, so that it is shown again at the start of the next break
+    #[cfg(not(all(unix, not(target_os = "macos"))))]
+    async fn close(&mut self) {
+        self.handle = None;
+    }
+}
+
+/// Notification which stays visible until it is closed
+fn countdown_notification(body: &str) -> OsNotification {
+    let mut notification = OsNotification::new();
+
+    notification
+        .summary("\u{25EF} zentime: Break")
+        .body(body)
+        .timeout(Timeout::Never);
+
+    notification
+}
+
+/// Text of the countdown notification - [None] if no break is running.
+/// The remaining time is rounded up to [UPDATE_STEP] seconds, so that the notification is
+/// not replaced on every tick.
+fn countdown_text(state: &ViewState) -> Option<String> {
+    if !state.is_break || state.hold.is_some() {
+        return None;
+    }
+
+    let seconds = time_to_seconds(&state.time)?;
+    let remaining = seconds_to_time(seconds.div_ceil(UPDATE_STEP) * UPDATE_STEP);
+
+    if state.is_paused {
+        Some(format!("{} remaining (paused)", remaining))
+    } else {
+        Some(format!("About {} remaining", remaining))
+    }
+}
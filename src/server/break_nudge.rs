@@ -0,0 +1,193 @@
+//! Break enforcement (see [BreakNudgeConfig]): nudges towards actually taking the break by
+//! raising its notification again and again, while the break keeps running (or waiting to be
+//! started).
+//!
+//! Each nudge escalates the previous one: from the second nudge on the screen is dimmed briefly
+//! (a little more each time) and from the third nudge on the notification is marked as critical,
+//! which keeps it on screen on most Linux desktops. Nudging stops as soon as the break is over,
+//! has been postponed or the cycle has been put on hold.
+
+use std::future::pending;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{bail, Context};
+use log::{info, warn};
+use notify_rust::{Notification as OsNotification, Timeout};
+use tokio::process::Command;
+use tokio::select;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::broadcast::Sender as BroadcastSender;
+use tokio::task::spawn_blocking;
+use tokio::time::{sleep, sleep_until, Instant};
+use zentime_rs_timer::util::seconds_to_time;
+
+use super::timer_output::TimerOutputAction;
+use crate::config::BreakNudgeConfig;
+
+/// Sets the gamma of all X11 screens
+#[cfg(target_os = "linux")]
+const DEFAULT_DIM_COMMAND: Option<&str> = Some("xgamma -quiet -gamma {level}");
+
+/// Sets the gamma of all X11 screens
+#[cfg(not(target_os = "linux"))]
+const DEFAULT_DIM_COMMAND: Option<&str> = None;
+
+/// Brightness the screen is dimmed to by the first dimming nudge
+const FIRST_DIM_LEVEL: f32 = 0.85;
+
+/// Each further nudge dims the screen by this much more
+const DIM_STEP: f32 = 0.15;
+
+/// The screen is never dimmed below this brightness, so that it stays readable
+const MIN_DIM_LEVEL: f32 = 0.4;
+
+/// Nudge from which on the notification is marked as critical
+#[cfg(all(unix, not(target_os = "macos")))]
+const CRITICAL_NUDGE: u32 = 3;
+
+/// Nudges while breaks are ignored, until the server shuts down
+pub async fn run(
+    config: BreakNudgeConfig,
+    timer_output_sender: Arc<BroadcastSender<TimerOutputAction>>,
+) {
+    let mut timer_output_receiver = timer_output_sender.subscribe();
+    let interval = Duration::from_secs(config.interval.max(1));
+    let dim_command = dim_command(&config);
+    let mut next_nudge: Option<Instant> = None;
+    let mut nudges = 0;
+
+    if config.dim && dim_command.is_none() {
+        warn!("The screen can't be dimmed on this platform - set `break_nudge.dim_command`");
+    }
+
+    loop {
+        let nudge_is_due = async {
+            match next_nudge {
+                Some(deadline) => sleep_until(deadline).await,
+                None => pending().await,
+            }
+        };
+
+        select! {
+            value = timer_output_receiver.recv() => match value {
+                Ok(TimerOutputAction::Timer(state)) => {
+                    let is_break = state.is_break && state.hold.is_none();
+
+                    if is_break && next_nudge.is_none() {
+                        nudges = 0;
+                        next_nudge = Some(Instant::now() + interval);
+                    } else if !is_break {
+                        next_nudge = None;
+                    }
+                }
+                Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            },
+            () = nudge_is_due => {
+                nudges += 1;
+                next_nudge = Some(Instant::now() + interval);
+
+                let ignored_for = seconds_to_time(u64::from(nudges) * interval.as_secs());
+                info!("Break has been ignored for {} - nudging", ignored_for);
+
+                if let Err(error) = show_notification(nudges, ignored_for).await {
+                    warn!("Could not show break nudge: {:#}", error);
+                }
+
+                let dimming = dim_command.filter(|_| config.dim).zip(dim_level(nudges));
+
+                if let Some((command, level)) = dimming {
+                    tokio::spawn(dim_briefly(
+                        command.to_string(),
+                        level,
+                        Duration::from_secs(config.dim_duration),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+/// Shell command which sets the brightness of the screen
+fn dim_command(config: &BreakNudgeConfig) -> Option<&str> {
+    config.dim_command.as_deref().or(DEFAULT_DIM_COMMAND)
+}
+
+/// Brightness the screen is dimmed to by the given nudge - [None] if the screen is not dimmed
+fn dim_level(nudge: u32) -> Option<f32> {
+    let step = nudge.checked_sub(2)?;
+
+    Some((FIRST_DIM_LEVEL - DIM_STEP * step as f32).max(MIN_DIM_LEVEL))
+}
+
+/// Raises the break notification again
+#[cfg_attr(not(all(unix, not(target_os = "macos"))), allow(unused_variables))]
+async fn show_notification(nudge: u32, ignored_for: String) -> anyhow::Result<()> {
+    let mut notification = OsNotification::new();
+
+    notification
+        .summary("\u{25EF} zentime: Take your break")
+        .body(&format!(
+            "The break has been ignored for {} - step away from the screen",
+            ignored_for
+        ))
+        .timeout(Timeout::Default);
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    if nudge >= CRITICAL_NUDGE {
+        notification.urgency(notify_rust::Urgency::Critical);
+    }
+
+    // Talking to the notification daemon blocks
+    spawn_blocking(move || notification.show().map(|_| ()))
+        .await
+        .context("Notification task failed")?
+        .context("Could not show notification")
+}
+
+/// Dims the screen to the given level and restores it after the given duration
+async fn dim_briefly(command: String, level: f32, duration: Duration) {
+    if let Err(error) = set_brightness(&command, level).await {
+        warn!("Could not dim the screen: {:#}", error);
+        return;
+    }
+
+    sleep(duration).await;
+
+    if let Err(error) = set_brightness(&command, 1.0).await {
+        warn!("Could not restore the screen brightness: {:#}", error);
+    }
+}
+
+/// Runs the dim command with the given brightness level
+async fn set_brightness(command: &str, level: f32) -> anyhow::Result<()> {
+    let command = command
+        .replace("{level}", &format!("{:.2}", level))
+        .replace("{percent}", &format!("{:.0}", level * 100.0));
+
+    let mut shell = if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+
+    let status = shell
+        .arg(&command)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .status()
+        .await
+        .context(format!("Could not run '{}'", command))?;
+
+    if !status.success() {
+        bail!("'{}' exited with {}", command, status);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,124 @@
+//! Registry of the clients which are currently connected to the server (see
+//! `zentime server clients`).
+//!
+//! Each connection registers itself and is removed again as soon as its [ClientHandle] is
+//! dropped - regardless of whether the client has detached, the connection broke or the client
+//! has been reaped, because it stopped sending heartbeats.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, SystemTime};
+
+use crate::config::ConnectionConfig;
+use crate::history::unix_timestamp;
+use crate::ipc::{ClientInfo, Role, Transport};
+
+#[derive(Debug, Default)]
+struct Registry {
+    next_id: u64,
+    clients: BTreeMap<u64, ClientInfo>,
+}
+
+/// Clients which are connected to the server
+#[derive(Debug, Clone, Default)]
+pub struct Clients {
+    registry: Arc<Mutex<Registry>>,
+    heartbeat_timeout: Option<Duration>,
+}
+
+impl Clients {
+    /// Creates an empty registry, whose clients time out as configured
+    pub fn new(config: &ConnectionConfig) -> Self {
+        Self {
+            registry: Arc::default(),
+            heartbeat_timeout: config.heartbeat_timeout(),
+        }
+    }
+
+    /// Registers a new connection, which stays listed until the returned handle is dropped
+    pub fn register(
+        &self,
+        role: Role,
+        transport: Transport,
+        pid: Option<u32>,
+        address: Option<String>,
+    ) -> ClientHandle {
+        let now = unix_timestamp(SystemTime::now());
+        let mut registry = self.lock();
+
+        registry.next_id += 1;
+        let id = registry.next_id;
+
+        registry.clients.insert(
+            id,
+            ClientInfo {
+                id,
+                role,
+                transport,
+                pid,
+                address,
+                connected_at: now,
+                last_seen: now,
+            },
+        );
+
+        ClientHandle {
+            id,
+            role,
+            clients: self.clone(),
+        }
+    }
+
+    /// All connected clients in the order they have connected
+    pub fn list(&self) -> Vec<ClientInfo> {
+        self.lock().clients.values().cloned().collect()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Registry> {
+        self.registry
+            .lock()
+            .expect("Client registry has been poisoned")
+    }
+}
+
+/// Registration of a single connection
+#[derive(Debug)]
+pub struct ClientHandle {
+    id: u64,
+    role: Role,
+    clients: Clients,
+}
+
+impl ClientHandle {
+    /// Role of the client
+    pub fn role(&self) -> Role {
+        self.role
+    }
+
+    /// Time after which the client is considered dead, unless it sends a message -
+    /// [None] if clients never time out
+    pub fn heartbeat_timeout(&self) -> Option<Duration> {
+        self.clients.heartbeat_timeout
+    }
+
+    /// Records that the client has just sent a message
+    pub fn touch(&self) {
+        if let Some(client) = self.clients.lock().clients.get_mut(&self.id) {
+            client.last_seen = unix_timestamp(SystemTime::now());
+        }
+    }
+
+    /// All other connected clients - the client asking for them is left out, because it is
+    /// usually just a short-lived `zentime server clients`
+    pub fn list(&self) -> Vec<ClientInfo> {
+        let mut clients = self.clients.list();
+        clients.retain(|client| client.id != self.id);
+        clients
+    }
+}
+
+impl Drop for ClientHandle {
+    fn drop(&mut self) {
+        self.clients.lock().clients.remove(&self.id);
+    }
+}
@@ -0,0 +1,173 @@
+//! Captures the context of an interval (see [ContextConfig]) as soon as it starts running and
+//! hands it over to the timer, which records it together with the interval.
+//!
+//! Nothing is sent anywhere - the tags only end up inside the local history.
+
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Duration;
+
+use log::{info, warn};
+use tokio::process::Command;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::broadcast::Sender as BroadcastSender;
+use tokio::time::timeout;
+
+use super::action_queue::{ActionSender, TimerInput};
+use super::timer_output::TimerOutputAction;
+use crate::client::timer_event::Phase;
+use crate::config::{ContextConfig, ContextSource};
+
+/// Time a command has to print the context
+const CAPTURE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Prints the title of the active window
+#[cfg(target_os = "macos")]
+const DEFAULT_WINDOW_COMMAND: Option<&str> = Some(
+    "osascript -e 'tell application \"System Events\" to get name of first application process whose frontmost is true'",
+);
+
+/// Prints the title of the active window
+#[cfg(all(unix, not(target_os = "macos")))]
+const DEFAULT_WINDOW_COMMAND: Option<&str> = Some("xdotool getactivewindow getwindowname");
+
+/// Prints the title of the active window
+#[cfg(not(unix))]
+const DEFAULT_WINDOW_COMMAND: Option<&str> = None;
+
+/// Captures the context whenever a focus interval starts (or resumes) running, until the server
+/// shuts down. The timer only keeps the context which has been captured first.
+pub async fn run(
+    config: ContextConfig,
+    timer_input_sender: ActionSender,
+    timer_output_sender: Arc<BroadcastSender<TimerOutputAction>>,
+) {
+    let mut timer_output_receiver = timer_output_sender.subscribe();
+    let mut was_focusing = false;
+
+    if config.capture.contains(&ContextSource::Window) && window_command(&config).is_none() {
+        warn!(
+            "The active window can't be determined on this platform - set `context.window_command`"
+        );
+    }
+
+    loop {
+        match timer_output_receiver.recv().await {
+            Ok(TimerOutputAction::Timer(state)) => {
+                let is_focusing = Phase::of(&state) == Phase::Focus && !state.is_paused;
+
+                if is_focusing && !was_focusing {
+                    let tags = capture(&config).await;
+
+                    if !tags.is_empty() {
+                        timer_input_sender.send_input(TimerInput::Tags(tags));
+                    }
+                }
+
+                was_focusing = is_focusing;
+            }
+            Ok(_) | Err(RecvError::Lagged(_)) => continue,
+            Err(RecvError::Closed) => break,
+        }
+    }
+}
+
+/// Captures all configured sources - sources which are not available (e.g. no git repository)
+/// are left out
+async fn capture(config: &ContextConfig) -> Vec<String> {
+    let mut tags = vec![];
+
+    for source in &config.capture {
+        let tag = match source {
+            ContextSource::Window => window_title(config)
+                .await
+                .map(|title| format!("window:{}", title)),
+            ContextSource::Git => git_repository(config)
+                .await
+                .map(|name| format!("git:{}", name)),
+        };
+
+        tags.extend(tag);
+    }
+
+    tags
+}
+
+/// Shell command which prints the title of the active window
+fn window_command(config: &ContextConfig) -> Option<&str> {
+    config.window_command.as_deref().or(DEFAULT_WINDOW_COMMAND)
+}
+
+/// Title of the active window
+async fn window_title(config: &ContextConfig) -> Option<String> {
+    let command = window_command(config)?;
+
+    let mut shell = if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+
+    shell.arg(command);
+    output(shell).await
+}
+
+/// Name of the git repository, which contains the configured directory
+async fn git_repository(config: &ContextConfig) -> Option<String> {
+    let mut git = Command::new("git");
+
+    if let Some(directory) = &config.git_directory {
+        git.arg("-C").arg(shellexpand::tilde(directory).as_ref());
+    }
+
+    git.args(["rev-parse", "--show-toplevel"]);
+
+    let root = output(git).await?;
+
+    Path::new(&root)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+}
+
+/// Runs the command and returns the first line it has printed (if it has been successful)
+async fn output(mut command: Command) -> Option<String> {
+    let child = command
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .output();
+
+    let output = match timeout(CAPTURE_TIMEOUT, child).await {
+        Ok(Ok(output)) if output.status.success() => output,
+        Ok(Ok(output)) => {
+            info!(
+                "Could not capture context: {:?} exited with {}",
+                command, output.status
+            );
+            return None;
+        }
+        Ok(Err(error)) => {
+            warn!("Could not capture context via {:?}: {}", command, error);
+            return None;
+        }
+        Err(_) => {
+            warn!(
+                "Could not capture context: {:?} did not finish in time",
+                command
+            );
+            return None;
+        }
+    };
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .next()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+}
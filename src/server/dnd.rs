@@ -0,0 +1,158 @@
+//! Do-not-disturb during focus intervals (see [DndConfig]): as soon as a focus interval is
+//! running, the notifications of other applications are held back by the OS. Once the interval
+//! ends or is paused, do-not-disturb is set back to what it has been before.
+//!
+//! Each way of switching do-not-disturb is implemented as a [DoNotDisturb] backend.
+
+mod command;
+mod dunst;
+mod macos;
+
+use std::fmt::Debug;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{bail, Context};
+use async_trait::async_trait;
+use log::{info, warn};
+use tokio::process::Command;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::broadcast::Sender as BroadcastSender;
+use tokio::time::timeout;
+use zentime_rs_timer::pomodoro_timer::ViewState;
+
+use super::timer_output::TimerOutputAction;
+use crate::client::timer_event::Phase;
+use crate::config::{DndBackend, DndConfig};
+use command::CommandDnd;
+use dunst::DunstDnd;
+use macos::MacosDnd;
+
+/// Time a command has to switch do-not-disturb
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Way of switching the do-not-disturb mode of the OS
+#[async_trait]
+trait DoNotDisturb: Debug + Send + Sync {
+    /// Name of the backend, which is used in log messages
+    fn name(&self) -> &'static str;
+
+    /// Denotes if do-not-disturb is enabled right now, so that it can be restored afterwards
+    async fn is_enabled(&self) -> anyhow::Result<bool>;
+
+    /// Enables or disables do-not-disturb
+    async fn set_enabled(&self, enabled: bool) -> anyhow::Result<()>;
+}
+
+/// Enables do-not-disturb while a focus interval is running, until the server shuts down
+pub async fn run(config: DndConfig, timer_output_sender: Arc<BroadcastSender<TimerOutputAction>>) {
+    let Some(backend) = backend(config) else {
+        warn!(
+            "Do-not-disturb can't be switched on this platform - set `dnd.backend = \"command\"` \
+            together with `dnd.enable_command` and `dnd.disable_command`"
+        );
+        return;
+    };
+
+    info!("Switching do-not-disturb via {}", backend.name());
+
+    let mut timer_output_receiver = timer_output_sender.subscribe();
+
+    // Whether do-not-disturb has been enabled before the current interval - [None] while no
+    // focus interval is running
+    let mut was_enabled: Option<bool> = None;
+
+    loop {
+        match timer_output_receiver.recv().await {
+            Ok(TimerOutputAction::Timer(state)) => match (is_focusing(&state), was_enabled) {
+                (true, None) => was_enabled = Some(enable(backend.as_ref()).await),
+                (false, Some(was_enabled_before)) => {
+                    restore(backend.as_ref(), was_enabled_before).await;
+                    was_enabled = None;
+                }
+                _ => {}
+            },
+            Ok(_) | Err(RecvError::Lagged(_)) => continue,
+            Err(RecvError::Closed) => break,
+        }
+    }
+
+    if let Some(was_enabled_before) = was_enabled {
+        restore(backend.as_ref(), was_enabled_before).await;
+    }
+}
+
+/// Backend of the config - [None] if there is none for this platform
+fn backend(config: DndConfig) -> Option<Box<dyn DoNotDisturb>> {
+    match config.backend {
+        DndBackend::Auto if cfg!(target_os = "macos") => Some(Box::new(MacosDnd)),
+        DndBackend::Auto if cfg!(all(unix, not(target_os = "macos"))) => Some(Box::new(DunstDnd)),
+        DndBackend::Auto => None,
+        DndBackend::Dunst => Some(Box::new(DunstDnd)),
+        DndBackend::Macos => Some(Box::new(MacosDnd)),
+        DndBackend::Command => Some(Box::new(CommandDnd::new(
+            config.enable_command,
+            config.disable_command,
+        ))),
+    }
+}
+
+/// Denotes if a focus interval is running right now
+fn is_focusing(state: &ViewState) -> bool {
+    Phase::of(state) == Phase::Focus && !state.is_paused && state.hold.is_none()
+}
+
+/// Enables do-not-disturb and returns whether it has been enabled before
+async fn enable(backend: &dyn DoNotDisturb) -> bool {
+    let was_enabled = match backend.is_enabled().await {
+        Ok(was_enabled) => was_enabled,
+        Err(error) => {
+            warn!("Could not determine do-not-disturb state: {:#}", error);
+            false
+        }
+    };
+
+    if was_enabled {
+        return true;
+    }
+
+    match backend.set_enabled(true).await {
+        Ok(()) => info!("Do-not-disturb has been enabled"),
+        Err(error) => warn!("Could not enable do-not-disturb: {:#}", error),
+    }
+
+    false
+}
+
+/// Disables do-not-disturb again - unless it has been enabled before the interval
+async fn restore(backend: &dyn DoNotDisturb, was_enabled: bool) {
+    if was_enabled {
+        return;
+    }
+
+    match backend.set_enabled(false).await {
+        Ok(()) => info!("Do-not-disturb has been disabled"),
+        Err(error) => warn!("Could not disable do-not-disturb: {:#}", error),
+    }
+}
+
+/// Runs the command and returns what it has printed, if it has been successful
+async fn output(mut command: Command) -> anyhow::Result<String> {
+    let child = command
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .output();
+
+    let output = timeout(COMMAND_TIMEOUT, child)
+        .await
+        .with_context(|| format!("{:?} did not finish in time", command))?
+        .with_context(|| format!("Could not run {:?}", command))?;
+
+    if !output.status.success() {
+        bail!("{:?} exited with {}", command, output.status);
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
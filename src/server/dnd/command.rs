@@ -0,0 +1,59 @@
+use super::{output, DoNotDisturb};
+use anyhow::Context;
+use async_trait::async_trait;
+use tokio::process::Command;
+
+/// Runs custom shell commands (e.g. to switch the Focus Assist of Windows via a script, which
+/// can't be done by other applications directly).
+/// The state before an interval can't be queried, therefore do-not-disturb is always disabled
+/// afterwards.
+#[derive(Debug, Clone)]
+pub struct CommandDnd {
+    enable_command: Option<String>,
+    disable_command: Option<String>,
+}
+
+impl CommandDnd {
+    /// Creates a backend, which runs the given shell commands
+    pub fn new(enable_command: Option<String>, disable_command: Option<String>) -> Self {
+        Self {
+            enable_command,
+            disable_command,
+        }
+    }
+}
+
+#[async_trait]
+impl DoNotDisturb for CommandDnd {
+    fn name(&self) -> &'static str {
+        "command"
+    }
+
+    async fn is_enabled(&self) -> anyhow::Result<bool> {
+        Ok(false)
+    }
+
+    async fn set_enabled(&self, enabled: bool) -> anyhow::Result<()> {
+        let (command, key) = if enabled {
+            (&self.enable_command, "dnd.enable_command")
+        } else {
+            (&self.disable_command, "dnd.disable_command")
+        };
+        let command = command
+            .as_deref()
+            .with_context(|| format!("`{}` has not been set", key))?;
+
+        let mut shell = if cfg!(windows) {
+            let mut shell = Command::new("cmd");
+            shell.arg("/C");
+            shell
+        } else {
+            let mut shell = Command::new("sh");
+            shell.arg("-c");
+            shell
+        };
+
+        shell.arg(command);
+        output(shell).await.map(|_| ())
+    }
+}
@@ -0,0 +1,29 @@
+use super::{output, DoNotDisturb};
+use async_trait::async_trait;
+use tokio::process::Command;
+
+/// Pauses the notifications of [dunst](https://dunst-project.org) via `dunstctl`.
+/// Notifications which arrive in the meantime are shown once dunst is resumed.
+#[derive(Debug, Clone, Copy)]
+pub struct DunstDnd;
+
+#[async_trait]
+impl DoNotDisturb for DunstDnd {
+    fn name(&self) -> &'static str {
+        "dunst"
+    }
+
+    async fn is_enabled(&self) -> anyhow::Result<bool> {
+        let mut dunstctl = Command::new("dunstctl");
+        dunstctl.arg("is-paused");
+
+        Ok(output(dunstctl).await? == "true")
+    }
+
+    async fn set_enabled(&self, enabled: bool) -> anyhow::Result<()> {
+        let mut dunstctl = Command::new("dunstctl");
+        dunstctl.args(["set-paused", &enabled.to_string()]);
+
+        output(dunstctl).await.map(|_| ())
+    }
+}
@@ -0,0 +1,47 @@
+use super::{output, DoNotDisturb};
+use async_trait::async_trait;
+use tokio::process::Command;
+
+/// Preferences domain of the notification center
+const DOMAIN: &str = "com.apple.notificationcenterui";
+
+/// Switches the do-not-disturb setting of the notification center via `defaults`.
+/// The notification center only picks up the new setting once it has been restarted.
+///
+/// Since macOS 12, do-not-disturb is one of the Focus modes, which can't be switched this way
+/// anymore - shortcuts of the Shortcuts app can be run via [super::command::CommandDnd]
+/// instead (e.g. `shortcuts run "Focus on"`).
+#[derive(Debug, Clone, Copy)]
+pub struct MacosDnd;
+
+#[async_trait]
+impl DoNotDisturb for MacosDnd {
+    fn name(&self) -> &'static str {
+        "notification center"
+    }
+
+    async fn is_enabled(&self) -> anyhow::Result<bool> {
+        let mut defaults = Command::new("defaults");
+        defaults.args(["-currentHost", "read", DOMAIN, "doNotDisturb"]);
+
+        // The key does not exist until do-not-disturb has been enabled for the first time
+        Ok(output(defaults).await.is_ok_and(|value| value == "1"))
+    }
+
+    async fn set_enabled(&self, enabled: bool) -> anyhow::Result<()> {
+        let mut defaults = Command::new("defaults");
+        defaults.args([
+            "-currentHost",
+            "write",
+            DOMAIN,
+            "doNotDisturb",
+            "-boolean",
+            &enabled.to_string(),
+        ]);
+        output(defaults).await?;
+
+        let mut killall = Command::new("killall");
+        killall.arg("NotificationCenter");
+        output(killall).await.map(|_| ())
+    }
+}
@@ -0,0 +1,173 @@
+//! Lowers the volume of other applications while the notification bell plays, so that the
+//! bell is audible over music.
+//!
+//! This talks to PulseAudio (or PipeWire via `pipewire-pulse`) through `pactl` and is
+//! therefore only available on Linux. The original volumes are restored as soon as the
+//! returned [DuckedStreams] are dropped.
+
+use log::{info, warn};
+
+/// Streams of other applications, whose volume has been lowered
+#[derive(Debug, Default)]
+pub struct DuckedStreams {
+    /// Index of each stream and its original volume per channel
+    streams: Vec<(u32, Vec<u32>)>,
+}
+
+impl Drop for DuckedStreams {
+    fn drop(&mut self) {
+        for (index, volumes) in &self.streams {
+            if let Err(error) = platform::set_volume(*index, volumes) {
+                warn!("Could not restore volume of stream {}: {:#}", index, error);
+            }
+        }
+    }
+}
+
+/// Lowers the volume of all streams of other applications to the given share of their
+/// current volume (e.g. `0.2` for 20%)
+pub fn duck(level: f32) -> DuckedStreams {
+    let streams = match platform::other_streams() {
+        Ok(streams) => streams,
+        Err(error) => {
+            warn!(
+                "Could not lower the volume of other applications: {:#}",
+                error
+            );
+            return DuckedStreams::default();
+        }
+    };
+
+    let mut ducked = DuckedStreams::default();
+
+    for (index, volumes) in streams {
+        let lowered: Vec<u32> = volumes
+            .iter()
+            .map(|volume| (*volume as f32 * level) as u32)
+            .collect();
+
+        match platform::set_volume(index, &lowered) {
+            Ok(()) => ducked.streams.push((index, volumes)),
+            Err(error) => warn!("Could not lower volume of stream {}: {:#}", index, error),
+        }
+    }
+
+    if !ducked.streams.is_empty() {
+        info!("Lowered the volume of {} streams", ducked.streams.len());
+    }
+
+    ducked
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::fmt;
+    use std::process::Command;
+
+    use anyhow::{bail, Context};
+    use serde::de::{MapAccess, Visitor};
+    use serde::{Deserialize, Deserializer};
+
+    /// Sink input as listed by `pactl --format=json list sink-inputs`
+    #[derive(Deserialize)]
+    struct SinkInput {
+        index: u32,
+        volume: ChannelVolumes,
+        #[serde(default)]
+        properties: Properties,
+    }
+
+    #[derive(Deserialize, Default)]
+    struct Properties {
+        #[serde(rename = "application.process.id")]
+        process_id: Option<String>,
+    }
+
+    /// Raw volume of each channel in the order of the channel map, which is also the order
+    /// `pactl set-sink-input-volume` expects
+    struct ChannelVolumes(Vec<u32>);
+
+    #[derive(Deserialize)]
+    struct ChannelVolume {
+        value: u32,
+    }
+
+    impl<'de> Deserialize<'de> for ChannelVolumes {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            struct ChannelVolumesVisitor;
+
+            impl<'de> Visitor<'de> for ChannelVolumesVisitor {
+                type Value = ChannelVolumes;
+
+                fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    write!(f, "a map of channel volumes")
+                }
+
+                // Visiting the map directly keeps the channel order, which a map type
+                // would lose
+                fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                    let mut volumes = vec![];
+
+                    while let Some((_, volume)) = map.next_entry::<String, ChannelVolume>()? {
+                        volumes.push(volume.value);
+                    }
+
+                    Ok(ChannelVolumes(volumes))
+                }
+            }
+
+            deserializer.deserialize_map(ChannelVolumesVisitor)
+        }
+    }
+
+    /// Streams of all other processes and their volume per channel
+    pub fn other_streams() -> anyhow::Result<Vec<(u32, Vec<u32>)>> {
+        let output = Command::new("pactl")
+            .args(["--format=json", "list", "sink-inputs"])
+            .output()
+            .context("Could not run pactl")?;
+
+        if !output.status.success() {
+            bail!("pactl exited with {}", output.status);
+        }
+
+        let sink_inputs: Vec<SinkInput> =
+            serde_json::from_slice(&output.stdout).context("Could not parse output of pactl")?;
+        let own_process_id = std::process::id().to_string();
+
+        Ok(sink_inputs
+            .into_iter()
+            .filter(|input| input.properties.process_id.as_ref() != Some(&own_process_id))
+            .map(|input| (input.index, input.volume.0))
+            .collect())
+    }
+
+    /// Sets the raw volume of each channel of the stream
+    pub fn set_volume(index: u32, volumes: &[u32]) -> anyhow::Result<()> {
+        let status = Command::new("pactl")
+            .arg("set-sink-input-volume")
+            .arg(index.to_string())
+            .args(volumes.iter().map(u32::to_string))
+            .status()
+            .context("Could not run pactl")?;
+
+        if !status.success() {
+            bail!("pactl exited with {}", status);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod platform {
+    use anyhow::bail;
+
+    pub fn other_streams() -> anyhow::Result<Vec<(u32, Vec<u32>)>> {
+        bail!("Lowering the volume of other applications is only supported on Linux")
+    }
+
+    pub fn set_volume(_index: u32, _volumes: &[u32]) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,152 @@
+//! Runs the shell commands, which have been configured inside the `[hooks]` section (see
+//! [HooksConfig]), whenever the timer state changes (e.g. an interval starts or the timer is
+//! paused) or a named timer/countdown has run out.
+//!
+//! In contrast to `view.exec_on`, these commands are run by the server itself, so that they
+//! are also run while no client is attached.
+
+use std::process::Stdio;
+use std::sync::mpsc::Receiver;
+
+use log::{error, info, warn};
+use zentime_rs_timer::event::{ObservedTimer, PomodoroEvent};
+use zentime_rs_timer::pomodoro_timer::ViewState;
+use zentime_rs_timer::transition_policy::TransitionPolicy;
+
+use super::notification::Notification;
+use crate::client::timer_event::{Phase, TimerEvent};
+use crate::config::HooksConfig;
+use crate::hooks::HookEnv;
+
+/// Subscriber to the [PomodoroEvent]s of the timer, which runs the configured commands for them
+#[derive(Debug)]
+pub struct EventHooks {
+    config: HooksConfig,
+    events: Receiver<PomodoroEvent>,
+    phase: Phase,
+}
+
+impl EventHooks {
+    /// Subscribes to the events of the given timer
+    pub fn subscribe<P: TransitionPolicy>(
+        config: HooksConfig,
+        timer: &mut ObservedTimer<P>,
+    ) -> Self {
+        Self {
+            config,
+            phase: Phase::of(&timer.view_state()),
+            events: timer.subscribe(),
+        }
+    }
+
+    /// Runs the commands for all events which have happened since the last call.
+    /// The given state is the one the timer has reached after these events - it provides the
+    /// remaining time and task of the environment, while the phase is the one of each event.
+    pub fn run(&mut self, state: &ViewState) {
+        let mut has_entered_phase = false;
+
+        for event in self.events.try_iter() {
+            let is_new_phase = has_entered_phase;
+            has_entered_phase = false;
+
+            let round = match event {
+                PomodoroEvent::IntervalStarted { round } => {
+                    self.phase = Phase::Focus;
+                    has_entered_phase = true;
+                    round
+                }
+                PomodoroEvent::BreakStarted { round, .. } => {
+                    self.phase = Phase::Break;
+                    has_entered_phase = true;
+                    round
+                }
+                PomodoroEvent::Postponed { .. } => {
+                    self.phase = Phase::Postponed;
+                    has_entered_phase = true;
+                    state.round
+                }
+                PomodoroEvent::Reset => {
+                    self.phase = Phase::Focus;
+                    state.round
+                }
+                PomodoroEvent::IntervalEnded { round, .. }
+                | PomodoroEvent::BreakEnded { round, .. } => round,
+                PomodoroEvent::Paused
+                | PomodoroEvent::Resumed
+                | PomodoroEvent::Extended { .. }
+                | PomodoroEvent::Reduced { .. } => state.round,
+            };
+
+            let Some(timer_event) = timer_event(&event) else {
+                continue;
+            };
+
+            // A phase change implies a new timer, therefore we don't report it being started
+            // right away (e.g. by `auto_start_breaks`) as resume
+            if is_new_phase && matches!(timer_event, TimerEvent::Pause | TimerEvent::Resume) {
+                continue;
+            }
+
+            if let Some(command) = self.config.command(timer_event) {
+                let mut env = HookEnv::from_view_state(timer_event, state);
+                env.phase = self.phase;
+                env.round = round;
+
+                run_command(command, env);
+            }
+        }
+
+        self.phase = Phase::of(state);
+    }
+}
+
+/// Event which hooks are configured for, if any
+fn timer_event(event: &PomodoroEvent) -> Option<TimerEvent> {
+    match event {
+        PomodoroEvent::IntervalStarted { .. } => Some(TimerEvent::IntervalStart),
+        PomodoroEvent::IntervalEnded { .. } => Some(TimerEvent::IntervalEnd),
+        PomodoroEvent::BreakStarted { .. } => Some(TimerEvent::BreakStart),
+        PomodoroEvent::BreakEnded { .. } => Some(TimerEvent::BreakEnd),
+        PomodoroEvent::Postponed { .. } => Some(TimerEvent::Postpone),
+        PomodoroEvent::Paused => Some(TimerEvent::Pause),
+        PomodoroEvent::Resumed => Some(TimerEvent::Resume),
+        PomodoroEvent::Extended { .. } | PomodoroEvent::Reduced { .. } | PomodoroEvent::Reset => {
+            None
+        }
+    }
+}
+
+/// Runs the `on_countdown_end` command (if there is one) for the notification about a named timer
+/// or countdown, which has run out
+pub fn run_countdown_hook(config: &HooksConfig, notification: &Notification) {
+    if let Some(command) = &config.on_countdown_end {
+        run_command(command, notification.hook_env());
+    }
+}
+
+/// Spawns the command without waiting for it, so that a long running command does not delay
+/// the commands of subsequent events
+fn run_command(command: &str, env: HookEnv) {
+    let event = env.event.clone();
+    info!("Running hook for {}", event);
+
+    let child = env.command(command).stdin(Stdio::null()).spawn();
+
+    let mut child = match child {
+        Ok(child) => child,
+        Err(error) => {
+            error!("Could not run hook for {}: {}", event, error);
+            return;
+        }
+    };
+
+    let command = command.to_string();
+
+    tokio::spawn(async move {
+        match child.wait().await {
+            Ok(status) if status.success() => {}
+            Ok(status) => warn!("Hook '{}' for {} exited with {}", command, event, status),
+            Err(error) => error!("Could not run hook '{}' for {}: {}", command, event, error),
+        }
+    });
+}
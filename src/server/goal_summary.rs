@@ -0,0 +1,70 @@
+//! Scheduler of the weekly summary of the focus time goal (see [GoalConfig]), which is shown on
+//! sunday at the configured local time while the server is running.
+//!
+//! The summary is handed over to the timer task, which notifies the user via all configured
+//! channels - just like at the end of an interval.
+
+use chrono::Weekday;
+use log::{error, info};
+use tokio::task::spawn_blocking;
+
+use super::action_queue::{ActionSender, TimerInput};
+use super::weekly_report::{next_due, wait_until};
+use crate::config::{GoalConfig, HistoryConfig};
+use crate::goals::{GoalPeriod, GoalProgress};
+use crate::history::format_minutes;
+use crate::wall_clock::WallClock;
+
+/// Summarizes the weekly goal at the end of each week, until the server shuts down
+pub async fn run(
+    config: GoalConfig,
+    history: HistoryConfig,
+    wall_clock: WallClock,
+    timer_input_sender: ActionSender,
+) {
+    loop {
+        let due = next_due(Weekday::Sun, config.summary_time, wall_clock.now());
+        info!("Next summary of the weekly goal is due at {}", due);
+
+        wait_until(wall_clock, due).await;
+
+        let history = history.clone();
+        let result = spawn_blocking(move || {
+            GoalProgress::load(&config, &history, wall_clock, GoalPeriod::Week)
+        })
+        .await;
+
+        match result {
+            Ok(Ok(Some(progress))) => timer_input_sender.send_input(TimerInput::GoalSummary(
+                summary(&progress, config.carry_over),
+            )),
+            Ok(Ok(None)) => {}
+            Ok(Err(error)) => error!("Could not evaluate the weekly goal: {:#}", error),
+            Err(error) => error!("Could not evaluate the weekly goal: {}", error),
+        }
+    }
+}
+
+/// Text of the notification, e.g. `Weekly goal missed: 12h of 20h - 60%. The missing 8h carry
+/// over to next week.`
+fn summary(progress: &GoalProgress, carry_over: bool) -> String {
+    if progress.is_met() {
+        return format!("Weekly goal met: {}", progress);
+    }
+
+    let summary = format!("Weekly goal missed: {}", progress);
+    // Only the plain goal of this week counts for the next one (see [crate::goals])
+    let carried_over = progress
+        .goal_seconds
+        .saturating_sub(progress.focused_seconds);
+
+    if !carry_over || carried_over == 0 {
+        return summary;
+    }
+
+    format!(
+        "{}. The missing {} carry over to next week.",
+        summary,
+        format_minutes(carried_over)
+    )
+}
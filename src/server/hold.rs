@@ -0,0 +1,62 @@
+//! Hold of the pomodoro cycle (e.g. during a meeting), which ends automatically
+
+use std::time::SystemTime;
+use zentime_rs_timer::clock;
+use zentime_rs_timer::pomodoro_timer::HoldState;
+use zentime_rs_timer::util::seconds_to_time;
+
+/// The pomodoro cycle is on hold until a given point in time.
+/// While on hold, the timer is paused and no notifications are sent.
+#[derive(Debug, Clone)]
+pub struct Hold {
+    reason: String,
+    until: SystemTime,
+
+    /// Denotes if the timer has been running when the hold started and should therefore be
+    /// resumed once the hold expires
+    resume: bool,
+
+    /// Denotes if the user is welcomed back with a notification once the hold expires
+    welcome_back: bool,
+}
+
+impl Hold {
+    /// Creates a new hold
+    pub fn new(reason: String, until: SystemTime, resume: bool, welcome_back: bool) -> Self {
+        Self {
+            reason,
+            until,
+            resume,
+            welcome_back,
+        }
+    }
+
+    /// Denotes if the hold has ended
+    pub fn is_expired(&self) -> bool {
+        clock::system_now() >= self.until
+    }
+
+    /// Denotes if the timer should be resumed once the hold expires
+    pub fn should_resume(&self) -> bool {
+        self.resume
+    }
+
+    /// Denotes if the user should be welcomed back once the hold expires
+    pub fn welcomes_back(&self) -> bool {
+        self.welcome_back
+    }
+
+    /// State of the hold, which is displayed by clients
+    pub fn state(&self) -> HoldState {
+        let remaining = self
+            .until
+            .duration_since(clock::system_now())
+            .unwrap_or_default();
+
+        HoldState {
+            reason: self.reason.clone(),
+            // Round up, so that the hold ends when 00:00 is displayed
+            remaining: seconds_to_time(remaining.as_millis().div_ceil(1000) as u64),
+        }
+    }
+}
@@ -0,0 +1,769 @@
+//! Optional HTTP server, which serves a spectator page showing the live timer.
+//!
+//! * `GET /` - built-in HTML page (e.g. for a second monitor or a TV during workshops)
+//! * `GET /ws` - WebSocket stream, which sends each [ViewState] as JSON text message
+//! * `GET /overlay.txt` - remaining time as plain text (e.g. for OBS text sources)
+//! * `GET /focus.ics` - calendar feed of focus blocks (only if `http.calendar` is enabled, see
+//!   [crate::calendar])
+//!
+//! Spectators can only watch the timer - messages they send are ignored.
+//!
+//! The JSON API (see [ApiConfig]) is served on its own port on localhost, because it controls
+//! the timer. It only answers requests which carry the configured token
+//! (`Authorization: Bearer <token>`) and whose `Host` (and `Origin`, if any) is localhost - or the
+//! configured `allowed_origin`, which is the only origin allowed to read responses via CORS:
+//!
+//! * `GET /state` - current [ViewState]
+//! * `GET /events` - server-sent events, which carry each [ViewState] (the WebSocket stream is
+//!   available via `GET /ws` as well)
+//! * `POST /play-pause`, `/skip`, `/reset`, `/postpone` and `/long-break` - applies the action
+//!   and answers with the resulting [ViewState], or with `409 Conflict` if the timer has
+//!   rejected it
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{bail, Context};
+use futures::{SinkExt, StreamExt};
+use log::{info, warn};
+use serde::Serialize;
+use serde_json::json;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::select;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::broadcast::Sender as BroadcastSender;
+use tokio::task::spawn_blocking;
+use tokio::time::timeout;
+use tokio_tungstenite::tungstenite::Message;
+use zentime_rs_timer::clock;
+use zentime_rs_timer::config::PomodoroTimerConfig;
+use zentime_rs_timer::pomodoro_timer::ViewState;
+use zentime_rs_timer::pomodoro_timer_action::PomodoroTimerAction;
+
+use super::action_queue::{ActionSender, Replier, TimerInput};
+use super::team::constant_time_eq;
+use super::timer_output::TimerOutputAction;
+use crate::calendar::{planned_blocks, recorded_blocks, to_ics};
+use crate::client::overlay::overlay_text;
+#[cfg(feature = "history")]
+use crate::config::HistoryConfig;
+use crate::config::{ApiConfig, HttpConfig};
+#[cfg(feature = "history")]
+use crate::history::History;
+use crate::secrets;
+
+/// Built-in spectator page
+const SPECTATOR_PAGE: &str = include_str!("spectator.html");
+
+/// Path of the WebSocket stream
+const WEBSOCKET_PATH: &str = "/ws";
+
+/// Path of the plain text overlay
+const OVERLAY_PATH: &str = "/overlay.txt";
+
+/// Path of the calendar feed
+const CALENDAR_PATH: &str = "/focus.ics";
+
+/// Path of the current state inside the API
+const STATE_PATH: &str = "/state";
+
+/// Path of the server-sent events inside the API
+const EVENTS_PATH: &str = "/events";
+
+/// Recorded focus blocks of this many days are part of the calendar feed
+#[cfg(feature = "history")]
+const CALENDAR_DAYS: u64 = 30;
+
+/// Maximum size of a request head (request line and headers)
+const MAX_REQUEST_HEAD_LENGTH: usize = 8 * 1024;
+
+/// Time a client has to send its request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Time the timer has to answer a request for its current state
+const STATE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Configuration the calendar feed is generated from
+#[derive(Debug)]
+pub struct CalendarSource {
+    /// History, which contains the recorded focus blocks
+    #[cfg(feature = "history")]
+    pub history: HistoryConfig,
+
+    /// Timer lengths, from which the planned focus blocks are projected
+    pub timers: PomodoroTimerConfig,
+}
+
+/// Routes which are served on an address
+#[derive(Debug)]
+enum Site {
+    /// Spectator page, which can only watch the timer
+    Spectator { calendar: Option<CalendarSource> },
+
+    /// JSON API, which controls the timer
+    Api(ApiAccess),
+}
+
+/// Who may access the JSON API
+#[derive(Debug)]
+struct ApiAccess {
+    /// Resolved token, which requests have to send
+    token: String,
+
+    /// Origin, which may read responses via CORS
+    allowed_origin: Option<String>,
+}
+
+impl ApiAccess {
+    /// Checks that the request comes from localhost (or the allowed origin) and carries the
+    /// token. Returns the status and reason of the rejection otherwise.
+    fn check(&self, head: &RequestHead) -> Result<(), (&'static str, &'static str)> {
+        // Requests of other hosts are pages, which have been rebound to our address via DNS
+        if !head.header("Host").is_some_and(is_localhost) {
+            return Err(("403 Forbidden", "Host is not allowed"));
+        }
+
+        if !head
+            .header("Origin")
+            .is_none_or(|origin| self.is_allowed_origin(origin) || is_local_origin(origin))
+        {
+            return Err(("403 Forbidden", "Origin is not allowed"));
+        }
+
+        // Preflight requests never carry credentials
+        if head.method == "OPTIONS" {
+            return Ok(());
+        }
+
+        let token = head
+            .header("Authorization")
+            .and_then(|value| value.strip_prefix("Bearer "));
+
+        if !token.is_some_and(|token| constant_time_eq(token.trim(), &self.token)) {
+            return Err(("401 Unauthorized", "Missing or invalid token"));
+        }
+
+        Ok(())
+    }
+
+    fn is_allowed_origin(&self, origin: &str) -> bool {
+        self.allowed_origin
+            .as_deref()
+            .is_some_and(|allowed| allowed.trim_end_matches('/') == origin)
+    }
+
+    /// CORS headers of responses to the request - only the allowed origin may read them
+    fn cors_headers(&self, head: &RequestHead) -> String {
+        match head.header("Origin") {
+            Some(origin) if self.is_allowed_origin(origin) => format!(
+                "Access-Control-Allow-Origin: {}\r\nVary: Origin\r\n",
+                origin
+            ),
+            _ => "Vary: Origin\r\n".to_string(),
+        }
+    }
+}
+
+/// Denotes if the value of a `Host` header names localhost (with any port)
+fn is_localhost(host: &str) -> bool {
+    let host = host.trim();
+    let name = match host.strip_prefix('[') {
+        Some(ipv6) => ipv6.split(']').next(),
+        None => host.split(':').next(),
+    };
+
+    name.is_some_and(|name| {
+        ["localhost", "127.0.0.1", "::1"]
+            .iter()
+            .any(|local| name.eq_ignore_ascii_case(local))
+    })
+}
+
+/// Denotes if the value of an `Origin` header is a page served by localhost
+fn is_local_origin(origin: &str) -> bool {
+    origin
+        .strip_prefix("http://")
+        .or_else(|| origin.strip_prefix("https://"))
+        .is_some_and(is_localhost)
+}
+
+/// Request line and headers of a request
+#[derive(Debug)]
+struct RequestHead {
+    method: String,
+    path: String,
+    headers: Vec<(String, String)>,
+}
+
+impl RequestHead {
+    /// Value of the header with the given (case-insensitive) name
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Accepts HTTP connections on the configured address, until the server shuts down
+pub async fn serve(
+    config: HttpConfig,
+    calendar: CalendarSource,
+    timer_input_sender: ActionSender,
+    timer_output_sender: Arc<BroadcastSender<TimerOutputAction>>,
+) -> anyhow::Result<()> {
+    let Some(address) = &config.listen else {
+        return Ok(());
+    };
+
+    let site = Site::Spectator {
+        calendar: config.calendar.then_some(calendar),
+    };
+
+    accept(address, site, timer_input_sender, timer_output_sender).await
+}
+
+/// Accepts connections to the JSON API on localhost, until the server shuts down
+pub async fn serve_api(
+    config: ApiConfig,
+    timer_input_sender: ActionSender,
+    timer_output_sender: Arc<BroadcastSender<TimerOutputAction>>,
+) -> anyhow::Result<()> {
+    let address = format!("127.0.0.1:{}", config.port);
+
+    let Some(token) = config.token else {
+        bail!("No token has been configured (api.token)");
+    };
+
+    // Resolving secrets might block (e.g. while waiting for the keyring)
+    let token = spawn_blocking(move || secrets::resolve(&token))
+        .await
+        .context("Could not resolve API token")??;
+
+    let access = ApiAccess {
+        token,
+        allowed_origin: config.allowed_origin,
+    };
+
+    accept(
+        &address,
+        Site::Api(access),
+        timer_input_sender,
+        timer_output_sender,
+    )
+    .await
+}
+
+/// Accepts HTTP connections on the given address and serves the routes of the site
+async fn accept(
+    address: &str,
+    site: Site,
+    timer_input_sender: ActionSender,
+    timer_output_sender: Arc<BroadcastSender<TimerOutputAction>>,
+) -> anyhow::Result<()> {
+    let listener = TcpListener::bind(address)
+        .await
+        .context(format!("Could not bind to {}", address))?;
+
+    match site {
+        Site::Spectator { .. } => info!("Serving spectator page on http://{}", address),
+        Site::Api(_) => info!("Serving JSON API on http://{}", address),
+    }
+
+    let site = Arc::new(site);
+
+    loop {
+        let (stream, peer) = match listener.accept().await {
+            Ok(connection) => connection,
+            Err(error) => {
+                warn!("Could not accept HTTP connection: {}", error);
+                continue;
+            }
+        };
+
+        let input_tx = timer_input_sender.clone();
+        let output_tx = timer_output_sender.clone();
+        let site = site.clone();
+
+        tokio::spawn(async move {
+            if let Err(error) = handle_request(stream, &site, input_tx, &output_tx).await {
+                info!("HTTP connection of {}: {:#}", peer, error);
+            }
+        });
+    }
+}
+
+/// Routes the request to the site
+async fn handle_request(
+    stream: TcpStream,
+    site: &Site,
+    timer_input_sender: ActionSender,
+    timer_output_sender: &BroadcastSender<TimerOutputAction>,
+) -> anyhow::Result<()> {
+    let head = timeout(REQUEST_TIMEOUT, peek_request_head(&stream))
+        .await
+        .context("Client did not send a request in time")??;
+
+    match site {
+        Site::Spectator { calendar } => {
+            handle_spectator_request(
+                stream,
+                &head.method,
+                &head.path,
+                calendar,
+                timer_input_sender,
+                timer_output_sender,
+            )
+            .await
+        }
+        Site::Api(access) => {
+            handle_api_request(
+                stream,
+                &head,
+                access,
+                timer_input_sender,
+                timer_output_sender,
+            )
+            .await
+        }
+    }
+}
+
+/// Serves the page or upgrades the connection to a WebSocket, depending on the request path
+async fn handle_spectator_request(
+    mut stream: TcpStream,
+    method: &str,
+    path: &str,
+    calendar: &Option<CalendarSource>,
+    timer_input_sender: ActionSender,
+    timer_output_sender: &BroadcastSender<TimerOutputAction>,
+) -> anyhow::Result<()> {
+    if method != "GET" {
+        return respond(&mut stream, "405 Method Not Allowed", "text/plain", "").await;
+    }
+
+    match path {
+        "/" | "/index.html" => {
+            respond(
+                &mut stream,
+                "200 OK",
+                "text/html; charset=utf-8",
+                SPECTATOR_PAGE,
+            )
+            .await
+        }
+        WEBSOCKET_PATH => stream_view_states(stream, timer_input_sender, timer_output_sender).await,
+        OVERLAY_PATH => {
+            let text = current_state(timer_input_sender, timer_output_sender)
+                .await
+                .map(|state| overlay_text(&state))
+                .unwrap_or_default();
+
+            respond(&mut stream, "200 OK", "text/plain; charset=utf-8", &text).await
+        }
+        CALENDAR_PATH => {
+            // The feed exposes the history, therefore it has to be enabled explicitly
+            let Some(calendar) = calendar else {
+                return respond(&mut stream, "404 Not Found", "text/plain", "Not found").await;
+            };
+
+            let state = current_state(timer_input_sender, timer_output_sender).await;
+
+            match focus_calendar(calendar, state).await {
+                Ok(ics) => {
+                    respond(&mut stream, "200 OK", "text/calendar; charset=utf-8", &ics).await
+                }
+                Err(error) => {
+                    warn!("Could not create calendar feed: {:#}", error);
+                    respond(
+                        &mut stream,
+                        "500 Internal Server Error",
+                        "text/plain",
+                        "Could not read history",
+                    )
+                    .await
+                }
+            }
+        }
+        _ => respond(&mut stream, "404 Not Found", "text/plain", "Not found").await,
+    }
+}
+
+/// Answers API requests with JSON or streams the view states
+async fn handle_api_request(
+    mut stream: TcpStream,
+    head: &RequestHead,
+    access: &ApiAccess,
+    timer_input_sender: ActionSender,
+    timer_output_sender: &BroadcastSender<TimerOutputAction>,
+) -> anyhow::Result<()> {
+    let cors = access.cors_headers(head);
+
+    if let Err((status, reason)) = access.check(head) {
+        warn!("Rejected API request: {}", reason);
+        return respond_json(&mut stream, status, &cors, &json!({ "error": reason })).await;
+    }
+
+    match (head.method.as_str(), head.path.as_str()) {
+        ("OPTIONS", _) => {
+            let headers = format!(
+                "{}Access-Control-Allow-Methods: GET, POST\r\n\
+                Access-Control-Allow-Headers: Authorization\r\n",
+                cors
+            );
+            respond_with_headers(&mut stream, "204 No Content", "text/plain", &headers, "").await
+        }
+        ("GET", STATE_PATH) => match current_state(timer_input_sender, timer_output_sender).await {
+            Some(state) => respond_json(&mut stream, "200 OK", &cors, &state).await,
+            None => {
+                let error = json!({ "error": "The timer did not answer in time" });
+                respond_json(&mut stream, "503 Service Unavailable", &cors, &error).await
+            }
+        },
+        ("GET", EVENTS_PATH) => {
+            stream_events(stream, &cors, timer_input_sender, timer_output_sender).await
+        }
+        ("GET", WEBSOCKET_PATH) => {
+            stream_view_states(stream, timer_input_sender, timer_output_sender).await
+        }
+        ("POST", path) => {
+            let Some(action) = api_action(path) else {
+                let error = json!({ "error": "Not found" });
+                return respond_json(&mut stream, "404 Not Found", &cors, &error).await;
+            };
+
+            match apply_action(action, timer_input_sender).await {
+                Ok(state) => respond_json(&mut stream, "200 OK", &cors, &state).await,
+                Err(message) => {
+                    let error = json!({ "error": message });
+                    respond_json(&mut stream, "409 Conflict", &cors, &error).await
+                }
+            }
+        }
+        (_, STATE_PATH | EVENTS_PATH | WEBSOCKET_PATH) => {
+            let error = json!({ "error": "Method not allowed" });
+            respond_json(&mut stream, "405 Method Not Allowed", &cors, &error).await
+        }
+        _ => {
+            let error = json!({ "error": "Not found" });
+            respond_json(&mut stream, "404 Not Found", &cors, &error).await
+        }
+    }
+}
+
+/// Timer action of an API path
+fn api_action(path: &str) -> Option<PomodoroTimerAction> {
+    let action = match path {
+        "/play-pause" => PomodoroTimerAction::PlayPause,
+        "/skip" => PomodoroTimerAction::Skip,
+        "/reset" => PomodoroTimerAction::ResetTimer,
+        "/postpone" => PomodoroTimerAction::PostponeBreak { seconds: None },
+        "/long-break" => PomodoroTimerAction::LongBreakNow,
+        _ => return None,
+    };
+
+    Some(action)
+}
+
+/// Applies the action to the timer and returns the resulting state - or the reason, why the
+/// timer has rejected the action. The timer replies to this request alone, so that neither
+/// states of other ticks nor the outcome of actions of other clients are returned.
+async fn apply_action(
+    action: PomodoroTimerAction,
+    timer_input_sender: ActionSender,
+) -> Result<ViewState, String> {
+    let (replier, reply) = Replier::new();
+    timer_input_sender
+        .try_send_input(TimerInput::Request { action, replier })
+        .map_err(|error| error.to_string())?;
+
+    match timeout(STATE_TIMEOUT, reply).await {
+        Ok(Ok(reply)) => reply,
+        Ok(Err(_)) => Err("The timer has stopped".to_string()),
+        Err(_) => Err("The timer did not answer in time".to_string()),
+    }
+}
+
+/// Reads the request head without consuming it, so that a WebSocket handshake can still read
+/// the complete request afterwards
+async fn peek_request_head(stream: &TcpStream) -> anyhow::Result<RequestHead> {
+    let mut buffer = vec![0; MAX_REQUEST_HEAD_LENGTH];
+
+    loop {
+        let length = stream
+            .peek(&mut buffer)
+            .await
+            .context("Could not read request")?;
+
+        if length == 0 {
+            bail!("Connection has been closed before the request was complete");
+        }
+
+        if let Some(end) = buffer[..length]
+            .windows(4)
+            .position(|bytes| bytes == b"\r\n\r\n")
+        {
+            let head = String::from_utf8_lossy(&buffer[..end]);
+            let mut lines = head.split("\r\n");
+            let mut parts = lines.next().unwrap_or_default().split_whitespace();
+
+            let (Some(method), Some(path)) = (parts.next(), parts.next()) else {
+                bail!("Invalid request line");
+            };
+
+            // Query strings are irrelevant for our few static routes
+            let path = path.split('?').next().unwrap_or_default();
+
+            let headers = lines
+                .filter_map(|line| line.split_once(':'))
+                .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+                .collect();
+
+            return Ok(RequestHead {
+                method: method.to_string(),
+                path: path.to_string(),
+                headers,
+            });
+        }
+
+        if length == buffer.len() {
+            bail!("Request head is too long");
+        }
+
+        // Peeking returns immediately while data is available, therefore we wait a bit
+        // until the rest of the request head has arrived
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+}
+
+/// Consumes the request head and writes a response, which closes the connection
+async fn respond(
+    stream: &mut TcpStream,
+    status: &str,
+    content_type: &str,
+    body: &str,
+) -> anyhow::Result<()> {
+    respond_with_headers(stream, status, content_type, "", body).await
+}
+
+/// Writes a JSON response with the given CORS headers (see [ApiAccess::cors_headers])
+async fn respond_json(
+    stream: &mut TcpStream,
+    status: &str,
+    cors: &str,
+    body: &impl Serialize,
+) -> anyhow::Result<()> {
+    let json = serde_json::to_string(body).context("Could not encode response")?;
+
+    respond_with_headers(stream, status, "application/json", cors, &json).await
+}
+
+/// Consumes the request head and writes a response with the given additional headers (each
+/// one terminated by CRLF)
+async fn respond_with_headers(
+    stream: &mut TcpStream,
+    status: &str,
+    content_type: &str,
+    headers: &str,
+    body: &str,
+) -> anyhow::Result<()> {
+    read_request_head(stream).await?;
+
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\n{}Connection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        headers,
+        body
+    );
+
+    stream
+        .write_all(response.as_bytes())
+        .await
+        .context("Could not write response")?;
+    stream.shutdown().await.ok();
+
+    Ok(())
+}
+
+/// Reads the request head, so that closing the connection does not reset it while the client
+/// is still sending
+async fn read_request_head(stream: &mut TcpStream) -> anyhow::Result<()> {
+    let mut head = Vec::new();
+    let mut buffer = [0; 1024];
+
+    while !head.windows(4).any(|bytes| bytes == b"\r\n\r\n") {
+        if head.len() > MAX_REQUEST_HEAD_LENGTH {
+            bail!("Request head is too long");
+        }
+
+        let length = stream
+            .read(&mut buffer)
+            .await
+            .context("Could not read request")?;
+
+        if length == 0 {
+            break;
+        }
+
+        head.extend_from_slice(&buffer[..length]);
+    }
+
+    Ok(())
+}
+
+/// Requests the current state from the timer.
+/// Returns [None], if the timer did not answer in time.
+async fn current_state(
+    timer_input_sender: ActionSender,
+    timer_output_sender: &BroadcastSender<TimerOutputAction>,
+) -> Option<ViewState> {
+    let mut timer_output_receiver = timer_output_sender.subscribe();
+    timer_input_sender.send(PomodoroTimerAction::None);
+
+    timeout(STATE_TIMEOUT, async {
+        loop {
+            match timer_output_receiver.recv().await {
+                Ok(TimerOutputAction::Timer(state)) => return Some(state),
+                Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    })
+    .await
+    .ok()
+    .flatten()
+}
+
+/// Creates the calendar feed of the recorded focus blocks and the focus blocks, which are planned
+/// for the rest of the cycle of the given timer state
+async fn focus_calendar(
+    calendar: &CalendarSource,
+    state: Option<ViewState>,
+) -> anyhow::Result<String> {
+    #[cfg(feature = "history")]
+    let records = {
+        let history = calendar.history.clone();
+
+        spawn_blocking(move || {
+            History::open(&history)?
+                .records_since(Duration::from_secs(CALENDAR_DAYS * 24 * 60 * 60))
+        })
+        .await
+        .context("History task failed")??
+    };
+    // Without a history, the feed only contains the planned focus blocks
+    #[cfg(not(feature = "history"))]
+    let records = vec![];
+
+    let now = clock::system_now();
+    let mut blocks = recorded_blocks(&records);
+
+    if let Some(state) = state {
+        blocks.extend(planned_blocks(&state, &calendar.timers, now));
+    }
+
+    Ok(to_ics(&blocks, now))
+}
+
+/// Upgrades the connection to a WebSocket and sends every view state as JSON to the client
+async fn stream_view_states(
+    stream: TcpStream,
+    timer_input_sender: ActionSender,
+    timer_output_sender: &BroadcastSender<TimerOutputAction>,
+) -> anyhow::Result<()> {
+    let mut socket = tokio_tungstenite::accept_async(stream)
+        .await
+        .context("WebSocket handshake failed")?;
+    let mut timer_output_receiver = timer_output_sender.subscribe();
+
+    // The timer only broadcasts changes, therefore we request the current state right away
+    timer_input_sender.send(PomodoroTimerAction::None);
+
+    loop {
+        select! {
+            output = timer_output_receiver.recv() => {
+                let state = match output {
+                    Ok(TimerOutputAction::Timer(state)) => state,
+                    Ok(_) => continue,
+                    // The next state will bring the page up to date
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                };
+
+                let json = serde_json::to_string(&state).context("Could not encode view state")?;
+                socket
+                    .send(Message::text(json))
+                    .await
+                    .context("Could not send view state")?;
+            },
+            msg = socket.next() => {
+                match msg {
+                    Some(Ok(Message::Close(_))) | None => break,
+                    Some(Ok(_)) => continue,
+                    Some(Err(error)) => return Err(error).context("Could not read from WebSocket"),
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Sends every view state as server-sent event to the client, until it closes the connection
+async fn stream_events(
+    mut stream: TcpStream,
+    cors: &str,
+    timer_input_sender: ActionSender,
+    timer_output_sender: &BroadcastSender<TimerOutputAction>,
+) -> anyhow::Result<()> {
+    read_request_head(&mut stream).await?;
+
+    let (mut reader, mut writer) = stream.split();
+    let mut timer_output_receiver = timer_output_sender.subscribe();
+
+    let response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-store\r\n\
+        {}Connection: keep-alive\r\n\r\n",
+        cors
+    );
+
+    writer
+        .write_all(response.as_bytes())
+        .await
+        .context("Could not write response")?;
+
+    // The timer only broadcasts changes, therefore we request the current state right away
+    timer_input_sender.send(PomodoroTimerAction::None);
+
+    let mut buffer = [0; 1024];
+
+    loop {
+        select! {
+            output = timer_output_receiver.recv() => {
+                let state = match output {
+                    Ok(TimerOutputAction::Timer(state)) => state,
+                    // The next state will bring the client up to date
+                    Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                };
+
+                let json = serde_json::to_string(&state).context("Could not encode view state")?;
+                writer
+                    .write_all(format!("data: {}\n\n", json).as_bytes())
+                    .await
+                    .context("Could not send view state")?;
+            },
+            // Clients don't send anything after their request, except for closing the connection
+            read = reader.read(&mut buffer) => {
+                if read.map_or(true, |length| length == 0) {
+                    break;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,246 @@
+//! Pauses a running focus interval once the user has been away from the keyboard and mouse for
+//! a while (see [IdleConfig]) and optionally resumes it as soon as they are back.
+//!
+//! The idle time is polled from the desktop environment - the timer itself only receives
+//! [TimerInput::AutoPause] and [TimerInput::AutoResume].
+
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Duration;
+
+use log::{info, warn};
+use tokio::process::Command;
+use tokio::select;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::broadcast::Sender as BroadcastSender;
+use tokio::time::{interval, timeout, Instant, MissedTickBehavior};
+use zentime_rs_timer::pomodoro_timer::ViewState;
+
+use super::action_queue::{ActionSender, TimerInput};
+use super::timer_output::TimerOutputAction;
+use crate::client::timer_event::Phase;
+use crate::config::IdleConfig;
+
+/// Reason of the pause, which is shown by the clients
+const IDLE_PAUSE_REASON: &str = "idle";
+
+/// Time between two queries of the idle time
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Time a command has to print the idle time
+const QUERY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Watches the idle time while a focus interval is running (or has been paused because of it),
+/// until the server shuts down
+pub async fn run(
+    config: IdleConfig,
+    timer_input_sender: ActionSender,
+    timer_output_sender: Arc<BroadcastSender<TimerOutputAction>>,
+) {
+    if config.command.is_none() && !cfg!(unix) {
+        warn!("The idle time can't be determined on this platform - set `idle.command`");
+        return;
+    }
+
+    // Failed queries are only logged as info later on, to not flood the log every few seconds
+    if idle_time(&config).await.is_none() {
+        warn!(
+            "Could not determine the idle time - install `xprintidle` (X11) or set `idle.command`"
+        );
+    }
+
+    let mut timer_output_receiver = timer_output_sender.subscribe();
+    let threshold = Duration::from_secs(config.threshold);
+    let mut polls = interval(POLL_INTERVAL);
+    polls.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    let mut state: Option<ViewState> = None;
+    let mut paused_at: Option<Instant> = None;
+
+    loop {
+        select! {
+            value = timer_output_receiver.recv() => match value {
+                Ok(TimerOutputAction::Timer(new_state)) => {
+                    let was_focusing = state.as_ref().is_some_and(is_focusing);
+                    let is_auto_paused = is_paused_for_idling(&new_state);
+
+                    if !is_auto_paused {
+                        paused_at = None;
+                    } else if paused_at.is_none() {
+                        paused_at = Some(Instant::now());
+                    }
+
+                    // Intervals which start while the user is away (e.g. at the end of a break)
+                    // are checked right away
+                    let has_started = is_focusing(&new_state) && !was_focusing;
+                    state = Some(new_state);
+
+                    if !has_started {
+                        continue;
+                    }
+                }
+                Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            },
+            _ = polls.tick() => {}
+        }
+
+        let Some(state) = &state else {
+            continue;
+        };
+
+        let should_resume = config.resume && paused_at.is_some();
+
+        if !is_focusing(state) && !should_resume {
+            continue;
+        }
+
+        let Some(idle_time) = idle_time(&config).await else {
+            continue;
+        };
+
+        if is_focusing(state) && idle_time >= threshold {
+            info!("No input for {} seconds - pausing", idle_time.as_secs());
+            timer_input_sender.send_input(TimerInput::AutoPause {
+                reason: IDLE_PAUSE_REASON.to_string(),
+            });
+        } else if should_resume && paused_at.is_some_and(|at| idle_time < at.elapsed()) {
+            info!("Input after being idle - resuming");
+            timer_input_sender.send_input(TimerInput::AutoResume);
+            paused_at = None;
+        }
+    }
+}
+
+/// Denotes if a focus interval is running right now
+fn is_focusing(state: &ViewState) -> bool {
+    Phase::of(state) == Phase::Focus && !state.is_paused && state.hold.is_none()
+}
+
+/// Denotes if the timer has been paused because the user has been idle
+fn is_paused_for_idling(state: &ViewState) -> bool {
+    state.is_paused && state.pause_reason.as_deref() == Some(IDLE_PAUSE_REASON)
+}
+
+/// Time since the last keyboard or mouse input - [None] if it could not be determined
+async fn idle_time(config: &IdleConfig) -> Option<Duration> {
+    let Some(command) = &config.command else {
+        return system_idle_time().await;
+    };
+
+    let mut shell = if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+
+    shell.arg(command);
+
+    let milliseconds = output(shell).await?;
+    parse_milliseconds(&milliseconds)
+}
+
+/// Idle time according to the idle monitor of GNOME (Wayland) or `xprintidle` (X11)
+#[cfg(all(unix, not(target_os = "macos")))]
+async fn system_idle_time() -> Option<Duration> {
+    if std::env::var_os("WAYLAND_DISPLAY").is_none() {
+        let milliseconds = output(Command::new("xprintidle")).await?;
+        return parse_milliseconds(&milliseconds);
+    }
+
+    let mut gdbus = Command::new("gdbus");
+    gdbus.args([
+        "call",
+        "--session",
+        "--dest",
+        "org.gnome.Mutter.IdleMonitor",
+        "--object-path",
+        "/org/gnome/Mutter/IdleMonitor/Core",
+        "--method",
+        "org.gnome.Mutter.IdleMonitor.GetIdletime",
+    ]);
+
+    // The reply looks like `(uint64 1234,)`
+    let reply = output(gdbus).await?;
+    parse_milliseconds(
+        reply
+            .trim_start_matches("(uint64")
+            .trim_end_matches(",)")
+            .trim(),
+    )
+}
+
+/// Idle time according to the event source of the window server
+#[cfg(target_os = "macos")]
+async fn system_idle_time() -> Option<Duration> {
+    /// Events of all processes (`kCGEventSourceStateCombinedSessionState`)
+    const COMBINED_SESSION_STATE: i32 = 0;
+
+    /// Keyboard, mouse and touch events (`kCGAnyInputEventType`)
+    const ANY_INPUT_EVENT: u32 = !0;
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGEventSourceSecondsSinceLastEventType(state: i32, event_type: u32) -> f64;
+    }
+
+    let seconds =
+        unsafe { CGEventSourceSecondsSinceLastEventType(COMBINED_SESSION_STATE, ANY_INPUT_EVENT) };
+
+    Duration::try_from_secs_f64(seconds).ok()
+}
+
+/// There is no built-in way to determine the idle time (see [run])
+#[cfg(not(unix))]
+async fn system_idle_time() -> Option<Duration> {
+    None
+}
+
+fn parse_milliseconds(text: &str) -> Option<Duration> {
+    match text.trim().parse::<u64>() {
+        Ok(milliseconds) => Some(Duration::from_millis(milliseconds)),
+        Err(_) => {
+            info!("Could not read idle time from {:?}", text);
+            None
+        }
+    }
+}
+
+/// Runs the command and returns the first line it has printed (if it has been successful)
+async fn output(mut command: Command) -> Option<String> {
+    let child = command
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .output();
+
+    let output = match timeout(QUERY_TIMEOUT, child).await {
+        Ok(Ok(output)) if output.status.success() => output,
+        Ok(Ok(output)) => {
+            info!(
+                "Could not determine idle time: {:?} exited with {}",
+                command, output.status
+            );
+            return None;
+        }
+        Ok(Err(error)) => {
+            info!("Could not determine idle time via {:?}: {}", command, error);
+            return None;
+        }
+        Err(_) => {
+            info!(
+                "Could not determine idle time: {:?} did not finish in time",
+                command
+            );
+            return None;
+        }
+    };
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .next()
+        .map(str::to_string)
+}
@@ -0,0 +1,139 @@
+//! Socket on which the server accepts local clients - either bound by the server itself or
+//! handed over by systemd (see [crate::server::systemd]).
+
+use std::io;
+
+#[cfg(target_os = "linux")]
+use anyhow::bail;
+use anyhow::Context;
+use interprocess::local_socket::tokio::{LocalSocketListener, LocalSocketStream};
+#[cfg(target_os = "linux")]
+use tokio::net::{UnixListener, UnixStream};
+#[cfg(target_os = "linux")]
+use tokio_util::compat::{TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt};
+
+#[cfg(unix)]
+use crate::ipc::is_socket_file;
+use crate::ipc::{verify_peer, ConnectionReader, ConnectionWriter};
+
+/// Listening socket of the server
+#[derive(Debug)]
+pub(crate) enum SocketListener {
+    /// Socket which has been bound by the server
+    Bound(LocalSocketListener),
+
+    /// Socket which has been bound by systemd and passed to the server (socket activation)
+    #[cfg(target_os = "linux")]
+    Activated(UnixListener),
+}
+
+/// Connection of a local client, which has been accepted by a [SocketListener]
+#[derive(Debug)]
+pub(crate) enum LocalConnection {
+    Bound(LocalSocketStream),
+
+    #[cfg(target_os = "linux")]
+    Activated(UnixStream),
+}
+
+impl SocketListener {
+    /// Binds the socket of the given name.
+    /// Socket files can only be connected to by the current user.
+    pub(crate) async fn bind(socket_name: &str) -> anyhow::Result<Self> {
+        let listener =
+            LocalSocketListener::bind(socket_name).context("Could not bind to local socket")?;
+
+        #[cfg(unix)]
+        if is_socket_file(socket_name) {
+            use std::os::unix::fs::PermissionsExt;
+
+            tokio::fs::set_permissions(socket_name, std::fs::Permissions::from_mode(0o600))
+                .await
+                .context("Could not restrict socket file permissions")?;
+        }
+
+        Ok(Self::Bound(listener))
+    }
+
+    /// Takes over a socket, which has already been bound and is listening (the permissions of
+    /// its file are up to whoever has bound it)
+    #[cfg(target_os = "linux")]
+    pub(crate) fn activated(listener: std::os::unix::net::UnixListener) -> anyhow::Result<Self> {
+        listener
+            .set_nonblocking(true)
+            .context("Could not make socket non-blocking")?;
+
+        let listener =
+            UnixListener::from_std(listener).context("Could not listen on passed socket")?;
+
+        Ok(Self::Activated(listener))
+    }
+
+    /// Waits for the next client to connect
+    pub(crate) async fn accept(&self) -> io::Result<LocalConnection> {
+        match self {
+            SocketListener::Bound(listener) => listener.accept().await.map(LocalConnection::Bound),
+
+            #[cfg(target_os = "linux")]
+            SocketListener::Activated(listener) => listener
+                .accept()
+                .await
+                .map(|(stream, _)| LocalConnection::Activated(stream)),
+        }
+    }
+}
+
+impl LocalConnection {
+    /// Makes sure that the client is run by the current user
+    pub(crate) fn verify_peer(&self) -> anyhow::Result<()> {
+        match self {
+            LocalConnection::Bound(stream) => verify_peer(stream),
+
+            #[cfg(target_os = "linux")]
+            LocalConnection::Activated(stream) => {
+                let credentials = stream
+                    .peer_cred()
+                    .context("Could not determine peer credentials")?;
+
+                // SAFETY: getuid() is always successful
+                if credentials.uid() != unsafe { libc::getuid() } {
+                    bail!("Peer is owned by another user");
+                }
+
+                Ok(())
+            }
+        }
+    }
+
+    /// Process id of the client (if the platform tells it)
+    pub(crate) fn peer_pid(&self) -> Option<u32> {
+        match self {
+            #[cfg(target_os = "linux")]
+            LocalConnection::Bound(stream) => stream.peer_pid().ok(),
+            #[cfg(not(target_os = "linux"))]
+            LocalConnection::Bound(_) => None,
+
+            #[cfg(target_os = "linux")]
+            LocalConnection::Activated(stream) => stream
+                .peer_cred()
+                .ok()
+                .and_then(|credentials| credentials.pid())
+                .and_then(|pid| u32::try_from(pid).ok()),
+        }
+    }
+
+    pub(crate) fn into_split(self) -> (ConnectionReader, ConnectionWriter) {
+        match self {
+            LocalConnection::Bound(stream) => {
+                let (reader, writer) = stream.into_split();
+                (Box::new(reader), Box::new(writer))
+            }
+
+            #[cfg(target_os = "linux")]
+            LocalConnection::Activated(stream) => {
+                let (reader, writer) = stream.into_split();
+                (Box::new(reader.compat()), Box::new(writer.compat_write()))
+            }
+        }
+    }
+}
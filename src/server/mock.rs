@@ -0,0 +1,140 @@
+//! Fake timer, which plays back a scripted [Scenario] instead of running a real pomodoro timer.
+//! This allows to iterate on client interfaces (e.g. themes and layouts) without waiting for
+//! real time to pass.
+
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{bail, Context};
+use log::info;
+use serde::{Deserialize, Serialize};
+use tokio::select;
+use tokio::sync::broadcast::Sender as BroadcastSender;
+use tokio::sync::watch::Sender as WatchSender;
+use tokio::time::{sleep_until, Instant};
+use zentime_rs_timer::config::PomodoroTimerConfig;
+use zentime_rs_timer::pomodoro_timer::ViewState;
+use zentime_rs_timer::pomodoro_timer_action::PomodoroTimerAction;
+
+use super::action_queue::{ActionReceiver, TimerInput};
+use super::shutdown;
+use super::timer_output::TimerOutputAction;
+use crate::ipc::Snapshot;
+
+/// Sequence of states, which is played back by a mock server
+/// (see `zentime server start --mock`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Scenario {
+    /// Starts over with the first step after the last one has been played
+    #[serde(default)]
+    pub repeat: bool,
+
+    /// Steps which are played in order
+    pub steps: Vec<ScenarioStep>,
+}
+
+/// Single state of a [Scenario]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScenarioStep {
+    /// Time in seconds the state is shown, before the next step is played
+    #[serde(default = "default_step_seconds")]
+    pub seconds: u64,
+
+    /// State which is broadcast to all clients
+    pub state: ViewState,
+}
+
+fn default_step_seconds() -> u64 {
+    1
+}
+
+impl Scenario {
+    /// Reads a scenario from a JSON file
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Could not read scenario {}", path.display()))?;
+        let scenario: Scenario = serde_json::from_str(&content)
+            .with_context(|| format!("Could not parse scenario {}", path.display()))?;
+
+        if scenario.steps.is_empty() {
+            bail!("Scenario {} does not contain any steps", path.display());
+        }
+
+        Ok(scenario)
+    }
+}
+
+/// Plays back the scenario and broadcasts each of its states.
+/// Skipping jumps to the next step right away, all other client actions only lead to a
+/// broadcast of the current state. The last state is kept once a scenario without
+/// repetition has been played.
+/// Syncing clients receive the current state together with the given timer config.
+pub async fn run(
+    scenario: Scenario,
+    timers: PomodoroTimerConfig,
+    timer_input_receiver: ActionReceiver,
+    timer_output_sender: Arc<BroadcastSender<TimerOutputAction>>,
+    snapshot_sender: WatchSender<Option<Snapshot>>,
+) {
+    info!(
+        "Playing mock scenario with {} steps...",
+        scenario.steps.len()
+    );
+
+    let mut index = 0;
+    // [None] once the last step of a scenario without repetition has been played
+    let mut step_end = Some(broadcast_step(&scenario, index, &timer_output_sender));
+
+    loop {
+        snapshot_sender.send_replace(Some(Snapshot {
+            state: scenario.steps[index].state.clone(),
+            timers,
+        }));
+
+        let should_advance = select! {
+            _ = sleep_until(step_end.unwrap_or_else(Instant::now)), if step_end.is_some() => true,
+            inputs = timer_input_receiver.recv() => {
+                if inputs.contains(&TimerInput::Shutdown) {
+                    shutdown::announce(&timer_output_sender);
+                    return;
+                }
+
+                inputs.contains(&TimerInput::Action(PomodoroTimerAction::Skip))
+            }
+        };
+
+        if !should_advance {
+            broadcast(&scenario.steps[index].state, &timer_output_sender);
+        } else if index + 1 < scenario.steps.len() || scenario.repeat {
+            index = (index + 1) % scenario.steps.len();
+            step_end = Some(broadcast_step(&scenario, index, &timer_output_sender));
+        } else {
+            step_end = None;
+            broadcast(&scenario.steps[index].state, &timer_output_sender);
+        }
+    }
+}
+
+/// Broadcasts the state of the given step and returns the point in time at which it ends
+fn broadcast_step(
+    scenario: &Scenario,
+    index: usize,
+    timer_output_sender: &BroadcastSender<TimerOutputAction>,
+) -> Instant {
+    let step = &scenario.steps[index];
+    info!("Playing step {} of mock scenario", index + 1);
+
+    broadcast(&step.state, timer_output_sender);
+
+    Instant::now() + Duration::from_secs(step.seconds)
+}
+
+fn broadcast(state: &ViewState, timer_output_sender: &BroadcastSender<TimerOutputAction>) {
+    // Sending only fails if no client is connected
+    timer_output_sender
+        .send(TimerOutputAction::Timer(state.clone()))
+        .ok();
+}
@@ -0,0 +1,218 @@
+//! Named countdowns, which run next to the pomodoro timer (e.g. a one-off "tea" timer).
+//!
+//! One timer is selected at a time: clients display the selected timer and actions like
+//! play/pause apply to it. A named timer disappears once it has run out (or has been skipped),
+//! after which the pomodoro timer is selected again.
+//!
+//! One-off countdowns (e.g. `zentime countdown 15m standup`) run next to them, but can't be
+//! selected or controlled - they only notify once they are due.
+
+use std::time::Duration;
+
+use anyhow::{anyhow, bail};
+use log::info;
+use zentime_rs_timer::countdown::Countdown;
+use zentime_rs_timer::pomodoro_timer::{CountdownState, NamedTimerState};
+use zentime_rs_timer::pomodoro_timer_action::PomodoroTimerAction;
+use zentime_rs_timer::ticking_timer::TickingTimer;
+
+use crate::ipc::MAIN_TIMER;
+
+/// Countdown which has been started via [crate::ipc::ClientToServerMsg::NewTimer]
+#[derive(Debug, Clone)]
+struct NamedTimer {
+    name: String,
+    timer: TickingTimer,
+}
+
+/// Named timers and countdowns of the server and which timer is currently selected
+#[derive(Debug, Clone, Default)]
+pub struct NamedTimers {
+    timers: Vec<NamedTimer>,
+
+    /// Name of the selected timer - [None] while the pomodoro timer is selected
+    selected: Option<String>,
+
+    /// Pending countdowns, in the order in which they are due
+    countdowns: Vec<Countdown>,
+}
+
+impl NamedTimers {
+    /// Starts a countdown of the given length, which is selected right away
+    pub fn add(&mut self, name: &str, duration: Duration) -> anyhow::Result<()> {
+        let name = name.trim();
+
+        if name.is_empty() || name == MAIN_TIMER {
+            bail!("A timer needs a name other than '{}'", MAIN_TIMER);
+        }
+
+        if duration.is_zero() {
+            bail!("Timer '{}' needs a length", name);
+        }
+
+        if self.timers.iter().any(|timer| timer.name == name) {
+            bail!("There already is a timer named '{}'", name);
+        }
+
+        info!("Started timer '{}' ({}s)", name, duration.as_secs());
+        self.timers.push(NamedTimer {
+            name: name.to_string(),
+            timer: TickingTimer::new_running(duration.as_secs()),
+        });
+        self.selected = Some(name.to_string());
+
+        Ok(())
+    }
+
+    /// Starts a one-off countdown of the given length
+    pub fn add_countdown(
+        &mut self,
+        duration: Duration,
+        label: Option<String>,
+    ) -> anyhow::Result<()> {
+        if duration.is_zero() {
+            bail!("A countdown needs a length");
+        }
+
+        let label = label
+            .map(|label| label.trim().to_string())
+            .filter(|label| !label.is_empty());
+
+        info!(
+            "Started countdown {} ({}s)",
+            label.as_deref().unwrap_or("without label"),
+            duration.as_secs()
+        );
+
+        let countdown = Countdown::new(duration, label);
+        let index = self
+            .countdowns
+            .partition_point(|pending| pending.remaining_time() <= countdown.remaining_time());
+        self.countdowns.insert(index, countdown);
+
+        Ok(())
+    }
+
+    /// Removes the countdowns which are due and returns them
+    pub fn take_due_countdowns(&mut self) -> Vec<Countdown> {
+        let due = self
+            .countdowns
+            .iter()
+            .take_while(|countdown| countdown.is_due())
+            .count();
+
+        self.countdowns.drain(..due).collect()
+    }
+
+    /// Selects the timer with the given name ([MAIN_TIMER] selects the pomodoro timer)
+    pub fn select(&mut self, name: &str) -> anyhow::Result<()> {
+        if name == MAIN_TIMER {
+            self.selected = None;
+        } else if self.timers.iter().any(|timer| timer.name == name) {
+            self.selected = Some(name.to_string());
+        } else {
+            return Err(anyhow!(
+                "Unknown timer '{}' (available: {})",
+                name,
+                self.names().join(", ")
+            ));
+        }
+
+        info!("Selected timer '{}'", name);
+        Ok(())
+    }
+
+    /// Denotes if a named timer is selected instead of the pomodoro timer
+    pub fn is_selected(&self) -> bool {
+        self.selected.is_some()
+    }
+
+    /// Applies an action to the selected named timer. Actions which only make sense for the
+    /// pomodoro timer (e.g. postponing a break) are rejected.
+    pub fn apply(&mut self, action: PomodoroTimerAction) -> anyhow::Result<()> {
+        let Some(index) = self.selected_index() else {
+            bail!("No named timer has been selected");
+        };
+        let timer = &mut self.timers[index].timer;
+
+        match action {
+            PomodoroTimerAction::PlayPause => timer.play_pause(),
+            PomodoroTimerAction::ResetTimer | PomodoroTimerAction::RestartCurrent => {
+                *timer = TickingTimer::new(timer.total_time().as_secs());
+            }
+            PomodoroTimerAction::ExtendTime { seconds } => {
+                timer.extend(Duration::from_secs(seconds))
+            }
+            PomodoroTimerAction::ReduceTime { seconds } => {
+                timer.reduce(Duration::from_secs(seconds))
+            }
+            PomodoroTimerAction::Skip => {
+                let removed = self.timers.remove(index);
+                info!("Removed timer '{}'", removed.name);
+                self.selected = None;
+            }
+            PomodoroTimerAction::None => {}
+            _ => bail!(
+                "Timer '{}' can only be started, paused, reset, adjusted or skipped",
+                self.timers[index].name
+            ),
+        }
+
+        Ok(())
+    }
+
+    /// Advances all timers and removes the ones which have run out. Returns their names.
+    pub fn tick(&mut self) -> Vec<String> {
+        let mut ended = vec![];
+
+        self.timers.retain_mut(|timer| {
+            if !timer.timer.tick() {
+                return true;
+            }
+
+            info!("Timer '{}' has run out", timer.name);
+            ended.push(timer.name.clone());
+            false
+        });
+
+        if self
+            .selected
+            .as_ref()
+            .is_some_and(|name| ended.contains(name))
+        {
+            self.selected = None;
+        }
+
+        ended
+    }
+
+    /// States of all named timers, which are broadcast together with the pomodoro timer
+    pub fn states(&self) -> Vec<NamedTimerState> {
+        self.timers
+            .iter()
+            .map(|timer| NamedTimerState {
+                name: timer.name.clone(),
+                time: timer.timer.status().current_time.to_string(),
+                is_paused: timer.timer.is_paused(),
+                is_selected: self.selected.as_ref() == Some(&timer.name),
+            })
+            .collect()
+    }
+
+    /// States of all pending countdowns, which are broadcast together with the pomodoro timer
+    pub fn countdown_states(&self) -> Vec<CountdownState> {
+        self.countdowns.iter().map(Countdown::state).collect()
+    }
+
+    /// Names of all timers, starting with the pomodoro timer
+    fn names(&self) -> Vec<&str> {
+        std::iter::once(MAIN_TIMER)
+            .chain(self.timers.iter().map(|timer| timer.name.as_str()))
+            .collect()
+    }
+
+    fn selected_index(&self) -> Option<usize> {
+        let selected = self.selected.as_ref()?;
+        self.timers.iter().position(|timer| &timer.name == selected)
+    }
+}
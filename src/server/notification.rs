@@ -1,85 +1,801 @@
-//! OS-Notification and sound playback related functions.
-
-use super::sound::{play, AudioPlaybackError};
-use crate::config::NotificationConfig;
-use anyhow::bail;
-use log::error;
-use notify_rust::{Notification, NotificationHandle};
-use rand::{seq::SliceRandom, thread_rng};
-use std::fmt::Write;
+//! Notifications which are dispatched at the end of each interval/break (and for reminders).
+//!
+//! Each notification channel (OS-notification, bell, webhook, hook commands) is implemented as a
+//! [Notifier]. The [NotificationDispatcher] runs all registered notifiers concurrently, so that
+//! a slow or failing channel does not affect the others. Notifications at the start of a break
+//! carry a [BreakSuggestion] for notifiers which show it.
+
+mod bell;
+#[cfg(feature = "notifications")]
+mod desktop;
+mod hook;
+mod suggestion;
+#[cfg(feature = "http")]
+mod webhook;
+
+pub use bell::BellNotifier;
+#[cfg(feature = "notifications")]
+pub use desktop::DesktopNotifier;
+pub use hook::HookNotifier;
+pub use suggestion::{BreakSuggestion, SuggestionProvider, BUILTIN_PROVIDER};
+#[cfg(feature = "http")]
+pub use webhook::WebhookNotifier;
+
+use super::action_queue::ActionSender;
+use super::sound::SoundPlayer;
+use crate::client::timer_event::Phase;
+use crate::config::{
+    NotificationConfig, NotificationFailurePolicy, PauseConfig, PauseTimeoutAction, ReminderConfig,
+    ScheduleConfig,
+};
+use crate::history::CycleSummary;
+use crate::hooks::HookEnv;
+use crate::wall_clock::WallClock;
+use async_trait::async_trait;
+use futures::future::{join, join_all};
+use log::info;
+#[cfg(not(all(feature = "notifications", feature = "http")))]
+use log::warn;
+use std::borrow::Cow;
+use std::fmt::{Debug, Display};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
 use thiserror::Error;
+use tokio::time::sleep;
+use zentime_rs_timer::countdown::Countdown;
+use zentime_rs_timer::pomodoro_timer::{DailyStats, TimerKind, ViewState};
+use zentime_rs_timer::pomodoro_timer_action::PomodoroTimerAction;
+use zentime_rs_timer::ticking_pomodoro_timer::{PomodoroPhase, TimerEnd};
+
+/// Delay before a failed notifier is retried for the first time (see
+/// [NotificationFailurePolicy::Retry]), which doubles with each further attempt
+const INITIAL_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Upper limit of the delay between two attempts of a notifier
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
 
-/// Something went wrong during notification dispatch
+/// A single notifier failed to deliver a notification
 #[derive(Debug, Error)]
-pub enum NotificationDispatchError {
-    /// Denotes that the given [SoundFile] could not be decoded
-    #[error("Could not play sound file")]
-    SoundPlayback(#[from] AudioPlaybackError),
+#[error("Could not send {notifier} notification: {error:#}")]
+pub struct NotificationDispatchError {
+    /// Name of the failed [Notifier]
+    pub notifier: &'static str,
+
+    /// Cause of the failure
+    #[source]
+    pub error: anyhow::Error,
+}
+
+/// Event a notification is sent for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationEvent {
+    /// A focus interval has ended
+    IntervalEnd,
+
+    /// A break has ended
+    BreakEnd,
+
+    /// A recurring reminder is due (see [ReminderConfig])
+    Reminder,
+
+    /// The timer has been paused for too long and has therefore been resumed or reset
+    /// (see [PauseConfig])
+    PauseTimeout,
+
+    /// A full pomodoro cycle has ended with its long break
+    CycleEnd,
+
+    /// A pause with a scheduled end (`zentime pause-until`) is over and the timer has been
+    /// resumed
+    WelcomeBack,
+
+    /// A week is over - the notification tells whether the weekly focus time goal has been met
+    WeeklyGoal,
+
+    /// The last interval, which has been needed to reach the daily goal, has run out
+    DailyGoal,
+
+    /// A named timer (e.g. `zentime timer new tea 5m`) or a one-off countdown (e.g.
+    /// `zentime countdown 15m standup`) has run out
+    CountdownEnd,
+}
+
+impl Display for NotificationEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            NotificationEvent::IntervalEnd => "interval_end",
+            NotificationEvent::BreakEnd => "break_end",
+            NotificationEvent::Reminder => "reminder",
+            NotificationEvent::PauseTimeout => "pause_timeout",
+            NotificationEvent::CycleEnd => "cycle_end",
+            NotificationEvent::WelcomeBack => "welcome_back",
+            NotificationEvent::WeeklyGoal => "weekly_goal",
+            NotificationEvent::DailyGoal => "daily_goal",
+            NotificationEvent::CountdownEnd => "countdown_end",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// Button of an OS-notification, which applies an action to the timer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationAction {
+    /// Starts the break, which is waiting to be started
+    StartBreak,
+
+    /// Starts the interval, which is waiting to be started
+    StartInterval,
+
+    /// Skips the current interval/break
+    Skip,
+
+    /// Postpones the current break
+    Postpone,
+}
+
+impl NotificationAction {
+    /// All actions, in the order in which they are offered
+    const ALL: [NotificationAction; 4] = [
+        NotificationAction::StartBreak,
+        NotificationAction::StartInterval,
+        NotificationAction::Skip,
+        NotificationAction::Postpone,
+    ];
+
+    /// Identifier of the button, which the notification daemon reports back once it has been
+    /// clicked
+    pub fn id(self) -> &'static str {
+        match self {
+            NotificationAction::StartBreak => "start_break",
+            NotificationAction::StartInterval => "start_interval",
+            NotificationAction::Skip => "skip",
+            NotificationAction::Postpone => "postpone",
+        }
+    }
+
+    /// Action with the given button identifier
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.id() == id)
+    }
+
+    /// Text of the button
+    pub fn label(self) -> &'static str {
+        match self {
+            NotificationAction::StartBreak => "Start break",
+            NotificationAction::StartInterval => "Start focus",
+            NotificationAction::Skip => "Skip",
+            NotificationAction::Postpone => "Postpone",
+        }
+    }
+
+    /// Action which is applied to the timer, once the button has been clicked
+    pub fn timer_action(self) -> PomodoroTimerAction {
+        match self {
+            NotificationAction::StartBreak | NotificationAction::StartInterval => {
+                PomodoroTimerAction::PlayPause
+            }
+            NotificationAction::Skip => PomodoroTimerAction::Skip,
+            NotificationAction::Postpone => PomodoroTimerAction::PostponeBreak { seconds: None },
+        }
+    }
+}
 
-    /// Denotes that something went wrong while zentime tried to send
-    /// a system notification.
-    /// NOTE: This case should currently not happen, because the underlying
-    /// call to the [notify_rust] library will always return with `Ok`
-    #[error("Could not send OS notification")]
-    OperatingSystemNotification(#[from] anyhow::Error),
+/// Buttons of an OS-notification together with the state of the timer they belong to.
+/// Once the timer has moved on to another round or phase, clicking them has no effect.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotificationButtons {
+    /// Round the buttons belong to
+    pub round: u64,
+
+    /// Whether the buttons belong to a break
+    pub is_break: bool,
+
+    /// Offered actions
+    pub actions: Vec<NotificationAction>,
+}
+
+impl NotificationButtons {
+    /// Buttons which apply to the given timer state: an interval/break which is waiting to be
+    /// started can be started, every interval/break can be skipped and only breaks can be
+    /// postponed
+    pub fn for_state(state: &ViewState) -> Self {
+        let mut actions = vec![];
+
+        match (state.is_paused, state.is_break) {
+            (true, true) => actions.push(NotificationAction::StartBreak),
+            (true, false) => actions.push(NotificationAction::StartInterval),
+            (false, _) => {}
+        }
+
+        actions.push(NotificationAction::Skip);
+
+        if state.is_break {
+            actions.push(NotificationAction::Postpone);
+        }
+
+        Self {
+            round: state.round,
+            is_break: state.is_break,
+            actions,
+        }
+    }
+}
+
+/// Information which is handed to all [Notifier]s
+#[derive(Debug, Clone)]
+pub struct Notification {
+    /// Event the notification is sent for
+    pub event: NotificationEvent,
+
+    /// Phase of the timer (for ended timers this is the phase which has ended)
+    pub phase: Phase,
+
+    /// Current pomodoro round
+    pub round: u64,
+
+    /// Remaining time of the current timer
+    pub remaining: String,
+
+    /// Name of the reminder, if this notification has been sent for one
+    pub reminder: Option<String>,
+
+    /// Label of the current session (e.g. the task which is worked on)
+    pub task: Option<String>,
+
+    /// Text which is shown to the user
+    pub message: Option<String>,
+
+    /// What to do during the break which starts with this notification
+    /// (set by the [NotificationDispatcher] for [Notifier::shows_suggestions])
+    pub suggestion: Option<BreakSuggestion>,
+
+    /// OS-notifications are closed automatically after this duration
+    pub expires_after: Option<Duration>,
+
+    /// Notifiers should not make any sound (e.g. while running on low battery)
+    pub is_muted: bool,
+
+    /// Phase which has ended, if this notification has been sent for the end of a timer
+    pub ended_phase: Option<PomodoroPhase>,
+
+    /// Buttons which OS-notifications offer to act on the timer
+    pub buttons: Option<NotificationButtons>,
 }
 
-/// Play a sound file and send an OS-notification.
-pub fn dispatch_notification(
-    config: NotificationConfig,
-    notification_string: Option<&str>,
-    should_show_suggestion: bool,
-) -> Result<(), NotificationDispatchError> {
-    if config.enable_bell {
-        play(config.sound_file, config.volume)?;
+impl Notification {
+    /// Creates the notification for a timer which has ended during the labeled session
+    pub fn timer_end(timer_end: TimerEnd, task: Option<String>, is_muted: bool) -> Self {
+        let (event, phase) = match timer_end.kind {
+            TimerKind::Interval => (NotificationEvent::IntervalEnd, Phase::Focus),
+            TimerKind::Break => (NotificationEvent::BreakEnd, Phase::Break),
+        };
+
+        Self {
+            event,
+            phase,
+            round: timer_end.state.round,
+            remaining: "00:00".to_string(),
+            reminder: None,
+            task,
+            message: timer_end.notification.map(String::from),
+            suggestion: None,
+            expires_after: None,
+            is_muted,
+            ended_phase: Some(timer_end.phase),
+            buttons: None,
+        }
+    }
+
+    /// Offers the buttons which apply to the given state of the timer (see
+    /// [NotificationButtons::for_state()])
+    pub fn with_buttons(mut self, state: &ViewState) -> Self {
+        self.buttons = Some(NotificationButtons::for_state(state));
+        self
     }
 
-    if !config.show_notification || notification_string.is_none() {
-        return Ok(());
-    };
+    /// Creates the notification for a reminder, which is due during the given timer state.
+    /// If the reminder has a duration, the notification disappears once it has passed.
+    pub fn reminder(config: &ReminderConfig, state: &ViewState, is_muted: bool) -> Self {
+        Self {
+            event: NotificationEvent::Reminder,
+            phase: Phase::of(state),
+            round: state.round,
+            remaining: state.time.clone(),
+            reminder: Some(config.name.clone()),
+            task: state.task.clone(),
+            message: Some(config.message.clone()),
+            suggestion: None,
+            expires_after: (config.duration > 0).then(|| Duration::from_secs(config.duration)),
+            is_muted: is_muted || !config.enable_bell,
+            ended_phase: None,
+            buttons: None,
+        }
+    }
 
-    let mut notification = notification_string.unwrap().to_string();
+    /// Creates the notification for a pause, which has lasted for too long and has therefore
+    /// been ended with the given action
+    pub fn pause_timeout(
+        config: &PauseConfig,
+        state: &ViewState,
+        task: Option<String>,
+        is_muted: bool,
+    ) -> Self {
+        let message = match config.on_timeout {
+            PauseTimeoutAction::Resume => format!(
+                "Resumed the timer, which has been paused for {} minutes",
+                config.max_pause_minutes
+            ),
+            PauseTimeoutAction::Reset => format!(
+                "Restarted the timer, which has been paused for {} minutes",
+                config.max_pause_minutes
+            ),
+        };
 
-    if !should_show_suggestion {
-        send(&notification)?;
-        return Ok(());
+        Self {
+            event: NotificationEvent::PauseTimeout,
+            phase: Phase::of(state),
+            round: state.round,
+            remaining: state.time.clone(),
+            reminder: None,
+            task,
+            message: Some(message),
+            suggestion: None,
+            expires_after: None,
+            is_muted,
+            ended_phase: None,
+            buttons: None,
+        }
     }
 
-    let suggestions = match config.break_suggestions {
-        Some(suggestions) => suggestions,
-        None => vec![],
-    };
+    /// Creates the notification which summarizes a cycle, that has ended with the given state
+    pub fn cycle_end(
+        summary: &CycleSummary,
+        state: &ViewState,
+        task: Option<String>,
+        is_muted: bool,
+    ) -> Self {
+        Self {
+            event: NotificationEvent::CycleEnd,
+            phase: Phase::of(state),
+            round: state.round,
+            remaining: state.time.clone(),
+            reminder: None,
+            task,
+            message: Some(summary.to_string()),
+            suggestion: None,
+            expires_after: None,
+            is_muted,
+            ended_phase: None,
+            buttons: None,
+        }
+    }
 
-    let random_suggestion = suggestions.choose(&mut thread_rng());
+    /// Creates the notification for a scheduled pause, which is over, so that the timer has been
+    /// resumed with the given state
+    pub fn welcome_back(state: &ViewState, task: Option<String>, is_muted: bool) -> Self {
+        Self {
+            event: NotificationEvent::WelcomeBack,
+            phase: Phase::of(state),
+            round: state.round,
+            remaining: state.time.clone(),
+            reminder: None,
+            task,
+            message: Some(format!(
+                "Welcome back! The timer has been resumed with {} left",
+                state.time
+            )),
+            suggestion: None,
+            expires_after: None,
+            is_muted,
+            ended_phase: None,
+            buttons: None,
+        }
+    }
 
-    if let Some(suggestion) = random_suggestion {
-        if let Err(error) = write!(notification, "\n\n{}", suggestion) {
-            error!("Could not concatenate random suggestion to notification message");
-            return Err(NotificationDispatchError::OperatingSystemNotification(
-                anyhow::Error::new(error),
+    /// Sample notification, which is used to try out the notification settings
+    pub fn sample() -> Self {
+        Self {
+            event: NotificationEvent::IntervalEnd,
+            phase: Phase::Focus,
+            round: 1,
+            remaining: "00:00".to_string(),
+            reminder: None,
+            task: None,
+            message: Some("This is how zentime tells you that it's time for a break".to_string()),
+            suggestion: None,
+            expires_after: Some(Duration::from_secs(10)),
+            is_muted: false,
+            ended_phase: None,
+            buttons: None,
+        }
+    }
+
+    /// Creates the notification which summarizes the weekly focus time goal
+    pub fn weekly_goal(
+        summary: String,
+        state: &ViewState,
+        task: Option<String>,
+        is_muted: bool,
+    ) -> Self {
+        Self {
+            event: NotificationEvent::WeeklyGoal,
+            phase: Phase::of(state),
+            round: state.round,
+            remaining: state.time.clone(),
+            reminder: None,
+            task,
+            message: Some(summary),
+            suggestion: None,
+            expires_after: None,
+            is_muted,
+            ended_phase: None,
+            buttons: None,
+        }
+    }
+
+    /// Creates the notification which congratulates on reaching the daily goal with the given
+    /// statistics of today
+    pub fn daily_goal(
+        today: DailyStats,
+        state: &ViewState,
+        task: Option<String>,
+        is_muted: bool,
+    ) -> Self {
+        Self {
+            event: NotificationEvent::DailyGoal,
+            phase: Phase::of(state),
+            round: state.round,
+            remaining: state.time.clone(),
+            reminder: None,
+            task,
+            message: Some(format!(
+                "Daily goal reached: {} intervals today",
+                today.intervals
+            )),
+            suggestion: None,
+            expires_after: None,
+            is_muted,
+            ended_phase: None,
+            buttons: None,
+        }
+    }
+
+    /// Creates the notification for a named timer, which has run out while the pomodoro timer
+    /// is in the given state
+    pub fn countdown_end(name: String, state: &ViewState, is_muted: bool) -> Self {
+        Self {
+            event: NotificationEvent::CountdownEnd,
+            phase: Phase::of(state),
+            round: state.round,
+            remaining: state.time.clone(),
+            message: Some(format!("Timer '{}' has run out", name)),
+            reminder: Some(name),
+            task: None,
+            suggestion: None,
+            expires_after: None,
+            is_muted,
+            ended_phase: None,
+            buttons: None,
+        }
+    }
+
+    /// Creates the notification for a one-off countdown, which is due while the pomodoro timer
+    /// is in the given state
+    pub fn countdown(countdown: &Countdown, state: &ViewState, is_muted: bool) -> Self {
+        let message = match countdown.label() {
+            Some(label) => format!("Countdown '{}' has run out", label),
+            None => "Countdown has run out".to_string(),
+        };
+
+        Self {
+            event: NotificationEvent::CountdownEnd,
+            phase: Phase::of(state),
+            round: state.round,
+            remaining: state.time.clone(),
+            reminder: countdown.label().map(str::to_string),
+            task: None,
+            message: Some(message),
+            suggestion: None,
+            expires_after: None,
+            is_muted,
+            ended_phase: None,
+            buttons: None,
+        }
+    }
+
+    /// Environment of hook commands, which are run for this notification
+    pub fn hook_env(&self) -> HookEnv {
+        HookEnv {
+            event: self.event.to_string(),
+            phase: self.phase,
+            round: self.round,
+            remaining: self.remaining.clone(),
+            task: self.task.clone(),
+            reminder: self.reminder.clone(),
+            suggestion: self.suggestion.as_ref().map(ToString::to_string),
+        }
+    }
+}
+
+/// Channel which notifies the user (e.g. about the end of a timer)
+#[async_trait]
+pub trait Notifier: Debug + Send + Sync {
+    /// Name of the notifier, which is used in error messages
+    fn name(&self) -> &'static str;
+
+    /// Denotes if the notifier shows the [BreakSuggestion] at the start of a break.
+    /// Only these notifiers wait until the suggestion has been fetched.
+    fn shows_suggestions(&self) -> bool {
+        false
+    }
+
+    /// Delivers the notification.
+    /// Blocking work has to be moved to a blocking task, because all notifiers run concurrently
+    /// on the async runtime.
+    async fn notify(&self, notification: &Notification) -> anyhow::Result<()>;
+}
+
+/// Handles to the rest of the server, which notifiers need besides their config
+#[derive(Debug, Clone)]
+pub struct NotifierContext {
+    /// Plays back the bell
+    pub sound_player: SoundPlayer,
+
+    /// Receives the actions of buttons inside OS-notifications
+    pub timer_input_sender: ActionSender,
+}
+
+/// Runs all registered [Notifier]s for each notification
+#[derive(Debug, Default)]
+pub struct NotificationDispatcher {
+    notifiers: Vec<RegisteredNotifier>,
+    suggestion_provider: Option<SuggestionProvider>,
+    on_failure: NotificationFailurePolicy,
+    retries: u8,
+    quiet_hours: Option<(ScheduleConfig, WallClock)>,
+}
+
+impl NotificationDispatcher {
+    /// Creates a dispatcher without any notifiers
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a dispatcher with all notifiers which have been enabled inside the config
+    pub fn from_config(config: &NotificationConfig, context: &NotifierContext) -> Self {
+        let mut dispatcher = Self::new();
+
+        if config.enable_bell {
+            dispatcher.register(BellNotifier::new(
+                context.sound_player.clone(),
+                config.sound_file.clone(),
+                config.sounds.clone(),
+                config.volume,
+                config.bell_patterns,
+                config.channels,
+            ));
+        }
+
+        if config.show_notification {
+            #[cfg(feature = "notifications")]
+            dispatcher.register(DesktopNotifier::new(
+                config.actions.then(|| context.timer_input_sender.clone()),
             ));
+            #[cfg(not(feature = "notifications"))]
+            warn!(
+                "zentime has been built without OS notifications - rebuild it with \
+                `--features notifications` or disable `notifications.show_notification`"
+            );
+        }
+
+        if let Some(url) = &config.webhook_url {
+            #[cfg(feature = "http")]
+            dispatcher.register(WebhookNotifier::new(url.clone()));
+            #[cfg(not(feature = "http"))]
+            warn!(
+                "zentime has been built without HTTP support - rebuild it with \
+                `--features http` to send notifications to {}",
+                url
+            );
+        }
+
+        for command in &config.hooks {
+            dispatcher.register(HookNotifier::new(command.clone()));
+        }
+
+        dispatcher.suggestion_provider = SuggestionProvider::from_config(config);
+        dispatcher.on_failure = config.on_failure;
+        dispatcher.retries = config.retries;
+        dispatcher
+    }
+
+    /// Mutes all notifications, which are dispatched during silent quiet hours (see
+    /// [ScheduleConfig::quiet_hours])
+    pub fn with_quiet_hours(mut self, schedule: &ScheduleConfig, wall_clock: WallClock) -> Self {
+        if !schedule.quiet_hours.is_empty() {
+            self.quiet_hours = Some((schedule.clone(), wall_clock));
+        }
+
+        self
+    }
+
+    /// Adds a notifier, which is run on each subsequent dispatch
+    pub fn register(&mut self, notifier: impl Notifier + 'static) {
+        self.notifiers.push(RegisteredNotifier {
+            notifier: Box::new(notifier),
+            failures: AtomicU32::new(0),
+        });
+    }
+
+    /// What should happen with the errors returned by [Self::dispatch()]
+    pub fn on_failure(&self) -> NotificationFailurePolicy {
+        self.on_failure
+    }
+
+    /// Runs all notifiers concurrently and waits until they have finished.
+    /// At the start of a break, notifiers which show suggestions wait for the suggestion to be
+    /// fetched, while all others (e.g. the bell) are run right away.
+    /// With [NotificationFailurePolicy::Retry] each failed notifier is retried on its own.
+    /// Returns the errors of all notifiers which have failed (in the end).
+    pub async fn dispatch(&self, notification: &Notification) -> Vec<NotificationDispatchError> {
+        let is_quiet = self
+            .quiet_hours
+            .as_ref()
+            .is_some_and(|(schedule, wall_clock)| schedule.is_silent(wall_clock.now()));
+
+        let muted;
+        let notification = if is_quiet && !notification.is_muted {
+            info!(
+                "Quiet hours - {} notification is silent",
+                notification.event
+            );
+            muted = Notification {
+                is_muted: true,
+                ..notification.clone()
+            };
+            &muted
+        } else {
+            notification
+        };
+
+        let retries = match self.on_failure {
+            NotificationFailurePolicy::Retry => self.retries,
+            _ => 0,
+        };
+
+        let provider = self
+            .suggestion_provider
+            .as_ref()
+            .filter(|_| notification.event == NotificationEvent::IntervalEnd);
+
+        let Some(provider) = provider else {
+            return run_notifiers(self.notifiers.iter(), notification, retries).await;
         };
+
+        let (showing, other): (Vec<_>, Vec<_>) = self
+            .notifiers
+            .iter()
+            .partition(|registered| registered.notifier.shows_suggestions());
+
+        let with_suggestion = async {
+            let mut notification = Cow::Borrowed(notification);
+
+            if !showing.is_empty() {
+                notification.to_mut().suggestion = provider.suggest(&notification.hook_env()).await;
+            }
+
+            run_notifiers(showing.into_iter(), &notification, retries).await
+        };
+
+        let (mut errors, other_errors) = join(
+            with_suggestion,
+            run_notifiers(other.into_iter(), notification, retries),
+        )
+        .await;
+
+        errors.extend(other_errors);
+        errors
     }
+}
 
-    send(&notification)?;
+/// Notifier of a [NotificationDispatcher] together with its retry state
+#[derive(Debug)]
+struct RegisteredNotifier {
+    notifier: Box<dyn Notifier>,
 
-    Ok(())
+    /// Notifications in a row, which the notifier has failed to deliver.
+    /// A notifier which keeps failing (e.g. because the notification daemon is missing) is
+    /// retried with longer delays right away.
+    failures: AtomicU32,
 }
 
-/// Send a OS-notificaion
-fn send(message: &str) -> anyhow::Result<NotificationHandle> {
-    match Notification::new()
-        .summary("\u{25EF} zentime")
-        .body(message)
-        .show()
-    {
-        Ok(handle) => Ok(handle),
-        Err(error) => {
-            // Currently show() will always return ok() (as per the definition of)
-            // notify_rust. However if they API changes one day an we are indeed able to receive
-            // errors, we wan't it to be logged in some way.
-            error!("Error on notification: {:?}", error);
-            bail!(error)
+impl RegisteredNotifier {
+    /// Delivers the notification and retries it up to `retries` times, if it fails
+    async fn notify(
+        &self,
+        notification: &Notification,
+        retries: u8,
+    ) -> Result<(), NotificationDispatchError> {
+        let failures = self.failures.load(Ordering::Relaxed).min(5);
+        let mut delay = (INITIAL_RETRY_DELAY * 2u32.pow(failures)).min(MAX_RETRY_DELAY);
+        let mut attempt = 0;
+
+        loop {
+            match self.notifier.notify(notification).await {
+                Ok(()) => {
+                    self.failures.store(0, Ordering::Relaxed);
+                    return Ok(());
+                }
+                Err(error) if attempt < retries => {
+                    info!(
+                        "Could not send {} notification ({:#}) - retrying in {:?}",
+                        self.notifier.name(),
+                        error,
+                        delay
+                    );
+
+                    sleep(delay).await;
+                    delay = (delay * 2).min(MAX_RETRY_DELAY);
+                    attempt += 1;
+                }
+                Err(error) => {
+                    self.failures.fetch_add(1, Ordering::Relaxed);
+
+                    return Err(NotificationDispatchError {
+                        notifier: self.notifier.name(),
+                        error,
+                    });
+                }
+            }
         }
     }
 }
+
+/// Runs the given notifiers concurrently and returns the errors of all which have failed
+async fn run_notifiers<'a>(
+    notifiers: impl Iterator<Item = &'a RegisteredNotifier>,
+    notification: &Notification,
+    retries: u8,
+) -> Vec<NotificationDispatchError> {
+    let results = join_all(notifiers.map(|notifier| notifier.notify(notification, retries))).await;
+
+    results.into_iter().filter_map(Result::err).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zentime_rs_timer::config::PomodoroTimerConfig;
+    use zentime_rs_timer::ticking_pomodoro_timer::TickingPomodoroTimer;
+
+    #[test]
+    fn offers_notification_buttons_for_the_next_phase() {
+        let mut timer = TickingPomodoroTimer::new(PomodoroTimerConfig::default());
+        timer.handle_action(PomodoroTimerAction::Skip).unwrap();
+
+        let buttons = NotificationButtons::for_state(&timer.view_state());
+        assert!(buttons.is_break);
+        assert_eq!(
+            buttons.actions,
+            [
+                NotificationAction::StartBreak,
+                NotificationAction::Skip,
+                NotificationAction::Postpone
+            ]
+        );
+
+        timer.handle_action(PomodoroTimerAction::Skip).unwrap();
+        timer.handle_action(PomodoroTimerAction::PlayPause).unwrap();
+
+        let buttons = NotificationButtons::for_state(&timer.view_state());
+        assert_eq!(buttons.round, 2);
+        assert_eq!(buttons.actions, [NotificationAction::Skip]);
+
+        assert_eq!(
+            NotificationAction::from_id(NotificationAction::Postpone.id()),
+            Some(NotificationAction::Postpone)
+        );
+        assert_eq!(NotificationAction::from_id("__closed"), None);
+    }
+}
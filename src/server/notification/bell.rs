@@ -0,0 +1,88 @@
+use super::{Notification, NotificationEvent, Notifier};
+use crate::config::{BellPatterns, EventSounds, SoundChannels};
+use crate::server::sound::{Sound, SoundChannel, SoundPlayer};
+use async_trait::async_trait;
+
+/// Plays a sound file - once or several times, depending on the [BellPatterns] of the event.
+/// The sound is played back by a [SoundPlayer], therefore this does not wait until the playback
+/// has finished. The end of each kind of timer may have its own sound (see [EventSounds]).
+/// Reminders are played on their own [SoundChannel], so that they neither drown out nor cut
+/// off the bell.
+#[derive(Debug)]
+pub struct BellNotifier {
+    sound_player: SoundPlayer,
+    sound_file: Option<String>,
+    sounds: EventSounds,
+    volume: f32,
+    patterns: BellPatterns,
+    channels: SoundChannels,
+}
+
+impl BellNotifier {
+    /// Creates a notifier which plays the given sound file (or the default bell sound)
+    pub fn new(
+        sound_player: SoundPlayer,
+        sound_file: Option<String>,
+        sounds: EventSounds,
+        volume: f32,
+        patterns: BellPatterns,
+        channels: SoundChannels,
+    ) -> Self {
+        Self {
+            sound_player,
+            sound_file,
+            sounds,
+            volume,
+            patterns,
+            channels,
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for BellNotifier {
+    fn name(&self) -> &'static str {
+        "bell"
+    }
+
+    async fn notify(&self, notification: &Notification) -> anyhow::Result<()> {
+        if !notification.is_muted {
+            let channel = match notification.event {
+                NotificationEvent::Reminder => SoundChannel::Reminder,
+                _ => SoundChannel::Bell,
+            };
+            let channel_config = self.channels.get(channel);
+            let event_sound = notification.ended_phase.map(|phase| self.sounds.get(phase));
+
+            self.sound_player.play(Sound {
+                channel,
+                priority: channel_config.priority,
+                sound_file: event_sound
+                    .and_then(|sound| sound.sound_file.clone())
+                    .or_else(|| self.sound_file.clone()),
+                volume: self.volume
+                    * channel_config.volume
+                    * event_sound.map_or(1.0, |sound| sound.volume),
+                rings: rings(&self.patterns, notification.event),
+                gap: self.patterns.gap(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Number of times the bell rings for the given event
+fn rings(patterns: &BellPatterns, event: NotificationEvent) -> u8 {
+    match event {
+        NotificationEvent::IntervalEnd => patterns.interval_end,
+        NotificationEvent::BreakEnd => patterns.break_end,
+        NotificationEvent::Reminder => patterns.reminder,
+        NotificationEvent::PauseTimeout => patterns.pause_timeout,
+        NotificationEvent::CycleEnd => patterns.cycle_end,
+        NotificationEvent::WelcomeBack => patterns.welcome_back,
+        NotificationEvent::WeeklyGoal => patterns.weekly_goal,
+        NotificationEvent::DailyGoal => patterns.daily_goal,
+        NotificationEvent::CountdownEnd => patterns.countdown_end,
+    }
+}
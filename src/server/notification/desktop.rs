@@ -0,0 +1,124 @@
+use super::{Notification, NotificationButtons, Notifier};
+use crate::server::action_queue::ActionSender;
+use anyhow::Context;
+use async_trait::async_trait;
+use notify_rust::{Notification as OsNotification, Timeout};
+use tokio::task::spawn_blocking;
+
+/// Sends an OS-notification, which shows the break suggestion at the start of each break.
+/// If it has been created with a timer input, the notification offers the
+/// [buttons](Notification::buttons) of the notification and applies the action of the clicked
+/// one to the timer (this requires a notification daemon implementing the freedesktop
+/// specification).
+#[derive(Debug, Clone)]
+pub struct DesktopNotifier {
+    timer_input_sender: Option<ActionSender>,
+}
+
+impl DesktopNotifier {
+    /// Creates a notifier, which sends the actions of clicked buttons to the given timer input -
+    /// without one, notifications have no buttons
+    pub fn new(timer_input_sender: Option<ActionSender>) -> Self {
+        Self { timer_input_sender }
+    }
+}
+
+#[async_trait]
+impl Notifier for DesktopNotifier {
+    fn name(&self) -> &'static str {
+        "OS"
+    }
+
+    fn shows_suggestions(&self) -> bool {
+        true
+    }
+
+    async fn notify(&self, notification: &Notification) -> anyhow::Result<()> {
+        let Some(mut body) = notification.message.clone() else {
+            return Ok(());
+        };
+
+        if let Some(suggestion) = &notification.suggestion {
+            body = format!("{}\n\n{}", body, suggestion);
+        }
+
+        let summary = match (&notification.reminder, &notification.task) {
+            (Some(reminder), _) => format!("\u{25E6} zentime: {}", reminder),
+            (None, Some(task)) => format!("\u{25EF} zentime: {}", task),
+            (None, None) => "\u{25EF} zentime".to_string(),
+        };
+
+        let timeout = notification
+            .expires_after
+            .map_or(Timeout::Default, |duration| {
+                Timeout::Milliseconds(u32::try_from(duration.as_millis()).unwrap_or(u32::MAX))
+            });
+
+        let buttons = notification
+            .buttons
+            .clone()
+            .zip(self.timer_input_sender.clone());
+        let actions = buttons
+            .as_ref()
+            .map(|(buttons, _)| buttons.actions.clone())
+            .unwrap_or_default();
+
+        // Sending the notification might block (e.g. while waiting for the notification daemon)
+        let handle = spawn_blocking(move || {
+            let mut os_notification = OsNotification::new();
+            os_notification
+                .summary(&summary)
+                .body(&body)
+                .timeout(timeout);
+
+            for action in actions {
+                os_notification.action(action.id(), action.label());
+            }
+
+            os_notification.show()
+        })
+        .await
+        .context("Notification task failed")?
+        .context("Could not show notification")?;
+
+        if let Some((buttons, timer_input_sender)) = buttons {
+            wait_for_button(handle, buttons, timer_input_sender);
+        }
+
+        Ok(())
+    }
+}
+
+/// Waits on a blocking task until a button of the notification has been clicked (or the
+/// notification has been closed) and sends the action of the button to the timer
+#[cfg(all(unix, not(target_os = "macos")))]
+fn wait_for_button(
+    handle: notify_rust::NotificationHandle,
+    buttons: NotificationButtons,
+    timer_input_sender: ActionSender,
+) {
+    use super::NotificationAction;
+    use crate::server::action_queue::TimerInput;
+    use log::info;
+
+    spawn_blocking(move || {
+        handle.wait_for_action(|id| {
+            let Some(action) = NotificationAction::from_id(id) else {
+                return;
+            };
+
+            info!("Notification button has been clicked: {}", action.label());
+            timer_input_sender.send_input(TimerInput::NotificationAction {
+                action: action.timer_action(),
+                round: buttons.round,
+                is_break: buttons.is_break,
+            });
+        })
+    });
+}
+
+/// Clicks are only reported by notification daemons implementing the freedesktop specification
+/// (macOS would require a main run loop, which the server does not have), therefore the
+/// notification is not waited for
+#[cfg(not(all(unix, not(target_os = "macos"))))]
+fn wait_for_button<H>(_handle: H, _buttons: NotificationButtons, _sender: ActionSender) {}
@@ -0,0 +1,39 @@
+use super::{Notification, Notifier};
+use anyhow::bail;
+use async_trait::async_trait;
+
+/// Runs a shell command.
+/// Information about the notification is passed to the command as environment variables
+/// (see [hooks](crate::hooks)).
+#[derive(Debug)]
+pub struct HookNotifier {
+    command: String,
+}
+
+impl HookNotifier {
+    /// Creates a notifier which runs the given shell command
+    pub fn new(command: String) -> Self {
+        Self { command }
+    }
+}
+
+#[async_trait]
+impl Notifier for HookNotifier {
+    fn name(&self) -> &'static str {
+        "hook"
+    }
+
+    fn shows_suggestions(&self) -> bool {
+        true
+    }
+
+    async fn notify(&self, notification: &Notification) -> anyhow::Result<()> {
+        let status = notification.hook_env().run(&self.command).await?;
+
+        if !status.success() {
+            bail!("'{}' exited with {}", self.command, status);
+        }
+
+        Ok(())
+    }
+}
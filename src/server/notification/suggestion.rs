@@ -0,0 +1,211 @@
+//! Suggestions of what to do during a break (e.g. a stretching exercise), which are added to the
+//! notifications at the start of each break.
+//!
+//! Suggestions come from a provider (see [NotificationConfig::suggestion_provider]), which is
+//! either the built-in one or an external command. An external provider is run like a hook
+//! command (see [hooks](crate::hooks)) and has to print a single JSON object:
+//!
+//! ```json
+//! {"title": "Neck rolls", "description": "Slowly roll your head in circles, five times each way"}
+//! ```
+//!
+//! The `description` is optional. If the command fails, does not finish in time or prints
+//! anything else, the built-in provider steps in, so that a broken script never costs a
+//! suggestion.
+
+use std::fmt::Display;
+use std::process::Stdio;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::{bail, Context};
+use log::warn;
+#[cfg(feature = "notifications")]
+use rand::{seq::SliceRandom, thread_rng};
+use serde::{Deserialize, Serialize};
+use tokio::time::timeout;
+
+use crate::config::NotificationConfig;
+use crate::hooks::HookEnv;
+
+/// Value of [NotificationConfig::suggestion_provider] which selects the built-in provider
+pub const BUILTIN_PROVIDER: &str = "builtin";
+
+/// Time an external provider has to print its suggestion
+const PROVIDER_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Exercises of the built-in provider (title, description)
+const STRETCHING_EXERCISES: &[(&str, &str)] = &[
+    (
+        "Neck rolls",
+        "Slowly roll your head in circles, five times each way",
+    ),
+    (
+        "Shoulder shrugs",
+        "Pull your shoulders up to your ears, hold for a moment and let them drop - ten times",
+    ),
+    (
+        "Chest opener",
+        "Interlace your fingers behind your back, straighten your arms and lift your chest",
+    ),
+    (
+        "Standing forward fold",
+        "Stand up, fold forward from the hips and let your head and arms hang loose",
+    ),
+    (
+        "Seated twist",
+        "Sit upright and twist to each side, holding onto the backrest for 20 seconds",
+    ),
+    (
+        "Wrist circles",
+        "Circle your wrists ten times each way, then gently pull back your fingers",
+    ),
+    (
+        "Calf raises",
+        "Stand up and rise onto your toes fifteen times",
+    ),
+    (
+        "Hip flexor stretch",
+        "Step into a lunge and sink your hips forward - 30 seconds each side",
+    ),
+    (
+        "Side stretch",
+        "Reach one arm over your head and lean to the opposite side - 20 seconds each side",
+    ),
+    (
+        "Eye break",
+        "Look at something at least 20 feet away for 20 seconds, then close your eyes",
+    ),
+    (
+        "Walk around",
+        "Get up, walk a few steps and refill your glass of water",
+    ),
+    (
+        "Upper back stretch",
+        "Stretch your arms out in front of you, round your upper back and push your hands away",
+    ),
+];
+
+/// Suggestion of what to do during a break
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BreakSuggestion {
+    /// Short name of the suggestion (e.g. "Neck rolls")
+    pub title: String,
+
+    /// Instructions of the suggestion
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl BreakSuggestion {
+    /// Suggestion which consists of a title only
+    pub fn new(title: impl ToString) -> Self {
+        Self {
+            title: title.to_string(),
+            description: None,
+        }
+    }
+}
+
+impl Display for BreakSuggestion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.description {
+            Some(description) => write!(f, "{}: {}", self.title, description),
+            None => write!(f, "{}", self.title),
+        }
+    }
+}
+
+/// Provides a suggestion at the start of each break.
+/// The built-in suggestions are shuffled once per session (i.e. when the server starts or reloads
+/// its config) and handed out one after another, so that no suggestion repeats before all of them
+/// have been made. Builds without the `notifications` feature hand them out in order.
+#[derive(Debug)]
+pub struct SuggestionProvider {
+    command: Option<String>,
+    suggestions: Vec<BreakSuggestion>,
+    remaining: Mutex<Vec<BreakSuggestion>>,
+}
+
+impl SuggestionProvider {
+    /// Creates the provider which has been configured - [None] if suggestions are disabled
+    pub fn from_config(config: &NotificationConfig) -> Option<Self> {
+        let provider = config
+            .suggestion_provider
+            .as_deref()
+            .or_else(|| config.break_suggestions.as_ref().map(|_| BUILTIN_PROVIDER))?;
+
+        let suggestions = match &config.break_suggestions {
+            Some(suggestions) if !suggestions.is_empty() => {
+                suggestions.iter().map(BreakSuggestion::new).collect()
+            }
+            _ => STRETCHING_EXERCISES
+                .iter()
+                .map(|(title, description)| BreakSuggestion {
+                    title: title.to_string(),
+                    description: Some(description.to_string()),
+                })
+                .collect(),
+        };
+
+        Some(Self {
+            command: (provider != BUILTIN_PROVIDER).then(|| provider.to_string()),
+            suggestions,
+            remaining: Mutex::default(),
+        })
+    }
+
+    /// Fetches the suggestion for the break which is about to start.
+    /// An external provider receives the given environment.
+    pub async fn suggest(&self, env: &HookEnv) -> Option<BreakSuggestion> {
+        if let Some(command) = &self.command {
+            match run_provider(command, env).await {
+                Ok(suggestion) => return Some(suggestion),
+                Err(error) => warn!(
+                    "Suggestion provider failed - suggesting a built-in exercise instead: {:#}",
+                    error
+                ),
+            }
+        }
+
+        self.next_builtin()
+    }
+
+    /// Next one of the built-in suggestions - starts over with a newly shuffled set, once all
+    /// of them have been made
+    fn next_builtin(&self) -> Option<BreakSuggestion> {
+        let mut remaining = self
+            .remaining
+            .lock()
+            .expect("Suggestions have been poisoned");
+
+        if remaining.is_empty() {
+            *remaining = self.suggestions.iter().rev().cloned().collect();
+            #[cfg(feature = "notifications")]
+            remaining.shuffle(&mut thread_rng());
+        }
+
+        remaining.pop()
+    }
+}
+
+/// Runs an external provider and parses the suggestion it has printed
+async fn run_provider(command: &str, env: &HookEnv) -> anyhow::Result<BreakSuggestion> {
+    let child = env
+        .command(command)
+        .stdin(Stdio::null())
+        .kill_on_drop(true)
+        .output();
+
+    let output = timeout(PROVIDER_TIMEOUT, child)
+        .await
+        .context(format!("'{}' did not finish in time", command))?
+        .context(format!("Could not run '{}'", command))?;
+
+    if !output.status.success() {
+        bail!("'{}' exited with {}", command, output.status);
+    }
+
+    serde_json::from_slice(&output.stdout)
+        .context(format!("'{}' did not print a suggestion as JSON", command))
+}
@@ -0,0 +1,73 @@
+use super::{BreakSuggestion, Notification, Notifier};
+use crate::secrets;
+use anyhow::{bail, Context};
+use async_trait::async_trait;
+use serde::Serialize;
+use std::time::Duration;
+use tokio::task::spawn_blocking;
+
+/// Maximum time a webhook request may take
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// JSON body of a webhook request
+#[derive(Debug, Serialize)]
+struct WebhookPayload {
+    event: String,
+    round: u64,
+    reminder: Option<String>,
+    task: Option<String>,
+    message: Option<String>,
+    suggestion: Option<BreakSuggestion>,
+}
+
+/// Sends a JSON `POST` request to a URL.
+/// The URL may be a secret reference (see [secrets](crate::secrets)).
+#[derive(Debug)]
+pub struct WebhookNotifier {
+    url: String,
+}
+
+impl WebhookNotifier {
+    /// Creates a notifier which posts to the given URL
+    pub fn new(url: String) -> Self {
+        Self { url }
+    }
+}
+
+#[async_trait]
+impl Notifier for WebhookNotifier {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    fn shows_suggestions(&self) -> bool {
+        true
+    }
+
+    async fn notify(&self, notification: &Notification) -> anyhow::Result<()> {
+        let url = self.url.clone();
+        let payload = WebhookPayload {
+            event: notification.event.to_string(),
+            round: notification.round,
+            reminder: notification.reminder.clone(),
+            task: notification.task.clone(),
+            message: notification.message.clone(),
+            suggestion: notification.suggestion.clone(),
+        };
+
+        spawn_blocking(move || -> anyhow::Result<()> {
+            let url = secrets::resolve(&url)?;
+
+            // Errors only describe the failure in general, because the resolved URL might
+            // contain a secret token
+            match ureq::post(&url).timeout(REQUEST_TIMEOUT).send_json(payload) {
+                Ok(_) => Ok(()),
+                Err(ureq::Error::Status(status, _)) => bail!("Server responded with {}", status),
+                Err(error) => bail!("{}", error.kind()),
+            }
+        })
+        .await
+        .context("Webhook task failed")?
+        .context(format!("Request to {} failed", self.url))
+    }
+}
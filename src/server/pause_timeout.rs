@@ -0,0 +1,55 @@
+//! Ends pauses which last longer than configured (see [PauseConfig])
+
+use std::time::SystemTime;
+use zentime_rs_timer::clock;
+use zentime_rs_timer::sequence::Schedule;
+use zentime_rs_timer::ticking_pomodoro_timer::TickingPomodoroTimer;
+
+use crate::config::{PauseConfig, PauseTimeoutAction};
+
+/// Keeps track of how long a started interval/break has been paused.
+/// Phases which have not been started yet and holds (which end on their own) don't count as
+/// pauses.
+#[derive(Debug, Clone)]
+pub struct PauseTimeout {
+    config: PauseConfig,
+    paused_since: Option<SystemTime>,
+}
+
+impl PauseTimeout {
+    /// Creates a new pause timeout, which does nothing if it has been disabled
+    pub fn new(config: PauseConfig) -> Self {
+        Self {
+            config,
+            paused_since: None,
+        }
+    }
+
+    /// Checks the current pause of the timer.
+    /// Returns the action which should be applied, once the pause has lasted for too long.
+    pub fn tick(
+        &mut self,
+        timer: &TickingPomodoroTimer<Schedule>,
+        is_on_hold: bool,
+    ) -> Option<PauseTimeoutAction> {
+        let max_pause = self.config.max_pause()?;
+
+        let is_interrupted =
+            timer.timer().is_paused() && !timer.timer().elapsed_time().is_zero() && !is_on_hold;
+
+        if !is_interrupted {
+            self.paused_since = None;
+            return None;
+        }
+
+        let now = clock::system_now();
+        let paused_since = *self.paused_since.get_or_insert(now);
+
+        if now.duration_since(paused_since).unwrap_or_default() < max_pause {
+            return None;
+        }
+
+        self.paused_since = None;
+        Some(self.config.on_timeout)
+    }
+}
@@ -0,0 +1,87 @@
+//! Keeps the [Plan] of the current day, which the timer works through
+
+use std::path::PathBuf;
+
+use log::{error, info};
+
+use crate::config::PlanConfig;
+use crate::plan::Plan;
+use crate::wall_clock::WallClock;
+
+/// Plan of the current day, which is written to the plan file on each change.
+/// The plan is small, therefore it is written right away instead of on a blocking task.
+#[derive(Debug)]
+pub struct Planner {
+    path: PathBuf,
+    wall_clock: WallClock,
+    plan: Plan,
+}
+
+impl Planner {
+    /// Reads the plan of the current day (an unreadable plan file is replaced by an empty plan)
+    pub fn new(config: &PlanConfig, wall_clock: WallClock) -> Self {
+        let path = config.path();
+        let today = wall_clock.today();
+        let plan = Plan::load(&path, today).unwrap_or_else(|error| {
+            error!("Could not restore the plan of today: {:#}", error);
+            Plan::new(today)
+        });
+
+        Self {
+            path,
+            wall_clock,
+            plan,
+        }
+    }
+
+    /// Adds a task to the plan
+    pub fn add(&mut self, task: String, estimate: u16) {
+        info!("Planned {} interval(s) for: {}", estimate, task);
+        self.plan_of_today().add(task, estimate);
+        self.save();
+    }
+
+    /// Removes all tasks from the plan
+    pub fn clear(&mut self) {
+        info!("Plan has been cleared");
+        self.plan_of_today().items.clear();
+        self.save();
+    }
+
+    /// Attributes a completed interval to the current task
+    pub fn complete_interval(&mut self) {
+        if let Some(item) = self.plan_of_today().complete_interval() {
+            info!(
+                "Completed interval {}/{} of: {}",
+                item.completed, item.estimate, item.task
+            );
+            self.save();
+        }
+    }
+
+    /// Task which is currently worked on
+    pub fn current_task(&self) -> Option<String> {
+        if self.plan.date != self.wall_clock.today() {
+            return None;
+        }
+
+        self.plan.current().map(|item| item.task.clone())
+    }
+
+    /// Plan of the current day - the plan of the previous day is dropped after midnight
+    fn plan_of_today(&mut self) -> &mut Plan {
+        let today = self.wall_clock.today();
+
+        if self.plan.date != today {
+            self.plan = Plan::new(today);
+        }
+
+        &mut self.plan
+    }
+
+    fn save(&self) {
+        if let Err(error) = self.plan.save(&self.path) {
+            error!("Could not save plan: {:#}", error);
+        }
+    }
+}
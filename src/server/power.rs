@@ -0,0 +1,133 @@
+//! Detection of the current power status (e.g. whether a laptop is running on battery)
+
+use log::warn;
+use std::fs::{read_dir, read_to_string};
+use std::time::{Duration, Instant};
+use tokio::process::Command;
+
+use crate::config::PowerConfig;
+
+/// How often the power status is queried from the operating system
+const POWER_STATUS_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Current power source of the machine
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PowerStatus {
+    /// Machine is connected to a power supply
+    Ac,
+
+    /// Machine is running on battery with the given charge in percent
+    Battery(u8),
+
+    /// Power status could not be determined (e.g. unsupported platform)
+    Unknown,
+}
+
+/// Queries the current [PowerStatus] from the operating system
+pub async fn power_status() -> PowerStatus {
+    if cfg!(target_os = "linux") {
+        linux_power_status()
+    } else if cfg!(target_os = "macos") {
+        macos_power_status().await
+    } else {
+        PowerStatus::Unknown
+    }
+}
+
+/// Reads battery information from `/sys/class/power_supply`
+fn linux_power_status() -> PowerStatus {
+    let Ok(entries) = read_dir("/sys/class/power_supply") else {
+        return PowerStatus::Unknown;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let read = |file: &str| {
+            read_to_string(path.join(file))
+                .map(|content| content.trim().to_string())
+                .unwrap_or_default()
+        };
+
+        if read("type") != "Battery" {
+            continue;
+        }
+
+        if read("status") != "Discharging" {
+            return PowerStatus::Ac;
+        }
+
+        return match read("capacity").parse::<u8>() {
+            Ok(capacity) => PowerStatus::Battery(capacity),
+            Err(_) => PowerStatus::Unknown,
+        };
+    }
+
+    PowerStatus::Ac
+}
+
+/// Parses the output of `pmset -g batt`, e.g.:
+/// `-InternalBattery-0 (id=1234) 85%; discharging; 4:10 remaining present: true`.
+/// The command is awaited, so that it doesn't block the timer task.
+async fn macos_power_status() -> PowerStatus {
+    let output = match Command::new("pmset").args(["-g", "batt"]).output().await {
+        Ok(output) => String::from_utf8_lossy(&output.stdout).to_string(),
+        Err(error) => {
+            warn!("Could not query power status: {}", error);
+            return PowerStatus::Unknown;
+        }
+    };
+
+    if !output.contains("discharging") {
+        return PowerStatus::Ac;
+    }
+
+    output
+        .split_whitespace()
+        .find_map(|word| word.strip_suffix("%;"))
+        .and_then(|percentage| percentage.parse::<u8>().ok())
+        .map_or(PowerStatus::Unknown, PowerStatus::Battery)
+}
+
+/// Caches the [PowerStatus] and determines if zentime should currently save power.
+/// The status is only refreshed every [POWER_STATUS_CHECK_INTERVAL], because querying it
+/// on every timer tick would defeat the purpose.
+#[derive(Debug, Clone, Copy)]
+pub struct PowerMonitor {
+    config: PowerConfig,
+    status: PowerStatus,
+    last_check: Option<Instant>,
+}
+
+impl PowerMonitor {
+    /// Creates a new monitor, which has not yet queried the power status
+    pub fn new(config: PowerConfig) -> Self {
+        Self {
+            config,
+            status: PowerStatus::Unknown,
+            last_check: None,
+        }
+    }
+
+    /// Denotes if the machine is running on battery below the configured threshold
+    pub async fn is_saving_power(&mut self) -> bool {
+        if !self.config.battery_saver {
+            return false;
+        }
+
+        let needs_check = self
+            .last_check
+            .is_none_or(|last_check| last_check.elapsed() >= POWER_STATUS_CHECK_INTERVAL);
+
+        if needs_check {
+            self.status = power_status().await;
+            self.last_check = Some(Instant::now());
+        }
+
+        matches!(self.status, PowerStatus::Battery(percentage) if percentage < self.config.battery_threshold)
+    }
+
+    /// Denotes if sound playback should currently be skipped
+    pub async fn should_mute(&mut self) -> bool {
+        self.config.mute_on_low_battery && self.is_saving_power().await
+    }
+}
@@ -0,0 +1,120 @@
+//! Keeps track of the timer profile which is currently active (see [TimerProfile](crate::config::TimerProfile))
+//! and of the schedule (see [ScheduleConfig](crate::config::ScheduleConfig)), which changes the
+//! timer lengths of the active profile by the day of the week and during quiet hours.
+
+use anyhow::anyhow;
+use chrono::{Datelike, NaiveDateTime, Weekday};
+use log::{info, warn};
+use zentime_rs_timer::config::PomodoroTimerConfig;
+
+use crate::config::{Config, DEFAULT_PROFILE};
+use crate::wall_clock::WallClock;
+
+/// Timer profiles of the config and the name of the active one
+#[derive(Debug, Clone)]
+pub struct Profiles {
+    config: Config,
+    active: String,
+    wall_clock: WallClock,
+
+    /// Day and auto-start permission the timer config has last been evaluated for
+    scheduled: (Weekday, bool),
+}
+
+impl Profiles {
+    /// Activates the profile the server has been configured to start with.
+    /// Fails if there is no such profile.
+    pub fn from_config(config: &Config) -> anyhow::Result<Self> {
+        let wall_clock = WallClock::from_config(&config.clock);
+        let mut profiles = Self {
+            config: config.clone(),
+            active: DEFAULT_PROFILE.to_string(),
+            wall_clock,
+            scheduled: schedule_key(config, wall_clock.now()),
+        };
+
+        if let Some(name) = &config.profile {
+            profiles.switch(name)?;
+        }
+
+        Ok(profiles)
+    }
+
+    /// Name of the active profile
+    pub fn active(&self) -> &str {
+        &self.active
+    }
+
+    /// Timer config of the active profile according to the schedule of the current day
+    pub fn timers(&self) -> PomodoroTimerConfig {
+        self.config
+            .profile_timers(&self.active, self.wall_clock.now())
+            .unwrap_or(self.config.timers)
+    }
+
+    /// Returns the timer config of the active profile, if the schedule has changed it since the
+    /// last call (e.g. because another day has started at midnight or quiet hours have begun)
+    pub fn scheduled_change(&mut self) -> Option<PomodoroTimerConfig> {
+        let key = schedule_key(&self.config, self.wall_clock.now());
+
+        if key == self.scheduled {
+            return None;
+        }
+
+        let (weekday, allows_auto_start) = key;
+        info!(
+            "Following the schedule of {} ({})",
+            weekday,
+            if allows_auto_start {
+                "auto-start allowed"
+            } else {
+                "quiet hours without auto-start"
+            }
+        );
+        self.scheduled = key;
+
+        Some(self.timers())
+    }
+
+    /// Activates the given profile and returns its timer config
+    pub fn switch(&mut self, name: &str) -> anyhow::Result<PomodoroTimerConfig> {
+        let now = self.wall_clock.now();
+        let timers = self.config.profile_timers(name, now).ok_or_else(|| {
+            anyhow!(
+                "Unknown timer profile '{}' (available: {})",
+                name,
+                self.config.profile_names().join(", ")
+            )
+        })?;
+
+        info!("Switched to timer profile '{}'", name);
+        self.active = name.to_string();
+
+        Ok(timers)
+    }
+
+    /// Takes the profiles of a reloaded config and returns the timer config of the active
+    /// profile. If the active profile has been removed, the default profile is activated.
+    pub fn reload(&mut self, config: &Config) -> PomodoroTimerConfig {
+        self.config = config.clone();
+        self.wall_clock = WallClock::from_config(&config.clock);
+
+        let now = self.wall_clock.now();
+        self.scheduled = schedule_key(config, now);
+
+        if self.config.profile_timers(&self.active, now).is_none() {
+            warn!(
+                "Timer profile '{}' has been removed - switching to '{}'",
+                self.active, DEFAULT_PROFILE
+            );
+            self.active = DEFAULT_PROFILE.to_string();
+        }
+
+        self.timers()
+    }
+}
+
+/// Everything the schedule evaluates the timer config by
+fn schedule_key(config: &Config, now: NaiveDateTime) -> (Weekday, bool) {
+    (now.weekday(), config.schedule.allows_auto_start(now))
+}
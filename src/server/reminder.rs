@@ -0,0 +1,126 @@
+//! Recurring reminders (e.g. micro-breaks or drinking some water), which are scheduled
+//! independently of intervals and breaks
+
+use log::warn;
+use std::time::{Duration, Instant};
+use zentime_rs_timer::clock;
+use zentime_rs_timer::pomodoro_timer::{ReminderState, ViewState};
+use zentime_rs_timer::util::seconds_to_time;
+
+use crate::config::ReminderConfig;
+
+/// State of a single reminder channel
+#[derive(Debug, Clone)]
+struct Reminder {
+    config: ReminderConfig,
+
+    /// Time which has been counted since the last reminder
+    elapsed: Duration,
+
+    /// Point in time until which the last reminder is displayed by clients
+    displayed_until: Option<Instant>,
+}
+
+impl Reminder {
+    fn reset(&mut self) {
+        self.elapsed = Duration::ZERO;
+        self.displayed_until = None;
+    }
+}
+
+/// Sends each reminder after [ReminderConfig::interval] of running time.
+/// Only time in which the timer is running (and not on hold) is counted.
+#[derive(Debug, Clone)]
+pub struct ReminderScheduler {
+    reminders: Vec<Reminder>,
+    time_jump_threshold: Duration,
+    last_tick: Option<Instant>,
+}
+
+impl ReminderScheduler {
+    /// Creates a new scheduler.
+    /// Gaps between two ticks of at least `time_jump_threshold` (e.g. while the system has been
+    /// suspended) are not counted.
+    pub fn new(configs: Vec<ReminderConfig>, time_jump_threshold: Duration) -> Self {
+        let reminders = configs
+            .into_iter()
+            .filter(|config| {
+                if config.interval == 0 {
+                    warn!("Ignoring reminder '{}' without interval", config.name);
+                }
+
+                config.interval > 0
+            })
+            .map(|config| Reminder {
+                config,
+                elapsed: Duration::ZERO,
+                displayed_until: None,
+            })
+            .collect();
+
+        Self {
+            reminders,
+            time_jump_threshold,
+            last_tick: None,
+        }
+    }
+
+    /// Advances all reminders according to the current timer state.
+    /// Returns the configs of all reminders which are due.
+    pub fn tick(&mut self, state: &ViewState, is_on_hold: bool) -> Vec<ReminderConfig> {
+        let now = clock::now();
+        let elapsed = self
+            .last_tick
+            .map(|last_tick| now.saturating_duration_since(last_tick))
+            .unwrap_or_default();
+        self.last_tick = Some(now);
+
+        let is_running = !state.is_paused && !is_on_hold && elapsed < self.time_jump_threshold;
+        let mut due = vec![];
+
+        for reminder in &mut self.reminders {
+            if reminder.displayed_until.is_some_and(|until| now >= until) {
+                reminder.displayed_until = None;
+            }
+
+            if reminder.config.focus_only && state.is_break {
+                reminder.reset();
+                continue;
+            }
+
+            if !is_running {
+                continue;
+            }
+
+            reminder.elapsed += elapsed;
+
+            if reminder.elapsed < Duration::from_secs(reminder.config.interval) {
+                continue;
+            }
+
+            reminder.elapsed = Duration::ZERO;
+            reminder.displayed_until = (reminder.config.duration > 0)
+                .then(|| now + Duration::from_secs(reminder.config.duration));
+
+            due.push(reminder.config.clone());
+        }
+
+        due
+    }
+
+    /// State of the reminder which is currently displayed by clients
+    pub fn state(&self) -> Option<ReminderState> {
+        let now = clock::now();
+
+        self.reminders.iter().find_map(|reminder| {
+            let remaining = reminder.displayed_until?.saturating_duration_since(now);
+
+            Some(ReminderState {
+                name: reminder.config.name.clone(),
+                message: reminder.config.message.clone(),
+                // Round up, so that the reminder disappears when 00:00 is displayed
+                remaining: seconds_to_time(remaining.as_millis().div_ceil(1000) as u64),
+            })
+        })
+    }
+}
@@ -0,0 +1,330 @@
+//! Records ended intervals and breaks into the [History]
+
+use chrono::NaiveDate;
+#[cfg(feature = "history")]
+use log::error;
+use log::info;
+#[cfg(not(feature = "history"))]
+use log::warn;
+use std::time::SystemTime;
+#[cfg(feature = "history")]
+use tokio::task::spawn_blocking;
+use zentime_rs_timer::clock;
+use zentime_rs_timer::pomodoro_timer::DailyStats;
+use zentime_rs_timer::sequence::Schedule;
+use zentime_rs_timer::ticking_pomodoro_timer::{PomodoroPhase, TickingPomodoroTimer};
+
+use crate::config::HistoryConfig;
+#[cfg(feature = "history")]
+use crate::history::History;
+use crate::history::{unix_timestamp, CycleSummary, SessionKind, SessionOutcome, SessionRecord};
+use crate::wall_clock::WallClock;
+
+/// Compares timer states before and after a tick or action and appends a [SessionRecord] to
+/// the history, whenever a session has ended.
+/// The sessions of the current pomodoro cycle are kept as well, so that the cycle can be
+/// summarized once its long break has ended.
+/// Besides that, the intervals of the current day are counted (see [DailyStats]) and compared
+/// with the daily goal.
+#[derive(Debug, Clone)]
+pub struct SessionRecorder {
+    #[cfg(feature = "history")]
+    history: Option<History>,
+
+    wall_clock: WallClock,
+
+    /// Counters of the current day
+    today: Today,
+
+    /// Intervals which should run out per day
+    daily_goal: Option<u16>,
+
+    /// Statistics of today, once the daily goal has been reached and this has not been taken yet
+    reached_daily_goal: Option<DailyStats>,
+
+    /// Reasons the current session has been paused for
+    pause_reasons: Vec<String>,
+
+    /// Context which has been captured at the start of the current interval
+    tags: Vec<String>,
+
+    /// Label of the current session
+    task: Option<String>,
+
+    /// Intervals which are given up early are recorded as broken contracts
+    /// (see [FocusContractConfig](crate::config::FocusContractConfig))
+    focus_contract: bool,
+
+    /// Sessions of the current cycle
+    cycle: Vec<SessionRecord>,
+
+    /// Summary of the last completed cycle, which has not been taken yet
+    cycle_summary: Option<CycleSummary>,
+}
+
+impl SessionRecorder {
+    /// Creates a recorder, which does not write any records if the history has been disabled.
+    /// Otherwise the counters of the current day are restored from the history, so that they
+    /// survive restarts of the server.
+    pub fn new(config: &HistoryConfig, wall_clock: WallClock) -> Self {
+        #[cfg(feature = "history")]
+        let (history, today) = {
+            let history = config
+                .enabled
+                .then(|| History::open(config))
+                .and_then(|result| {
+                    result
+                        .map_err(|error| error!("Sessions won't be recorded: {:#}", error))
+                        .ok()
+                });
+            let mut today = Today::new(&wall_clock);
+
+            if let Some(history) = &history {
+                let since = unix_timestamp(wall_clock.start_of_day(today.date));
+
+                match history.records() {
+                    Ok(records) => records
+                        .iter()
+                        .filter(|record| record.ended_at >= since)
+                        .for_each(|record| today.add(record)),
+                    Err(error) => {
+                        error!("Could not restore the statistics of today: {:#}", error)
+                    }
+                }
+            }
+
+            (history, today)
+        };
+
+        #[cfg(not(feature = "history"))]
+        let today = {
+            if config.enabled {
+                warn!(
+                    "zentime has been built without history support - rebuild it with \
+                    `--features history` or disable `history.enabled`"
+                );
+            }
+
+            Today::new(&wall_clock)
+        };
+
+        Self {
+            #[cfg(feature = "history")]
+            history,
+            wall_clock,
+            today,
+            daily_goal: None,
+            reached_daily_goal: None,
+            pause_reasons: vec![],
+            tags: vec![],
+            task: None,
+            focus_contract: false,
+            cycle: vec![],
+            cycle_summary: None,
+        }
+    }
+
+    /// Statistics of the current day
+    pub fn today(&mut self) -> DailyStats {
+        let today = self.current_day();
+
+        DailyStats {
+            intervals: u16::try_from(today.intervals).unwrap_or(u16::MAX),
+            focused_minutes: u16::try_from(today.focused_seconds / 60).unwrap_or(u16::MAX),
+            goal: self.daily_goal,
+        }
+    }
+
+    /// Counters of the current day, which are reset once the day has changed
+    fn current_day(&mut self) -> &mut Today {
+        if clock::system_now() >= self.today.ends_at {
+            self.today = Today::new(&self.wall_clock);
+        }
+
+        &mut self.today
+    }
+
+    /// Remembers why the timer has been paused, so that the reason is recorded together with
+    /// the current session
+    pub fn add_pause_reason(&mut self, reason: String) {
+        self.pause_reasons.push(reason);
+    }
+
+    /// Remembers the context of the current interval, so that it is recorded together with the
+    /// interval. Only the context which has been captured first (i.e. at the start of the
+    /// interval) is kept.
+    pub fn add_tags(&mut self, tags: Vec<String>) {
+        if self.tags.is_empty() {
+            self.tags = tags;
+        }
+    }
+
+    /// Remembers the label of the current session, so that it is recorded together with the
+    /// session once it ends. Unlike pause reasons and tags, the label is kept for the following
+    /// sessions until it is changed.
+    pub fn set_task(&mut self, task: Option<String>) {
+        self.task = task;
+    }
+
+    /// Enables/Disables the focus contract for the sessions which end from now on
+    pub fn set_focus_contract(&mut self, enabled: bool) {
+        self.focus_contract = enabled;
+    }
+
+    /// Sets the intervals which should run out per day - [None] removes the daily goal
+    pub fn set_daily_goal(&mut self, goal: Option<u16>) {
+        self.daily_goal = goal;
+    }
+
+    /// Returns the statistics of today, if the daily goal has been reached since the last call
+    pub fn take_reached_daily_goal(&mut self) -> Option<DailyStats> {
+        self.reached_daily_goal.take()
+    }
+
+    /// Returns the summary of a cycle, which has ended since the last call
+    pub fn take_cycle_summary(&mut self) -> Option<CycleSummary> {
+        self.cycle_summary.take()
+    }
+
+    /// Records the session `previous` has been in, if `current` has left it.
+    /// Postponed breaks are not recorded themselves, because they are neither focus time
+    /// nor a break - pause reasons are therefore added to the next recorded session.
+    pub fn record(
+        &mut self,
+        previous: &TickingPomodoroTimer<Schedule>,
+        current: &TickingPomodoroTimer<Schedule>,
+        outcome: SessionOutcome,
+    ) {
+        let kind = match previous.phase() {
+            PomodoroPhase::Interval => SessionKind::Interval,
+            PomodoroPhase::ShortBreak => SessionKind::ShortBreak,
+            PomodoroPhase::LongBreak => SessionKind::LongBreak,
+            PomodoroPhase::PostponedShortBreak { .. }
+            | PomodoroPhase::PostponedLongBreak { .. } => return,
+        };
+
+        let planned_seconds = previous.timer().total_time().as_secs();
+        let elapsed_seconds = match outcome {
+            SessionOutcome::Completed => planned_seconds,
+            _ => previous.timer().elapsed_time().as_secs(),
+        };
+
+        let has_left_session = previous.phase() != current.phase()
+            || previous.shared_state().round != current.shared_state().round;
+
+        // Resetting a session which has not been started yet does not end anything
+        if !has_left_session && elapsed_seconds == 0 {
+            return;
+        }
+
+        let broken_contract = self.focus_contract
+            && kind == SessionKind::Interval
+            && matches!(outcome, SessionOutcome::Skipped | SessionOutcome::Reset);
+
+        if broken_contract {
+            info!("Focus contract has been broken");
+        }
+
+        let record = SessionRecord {
+            kind,
+            outcome,
+            round: previous.shared_state().round,
+            ended_at: unix_timestamp(SystemTime::now()),
+            planned_seconds,
+            elapsed_seconds,
+            pause_reasons: std::mem::take(&mut self.pause_reasons),
+            tags: std::mem::take(&mut self.tags),
+            task: self.task.clone(),
+            broken_contract,
+        };
+
+        self.current_day().add(&record);
+
+        // Only the interval which reaches the goal counts, so that it is reached once per day
+        let today = self.today();
+        if kind == SessionKind::Interval
+            && outcome == SessionOutcome::Completed
+            && today.goal == Some(today.intervals)
+        {
+            self.reached_daily_goal = Some(today);
+        }
+
+        let is_cycle_reset = outcome == SessionOutcome::Reset
+            && current.phase() == PomodoroPhase::Interval
+            && current.shared_state().round == 1;
+
+        if is_cycle_reset {
+            self.cycle.clear();
+        } else {
+            self.cycle.push(record.clone());
+        }
+
+        // Postponing does not end the cycle, because the long break is taken afterwards
+        let has_ended_cycle = kind == SessionKind::LongBreak
+            && matches!(outcome, SessionOutcome::Completed | SessionOutcome::Skipped);
+
+        if has_ended_cycle {
+            self.cycle_summary = Some(CycleSummary::from_records(&self.cycle));
+            self.cycle.clear();
+        }
+
+        #[cfg(feature = "history")]
+        {
+            let Some(history) = self.history.clone() else {
+                return;
+            };
+
+            spawn_blocking(move || {
+                if let Err(error) = history.append(&record) {
+                    error!("Could not record session: {:#}", error);
+                }
+            });
+        }
+    }
+}
+
+/// Counters of the current day, which are broadcast with every view state
+#[derive(Debug, Clone)]
+struct Today {
+    /// Day the counters belong to, which is only needed to restore them from the history
+    #[cfg_attr(not(feature = "history"), allow(dead_code))]
+    date: NaiveDate,
+
+    /// Point in time at which the next day starts
+    ends_at: SystemTime,
+
+    /// Intervals which have run out
+    intervals: u64,
+
+    /// Focus time which has elapsed during all intervals (including skipped ones)
+    focused_seconds: u64,
+}
+
+impl Today {
+    fn new(wall_clock: &WallClock) -> Self {
+        let date = wall_clock.today();
+        let ends_at = date.succ_opt().map_or(SystemTime::UNIX_EPOCH, |tomorrow| {
+            wall_clock.start_of_day(tomorrow)
+        });
+
+        Self {
+            date,
+            ends_at,
+            intervals: 0,
+            focused_seconds: 0,
+        }
+    }
+
+    /// Counts an ended session (see [CycleSummary::from_records])
+    fn add(&mut self, record: &SessionRecord) {
+        if record.kind != SessionKind::Interval {
+            return;
+        }
+
+        if record.outcome == SessionOutcome::Completed {
+            self.intervals += 1;
+        }
+
+        self.focused_seconds += record.elapsed_seconds.min(record.planned_seconds);
+    }
+}
@@ -0,0 +1,61 @@
+//! Graceful shutdown of the server.
+//!
+//! A shutdown is requested by a client ([crate::ipc::ClientToServerMsg::Quit]) or by a signal
+//! (SIGTERM/SIGINT, Ctrl+C on Windows), both of which end up as [TimerInput::Shutdown] in the
+//! queue of the timer task. That task saves its state, [announces](announce) the shutdown to all
+//! clients and ends. The listener then stops accepting connections, gives connected clients a
+//! moment to receive the announcement and returns, so that the socket and PID file can be
+//! removed before the process exits.
+
+use std::time::Duration;
+
+use log::{error, info};
+use tokio::sync::broadcast::Sender as BroadcastSender;
+
+use super::action_queue::{ActionSender, TimerInput};
+use super::timer_output::TimerOutputAction;
+
+/// Time connections get to send [crate::ipc::ServerToClientMsg::ShuttingDown] to their clients,
+/// before they are closed anyway
+pub const DRAIN_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Tells all clients that the server is shutting down, after which their connections are
+/// closed. Persisted state has to be saved before, because the process exits soon afterwards.
+pub fn announce(timer_output_sender: &BroadcastSender<TimerOutputAction>) {
+    info!("Shutting down...");
+
+    // Sending only fails if no client is connected
+    timer_output_sender
+        .send(TimerOutputAction::ShuttingDown)
+        .ok();
+}
+
+/// Requests a shutdown as soon as the process receives SIGTERM or SIGINT (or Ctrl+C on
+/// Windows)
+pub async fn forward_signals(timer_input_sender: ActionSender) {
+    match signal().await {
+        Ok(name) => {
+            info!("Received {} - shutting down", name);
+            timer_input_sender.send_input(TimerInput::Shutdown);
+        }
+        Err(error) => error!("Could not listen for signals: {}", error),
+    }
+}
+
+#[cfg(unix)]
+async fn signal() -> std::io::Result<&'static str> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut terminate = signal(SignalKind::terminate())?;
+    let mut interrupt = signal(SignalKind::interrupt())?;
+
+    tokio::select! {
+        _ = terminate.recv() => Ok("SIGTERM"),
+        _ = interrupt.recv() => Ok("SIGINT"),
+    }
+}
+
+#[cfg(not(unix))]
+async fn signal() -> std::io::Result<&'static str> {
+    tokio::signal::ctrl_c().await.map(|_| "Ctrl+C")
+}
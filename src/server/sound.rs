@@ -1,78 +1,91 @@
-//! Sound playback related functions
-use log::{error, info};
-use rodio::decoder::DecoderError;
-use rodio::StreamError;
-use std::io::Cursor;
-use std::thread;
-use thiserror::Error;
+//! Sound playback related functions.
+//! Sounds are only played back with the `audio` feature - otherwise they are dropped.
+mod mixer;
+#[cfg(feature = "audio")]
+mod worker;
+
+pub use mixer::{Mixer, Sound, SoundChannel};
 
-// Code copied from: https://github.com/yuizho/pomors/blob/master/src/sound.rs
+#[cfg(feature = "audio")]
+pub use worker::AudioPlaybackError;
 
-/// Error type that describes error that could happen before/during audio playback
-#[derive(Debug, Error)]
-pub enum AudioPlaybackError {
-    /// Denotes that the given [SoundFile] could not be decoded
-    #[error("Could not decode audio data")]
-    DecodeError(#[from] DecoderError),
+use log::error;
+#[cfg(not(feature = "audio"))]
+use log::warn;
+#[cfg(not(feature = "audio"))]
+use std::sync::mpsc::Receiver;
+use std::sync::mpsc::{channel, Sender};
+use std::thread;
 
-    /// Denotes that no output device could be found
-    #[error("Failed to find output device")]
-    DeviceNotFound(#[from] StreamError),
+#[cfg(feature = "audio")]
+use self::worker::run_worker;
+use crate::config::DuckingConfig;
 
-    /// The sink on the device to playback the sound could not be created
-    #[error("Could not play back sound file because sink could not be created")]
-    SinkNotCreated,
+/// Command handled by the sound worker thread
+#[derive(Debug)]
+#[cfg_attr(not(feature = "audio"), allow(dead_code))]
+enum SoundCommand {
+    Play(Sound),
+    Stop,
 }
 
-/// Play the sound file from sound_file path or the default sound file
-pub fn play(sound_file: Option<String>, volume: f32) -> Result<(), AudioPlaybackError> {
-    let custom_sound = match sound_file {
-        Some(path) => match std::fs::read(path) {
-            Ok(bytes) => Some(SoundFile::Custom(bytes)),
-            Err(error) => {
-                error!("Could not read custom sound file: {}", error);
-                None
-            }
-        },
-        None => None,
-    };
+/// Handle to a worker thread which plays back sounds.
+/// Playback requests are queued up and handled by the worker, so that neither decoding a large
+/// sound file nor a slow audio backend ever blocks the caller. Overlapping sounds are played one
+/// after another by priority (see [Mixer]).
+#[derive(Debug, Clone)]
+pub struct SoundPlayer {
+    commands: Sender<SoundCommand>,
+}
 
-    let sound_file = custom_sound.unwrap_or_else(|| {
-        info!("No custom sound file provided, falling back to default sound");
-        SoundFile::Default
-    });
+impl SoundPlayer {
+    /// Spawns the sound worker thread. The thread terminates as soon as all handles to it have
+    /// been dropped.
+    /// If ducking is enabled, other applications are turned down while a sound is playing.
+    pub fn spawn(ducking: DuckingConfig) -> Self {
+        let (commands, receiver) = channel();
+        let ducking_level = ducking.enabled.then_some(ducking.volume);
 
-    let audio = rodio::Decoder::new(Cursor::new(sound_file.get_bytes()))?;
+        thread::spawn(move || run_worker(receiver, ducking_level));
 
-    thread::spawn(move || -> Result<(), AudioPlaybackError> {
-        let (_stream, stream_handle) = rodio::OutputStream::try_default()?;
-        let sink =
-            rodio::Sink::try_new(&stream_handle).map_err(|_| AudioPlaybackError::SinkNotCreated)?;
-        sink.append(audio);
-        sink.set_volume(volume);
-        sink.sleep_until_end();
-        Ok(())
-    })
-    .join()
-    .unwrap()?;
+        Self { commands }
+    }
 
-    Ok(())
-}
+    /// Plays the sound right away, if nothing more important is playing - otherwise it waits
+    /// for its turn.
+    /// Stopping the sound also cancels all rings which have not been played yet.
+    pub fn play(&self, sound: Sound) {
+        if sound.rings == 0 {
+            return;
+        }
 
-trait FileData {
-    fn get_bytes(&self) -> Vec<u8>;
-}
+        self.send(SoundCommand::Play(sound))
+    }
+
+    /// Stops the sound which is currently playing (if any) and drops all waiting ones
+    pub fn stop(&self) {
+        self.send(SoundCommand::Stop)
+    }
 
-enum SoundFile {
-    Default,
-    Custom(Vec<u8>),
+    fn send(&self, command: SoundCommand) {
+        if self.commands.send(command).is_err() {
+            error!("Sound worker has terminated");
+        }
+    }
 }
 
-impl FileData for SoundFile {
-    fn get_bytes(&self) -> Vec<u8> {
-        match self {
-            SoundFile::Default => include_bytes!("bell.wav").to_vec(),
-            SoundFile::Custom(bytes) => bytes.to_owned(),
+/// Stand-in for the sound worker of builds without the `audio` feature, which drops all sounds
+#[cfg(not(feature = "audio"))]
+fn run_worker(commands: Receiver<SoundCommand>, _ducking_level: Option<f32>) {
+    let mut has_warned = false;
+
+    for command in commands {
+        if matches!(command, SoundCommand::Play { .. }) && !has_warned {
+            warn!(
+                "zentime has been built without audio support - rebuild it with \
+                `--features audio` or disable `notifications.enable_bell`"
+            );
+            has_warned = true;
         }
     }
 }
@@ -0,0 +1,197 @@
+//! Decides which sound is played back, if several of them overlap (e.g. a reminder chime while
+//! the bell is still ringing).
+//!
+//! Only a single sound is played at a time. A sound of a channel with a higher priority
+//! interrupts the current one, which is queued up again and played once the more important
+//! sound has finished. Sounds of the same or a lower priority wait for their turn.
+
+use std::fmt::Display;
+use std::time::{Duration, Instant};
+
+use log::info;
+use serde::{Deserialize, Serialize};
+
+/// Time after which a waiting sound is dropped, because it would be out of place by then
+const MAX_QUEUE_DELAY: Duration = Duration::from_secs(30);
+
+/// Maximum number of waiting sounds - the least important one is dropped beyond this
+const MAX_QUEUED_SOUNDS: usize = 8;
+
+/// Source of a sound, whose volume and priority can be configured
+/// (see [SoundChannels](crate::config::SoundChannels))
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SoundChannel {
+    /// The notification bell at the end of intervals and breaks (and other timer events)
+    Bell,
+
+    /// Chime of reminders (incl. micro-breaks)
+    Reminder,
+}
+
+impl Display for SoundChannel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SoundChannel::Bell => write!(f, "bell"),
+            SoundChannel::Reminder => write!(f, "reminder"),
+        }
+    }
+}
+
+/// Sound which should be played back
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sound {
+    /// Channel the sound is played on
+    pub channel: SoundChannel,
+
+    /// Sounds with a higher priority interrupt the ones with a lower priority
+    pub priority: u8,
+
+    /// Path of the sound file - the default bell sound is played, if this is [None]
+    pub sound_file: Option<String>,
+
+    /// Playback volume (0.0 - 1.0)
+    pub volume: f32,
+
+    /// Number of times the sound is played in a row
+    pub rings: u8,
+
+    /// Silence between two rings
+    pub gap: Duration,
+}
+
+/// Keeps track of the sound which is playing and of the ones waiting for their turn
+#[derive(Debug, Default)]
+pub struct Mixer {
+    playing: Option<Sound>,
+    queue: Vec<(Sound, Instant)>,
+}
+
+impl Mixer {
+    /// Creates a mixer which does not play anything
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sound which is currently playing
+    pub fn playing(&self) -> Option<&Sound> {
+        self.playing.as_ref()
+    }
+
+    /// Number of sounds waiting for their turn
+    pub fn queued(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Hands a new sound to the mixer.
+    /// Returns the sound which has to be started right away (replacing the current playback) -
+    /// [None] if the sound has been queued up.
+    pub fn play(&mut self, sound: Sound, now: Instant) -> Option<Sound> {
+        match self.playing.take() {
+            Some(current) if current.priority >= sound.priority => {
+                self.playing = Some(current);
+                self.enqueue(sound, now);
+                None
+            }
+            interrupted => {
+                if let Some(interrupted) = interrupted {
+                    self.enqueue(interrupted, now);
+                }
+
+                self.playing = Some(sound.clone());
+                Some(sound)
+            }
+        }
+    }
+
+    /// Marks the current sound as finished and returns the sound which has to be started next
+    /// (the most important one, which has been waiting the longest)
+    pub fn finish(&mut self, now: Instant) -> Option<Sound> {
+        self.queue
+            .retain(|(_, queued_at)| now.duration_since(*queued_at) <= MAX_QUEUE_DELAY);
+
+        let next = self
+            .queue
+            .iter()
+            .enumerate()
+            .max_by(|(a_index, (a, _)), (b_index, (b, _))| {
+                a.priority.cmp(&b.priority).then(b_index.cmp(a_index))
+            })
+            .map(|(index, _)| index);
+
+        self.playing = next.map(|index| self.queue.remove(index).0);
+        self.playing.clone()
+    }
+
+    /// Stops the current sound and drops all waiting ones
+    pub fn stop(&mut self) {
+        self.playing = None;
+        self.queue.clear();
+    }
+
+    fn enqueue(&mut self, sound: Sound, now: Instant) {
+        self.queue.push((sound, now));
+
+        if self.queue.len() > MAX_QUEUED_SOUNDS {
+            let least_important = self
+                .queue
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, (sound, _))| sound.priority)
+                .map(|(index, _)| index);
+
+            if let Some(index) = least_important {
+                let (dropped, _) = self.queue.remove(index);
+                info!(
+                    "Too many sounds are waiting - dropping {} sound",
+                    dropped.channel
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plays_overlapping_sounds_by_priority() {
+        let sound = |channel, priority| Sound {
+            channel,
+            priority,
+            sound_file: None,
+            volume: 0.5,
+            rings: 1,
+            gap: Duration::from_millis(250),
+        };
+        let bell = sound(SoundChannel::Bell, 2);
+        let reminder = sound(SoundChannel::Reminder, 1);
+        let now = Instant::now();
+        let mut mixer = Mixer::new();
+
+        assert_eq!(mixer.play(reminder.clone(), now), Some(reminder.clone()));
+
+        // The bell cuts off the reminder, which is played again afterwards
+        assert_eq!(mixer.play(bell.clone(), now), Some(bell.clone()));
+        assert_eq!(mixer.queued(), 1);
+
+        // Neither another reminder nor another bell interrupt the bell
+        assert_eq!(mixer.play(reminder.clone(), now), None);
+        assert_eq!(mixer.play(bell.clone(), now), None);
+        assert_eq!(mixer.playing(), Some(&bell));
+
+        assert_eq!(mixer.finish(now), Some(bell));
+        assert_eq!(mixer.finish(now), Some(reminder.clone()));
+
+        // Sounds which have been waiting for too long are out of place
+        assert_eq!(mixer.finish(now + Duration::from_secs(60)), None);
+        assert_eq!(mixer.queued(), 0);
+
+        mixer.play(reminder.clone(), now);
+        mixer.play(reminder, now);
+        mixer.stop();
+        assert_eq!(mixer.playing(), None);
+        assert_eq!(mixer.finish(now), None);
+    }
+}
@@ -0,0 +1,182 @@
+//! Worker thread, which plays back sounds via rodio
+
+use log::{error, info};
+use rodio::decoder::DecoderError;
+use rodio::{OutputStream, Sink, Source, StreamError};
+use std::io::Cursor;
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+use super::{Mixer, Sound, SoundCommand};
+use crate::server::ducking::{duck, DuckedStreams};
+
+/// Interval in which the worker checks if a sound has finished, while it is playing
+const PLAYBACK_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+// Code copied from: https://github.com/yuizho/pomors/blob/master/src/sound.rs
+
+/// Error type that describes error that could happen before/during audio playback
+#[derive(Debug, Error)]
+pub enum AudioPlaybackError {
+    /// Denotes that the given [SoundFile] could not be decoded
+    #[error("Could not decode audio data")]
+    DecodeError(#[from] DecoderError),
+
+    /// Denotes that no output device could be found
+    #[error("Failed to find output device")]
+    DeviceNotFound(#[from] StreamError),
+
+    /// The sink on the device to playback the sound could not be created
+    #[error("Could not play back sound file because sink could not be created")]
+    SinkNotCreated,
+}
+
+/// Sound which is currently being played back.
+/// The stream has to be kept alive until the playback has finished. Other applications are
+/// turned up again, as soon as the playback is dropped.
+struct Playback {
+    _stream: OutputStream,
+    sink: Sink,
+    _ducked: Option<DuckedStreams>,
+}
+
+pub(super) fn run_worker(commands: Receiver<SoundCommand>, ducking_level: Option<f32>) {
+    let mut mixer = Mixer::new();
+    let mut playback: Option<Playback> = None;
+
+    loop {
+        // While a sound is playing, we have to notice when it has finished, so that other
+        // applications are turned up again (or the next sound is started) right away
+        let command = if playback.is_some() {
+            match commands.recv_timeout(PLAYBACK_POLL_INTERVAL) {
+                Ok(command) => command,
+                Err(RecvTimeoutError::Timeout) => {
+                    if playback
+                        .as_ref()
+                        .is_some_and(|playback| playback.sink.empty())
+                    {
+                        let next = mixer.finish(Instant::now());
+                        playback = start_next(&mut mixer, next, ducking_level);
+                    }
+                    continue;
+                }
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        } else {
+            match commands.recv() {
+                Ok(command) => command,
+                Err(_) => break,
+            }
+        };
+
+        match command {
+            SoundCommand::Play(sound) => {
+                // Otherwise the sound waits until the current one has finished
+                if let Some(sound) = mixer.play(sound, Instant::now()) {
+                    if let Some(playback) = playback.take() {
+                        playback.sink.stop();
+                    }
+
+                    playback = start_next(&mut mixer, Some(sound), ducking_level);
+                }
+            }
+            SoundCommand::Stop => {
+                mixer.stop();
+
+                if let Some(playback) = playback.take() {
+                    playback.sink.stop();
+                }
+            }
+        }
+    }
+}
+
+/// Starts the given sound - or the next waiting one, as long as sounds fail to play
+fn start_next(
+    mixer: &mut Mixer,
+    mut sound: Option<Sound>,
+    ducking_level: Option<f32>,
+) -> Option<Playback> {
+    while let Some(next) = sound {
+        match start_playback(next, ducking_level) {
+            Ok(playback) => return Some(playback),
+            Err(error) => error!("Could not play sound: {}", error),
+        }
+
+        sound = mixer.finish(Instant::now());
+    }
+
+    None
+}
+
+/// Starts playing back the sound file of the given sound (or the default sound file) as often
+/// as it rings, separated by its gap
+fn start_playback(
+    sound: Sound,
+    ducking_level: Option<f32>,
+) -> Result<Playback, AudioPlaybackError> {
+    let Sound {
+        sound_file,
+        volume,
+        rings,
+        gap,
+        ..
+    } = sound;
+
+    let custom_sound = match sound_file {
+        Some(path) => match std::fs::read(path) {
+            Ok(bytes) => Some(SoundFile::Custom(bytes)),
+            Err(error) => {
+                error!("Could not read custom sound file: {}", error);
+                None
+            }
+        },
+        None => None,
+    };
+
+    let sound_file = custom_sound.unwrap_or_else(|| {
+        info!("No custom sound file provided, falling back to default sound");
+        SoundFile::Default
+    });
+
+    let bytes = sound_file.get_bytes();
+
+    let (stream, stream_handle) = OutputStream::try_default()?;
+    let sink = Sink::try_new(&stream_handle).map_err(|_| AudioPlaybackError::SinkNotCreated)?;
+    sink.set_volume(volume);
+
+    // Other applications are turned down before the sound starts, so that it is not drowned out
+    let ducked = ducking_level.map(duck);
+
+    for ring in 0..rings {
+        let audio = rodio::Decoder::new(Cursor::new(bytes.clone()))?;
+        let delay = if ring == 0 { Duration::ZERO } else { gap };
+
+        sink.append(audio.delay(delay));
+    }
+
+    Ok(Playback {
+        _stream: stream,
+        sink,
+        _ducked: ducked,
+    })
+}
+
+trait FileData {
+    fn get_bytes(&self) -> Vec<u8>;
+}
+
+enum SoundFile {
+    Default,
+    Custom(Vec<u8>),
+}
+
+impl FileData for SoundFile {
+    fn get_bytes(&self) -> Vec<u8> {
+        match self {
+            SoundFile::Default => include_bytes!("../bell.wav").to_vec(),
+            SoundFile::Custom(bytes) => bytes.to_owned(),
+        }
+    }
+}
@@ -1,162 +1,1335 @@
-use crate::config::Config;
+use crate::config::{
+    Config, ConfigLoader, NotificationFailurePolicy, PauseTimeoutAction, TimerMode,
+};
+use crate::history::SessionOutcome;
 use crate::ipc::{
-    get_socket_name, ClientToServerMsg, InterProcessCommunication, ServerToClientMsg,
+    get_socket_name, remove_socket_file, ClientToServerMsg, ErrorCode, IpcReader, IpcWriter, Role,
+    ServerInfo, ServerToClientMsg, Snapshot, Transport, PROTOCOL_VERSION,
+};
+use crate::server::action_queue::{
+    action_queue, ActionReceiver, ActionSender, Replier, TimerInput,
 };
-use crate::server::notification::dispatch_notification;
+use crate::server::alignment::IntervalAlignment;
+#[cfg(feature = "notifications")]
+use crate::server::break_countdown;
+#[cfg(feature = "notifications")]
+use crate::server::break_nudge;
+use crate::server::clients::{ClientHandle, Clients};
+use crate::server::context_capture;
+use crate::server::dnd;
+use crate::server::event_hooks::{self, EventHooks};
+#[cfg(feature = "history")]
+use crate::server::goal_summary;
+use crate::server::hold::Hold;
+#[cfg(feature = "http")]
+use crate::server::http;
+use crate::server::idle;
+use crate::server::listener::SocketListener;
+use crate::server::mock::{self, Scenario};
+use crate::server::named_timers::NamedTimers;
+use crate::server::notification::{Notification, NotificationDispatcher, NotifierContext};
+use crate::server::pause_timeout::PauseTimeout;
+use crate::server::planner::Planner;
+use crate::server::power::PowerMonitor;
+use crate::server::profiles::Profiles;
+use crate::server::reminder::ReminderScheduler;
+use crate::server::session_recorder::SessionRecorder;
+use crate::server::shutdown::{self, DRAIN_TIMEOUT};
+use crate::server::sound::SoundPlayer;
+use crate::server::state_file::StateFile;
+use crate::server::stopwatch;
+#[cfg(target_os = "linux")]
+use crate::server::systemd;
+use crate::server::team;
+use crate::server::text_protocol;
 use crate::server::timer_output::TimerOutputAction;
+use crate::server::view_filter::ViewFilter;
+#[cfg(feature = "history")]
+use crate::server::weekly_report;
+use crate::wall_clock::WallClock;
 use anyhow::Context;
-use crossbeam::channel::{unbounded, Sender};
-use interprocess::local_socket::tokio::OwnedWriteHalf;
-use log::{error, info};
-use tokio::task::{spawn_blocking, yield_now};
-use zentime_rs_timer::pomodoro_timer::{PomodoroTimer, TimerKind};
+use futures::{AsyncRead, AsyncReadExt, AsyncWrite};
+use log::{error, info, warn};
+use tokio::runtime::Builder;
+use tokio::task::{yield_now, JoinSet};
+use tokio::time::{interval, sleep_until, timeout, Instant, Interval, MissedTickBehavior};
+use zentime_rs_timer::event::ObservedTimer;
 use zentime_rs_timer::pomodoro_timer_action::PomodoroTimerAction;
+use zentime_rs_timer::sequence::Schedule;
+use zentime_rs_timer::ticking_pomodoro_timer::{PomodoroPhase, TickingPomodoroTimer};
 
-use std::rc::Rc;
+use std::fmt::Display;
+use std::future::{pending, Future};
 use std::sync::Arc;
 use tokio::select;
+use tokio::sync::broadcast::Sender as BroadcastSender;
+use tokio::sync::watch::{self, Receiver as WatchReceiver, Sender as WatchSender};
 use tokio::sync::{self, broadcast::Receiver as BroadcastReceiver};
 
-use futures::io::BufReader;
-use interprocess::local_socket::tokio::{LocalSocketListener, LocalSocketStream};
+use futures::io::{BufReader, Cursor};
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::status::{remove_pid_file, server_status, PidFile, ServerStatus};
 
-use std::time::Duration;
-use tokio::fs::{metadata, remove_file};
+/// Interval in which the timer is advanced and its state is broadcast to clients
+const TICK_INTERVAL: Duration = Duration::from_millis(250);
 
+/// Tick interval used while running on low battery.
+/// This reduces CPU wakeups and the number of view states broadcast to clients, but does not
+/// delay client actions, because those are handled as soon as they arrive.
+const BATTERY_SAVER_TICK_INTERVAL: Duration = Duration::from_secs(1);
 
-use super::status::{server_status, ServerStatus};
+/// Time blocking tasks (e.g. notifications waiting for a click) get to finish, once the server
+/// has shut down
+const RUNTIME_SHUTDOWN_TIMEOUT: Duration = Duration::from_millis(500);
 
 /// Starts the server by opening the zentime socket and listening for incoming connections.
 /// This will just quit if another zentime server process is already running.
+/// The config loader is used whenever a client requests the config to be reloaded.
+/// Returns once the server has shut down gracefully.
 ///
 /// NOTE:
 /// This spawns a tokio runtime and should therefore not be run inside another tokio runtime.
-#[tokio::main]
-pub async fn start(config: Config) -> anyhow::Result<()> {
-    let socket_name = get_socket_name();
+pub fn start(config: Config, config_loader: ConfigLoader) -> anyhow::Result<()> {
+    run(serve(config, config_loader, None))
+}
+
+/// Starts a mock server, which plays back the given scenario instead of running a timer
+/// (see [start]).
+///
+/// NOTE:
+/// This spawns a tokio runtime and should therefore not be run inside another tokio runtime.
+pub fn start_mock(config: Config, scenario: Scenario) -> anyhow::Result<()> {
+    // Scenarios are played back without reloading the config
+    let fixed_config = config.clone();
+    let config_loader: ConfigLoader = Arc::new(move || Ok(fixed_config.clone()));
+
+    run(serve(config, config_loader, Some(scenario)))
+}
+
+/// Runs the server on a new tokio runtime
+fn run(server: impl Future<Output = anyhow::Result<()>>) -> anyhow::Result<()> {
+    let runtime = Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .context("Could not start tokio runtime")?;
+
+    let result = runtime.block_on(server);
 
-    let socket_file_already_exists = metadata(socket_name).await.is_ok();
+    // Dropping the runtime would wait for all blocking tasks, however long they take
+    runtime.shutdown_timeout(RUNTIME_SHUTDOWN_TIMEOUT);
 
-    if socket_file_already_exists && server_status() == ServerStatus::Running {
+    result
+}
+
+/// Listens on the zentime socket, unless another server is already running
+async fn serve(
+    config: Config,
+    config_loader: ConfigLoader,
+    scenario: Option<Scenario>,
+) -> anyhow::Result<()> {
+    #[cfg(target_os = "linux")]
+    let activated_listener = systemd::activated_listener()?;
+    #[cfg(not(target_os = "linux"))]
+    let activated_listener: Option<SocketListener> = None;
+
+    // Pinging the socket of systemd would only queue up a connection for ourselves
+    if activated_listener.is_none() && server_status().await == ServerStatus::Running {
         info!("Server is already running. Terminating this process...");
         // Apparently a server is already running and we don't need to do anything
         return Ok(());
     }
 
-    if socket_file_already_exists {
-        info!("Socket file already exists - removing file");
+    // Another server might have been started at the same time, which has not opened the
+    // socket yet - the lock on the PID file makes sure, that only one of them keeps running
+    let Some(_pid_file) = PidFile::acquire()? else {
+        info!("Server is already starting. Terminating this process...");
+        return Ok(());
+    };
+
+    let listener = match activated_listener {
+        Some(listener) => {
+            info!("Listening on the socket passed by systemd");
+            listener
+        }
+        None => {
+            let socket_name = get_socket_name();
+
+            // We might have a dangling socket file without an attached server process.
+            // In that case we simply remove the file and start a new server process
+            remove_socket_file(socket_name).await?;
 
-        // We have a dangling socket file without an attached server process.
-        // In that case we simply remove the file and start a new server process
-        remove_file(socket_name)
-            .await
-            .context("Could not remove existing socket file")?
+            info!("Binding to socket...");
+            SocketListener::bind(socket_name).await?
+        }
     };
 
     info!("Start listening for connections...");
 
-    listen(config, socket_name)
+    let result = listen(config, config_loader, listener, scenario, true)
         .await
-        .context("Error while listening for connections")?;
+        .context("Error while listening for connections");
 
-    Ok(())
+    // The socket of systemd is kept, so that the next client starts a server again
+    #[cfg(target_os = "linux")]
+    let keep_socket = systemd::is_socket_activated();
+    #[cfg(not(target_os = "linux"))]
+    let keep_socket = false;
+
+    if !keep_socket {
+        info!("Cleaning up socket file");
+        if let Err(error) = remove_socket_file(get_socket_name()).await {
+            error!("{:#}", error);
+        }
+    }
+
+    remove_pid_file();
+
+    result
 }
 
-/// This starts a blocking tokio task which runs the actual synchronous timer logic, but
-/// also listens for incoming client connections and spawns a new async task for each incoming
-/// connection.
-async fn listen(config: Config, socket_name: &str) -> anyhow::Result<()> {
-    info!("Binding to socket...");
-    let listener =
-        LocalSocketListener::bind(socket_name).context("Could not bind to local socket")?;
+/// This spawns a tokio task which drives the timer (or plays back the given mock scenario),
+/// but also listens for incoming client connections and spawns a new async task for each
+/// incoming connection.
+/// Returns once the server has been shut down and its connections have been drained. Signals
+/// only lead to a shutdown, if `handle_signals` is set.
+pub(crate) async fn listen(
+    config: Config,
+    config_loader: ConfigLoader,
+    listener: SocketListener,
+    scenario: Option<Scenario>,
+    handle_signals: bool,
+) -> anyhow::Result<()> {
+    let profiles = Profiles::from_config(&config)?;
 
-    let (timer_input_sender, timer_input_receiver) = unbounded();
+    let (timer_input_sender, timer_input_receiver) = action_queue();
     let (timer_output_sender, _timer_output_receiver) = sync::broadcast::channel(24);
 
     let timer_output_sender = Arc::new(timer_output_sender.clone());
     // Arc clone to create a reference to our sender which can be consumed by the
-    // timer thread. This is necessary because we need a reference to this sender later on
+    // timer task. This is necessary because we need a reference to this sender later on
     // to continuously subscribe to it on incoming client connections
     let timer_out_tx = timer_output_sender.clone();
+    // Latest state of the timer, with which syncing clients are answered right away
+    let (snapshot_sender, snapshot_receiver) = watch::channel(None);
+    let clients = Clients::new(&config.connections);
 
-    spawn_blocking(move || {
-        info!("Starting timer...");
+    if handle_signals {
+        tokio::spawn(shutdown::forward_signals(timer_input_sender.clone()));
+    }
 
-        PomodoroTimer::new(
-            config.timers,
-            Rc::new(move |_, msg, kind| {
-                let result = dispatch_notification(
-                    config.clone().notifications,
-                    msg,
-                    kind == TimerKind::Interval
-                );
+    if config.team.listen.is_some() {
+        let team_input_tx = timer_input_sender.clone();
+        let team_output_tx = timer_output_sender.clone();
+        let team_config = config.team.clone();
+        let team_clients = clients.clone();
+        let team_snapshot_rx = snapshot_receiver.clone();
 
-                if let Err(error) = result {
-                    error!("{}", error);
-                }
-            }),
-            Rc::new(move |view_state| {
-                // Update the view
-                timer_out_tx.send(TimerOutputAction::Timer(view_state)).ok();
-
-                // Handle app actions and hand them to the timer caller
-                match timer_input_receiver.recv_timeout(Duration::from_millis(100)) {
-                    Ok(action) => Some(action),
-                    _ => Some(PomodoroTimerAction::None),
-                }
-            }),
-        )
-        .init()
-    });
+        tokio::spawn(async move {
+            if let Err(error) = team::serve(
+                team_config,
+                team_clients,
+                team_input_tx,
+                team_output_tx,
+                team_snapshot_rx,
+            )
+            .await
+            {
+                error!("Team mode has been disabled: {:#}", error);
+            }
+        });
+    }
+
+    #[cfg(not(feature = "http"))]
+    if let Some(address) = &config.http.listen {
+        warn!(
+            "zentime has been built without HTTP support - rebuild it with `--features http` \
+            to serve {}",
+            address
+        );
+    }
+
+    #[cfg(feature = "http")]
+    if config.http.listen.is_some() {
+        let http_input_tx = timer_input_sender.clone();
+        let http_output_tx = timer_output_sender.clone();
+        let http_config = config.http.clone();
+        let calendar = http::CalendarSource {
+            #[cfg(feature = "history")]
+            history: config.history.clone(),
+            timers: config.timers,
+        };
+
+        tokio::spawn(async move {
+            if let Err(error) =
+                http::serve(http_config, calendar, http_input_tx, http_output_tx).await
+            {
+                error!("HTTP server has been disabled: {:#}", error);
+            }
+        });
+    }
+
+    #[cfg(not(feature = "http"))]
+    if config.api.enabled {
+        warn!(
+            "zentime has been built without HTTP support - rebuild it with `--features http` \
+            or disable `api.enabled`"
+        );
+    }
+
+    #[cfg(feature = "http")]
+    if config.api.enabled {
+        let api_input_tx = timer_input_sender.clone();
+        let api_output_tx = timer_output_sender.clone();
+        let api_config = config.api.clone();
+
+        tokio::spawn(async move {
+            if let Err(error) = http::serve_api(api_config, api_input_tx, api_output_tx).await {
+                error!("JSON API has been disabled: {:#}", error);
+            }
+        });
+    }
+
+    if config.notifications.break_countdown {
+        #[cfg(feature = "notifications")]
+        tokio::spawn(break_countdown::run(timer_output_sender.clone()));
+        #[cfg(not(feature = "notifications"))]
+        warn!(
+            "zentime has been built without OS notifications - rebuild it with \
+            `--features notifications` or disable `notifications.break_countdown`"
+        );
+    }
+
+    if config.break_nudge.enabled {
+        #[cfg(feature = "notifications")]
+        tokio::spawn(break_nudge::run(
+            config.break_nudge.clone(),
+            timer_output_sender.clone(),
+        ));
+        #[cfg(not(feature = "notifications"))]
+        warn!(
+            "zentime has been built without OS notifications - rebuild it with \
+            `--features notifications` or disable `break_nudge.enabled`"
+        );
+    }
+
+    if !config.context.capture.is_empty() {
+        tokio::spawn(context_capture::run(
+            config.context.clone(),
+            timer_input_sender.clone(),
+            timer_output_sender.clone(),
+        ));
+    }
+
+    if config.dnd.enabled {
+        tokio::spawn(dnd::run(config.dnd.clone(), timer_output_sender.clone()));
+    }
+
+    if config.idle.enabled {
+        tokio::spawn(idle::run(
+            config.idle.clone(),
+            timer_input_sender.clone(),
+            timer_output_sender.clone(),
+        ));
+    }
+
+    if config.report.enabled {
+        #[cfg(feature = "history")]
+        tokio::spawn(weekly_report::run(
+            config.report.clone(),
+            config.history.clone(),
+            WallClock::from_config(&config.clock),
+        ));
+        #[cfg(not(feature = "history"))]
+        warn!(
+            "zentime has been built without history support - rebuild it with \
+            `--features history` or disable `report.enabled`"
+        );
+    }
+
+    if config.goals.weekly_summary && config.goals.weekly.is_some() {
+        #[cfg(feature = "history")]
+        tokio::spawn(goal_summary::run(
+            config.goals,
+            config.history.clone(),
+            WallClock::from_config(&config.clock),
+            timer_input_sender.clone(),
+        ));
+        #[cfg(not(feature = "history"))]
+        warn!(
+            "zentime has been built without history support - rebuild it with \
+            `--features history` or disable `goals.weekly_summary`"
+        );
+    }
+
+    // The timer task ends once the server has been told to shut down
+    let mut timer_task = match scenario {
+        Some(scenario) => tokio::spawn(mock::run(
+            scenario,
+            profiles.timers(),
+            timer_input_receiver,
+            timer_out_tx,
+            snapshot_sender,
+        )),
+        None if config.mode == TimerMode::Stopwatch => tokio::spawn(stopwatch::run(
+            profiles.timers(),
+            timer_input_receiver,
+            timer_out_tx,
+            snapshot_sender,
+        )),
+        None => tokio::spawn(run_timer(
+            config,
+            config_loader,
+            profiles,
+            timer_input_sender.clone(),
+            timer_input_receiver,
+            timer_out_tx,
+            snapshot_sender,
+        )),
+    };
+
+    let mut connections = JoinSet::new();
 
     // Set up our loop boilerplate that processes our incoming connections.
     loop {
-        let connection = listener
-            .accept()
-            .await
-            .context("There was an error with an incoming connection")?;
+        let connection = select! {
+            connection = listener.accept() => {
+                connection.context("There was an error with an incoming connection")?
+            },
+            // Finished connections are reaped, so that they don't pile up
+            Some(_) = connections.join_next() => continue,
+            result = &mut timer_task => {
+                if let Err(error) = result {
+                    error!("Timer task has failed: {}", error);
+                }
+
+                break;
+            },
+        };
+
+        if let Err(error) = connection.verify_peer() {
+            warn!("Rejected connection: {}", error);
+            continue;
+        }
+
+        let pid = connection.peer_pid();
 
         let input_tx = timer_input_sender.clone();
         let output_rx = timer_output_sender.subscribe();
+        let snapshot_rx = snapshot_receiver.clone();
+        let clients = clients.clone();
 
         // Spawn new parallel asynchronous tasks onto the Tokio runtime
         // and hand the connection over to them so that multiple clients
         // could be processed simultaneously in a lightweight fashion.
-        tokio::spawn(async move {
+        connections.spawn(async move {
             info!("New connection received.");
-            if let Err(error) = handle_conn(connection, input_tx, output_rx).await {
+
+            let (mut reader, writer) = connection.into_split();
+
+            // Clients always write first, which tells the protocol they speak
+            let (is_text_protocol, first_bytes) = match text_protocol::detect(&mut reader).await {
+                Ok(detected) => detected,
+                Err(error) => {
+                    info!("Connection closed before any message: {}", error);
+                    return;
+                }
+            };
+
+            let reader = BufReader::new(Cursor::new(first_bytes).chain(reader));
+
+            let result = if is_text_protocol {
+                let client = clients.register(Role::Owner, Transport::PlainText, pid, None);
+                text_protocol::handle_conn(reader, writer, client, input_tx, output_rx).await
+            } else {
+                let reader = IpcReader::new(reader);
+                let writer = IpcWriter::new(writer);
+                let client = clients.register(Role::Owner, Transport::Socket, pid, None);
+
+                handle_conn(reader, writer, client, input_tx, output_rx, snapshot_rx).await
+            };
+
+            if let Err(error) = result {
                 error!("Could not handle connection: {}", error);
             };
         });
     }
+
+    info!("Closing {} connection(s)...", connections.len());
+
+    // Connections close themselves, once they have told their client about the shutdown
+    let drained = timeout(DRAIN_TIMEOUT, async {
+        while connections.join_next().await.is_some() {}
+    })
+    .await;
+
+    if drained.is_err() {
+        warn!("Closing {} connection(s) forcibly", connections.len());
+        connections.shutdown().await;
+    }
+
+    Ok(())
+}
+
+/// Drives the timer on each tick of a [tokio::time::Interval] and applies client actions as
+/// soon as they are received.
+/// After each tick or action, the current timer state is broadcast to all clients and kept as
+/// snapshot for clients which sync.
+async fn run_timer(
+    config: Config,
+    config_loader: ConfigLoader,
+    mut profiles: Profiles,
+    timer_input_sender: ActionSender,
+    timer_input_receiver: ActionReceiver,
+    timer_output_sender: Arc<BroadcastSender<TimerOutputAction>>,
+    snapshot_sender: WatchSender<Option<Snapshot>>,
+) {
+    info!("Starting timer...");
+
+    let mut timer = TickingPomodoroTimer::with_policy(profiles.timers(), config.schedule());
+    let mut state_file = StateFile::new(&config.state);
+    if config.state.resume {
+        state_file.resume(&mut timer, &mut profiles);
+    }
+
+    let mut timer = ObservedTimer::new(timer);
+    let mut event_hooks = EventHooks::subscribe(config.hooks.clone(), &mut timer);
+
+    let mut reminders = ReminderScheduler::new(
+        config
+            .micro_breaks
+            .reminder()
+            .into_iter()
+            .chain(config.reminders)
+            .collect(),
+        config.timers.time_jump_threshold(),
+    );
+    let mut pause_timeout = PauseTimeout::new(config.pause);
+    let mut alignment =
+        IntervalAlignment::new(&config.alignment, WallClock::from_config(&config.clock));
+    let mut power_monitor = PowerMonitor::new(config.power);
+    let sound_player = SoundPlayer::spawn(config.notifications.ducking);
+    let mut session_recorder =
+        SessionRecorder::new(&config.history, WallClock::from_config(&config.clock));
+    session_recorder.set_focus_contract(config.focus_contract.enabled);
+    session_recorder.set_daily_goal(config.goals.daily);
+    let mut planner = Planner::new(&config.plan, WallClock::from_config(&config.clock));
+    let notifier_context = NotifierContext {
+        sound_player: sound_player.clone(),
+        timer_input_sender,
+    };
+    let mut notification_dispatcher = Arc::new(
+        NotificationDispatcher::from_config(&config.notifications, &notifier_context)
+            .with_quiet_hours(&config.schedule, WallClock::from_config(&config.clock)),
+    );
+    let mut tick_period = TICK_INTERVAL;
+    let mut ticks = tick_interval(tick_period);
+    let mut last_view_state = None;
+    let mut hold: Option<Hold> = None;
+    let mut task: Option<String> = None;
+    let mut banner: Option<Banner> = None;
+    let mut auto_pause: Option<AutoPause> = None;
+    let mut named_timers = NamedTimers::default();
+
+    loop {
+        let (mut actions, is_tick) = select! {
+            _ = ticks.tick() => (timer_input_receiver.drain(), true),
+            // Everything which has been queued up in the meantime is applied at once, so that
+            // rapid inputs (e.g. pause and skip) are handled together
+            actions = timer_input_receiver.recv() => (actions, false),
+        };
+
+        // Client actions always lead to a broadcast, even if the view state did not change.
+        // This way clients which have just connected or synced receive the current state.
+        let has_handled_actions = !actions.is_empty();
+
+        if actions.contains(&TimerInput::Shutdown) {
+            state_file.save(&timer, profiles.active());
+            shutdown::announce(&timer_output_sender);
+            return;
+        }
+
+        let goal_summaries = take_goal_summaries(&mut actions);
+        if take_reload_request(&mut actions) {
+            reload_config(
+                &config_loader,
+                &mut timer,
+                &mut profiles,
+                &mut notification_dispatcher,
+                &notifier_context,
+                &mut session_recorder,
+                &timer_output_sender,
+            );
+        }
+
+        apply_schedule(&mut profiles, &mut timer);
+        apply_profile_switches(
+            &mut actions,
+            &mut profiles,
+            &mut timer,
+            &timer_output_sender,
+        );
+        apply_named_timer_inputs(&mut actions, &mut named_timers, &timer_output_sender);
+        apply_plan_changes(&mut actions, &mut planner);
+        apply_auto_pauses(
+            &mut actions,
+            &mut timer,
+            &mut auto_pause,
+            &mut session_recorder,
+        );
+        let repliers = apply_actions(
+            &mut timer,
+            &mut hold,
+            &mut task,
+            actions,
+            &sound_player,
+            &mut session_recorder,
+            &timer_output_sender,
+        );
+        session_recorder.set_task(session_label(&task, &planner));
+
+        // Time only advances on ticks - actions in between are broadcast right away instead
+        if is_tick {
+            if release_expired_hold(&mut timer, &mut hold)
+                .is_some_and(|expired| expired.welcomes_back())
+            {
+                let is_muted = should_mute(&mut power_monitor).await;
+                notify(
+                    &notification_dispatcher,
+                    &timer_output_sender,
+                    Notification::welcome_back(
+                        &timer.view_state(),
+                        session_label(&task, &planner),
+                        is_muted,
+                    ),
+                );
+            }
+
+            for name in named_timers.tick() {
+                let is_muted = should_mute(&mut power_monitor).await;
+                let notification = Notification::countdown_end(name, &timer.view_state(), is_muted);
+                event_hooks::run_countdown_hook(&config.hooks, &notification);
+                notify(&notification_dispatcher, &timer_output_sender, notification);
+            }
+
+            for countdown in named_timers.take_due_countdowns() {
+                let is_muted = should_mute(&mut power_monitor).await;
+                let notification =
+                    Notification::countdown(&countdown, &timer.view_state(), is_muted);
+                event_hooks::run_countdown_hook(&config.hooks, &notification);
+                notify(&notification_dispatcher, &timer_output_sender, notification);
+            }
+
+            let previous = TickingPomodoroTimer::clone(&timer);
+
+            if let Some(timer_end) = timer.tick() {
+                session_recorder.record(&previous, &timer, SessionOutcome::Completed);
+
+                if previous.phase() == PomodoroPhase::Interval {
+                    planner.complete_interval();
+                }
+
+                if hold.is_none() {
+                    let is_muted = should_mute(&mut power_monitor).await;
+                    notify(
+                        &notification_dispatcher,
+                        &timer_output_sender,
+                        Notification::timer_end(
+                            timer_end,
+                            session_label(&task, &planner),
+                            is_muted,
+                        )
+                        .with_buttons(&timer.view_state()),
+                    );
+                }
+            }
+
+            let period = if power_monitor.is_saving_power().await {
+                BATTERY_SAVER_TICK_INTERVAL
+            } else {
+                TICK_INTERVAL
+            };
+
+            if period != tick_period {
+                tick_period = period;
+                ticks = tick_interval(tick_period);
+            }
+        }
+
+        for summary in goal_summaries {
+            let is_muted = should_mute(&mut power_monitor).await;
+            let notification = Notification::weekly_goal(
+                summary,
+                &timer.view_state(),
+                session_label(&task, &planner),
+                is_muted,
+            );
+            notify(&notification_dispatcher, &timer_output_sender, notification);
+        }
+
+        // Pauses are checked after actions as well, so that they are timed from the moment the
+        // timer has been paused
+        if let Some(action) = pause_timeout.tick(&timer, hold.is_some()) {
+            end_pause(&mut timer, action, &mut session_recorder);
+
+            let is_muted = should_mute(&mut power_monitor).await;
+            let notification = Notification::pause_timeout(
+                &config.pause,
+                &timer.view_state(),
+                session_label(&task, &planner),
+                is_muted,
+            );
+            notify(&notification_dispatcher, &timer_output_sender, notification);
+        }
+
+        // Checked last, so that intervals started by actions and by the end of a break are
+        // held before the first state in which they are running is broadcast
+        if let Some((reason, until)) = alignment.tick(&timer, hold.is_some()) {
+            start_hold(&mut timer, &mut hold, reason, until, false);
+        }
+
+        if let Some(summary) = session_recorder.take_cycle_summary() {
+            info!("{}", summary);
+
+            if hold.is_none() {
+                let is_muted = should_mute(&mut power_monitor).await;
+                let notification = Notification::cycle_end(
+                    &summary,
+                    &timer.view_state(),
+                    session_label(&task, &planner),
+                    is_muted,
+                );
+                notify(&notification_dispatcher, &timer_output_sender, notification);
+            }
+
+            banner = Some(Banner {
+                round: timer.shared_state().round,
+                text: summary.to_string(),
+            });
+        }
+
+        if let Some(today) = session_recorder.take_reached_daily_goal() {
+            info!("Daily goal has been reached");
+
+            if config.goals.daily_notification && hold.is_none() {
+                let is_muted = should_mute(&mut power_monitor).await;
+                let notification = Notification::daily_goal(
+                    today,
+                    &timer.view_state(),
+                    session_label(&task, &planner),
+                    is_muted,
+                );
+                notify(&notification_dispatcher, &timer_output_sender, notification);
+            }
+        }
+
+        let mut view_state = timer.view_state();
+        view_state.hold = hold.as_ref().map(Hold::state);
+        view_state.task = session_label(&task, &planner);
+        view_state.profile = Some(profiles.active().to_string());
+        view_state.today = Some(session_recorder.today());
+        view_state.timers = named_timers.states();
+        view_state.countdowns = named_timers.countdown_states();
+
+        // Reminders are only due on ticks, according to the state which is about to be broadcast
+        if is_tick {
+            for reminder in reminders.tick(&view_state, hold.is_some()) {
+                let is_muted = should_mute(&mut power_monitor).await;
+                let notification = Notification::reminder(&reminder, &view_state, is_muted);
+                notify(&notification_dispatcher, &timer_output_sender, notification);
+            }
+        }
+
+        view_state.reminder = reminders.state();
+
+        // The banner disappears as soon as the next cycle has been started
+        if banner.as_ref().is_some_and(|banner| {
+            banner.round != view_state.round || view_state.is_break || !view_state.is_paused
+        }) {
+            banner = None;
+        }
+
+        view_state.banner = banner.as_ref().map(|banner| banner.text.clone());
+
+        // Resuming (or ending) the interval in any way ends the automatic pause
+        if auto_pause.as_ref().is_some_and(|auto_pause| {
+            auto_pause.round != view_state.round
+                || view_state.is_break
+                || view_state.is_postponed
+                || !view_state.is_paused
+        }) {
+            auto_pause = None;
+        }
+
+        view_state.pause_reason = auto_pause
+            .as_ref()
+            .map(|auto_pause| auto_pause.reason.clone());
+
+        // Hooks are run once all events of this iteration have happened, so that they receive
+        // the resulting state (incl. the task)
+        event_hooks.run(&view_state);
+
+        // The progress changes on every tick, therefore it is left out of the comparison below
+        let mut sent_state = view_state.clone();
+        sent_state.progress = Some(timer.progress());
+
+        snapshot_sender.send_replace(Some(Snapshot {
+            state: sent_state.clone(),
+            timers: profiles.timers(),
+        }));
+        state_file.update(&timer, profiles.active());
+
+        for replier in repliers {
+            replier.reply(Ok(sent_state.clone()));
+        }
+
+        // Only update the view if something has changed, so that clients aren't
+        // redrawing identical states on every tick
+        if has_handled_actions || last_view_state.as_ref() != Some(&view_state) {
+            timer_output_sender
+                .send(TimerOutputAction::Timer(sent_state))
+                .ok();
+            last_view_state = Some(view_state);
+        }
+    }
+}
+
+/// Message which is shown by all clients until the given round is started
+#[derive(Debug, Clone)]
+struct Banner {
+    round: u64,
+    text: String,
+}
+
+/// Pause of a focus interval, which has been started by the server itself
+#[derive(Debug, Clone)]
+struct AutoPause {
+    round: u64,
+    reason: String,
+}
+
+fn tick_interval(period: Duration) -> Interval {
+    let mut ticks = interval(period);
+    // After a suspend we only want a single tick instead of a burst of missed ones
+    ticks.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    ticks
+}
+
+/// Removes all requests to reload the config from the given inputs and returns whether there has
+/// been at least one
+fn take_reload_request(inputs: &mut Vec<TimerInput>) -> bool {
+    let count = inputs.len();
+    inputs.retain(|input| *input != TimerInput::ReloadConfig);
+
+    inputs.len() != count
+}
+
+/// Removes all summaries of the weekly goal from the given inputs and returns them
+fn take_goal_summaries(inputs: &mut Vec<TimerInput>) -> Vec<String> {
+    let mut summaries = vec![];
+
+    inputs.retain(|input| match input {
+        TimerInput::GoalSummary(summary) => {
+            summaries.push(summary.clone());
+            false
+        }
+        _ => true,
+    });
+
+    summaries
+}
+
+/// Applies (and removes) the inputs which change the plan, because they don't affect the timer
+fn apply_plan_changes(inputs: &mut Vec<TimerInput>, planner: &mut Planner) {
+    inputs.retain(|input| match input {
+        TimerInput::AddToPlan { task, estimate } => {
+            planner.add(task.clone(), *estimate);
+            false
+        }
+        TimerInput::ClearPlan => {
+            planner.clear();
+            false
+        }
+        _ => true,
+    });
+}
+
+/// Applies (and removes) the inputs which pause and resume the timer on behalf of the server.
+/// Only running focus intervals are paused and only automatic pauses are resumed, so that a
+/// pause of the user is never ended by the server.
+fn apply_auto_pauses(
+    inputs: &mut Vec<TimerInput>,
+    timer: &mut ObservedTimer<Schedule>,
+    auto_pause: &mut Option<AutoPause>,
+    session_recorder: &mut SessionRecorder,
+) {
+    inputs.retain(|input| {
+        match input {
+            TimerInput::AutoPause { reason }
+                if timer.phase() == PomodoroPhase::Interval && !timer.timer().is_paused() =>
+            {
+                info!("Pausing the timer: {}", reason);
+                timer.handle_action(PomodoroTimerAction::PlayPause).ok();
+                session_recorder.add_pause_reason(reason.clone());
+                *auto_pause = Some(AutoPause {
+                    round: timer.shared_state().round,
+                    reason: reason.clone(),
+                });
+            }
+            TimerInput::AutoResume if auto_pause.is_some() && timer.timer().is_paused() => {
+                info!("Resuming the timer after an automatic pause");
+                timer.handle_action(PomodoroTimerAction::PlayPause).ok();
+                *auto_pause = None;
+            }
+            TimerInput::AutoPause { .. } | TimerInput::AutoResume => {}
+            _ => return true,
+        }
+
+        false
+    });
+}
+
+/// Applies (and removes) the inputs which switch the timer profile. Like a reloaded config, the
+/// new lengths apply from the next interval/break on - unless the current one has not been
+/// started yet, which is restarted with its new length right away.
+fn apply_profile_switches(
+    inputs: &mut Vec<TimerInput>,
+    profiles: &mut Profiles,
+    timer: &mut ObservedTimer<Schedule>,
+    timer_output_sender: &BroadcastSender<TimerOutputAction>,
+) {
+    inputs.retain(|input| {
+        let TimerInput::SwitchProfile(name) = input else {
+            return true;
+        };
+
+        match profiles.switch(name) {
+            Ok(timers) => {
+                timer.set_config(timers);
+
+                if timer.timer().is_paused() && timer.timer().elapsed_time().is_zero() {
+                    timer.restart_phase();
+                }
+            }
+            Err(error) => {
+                info!("Rejected profile switch: {:#}", error);
+                report_error(timer_output_sender, ErrorCode::ActionRejected, error);
+            }
+        }
+
+        false
+    });
+}
+
+/// Applies (and removes) the inputs which start and select named timers or start countdowns, as
+/// well as the actions which are meant for the selected named timer instead of the pomodoro timer
+fn apply_named_timer_inputs(
+    inputs: &mut Vec<TimerInput>,
+    named_timers: &mut NamedTimers,
+    timer_output_sender: &BroadcastSender<TimerOutputAction>,
+) {
+    inputs.retain(|input| {
+        let result = match input {
+            TimerInput::NewTimer { name, duration } => named_timers.add(name, *duration),
+            TimerInput::SelectTimer(name) => named_timers.select(name),
+            TimerInput::Countdown { duration, label } => {
+                named_timers.add_countdown(*duration, label.clone())
+            }
+            TimerInput::Action(action) if named_timers.is_selected() => named_timers.apply(*action),
+            _ => return true,
+        };
+
+        if let Err(error) = result {
+            info!("Rejected named timer input: {:#}", error);
+            report_error(timer_output_sender, ErrorCode::ActionRejected, error);
+        }
+
+        false
+    });
+}
+
+/// Label of the current session - a label which has been set explicitly takes precedence over
+/// the task, which is currently worked on according to the plan
+fn session_label(task: &Option<String>, planner: &Planner) -> Option<String> {
+    task.clone().or_else(|| planner.current_task())
+}
+
+/// Switches to the timer config of another day or of quiet hours, once the schedule asks for
+/// it. Like with a profile switch, the running interval or break keeps its length.
+fn apply_schedule(profiles: &mut Profiles, timer: &mut ObservedTimer<Schedule>) {
+    if let Some(timers) = profiles.scheduled_change() {
+        timer.set_config(timers);
+    }
+}
+
+/// Reads the config again and applies the new timer lengths, schedule and sequence (from the
+/// next interval/break on), notification settings and the focus contract. Other settings only take
+/// effect once the server is restarted. All clients are informed about the result.
+fn reload_config(
+    config_loader: &ConfigLoader,
+    timer: &mut ObservedTimer<Schedule>,
+    profiles: &mut Profiles,
+    notification_dispatcher: &mut Arc<NotificationDispatcher>,
+    notifier_context: &NotifierContext,
+    session_recorder: &mut SessionRecorder,
+    timer_output_sender: &BroadcastSender<TimerOutputAction>,
+) {
+    let config = match config_loader() {
+        Ok(config) => config,
+        Err(error) => {
+            error!("Could not reload config: {:#}", error);
+            report_error(
+                timer_output_sender,
+                ErrorCode::Config,
+                format!("Could not reload config: {:#}", error),
+            );
+            return;
+        }
+    };
+
+    info!("Config has been reloaded");
+
+    timer.set_config(profiles.reload(&config));
+    timer.set_policy(config.schedule());
+    *notification_dispatcher = Arc::new(
+        NotificationDispatcher::from_config(&config.notifications, notifier_context)
+            .with_quiet_hours(&config.schedule, WallClock::from_config(&config.clock)),
+    );
+    session_recorder.set_focus_contract(config.focus_contract.enabled);
+    session_recorder.set_daily_goal(config.goals.daily);
+
+    timer_output_sender
+        .send(TimerOutputAction::ConfigReloaded)
+        .ok();
+}
+
+/// Applies client inputs to the timer.
+/// If this starts a new phase, a notification sound which might still be playing is stopped.
+/// Sessions which have been ended by an action are recorded and actions which have been
+/// rejected by the timer are reported to all clients (or only to the client which has requested
+/// it). Returns the repliers of the requests which have been applied, which are answered with
+/// the resulting state.
+fn apply_actions(
+    timer: &mut ObservedTimer<Schedule>,
+    hold: &mut Option<Hold>,
+    task: &mut Option<String>,
+    inputs: impl IntoIterator<Item = TimerInput>,
+    sound_player: &SoundPlayer,
+    session_recorder: &mut SessionRecorder,
+    timer_output_sender: &BroadcastSender<TimerOutputAction>,
+) -> Vec<Replier> {
+    let phase_of = |timer: &TickingPomodoroTimer<Schedule>| {
+        (
+            timer.phase(),
+            timer.shared_state().round,
+            timer.view_state().is_paused,
+        )
+    };
+
+    let (previous_phase, previous_round, was_paused) = phase_of(timer);
+    let mut repliers = vec![];
+
+    for input in inputs {
+        let (action, replier) = match input {
+            TimerInput::Action(action) => (action, None),
+            TimerInput::Request { action, replier } => (action, Some(replier)),
+            TimerInput::NotificationAction {
+                action,
+                round,
+                is_break,
+            } => {
+                let state = timer.view_state();
+
+                // Start buttons must not pause an interval/break which has been started since
+                if state.round != round
+                    || state.is_break != is_break
+                    || (action == PomodoroTimerAction::PlayPause && !state.is_paused)
+                {
+                    info!("Ignoring outdated notification action: {:?}", action);
+                    continue;
+                }
+
+                (action, None)
+            }
+            TimerInput::Hold {
+                reason,
+                until,
+                welcome_back,
+            } => {
+                start_hold(timer, hold, reason, until, welcome_back);
+                continue;
+            }
+            TimerInput::PauseReason(reason) if timer.timer().is_paused() => {
+                info!("Timer has been paused for: {}", reason);
+                session_recorder.add_pause_reason(reason);
+                continue;
+            }
+            TimerInput::PauseReason(_) => {
+                info!("Ignoring pause reason, because the timer is running");
+                continue;
+            }
+            TimerInput::Task(label) => {
+                info!("Session has been labeled: {:?}", label);
+                // Sessions ended by the following actions are recorded with the new label
+                // (the planned task is filled in once all actions have been applied)
+                session_recorder.set_task(label.clone());
+                *task = label;
+                continue;
+            }
+            TimerInput::Tags(tags) if timer.phase() == PomodoroPhase::Interval => {
+                info!("Interval has been tagged: {:?}", tags);
+                session_recorder.add_tags(tags);
+                continue;
+            }
+            // The interval has already ended, while its context has been captured
+            TimerInput::Tags(_) => continue,
+            // Shutdowns, reloads, profile switches, named timers and countdowns, plan changes,
+            // automatic pauses and goal summaries are taken out of the inputs beforehand (see
+            // shut_down, take_reload_request, apply_profile_switches, apply_named_timer_inputs,
+            // apply_plan_changes, apply_auto_pauses and take_goal_summaries)
+            TimerInput::ReloadConfig
+            | TimerInput::SwitchProfile(_)
+            | TimerInput::AddToPlan { .. }
+            | TimerInput::ClearPlan
+            | TimerInput::AutoPause { .. }
+            | TimerInput::AutoResume
+            | TimerInput::GoalSummary(_)
+            | TimerInput::NewTimer { .. }
+            | TimerInput::SelectTimer(_)
+            | TimerInput::Countdown { .. }
+            | TimerInput::Shutdown => continue,
+        };
+
+        // Explicitly resuming (or pausing) the timer ends a hold
+        if action == PomodoroTimerAction::PlayPause && hold.take().is_some() {
+            info!("Hold has been released");
+        }
+
+        let previous = TickingPomodoroTimer::clone(timer);
+
+        if let Err(error) = timer.handle_action(action) {
+            info!("Rejected action: {}", error);
+
+            // Only the requesting client is told about the rejection of its request
+            match replier {
+                Some(replier) => replier.reply(Err(error.to_string())),
+                None => report_error(timer_output_sender, ErrorCode::ActionRejected, error),
+            }
+
+            continue;
+        }
+
+        repliers.extend(replier);
+
+        let outcome = match action {
+            PomodoroTimerAction::Skip
+            | PomodoroTimerAction::LongBreakNow
+            | PomodoroTimerAction::Previous => SessionOutcome::Skipped,
+            PomodoroTimerAction::PostponeBreak { .. } => SessionOutcome::Postponed,
+            PomodoroTimerAction::ResetTimer | PomodoroTimerAction::RestartCurrent => {
+                SessionOutcome::Reset
+            }
+            PomodoroTimerAction::None
+            | PomodoroTimerAction::PlayPause
+            | PomodoroTimerAction::ExtendTime { .. }
+            | PomodoroTimerAction::ReduceTime { .. } => continue,
+        };
+
+        session_recorder.record(&previous, timer, outcome);
+    }
+
+    let (phase, round, is_paused) = phase_of(timer);
+
+    if phase != previous_phase || round != previous_round || (was_paused && !is_paused) {
+        sound_player.stop();
+    }
+
+    repliers
+}
+
+/// Pauses the timer until the given point in time.
+/// A hold replacing another one resumes the timer only if it was running before the first hold -
+/// unless the user should be welcomed back, which always resumes the timer.
+fn start_hold(
+    timer: &mut ObservedTimer<Schedule>,
+    hold: &mut Option<Hold>,
+    reason: String,
+    until: SystemTime,
+    welcome_back: bool,
+) {
+    let is_running = !timer.timer().is_paused();
+    let resume = welcome_back || hold.as_ref().map_or(is_running, Hold::should_resume);
+
+    if is_running {
+        timer.handle_action(PomodoroTimerAction::PlayPause).ok();
+    }
+
+    info!("Holding the timer: {}", reason);
+    *hold = Some(Hold::new(reason, until, resume, welcome_back));
+}
+
+/// Ends an expired hold and resumes the timer, if it has been running before.
+/// Returns the expired hold.
+fn release_expired_hold(
+    timer: &mut ObservedTimer<Schedule>,
+    hold: &mut Option<Hold>,
+) -> Option<Hold> {
+    if !hold.as_ref().is_some_and(Hold::is_expired) {
+        return None;
+    }
+
+    let expired = hold.take()?;
+    info!("Hold has expired");
+
+    if expired.should_resume() && timer.timer().is_paused() {
+        timer.handle_action(PomodoroTimerAction::PlayPause).ok();
+    }
+
+    Some(expired)
+}
+
+/// Ends a pause, which has lasted for too long
+fn end_pause(
+    timer: &mut ObservedTimer<Schedule>,
+    action: PauseTimeoutAction,
+    session_recorder: &mut SessionRecorder,
+) {
+    match action {
+        PauseTimeoutAction::Resume => {
+            info!("Resuming the timer after a long pause");
+            timer.handle_action(PomodoroTimerAction::PlayPause).ok();
+        }
+        PauseTimeoutAction::Reset => {
+            info!("Restarting the current timer after a long pause");
+
+            let previous = TickingPomodoroTimer::clone(timer);
+            timer.restart_phase();
+            session_recorder.record(&previous, timer, SessionOutcome::Reset);
+        }
+    }
+}
+
+/// Denotes if notifications should be sent without sound (e.g. while running on low battery)
+async fn should_mute(power_monitor: &mut PowerMonitor) -> bool {
+    let is_muted = power_monitor.should_mute().await;
+
+    if is_muted {
+        info!("Running on low battery - skipping sound playback");
+    }
+
+    is_muted
+}
+
+/// Dispatches a notification (e.g. for a timer which has ended).
+/// Notifiers are run on a separate task, so that slow notifiers don't delay the timer.
+fn notify(
+    notification_dispatcher: &Arc<NotificationDispatcher>,
+    timer_output_sender: &Arc<BroadcastSender<TimerOutputAction>>,
+    notification: Notification,
+) {
+    let notification_dispatcher = notification_dispatcher.clone();
+    let timer_output_sender = timer_output_sender.clone();
+
+    tokio::spawn(async move {
+        let errors = notification_dispatcher.dispatch(&notification).await;
+
+        for error in &errors {
+            error!("{}", error);
+        }
+
+        match notification_dispatcher.on_failure() {
+            NotificationFailurePolicy::Silent => {}
+            NotificationFailurePolicy::Bell if !errors.is_empty() => {
+                let message = notification
+                    .message
+                    .unwrap_or_else(|| format!("zentime: {}", notification.event));
+
+                timer_output_sender
+                    .send(TimerOutputAction::Bell { message })
+                    .ok();
+            }
+            NotificationFailurePolicy::Bell => {}
+            NotificationFailurePolicy::Retry | NotificationFailurePolicy::Report => {
+                for error in errors {
+                    report_error(&timer_output_sender, ErrorCode::Notification, error);
+                }
+            }
+        }
+    });
+}
+
+/// Reports an error to all connected clients
+fn report_error(
+    timer_output_sender: &BroadcastSender<TimerOutputAction>,
+    code: ErrorCode,
+    error: impl Display,
+) {
+    timer_output_sender
+        .send(TimerOutputAction::Error {
+            code,
+            message: error.to_string(),
+        })
+        .ok();
 }
 
 /// Describe the things we do when we've got a connection ready.
 /// This will continously send the current timer state to the client and also listen for incoming
-/// [ClientToServerMsg]s.
-async fn handle_conn(
-    conn: LocalSocketStream,
-    timer_input_sender: Sender<PomodoroTimerAction>,
+/// [ClientToServerMsg]s, which are only applied if the [Role] of the client permits them.
+/// Clients which stay silent for longer than the heartbeat timeout are considered dead and
+/// their connection is closed.
+pub(super) async fn handle_conn<R, W>(
+    mut reader: IpcReader<R>,
+    mut writer: IpcWriter<W>,
+    client: ClientHandle,
+    timer_input_sender: ActionSender,
     mut timer_output_receiver: BroadcastReceiver<TimerOutputAction>,
-) -> anyhow::Result<()> {
-    // Split the connection into two halves to process
-    // received and sent data concurrently.
-    let (reader, mut writer) = conn.into_split();
-    let mut reader = BufReader::new(reader);
+    snapshot_receiver: WatchReceiver<Option<Snapshot>>,
+) -> anyhow::Result<()>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut view_filter = ViewFilter::default();
+    let heartbeat_timeout = client.heartbeat_timeout();
+    let mut deadline = heartbeat_timeout.map(|timeout| Instant::now() + timeout);
+    // Timer states are only forwarded once the first message (usually the handshake) has been
+    // answered. A socket activated server might already broadcast, before that message is read.
+    let mut has_answered = false;
 
     loop {
+        let heartbeat_missed = async {
+            match deadline {
+                Some(deadline) => sleep_until(deadline).await,
+                None => pending().await,
+            }
+        };
+
         select! {
-            msg = InterProcessCommunication::recv_ipc_message::<ClientToServerMsg>(&mut reader) => {
+            msg = reader.recv::<ClientToServerMsg>() => {
                 let msg = msg.context("Could not receive message from socket")?;
-                if let CloseConnection::Yes = handle_client_to_server_msg(msg, &timer_input_sender)
+                client.touch();
+                deadline = heartbeat_timeout.map(|timeout| Instant::now() + timeout);
+
+                if let CloseConnection::Yes = handle_client_to_server_msg(msg, &client, &timer_input_sender, &snapshot_receiver, &mut view_filter, &mut writer)
                     .await
                     .context("Could not handle client to server message")? {
                         break;
                     };
+                has_answered = true;
             },
-            value = timer_output_receiver.recv() => {
+            value = timer_output_receiver.recv(), if has_answered => {
                 let action = value.context("Could not receive output from timer")?;
-                handle_timer_output_action(action, &mut writer).await.context("Couuld not handle timer output action")?;
+                let is_shutting_down = matches!(action, TimerOutputAction::ShuttingDown);
+                handle_timer_output_action(action, &mut view_filter, &mut writer).await.context("Couuld not handle timer output action")?;
+
+                if is_shutting_down {
+                    break;
+                }
+            }
+            () = heartbeat_missed => {
+                info!("Client has not sent a heartbeat in time - closing its connection");
+                break;
             }
         }
 
@@ -172,50 +1345,80 @@ enum CloseConnection {
     No,
 }
 
-async fn handle_client_to_server_msg(
+async fn handle_client_to_server_msg<W: AsyncWrite + Unpin>(
     msg: ClientToServerMsg,
-    timer_input_sender: &Sender<PomodoroTimerAction>,
+    client: &ClientHandle,
+    timer_input_sender: &ActionSender,
+    snapshot_receiver: &WatchReceiver<Option<Snapshot>>,
+    view_filter: &mut ViewFilter,
+    writer: &mut IpcWriter<W>,
 ) -> anyhow::Result<CloseConnection> {
+    let role = client.role();
+
+    if !role.permits(&msg) {
+        writer
+            .send(&ServerToClientMsg::Error {
+                code: ErrorCode::Unauthorized,
+                message: format!("A team {} is not allowed to do this", role),
+            })
+            .await
+            .context("Could not reject message")?;
+
+        return Ok(CloseConnection::No);
+    }
+
     match msg {
         // Shutdown server
+        // The timer is saved before the server shuts down (see Shutdown::trigger)
         ClientToServerMsg::Quit => {
             info!("\nClient told server to shutdown");
+            timer_input_sender.send_input(TimerInput::Shutdown);
+        }
 
-            info!("Cleaning up socket file");
-            let socket_name = get_socket_name();
-            remove_file(socket_name)
-                .await
-                .context("Could not remove existing socket file")?;
+        ClientToServerMsg::Reset => {
+            timer_input_sender.send(PomodoroTimerAction::ResetTimer);
+        }
 
-            info!("Shutting down...");
-            std::process::exit(0);
+        // Go back to the phase before the current one
+        ClientToServerMsg::Previous => {
+            timer_input_sender.send(PomodoroTimerAction::Previous);
         }
 
-        ClientToServerMsg::Reset => {
-            timer_input_sender
-                .send(PomodoroTimerAction::ResetTimer)
-                .context("Could not send ResetTimer to timer")?;
+        ClientToServerMsg::RestartCurrent => {
+            timer_input_sender.send(PomodoroTimerAction::RestartCurrent);
+        }
+
+        // End the current cycle early with its long break
+        ClientToServerMsg::LongBreakNow => {
+            timer_input_sender.send(PomodoroTimerAction::LongBreakNow);
         }
 
         // Play/Pause the timer
         ClientToServerMsg::PlayPause => {
-            timer_input_sender
-                .send(PomodoroTimerAction::PlayPause)
-                .context("Could not send Play/Pause to timer")?;
+            timer_input_sender.send(PomodoroTimerAction::PlayPause);
         }
 
         // Skip to next timer interval
         ClientToServerMsg::Skip => {
-            timer_input_sender
-                .send(PomodoroTimerAction::Skip)
-                .context("Could not send Skip to timer")?;
+            timer_input_sender.send(PomodoroTimerAction::Skip);
         }
 
         // Try to postpone the current break (limited by pomodoro timer config and state)
-        ClientToServerMsg::PostPone => {
-            timer_input_sender
-                .send(PomodoroTimerAction::PostponeBreak)
-                .context("Could not send Skip to timer")?;
+        ClientToServerMsg::PostPone { seconds } => {
+            timer_input_sender.send(PomodoroTimerAction::PostponeBreak { seconds });
+        }
+
+        // Extend or shorten the current interval or break
+        ClientToServerMsg::AdjustTime(seconds) => {
+            timer_input_sender.send(if seconds < 0 {
+                PomodoroTimerAction::ReduceTime {
+                    seconds: seconds.unsigned_abs(),
+                }
+            } else {
+                PomodoroTimerAction::ExtendTime {
+                    seconds: seconds.unsigned_abs(),
+                }
+            });
         }
 
         // Close connection, because client has detached
@@ -224,21 +1427,224 @@ async fn handle_client_to_server_msg(
             return Ok(CloseConnection::Yes);
         }
 
+        // The timer only broadcasts its state on changes, therefore clients explicitly request
+        // the current state via sync. It is answered from the latest snapshot, so that the other
+        // clients don't receive a broadcast of an unchanged state.
         ClientToServerMsg::Sync => {
             info!("Client synced with server");
+            let snapshot = snapshot_receiver.borrow().clone();
+
+            match snapshot {
+                Some(mut snapshot) => {
+                    snapshot.state = view_filter.apply_forced(snapshot.state);
+
+                    writer
+                        .send(&ServerToClientMsg::Snapshot(snapshot))
+                        .await
+                        .context("Could not send snapshot")?;
+                }
+                // The timer has not produced its first state yet, which will be broadcast
+                None => {
+                    view_filter.force_next();
+                    timer_input_sender.send(PomodoroTimerAction::None);
+                }
+            }
+        }
+
+        ClientToServerMsg::Hello(preferences) => {
+            info!("Client declared {:?}", preferences);
+            view_filter.set_preferences(preferences);
+        }
+
+        ClientToServerMsg::Hold { reason, until } => {
+            let input = TimerInput::Hold {
+                reason,
+                until: UNIX_EPOCH + Duration::from_secs(until),
+                welcome_back: false,
+            };
+            queue_input(timer_input_sender, input, writer).await?;
+        }
+
+        ClientToServerMsg::PauseUntil { reason, until } => {
+            let input = TimerInput::Hold {
+                reason,
+                until: UNIX_EPOCH + Duration::from_secs(until),
+                welcome_back: true,
+            };
+            queue_input(timer_input_sender, input, writer).await?;
+        }
+
+        ClientToServerMsg::PauseReason { reason } => {
+            let reason = reason.trim();
+
+            if !reason.is_empty() {
+                let input = TimerInput::PauseReason(reason.to_string());
+                queue_input(timer_input_sender, input, writer).await?;
+            }
+        }
+
+        ClientToServerMsg::SetTask { task } => {
+            let task = task
+                .map(|task| task.trim().to_string())
+                .filter(|task| !task.is_empty());
+
+            queue_input(timer_input_sender, TimerInput::Task(task), writer).await?;
+        }
+
+        ClientToServerMsg::AddToPlan { task, pomodoros } => {
+            let task = task.trim();
+
+            if task.is_empty() || pomodoros == 0 {
+                writer
+                    .send(&ServerToClientMsg::Error {
+                        code: ErrorCode::ActionRejected,
+                        message: "A planned task needs a name and at least one pomodoro"
+                            .to_string(),
+                    })
+                    .await
+                    .context("Could not reject plan")?;
+            } else {
+                let input = TimerInput::AddToPlan {
+                    task: task.to_string(),
+                    estimate: pomodoros,
+                };
+                queue_input(timer_input_sender, input, writer).await?;
+            }
+        }
+
+        ClientToServerMsg::ClearPlan => {
+            queue_input(timer_input_sender, TimerInput::ClearPlan, writer).await?;
+        }
+
+        ClientToServerMsg::SwitchProfile(name) => {
+            queue_input(timer_input_sender, TimerInput::SwitchProfile(name), writer).await?;
+        }
+
+        ClientToServerMsg::NewTimer { name, seconds } => {
+            let input = TimerInput::NewTimer {
+                name,
+                duration: Duration::from_secs(seconds),
+            };
+            queue_input(timer_input_sender, input, writer).await?;
+        }
+
+        ClientToServerMsg::SelectTimer(name) => {
+            queue_input(timer_input_sender, TimerInput::SelectTimer(name), writer).await?;
+        }
+
+        ClientToServerMsg::Countdown { seconds, label } => {
+            let input = TimerInput::Countdown {
+                duration: Duration::from_secs(seconds),
+                label,
+            };
+            queue_input(timer_input_sender, input, writer).await?;
+        }
+
+        ClientToServerMsg::Ping => {
+            writer
+                .send(&ServerToClientMsg::Pong)
+                .await
+                .context("Could not answer ping")?;
+        }
+
+        // Every message keeps the connection alive, therefore there is nothing left to do
+        ClientToServerMsg::Heartbeat => {}
+
+        ClientToServerMsg::ListClients => {
+            writer
+                .send(&ServerToClientMsg::Clients(client.list()))
+                .await
+                .context("Could not list clients")?;
+        }
+
+        ClientToServerMsg::ReloadConfig => {
+            info!("Client requested to reload the config");
+            queue_input(timer_input_sender, TimerInput::ReloadConfig, writer).await?;
+        }
+
+        // Team participants join before their connection is handled, therefore this only
+        // confirms the role of the client
+        ClientToServerMsg::Join { .. } => {
+            writer
+                .send(&ServerToClientMsg::Joined { role })
+                .await
+                .context("Could not answer join")?;
+        }
+
+        // Clients which don't speak our protocol would only receive messages they can't decode
+        ClientToServerMsg::Handshake { version } if version != PROTOCOL_VERSION => {
+            info!(
+                "Client speaks protocol version {} - closing its connection",
+                version
+            );
+
+            let server = ServerInfo::current();
+            writer
+                .send(&ServerToClientMsg::Error {
+                    code: ErrorCode::VersionMismatch,
+                    message: format!(
+                        "The server speaks protocol version {} (zentime {}), while the client \
+                        speaks version {}",
+                        server.protocol_version, server.zentime_version, version
+                    ),
+                })
+                .await
+                .context("Could not reject handshake")?;
+
+            return Ok(CloseConnection::Yes);
+        }
+
+        ClientToServerMsg::Handshake { .. } => {
+            writer
+                .send(&ServerToClientMsg::Welcome(ServerInfo::current()))
+                .await
+                .context("Could not answer handshake")?;
         }
     }
 
     Ok(CloseConnection::No)
 }
 
-async fn handle_timer_output_action(
+/// Queues up a control input of a client - or tells the client, that it has been rejected
+/// because the timer is busy (see [ActionSender::try_send_input()])
+async fn queue_input<W: AsyncWrite + Unpin>(
+    timer_input_sender: &ActionSender,
+    input: TimerInput,
+    writer: &mut IpcWriter<W>,
+) -> anyhow::Result<()> {
+    if let Err(error) = timer_input_sender.try_send_input(input) {
+        warn!("{}", error);
+
+        writer
+            .send(&ServerToClientMsg::Error {
+                code: ErrorCode::ActionRejected,
+                message: error.to_string(),
+            })
+            .await
+            .context("Could not reject input")?;
+    }
+
+    Ok(())
+}
+
+async fn handle_timer_output_action<W: AsyncWrite + Unpin>(
     action: TimerOutputAction,
-    writer: &mut OwnedWriteHalf,
+    view_filter: &mut ViewFilter,
+    writer: &mut IpcWriter<W>,
 ) -> anyhow::Result<()> {
-    let TimerOutputAction::Timer(state) = action;
-    let msg = ServerToClientMsg::Timer(state);
-    InterProcessCommunication::send_ipc_message(msg, writer)
+    let msg = match action {
+        TimerOutputAction::Timer(state) => match view_filter.apply(state) {
+            Some(state) => ServerToClientMsg::Timer(state),
+            None => return Ok(()),
+        },
+        TimerOutputAction::ConfigReloaded => ServerToClientMsg::ConfigReloaded,
+        TimerOutputAction::Error { code, message } => ServerToClientMsg::Error { code, message },
+        TimerOutputAction::Bell { message } => ServerToClientMsg::Bell { message },
+        TimerOutputAction::ShuttingDown => ServerToClientMsg::ShuttingDown,
+    };
+
+    writer
+        .send(&msg)
         .await
         .context("Could not send IPC message from server to client")?;
 
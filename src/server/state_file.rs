@@ -0,0 +1,168 @@
+//! Keeps the state of the timer in a file (see [StateConfig]), so that a restarted server can
+//! continue where the previous one has left off
+
+use std::fs::{create_dir_all, read_to_string, rename, write};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use anyhow::Context;
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use zentime_rs_timer::ticking_pomodoro_timer::{SavedTimer, TickingPomodoroTimer};
+use zentime_rs_timer::transition_policy::TransitionPolicy;
+
+use super::profiles::Profiles;
+use crate::config::StateConfig;
+
+/// Interval in which the remaining time of a running timer is written.
+/// Everything else (e.g. a new phase or pausing the timer) is written right away.
+const SAVE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Content of the state file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct SavedState {
+    /// Profile the lengths of intervals and breaks have been taken from
+    profile: String,
+
+    /// Phase, round and remaining time of the timer
+    timer: SavedTimer,
+}
+
+/// State which has been written last
+#[derive(Debug)]
+struct Checkpoint {
+    state: SavedState,
+    is_paused: bool,
+    saved_at: Instant,
+}
+
+/// File the timer is kept in.
+/// The state is small, therefore it is written right away instead of on a blocking task.
+#[derive(Debug)]
+pub struct StateFile {
+    path: PathBuf,
+    last_saved: Option<Checkpoint>,
+}
+
+impl StateFile {
+    /// Creates a state file at the configured path (which is only written once the timer is
+    /// saved)
+    pub fn new(config: &StateConfig) -> Self {
+        Self {
+            path: config.path(),
+            last_saved: None,
+        }
+    }
+
+    /// Continues the timer of the previous server (incl. its profile), if it has left a
+    /// readable state file behind
+    pub fn resume<P: TransitionPolicy + Clone>(
+        &self,
+        timer: &mut TickingPomodoroTimer<P>,
+        profiles: &mut Profiles,
+    ) {
+        let saved = match self.load() {
+            Ok(Some(saved)) => saved,
+            Ok(None) => {
+                info!("There is no state to resume - starting with the first interval");
+                return;
+            }
+            Err(error) => {
+                error!("Could not resume the previous timer: {:#}", error);
+                return;
+            }
+        };
+
+        if saved.profile != profiles.active() {
+            if let Err(error) = profiles.switch(&saved.profile) {
+                warn!("Could not resume profile {}: {:#}", saved.profile, error);
+            }
+        }
+
+        *timer =
+            TickingPomodoroTimer::restore(profiles.timers(), timer.policy().clone(), saved.timer);
+
+        info!(
+            "Resumed round {} ({:?}) with {} seconds left",
+            saved.timer.state.round,
+            saved.timer.phase,
+            saved.timer.remaining_millis / 1000
+        );
+    }
+
+    /// Writes the timer, if it has changed in more than its remaining time - the remaining time
+    /// of a running timer is only written every [SAVE_INTERVAL]
+    pub fn update<P: TransitionPolicy>(&mut self, timer: &TickingPomodoroTimer<P>, profile: &str) {
+        let state = saved_state(timer, profile);
+        let is_paused = timer.timer().is_paused();
+
+        let is_unchanged = self.last_saved.as_ref().is_some_and(|last_saved| {
+            let mut last_state = last_saved.state.clone();
+            last_state.timer.remaining_millis = state.timer.remaining_millis;
+
+            last_state == state
+                && last_saved.is_paused == is_paused
+                && (is_paused || last_saved.saved_at.elapsed() < SAVE_INTERVAL)
+        });
+
+        if !is_unchanged {
+            self.write(state, is_paused);
+        }
+    }
+
+    /// Writes the timer right away (e.g. before the server shuts down)
+    pub fn save<P: TransitionPolicy>(&mut self, timer: &TickingPomodoroTimer<P>, profile: &str) {
+        self.write(saved_state(timer, profile), timer.timer().is_paused());
+    }
+
+    /// Reads the state of the previous server - [None] if there is no state file
+    fn load(&self) -> anyhow::Result<Option<SavedState>> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+
+        let content = read_to_string(&self.path)
+            .with_context(|| format!("Could not read {}", self.path.display()))?;
+
+        serde_json::from_str(&content)
+            .with_context(|| format!("Could not parse {}", self.path.display()))
+            .map(Some)
+    }
+
+    fn write(&mut self, state: SavedState, is_paused: bool) {
+        if let Err(error) = self.try_write(&state) {
+            error!("Could not save the state of the timer: {:#}", error);
+        }
+
+        // A failed write is not retried before the next change, so that the log is not
+        // flooded on every tick
+        self.last_saved = Some(Checkpoint {
+            state,
+            is_paused,
+            saved_at: Instant::now(),
+        });
+    }
+
+    /// Writes into a temporary file first, so that a crash never leaves a truncated state behind
+    fn try_write(&self, state: &SavedState) -> anyhow::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            create_dir_all(parent)
+                .with_context(|| format!("Could not create {}", parent.display()))?;
+        }
+
+        let content = serde_json::to_string_pretty(state).context("Could not serialize state")?;
+        let temporary_path = self.path.with_extension("tmp");
+
+        write(&temporary_path, content)
+            .with_context(|| format!("Could not write {}", temporary_path.display()))?;
+        rename(&temporary_path, &self.path)
+            .with_context(|| format!("Could not replace {}", self.path.display()))
+    }
+}
+
+fn saved_state<P: TransitionPolicy>(timer: &TickingPomodoroTimer<P>, profile: &str) -> SavedState {
+    SavedState {
+        profile: profile.to_string(),
+        timer: timer.save(),
+    }
+}
@@ -1,7 +1,40 @@
 //! Code related to server status information
 use std::fmt::Display;
+#[cfg(unix)]
+use std::fs::DirBuilder;
+use std::fs::{remove_file, File, OpenOptions, TryLockError};
+#[cfg(unix)]
+use std::io;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::Duration;
 
-use sysinfo::{ProcessExt, System, SystemExt};
+#[cfg(unix)]
+use anyhow::bail;
+use anyhow::Context;
+use futures::io::BufReader;
+use log::warn;
+use tokio::time::timeout;
+use zentime_rs_timer::pomodoro_timer::ViewState;
+
+use crate::ipc::{
+    connect, user_id, ClientInfo, ClientToServerMsg, IpcReader, IpcWriter, ServerToClientMsg,
+    Snapshot,
+};
+
+const DEFAULT_PID_FILE_NAME: &str = "zentime.pid";
+const DEBUG_PID_FILE_NAME: &str = "zentime_debug.pid";
+
+/// Prefix of the directory inside the temp directory, which contains the PID file as long as
+/// there is no `$XDG_RUNTIME_DIR`
+const PRIVATE_DIR_PREFIX: &str = "zentime";
+
+/// Time a server has to answer a ping, before it is considered to be unresponsive
+const PING_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Time a server has to answer a request for its timer state
+const STATE_TIMEOUT: Duration = Duration::from_secs(2);
 
 /// Current status of the zentime server
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -22,36 +55,268 @@ impl Display for ServerStatus {
     }
 }
 
-/// Gets the current status of the zentime server, by checking if a process is running
-/// which was started by a `zentime server`-command.
-pub fn server_status() -> ServerStatus {
-    let system = System::new_all();
+/// Gets the current status of the zentime server.
+/// The server is asked via a ping over the zentime socket. If it does not answer (e.g. because
+/// it is still starting up), we fall back to checking if the server PID file is locked.
+pub async fn server_status() -> ServerStatus {
+    if ping().await.is_ok() || pid_file_is_locked() {
+        ServerStatus::Running
+    } else {
+        ServerStatus::Stopped
+    }
+}
+
+/// Sends a [ClientToServerMsg::Ping] to the server and waits for its answer
+pub async fn ping() -> anyhow::Result<()> {
+    timeout(PING_TIMEOUT, async {
+        let (reader, writer) = connect().await?.into_split();
+        let mut reader = IpcReader::new(BufReader::new(reader));
+        let mut writer = IpcWriter::new(writer);
+
+        writer.send(&ClientToServerMsg::Ping).await?;
+
+        // The server might send timer updates before answering our ping
+        while !matches!(
+            reader.recv::<ServerToClientMsg>().await?,
+            ServerToClientMsg::Pong
+        ) {}
+
+        writer.send(&ClientToServerMsg::Detach).await.ok();
+
+        anyhow::Ok(())
+    })
+    .await
+    .context("Server did not answer in time")?
+}
+
+/// Asks the server for the current state of its timer
+pub async fn timer_state() -> anyhow::Result<ViewState> {
+    timeout(STATE_TIMEOUT, async {
+        let (reader, writer) = connect().await?.into_split();
+        let mut reader = IpcReader::new(BufReader::new(reader));
+        let mut writer = IpcWriter::new(writer);
+
+        writer.send(&ClientToServerMsg::Sync).await?;
+
+        // Errors, which are broadcast to all clients in the meantime, are skipped
+        let state = loop {
+            if let ServerToClientMsg::Timer(state)
+            | ServerToClientMsg::Snapshot(Snapshot { state, .. }) =
+                reader.recv::<ServerToClientMsg>().await?
+            {
+                break state;
+            }
+        };
+
+        writer.send(&ClientToServerMsg::Detach).await.ok();
+
+        anyhow::Ok(state)
+    })
+    .await
+    .context("Server did not send its timer state in time")?
+}
+
+/// Asks the server for the clients which are currently connected to it
+pub async fn connected_clients() -> anyhow::Result<Vec<ClientInfo>> {
+    timeout(STATE_TIMEOUT, async {
+        let (reader, writer) = connect().await?.into_split();
+        let mut reader = IpcReader::new(BufReader::new(reader));
+        let mut writer = IpcWriter::new(writer);
+
+        writer.send(&ClientToServerMsg::ListClients).await?;
 
-    let mut zentime_process_instances = system.processes_by_name("zentime");
+        let clients = loop {
+            if let ServerToClientMsg::Clients(clients) = reader.recv::<ServerToClientMsg>().await? {
+                break clients;
+            }
+        };
+
+        writer.send(&ClientToServerMsg::Detach).await.ok();
+
+        anyhow::Ok(clients)
+    })
+    .await
+    .context("Server did not list its clients in time")?
+}
 
-    // WHY:
-    // We identify a server process by its command (e.g. "zentime server start") and assume that
-    // there is no other way, that the word "start" is part of a server command
-    //
-    // NOTE: During debug builds we use a different socket and therefore the server is not
-    // shared with the production one
-    let server_is_running = if cfg!(debug_assertions) {
-        zentime_process_instances.any(|p| {
-            p.cmd()[0].contains(&String::from("target/debug"))
-                && p.cmd().contains(&String::from("server"))
-                && p.cmd().contains(&String::from("start"))
-        })
+/// Path of the file containing the PID of the running server.
+/// The file is kept inside `$XDG_RUNTIME_DIR` - or, if it is not set, inside a directory of the
+/// temp directory, which is named after the id of the current user (see
+/// [get_socket_name](crate::ipc::get_socket_name)) and only accessible by that user.
+/// This way other users can't put a PID file into place before the server starts.
+pub fn pid_file_path() -> PathBuf {
+    let file_name = if cfg!(debug_assertions) {
+        DEBUG_PID_FILE_NAME
     } else {
-        zentime_process_instances.any(|p| {
-            !p.cmd()[0].contains(&String::from("target/debug"))
-                && p.cmd().contains(&String::from("server"))
-                && p.cmd().contains(&String::from("start"))
-        })
+        DEFAULT_PID_FILE_NAME
     };
 
-    if server_is_running {
-        ServerStatus::Running
-    } else {
-        ServerStatus::Stopped
+    let runtime_dir = std::env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .unwrap_or_else(|| {
+            std::env::temp_dir().join(format!("{}-{}", PRIVATE_DIR_PREFIX, user_id()))
+        });
+
+    runtime_dir.join(file_name)
+}
+
+/// Creates the directory (unless it exists) and makes sure, that it is a directory which only
+/// the current user can access
+#[cfg(unix)]
+fn ensure_private_dir(dir: &Path) -> anyhow::Result<()> {
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt};
+
+    match DirBuilder::new().mode(0o700).create(dir) {
+        Ok(()) => {}
+        Err(error) if error.kind() == io::ErrorKind::AlreadyExists => {}
+        Err(error) => {
+            return Err(error).context(format!("Could not create directory {}", dir.display()))
+        }
+    }
+
+    let metadata = std::fs::symlink_metadata(dir)
+        .context(format!("Could not read directory {}", dir.display()))?;
+
+    // SAFETY: getuid() is always successful
+    if !metadata.is_dir() || metadata.uid() != unsafe { libc::getuid() } {
+        bail!("{} is not a directory of the current user", dir.display());
+    }
+
+    if metadata.mode() & 0o077 != 0 {
+        bail!("{} is accessible by other users", dir.display());
+    }
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn ensure_private_dir(dir: &Path) -> anyhow::Result<()> {
+    std::fs::create_dir_all(dir).context(format!("Could not create directory {}", dir.display()))
+}
+
+/// Makes sure that the PID file is owned by the current user
+/// (like [connect](crate::ipc::connect) does for socket files)
+#[cfg(unix)]
+fn verify_pid_file_owner(file: &File, path: &Path) -> anyhow::Result<()> {
+    use std::os::unix::fs::MetadataExt;
+
+    let metadata = file
+        .metadata()
+        .context(format!("Could not read PID file {}", path.display()))?;
+
+    // SAFETY: getuid() is always successful
+    if metadata.uid() != unsafe { libc::getuid() } {
+        bail!("PID file {} is owned by another user", path.display());
+    }
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn verify_pid_file_owner(_file: &File, _path: &Path) -> anyhow::Result<()> {
+    Ok(())
+}
+
+/// Opens the existing PID file for reading, unless it is owned by another user
+fn open_pid_file() -> Option<File> {
+    let path = pid_file_path();
+    let file = File::open(&path).ok()?;
+
+    match verify_pid_file_owner(&file, &path) {
+        Ok(()) => Some(file),
+        Err(error) => {
+            warn!("{:#}", error);
+            None
+        }
+    }
+}
+
+/// Exclusive lock on the server PID file, which is held as long as the server is running.
+/// The operating system releases the lock once the server process exits (even after a crash),
+/// therefore a locked PID file always denotes a running server - regardless of the name of its
+/// executable or whether its PID has been reused by another process since.
+#[derive(Debug)]
+pub struct PidFile {
+    _file: File,
+}
+
+impl PidFile {
+    /// Locks the server PID file and writes the PID of the current process into it.
+    /// Returns [None], if the file is locked by another running server.
+    pub fn acquire() -> anyhow::Result<Option<Self>> {
+        let path = pid_file_path();
+        let error_context = || format!("Could not write PID file {}", path.display());
+
+        if let Some(dir) = path.parent() {
+            ensure_private_dir(dir)?;
+        }
+
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+            .with_context(error_context)?;
+        verify_pid_file_owner(&file, &path)?;
+
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => return Ok(None),
+            Err(TryLockError::Error(error)) => {
+                return Err(error).context(format!("Could not lock PID file {}", path.display()))
+            }
+        }
+
+        file.set_len(0).with_context(error_context)?;
+        file.write_all(process::id().to_string().as_bytes())
+            .with_context(error_context)?;
+
+        Ok(Some(Self { _file: file }))
+    }
+}
+
+/// Removes the server PID file (if it exists)
+pub fn remove_pid_file() {
+    remove_file(pid_file_path()).ok();
+}
+
+/// PID of the running server as written into the server PID file
+pub fn server_pid() -> Option<u32> {
+    let mut content = String::new();
+    open_pid_file()?.read_to_string(&mut content).ok()?;
+
+    content.trim().parse::<u32>().ok()
+}
+
+/// Checks if the server PID file is locked by a running server (see [PidFile])
+pub fn pid_file_is_locked() -> bool {
+    let Some(file) = open_pid_file() else {
+        return false;
+    };
+
+    matches!(file.try_lock_shared(), Err(TryLockError::WouldBlock))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    #[test]
+    fn keeps_the_pid_file_inside_a_private_directory() {
+        let dir = std::env::temp_dir().join(format!("zentime-status-test-{}", process::id()));
+        std::fs::remove_dir_all(&dir).ok();
+
+        ensure_private_dir(&dir).unwrap();
+        let permissions = std::fs::metadata(&dir).unwrap().permissions();
+        assert_eq!(permissions.mode() & 0o777, 0o700);
+
+        // Directories which other users can access (e.g. created by them beforehand) are refused
+        std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o777)).unwrap();
+        assert!(ensure_private_dir(&dir).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
     }
 }
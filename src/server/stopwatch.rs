@@ -0,0 +1,173 @@
+//! Stopwatch mode of the server (see `mode = "stopwatch"`), which simply counts up how long the
+//! user has been working instead of running intervals and breaks.
+//!
+//! Play/pause starts and pauses the stopwatch, skipping takes a lap and resetting sets it back
+//! to zero. Everything which only makes sense for intervals (e.g. postponing) is rejected.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use log::info;
+use tokio::select;
+use tokio::sync::broadcast::Sender as BroadcastSender;
+use tokio::sync::watch::Sender as WatchSender;
+use tokio::time::{interval, MissedTickBehavior};
+use zentime_rs_timer::config::PomodoroTimerConfig;
+use zentime_rs_timer::pomodoro_timer_action::PomodoroTimerAction;
+use zentime_rs_timer::stopwatch::Stopwatch;
+use zentime_rs_timer::util::seconds_to_time;
+
+use super::action_queue::{ActionReceiver, Replier, TimerInput};
+use super::shutdown;
+use super::timer_output::TimerOutputAction;
+use crate::ipc::{ErrorCode, Snapshot};
+
+/// The elapsed time is displayed in whole seconds, which this keeps up with
+const TICK_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Runs the stopwatch and broadcasts its state whenever it has changed (or a client action has
+/// been applied).
+/// Syncing clients receive the current state together with the given timer config.
+pub async fn run(
+    timers: PomodoroTimerConfig,
+    timer_input_receiver: ActionReceiver,
+    timer_output_sender: Arc<BroadcastSender<TimerOutputAction>>,
+    snapshot_sender: WatchSender<Option<Snapshot>>,
+) {
+    info!("Starting stopwatch...");
+
+    let mut stopwatch = Stopwatch::new();
+    let mut task: Option<String> = None;
+    let mut ticks = interval(TICK_INTERVAL);
+    ticks.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    let mut last_view_state = None;
+
+    loop {
+        let (has_handled_actions, repliers) = select! {
+            _ = ticks.tick() => (false, vec![]),
+            inputs = timer_input_receiver.recv() => {
+                if inputs.contains(&TimerInput::Shutdown) {
+                    shutdown::announce(&timer_output_sender);
+                    return;
+                }
+
+                (true, apply_inputs(&mut stopwatch, &mut task, inputs, &timer_output_sender))
+            }
+        };
+
+        let mut view_state = stopwatch.view_state();
+        view_state.task = task.clone();
+
+        snapshot_sender.send_replace(Some(Snapshot {
+            state: view_state.clone(),
+            timers,
+        }));
+
+        for replier in repliers {
+            replier.reply(Ok(view_state.clone()));
+        }
+
+        if has_handled_actions || last_view_state.as_ref() != Some(&view_state) {
+            // Sending only fails if no client is connected
+            timer_output_sender
+                .send(TimerOutputAction::Timer(view_state.clone()))
+                .ok();
+            last_view_state = Some(view_state);
+        }
+    }
+}
+
+/// Applies the inputs to the stopwatch and returns the repliers of the requests which have been
+/// applied, which are answered with the resulting state
+fn apply_inputs(
+    stopwatch: &mut Stopwatch,
+    task: &mut Option<String>,
+    inputs: Vec<TimerInput>,
+    timer_output_sender: &BroadcastSender<TimerOutputAction>,
+) -> Vec<Replier> {
+    let mut repliers = vec![];
+
+    for input in inputs {
+        match input {
+            TimerInput::Action(action) => {
+                if let Err(message) = apply_action(stopwatch, action) {
+                    timer_output_sender
+                        .send(TimerOutputAction::Error {
+                            code: ErrorCode::ActionRejected,
+                            message,
+                        })
+                        .ok();
+                }
+            }
+            TimerInput::Request { action, replier } => match apply_action(stopwatch, action) {
+                Ok(()) => repliers.push(replier),
+                Err(message) => replier.reply(Err(message)),
+            },
+            TimerInput::Task(label) => {
+                info!("Session has been labeled: {:?}", label);
+                *task = label;
+            }
+            TimerInput::ReloadConfig => {
+                timer_output_sender
+                    .send(TimerOutputAction::Error {
+                        code: ErrorCode::Config,
+                        message: "The config can't be reloaded in stopwatch mode - restart the \
+                            server instead"
+                            .to_string(),
+                    })
+                    .ok();
+            }
+            TimerInput::NewTimer { .. }
+            | TimerInput::SelectTimer(_)
+            | TimerInput::Countdown { .. } => {
+                timer_output_sender
+                    .send(TimerOutputAction::Error {
+                        code: ErrorCode::ActionRejected,
+                        message: "Named timers and countdowns can't be started in stopwatch mode"
+                            .to_string(),
+                    })
+                    .ok();
+            }
+            // Holds, (automatic) pauses, profiles, the plan of the day and notification buttons
+            // belong to the pomodoro cycle
+            TimerInput::NotificationAction { .. }
+            | TimerInput::Hold { .. }
+            | TimerInput::PauseReason(_)
+            | TimerInput::AutoPause { .. }
+            | TimerInput::AutoResume
+            | TimerInput::AddToPlan { .. }
+            | TimerInput::ClearPlan
+            | TimerInput::SwitchProfile(_)
+            | TimerInput::Tags(_)
+            | TimerInput::GoalSummary(_) => {}
+            // Handled before any other input
+            TimerInput::Shutdown => {}
+        }
+    }
+
+    repliers
+}
+
+fn apply_action(stopwatch: &mut Stopwatch, action: PomodoroTimerAction) -> Result<(), String> {
+    match action {
+        PomodoroTimerAction::PlayPause => stopwatch.play_pause(),
+        PomodoroTimerAction::Skip => {
+            let lap = stopwatch.lap();
+            info!(
+                "Lap {}: {}",
+                stopwatch.laps().len(),
+                seconds_to_time(lap.as_secs())
+            );
+        }
+        PomodoroTimerAction::ResetTimer => stopwatch.reset(),
+        PomodoroTimerAction::None => {}
+        action => {
+            info!("Rejected action in stopwatch mode: {:?}", action);
+            return Err(
+                "The stopwatch can only be started, paused, reset or split into laps".to_string(),
+            );
+        }
+    }
+
+    Ok(())
+}
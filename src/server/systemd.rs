@@ -0,0 +1,192 @@
+//! Running the zentime server as systemd user service.
+//!
+//! `zentime server install-service` writes a socket unit and a service unit into the systemd
+//! user directory. systemd then listens on the zentime socket itself and starts the server as
+//! soon as the first client connects, handing the listening socket over to it (socket
+//! activation, see `sd_listen_fds(3)`). A server which has quit or crashed is simply started
+//! again by the next client.
+//!
+//! The units are named `zentime-server.*`, so that they don't collide with the unit of
+//! `zentime install-autostart` (`zentime.service`), which starts a daemonizing server on login.
+//! Only one of both installations can be used at a time.
+
+use std::env::{current_exe, var};
+use std::fs::{create_dir_all, remove_file, write};
+use std::os::unix::io::{FromRawFd, RawFd};
+use std::os::unix::net::UnixListener;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command};
+
+use anyhow::{bail, Context};
+
+use crate::ipc::systemd_socket_file_name;
+use crate::server::listener::SocketListener;
+
+/// Name of the socket unit, which listens on the zentime socket
+pub const SOCKET_UNIT_NAME: &str = "zentime-server.socket";
+
+/// Name of the service unit, which runs the server
+pub const SERVICE_UNIT_NAME: &str = "zentime-server.service";
+
+/// Name of the unit, which `zentime install-autostart` writes
+pub const AUTOSTART_UNIT_NAME: &str = "zentime.service";
+
+/// First file descriptor, which systemd passes to a socket activated process
+const LISTEN_FDS_START: RawFd = 3;
+
+/// Denotes if systemd has started the current process and passed a listening socket to it
+pub fn is_socket_activated() -> bool {
+    let passed_to_us = var("LISTEN_PID")
+        .ok()
+        .and_then(|pid| pid.parse::<u32>().ok())
+        .is_some_and(|pid| pid == process::id());
+    let socket_count = var("LISTEN_FDS")
+        .ok()
+        .and_then(|count| count.parse::<u32>().ok())
+        .unwrap_or_default();
+
+    passed_to_us && socket_count > 0
+}
+
+/// Takes over the socket, which systemd has passed to the current process
+/// ([None] if the process has not been socket activated).
+///
+/// NOTE:
+/// This must only be called once - the socket belongs to the returned listener afterwards.
+pub(crate) fn activated_listener() -> anyhow::Result<Option<SocketListener>> {
+    if !is_socket_activated() {
+        return Ok(None);
+    }
+
+    // Commands of hooks must not inherit the socket, otherwise it would stay open (and clients
+    // would hang) after the server has quit
+    // SAFETY: Setting a flag of a file descriptor has no effect on memory
+    if unsafe { libc::fcntl(LISTEN_FDS_START, libc::F_SETFD, libc::FD_CLOEXEC) } == -1 {
+        bail!(
+            "Could not take over socket of systemd (file descriptor {})",
+            LISTEN_FDS_START
+        );
+    }
+
+    // SAFETY: systemd passes the listening socket as the first file descriptor after stdio,
+    // which is owned by nothing else inside this process
+    let listener = unsafe { UnixListener::from_raw_fd(LISTEN_FDS_START) };
+
+    SocketListener::activated(listener).map(Some)
+}
+
+/// Socket unit which listens on the zentime socket (inside `$XDG_RUNTIME_DIR`).
+/// The socket file is removed together with the unit, so that clients fall back to the socket
+/// of a server which binds it itself.
+pub fn socket_unit() -> String {
+    format!(
+        "[Unit]
+Description=zentime pomodoro timer socket
+
+[Socket]
+ListenStream=%t/{}
+SocketMode=0600
+RemoveOnStop=yes
+
+[Install]
+WantedBy=sockets.target
+",
+        systemd_socket_file_name()
+    )
+}
+
+/// Service unit which runs the server with the given config file, once a client connects to
+/// the socket of [socket_unit].
+/// A socket activated `server start` runs in the foreground, therefore the unit is of type
+/// `simple`.
+pub fn service_unit(exe: &str, config_path: &str) -> String {
+    format!(
+        "[Unit]
+Description=zentime pomodoro timer server
+Requires={socket}
+After={socket}
+
+[Service]
+Type=simple
+ExecStart=\"{exe}\" server start -c \"{config}\"
+Restart=on-failure
+",
+        socket = SOCKET_UNIT_NAME,
+        exe = exe,
+        config = config_path
+    )
+}
+
+/// Denotes if the socket and service unit have been installed
+pub fn is_installed() -> bool {
+    unit_dir().join(SOCKET_UNIT_NAME).exists()
+}
+
+/// Writes the socket and service unit and starts listening on the zentime socket.
+/// The given config path is handed to the server on each start.
+/// Returns the directory the units have been written to.
+pub fn install(config_path: &str) -> anyhow::Result<PathBuf> {
+    let exe = current_exe().context("Could not determine zentime executable")?;
+    let unit_dir = unit_dir();
+
+    // Both would start a server, which then compete for the PID file and the socket
+    let autostart_unit = unit_dir.join(AUTOSTART_UNIT_NAME);
+    if autostart_unit.exists() {
+        bail!(
+            "{} starts the server on login already - remove it with `zentime install-autostart \
+            --uninstall` first",
+            autostart_unit.display()
+        );
+    }
+
+    create_dir_all(&unit_dir)
+        .with_context(|| format!("Could not create {}", unit_dir.display()))?;
+    write_unit(&unit_dir.join(SOCKET_UNIT_NAME), &socket_unit())?;
+    write_unit(
+        &unit_dir.join(SERVICE_UNIT_NAME),
+        &service_unit(&exe.to_string_lossy(), config_path),
+    )?;
+
+    systemctl(&["daemon-reload"])?;
+    systemctl(&["enable", "--now", SOCKET_UNIT_NAME])?;
+
+    Ok(unit_dir)
+}
+
+/// Stops the server and the socket and removes both units
+pub fn uninstall() -> anyhow::Result<()> {
+    // Disabling fails if the units have never been enabled, which is fine while uninstalling
+    systemctl(&["disable", "--now", SOCKET_UNIT_NAME, SERVICE_UNIT_NAME]).ok();
+
+    for unit in [SOCKET_UNIT_NAME, SERVICE_UNIT_NAME] {
+        let path = unit_dir().join(unit);
+
+        if path.exists() {
+            remove_file(&path).with_context(|| format!("Could not remove {}", path.display()))?;
+        }
+    }
+
+    systemctl(&["daemon-reload"])
+}
+
+fn unit_dir() -> PathBuf {
+    PathBuf::from(shellexpand::tilde("~/.config/systemd/user").as_ref())
+}
+
+fn write_unit(path: &Path, content: &str) -> anyhow::Result<()> {
+    write(path, content).with_context(|| format!("Could not write {}", path.display()))
+}
+
+fn systemctl(args: &[&str]) -> anyhow::Result<()> {
+    let status = Command::new("systemctl")
+        .arg("--user")
+        .args(args)
+        .status()
+        .context("Could not run systemctl")?;
+
+    if !status.success() {
+        bail!("systemctl --user {} exited with {}", args.join(" "), status);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,269 @@
+//! Team mode: participants on other machines join the timer of this server via TCP.
+//!
+//! The server stays authoritative - participants receive the same view states as local
+//! clients and are therefore always in sync with them. Depending on the token a participant
+//! joins with, it may either control the timer or only watch it (see [Role]).
+//!
+//! Participants either send [ClientToServerMsg::Join] or speak the plain-text protocol (see
+//! [super::text_protocol]) and send `JOIN <token>` as their first line.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{bail, Context};
+use futures::io::{BufReader, Cursor};
+use futures::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWrite};
+use log::{info, warn};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast::Sender as BroadcastSender;
+use tokio::sync::watch::Receiver as WatchReceiver;
+use tokio::task::spawn_blocking;
+use tokio::time::timeout;
+use tokio_util::compat::{TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt};
+
+use super::action_queue::ActionSender;
+use super::clients::Clients;
+use super::start::handle_conn;
+use super::text_protocol;
+use super::timer_output::TimerOutputAction;
+use crate::config::TeamConfig;
+use crate::ipc::{
+    ClientToServerMsg, ErrorCode, IpcReader, IpcWriter, Role, ServerToClientMsg, Snapshot,
+    Transport,
+};
+use crate::secrets;
+
+/// Time a participant has to send its [ClientToServerMsg::Join] after connecting
+const JOIN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Resolved tokens of a team session
+#[derive(Clone)]
+struct TeamTokens {
+    control: Option<String>,
+    viewer: Option<String>,
+}
+
+impl TeamTokens {
+    /// Resolves the configured tokens, which might be secret references
+    async fn resolve(config: &TeamConfig) -> anyhow::Result<Self> {
+        let control_token = config.control_token.clone();
+        let viewer_token = config.viewer_token.clone();
+
+        // Resolving secrets might block (e.g. while waiting for the keyring)
+        let tokens = spawn_blocking(move || -> anyhow::Result<Self> {
+            Ok(Self {
+                control: control_token.as_deref().map(secrets::resolve).transpose()?,
+                viewer: viewer_token.as_deref().map(secrets::resolve).transpose()?,
+            })
+        })
+        .await
+        .context("Could not resolve team tokens")??;
+
+        if tokens.control.is_none() && tokens.viewer.is_none() {
+            bail!("Neither a control_token nor a viewer_token has been configured");
+        }
+
+        Ok(tokens)
+    }
+
+    /// Role which is granted for the given token (control takes precedence)
+    fn role(&self, token: &str) -> Option<Role> {
+        let matches = |expected: &Option<String>| {
+            expected
+                .as_deref()
+                .is_some_and(|expected| constant_time_eq(expected, token))
+        };
+
+        if matches(&self.control) {
+            Some(Role::Controller)
+        } else if matches(&self.viewer) {
+            Some(Role::Viewer)
+        } else {
+            None
+        }
+    }
+}
+
+/// Accepts participants on the configured address, until the server shuts down
+pub async fn serve(
+    config: TeamConfig,
+    clients: Clients,
+    timer_input_sender: ActionSender,
+    timer_output_sender: Arc<BroadcastSender<TimerOutputAction>>,
+    snapshot_receiver: WatchReceiver<Option<Snapshot>>,
+) -> anyhow::Result<()> {
+    let Some(address) = &config.listen else {
+        return Ok(());
+    };
+
+    let tokens = TeamTokens::resolve(&config).await?;
+    let listener = TcpListener::bind(address)
+        .await
+        .context(format!("Could not bind to {}", address))?;
+
+    info!("Accepting team participants on {}", address);
+
+    loop {
+        let (stream, peer) = match listener.accept().await {
+            Ok(connection) => connection,
+            Err(error) => {
+                warn!("Could not accept team participant: {}", error);
+                continue;
+            }
+        };
+
+        let tokens = tokens.clone();
+        let clients = clients.clone();
+        let input_tx = timer_input_sender.clone();
+        let output_tx = timer_output_sender.clone();
+        let snapshot_rx = snapshot_receiver.clone();
+
+        tokio::spawn(async move {
+            if let Err(error) = handle_participant(
+                stream,
+                peer,
+                &tokens,
+                &clients,
+                input_tx,
+                &output_tx,
+                snapshot_rx,
+            )
+            .await
+            {
+                warn!("Team participant {}: {:#}", peer, error);
+            }
+        });
+    }
+}
+
+/// Lets the participant join with its token and handles its connection afterwards
+async fn handle_participant(
+    stream: TcpStream,
+    peer: SocketAddr,
+    tokens: &TeamTokens,
+    clients: &Clients,
+    timer_input_sender: ActionSender,
+    timer_output_sender: &BroadcastSender<TimerOutputAction>,
+    snapshot_receiver: WatchReceiver<Option<Snapshot>>,
+) -> anyhow::Result<()> {
+    stream.set_nodelay(true).ok();
+
+    let (reader, writer) = stream.into_split();
+    let mut reader = reader.compat();
+    let (is_text_protocol, first_bytes) = timeout(JOIN_TIMEOUT, text_protocol::detect(&mut reader))
+        .await
+        .context("Participant did not join in time")??;
+    let reader = BufReader::new(Cursor::new(first_bytes).chain(reader));
+
+    if is_text_protocol {
+        return handle_plain_text_participant(
+            reader,
+            writer.compat_write(),
+            peer,
+            tokens,
+            clients,
+            timer_input_sender,
+            timer_output_sender,
+        )
+        .await;
+    }
+
+    let mut reader = IpcReader::new(reader);
+    let mut writer = IpcWriter::new(writer.compat_write());
+
+    let msg = timeout(JOIN_TIMEOUT, reader.recv::<ClientToServerMsg>())
+        .await
+        .context("Participant did not join in time")??;
+
+    let role = match msg {
+        ClientToServerMsg::Join { token } => tokens.role(&token),
+        _ => None,
+    };
+
+    let Some(role) = role else {
+        writer
+            .send(&ServerToClientMsg::Error {
+                code: ErrorCode::Unauthorized,
+                message: "Invalid team token".to_string(),
+            })
+            .await
+            .ok();
+
+        bail!("Rejected participant without a valid token");
+    };
+
+    writer.send(&ServerToClientMsg::Joined { role }).await?;
+    info!("Team participant {} joined as {}", peer, role);
+
+    let client = clients.register(role, Transport::Tcp, None, Some(peer.to_string()));
+
+    handle_conn(
+        reader,
+        writer,
+        client,
+        timer_input_sender,
+        timer_output_sender.subscribe(),
+        snapshot_receiver,
+    )
+    .await
+}
+
+/// Lets a plain-text participant join via `JOIN <token>` and answers its commands afterwards
+async fn handle_plain_text_participant<R, W>(
+    mut reader: R,
+    mut writer: W,
+    peer: SocketAddr,
+    tokens: &TeamTokens,
+    clients: &Clients,
+    timer_input_sender: ActionSender,
+    timer_output_sender: &BroadcastSender<TimerOutputAction>,
+) -> anyhow::Result<()>
+where
+    R: AsyncBufRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut line = String::new();
+    timeout(JOIN_TIMEOUT, reader.read_line(&mut line))
+        .await
+        .context("Participant did not join in time")?
+        .context("Could not read JOIN")?;
+
+    let role = line
+        .trim()
+        .split_once(' ')
+        .filter(|(command, _)| command.eq_ignore_ascii_case("JOIN"))
+        .and_then(|(_, token)| tokens.role(token.trim()));
+
+    let Some(role) = role else {
+        text_protocol::write_line(&mut writer, "ERROR Invalid team token")
+            .await
+            .ok();
+
+        bail!("Rejected participant without a valid token");
+    };
+
+    text_protocol::write_line(&mut writer, &format!("OK {}", role)).await?;
+    info!("Team participant {} joined as {} (plain-text)", peer, role);
+
+    let client = clients.register(role, Transport::Tcp, None, Some(peer.to_string()));
+
+    text_protocol::handle_conn(
+        reader,
+        writer,
+        client,
+        timer_input_sender,
+        timer_output_sender.subscribe(),
+    )
+    .await
+}
+
+/// Compares two strings in constant time (regarding their content), so that tokens can't be
+/// guessed by measuring response times
+pub(super) fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0, |difference, (a, b)| difference | (a ^ b))
+            == 0
+}
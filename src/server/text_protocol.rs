@@ -0,0 +1,199 @@
+//! Plain-text protocol on the zentime socket, so that the timer can be controlled without the
+//! binary protocol (e.g. via `zentime repl` over ssh or via netcat/socat).
+//!
+//! Each command is a single line and is answered with a single line:
+//!
+//! * `STATUS` - remaining time and phase of the timer (e.g. `24:59 Focus`)
+//! * `TOGGLE` - plays/pauses the timer and answers with `OK`
+//! * `SKIP` - skips to the next interval or break and answers with `OK`
+//! * `HELP` - lists the available commands
+//!
+//! Commands are case-insensitive, unknown commands (and commands which the role of the client
+//! does not permit) are answered with `ERROR <reason>`.
+//! Before the server shuts down, it sends `BYE <reason>` and closes the connection.
+//! The protocol is detected by the first bytes of a connection: message frames start with
+//! their length, which never exceeds [MAX_FRAME_LENGTH], while a text command starts with
+//! characters (which would announce a much larger frame). Lines which are shorter than a frame
+//! header (e.g. `?`) are detected by their line break, once no further bytes follow.
+//!
+//! Team participants may use the protocol as well, by sending `JOIN <token>` as first line
+//! (see [super::team]).
+
+use std::io;
+use std::time::Duration;
+
+use anyhow::Context;
+use futures::{
+    AsyncBufRead, AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, StreamExt,
+};
+use log::info;
+use tokio::select;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::broadcast::Receiver as BroadcastReceiver;
+use tokio::time::timeout;
+use zentime_rs_timer::pomodoro_timer_action::PomodoroTimerAction;
+
+use super::action_queue::ActionSender;
+use super::clients::ClientHandle;
+use super::timer_output::TimerOutputAction;
+use crate::client::overlay::overlay_text;
+use crate::ipc::{ClientToServerMsg, FRAME_HEADER_LENGTH, MAX_FRAME_LENGTH};
+
+/// Available commands, as listed by `HELP`
+const HELP: &str = "Commands: STATUS, TOGGLE, SKIP, HELP";
+
+/// Time in which the rest of a frame header has to arrive, after a line break has been received.
+/// Frames are written at once, while a short command is followed by nothing until it is answered.
+const SHORT_LINE_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Reads the first bytes of a connection, until they tell whether the connection uses the
+/// plain-text protocol instead of message frames.
+/// Returns that decision together with the bytes which have been read, so that they can be
+/// handed on to whichever protocol handles the connection.
+pub(super) async fn detect<R>(reader: &mut R) -> io::Result<(bool, Vec<u8>)>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut first_bytes = Vec::with_capacity(FRAME_HEADER_LENGTH);
+    let mut buffer = [0; FRAME_HEADER_LENGTH];
+
+    while first_bytes.len() < FRAME_HEADER_LENGTH {
+        let missing = &mut buffer[..FRAME_HEADER_LENGTH - first_bytes.len()];
+
+        let read = if is_short_line(&first_bytes) {
+            match timeout(SHORT_LINE_TIMEOUT, reader.read(missing)).await {
+                Ok(read) => read?,
+                Err(_) => return Ok((true, first_bytes)),
+            }
+        } else {
+            reader.read(missing).await?
+        };
+
+        if read == 0 {
+            if is_short_line(&first_bytes) {
+                return Ok((true, first_bytes));
+            }
+
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+
+        first_bytes.extend_from_slice(&buffer[..read]);
+    }
+
+    let header = first_bytes[..]
+        .try_into()
+        .expect("First bytes have the length of a frame header");
+
+    Ok((is_text_protocol(header), first_bytes))
+}
+
+/// Denotes if a connection, which has started with the given bytes, uses the plain-text
+/// protocol instead of message frames
+fn is_text_protocol(first_bytes: [u8; FRAME_HEADER_LENGTH]) -> bool {
+    u32::from_le_bytes(first_bytes) as usize > MAX_FRAME_LENGTH
+        && first_bytes.iter().all(u8::is_ascii)
+}
+
+/// Denotes if the bytes, which are shorter than a frame header, might be a complete command
+fn is_short_line(bytes: &[u8]) -> bool {
+    bytes.last() == Some(&b'\n') && bytes.iter().all(u8::is_ascii)
+}
+
+/// Answers the commands of a plain-text connection, until the client closes it
+/// (plain-text clients are usually driven by a human and therefore never time out)
+pub(super) async fn handle_conn<R, W>(
+    reader: R,
+    mut writer: W,
+    client: ClientHandle,
+    timer_input_sender: ActionSender,
+    mut timer_output_receiver: BroadcastReceiver<TimerOutputAction>,
+) -> anyhow::Result<()>
+where
+    R: AsyncBufRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut lines = reader.lines();
+    // The next state, which is broadcast by the timer, answers a pending `STATUS`
+    let mut is_status_pending = false;
+
+    loop {
+        select! {
+            line = lines.next() => {
+                let Some(line) = line else {
+                    break;
+                };
+                let line = line.context("Could not read command")?;
+                client.touch();
+                let command = line.trim().to_uppercase();
+
+                let answer = match command.as_str() {
+                    "" => continue,
+                    "STATUS" => {
+                        is_status_pending = true;
+                        timer_input_sender.send(PomodoroTimerAction::None);
+                        continue;
+                    }
+                    "TOGGLE" => control(
+                        &client,
+                        &timer_input_sender,
+                        ClientToServerMsg::PlayPause,
+                        PomodoroTimerAction::PlayPause,
+                    ),
+                    "SKIP" => control(
+                        &client,
+                        &timer_input_sender,
+                        ClientToServerMsg::Skip,
+                        PomodoroTimerAction::Skip,
+                    ),
+                    "HELP" => HELP.to_string(),
+                    _ => format!("ERROR Unknown command '{}' (see HELP)", line.trim()),
+                };
+
+                write_line(&mut writer, &answer).await?;
+            },
+            value = timer_output_receiver.recv() => match value {
+                Ok(TimerOutputAction::Timer(state)) if is_status_pending => {
+                    is_status_pending = false;
+                    write_line(&mut writer, &overlay_text(&state)).await?;
+                }
+                Ok(TimerOutputAction::ShuttingDown) => {
+                    write_line(&mut writer, "BYE Server is shutting down").await?;
+                    break;
+                }
+                // Only answers are sent, therefore all other broadcasts are skipped
+                Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            },
+        }
+    }
+
+    info!("Closing plain-text connection");
+    Ok(())
+}
+
+/// Applies the action, if the role of the client permits the corresponding message
+fn control(
+    client: &ClientHandle,
+    timer_input_sender: &ActionSender,
+    msg: ClientToServerMsg,
+    action: PomodoroTimerAction,
+) -> String {
+    let role = client.role();
+
+    if !role.permits(&msg) {
+        return format!("ERROR A team {} is not allowed to do this", role);
+    }
+
+    timer_input_sender.send(action);
+    "OK".to_string()
+}
+
+pub(super) async fn write_line<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    line: &str,
+) -> anyhow::Result<()> {
+    writer
+        .write_all(format!("{}\n", line).as_bytes())
+        .await
+        .context("Could not send answer")
+}
@@ -1,8 +1,16 @@
 use serde::{Deserialize, Serialize};
 use zentime_rs_timer::pomodoro_timer::ViewState;
 
-/// Carries the timer state as view state
+use crate::ipc::ErrorCode;
+
+/// Carries the timer state as view state (or an event/error which should be reported to clients)
+// Broadcasts are almost always timer states, which are therefore not boxed
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TimerOutputAction {
     Timer(ViewState),
+    ConfigReloaded,
+    Error { code: ErrorCode, message: String },
+    Bell { message: String },
+    ShuttingDown,
 }
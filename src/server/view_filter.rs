@@ -0,0 +1,106 @@
+//! Tailors the timer states, which are sent to a single client, to its [ClientPreferences]
+
+use std::time::{Duration, Instant};
+
+use zentime_rs_timer::clock;
+use zentime_rs_timer::pomodoro_timer::ViewState;
+use zentime_rs_timer::util::time_to_seconds;
+
+use crate::ipc::{ClientPreferences, TimePrecision, UpdateMode};
+
+/// Decides which timer states are sent to a client and in which form.
+/// States which would not change anything for the client are dropped, so that e.g. simple
+/// status bar consumers don't have to process a state on every tick.
+#[derive(Debug, Clone, Default)]
+pub struct ViewFilter {
+    preferences: ClientPreferences,
+    last_sent: Option<ViewState>,
+    last_sent_at: Option<Instant>,
+    is_forced: bool,
+}
+
+impl ViewFilter {
+    /// Applies the preferences the client has declared
+    pub fn set_preferences(&mut self, preferences: ClientPreferences) {
+        self.preferences = preferences;
+        self.last_sent = None;
+    }
+
+    /// Makes sure that the next state is sent in any case (e.g. because the client has
+    /// requested it via sync)
+    pub fn force_next(&mut self) {
+        self.is_forced = true;
+    }
+
+    /// Tailors a state which is sent to the client in any case (e.g. as answer to a sync)
+    pub fn apply_forced(&mut self, state: ViewState) -> ViewState {
+        let state = self.tailor(state);
+        self.remember(&state);
+
+        state
+    }
+
+    /// Returns the state which should be sent to the client, or [None] if the client is not
+    /// interested in it
+    pub fn apply(&mut self, state: ViewState) -> Option<ViewState> {
+        let state = self.tailor(state);
+
+        let is_relevant = match (&self.last_sent, self.preferences.updates) {
+            (None, _) => true,
+            // Broadcasts of unchanged states confirm client actions, which is why they are only
+            // dropped if the time has been rounded
+            (Some(last_sent), UpdateMode::Ticks) if self.is_resync_pending(last_sent, &state) => {
+                false
+            }
+            (Some(last_sent), UpdateMode::Ticks) => {
+                self.preferences.precision == TimePrecision::Seconds || *last_sent != state
+            }
+            (Some(last_sent), UpdateMode::Events) => !last_sent.differs_only_in_time(&state),
+        };
+
+        if !is_relevant && !self.is_forced {
+            return None;
+        }
+
+        self.remember(&state);
+
+        Some(state)
+    }
+
+    fn tailor(&self, mut state: ViewState) -> ViewState {
+        if self.preferences.precision == TimePrecision::Minutes {
+            state.time = started_minutes(&state.time);
+            // Counting down seconds would defeat the purpose of rounded times
+            state.progress = None;
+        }
+
+        state
+    }
+
+    /// Denotes if the client counts down on its own and the state can wait for the next resync,
+    /// because nothing but the time has changed
+    fn is_resync_pending(&self, last_sent: &ViewState, state: &ViewState) -> bool {
+        let resync = Duration::from_secs(self.preferences.resync_seconds);
+
+        !resync.is_zero()
+            && last_sent.time != state.time
+            && last_sent.differs_only_in_time(state)
+            && self
+                .last_sent_at
+                .is_some_and(|sent_at| clock::now().duration_since(sent_at) < resync)
+    }
+
+    fn remember(&mut self, state: &ViewState) {
+        self.is_forced = false;
+        self.last_sent = Some(state.clone());
+        self.last_sent_at = Some(clock::now());
+    }
+}
+
+/// Formats the remaining time as started minutes (e.g. `25m` for `24:59`)
+fn started_minutes(time: &str) -> String {
+    match time_to_seconds(time) {
+        Some(seconds) => format!("{}m", seconds.div_ceil(60)),
+        None => time.to_string(),
+    }
+}
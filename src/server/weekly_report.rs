@@ -0,0 +1,74 @@
+//! Scheduler of the weekly report (see [crate::report]), which is written at the configured day
+//! and local time while the server is running.
+//!
+//! The wall clock is checked in short steps instead of sleeping until the report is due, because
+//! a suspended machine doesn't advance monotonic timers. A report, which has become due while the
+//! machine has been suspended, is therefore written right after it has woken up.
+
+use std::time::Duration;
+
+use chrono::{Datelike, NaiveDateTime, NaiveTime, Weekday};
+use log::{error, info};
+use tokio::task::spawn_blocking;
+use tokio::time::sleep;
+use zentime_rs_timer::clock;
+
+use crate::config::{HistoryConfig, ReportConfig};
+use crate::report::WeeklyReport;
+use crate::wall_clock::WallClock;
+
+/// Longest time between two checks of the wall clock
+const MAX_SLEEP: Duration = Duration::from_secs(60);
+
+/// Writes (and sends) a report each week, until the server shuts down
+pub async fn run(config: ReportConfig, history: HistoryConfig, wall_clock: WallClock) {
+    loop {
+        let due = next_due(config.weekday, config.time, wall_clock.now());
+        info!("Next weekly report is due at {}", due);
+
+        wait_until(wall_clock, due).await;
+
+        let config = config.clone();
+        let history = history.clone();
+
+        let result = spawn_blocking(move || {
+            let report = WeeklyReport::load(&history, wall_clock, due)?;
+            let path = report.write(&config)?;
+            info!("Weekly report has been written to {}", path.display());
+
+            report.send(&config)
+        })
+        .await;
+
+        match result {
+            Ok(Ok(())) => {}
+            Ok(Err(error)) => error!("Could not create weekly report: {:#}", error),
+            Err(error) => error!("Could not create weekly report: {}", error),
+        }
+    }
+}
+
+/// Waits until the wall clock has reached the given local time
+pub(super) async fn wait_until(wall_clock: WallClock, due: NaiveDateTime) {
+    while wall_clock.now() < due {
+        let remaining = wall_clock
+            .system_time(due)
+            .duration_since(clock::system_now())
+            .unwrap_or_default();
+
+        sleep(remaining.clamp(Duration::from_secs(1), MAX_SLEEP)).await;
+    }
+}
+
+/// Next local time after `now`, which matches the given weekday and time
+pub(super) fn next_due(weekday: Weekday, time: NaiveTime, now: NaiveDateTime) -> NaiveDateTime {
+    let days_ahead =
+        (7 + weekday.num_days_from_monday() - now.weekday().num_days_from_monday()) % 7;
+    let due = (now.date() + chrono::Days::new(u64::from(days_ahead))).and_time(time);
+
+    if due <= now {
+        due + chrono::Days::new(7)
+    } else {
+        due
+    }
+}
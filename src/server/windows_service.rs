@@ -0,0 +1,255 @@
+//! Running the zentime server as a Windows service.
+//!
+//! The service is registered via `zentime server install-service` and is then started by the
+//! Windows service control manager, which runs `zentime server run-service`.
+//! Logs are written to the Windows event log.
+
+use std::env::current_exe;
+use std::ffi::OsString;
+use std::iter::once;
+use std::ptr::{null, null_mut};
+use std::sync::mpsc::channel;
+use std::sync::OnceLock;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::Context;
+use log::{error, info, Level, LevelFilter, Log, Metadata, Record};
+use windows_service::service::{
+    ServiceAccess, ServiceControl, ServiceControlAccept, ServiceErrorControl, ServiceExitCode,
+    ServiceInfo, ServiceStartType, ServiceState, ServiceStatus, ServiceType,
+};
+use windows_service::service_control_handler::{self, ServiceControlHandlerResult};
+use windows_service::service_manager::{ServiceManager, ServiceManagerAccess};
+use windows_service::{define_windows_service, service_dispatcher};
+use windows_sys::Win32::Foundation::HANDLE;
+use windows_sys::Win32::System::EventLog::{
+    RegisterEventSourceW, ReportEventW, EVENTLOG_ERROR_TYPE, EVENTLOG_INFORMATION_TYPE,
+    EVENTLOG_WARNING_TYPE,
+};
+
+use crate::config::{Config, ConfigLoader};
+use crate::server::start;
+
+const SERVICE_NAME: &str = "zentime";
+const SERVICE_DISPLAY_NAME: &str = "zentime server";
+const SERVICE_TYPE: ServiceType = ServiceType::OWN_PROCESS;
+
+/// Config (and its loader) handed from [run] to the service main function, which is called by
+/// the service dispatcher and can therefore not receive it as argument
+static SERVICE_CONFIG: OnceLock<(Config, ConfigLoader)> = OnceLock::new();
+
+define_windows_service!(ffi_service_main, service_main);
+
+/// Registers the zentime server as Windows service, which is started automatically.
+/// The given config path is handed to the server on each service start.
+pub fn install(config_path: &str) -> anyhow::Result<()> {
+    let manager = ServiceManager::local_computer(
+        None::<&str>,
+        ServiceManagerAccess::CONNECT | ServiceManagerAccess::CREATE_SERVICE,
+    )
+    .context("Could not connect to service manager")?;
+
+    let service_info = ServiceInfo {
+        name: OsString::from(SERVICE_NAME),
+        display_name: OsString::from(SERVICE_DISPLAY_NAME),
+        service_type: SERVICE_TYPE,
+        start_type: ServiceStartType::AutoStart,
+        error_control: ServiceErrorControl::Normal,
+        executable_path: current_exe().context("Could not determine zentime executable")?,
+        launch_arguments: vec![
+            OsString::from("server"),
+            OsString::from("run-service"),
+            OsString::from("-c"),
+            OsString::from(config_path),
+        ],
+        dependencies: vec![],
+        account_name: None,
+        account_password: None,
+    };
+
+    let service = manager
+        .create_service(&service_info, ServiceAccess::CHANGE_CONFIG)
+        .context("Could not create service")?;
+    service
+        .set_description("Pomodoro and productivity timer server")
+        .context("Could not set service description")?;
+
+    Ok(())
+}
+
+/// Stops and removes a previously installed zentime service
+pub fn uninstall() -> anyhow::Result<()> {
+    let manager = ServiceManager::local_computer(None::<&str>, ServiceManagerAccess::CONNECT)
+        .context("Could not connect to service manager")?;
+
+    let service = manager
+        .open_service(
+            SERVICE_NAME,
+            ServiceAccess::QUERY_STATUS | ServiceAccess::STOP | ServiceAccess::DELETE,
+        )
+        .context("Could not open service")?;
+
+    let status = service
+        .query_status()
+        .context("Could not query service status")?;
+
+    if status.current_state != ServiceState::Stopped {
+        service.stop().context("Could not stop service")?;
+    }
+
+    service.delete().context("Could not delete service")?;
+
+    Ok(())
+}
+
+/// Hands the current process over to the Windows service dispatcher.
+/// This blocks until the service has been stopped.
+pub fn run(config: Config, config_loader: ConfigLoader) -> anyhow::Result<()> {
+    EventLogLogger::init().context("Could not initialize event log")?;
+
+    SERVICE_CONFIG.set((config, config_loader)).ok();
+
+    service_dispatcher::start(SERVICE_NAME, ffi_service_main)
+        .context("Could not start service dispatcher")?;
+
+    Ok(())
+}
+
+fn service_main(_arguments: Vec<OsString>) {
+    if let Err(error) = run_service() {
+        error!("Service failed: {:?}", error);
+    }
+}
+
+/// Runs the server on a separate thread, until the service control manager
+/// tells us to stop (or the server terminates on its own).
+fn run_service() -> anyhow::Result<()> {
+    let (config, config_loader) = SERVICE_CONFIG
+        .get()
+        .cloned()
+        .context("Service has been started without config")?;
+
+    let (shutdown_tx, shutdown_rx) = channel();
+    let server_shutdown_tx = shutdown_tx.clone();
+
+    let status_handle =
+        service_control_handler::register(SERVICE_NAME, move |control| match control {
+            ServiceControl::Stop | ServiceControl::Shutdown => {
+                shutdown_tx.send(()).ok();
+                ServiceControlHandlerResult::NoError
+            }
+            ServiceControl::Interrogate => ServiceControlHandlerResult::NoError,
+            _ => ServiceControlHandlerResult::NotImplemented,
+        })
+        .context("Could not register service control handler")?;
+
+    status_handle
+        .set_service_status(service_status(
+            ServiceState::Running,
+            ServiceControlAccept::STOP | ServiceControlAccept::SHUTDOWN,
+        ))
+        .context("Could not report running service")?;
+
+    info!("Service started");
+
+    thread::spawn(move || {
+        if let Err(error) = start(config, config_loader) {
+            error!("A server error occured: {}", error);
+        }
+
+        server_shutdown_tx.send(()).ok();
+    });
+
+    shutdown_rx.recv().ok();
+
+    info!("Service stopped");
+
+    status_handle
+        .set_service_status(service_status(
+            ServiceState::Stopped,
+            ServiceControlAccept::empty(),
+        ))
+        .context("Could not report stopped service")?;
+
+    Ok(())
+}
+
+fn service_status(state: ServiceState, controls_accepted: ServiceControlAccept) -> ServiceStatus {
+    ServiceStatus {
+        service_type: SERVICE_TYPE,
+        current_state: state,
+        controls_accepted,
+        exit_code: ServiceExitCode::Win32(0),
+        checkpoint: 0,
+        wait_hint: Duration::default(),
+        process_id: None,
+    }
+}
+
+/// [Log] implementation which writes to the Windows event log
+#[derive(Debug, Clone, Copy)]
+struct EventLogLogger {
+    event_source: HANDLE,
+}
+
+impl EventLogLogger {
+    fn init() -> anyhow::Result<()> {
+        let source_name = to_wide_string(SERVICE_NAME);
+
+        // SAFETY: `source_name` is a valid, null-terminated wide string
+        let event_source = unsafe { RegisterEventSourceW(null(), source_name.as_ptr()) };
+
+        if event_source == 0 {
+            anyhow::bail!("Could not register event source");
+        }
+
+        log::set_boxed_logger(Box::new(Self { event_source })).context("Could not set logger")?;
+        log::set_max_level(LevelFilter::Info);
+
+        Ok(())
+    }
+}
+
+impl Log for EventLogLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let event_type = match record.level() {
+            Level::Error => EVENTLOG_ERROR_TYPE,
+            Level::Warn => EVENTLOG_WARNING_TYPE,
+            _ => EVENTLOG_INFORMATION_TYPE,
+        };
+
+        let message = to_wide_string(&record.args().to_string());
+        let strings = [message.as_ptr()];
+
+        // SAFETY: `strings` contains a single valid, null-terminated wide string, which outlives
+        // this call
+        unsafe {
+            ReportEventW(
+                self.event_source,
+                event_type,
+                0,
+                0,
+                null_mut(),
+                1,
+                0,
+                strings.as_ptr(),
+                null(),
+            );
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+fn to_wide_string(value: &str) -> Vec<u16> {
+    value.encode_utf16().chain(once(0)).collect()
+}
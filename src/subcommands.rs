@@ -1,6 +1,86 @@
+#[cfg(feature = "history")]
+pub mod calendar;
+pub mod countdown;
+#[cfg(feature = "history")]
+pub mod export;
+pub mod hold;
+pub mod hooks;
+pub mod init;
+pub mod install_autostart;
+pub mod ipc_schema;
+#[cfg(feature = "tui")]
+pub mod join;
+pub mod listen;
+pub mod log;
+pub mod long_break;
+pub mod overlay;
+pub mod pause_until;
+pub mod plan;
+#[cfg(feature = "tui")]
+pub mod popup;
 pub mod postpone;
+pub mod profile;
 pub mod query_server_once;
+pub mod reload;
+pub mod repl;
+#[cfg(feature = "history")]
+pub mod replay;
+#[cfg(feature = "history")]
+pub mod report;
 pub mod reset_timer;
+pub mod secrets;
 pub mod server;
 pub mod skip_timer;
+#[cfg(feature = "history")]
+pub mod stats;
+pub mod status;
+pub mod task;
+pub mod timer;
 pub mod toggle_timer;
+#[cfg(all(feature = "tray", target_os = "linux"))]
+pub mod tray;
+
+use futures::io::BufReader;
+use std::process;
+use zentime_rs::client::one_shot_connection::one_shot_connection;
+use zentime_rs::ipc::ClientToServerMsg;
+use zentime_rs::ipc::InterProcessCommunication;
+use zentime_rs::ipc::ServerToClientMsg;
+
+use crate::cli_error::CliError;
+
+/// Sends the change to the server and prints the confirmation, once the server has applied it
+pub async fn send_change(msg: ClientToServerMsg, confirmation: &str, silent: bool) {
+    let (reader, mut writer) = match one_shot_connection().await {
+        Ok(c) => c,
+        Err(error) => CliError::Connect(error).exit(),
+    };
+
+    let mut reader = BufReader::new(reader);
+
+    if let Err(err) = InterProcessCommunication::send_ipc_message(msg, &mut writer).await {
+        CliError::Communication(err).exit()
+    };
+
+    let msg_result =
+        InterProcessCommunication::recv_ipc_message::<ServerToClientMsg>(&mut reader).await;
+
+    let mut has_failed = false;
+
+    match msg_result {
+        Ok(ServerToClientMsg::Timer(_)) if !silent => println!("{}", confirmation),
+        Ok(ServerToClientMsg::Error { message, .. }) => {
+            eprintln!("{}", message);
+            has_failed = true;
+        }
+        _ => {}
+    }
+
+    InterProcessCommunication::send_ipc_message(ClientToServerMsg::Detach, &mut writer)
+        .await
+        .ok();
+
+    if has_failed {
+        process::exit(1);
+    }
+}
@@ -0,0 +1,33 @@
+use std::fs::write;
+
+use zentime_rs::calendar::{recorded_blocks, to_ics};
+use zentime_rs::config::HistoryConfig;
+use zentime_rs::history::History;
+use zentime_rs_timer::clock;
+
+use crate::cli_error::CliError;
+
+/// Prints the recorded focus blocks of the last `days` days as iCalendar document, or writes it
+/// to the given file (e.g. a file which is synced into a calendar app).
+/// Planned focus blocks are only part of the feed served by the HTTP server, because they depend
+/// on the running timer.
+pub fn calendar(config: &HistoryConfig, days: u64, output: Option<&str>) {
+    let records = match History::open(config).and_then(|history| history.records_of_last_days(days))
+    {
+        Ok(records) => records,
+        Err(error) => CliError::Other(format!("{:#}", error)).exit(),
+    };
+
+    let ics = to_ics(&recorded_blocks(&records), clock::system_now());
+
+    let Some(output) = output else {
+        print!("{}", ics);
+        return;
+    };
+
+    let path = shellexpand::tilde(output);
+
+    if let Err(error) = write(path.as_ref(), ics) {
+        CliError::Other(format!("Could not write {}: {}", path, error)).exit();
+    }
+}
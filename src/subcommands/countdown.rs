@@ -0,0 +1,26 @@
+use std::time::Duration;
+
+use zentime_rs::ipc::ClientToServerMsg;
+use zentime_rs_timer::util::seconds_to_time;
+
+use super::send_change;
+
+/// Starts a one-off countdown, which notifies once it has run out
+#[tokio::main]
+pub async fn countdown(duration: Duration, label: Option<String>, silent: bool) {
+    let time = seconds_to_time(duration.as_secs());
+    let confirmation = match &label {
+        Some(label) => format!("Started countdown '{}' ({})", label, time),
+        None => format!("Started countdown ({})", time),
+    };
+
+    send_change(
+        ClientToServerMsg::Countdown {
+            seconds: duration.as_secs(),
+            label,
+        },
+        &confirmation,
+        silent,
+    )
+    .await;
+}
@@ -0,0 +1,111 @@
+use std::fs::write;
+use std::time::UNIX_EPOCH;
+
+use chrono::{Days, NaiveDate};
+use clap::ValueEnum;
+use zentime_rs::config::HistoryConfig;
+use zentime_rs::export::{completed_intervals, to_csv, to_json, to_timew};
+use zentime_rs::history::History;
+use zentime_rs::wall_clock::WallClock;
+
+use crate::cli_error::CliError;
+
+/// Output format of `zentime export`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    /// CSV with the columns of Toggl's CSV import, in local time
+    Csv,
+
+    /// JSON array with one object per interval
+    Json,
+
+    /// JSON which can be imported into timewarrior (`timew import`)
+    Timew,
+}
+
+/// Day of the command line - a date or a day relative to today
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Day {
+    /// The current day
+    Today,
+
+    /// The day before the current day
+    Yesterday,
+
+    /// A fixed date
+    Date(NaiveDate),
+}
+
+impl Day {
+    /// Date of the day according to the wall clock
+    fn date(self, wall_clock: WallClock) -> NaiveDate {
+        match self {
+            Day::Today => wall_clock.today(),
+            Day::Yesterday => wall_clock.today() - Days::new(1),
+            Day::Date(date) => date,
+        }
+    }
+}
+
+/// Parses a date like `2024-01-31`, `today` or `yesterday`
+pub fn parse_day(value: &str) -> Result<Day, String> {
+    match value.trim() {
+        "today" => Ok(Day::Today),
+        "yesterday" => Ok(Day::Yesterday),
+        date => NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map(Day::Date)
+            .map_err(|_| {
+                format!(
+                    "'{}' is not a date like 2024-01-31, today or yesterday",
+                    value
+                )
+            }),
+    }
+}
+
+/// Prints the completed intervals between both days (inclusive) in the given format, or writes
+/// them to the given file. Without a first day, all recorded intervals up to the last day are
+/// exported.
+pub fn export(
+    config: &HistoryConfig,
+    wall_clock: WallClock,
+    from: Option<Day>,
+    to: Day,
+    format: ExportFormat,
+    output: Option<&str>,
+) {
+    let last_day = to.date(wall_clock);
+    let start = from.map_or(UNIX_EPOCH, |day| {
+        wall_clock.start_of_day(day.date(wall_clock))
+    });
+    let end = wall_clock.start_of_day(last_day + Days::new(1));
+
+    let records =
+        match History::open(config).and_then(|history| history.records_between(start..end)) {
+            Ok(records) => records,
+            Err(error) => CliError::Other(format!("{:#}", error)).exit(),
+        };
+
+    let intervals = completed_intervals(&records);
+    let exported = match format {
+        ExportFormat::Csv => Ok(to_csv(&intervals, wall_clock)),
+        ExportFormat::Json => to_json(&intervals),
+        ExportFormat::Timew => to_timew(&intervals),
+    };
+
+    let exported = match exported {
+        Ok(exported) => exported,
+        Err(error) => CliError::Other(format!("Could not export intervals: {}", error)).exit(),
+    };
+
+    let Some(output) = output else {
+        println!("{}", exported.trim_end());
+        return;
+    };
+
+    let path = shellexpand::tilde(output);
+
+    if let Err(error) = write(path.as_ref(), exported) {
+        CliError::Other(format!("Could not write {}: {}", path, error)).exit();
+    }
+}
@@ -0,0 +1,80 @@
+use futures::io::BufReader;
+use std::process;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use zentime_rs::client::one_shot_connection::one_shot_connection;
+use zentime_rs::ipc::ClientToServerMsg;
+use zentime_rs::ipc::InterProcessCommunication;
+use zentime_rs::ipc::ServerToClientMsg;
+use zentime_rs_timer::duration::{DurationError, HumanDuration};
+
+use crate::cli_error::CliError;
+
+/// Puts the pomodoro cycle on hold for the given duration
+#[tokio::main]
+pub async fn hold(duration: Duration, reason: String, silent: bool) {
+    let (reader, mut writer) = match one_shot_connection().await {
+        Ok(c) => c,
+        Err(error) => CliError::Connect(error).exit(),
+    };
+
+    let mut reader = BufReader::new(reader);
+
+    let Some(until) = SystemTime::now().checked_add(duration) else {
+        CliError::Other(format!("Can't hold for {}s", duration.as_secs())).exit()
+    };
+
+    let until = until
+        .duration_since(UNIX_EPOCH)
+        .map(|until| until.as_secs())
+        .unwrap_or_default();
+
+    if let Err(err) = InterProcessCommunication::send_ipc_message(
+        ClientToServerMsg::Hold { reason, until },
+        &mut writer,
+    )
+    .await
+    {
+        CliError::Communication(err).exit()
+    };
+
+    let msg_result =
+        InterProcessCommunication::recv_ipc_message::<ServerToClientMsg>(&mut reader).await;
+
+    let mut has_failed = false;
+
+    match msg_result {
+        Ok(ServerToClientMsg::Timer(state)) if !silent => {
+            if let Some(hold) = state.hold {
+                println!("{}", hold);
+            }
+        }
+        Ok(ServerToClientMsg::Error { message, .. }) => {
+            eprintln!("{}", message);
+            has_failed = true;
+        }
+        _ => {}
+    }
+
+    InterProcessCommunication::send_ipc_message(ClientToServerMsg::Detach, &mut writer)
+        .await
+        .ok();
+
+    if has_failed {
+        process::exit(1);
+    }
+}
+
+/// Parses durations like `45m`, `1h30m` or `90s` as [HumanDuration], so that plain numbers are
+/// seconds, just like inside the config. Zero is rejected.
+pub fn parse_duration(value: &str) -> Result<Duration, String> {
+    let duration: Duration = value
+        .parse::<HumanDuration>()
+        .map_err(|error| error.to_string())?
+        .into();
+
+    if duration.is_zero() {
+        return Err(DurationError::Invalid.to_string());
+    }
+
+    Ok(duration)
+}
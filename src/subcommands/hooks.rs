@@ -0,0 +1,69 @@
+use std::process;
+use zentime_rs::config::HooksConfig;
+use zentime_rs::hooks::{HookEnv, ENV_EVENT};
+
+/// Runs each configured hook command (`notifications.hooks` and the `[hooks]` section) once with
+/// sample data and reports whether it succeeded
+#[tokio::main]
+pub async fn test_hooks(notification_hooks: &[String], event_hooks: &HooksConfig) {
+    let sample = HookEnv::sample();
+
+    // Event hooks are run with the name of their own event
+    let hooks: Vec<(HookEnv, &str)> = notification_hooks
+        .iter()
+        .map(|hook| (sample.clone(), hook.as_str()))
+        .chain(event_hooks.commands().filter_map(|(event, command)| {
+            let env = HookEnv {
+                event: event.to_string(),
+                ..sample.clone()
+            };
+
+            command.map(|command| (env, command))
+        }))
+        .chain(event_hooks.on_countdown_end.as_deref().map(|command| {
+            let env = HookEnv {
+                event: "countdown_end".to_string(),
+                reminder: Some("Sample countdown".to_string()),
+                ..sample.clone()
+            };
+
+            (env, command)
+        }))
+        .collect();
+
+    if hooks.is_empty() {
+        println!("No hooks configured (see `notifications.hooks` and `[hooks]`)");
+        return;
+    }
+
+    println!("Running hooks with:");
+    for (name, value) in sample.vars() {
+        println!("  {}={}", name, value);
+    }
+
+    let mut has_failed = false;
+
+    for (env, hook) in hooks {
+        println!("\n$ {}", hook);
+
+        if env.event != sample.event {
+            println!("  ({}={})", ENV_EVENT, env.event);
+        }
+
+        match env.run(hook).await {
+            Ok(status) if status.success() => println!("-> ok"),
+            Ok(status) => {
+                println!("-> failed ({})", status);
+                has_failed = true;
+            }
+            Err(error) => {
+                println!("-> failed ({:#})", error);
+                has_failed = true;
+            }
+        }
+    }
+
+    if has_failed {
+        process::exit(1);
+    }
+}
@@ -0,0 +1,291 @@
+use std::fs;
+use std::io::{stdin, stdout, Write};
+use std::path::{Path, PathBuf};
+
+use figment::providers::{Format, Serialized, Toml};
+use figment::Figment;
+use zentime_rs::config::{Config, ConfigDraft, ThemeColor, ThemeColors, TuiLayout};
+#[cfg(feature = "notifications")]
+use zentime_rs::server::notification::DesktopNotifier;
+use zentime_rs::server::notification::{BellNotifier, Notification, NotificationDispatcher};
+use zentime_rs::server::sound::SoundPlayer;
+use zentime_rs_timer::duration::HumanDuration;
+
+use super::hold::parse_duration;
+use crate::cli_error::CliError;
+
+/// Walks a new user through the most important settings (timer lengths, notifications, sound and
+/// colors), tries out the chosen notifications and writes the config file.
+/// Each question suggests the default value, which is kept by pressing enter.
+pub fn init(config_path: &str) {
+    let path = PathBuf::from(shellexpand::tilde(config_path.trim()).as_ref());
+
+    println!(
+        "Welcome to zentime! This sets up {} - press enter to keep the suggested value.",
+        path.display()
+    );
+
+    if path.exists()
+        && !ask_yes_no(
+            &format!("{} already exists. Overwrite it?", path.display()),
+            false,
+        )
+    {
+        return;
+    }
+
+    let mut draft = ConfigDraft::default();
+
+    println!("\nTimer");
+    draft.timer = ask_duration("Length of a focus interval", draft.timer);
+    draft.minor_break = ask_duration("Length of a short break", draft.minor_break);
+    draft.major_break = ask_duration("Length of the long break", draft.major_break);
+    draft.intervals = ask_number("Intervals before the long break", draft.intervals);
+
+    loop {
+        println!("\nNotifications");
+        choose_notifications(&mut draft);
+
+        let is_silent = !draft.enable_bell && !draft.show_notification;
+
+        if is_silent || test_notifications(&draft) {
+            break;
+        }
+    }
+
+    println!("\nInterface");
+    draft.layout = choose(
+        "Layout",
+        &[
+            ("Boxes with the key bindings below", TuiLayout::Default),
+            ("A single compact line", TuiLayout::Compact),
+        ],
+        0,
+    );
+    draft.colors = choose("Colors", &color_schemes(), 0);
+
+    write_config(&path, &draft);
+}
+
+/// Asks how the user wants to be notified and which sound should be played
+fn choose_notifications(draft: &mut ConfigDraft) {
+    let styles = [
+        ("Sound and OS notification", (true, true)),
+        ("OS notification only", (false, true)),
+        ("Sound only", (true, false)),
+        ("Not at all", (false, false)),
+    ];
+    let current = styles
+        .iter()
+        .position(|(_, style)| *style == (draft.enable_bell, draft.show_notification))
+        .unwrap_or(0);
+
+    (draft.enable_bell, draft.show_notification) = choose(
+        "How should zentime tell you that an interval or break is over?",
+        &styles,
+        current,
+    );
+
+    if !draft.enable_bell {
+        return;
+    }
+
+    let sound_file = ask(
+        "Sound file (leave empty for the default bell)",
+        draft.sound_file.as_deref().unwrap_or_default(),
+    );
+    draft.sound_file =
+        (!sound_file.is_empty()).then(|| shellexpand::tilde(&sound_file).into_owned());
+
+    loop {
+        let volume = ask("Volume in percent", &format!("{:.0}", draft.volume * 100.0));
+
+        match volume.trim_end_matches('%').parse::<u8>() {
+            Ok(percent) if percent <= 100 => {
+                draft.volume = f32::from(percent) / 100.0;
+                return;
+            }
+            _ => println!("Please enter a number between 0 and 100"),
+        }
+    }
+}
+
+/// Sends a sample notification with the chosen settings.
+/// Returns `false`, if the user wants to change the settings.
+#[tokio::main]
+async fn test_notifications(draft: &ConfigDraft) -> bool {
+    let config = draft.notifications();
+    let mut dispatcher = NotificationDispatcher::new();
+
+    if config.enable_bell {
+        dispatcher.register(BellNotifier::new(
+            SoundPlayer::spawn(config.ducking),
+            config.sound_file.clone(),
+            config.sounds.clone(),
+            config.volume,
+            config.bell_patterns,
+            config.channels,
+        ));
+    }
+
+    if config.show_notification {
+        #[cfg(feature = "notifications")]
+        dispatcher.register(DesktopNotifier::new(None));
+        #[cfg(not(feature = "notifications"))]
+        println!(
+            "zentime has been built without OS notifications - rebuild it with \
+            `--features notifications` to show them"
+        );
+    }
+
+    println!("\nSending a test notification...");
+
+    for error in dispatcher.dispatch(&Notification::sample()).await {
+        println!("-> {}", error);
+    }
+
+    // The sound keeps playing while the dispatcher (and thereby the sound player) is alive
+    ask_yes_no("Did it work?", true) || !ask_yes_no("Change the notification settings?", true)
+}
+
+/// Color schemes of the default interface
+fn color_schemes() -> [(&'static str, ThemeColors); 3] {
+    [
+        ("Colorful", ThemeColors::default()),
+        (
+            "Monochrome (keeps the colors of the terminal)",
+            ThemeColors {
+                focus: ThemeColor::Reset,
+                break_phase: ThemeColor::Reset,
+                postponed: ThemeColor::Reset,
+                timer: ThemeColor::Reset,
+                text: ThemeColor::Reset,
+                round: ThemeColor::Reset,
+                hints: ThemeColor::Reset,
+                error: ThemeColor::Red,
+            },
+        ),
+        (
+            "Light (for terminals with a light background)",
+            ThemeColors {
+                focus: ThemeColor::Blue,
+                break_phase: ThemeColor::Magenta,
+                postponed: ThemeColor::Red,
+                timer: ThemeColor::Black,
+                text: ThemeColor::Reset,
+                round: ThemeColor::DarkGray,
+                hints: ThemeColor::Gray,
+                error: ThemeColor::Red,
+            },
+        ),
+    ]
+}
+
+/// Writes the config file, after making sure that zentime is able to read it
+fn write_config(path: &Path, draft: &ConfigDraft) {
+    let toml = draft.to_toml();
+
+    if let Err(error) = Figment::from(Serialized::defaults(Config::default()))
+        .merge(Toml::string(&toml))
+        .extract::<Config>()
+    {
+        CliError::config(&path.display().to_string(), error).exit();
+    }
+
+    let written = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|()| fs::write(path, toml));
+
+    if let Err(error) = written {
+        CliError::Other(format!("Could not write {}: {}", path.display(), error)).exit();
+    }
+
+    println!(
+        "\nWrote {} - run `zentime` to start your first interval.\n\
+        All other options are listed inside zentime.example.toml.",
+        path.display()
+    );
+}
+
+/// Asks a question and returns the answer - or the default value, if the answer is empty
+/// (or stdin has been closed)
+fn ask(question: &str, default: &str) -> String {
+    if default.is_empty() {
+        print!("{}: ", question);
+    } else {
+        print!("{} [{}]: ", question, default);
+    }
+
+    stdout().flush().ok();
+
+    let mut answer = String::new();
+
+    if let Err(error) = stdin().read_line(&mut answer) {
+        CliError::Other(format!("Could not read answer: {}", error)).exit();
+    }
+
+    match answer.trim() {
+        "" => default.to_string(),
+        answer => answer.to_string(),
+    }
+}
+
+/// Asks a yes/no question
+pub fn ask_yes_no(question: &str, default: bool) -> bool {
+    let hint = if default { "Y/n" } else { "y/N" };
+
+    loop {
+        match ask(&format!("{} [{}]", question, hint), "")
+            .to_lowercase()
+            .as_str()
+        {
+            "" => return default,
+            "y" | "yes" => return true,
+            "n" | "no" => return false,
+            _ => println!("Please answer with y or n"),
+        }
+    }
+}
+
+/// Asks for a duration (e.g. `25m`, `90s` or `1h30m`) and returns it in seconds
+fn ask_duration(question: &str, default: u64) -> u64 {
+    let default = HumanDuration::from_secs(default).to_string();
+
+    loop {
+        match parse_duration(&ask(question, &default)) {
+            Ok(duration) => return duration.as_secs(),
+            Err(error) => println!("{} (e.g. 25m, 90s or 1h30m)", error),
+        }
+    }
+}
+
+/// Asks for a positive number
+fn ask_number(question: &str, default: u64) -> u64 {
+    loop {
+        match ask(question, &default.to_string()).parse::<u64>() {
+            Ok(number) if number > 0 => return number,
+            _ => println!("Please enter a number greater than 0"),
+        }
+    }
+}
+
+/// Lets the user pick one of the given options by its number
+fn choose<T: Clone>(question: &str, options: &[(&str, T)], default: usize) -> T {
+    println!("{}", question);
+
+    for (index, (label, _)) in options.iter().enumerate() {
+        println!("  {}) {}", index + 1, label);
+    }
+
+    loop {
+        let choice = ask("Choice", &(default + 1).to_string());
+
+        match choice.parse::<usize>() {
+            Ok(number) if (1..=options.len()).contains(&number) => {
+                return options[number - 1].1.clone();
+            }
+            _ => println!("Please enter a number between 1 and {}", options.len()),
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use anyhow::{bail, Context};
+use std::env::current_exe;
+use std::fs::{create_dir_all, remove_file, write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::cli_error::CliError;
+
+const SYSTEMD_UNIT_NAME: &str = "zentime.service";
+const LAUNCHD_LABEL: &str = "com.zentime.server";
+const WINDOWS_TASK_NAME: &str = "zentime";
+
+/// Installs (or removes) an autostart artifact for the current platform, so that the
+/// zentime server daemon is started on login:
+///
+/// * Linux: systemd user unit
+/// * macOS: launchd agent
+/// * Windows: scheduled task
+pub fn install_autostart(config_path: &str, uninstall: bool) {
+    let result = if uninstall {
+        uninstall_artifact()
+    } else {
+        install_artifact(config_path)
+    };
+
+    if let Err(error) = result {
+        CliError::Other(format!("Could not update autostart: {:#}", error)).exit();
+    }
+}
+
+fn install_artifact(config_path: &str) -> anyhow::Result<()> {
+    let exe = current_exe().context("Could not determine path of zentime executable")?;
+    let exe = exe.to_string_lossy();
+
+    if cfg!(target_os = "linux") {
+        #[cfg(target_os = "linux")]
+        if zentime_rs::server::systemd::is_installed() {
+            bail!(
+                "The server is installed as socket activated service already - remove it with \
+                `zentime server install-service --uninstall` first"
+            );
+        }
+
+        let path = systemd_unit_path();
+        write_file(&path, &systemd_unit(&exe, config_path))?;
+        run("systemctl", &["--user", "daemon-reload"])?;
+        run("systemctl", &["--user", "enable", SYSTEMD_UNIT_NAME])?;
+        println!("Installed systemd user unit at {}", path.display());
+    } else if cfg!(target_os = "macos") {
+        let path = launchd_plist_path();
+        write_file(&path, &launchd_plist(&exe, config_path))?;
+        println!("Installed launchd agent at {}", path.display());
+    } else if cfg!(target_os = "windows") {
+        let task_command = format!("\"{}\" server start -c \"{}\"", exe, config_path);
+        run(
+            "schtasks",
+            &[
+                "/Create",
+                "/F",
+                "/SC",
+                "ONLOGON",
+                "/TN",
+                WINDOWS_TASK_NAME,
+                "/TR",
+                &task_command,
+            ],
+        )?;
+        println!("Installed scheduled task '{}'", WINDOWS_TASK_NAME);
+    } else {
+        bail!("Autostart is not supported on this platform");
+    }
+
+    Ok(())
+}
+
+fn uninstall_artifact() -> anyhow::Result<()> {
+    if cfg!(target_os = "linux") {
+        let path = systemd_unit_path();
+        // Disabling fails if the unit has never been enabled, which is fine while uninstalling
+        run("systemctl", &["--user", "disable", SYSTEMD_UNIT_NAME]).ok();
+        remove_if_exists(&path)?;
+        run("systemctl", &["--user", "daemon-reload"])?;
+        println!("Removed systemd user unit {}", path.display());
+    } else if cfg!(target_os = "macos") {
+        let path = launchd_plist_path();
+        remove_if_exists(&path)?;
+        println!("Removed launchd agent {}", path.display());
+    } else if cfg!(target_os = "windows") {
+        run("schtasks", &["/Delete", "/F", "/TN", WINDOWS_TASK_NAME])?;
+        println!("Removed scheduled task '{}'", WINDOWS_TASK_NAME);
+    } else {
+        bail!("Autostart is not supported on this platform");
+    }
+
+    Ok(())
+}
+
+/// Systemd user unit starting the server on login.
+/// `server start` daemonizes itself, therefore the unit is of type `forking`.
+fn systemd_unit(exe: &str, config_path: &str) -> String {
+    format!(
+        "[Unit]
+Description=zentime pomodoro timer server
+
+[Service]
+Type=forking
+ExecStart=\"{}\" server start -c \"{}\"
+
+[Install]
+WantedBy=default.target
+",
+        exe, config_path
+    )
+}
+
+/// Launchd agent starting the server on login.
+/// `AbandonProcessGroup` is necessary, because otherwise launchd would kill our daemonized
+/// server as soon as the initial process exits.
+fn launchd_plist(exe: &str, config_path: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{}</string>
+    <key>ProgramArguments</key>
+    <array>
+        <string>{}</string>
+        <string>server</string>
+        <string>start</string>
+        <string>-c</string>
+        <string>{}</string>
+    </array>
+    <key>RunAtLoad</key>
+    <true/>
+    <key>AbandonProcessGroup</key>
+    <true/>
+</dict>
+</plist>
+"#,
+        LAUNCHD_LABEL, exe, config_path
+    )
+}
+
+fn systemd_unit_path() -> PathBuf {
+    let mut path = PathBuf::from(shellexpand::tilde("~/.config/systemd/user").as_ref());
+    path.push(SYSTEMD_UNIT_NAME);
+    path
+}
+
+fn launchd_plist_path() -> PathBuf {
+    let mut path = PathBuf::from(shellexpand::tilde("~/Library/LaunchAgents").as_ref());
+    path.push(format!("{}.plist", LAUNCHD_LABEL));
+    path
+}
+
+fn write_file(path: &Path, content: &str) -> anyhow::Result<()> {
+    if let Some(parent) = path.parent() {
+        create_dir_all(parent).context(format!("Could not create {}", parent.display()))?;
+    }
+
+    write(path, content).context(format!("Could not write {}", path.display()))
+}
+
+fn remove_if_exists(path: &Path) -> anyhow::Result<()> {
+    if path.exists() {
+        remove_file(path).context(format!("Could not remove {}", path.display()))?;
+    }
+
+    Ok(())
+}
+
+fn run(program: &str, args: &[&str]) -> anyhow::Result<()> {
+    let status = Command::new(program)
+        .args(args)
+        .status()
+        .context(format!("Could not run {}", program))?;
+
+    if !status.success() {
+        bail!("{} {} exited with {}", program, args.join(" "), status);
+    }
+
+    Ok(())
+}
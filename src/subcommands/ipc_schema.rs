@@ -0,0 +1,11 @@
+use zentime_rs::ipc::protocol_schema;
+
+use crate::cli_error::CliError;
+
+/// Prints the JSON schema of all IPC messages (see [protocol_schema])
+pub fn ipc_schema() {
+    match serde_json::to_string_pretty(&protocol_schema()) {
+        Ok(schema) => println!("{}", schema),
+        Err(error) => CliError::Other(format!("Could not serialize schema: {}", error)).exit(),
+    }
+}
@@ -0,0 +1,55 @@
+use std::io::{stdin, IsTerminal};
+use std::process;
+
+use zentime_rs::client::start_at;
+use zentime_rs::config::Config;
+use zentime_rs::ipc::ServerAddress;
+use zentime_rs::secrets;
+
+use crate::cli_error::CliError;
+
+/// Joins the shared team session of the zentime server at the given address and attaches a
+/// client to it.
+/// If no token is given, it is read from the terminal (without echo) or from stdin.
+/// The token may also be a secret reference (e.g. `keyring:zentime/team`).
+pub fn join(address: &str, token: Option<&str>, config: Config) {
+    let token = match token {
+        Some(token) => token.to_string(),
+        None => read_token(),
+    };
+
+    let token = match secrets::resolve(&token) {
+        Ok(token) => token,
+        Err(error) => {
+            eprintln!("{:#}", anyhow::Error::new(error));
+            process::exit(1);
+        }
+    };
+
+    attach(
+        config,
+        ServerAddress::Team {
+            address: address.to_string(),
+            token,
+        },
+    );
+}
+
+#[tokio::main]
+async fn attach(config: Config, address: ServerAddress) {
+    start_at(config, address).await;
+}
+
+fn read_token() -> String {
+    let token = if stdin().is_terminal() {
+        rpassword::prompt_password("Team token: ")
+    } else {
+        let mut token = String::new();
+        stdin().read_line(&mut token).map(|_| token)
+    };
+
+    match token {
+        Ok(token) => token.trim_end_matches(['\r', '\n']).to_string(),
+        Err(error) => CliError::Other(format!("Could not read token: {}", error)).exit(),
+    }
+}
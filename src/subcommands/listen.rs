@@ -0,0 +1,103 @@
+use futures::io::BufReader;
+use log::{error, info};
+use tokio::select;
+use zentime_rs::client::one_shot_connection::one_shot_connection;
+use zentime_rs::client::timer_event::{TimerEvent, TimerEventDetector};
+use zentime_rs::hooks::HookEnv;
+use zentime_rs::ipc::{heartbeat_interval, ClientPreferences, ClientToServerMsg, UpdateMode};
+use zentime_rs::ipc::{InterProcessCommunication, IpcReader, ServerToClientMsg, Snapshot};
+use zentime_rs_timer::pomodoro_timer::ViewState;
+
+use crate::cli_error::CliError;
+
+/// Attaches to the server without any interface and runs the given shell command for each
+/// [TimerEvent]. Information about the event is passed to the command as environment variables.
+#[tokio::main]
+pub async fn listen(command: &str) {
+    let (reader, mut writer) = match one_shot_connection().await {
+        Ok(c) => c,
+        Err(error) => CliError::Connect(error).exit(),
+    };
+
+    let mut reader = IpcReader::new(BufReader::new(reader));
+
+    // Events never depend on the remaining time, therefore we don't need a state on every tick
+    let hello = ClientToServerMsg::Hello(ClientPreferences {
+        updates: UpdateMode::Events,
+        ..ClientPreferences::default()
+    });
+
+    if let Err(err) = InterProcessCommunication::send_ipc_message(hello, &mut writer).await {
+        CliError::Communication(err).exit()
+    };
+
+    if let Err(err) =
+        InterProcessCommunication::send_ipc_message(ClientToServerMsg::Sync, &mut writer).await
+    {
+        CliError::Communication(err).exit()
+    };
+
+    let mut detector = TimerEventDetector::default();
+    let mut heartbeat = heartbeat_interval();
+
+    loop {
+        let msg = select! {
+            msg = reader.recv::<ServerToClientMsg>() => match msg {
+                Ok(msg) => msg,
+                Err(error) => {
+                    println!("Server connection closed: {}", error);
+                    return;
+                }
+            },
+            _ = heartbeat.tick() => {
+                let heartbeat = ClientToServerMsg::Heartbeat;
+
+                if let Err(err) = InterProcessCommunication::send_ipc_message(heartbeat, &mut writer).await {
+                    CliError::Communication(err).exit()
+                }
+
+                continue;
+            }
+        };
+
+        let state = match msg {
+            ServerToClientMsg::Timer(state)
+            | ServerToClientMsg::Snapshot(Snapshot { state, .. }) => state,
+            ServerToClientMsg::Error { code, message } => {
+                error!("Server reported {} error: {}", code, message);
+                continue;
+            }
+            ServerToClientMsg::ShuttingDown => {
+                println!("Server is shutting down");
+                return;
+            }
+            ServerToClientMsg::Pong
+            | ServerToClientMsg::Joined { .. }
+            | ServerToClientMsg::ConfigReloaded
+            | ServerToClientMsg::Clients(_)
+            | ServerToClientMsg::Bell { .. }
+            | ServerToClientMsg::Welcome(_) => continue,
+        };
+
+        for event in detector.detect(&state) {
+            run_command(command, event, &state);
+        }
+    }
+}
+
+/// Spawns the command without waiting for it, so that a long running command does not block
+/// the event loop
+fn run_command(command: &str, event: TimerEvent, state: &ViewState) {
+    info!("Running command for {}", event);
+
+    let child = HookEnv::from_view_state(event, state)
+        .command(command)
+        .spawn();
+
+    match child {
+        Ok(mut child) => {
+            tokio::spawn(async move { child.wait().await.ok() });
+        }
+        Err(error) => error!("Could not run command for {}: {}", event, error),
+    }
+}
@@ -0,0 +1,150 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::Path;
+use std::process;
+use std::str::FromStr;
+use std::thread::sleep;
+use std::time::Duration;
+
+use log::{Level, LevelFilter};
+
+use super::server::daemon_output_paths;
+
+/// Interval in which the log file is checked for new lines while following it
+const FOLLOW_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Prints the last `lines` lines of the server log (see `log.file`), which match the given
+/// level, and keeps printing new lines if `follow` is set.
+///
+/// NOTE: The server only logs what its own log level permits (see `log.level` and `RUST_LOG`),
+/// therefore e.g. info logs are only shown if the server has been started with that level.
+pub fn log(path: &Path, level: LevelFilter, lines: usize, follow: bool) {
+    let (_, panic_path) = daemon_output_paths();
+
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(error) => {
+            eprintln!("Could not open server log {}: {}", path.display(), error);
+            eprintln!("NOTE: The server creates its log once it has been started");
+            process::exit(1);
+        }
+    };
+
+    let mut reader = BufReader::new(file);
+    let mut filter = LevelMatcher::new(level);
+
+    let mut recent = Vec::new();
+    for line in read_lines(&mut reader) {
+        if filter.matches(&line) {
+            recent.push(line);
+        }
+    }
+
+    for line in &recent[recent.len().saturating_sub(lines)..] {
+        println!("{}", line);
+    }
+
+    let has_panicked = std::fs::metadata(panic_path).is_ok_and(|panics| panics.len() > 0);
+    if has_panicked {
+        eprintln!("\nNOTE: The server has panicked - see {}", panic_path);
+    }
+
+    if !follow {
+        return;
+    }
+
+    loop {
+        sleep(FOLLOW_INTERVAL);
+
+        // A restarted server recreates the log file, in which case we start from its beginning
+        if let Err(error) = reopen_if_truncated(&mut reader, path) {
+            eprintln!("Could not read server log {}: {}", path.display(), error);
+            process::exit(1);
+        }
+
+        for line in read_lines(&mut reader) {
+            if filter.matches(&line) {
+                println!("{}", line);
+            }
+        }
+    }
+}
+
+/// Reads all complete lines which have been appended since the last read.
+/// A partially written line is kept for the next read.
+fn read_lines(reader: &mut BufReader<File>) -> Vec<String> {
+    let mut lines = vec![];
+
+    loop {
+        let position = reader.stream_position().unwrap_or_default();
+        let mut line = String::new();
+
+        match reader.read_line(&mut line) {
+            Ok(0) | Err(_) => break,
+            Ok(_) if !line.ends_with('\n') => {
+                reader.seek(SeekFrom::Start(position)).ok();
+                break;
+            }
+            Ok(_) => lines.push(line.trim_end().to_string()),
+        }
+    }
+
+    lines
+}
+
+/// Starts reading from the beginning again, if the file has become shorter than what we have
+/// read so far or has been replaced
+fn reopen_if_truncated(reader: &mut BufReader<File>, path: &Path) -> std::io::Result<()> {
+    let position = reader.stream_position()?;
+    let length = std::fs::metadata(path)?.len();
+
+    if length < position {
+        *reader = BufReader::new(File::open(path)?);
+    }
+
+    Ok(())
+}
+
+/// Filters log lines by their level.
+/// Lines without a level (e.g. of multi-line messages) belong to the previous log entry.
+#[derive(Debug)]
+struct LevelMatcher {
+    filter: LevelFilter,
+    is_matching: bool,
+}
+
+impl LevelMatcher {
+    fn new(filter: LevelFilter) -> Self {
+        Self {
+            filter,
+            is_matching: true,
+        }
+    }
+
+    fn matches(&mut self, line: &str) -> bool {
+        if let Some(level) = level_of(line) {
+            self.is_matching = level <= self.filter;
+        }
+
+        self.is_matching
+    }
+}
+
+/// Level of a line written by env_logger (e.g. `[2023-01-01T12:00:00Z INFO  zentime] ...`) or
+/// of a JSON entry (see `log.json`)
+fn level_of(line: &str) -> Option<Level> {
+    if line.starts_with('{') {
+        let entry: serde_json::Value = serde_json::from_str(line).ok()?;
+
+        return entry["level"]
+            .as_str()
+            .and_then(|level| Level::from_str(level).ok());
+    }
+
+    let header = line.strip_prefix('[')?;
+
+    header
+        .split_whitespace()
+        .nth(1)
+        .and_then(|level| Level::from_str(level).ok())
+}
@@ -0,0 +1,55 @@
+use futures::io::BufReader;
+use std::process;
+use zentime_rs::client::one_shot_connection::one_shot_connection;
+use zentime_rs::ipc::ClientToServerMsg;
+use zentime_rs::ipc::InterProcessCommunication;
+use zentime_rs::ipc::ServerToClientMsg;
+
+use crate::cli_error::CliError;
+
+/// Ends the current interval or break and starts the long break right away
+#[tokio::main]
+pub async fn long_break(silent: bool) {
+    let (reader, mut writer) = match one_shot_connection().await {
+        Ok(c) => c,
+        Err(error) => CliError::Connect(error).exit(),
+    };
+
+    let mut reader = BufReader::new(reader);
+
+    if let Err(err) =
+        InterProcessCommunication::send_ipc_message(ClientToServerMsg::LongBreakNow, &mut writer)
+            .await
+    {
+        CliError::Communication(err).exit()
+    };
+
+    let msg_result =
+        InterProcessCommunication::recv_ipc_message::<ServerToClientMsg>(&mut reader).await;
+
+    let mut has_failed = false;
+
+    match msg_result {
+        Ok(ServerToClientMsg::Timer(state)) if !silent => {
+            println!(
+                "{} {} {}",
+                state.round,
+                state.time,
+                if state.is_break { "Break" } else { "Focus" }
+            );
+        }
+        Ok(ServerToClientMsg::Error { message, .. }) => {
+            eprintln!("{}", message);
+            has_failed = true;
+        }
+        _ => {}
+    }
+
+    InterProcessCommunication::send_ipc_message(ClientToServerMsg::Detach, &mut writer)
+        .await
+        .ok();
+
+    if has_failed {
+        process::exit(1);
+    }
+}
@@ -0,0 +1,96 @@
+use std::path::{Path, PathBuf};
+use std::process;
+
+use anyhow::Context;
+use futures::io::BufReader;
+use log::error;
+use tokio::fs::{rename, write};
+use tokio::select;
+use zentime_rs::client::one_shot_connection::one_shot_connection;
+use zentime_rs::client::overlay::overlay_text;
+use zentime_rs::ipc::{heartbeat_interval, ClientToServerMsg};
+use zentime_rs::ipc::{InterProcessCommunication, IpcReader, ServerToClientMsg, Snapshot};
+
+use crate::cli_error::CliError;
+
+/// Attaches to the server without any interface and keeps writing the remaining time into the
+/// given file (e.g. for an OBS text source), until the server connection is closed.
+/// The file is cleared afterwards, so that no outdated time is shown.
+#[tokio::main]
+pub async fn overlay(file: &str) {
+    let path = PathBuf::from(shellexpand::tilde(file).as_ref());
+
+    let (reader, mut writer) = match one_shot_connection().await {
+        Ok(c) => c,
+        Err(error) => CliError::Connect(error).exit(),
+    };
+
+    let mut reader = IpcReader::new(BufReader::new(reader));
+
+    if let Err(err) =
+        InterProcessCommunication::send_ipc_message(ClientToServerMsg::Sync, &mut writer).await
+    {
+        CliError::Communication(err).exit()
+    };
+
+    let mut last_text = None;
+    let mut heartbeat = heartbeat_interval();
+
+    loop {
+        let msg = select! {
+            msg = reader.recv::<ServerToClientMsg>() => match msg {
+                Ok(msg) => msg,
+                Err(error) => {
+                    println!("Server connection closed: {}", error);
+                    break;
+                }
+            },
+            _ = heartbeat.tick() => {
+                let heartbeat = ClientToServerMsg::Heartbeat;
+
+                if let Err(error) = InterProcessCommunication::send_ipc_message(heartbeat, &mut writer).await {
+                    println!("Server connection closed: {}", error);
+                    break;
+                }
+
+                continue;
+            }
+        };
+
+        let (ServerToClientMsg::Timer(state) | ServerToClientMsg::Snapshot(Snapshot { state, .. })) =
+            msg
+        else {
+            continue;
+        };
+
+        let text = overlay_text(&state);
+
+        if last_text.as_ref() == Some(&text) {
+            continue;
+        }
+
+        if let Err(error) = write_atomically(&path, &text).await {
+            eprintln!("{:#}", error);
+            process::exit(1);
+        }
+
+        last_text = Some(text);
+    }
+
+    if let Err(error) = write_atomically(&path, "").await {
+        error!("Could not clear overlay file: {:#}", error);
+    }
+}
+
+/// Replaces the content of the file at once, so that readers never see a partially written file
+async fn write_atomically(path: &Path, content: &str) -> anyhow::Result<()> {
+    let mut temporary_path = path.as_os_str().to_owned();
+    temporary_path.push(".tmp");
+
+    write(&temporary_path, content)
+        .await
+        .context(format!("Could not write {}", path.display()))?;
+    rename(&temporary_path, path)
+        .await
+        .context(format!("Could not write {}", path.display()))
+}
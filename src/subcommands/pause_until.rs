@@ -0,0 +1,39 @@
+use chrono::NaiveTime;
+use std::time::Duration;
+use zentime_rs::client::pause::{Pause, PauseEnd};
+use zentime_rs::wall_clock::WallClock;
+
+use super::send_change;
+use crate::cli_error::CliError;
+
+/// Pauses the timer until the given time of day (or for the given duration). The server
+/// resumes the timer afterwards and welcomes the user back with a notification.
+#[tokio::main]
+pub async fn pause_until(
+    time: Option<NaiveTime>,
+    duration: Option<Duration>,
+    wall_clock: WallClock,
+    silent: bool,
+) {
+    let end = match (time, duration) {
+        (_, Some(duration)) => PauseEnd::After(duration),
+        (Some(time), None) => PauseEnd::At(time),
+        (None, None) => {
+            CliError::Other("Either a time or a duration is required".to_string()).exit()
+        }
+    };
+
+    let pause = match Pause::new(end, wall_clock) {
+        Ok(pause) => pause,
+        Err(error) => CliError::Other(format!("{:#}", error)).exit(),
+    };
+    let confirmation = format!("Paused - {}", pause.reason);
+
+    send_change(pause.msg(), &confirmation, silent).await;
+}
+
+/// Parses a time of day like `13:30`
+pub fn parse_time_of_day(value: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M")
+        .map_err(|_| format!("'{}' is not a time of day like 13:30", value))
+}
@@ -0,0 +1,50 @@
+use std::process;
+use zentime_rs::config::PlanConfig;
+use zentime_rs::ipc::ClientToServerMsg;
+use zentime_rs::plan::Plan;
+use zentime_rs::wall_clock::WallClock;
+
+use super::send_change;
+
+/// Prints the plan of today
+pub fn show_plan(config: &PlanConfig, wall_clock: WallClock) {
+    match Plan::load(&config.path(), wall_clock.today()) {
+        Ok(plan) if plan.items.is_empty() => {
+            println!("Nothing has been planned today - add a task via `zentime plan add`")
+        }
+        Ok(plan) => println!("{}", plan),
+        Err(error) => {
+            eprintln!("{:#}", error);
+            process::exit(1);
+        }
+    }
+}
+
+/// Adds a task with an estimate of pomodoros to the plan of today
+#[tokio::main]
+pub async fn add_to_plan(task: String, pomodoros: u16, silent: bool) {
+    let confirmation = format!(
+        "Planned {} pomodoro{} for: {}",
+        pomodoros,
+        if pomodoros == 1 { "" } else { "s" },
+        task
+    );
+
+    send_change(
+        ClientToServerMsg::AddToPlan { task, pomodoros },
+        &confirmation,
+        silent,
+    )
+    .await;
+}
+
+/// Removes all tasks from the plan of today
+#[tokio::main]
+pub async fn clear_plan(silent: bool) {
+    send_change(
+        ClientToServerMsg::ClearPlan,
+        "Plan has been cleared",
+        silent,
+    )
+    .await;
+}
@@ -0,0 +1,14 @@
+use zentime_rs::config::{Config, TuiLayout};
+
+use crate::default_cmd::default_cmd;
+use crate::CommonArgs;
+
+/// Attaches a small interactive client, which is meant to be run inside a tmux popup
+/// (`tmux display-popup -E -w 50 -h 4 zentime popup`). The server is started if necessary.
+/// Quitting (or closing the popup) only detaches the client.
+pub fn popup(common_args: &CommonArgs, mut config: Config) {
+    config.view.interface = "default".to_string();
+    config.view.layout = TuiLayout::Popup;
+
+    default_cmd(common_args, config);
+}
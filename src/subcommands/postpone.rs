@@ -1,29 +1,38 @@
 use futures::io::BufReader;
+use std::process;
+use std::time::Duration;
 use zentime_rs::client::one_shot_connection::one_shot_connection;
 use zentime_rs::ipc::ClientToServerMsg;
 use zentime_rs::ipc::InterProcessCommunication;
 use zentime_rs::ipc::ServerToClientMsg;
 
+use crate::cli_error::CliError;
+
+/// Postpones the current break for the given duration (or the configured postpone timer)
 #[tokio::main]
-pub async fn postpone(silent: bool) {
+pub async fn postpone(duration: Option<Duration>, silent: bool) {
     let (reader, mut writer) = match one_shot_connection().await {
         Ok(c) => c,
-        Err(error) => panic!("Could not conenct to server: {}", error),
+        Err(error) => CliError::Connect(error).exit(),
     };
 
     let mut reader = BufReader::new(reader);
 
-    if let Err(err) =
-        InterProcessCommunication::send_ipc_message(ClientToServerMsg::PostPone, &mut writer).await
-    {
-        panic!("Could not send to the server: {}", err)
+    let msg = ClientToServerMsg::PostPone {
+        seconds: duration.map(|duration| duration.as_secs()),
+    };
+
+    if let Err(err) = InterProcessCommunication::send_ipc_message(msg, &mut writer).await {
+        CliError::Communication(err).exit()
     };
 
     let msg_result =
         InterProcessCommunication::recv_ipc_message::<ServerToClientMsg>(&mut reader).await;
 
-    if !silent {
-        if let Ok(ServerToClientMsg::Timer(state)) = msg_result {
+    let mut has_failed = false;
+
+    match msg_result {
+        Ok(ServerToClientMsg::Timer(state)) if !silent => {
             println!(
                 "{} {} {}",
                 state.round,
@@ -31,9 +40,18 @@ pub async fn postpone(silent: bool) {
                 if state.is_break { "Break" } else { "Focus" }
             );
         }
+        Ok(ServerToClientMsg::Error { message, .. }) => {
+            eprintln!("{}", message);
+            has_failed = true;
+        }
+        _ => {}
     }
 
     InterProcessCommunication::send_ipc_message(ClientToServerMsg::Detach, &mut writer)
         .await
         .ok();
+
+    if has_failed {
+        process::exit(1);
+    }
 }
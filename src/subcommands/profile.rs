@@ -0,0 +1,51 @@
+use zentime_rs::config::Config;
+use zentime_rs::ipc::ClientToServerMsg;
+use zentime_rs::server::status::timer_state;
+use zentime_rs::wall_clock::WallClock;
+use zentime_rs_timer::util::seconds_to_time;
+
+use super::send_change;
+
+/// Lists the timer profiles of the config with their lengths (focus / short break / long break).
+/// The active profile is marked, if a server is running.
+#[tokio::main]
+pub async fn list_profiles(config: &Config) {
+    let active = timer_state().await.ok().and_then(|state| state.profile);
+    let names = config.profile_names();
+    let width = names.iter().map(String::len).max().unwrap_or_default();
+
+    let now = WallClock::from_config(&config.clock).now();
+
+    for name in names {
+        let Some(timers) = config.profile_timers(&name, now) else {
+            continue;
+        };
+
+        println!(
+            "{} {:<width$}  {} / {} / {}",
+            if active.as_ref() == Some(&name) {
+                "*"
+            } else {
+                " "
+            },
+            name,
+            seconds_to_time(timers.timer),
+            seconds_to_time(timers.minor_break),
+            seconds_to_time(timers.major_break),
+            width = width,
+        );
+    }
+}
+
+/// Switches the server to another timer profile
+#[tokio::main]
+pub async fn switch_profile(name: String, silent: bool) {
+    let confirmation = format!("Switched to timer profile '{}'", name);
+
+    send_change(
+        ClientToServerMsg::SwitchProfile(name),
+        &confirmation,
+        silent,
+    )
+    .await;
+}
@@ -1,13 +1,15 @@
 use futures::io::BufReader;
 use zentime_rs::client::one_shot_connection::one_shot_connection;
 use zentime_rs::ipc::ClientToServerMsg;
-use zentime_rs::ipc::{InterProcessCommunication, ServerToClientMsg};
+use zentime_rs::ipc::{InterProcessCommunication, ServerToClientMsg, Snapshot};
+
+use crate::cli_error::CliError;
 
 #[tokio::main]
 pub async fn query_server_once() {
     let (reader, mut writer) = match one_shot_connection().await {
         Ok(c) => c,
-        Err(error) => panic!("Could not conenct to server: {}", error),
+        Err(error) => CliError::Connect(error).exit(),
     };
 
     let mut reader = BufReader::new(reader);
@@ -15,13 +17,16 @@ pub async fn query_server_once() {
     if let Err(err) =
         InterProcessCommunication::send_ipc_message(ClientToServerMsg::Sync, &mut writer).await
     {
-        panic!("Could not sync with server: {}", err)
+        CliError::Communication(err).exit()
     };
 
     let msg_result =
         InterProcessCommunication::recv_ipc_message::<ServerToClientMsg>(&mut reader).await;
 
-    if let Ok(ServerToClientMsg::Timer(state)) = msg_result {
+    if let Ok(
+        ServerToClientMsg::Timer(state) | ServerToClientMsg::Snapshot(Snapshot { state, .. }),
+    ) = msg_result
+    {
         println!(
             "{} {} {}",
             state.round,
@@ -0,0 +1,61 @@
+use futures::io::BufReader;
+use std::process;
+use std::time::Duration;
+use tokio::time::timeout;
+use zentime_rs::client::one_shot_connection::one_shot_connection;
+use zentime_rs::ipc::ClientToServerMsg;
+use zentime_rs::ipc::InterProcessCommunication;
+use zentime_rs::ipc::ServerToClientMsg;
+
+use crate::cli_error::CliError;
+
+/// Time the server has to confirm the reload
+const RELOAD_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Makes the server read its config file again.
+/// New timer lengths apply from the next interval or break on, notification settings right away.
+#[tokio::main]
+pub async fn reload(silent: bool) {
+    let (reader, mut writer) = match one_shot_connection().await {
+        Ok(c) => c,
+        Err(error) => CliError::Connect(error).exit(),
+    };
+
+    let mut reader = BufReader::new(reader);
+
+    if let Err(err) =
+        InterProcessCommunication::send_ipc_message(ClientToServerMsg::ReloadConfig, &mut writer)
+            .await
+    {
+        CliError::Communication(err).exit()
+    };
+
+    // Timer states are broadcast in between, until the server has answered
+    let answer = timeout(RELOAD_TIMEOUT, async {
+        loop {
+            match InterProcessCommunication::recv_ipc_message::<ServerToClientMsg>(&mut reader)
+                .await
+            {
+                Ok(ServerToClientMsg::ConfigReloaded) => return Ok(()),
+                Ok(ServerToClientMsg::Error { message, .. }) => return Err(message),
+                Ok(_) => continue,
+                Err(error) => return Err(format!("{:#}", error)),
+            }
+        }
+    })
+    .await
+    .unwrap_or_else(|_| Err("The server did not confirm the reload".to_string()));
+
+    InterProcessCommunication::send_ipc_message(ClientToServerMsg::Detach, &mut writer)
+        .await
+        .ok();
+
+    match answer {
+        Ok(()) if !silent => println!("Config has been reloaded"),
+        Ok(()) => {}
+        Err(message) => {
+            eprintln!("{}", message);
+            process::exit(1);
+        }
+    }
+}
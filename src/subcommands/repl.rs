@@ -0,0 +1,54 @@
+use anyhow::anyhow;
+use futures::io::BufReader;
+use futures::{AsyncBufReadExt, AsyncWriteExt, StreamExt};
+use std::io::{stdout, IsTerminal, Write};
+use tokio::io::{stdin, AsyncBufReadExt as _};
+use zentime_rs::client::one_shot_connection::one_shot_connection;
+
+use crate::cli_error::CliError;
+
+/// Reads plain-text commands (e.g. `STATUS`, `TOGGLE` or `SKIP`) line by line from stdin and
+/// prints the answers of the server, e.g. to control a remote server via `ssh host zentime repl`.
+/// A prompt is only shown, if stdin is a terminal, so that the output can be parsed by scripts.
+#[tokio::main]
+pub async fn repl() {
+    let (reader, mut writer) = match one_shot_connection().await {
+        Ok(c) => c,
+        Err(error) => CliError::Connect(error).exit(),
+    };
+
+    let mut answers = BufReader::new(reader).lines();
+    let mut commands = tokio::io::BufReader::new(stdin()).lines();
+    let is_interactive = std::io::stdin().is_terminal();
+
+    loop {
+        if is_interactive {
+            print!("> ");
+            stdout().flush().ok();
+        }
+
+        let command = match commands.next_line().await {
+            Ok(Some(command)) => command,
+            Ok(None) => break,
+            Err(error) => CliError::Other(format!("Could not read command: {}", error)).exit(),
+        };
+
+        // Empty lines are not answered by the server
+        if command.trim().is_empty() {
+            continue;
+        }
+
+        if let Err(error) = writer
+            .write_all(format!("{}\n", command.trim()).as_bytes())
+            .await
+        {
+            CliError::Communication(error.into()).exit();
+        }
+
+        match answers.next().await {
+            Some(Ok(answer)) => println!("{}", answer),
+            Some(Err(error)) => CliError::Communication(error.into()).exit(),
+            None => CliError::Communication(anyhow!("Server closed the connection")).exit(),
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use std::process;
+use std::thread::sleep;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use chrono::NaiveDate;
+use zentime_rs::config::HistoryConfig;
+use zentime_rs::history::{
+    format_minutes, CycleSummary, History, SessionKind, SessionOutcome, SessionRecord,
+};
+use zentime_rs::wall_clock::WallClock;
+
+/// Longest bar of a single session (in minutes), so that long sessions don't wrap
+const MAX_BAR_LENGTH: u64 = 60;
+
+/// Gaps between two sessions below this length (in seconds) are not shown
+const MIN_SHOWN_GAP: u64 = 60;
+
+/// Prints the sessions of the given day (or today) as timeline, with one bar segment per minute.
+/// With a `speed` the day is played back accelerated by that factor (e.g. `60` plays one minute
+/// per second), so that the distribution of focus and breaks can be followed along.
+///
+/// NOTE: Sessions are placed by the time they've ended, therefore pauses are not shown.
+pub fn replay(
+    config: &HistoryConfig,
+    wall_clock: WallClock,
+    date: Option<NaiveDate>,
+    speed: Option<u32>,
+) {
+    let date = date.unwrap_or_else(|| wall_clock.today());
+    let day = wall_clock.start_of_day(date)
+        ..date
+            .succ_opt()
+            .map_or(SystemTime::now(), |next| wall_clock.start_of_day(next));
+
+    let records = match History::open(config).and_then(|history| history.records_between(day)) {
+        Ok(records) => records,
+        Err(error) => {
+            eprintln!("{:#}", error);
+            process::exit(1);
+        }
+    };
+
+    println!("Sessions of {}\n", date.format("%Y-%m-%d %a"));
+
+    // Postponements are recorded once more, when the break is actually taken
+    let sessions: Vec<_> = records
+        .iter()
+        .filter(|record| record.outcome != SessionOutcome::Postponed)
+        .collect();
+
+    if sessions.is_empty() {
+        println!("No sessions have been recorded on this day");
+        return;
+    }
+
+    let clock_time = |timestamp: u64| {
+        wall_clock
+            .local_time(UNIX_EPOCH + Duration::from_secs(timestamp))
+            .format("%H:%M")
+    };
+    let mut previous_end: Option<u64> = None;
+
+    for record in &sessions {
+        let start = record.ended_at.saturating_sub(record.elapsed_seconds);
+        let gap = previous_end.map_or(0, |end| start.saturating_sub(end));
+
+        if let Some(speed) = speed {
+            sleep(Duration::from_secs(gap + record.elapsed_seconds) / speed.max(1));
+        }
+
+        if gap >= MIN_SHOWN_GAP {
+            println!("{:>13}  {} idle", "", format_minutes(gap));
+        }
+
+        println!(
+            "{}-{}  {:<11} {:>5}  {:<9}  {}",
+            clock_time(start),
+            clock_time(record.ended_at),
+            kind_label(record.kind),
+            format_minutes(record.elapsed_seconds),
+            outcome_label(record.outcome),
+            bar(record)
+        );
+
+        for reason in &record.pause_reasons {
+            println!("{:>13}  paused: {}", "", reason);
+        }
+
+        previous_end = Some(record.ended_at);
+    }
+
+    // The whole day is evaluated just like a cycle
+    let summary = CycleSummary::from_records(sessions.iter().copied());
+    let break_seconds: u64 = sessions
+        .iter()
+        .filter(|record| record.kind.is_break())
+        .map(|record| record.elapsed_seconds.min(record.planned_seconds))
+        .sum();
+
+    println!(
+        "\nFocus: {} ({} interval{} completed), breaks: {}",
+        format_minutes(summary.focused_seconds),
+        summary.intervals,
+        if summary.intervals == 1 { "" } else { "s" },
+        format_minutes(break_seconds)
+    );
+}
+
+fn kind_label(kind: SessionKind) -> &'static str {
+    match kind {
+        SessionKind::Interval => "Focus",
+        SessionKind::ShortBreak => "Short break",
+        SessionKind::LongBreak => "Long break",
+    }
+}
+
+fn outcome_label(outcome: SessionOutcome) -> &'static str {
+    match outcome {
+        SessionOutcome::Completed => "completed",
+        SessionOutcome::Skipped => "skipped",
+        SessionOutcome::Postponed => "postponed",
+        SessionOutcome::Reset => "reset",
+    }
+}
+
+/// One segment per elapsed minute - focus is drawn solid, breaks are drawn light
+fn bar(record: &SessionRecord) -> String {
+    let segment = if record.kind.is_break() { "░" } else { "█" };
+    let length = (record.elapsed_seconds / 60).min(MAX_BAR_LENGTH);
+
+    segment.repeat(length as usize)
+}
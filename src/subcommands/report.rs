@@ -0,0 +1,38 @@
+use zentime_rs::config::Config;
+use zentime_rs::report::WeeklyReport;
+use zentime_rs::wall_clock::WallClock;
+
+use crate::cli_error::CliError;
+
+/// Prints the weekly report of the last seven days as markdown.
+/// With `write`/`send` the report is also written into the report directory or sent via email,
+/// just like the server does at the configured day and time.
+pub fn report(config: &Config, write: bool, send: bool) {
+    let wall_clock = WallClock::from_config(&config.clock);
+
+    let report = match WeeklyReport::load(&config.history, wall_clock, wall_clock.now()) {
+        Ok(report) => report,
+        Err(error) => CliError::Other(format!("{:#}", error)).exit(),
+    };
+
+    print!("{}", report.to_markdown());
+
+    if write {
+        match report.write(&config.report) {
+            Ok(path) => eprintln!("Report has been written to {}", path.display()),
+            Err(error) => CliError::Other(format!("{:#}", error)).exit(),
+        }
+    }
+
+    if send {
+        if config.report.email.is_none() {
+            CliError::Other("No email address has been configured (see `report.email`)".into())
+                .exit();
+        }
+
+        match report.send(&config.report) {
+            Ok(()) => eprintln!("Report has been sent"),
+            Err(error) => CliError::Other(format!("{:#}", error)).exit(),
+        }
+    }
+}
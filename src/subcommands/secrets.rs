@@ -0,0 +1,67 @@
+use std::io::{stdin, IsTerminal};
+use std::process;
+
+use zentime_rs::secrets::{remove, store, SecretRef, SECRET_REF_PREFIX};
+
+use crate::cli_error::CliError;
+
+/// Stores a secret, which can then be referenced inside the config as `keyring:<name>`.
+/// The secret is read from the terminal (without echo) or from stdin, if input is piped.
+pub fn set_secret(name: &str) {
+    let reference = parse_reference(name);
+
+    let secret = if stdin().is_terminal() {
+        rpassword::prompt_password(format!("Secret for {}: ", reference))
+    } else {
+        let mut secret = String::new();
+        stdin().read_line(&mut secret).map(|_| secret)
+    };
+
+    let secret = match secret {
+        Ok(secret) => secret.trim_end_matches(['\r', '\n']).to_string(),
+        Err(error) => CliError::Other(format!("Could not read secret: {}", error)).exit(),
+    };
+
+    if secret.is_empty() {
+        eprintln!("Secret must not be empty");
+        process::exit(1);
+    }
+
+    match store(&reference, &secret) {
+        Ok(storage) => println!("Stored {} in {}", reference, storage),
+        Err(error) => {
+            eprintln!("{:#}", anyhow::Error::new(error));
+            process::exit(1);
+        }
+    }
+}
+
+/// Removes a previously stored secret
+pub fn remove_secret(name: &str) {
+    let reference = parse_reference(name);
+
+    match remove(&reference) {
+        Ok(()) => println!("Removed {}", reference),
+        Err(error) => {
+            eprintln!("{:#}", anyhow::Error::new(error));
+            process::exit(1);
+        }
+    }
+}
+
+/// Accepts names with and without prefix (`zentime/slack` or `keyring:zentime/slack`)
+fn parse_reference(name: &str) -> SecretRef {
+    let reference = if name.starts_with(SECRET_REF_PREFIX) {
+        name.to_string()
+    } else {
+        format!("{}{}", SECRET_REF_PREFIX, name)
+    };
+
+    match reference.parse() {
+        Ok(reference) => reference,
+        Err(error) => {
+            eprintln!("{}", error);
+            process::exit(1);
+        }
+    }
+}
@@ -1,19 +1,32 @@
+use anyhow::{anyhow, bail, Context};
 use daemonize::Daemonize;
 use figment::providers::Serialized;
-use interprocess::local_socket::tokio::LocalSocketStream;
 use log::{error, info};
-use std::env::current_dir;
+use std::env::{current_dir, current_exe};
 use std::fs::File;
-use std::thread::sleep;
-use std::time::Duration;
+use std::process;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::process::{Child, Command};
+use tokio::time::{sleep, Instant};
+use zentime_rs::client::timer_event::Phase;
 use zentime_rs::config::create_base_config;
-use zentime_rs::config::Config;
-use zentime_rs::ipc::get_socket_name;
+use zentime_rs::config::{Config, ConfigLoader};
+use zentime_rs::history::unix_timestamp;
+use zentime_rs::ipc::connect;
+use zentime_rs::ipc::socket_is_systemd_managed;
 use zentime_rs::ipc::ClientToServerMsg;
 use zentime_rs::ipc::InterProcessCommunication;
-use zentime_rs::server::start;
-use zentime_rs::server::status::server_status;
+use zentime_rs::logging::{self, LogTarget};
+use zentime_rs::server::mock::Scenario;
+use zentime_rs::server::status::{
+    connected_clients, pid_file_is_locked, ping, server_pid, server_status, timer_state,
+    ServerStatus,
+};
+use zentime_rs::server::{start, start_mock};
+use zentime_rs_timer::util::seconds_to_time;
 
+use crate::cli_error::CliError;
 use crate::CommonArgs;
 
 const DEFAULT_OUT_FILE: &str = "/tmp/zentime.d.out";
@@ -21,31 +34,61 @@ const DEFAULT_ERROR_FILE: &str = "/tmp/zentime.d.err";
 const DEBUG_OUT_FILE: &str = "/tmp/zentime_debug.d.out";
 const DEBUG_ERROR_FILE: &str = "/tmp/zentime_debug.d.err";
 
+/// Time a freshly spawned server has to start listening on the zentime socket
+const SERVER_STARTUP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Time a server has to shut down after it has been told to quit
+const SERVER_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Interval in which we check if a server has started up or shut down
+const SERVER_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Files the stdout and stderr of the daemonized server are redirected to
+pub fn daemon_output_paths() -> (&'static str, &'static str) {
+    if cfg!(debug_assertions) {
+        (DEBUG_OUT_FILE, DEBUG_ERROR_FILE)
+    } else {
+        (DEFAULT_OUT_FILE, DEFAULT_ERROR_FILE)
+    }
+}
+
 /// Daemonizes the current process and then starts a zentime server instance in it (if there isn't
 /// another server already running - otherwise the process terminates).
+/// If a mock scenario is given, the server plays it back instead of running a timer.
+/// A server which has been started by systemd socket activation is not daemonized.
 ///
 /// NOTE: It's important, that we run this synchronously.
 /// [server::start()] will then create a tokio runtime, after the process has been
 /// deamonized
-pub fn start_daemonized(args: &CommonArgs) {
-    let stdout_path = if cfg!(debug_assertions) {
-        DEBUG_OUT_FILE
-    } else {
-        DEFAULT_OUT_FILE
-    };
-    let stdout = File::create(stdout_path)
-        .unwrap_or_else(|error| panic!("Could not create {}: {}", stdout_path, error));
+pub fn start_daemonized(args: &CommonArgs, mock_scenario: Option<&str>) {
+    // Errors inside the scenario are reported before the output is redirected
+    let scenario = mock_scenario.map(|path| {
+        Scenario::load(shellexpand::tilde(path).as_ref()).unwrap_or_else(|error| {
+            eprintln!("{:#}", error);
+            process::exit(1);
+        })
+    });
 
-    let stderr_path = if cfg!(debug_assertions) {
-        DEBUG_ERROR_FILE
-    } else {
-        DEFAULT_ERROR_FILE
+    // systemd has passed the socket to this very process and collects its output itself
+    #[cfg(target_os = "linux")]
+    if zentime_rs::server::systemd::is_socket_activated() {
+        run_server(args, scenario);
+        return;
+    }
+
+    let (stdout_path, stderr_path) = daemon_output_paths();
+
+    let create_output = |path: &str| {
+        File::create(path)
+            .with_context(|| format!("Could not create {}", path))
+            .unwrap_or_else(|error| CliError::ServerSpawn(error).exit())
     };
-    let stderr = File::create(stderr_path)
-        .unwrap_or_else(|error| panic!("Could not create {}: {}", stderr_path, error));
+    let stdout = create_output(stdout_path);
+    let stderr = create_output(stderr_path);
 
     let current_directory = current_dir()
-        .expect("Could not get current directory")
+        .context("Could not get current directory")
+        .unwrap_or_else(|error| CliError::ServerSpawn(error).exit())
         .into_os_string();
 
     let daemonize = Daemonize::new()
@@ -54,14 +97,28 @@ pub fn start_daemonized(args: &CommonArgs) {
         .stderr(stderr); // Redirect stderr to `/tmp/daemon.err`.
 
     if let Err(error) = daemonize.start() {
-        panic!("Could not daemonize server process: {}", error);
+        CliError::ServerSpawn(anyhow!(error).context("Could not daemonize server process")).exit();
     };
 
-    info!("Daemonized server process");
+    run_server(args, scenario);
+}
 
+fn run_server(args: &CommonArgs, scenario: Option<Scenario>) {
     let config = get_server_config(args);
 
-    if let Err(error) = start(config) {
+    // Errors end up in the stderr file of the daemon (or in the journal)
+    if let Err(error) = logging::init(&config.log, LogTarget::Server) {
+        eprintln!("Could not set up server log: {:#}", error);
+    }
+
+    info!("Starting server (PID {})", process::id());
+
+    let result = match scenario {
+        Some(scenario) => start_mock(config, scenario),
+        None => start(config, server_config_loader(args)),
+    };
+
+    if let Err(error) = result {
         error!("A server error occured: {}", error);
     };
 }
@@ -73,15 +130,60 @@ fn get_server_config(args: &CommonArgs) -> Config {
     create_base_config(config_path)
         .merge(Serialized::defaults(args.server_config.clone()))
         .extract()
-        .expect("Could not create config")
+        .unwrap_or_else(|error| CliError::config(config_path, error).exit())
+}
+
+/// Reads the config file again whenever the server reloads its config.
+/// Values which have been passed as arguments keep overriding the ones inside the file.
+fn server_config_loader(args: &CommonArgs) -> ConfigLoader {
+    let config_path = args.config.clone();
+    let server_config = args.server_config.clone();
+
+    Arc::new(move || {
+        info!("Reloading config from path: {}", config_path);
+
+        create_base_config(&config_path)
+            .merge(Serialized::defaults(server_config.clone()))
+            .extract()
+            .with_context(|| format!("Invalid config file {}", config_path))
+    })
 }
 
 /// Stops a currently running zentime server (there can only ever be a single instance - all
 /// clients will automatically shutdown, when their connection closes).
 #[tokio::main]
 pub async fn stop() {
-    let socket_name = get_socket_name();
+    if server_status().await == ServerStatus::Stopped {
+        println!("Server is not running");
+        return;
+    }
+
+    if let Err(error) = shutdown_server().await {
+        CliError::Communication(error).exit();
+    }
+
+    println!("Server stopped");
+}
 
+/// Stops the currently running zentime server (if there is one) and starts a new one with the
+/// given arguments, e.g. to apply settings which can't be reloaded.
+#[tokio::main]
+pub async fn restart(args: &CommonArgs) {
+    if server_status().await == ServerStatus::Running {
+        if let Err(error) = shutdown_server().await {
+            CliError::Communication(error).exit();
+        }
+    }
+
+    if let Err(error) = spawn_server(args).await {
+        CliError::ServerSpawn(error).exit();
+    }
+
+    println!("Server restarted");
+}
+
+/// Tells the server to quit and waits until it has shut down
+pub async fn shutdown_server() -> anyhow::Result<()> {
     let mut connection_tries = 0;
 
     info!("Connecting to server...");
@@ -89,32 +191,377 @@ pub async fn stop() {
     let connection = loop {
         connection_tries += 1;
 
-        if connection_tries == 4 {
-            panic!("Could not connect to server");
+        match connect().await {
+            Ok(conn) => break conn,
+            Err(error) if connection_tries == 3 => CliError::Connect(error).exit(),
+            Err(_) => sleep(Duration::from_millis(200)).await,
         }
+    };
 
-        let result = LocalSocketStream::connect(socket_name).await;
+    info!("Shutting down...");
+
+    let (_, mut writer) = connection.into_split();
+
+    InterProcessCommunication::send_ipc_message(ClientToServerMsg::Quit, &mut writer).await?;
 
-        if let Ok(conn) = result {
-            break conn;
+    let deadline = Instant::now() + SERVER_SHUTDOWN_TIMEOUT;
+
+    // A ping to the socket of systemd would start the next server right away, but the old one
+    // has shut down as soon as it has released its PID file
+    let is_running = || async {
+        if socket_is_systemd_managed() {
+            pid_file_is_locked()
         } else {
-            sleep(Duration::from_millis(200));
+            server_status().await == ServerStatus::Running
         }
     };
 
-    info!("Shutting down...");
-
-    let (_, mut writer) = connection.into_split();
+    while is_running().await {
+        if Instant::now() >= deadline {
+            bail!(
+                "Server did not shut down within {} seconds",
+                SERVER_SHUTDOWN_TIMEOUT.as_secs()
+            );
+        }
 
-    let msg = ClientToServerMsg::Quit;
-    InterProcessCommunication::send_ipc_message(msg, &mut writer)
-        .await
-        .expect("Could not send Quit message");
+        sleep(SERVER_POLL_INTERVAL).await;
+    }
 
     info!("Done.");
+    Ok(())
 }
 
-/// Prints the current status of the zentime server
-pub fn status() {
-    println!("Server is {}", server_status());
+/// Spawns a daemonized server via `zentime server start` and waits until it is ready
+pub async fn spawn_server(args: &CommonArgs) -> anyhow::Result<()> {
+    // WHY:
+    // We want to use the path to the executable of the current zentime process.
+    // That way this does also work in ci or during development, where one might not have
+    // added a specific zentime binary to their path.
+    let current_exe = current_exe().context("Could not determine path of zentime executable")?;
+    let current_dir = current_dir()
+        .context("Could not get current directory")?
+        .into_os_string();
+
+    // NOTE:
+    // We don't wait for the spawned process, because it exits as soon as the server has
+    // been daemonized - which says nothing about whether the server is already listening.
+    let mut server_process = Command::new(current_exe)
+        .arg("server")
+        .arg("start")
+        .args(get_server_args(args))
+        .current_dir(current_dir)
+        .spawn()
+        .context("Could not run `zentime server start`")?;
+
+    wait_for_server(&mut server_process).await
+}
+
+/// Registers (or removes) the zentime server as Windows service or as socket activated systemd
+/// user service
+#[cfg_attr(not(any(windows, target_os = "linux")), allow(unused_variables))]
+pub fn install_service(args: &CommonArgs, uninstall: bool) {
+    #[cfg(windows)]
+    {
+        use zentime_rs::server::windows_service;
+
+        let result = if uninstall {
+            windows_service::uninstall()
+        } else {
+            windows_service::install(&args.config)
+        };
+
+        match result {
+            Ok(()) if uninstall => println!("Removed zentime service"),
+            Ok(()) => println!("Installed zentime service"),
+            Err(error) => {
+                CliError::Other(format!("Could not update zentime service: {:#}", error)).exit()
+            }
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        use zentime_rs::server::systemd;
+
+        if uninstall {
+            if let Err(error) = systemd::uninstall() {
+                CliError::Other(format!("Could not remove zentime service: {:#}", error)).exit();
+            }
+
+            println!("Removed systemd units of zentime");
+            return;
+        }
+
+        // The running server holds the PID file, which the first socket activated server needs
+        stop_running_server();
+
+        match systemd::install(&args.config) {
+            Ok(unit_dir) => println!(
+                "Installed {} and {} at {}",
+                systemd::SOCKET_UNIT_NAME,
+                systemd::SERVICE_UNIT_NAME,
+                unit_dir.display()
+            ),
+            Err(error) => {
+                CliError::Other(format!("Could not install zentime service: {:#}", error)).exit()
+            }
+        }
+    }
+
+    #[cfg(not(any(windows, target_os = "linux")))]
+    {
+        CliError::Other(
+            "Installing the server as service is only supported on Windows and Linux".to_string(),
+        )
+        .exit();
+    }
+}
+
+/// Shuts down the server, if one is running
+#[cfg(target_os = "linux")]
+#[tokio::main]
+async fn stop_running_server() {
+    if server_status().await == ServerStatus::Stopped {
+        return;
+    }
+
+    if let Err(error) = shutdown_server().await {
+        CliError::Communication(error).exit();
+    }
+
+    println!("Stopped running server");
+}
+
+/// Runs the zentime server as Windows service (invoked by the service control manager)
+#[cfg_attr(not(windows), allow(unused_variables))]
+pub fn run_service(args: &CommonArgs) {
+    #[cfg(windows)]
+    {
+        let config = get_server_config(args);
+        let config_loader = server_config_loader(args);
+
+        if let Err(error) = zentime_rs::server::windows_service::run(config, config_loader) {
+            CliError::Other(format!("Could not run zentime service: {:#}", error)).exit();
+        }
+    }
+
+    #[cfg(not(windows))]
+    {
+        CliError::Other("Running the server as service is only supported on Windows".to_string())
+            .exit();
+    }
+}
+
+/// Prints the current status of the zentime server including the state of its timer, e.g.
+///
+/// ```text
+/// Server is running (PID 4242)
+/// Round:     3
+/// Phase:     focus
+/// Remaining: 12:34
+/// Paused:    no
+/// Profile:   deep-work
+/// Task:      write docs
+/// Clients:   2
+/// ```
+#[tokio::main]
+pub async fn status() {
+    if server_status().await == ServerStatus::Stopped {
+        println!("Server is not running");
+        return;
+    }
+
+    match server_pid() {
+        Some(pid) => println!("Server is running (PID {})", pid),
+        None => println!("Server is running"),
+    }
+
+    // A server which is still starting up has no timer yet
+    let state = match timer_state().await {
+        Ok(state) => state,
+        Err(error) => {
+            println!("Timer state is not available: {:#}", error);
+            return;
+        }
+    };
+
+    let phase = if state.hold.is_some() {
+        "on hold".to_string()
+    } else {
+        Phase::of(&state).to_string()
+    };
+
+    println!("Round:     {}", state.round);
+    println!("Phase:     {}", phase);
+    println!("Remaining: {}", state.time);
+    println!("Paused:    {}", if state.is_paused { "yes" } else { "no" });
+
+    if let Some(profile) = &state.profile {
+        println!("Profile:   {}", profile);
+    }
+
+    if let Some(task) = &state.task {
+        println!("Task:      {}", task);
+    }
+
+    if let Ok(clients) = connected_clients().await {
+        println!("Clients:   {}", clients.len());
+    }
+}
+
+/// Lists the clients which are connected to the zentime server, e.g.
+///
+/// ```text
+/// ID  ROLE        TRANSPORT  PEER             CONNECTED  LAST SEEN
+/// 1   owner       socket     PID 4711         25:03      00:04 ago
+/// 4   controller  tcp        10.0.0.7:51234   03:12      00:09 ago
+/// ```
+#[tokio::main]
+pub async fn clients() {
+    if server_status().await == ServerStatus::Stopped {
+        println!("Server is not running");
+        return;
+    }
+
+    let clients = match connected_clients().await {
+        Ok(clients) => clients,
+        Err(error) => CliError::Communication(error).exit(),
+    };
+
+    if clients.is_empty() {
+        println!("No clients are connected");
+        return;
+    }
+
+    let now = unix_timestamp(SystemTime::now());
+
+    println!(
+        "{:<3} {:<11} {:<10} {:<16} {:<10} LAST SEEN",
+        "ID", "ROLE", "TRANSPORT", "PEER", "CONNECTED"
+    );
+
+    for client in clients {
+        let peer = match (&client.address, client.pid) {
+            (Some(address), _) => address.clone(),
+            (None, Some(pid)) => format!("PID {}", pid),
+            (None, None) => "-".to_string(),
+        };
+
+        println!(
+            "{:<3} {:<11} {:<10} {:<16} {:<10} {} ago",
+            client.id,
+            client.role.to_string(),
+            client.transport.to_string(),
+            peer,
+            seconds_to_time(now.saturating_sub(client.connected_at)),
+            seconds_to_time(now.saturating_sub(client.last_seen)),
+        );
+    }
+}
+
+/// Polls the zentime socket until the freshly spawned server answers (or the timeout is reached)
+async fn wait_for_server(server_process: &mut Child) -> anyhow::Result<()> {
+    let deadline = Instant::now() + SERVER_STARTUP_TIMEOUT;
+
+    while Instant::now() < deadline {
+        if ping().await.is_ok() {
+            return Ok(());
+        }
+
+        if let Some(status) = server_process
+            .try_wait()
+            .context("Could not check server process")?
+        {
+            if !status.success() {
+                bail!("Server process exited with {}", status);
+            }
+        }
+
+        sleep(SERVER_POLL_INTERVAL).await;
+    }
+
+    bail!(
+        "Server did not open its socket within {} seconds",
+        SERVER_STARTUP_TIMEOUT.as_secs()
+    )
+}
+
+fn get_server_args(common_args: &CommonArgs) -> Vec<String> {
+    let mut args: Vec<String> = vec![
+        // Config path
+        "-c".to_string(),
+        common_args.config.to_string(),
+    ];
+
+    if let Some(postpone_limit) = &common_args.server_config.timers.postpone_limit {
+        args.push("--postpone-limit".to_string());
+        args.push(postpone_limit.to_string());
+    }
+
+    if let Some(postpone_timer) = &common_args.server_config.timers.postpone_timer {
+        args.push("--postpone-timer".to_string());
+        args.push(postpone_timer.to_string());
+    }
+
+    if let Some(enable_bell) = &common_args.server_config.notifications.enable_bell {
+        args.push("--enable-bell".to_string());
+        args.push(enable_bell.to_string());
+    }
+
+    if let Some(sound_file) = &common_args.server_config.notifications.sound_file {
+        args.push("--sound-file".to_string());
+        args.push(sound_file.to_string());
+    }
+
+    if let Some(volume) = &common_args.server_config.notifications.volume {
+        args.push("--volume".to_string());
+        args.push(volume.to_string());
+    }
+
+    if let Some(show_notification) = &common_args.server_config.notifications.show_notification {
+        args.push("--show-notification".to_string());
+        args.push(show_notification.to_string());
+    }
+
+    if let Some(timer) = &common_args.server_config.timers.timer {
+        args.push("--timer".to_string());
+        args.push(timer.to_string());
+    }
+
+    if let Some(minor_break) = &common_args.server_config.timers.minor_break {
+        args.push("--minor-break".to_string());
+        args.push(minor_break.to_string());
+    }
+
+    if let Some(major_break) = &common_args.server_config.timers.major_break {
+        args.push("--major-break".to_string());
+        args.push(major_break.to_string());
+    }
+
+    if let Some(intervals) = &common_args.server_config.timers.intervals {
+        args.push("--intervals".to_string());
+        args.push(intervals.to_string())
+    }
+
+    if let Some(auto_start_break) = &common_args.server_config.timers.auto_start_break {
+        args.push("--auto-start-break".to_string());
+        args.push(auto_start_break.to_string());
+    }
+
+    if let Some(auto_start_work) = &common_args.server_config.timers.auto_start_work {
+        args.push("--auto-start-work".to_string());
+        args.push(auto_start_work.to_string());
+    }
+
+    if let Some(profile) = &common_args.server_config.profile {
+        args.push("--profile".to_string());
+        args.push(profile.to_string());
+    }
+
+    if let Some(resume) = &common_args.server_config.state.resume {
+        args.push("--resume".to_string());
+        args.push(resume.to_string());
+    }
+
+    args
 }
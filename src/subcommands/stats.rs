@@ -0,0 +1,203 @@
+use chrono::Datelike;
+use clap::ValueEnum;
+use std::process;
+use std::time::{Duration, UNIX_EPOCH};
+use zentime_rs::config::{FocusContractConfig, GoalConfig, HistoryConfig, PlanConfig};
+use zentime_rs::goals::{GoalPeriod, GoalProgress};
+use zentime_rs::history::{
+    focus_by_tag, format_minutes, pause_reason_counts, BreakCompliance, ContractRecord,
+    CycleSummary, FocusScore, History, SessionKind, SessionOutcome, SessionRecord,
+};
+use zentime_rs::plan::Plan;
+use zentime_rs::wall_clock::WallClock;
+
+/// Break compliance below which a reminder to take breaks is shown
+const COMPLIANCE_NUDGE_THRESHOLD: u64 = 80;
+
+/// Number of the most common pause reasons which are listed
+const MAX_LISTED_INTERRUPTIONS: usize = 5;
+
+/// Number of the tags with the most focus time which are listed
+const MAX_LISTED_TAGS: usize = 10;
+
+/// Period by which the summary is broken down
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum StatsPeriod {
+    /// One line per day
+    Day,
+
+    /// One line per (ISO) calendar week
+    Week,
+}
+
+/// Prints a summary of the recorded sessions of the last `days` days, the progress towards the
+/// focus time goals and the completion of the plan of today.
+/// The track record of the focus contract is shown, once it is enabled or has been broken.
+/// If a period is given, the intervals and focus time of each day/week are listed, too.
+pub fn stats(
+    config: &HistoryConfig,
+    plan_config: &PlanConfig,
+    goal_config: &GoalConfig,
+    contract_config: &FocusContractConfig,
+    wall_clock: WallClock,
+    days: u64,
+    by: Option<StatsPeriod>,
+) {
+    let records = match History::open(config).and_then(|history| history.records_of_last_days(days))
+    {
+        Ok(records) => records,
+        Err(error) => {
+            eprintln!("{:#}", error);
+            process::exit(1);
+        }
+    };
+
+    if !config.enabled {
+        println!("NOTE: Recording sessions is disabled (see `history.enabled`)\n");
+    }
+
+    let count = |kind: fn(&SessionKind) -> bool, outcome: SessionOutcome| {
+        records
+            .iter()
+            .filter(|record| kind(&record.kind) && record.outcome == outcome)
+            .count()
+    };
+    let is_interval = |kind: &SessionKind| *kind == SessionKind::Interval;
+
+    println!("Last {} day{}", days, if days == 1 { "" } else { "s" });
+    println!(
+        "Intervals:        {} completed, {} skipped",
+        count(is_interval, SessionOutcome::Completed),
+        count(is_interval, SessionOutcome::Skipped)
+    );
+
+    let compliance = BreakCompliance::from_records(&records);
+
+    println!(
+        "Breaks:           {} taken, {} skipped, {} postponed",
+        compliance.taken, compliance.skipped, compliance.postponed
+    );
+
+    match compliance.percentage() {
+        Some(percentage) => {
+            println!("Break compliance: {}%", percentage);
+
+            if percentage < COMPLIANCE_NUDGE_THRESHOLD {
+                println!("\nRegular breaks keep you focused - try not to skip them!");
+            }
+        }
+        None => println!("Break compliance: -"),
+    }
+
+    match FocusScore::from_records(&records).value() {
+        Some(score) => println!("Focus score:      {}/100", score),
+        None => println!("Focus score:      -"),
+    }
+
+    let contract = ContractRecord::from_records(&records);
+
+    match contract.broken {
+        0 if contract_config.enabled => println!("Focus contract:   never broken"),
+        0 => {}
+        broken => println!(
+            "Focus contract:   {} broken, {} interval{} kept since the last one",
+            broken,
+            contract.kept_since_broken,
+            if contract.kept_since_broken == 1 {
+                ""
+            } else {
+                "s"
+            }
+        ),
+    }
+
+    for period in [GoalPeriod::Week, GoalPeriod::Month] {
+        match GoalProgress::load(goal_config, config, wall_clock, period) {
+            Ok(Some(progress)) => println!(
+                "{:<17} {}{}",
+                format!("{}:", period),
+                progress,
+                if progress.is_met() { " - met" } else { "" }
+            ),
+            Ok(None) => {}
+            Err(error) => eprintln!("{:#}", error),
+        }
+    }
+
+    if let Some(period) = by {
+        print_periods(&records, wall_clock, period);
+    }
+
+    // The plan is a bonus, therefore an unreadable plan does not fail the statistics
+    match Plan::load(&plan_config.path(), wall_clock.today()) {
+        Ok(plan) if !plan.items.is_empty() => println!("\n{}", plan),
+        Ok(_) => {}
+        Err(error) => eprintln!("\n{:#}", error),
+    }
+
+    let tags = focus_by_tag(&records);
+
+    if !tags.is_empty() {
+        println!("\nFocus by tag:");
+
+        for (tag, focused_seconds) in tags.iter().take(MAX_LISTED_TAGS) {
+            println!("  {:>8} {}", format_minutes(*focused_seconds), tag);
+        }
+    }
+
+    let interruptions = pause_reason_counts(&records);
+
+    if !interruptions.is_empty() {
+        println!("\nInterruptions:");
+
+        for (reason, count) in interruptions.iter().take(MAX_LISTED_INTERRUPTIONS) {
+            println!("  {:>3}x {}", count, reason);
+        }
+    }
+}
+
+/// Prints the completed intervals and the focus time of each day/week, in which sessions have
+/// been recorded
+fn print_periods(records: &[SessionRecord], wall_clock: WallClock, period: StatsPeriod) {
+    let mut periods: Vec<(String, Vec<&SessionRecord>)> = vec![];
+
+    for record in records {
+        let date = wall_clock
+            .local_time(UNIX_EPOCH + Duration::from_secs(record.ended_at))
+            .date();
+        let label = match period {
+            StatsPeriod::Day => date.format("%Y-%m-%d %a").to_string(),
+            StatsPeriod::Week => {
+                let week = date.iso_week();
+                format!("{}-W{:02}", week.year(), week.week())
+            }
+        };
+
+        // Records are stored in the order they have ended
+        match periods.last_mut() {
+            Some((last, records)) if *last == label => records.push(record),
+            _ => periods.push((label, vec![record])),
+        }
+    }
+
+    println!(
+        "\nPer {}:",
+        match period {
+            StatsPeriod::Day => "day",
+            StatsPeriod::Week => "week",
+        }
+    );
+
+    for (label, records) in periods {
+        // Periods are evaluated just like cycles
+        let summary = CycleSummary::from_records(records);
+
+        println!(
+            "  {:<14} {:>3} interval{} {:>8} focused",
+            label,
+            summary.intervals,
+            if summary.intervals == 1 { " " } else { "s" },
+            format_minutes(summary.focused_seconds)
+        );
+    }
+}
@@ -0,0 +1,158 @@
+use clap::ValueEnum;
+use futures::io::BufReader;
+use serde::Serialize;
+use zentime_rs::client::one_shot_connection::one_shot_connection;
+use zentime_rs::client::overlay::overlay_text;
+use zentime_rs::history::format_minutes;
+use zentime_rs::ipc::ClientToServerMsg;
+use zentime_rs::ipc::{InterProcessCommunication, IpcReader, ServerToClientMsg, Snapshot};
+use zentime_rs_timer::pomodoro_timer::ViewState;
+use zentime_rs_timer::status_template::StatusTemplate;
+
+use crate::cli_error::CliError;
+
+/// Output format of `zentime status`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum StatusFormat {
+    /// Remaining time and phase, followed by pending countdowns (e.g.
+    /// `24:59 Focus · standup 14:59`)
+    Plain,
+
+    /// The whole timer state as JSON object
+    Json,
+
+    /// JSON object for custom waybar modules (`text`, `tooltip` and `class`)
+    Waybar,
+}
+
+/// Output of a custom waybar module
+#[derive(Debug, Serialize)]
+struct WaybarModule {
+    text: String,
+    tooltip: String,
+    class: Vec<&'static str>,
+}
+
+/// Queries the current state of the timer and prints it in the given format (e.g. for status
+/// bars, which poll the timer). A template replaces the plain line and the text of waybar
+/// modules.
+#[tokio::main]
+pub async fn print_status(format: StatusFormat, template: Option<StatusTemplate>) {
+    let (reader, mut writer) = match one_shot_connection().await {
+        Ok(c) => c,
+        Err(error) => CliError::Connect(error).exit(),
+    };
+
+    let mut reader = IpcReader::new(BufReader::new(reader));
+
+    if let Err(err) =
+        InterProcessCommunication::send_ipc_message(ClientToServerMsg::Sync, &mut writer).await
+    {
+        CliError::Communication(err).exit()
+    };
+
+    // Errors, which are broadcast to all clients in the meantime, are skipped
+    let state = loop {
+        match reader.recv::<ServerToClientMsg>().await {
+            Ok(
+                ServerToClientMsg::Timer(state)
+                | ServerToClientMsg::Snapshot(Snapshot { state, .. }),
+            ) => break state,
+            Ok(_) => continue,
+            Err(error) => CliError::Communication(error).exit(),
+        }
+    };
+
+    InterProcessCommunication::send_ipc_message(ClientToServerMsg::Detach, &mut writer)
+        .await
+        .ok();
+
+    let output = match format {
+        StatusFormat::Plain => Ok(match &template {
+            Some(template) => template.render(&state),
+            None => plain_status(&state),
+        }),
+        StatusFormat::Json => serde_json::to_string(&state),
+        StatusFormat::Waybar => serde_json::to_string(&waybar_module(&state, template.as_ref())),
+    };
+
+    match output {
+        Ok(output) => println!("{}", output),
+        Err(error) => CliError::Other(format!("Could not serialize status: {}", error)).exit(),
+    }
+}
+
+/// Appends the pending countdowns to the remaining time and phase
+fn plain_status(state: &ViewState) -> String {
+    state
+        .countdowns
+        .iter()
+        .fold(overlay_text(state), |mut text, countdown| {
+            text.push_str(" · ");
+
+            if let Some(label) = &countdown.label {
+                text.push_str(label);
+                text.push(' ');
+            }
+
+            text.push_str(&countdown.time);
+            text
+        })
+}
+
+/// Shows the remaining time (or the templated line) inside the bar and the details of the session (incl. pending
+/// countdowns) inside the tooltip.
+/// The classes allow to style each phase (`focus`, `break`, `postponed` and `hold`) and paused
+/// timers (`paused`).
+fn waybar_module(state: &ViewState, template: Option<&StatusTemplate>) -> WaybarModule {
+    let mut class = vec![if state.hold.is_some() {
+        "hold"
+    } else if state.is_break {
+        "break"
+    } else if state.is_postponed {
+        "postponed"
+    } else {
+        "focus"
+    }];
+
+    if state.is_paused {
+        class.push("paused");
+    }
+
+    if state.today.is_some_and(|today| today.has_reached_goal()) {
+        class.push("goal-reached");
+    }
+
+    let mut tooltip = match &state.task {
+        Some(task) => format!("Round {} - {}", state.round, task),
+        None => format!("Round {}", state.round),
+    };
+
+    if let Some(today) = state.today {
+        let intervals = match today.goal {
+            Some(goal) => format!("{}/{} intervals", today.intervals, goal),
+            None if today.intervals == 1 => "1 interval".to_string(),
+            None => format!("{} intervals", today.intervals),
+        };
+
+        tooltip.push_str(&format!(
+            "\n{} today, {} focused",
+            intervals,
+            format_minutes(u64::from(today.focused_minutes) * 60)
+        ));
+    }
+
+    for countdown in &state.countdowns {
+        tooltip.push_str(&format!(
+            "\nCountdown {}: {}",
+            countdown.label.as_deref().unwrap_or("without label"),
+            countdown.time
+        ));
+    }
+
+    WaybarModule {
+        text: template.map_or_else(|| overlay_text(state), |template| template.render(state)),
+        tooltip,
+        class,
+    }
+}
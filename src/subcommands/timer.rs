@@ -0,0 +1,82 @@
+use std::process;
+use std::time::Duration;
+
+use zentime_rs::ipc::{ClientToServerMsg, MAIN_TIMER};
+use zentime_rs::server::status::timer_state;
+use zentime_rs_timer::util::seconds_to_time;
+
+use super::send_change;
+
+/// Starts a named countdown next to the pomodoro timer, which is selected right away
+#[tokio::main]
+pub async fn new_timer(name: String, duration: Duration, silent: bool) {
+    let confirmation = format!(
+        "Started timer '{}' ({})",
+        name,
+        seconds_to_time(duration.as_secs())
+    );
+
+    send_change(
+        ClientToServerMsg::NewTimer {
+            name,
+            seconds: duration.as_secs(),
+        },
+        &confirmation,
+        silent,
+    )
+    .await;
+}
+
+/// Lists the pomodoro timer and all named timers with their remaining time.
+/// The selected timer is marked.
+#[tokio::main]
+pub async fn list_timers() {
+    let state = match timer_state().await {
+        Ok(state) => state,
+        Err(error) => {
+            eprintln!("{:#}", error);
+            process::exit(1);
+        }
+    };
+
+    let timers = std::iter::once((
+        MAIN_TIMER,
+        state.time.as_str(),
+        state.is_paused,
+        state.selected_timer().is_none(),
+    ))
+    .chain(state.timers.iter().map(|timer| {
+        (
+            timer.name.as_str(),
+            timer.time.as_str(),
+            timer.is_paused,
+            timer.is_selected,
+        )
+    }));
+    let width = state
+        .timers
+        .iter()
+        .map(|timer| timer.name.len())
+        .chain([MAIN_TIMER.len()])
+        .max()
+        .unwrap_or_default();
+
+    for (name, time, is_paused, is_selected) in timers {
+        println!(
+            "{} {:<width$}  {}{}",
+            if is_selected { "*" } else { " " },
+            name,
+            time,
+            if is_paused { " (paused)" } else { "" },
+            width = width,
+        );
+    }
+}
+
+/// Selects the timer, which all clients display and control from now on
+#[tokio::main]
+pub async fn select_timer(name: String, silent: bool) {
+    let confirmation = format!("Selected timer '{}'", name);
+
+    send_change(ClientToServerMsg::SelectTimer(name), &confirmation, silent).await;
+}
@@ -1,14 +1,18 @@
 use futures::io::BufReader;
+use std::process;
 use zentime_rs::client::one_shot_connection::one_shot_connection;
 use zentime_rs::ipc::ClientToServerMsg;
 use zentime_rs::ipc::InterProcessCommunication;
 use zentime_rs::ipc::ServerToClientMsg;
 
+use crate::cli_error::CliError;
+
+/// Toggles between play/pause. If the timer is paused by this, the given reason is recorded.
 #[tokio::main]
-pub async fn toggle_timer(silent: bool) {
+pub async fn toggle_timer(reason: Option<String>, silent: bool) {
     let (reader, mut writer) = match one_shot_connection().await {
         Ok(c) => c,
-        Err(error) => panic!("Could not conenct to server: {}", error),
+        Err(error) => CliError::Connect(error).exit(),
     };
 
     let mut reader = BufReader::new(reader);
@@ -16,14 +20,28 @@ pub async fn toggle_timer(silent: bool) {
     if let Err(err) =
         InterProcessCommunication::send_ipc_message(ClientToServerMsg::PlayPause, &mut writer).await
     {
-        panic!("Could not send to the server: {}", err)
+        CliError::Communication(err).exit()
     };
 
+    // The server ignores the reason, if the timer has been resumed instead
+    if let Some(reason) = reason {
+        if let Err(err) = InterProcessCommunication::send_ipc_message(
+            ClientToServerMsg::PauseReason { reason },
+            &mut writer,
+        )
+        .await
+        {
+            CliError::Communication(err).exit()
+        };
+    }
+
     let msg_result =
         InterProcessCommunication::recv_ipc_message::<ServerToClientMsg>(&mut reader).await;
 
-    if !silent {
-        if let Ok(ServerToClientMsg::Timer(state)) = msg_result {
+    let mut has_failed = false;
+
+    match msg_result {
+        Ok(ServerToClientMsg::Timer(state)) if !silent => {
             println!(
                 "{} {} {}",
                 state.round,
@@ -31,9 +49,18 @@ pub async fn toggle_timer(silent: bool) {
                 if state.is_break { "Break" } else { "Focus" }
             );
         }
+        Ok(ServerToClientMsg::Error { message, .. }) => {
+            eprintln!("{}", message);
+            has_failed = true;
+        }
+        _ => {}
     }
 
     InterProcessCommunication::send_ipc_message(ClientToServerMsg::Detach, &mut writer)
         .await
         .ok();
+
+    if has_failed {
+        process::exit(1);
+    }
 }
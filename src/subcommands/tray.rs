@@ -0,0 +1,92 @@
+use std::time::Duration;
+
+use ksni::{Handle, TrayMethods};
+use log::{info, warn};
+use tokio::select;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+use tokio::time::sleep;
+use zentime_rs::client::tray::TimerTray;
+use zentime_rs::client_api::ZentimeClient;
+use zentime_rs::ipc::{ClientPreferences, ClientToServerMsg, ServerToClientMsg, Snapshot};
+use zentime_rs::ipc::{TimePrecision, UpdateMode};
+
+use crate::cli_error::CliError;
+
+/// Time between two attempts to reach the server, while it is not running
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// Shows the timer as tray icon, until it is closed via its menu.
+/// Unlike other clients, the tray outlives the server: it shows that it is not connected and
+/// reconnects as soon as the server is running again.
+#[tokio::main]
+pub async fn tray() {
+    let (action_sender, mut actions) = unbounded_channel();
+
+    let handle = match TimerTray::new(action_sender).spawn().await {
+        Ok(handle) => handle,
+        Err(error) => CliError::Other(format!("Could not show tray icon: {}", error)).exit(),
+    };
+
+    loop {
+        match ZentimeClient::connect().await {
+            Ok(client) => match follow(client, &handle, &mut actions).await {
+                Ok(()) => break,
+                Err(error) => {
+                    info!("{:#}", error);
+                    handle.update(|tray| tray.set_state(None)).await;
+                }
+            },
+            Err(error) => info!("{:#}", error),
+        }
+
+        // Closing the tray has to work while it is not connected as well
+        select! {
+            _ = sleep(RECONNECT_DELAY) => {}
+            msg = actions.recv() => {
+                if matches!(msg, None | Some(ClientToServerMsg::Detach)) {
+                    break;
+                }
+            }
+        }
+    }
+
+    handle.shutdown().await;
+}
+
+/// Keeps the tray in sync with the server and forwards the actions of its menu.
+/// Returns once the tray has been closed and an error if the connection has been lost.
+async fn follow(
+    mut client: ZentimeClient,
+    handle: &Handle<TimerTray>,
+    actions: &mut UnboundedReceiver<ClientToServerMsg>,
+) -> anyhow::Result<()> {
+    // The tray only shows started minutes, so it does not need to be redrawn every second
+    client
+        .hello(ClientPreferences {
+            updates: UpdateMode::Ticks,
+            precision: TimePrecision::Minutes,
+            ..ClientPreferences::default()
+        })
+        .await?;
+
+    let Snapshot { state, .. } = client.state().await?;
+    handle.update(|tray| tray.set_state(Some(state))).await;
+
+    loop {
+        select! {
+            msg = client.recv() => match msg? {
+                ServerToClientMsg::Timer(state)
+                | ServerToClientMsg::Snapshot(Snapshot { state, .. }) => {
+                    handle.update(|tray| tray.set_state(Some(state))).await;
+                }
+                ServerToClientMsg::Error { message, .. } => warn!("{}", message),
+                ServerToClientMsg::ShuttingDown => anyhow::bail!("Server is shutting down"),
+                _ => {}
+            },
+            msg = actions.recv() => match msg {
+                None | Some(ClientToServerMsg::Detach) => return client.detach().await,
+                Some(msg) => client.send(&msg).await?,
+            }
+        }
+    }
+}
@@ -0,0 +1,455 @@
+//! Utilities for end-to-end tests of zentime servers and clients (requires the `test-support`
+//! feature).
+//!
+//! A [TestServer] runs inside the current tokio runtime on its own temporary socket, so that
+//! tests never interfere with a real zentime server. [TestClient]s can then be used to send
+//! scripted messages to it and to assert on the messages it broadcasts.
+//! Timers can be fast-forwarded via [advance_clock].
+//!
+//! ## Example
+//!
+//! ```no_run
+//! use zentime_rs::config::Config;
+//! use zentime_rs::ipc::ClientToServerMsg;
+//! use zentime_rs::test_support::TestServer;
+//!
+//! #[tokio::main]
+//! async fn main() -> anyhow::Result<()> {
+//!     let server = TestServer::start(Config::default()).await?;
+//!     let mut client = server.connect().await?;
+//!
+//!     client.send(ClientToServerMsg::PlayPause).await?;
+//!     let state = client.recv_state_matching(|state| !state.is_paused).await?;
+//!     assert_eq!(state.round, 1);
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{bail, Context};
+use futures::io::BufReader;
+use interprocess::local_socket::tokio::LocalSocketStream;
+use interprocess::local_socket::NameTypeSupport;
+use tokio::task::JoinHandle;
+use tokio::time::{sleep, timeout};
+use zentime_rs_timer::pomodoro_timer::ViewState;
+
+use crate::config::{Config, ConfigLoader};
+use crate::history::History;
+use crate::ipc::{
+    handshake, is_socket_file, ClientToServerMsg, Connection, ConnectionReader, ConnectionWriter,
+    IpcReader, IpcWriter, Role, ServerAddress, ServerToClientMsg, Snapshot,
+};
+use crate::server::mock::Scenario;
+use crate::server::{listen, SocketListener};
+
+/// Time a [TestClient] waits for a message, before the test is considered to have failed
+const RECV_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Time a [TestServer] has to bind its socket
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Time a [TestServer] has to shut down, once it has been told to
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+static ID_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Advances the clock of all timers by the given duration (see [zentime_rs_timer::clock]).
+/// The server picks up the new time on its next tick.
+pub fn advance_clock(duration: Duration) {
+    zentime_rs_timer::clock::advance(duration);
+}
+
+/// Zentime server running on a temporary socket inside the current tokio runtime.
+/// The server is stopped when this is dropped.
+#[derive(Debug)]
+pub struct TestServer {
+    socket_name: String,
+    team_address: Option<String>,
+    history: History,
+    plan_path: PathBuf,
+    state_path: PathBuf,
+    task: JoinHandle<anyhow::Result<()>>,
+}
+
+impl TestServer {
+    /// Starts a server with the given config and waits until it accepts connections.
+    /// Sessions are recorded into a temporary history file (see [TestServer::history]).
+    pub async fn start(config: Config) -> anyhow::Result<Self> {
+        let fixed_config = config.clone();
+        Self::spawn(config, Arc::new(move || Ok(fixed_config.clone())), None).await
+    }
+
+    /// Starts a server, which resumes the timer the given server has left in its state file
+    /// (e.g. after it has crashed)
+    pub async fn start_resuming(mut config: Config, previous: &TestServer) -> anyhow::Result<Self> {
+        config.state.resume = true;
+        config.state.path = previous.state_path.to_string_lossy().into();
+
+        let fixed_config = config.clone();
+        Self::spawn(config, Arc::new(move || Ok(fixed_config.clone())), None).await
+    }
+
+    /// Starts a server, which calls the given loader instead of reading a config file, whenever
+    /// it is asked to reload its config
+    pub async fn start_reloadable(
+        config: Config,
+        config_loader: ConfigLoader,
+    ) -> anyhow::Result<Self> {
+        Self::spawn(config, config_loader, None).await
+    }
+
+    /// Starts a mock server, which plays back the given scenario instead of running a timer
+    pub async fn start_mock(config: Config, scenario: Scenario) -> anyhow::Result<Self> {
+        let fixed_config = config.clone();
+        Self::spawn(
+            config,
+            Arc::new(move || Ok(fixed_config.clone())),
+            Some(scenario),
+        )
+        .await
+    }
+
+    /// Starts a server on a socket file, which has been bound beforehand - like the socket
+    /// systemd passes to a socket activated server
+    #[cfg(target_os = "linux")]
+    pub async fn start_socket_activated(config: Config) -> anyhow::Result<Self> {
+        let socket_name: String = std::env::temp_dir()
+            .join(format!("{}.sock", unique_id()))
+            .to_string_lossy()
+            .into();
+        let listener = std::os::unix::net::UnixListener::bind(&socket_name)
+            .context("Could not bind test socket")?;
+        let listener = SocketListener::activated(listener)?;
+
+        let fixed_config = config.clone();
+        Self::spawn_on(
+            config,
+            Arc::new(move || Ok(fixed_config.clone())),
+            None,
+            Some((socket_name, listener)),
+        )
+        .await
+    }
+
+    async fn spawn(
+        config: Config,
+        config_loader: ConfigLoader,
+        scenario: Option<Scenario>,
+    ) -> anyhow::Result<Self> {
+        Self::spawn_on(config, config_loader, scenario, None).await
+    }
+
+    /// Spawns a server on the given socket, or on a temporary one which the server binds itself
+    async fn spawn_on(
+        mut config: Config,
+        config_loader: ConfigLoader,
+        scenario: Option<Scenario>,
+        socket: Option<(String, SocketListener)>,
+    ) -> anyhow::Result<Self> {
+        let (socket_name, listener) = match socket {
+            Some((socket_name, listener)) => (socket_name, Some(listener)),
+            None => (temporary_socket_name(), None),
+        };
+
+        let history_path = std::env::temp_dir().join(format!("{}.history", unique_id()));
+        config.history.path = Some(history_path.to_string_lossy().into());
+        let history = History::open(&config.history)?;
+
+        let plan_path = std::env::temp_dir().join(format!("{}.plan", unique_id()));
+        config.plan.path = plan_path.to_string_lossy().into();
+
+        // A resuming server continues from a copy, which belongs to it alone
+        let state_path = std::env::temp_dir().join(format!("{}.state", unique_id()));
+        if config.state.resume {
+            std::fs::copy(&config.state.path, &state_path).context("Could not copy state file")?;
+        }
+        config.state.path = state_path.to_string_lossy().into();
+        let team_address = config.team.listen.clone();
+
+        let server_socket_name = socket_name.clone();
+        let task = tokio::spawn(async move {
+            let listener = match listener {
+                Some(listener) => listener,
+                None => SocketListener::bind(&server_socket_name).await?,
+            };
+
+            // Signals are left to the test runner
+            listen(config, config_loader, listener, scenario, false).await
+        });
+
+        let server = Self {
+            socket_name,
+            team_address,
+            history,
+            plan_path,
+            state_path,
+            task,
+        };
+
+        timeout(STARTUP_TIMEOUT, async {
+            while LocalSocketStream::connect(server.socket_name.as_str())
+                .await
+                .is_err()
+            {
+                if server.task.is_finished() {
+                    bail!("Server terminated during startup");
+                }
+
+                sleep(Duration::from_millis(10)).await;
+            }
+
+            Ok(())
+        })
+        .await
+        .context("Server did not start in time")??;
+
+        Ok(server)
+    }
+
+    /// Name of the socket the server is listening on
+    pub fn socket_name(&self) -> &str {
+        &self.socket_name
+    }
+
+    /// History the server records sessions into
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+
+    /// File the server keeps the plan of the day in
+    pub fn plan_path(&self) -> &Path {
+        &self.plan_path
+    }
+
+    /// File the server keeps the state of its timer in
+    pub fn state_path(&self) -> &Path {
+        &self.state_path
+    }
+
+    /// Waits until the server has shut down (e.g. after a client has sent
+    /// [ClientToServerMsg::Quit]) and returns its result
+    pub async fn stopped(&mut self) -> anyhow::Result<()> {
+        timeout(SHUTDOWN_TIMEOUT, &mut self.task)
+            .await
+            .context("Server did not shut down in time")?
+            .context("Server task has panicked")?
+    }
+
+    /// Connects to the server, e.g. to test a [crate::client_api::ZentimeClient] against it
+    pub async fn connection(&self) -> anyhow::Result<Connection> {
+        let connection = LocalSocketStream::connect(self.socket_name.as_str())
+            .await
+            .context("Could not connect to test server")?;
+        let (reader, writer) = connection.into_split();
+        let mut reader = IpcReader::new(BufReader::new(Box::new(reader) as ConnectionReader));
+        let mut writer = IpcWriter::new(Box::new(writer) as ConnectionWriter);
+        let server = handshake(&mut reader, &mut writer).await?;
+
+        Ok(Connection {
+            reader,
+            writer,
+            role: Role::Owner,
+            server,
+        })
+    }
+
+    /// Connects a new client to the server and syncs it, so that it receives the current state
+    pub async fn connect(&self) -> anyhow::Result<TestClient> {
+        let Connection {
+            reader,
+            writer,
+            role,
+            ..
+        } = self.connection().await?;
+
+        let mut client = TestClient {
+            reader,
+            writer,
+            role,
+        };
+
+        client.send(ClientToServerMsg::Sync).await?;
+
+        Ok(client)
+    }
+
+    /// Joins the team session of the server (see `team` inside the [Config]) with the given
+    /// token and syncs the client
+    pub async fn join(&self, token: &str) -> anyhow::Result<TestClient> {
+        let address = ServerAddress::Team {
+            address: self
+                .team_address
+                .clone()
+                .context("Team mode has not been configured")?,
+            token: token.to_string(),
+        };
+
+        // The team listener is started in the background and might not be ready yet
+        let Connection {
+            reader,
+            writer,
+            role,
+            ..
+        } = timeout(STARTUP_TIMEOUT, async {
+            loop {
+                match address.connect().await {
+                    Ok(connection) => return Ok(connection),
+                    Err(error) if is_connection_refused(&error) => {
+                        sleep(Duration::from_millis(10)).await
+                    }
+                    Err(error) => return Err(error),
+                }
+            }
+        })
+        .await
+        .context("Team listener did not start in time")??;
+
+        let mut client = TestClient {
+            reader,
+            writer,
+            role,
+        };
+
+        client.send(ClientToServerMsg::Sync).await?;
+
+        Ok(client)
+    }
+}
+
+/// Denotes if the error has been caused by a server which is not listening (yet)
+fn is_connection_refused(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        cause
+            .downcast_ref::<std::io::Error>()
+            .is_some_and(|error| error.kind() == std::io::ErrorKind::ConnectionRefused)
+    })
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        self.task.abort();
+
+        if is_socket_file(&self.socket_name) {
+            std::fs::remove_file(&self.socket_name).ok();
+        }
+
+        std::fs::remove_file(self.history.path()).ok();
+        std::fs::remove_file(&self.plan_path).ok();
+        std::fs::remove_file(&self.state_path).ok();
+    }
+}
+
+/// Client of a [TestServer], which sends scripted messages and records broadcasts
+pub struct TestClient {
+    reader: IpcReader<BufReader<ConnectionReader>>,
+    writer: IpcWriter<ConnectionWriter>,
+    role: Role,
+}
+
+impl std::fmt::Debug for TestClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TestClient")
+            .field("role", &self.role)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TestClient {
+    /// Role the server has granted this client
+    pub fn role(&self) -> Role {
+        self.role
+    }
+
+    /// Sends a single message to the server
+    pub async fn send(&mut self, msg: ClientToServerMsg) -> anyhow::Result<()> {
+        self.writer.send(&msg).await
+    }
+
+    /// Sends all messages of a script to the server in order
+    pub async fn send_all(
+        &mut self,
+        script: impl IntoIterator<Item = ClientToServerMsg>,
+    ) -> anyhow::Result<()> {
+        for msg in script {
+            self.send(msg).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Receives the next message. Fails if the server does not send anything in time.
+    pub async fn recv(&mut self) -> anyhow::Result<ServerToClientMsg> {
+        timeout(RECV_TIMEOUT, self.reader.recv())
+            .await
+            .context("Timed out waiting for a message from the server")?
+    }
+
+    /// Receives messages until one matches the given predicate.
+    /// Returns all received messages (including the matching one), so that tests can assert
+    /// on the whole broadcast sequence.
+    pub async fn recv_until(
+        &mut self,
+        predicate: impl Fn(&ServerToClientMsg) -> bool,
+    ) -> anyhow::Result<Vec<ServerToClientMsg>> {
+        let mut received = vec![];
+
+        loop {
+            let msg = self.recv().await?;
+            let is_match = predicate(&msg);
+            received.push(msg);
+
+            if is_match {
+                return Ok(received);
+            }
+        }
+    }
+
+    /// Receives messages until a timer state matching the given predicate is broadcast (or
+    /// sent as snapshot)
+    pub async fn recv_state_matching(
+        &mut self,
+        predicate: impl Fn(&ViewState) -> bool,
+    ) -> anyhow::Result<ViewState> {
+        let received = self
+            .recv_until(|msg| match msg {
+                ServerToClientMsg::Timer(state)
+                | ServerToClientMsg::Snapshot(Snapshot { state, .. }) => predicate(state),
+                _ => false,
+            })
+            .await?;
+
+        match received.into_iter().last() {
+            Some(
+                ServerToClientMsg::Timer(state)
+                | ServerToClientMsg::Snapshot(Snapshot { state, .. }),
+            ) => Ok(state),
+            _ => unreachable!("recv_until only returns after a matching timer state"),
+        }
+    }
+}
+
+/// Creates an identifier which is unique for this process
+fn unique_id() -> String {
+    format!(
+        "zentime-test-{}-{}",
+        process::id(),
+        ID_COUNTER.fetch_add(1, Ordering::SeqCst)
+    )
+}
+
+/// Creates a socket name which is unique for this process
+fn temporary_socket_name() -> String {
+    let id = format!("{}.sock", unique_id());
+
+    match NameTypeSupport::query() {
+        NameTypeSupport::OnlyPaths => std::env::temp_dir().join(id).to_string_lossy().into(),
+        NameTypeSupport::OnlyNamespaced | NameTypeSupport::Both => format!("@{}", id),
+    }
+}
@@ -0,0 +1,192 @@
+//! Conversion between points in time and the local wall clock time, which features based on the
+//! time of day (e.g. schedules or statistics per day) are evaluated in.
+//!
+//! Local times are resolved according to the time zone rules at that date, so that daylight
+//! saving time transitions are handled correctly:
+//! * a local time which occurs twice (when the clock is turned back) resolves to its first
+//!   occurrence
+//! * a local time which is skipped (when the clock is turned forward) resolves to the point in
+//!   time at which the clock would have shown it without the transition (e.g. 02:30 becomes
+//!   03:30)
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, LocalResult, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use zentime_rs_timer::clock;
+
+use crate::config::ClockConfig;
+
+/// Local times inside a skipped range are searched for this far into the past, to find the
+/// offset which has been in effect before the transition
+const MAX_TRANSITION_GAP: chrono::Duration = chrono::Duration::hours(24);
+
+/// Steps in which the offset before a skipped range is searched for
+const TRANSITION_SEARCH_STEP: chrono::Duration = chrono::Duration::minutes(15);
+
+/// Time zone of the wall clock
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WallClock {
+    /// Time zone of the operating system
+    #[default]
+    System,
+
+    /// Explicitly configured time zone (e.g. while traveling)
+    Zone(Tz),
+}
+
+impl WallClock {
+    /// Wall clock in the configured time zone (or the one of the operating system)
+    pub fn from_config(config: &ClockConfig) -> Self {
+        config.time_zone.map_or(Self::System, Self::Zone)
+    }
+
+    /// Current local time
+    pub fn now(&self) -> NaiveDateTime {
+        self.local_time(clock::system_now())
+    }
+
+    /// Current local date
+    pub fn today(&self) -> NaiveDate {
+        self.now().date()
+    }
+
+    /// Local time at the given point in time
+    pub fn local_time(&self, time: SystemTime) -> NaiveDateTime {
+        let utc = DateTime::<Utc>::from(time);
+
+        match self {
+            Self::System => utc.with_timezone(&chrono::Local).naive_local(),
+            Self::Zone(zone) => utc.with_timezone(zone).naive_local(),
+        }
+    }
+
+    /// Point in time at which the wall clock shows the given local time (see module
+    /// documentation for times around daylight saving time transitions)
+    pub fn system_time(&self, local: NaiveDateTime) -> SystemTime {
+        let utc = match self {
+            Self::System => resolve(&chrono::Local, local),
+            Self::Zone(zone) => resolve(zone, local),
+        };
+
+        let seconds = utc.timestamp();
+        let nanos = Duration::from_nanos(u64::from(utc.timestamp_subsec_nanos()));
+
+        match u64::try_from(seconds) {
+            Ok(seconds) => UNIX_EPOCH + Duration::from_secs(seconds) + nanos,
+            Err(_) => UNIX_EPOCH - Duration::from_secs(seconds.unsigned_abs()) + nanos,
+        }
+    }
+
+    /// Point in time at which the given local date starts.
+    /// This is not necessarily midnight, because some time zones skip midnight when daylight
+    /// saving time starts.
+    pub fn start_of_day(&self, date: NaiveDate) -> SystemTime {
+        self.system_time(date.and_time(chrono::NaiveTime::MIN))
+    }
+}
+
+/// Resolves a local time inside the given time zone (see module documentation)
+fn resolve<Z: TimeZone>(zone: &Z, local: NaiveDateTime) -> DateTime<Utc> {
+    match zone.from_local_datetime(&local) {
+        LocalResult::Single(time) | LocalResult::Ambiguous(time, _) => time.with_timezone(&Utc),
+        LocalResult::None => {
+            let mut before = local - TRANSITION_SEARCH_STEP;
+
+            while local - before <= MAX_TRANSITION_GAP {
+                if let Some(time) = zone.from_local_datetime(&before).earliest() {
+                    let offset = time.naive_local() - time.naive_utc();
+                    return DateTime::from_naive_utc_and_offset(local - offset, Utc);
+                }
+
+                before -= TRANSITION_SEARCH_STEP;
+            }
+
+            // Not a valid time zone transition - the time is treated as UTC instead
+            DateTime::from_naive_utc_and_offset(local, Utc)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use chrono_tz::{America, Europe};
+
+    use super::*;
+
+    fn local(date: (i32, u32, u32), hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(date.0, date.1, date.2)
+            .and_then(|date| date.and_hms_opt(hour, minute, 0))
+            .unwrap()
+    }
+
+    fn utc(date: (i32, u32, u32), hour: u32, minute: u32) -> SystemTime {
+        SystemTime::from(local(date, hour, minute).and_utc())
+    }
+
+    #[test]
+    fn resolves_unambiguous_local_times() {
+        let clock = WallClock::Zone(Europe::Berlin);
+
+        assert_eq!(
+            clock.system_time(local((2024, 1, 15), 9, 0)),
+            utc((2024, 1, 15), 8, 0)
+        );
+        assert_eq!(
+            clock.local_time(utc((2024, 7, 15), 7, 0)),
+            local((2024, 7, 15), 9, 0)
+        );
+    }
+
+    #[test]
+    fn moves_skipped_local_times_past_the_gap() {
+        // Berlin turns the clock from 02:00 CET to 03:00 CEST on 2024-03-31
+        let clock = WallClock::Zone(Europe::Berlin);
+
+        assert_eq!(
+            clock.system_time(local((2024, 3, 31), 2, 30)),
+            utc((2024, 3, 31), 1, 30)
+        );
+        assert_eq!(
+            clock.local_time(clock.system_time(local((2024, 3, 31), 2, 30))),
+            local((2024, 3, 31), 3, 30)
+        );
+        assert_eq!(
+            clock.system_time(local((2024, 3, 31), 3, 0)),
+            utc((2024, 3, 31), 1, 0)
+        );
+    }
+
+    #[test]
+    fn resolves_repeated_local_times_to_their_first_occurrence() {
+        // Berlin turns the clock from 03:00 CEST back to 02:00 CET on 2024-10-27
+        let clock = WallClock::Zone(Europe::Berlin);
+
+        assert_eq!(
+            clock.system_time(local((2024, 10, 27), 2, 30)),
+            utc((2024, 10, 27), 0, 30)
+        );
+        assert_eq!(
+            clock.local_time(utc((2024, 10, 27), 0, 30)),
+            local((2024, 10, 27), 2, 30)
+        );
+        assert_eq!(
+            clock.local_time(utc((2024, 10, 27), 1, 30)),
+            local((2024, 10, 27), 2, 30)
+        );
+    }
+
+    #[test]
+    fn starts_days_which_skip_midnight_at_the_first_valid_time() {
+        // Santiago turns the clock from 00:00 to 01:00 on 2024-09-08
+        let clock = WallClock::Zone(America::Santiago);
+        let date = NaiveDate::from_ymd_opt(2024, 9, 8).unwrap();
+
+        assert_eq!(clock.start_of_day(date), utc((2024, 9, 8), 4, 0));
+        assert_eq!(
+            clock.local_time(clock.start_of_day(date)),
+            local((2024, 9, 8), 1, 0)
+        );
+    }
+}
@@ -0,0 +1,2257 @@
+//! End-to-end tests of the client/server IPC (run with `--features test-support`)
+
+use std::sync::Arc;
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+use chrono::{Datelike, NaiveTime};
+use figment::providers::{Format, Serialized, Toml};
+use figment::Figment;
+
+use futures::{AsyncBufReadExt, AsyncWriteExt as _, StreamExt};
+use interprocess::local_socket::tokio::LocalSocketStream;
+#[cfg(feature = "http")]
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+#[cfg(feature = "http")]
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+#[cfg(feature = "http")]
+use tokio_tungstenite::tungstenite::Message;
+use zentime_rs::client::interpolation::Interpolation;
+use zentime_rs::client::timer_event::TimerEvent;
+use zentime_rs::client_api::ZentimeClient;
+#[cfg(feature = "notifications")]
+use zentime_rs::config::BreakNudgeConfig;
+use zentime_rs::config::{
+    AlignmentConfig, ClockConfig, Config, ConnectionConfig, ContextConfig, ContextSource,
+    DndBackend, DndConfig, GoalConfig, HooksConfig, IdleConfig, NotificationConfig,
+    NotificationFailurePolicy, PauseConfig, PauseTimeoutAction, TeamConfig, TimerMode,
+    TimerProfile,
+};
+#[cfg(feature = "http")]
+use zentime_rs::config::{ApiConfig, HttpConfig};
+use zentime_rs::history::{BreakCompliance, ContractRecord, SessionKind, SessionOutcome};
+use zentime_rs::ipc::{
+    ClientPreferences, ClientToServerMsg, ErrorCode, IpcReader, IpcWriter, Role, ServerInfo,
+    ServerToClientMsg, Snapshot, TimePrecision, Transport, UpdateMode, MAIN_TIMER,
+    PROTOCOL_VERSION,
+};
+use zentime_rs::plan::Plan;
+use zentime_rs::server::mock::{Scenario, ScenarioStep};
+use zentime_rs::test_support::{advance_clock, TestServer};
+use zentime_rs::wall_clock::WallClock;
+use zentime_rs_timer::clock;
+use zentime_rs_timer::config::PomodoroTimerConfig;
+use zentime_rs_timer::duration::HumanDuration;
+use zentime_rs_timer::pomodoro_timer::{DailyStats, ViewState};
+use zentime_rs_timer::timer::TimeJumpPolicy;
+use zentime_rs_timer::util::time_to_seconds;
+
+/// Address on localhost with a port, which is currently not in use
+fn free_address() -> anyhow::Result<String> {
+    let port = std::net::TcpListener::bind("127.0.0.1:0")?
+        .local_addr()?
+        .port();
+    Ok(format!("127.0.0.1:{}", port))
+}
+
+/// Sends a GET request and returns the whole response
+#[cfg(feature = "http")]
+async fn http_get(address: &str, path: &str) -> anyhow::Result<String> {
+    http_request(address, "GET", path).await
+}
+
+/// Sends a request without body and returns the whole response.
+/// Retries to connect, because the HTTP server is started in the background.
+#[cfg(feature = "http")]
+async fn http_request(address: &str, method: &str, path: &str) -> anyhow::Result<String> {
+    http_request_with_headers(address, method, path, "Host: localhost\r\n").await
+}
+
+/// Sends a request to the JSON API, which carries the given token
+#[cfg(feature = "http")]
+async fn api_request(
+    address: &str,
+    method: &str,
+    path: &str,
+    token: &str,
+) -> anyhow::Result<String> {
+    let headers = format!("Host: localhost\r\nAuthorization: Bearer {}\r\n", token);
+
+    http_request_with_headers(address, method, path, &headers).await
+}
+
+/// Sends a request with the given headers (each one terminated by CRLF) and returns the whole
+/// response
+#[cfg(feature = "http")]
+async fn http_request_with_headers(
+    address: &str,
+    method: &str,
+    path: &str,
+    headers: &str,
+) -> anyhow::Result<String> {
+    let mut stream = loop {
+        match TcpStream::connect(address).await {
+            Ok(stream) => break stream,
+            Err(_) => tokio::time::sleep(Duration::from_millis(10)).await,
+        }
+    };
+
+    let request = format!("{} {} HTTP/1.1\r\n{}\r\n", method, path, headers);
+    stream.write_all(request.as_bytes()).await?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await?;
+
+    Ok(response)
+}
+
+fn test_config() -> Config {
+    Config {
+        notifications: NotificationConfig {
+            enable_bell: false,
+            show_notification: false,
+            ..NotificationConfig::default()
+        },
+        ..Config::default()
+    }
+}
+
+#[tokio::test]
+async fn answers_ping() -> anyhow::Result<()> {
+    let server = TestServer::start(test_config()).await?;
+    let mut client = server.connect().await?;
+
+    client.send(ClientToServerMsg::Ping).await?;
+    let received = client
+        .recv_until(|msg| matches!(msg, ServerToClientMsg::Pong))
+        .await?;
+
+    assert!(matches!(received.last(), Some(ServerToClientMsg::Pong)));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn answers_plain_text_commands() -> anyhow::Result<()> {
+    let server = TestServer::start(test_config()).await?;
+    let (reader, mut writer) = LocalSocketStream::connect(server.socket_name())
+        .await?
+        .into_split();
+    let mut answers = futures::io::BufReader::new(reader).lines();
+
+    writer.write_all(b"STATUS\n").await?;
+    assert_eq!(
+        answers.next().await.transpose()?.as_deref(),
+        Some("25:00 Focus (paused)")
+    );
+
+    writer.write_all(b"toggle\nskip\nSTATUS\n").await?;
+    assert_eq!(answers.next().await.transpose()?.as_deref(), Some("OK"));
+    assert_eq!(answers.next().await.transpose()?.as_deref(), Some("OK"));
+    assert_eq!(
+        answers.next().await.transpose()?.as_deref(),
+        Some("05:00 Break (paused)")
+    );
+
+    writer.write_all(b"PLAY\n").await?;
+    let answer = answers.next().await.transpose()?.unwrap_or_default();
+    assert!(answer.starts_with("ERROR"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn answers_commands_shorter_than_a_frame_header() -> anyhow::Result<()> {
+    let server = TestServer::start(test_config()).await?;
+    let (reader, mut writer) = LocalSocketStream::connect(server.socket_name())
+        .await?
+        .into_split();
+    let mut answers = futures::io::BufReader::new(reader).lines();
+
+    writer.write_all(b"?\n").await?;
+    let answer = timeout(Duration::from_secs(2), answers.next()).await?;
+    assert!(answer.transpose()?.unwrap_or_default().starts_with("ERROR"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn broadcasts_actions_to_all_clients() -> anyhow::Result<()> {
+    let server = TestServer::start(test_config()).await?;
+    let mut first = server.connect().await?;
+    let mut second = server.connect().await?;
+
+    let initial_state = first.recv_state_matching(|_| true).await?;
+    assert!(initial_state.is_paused);
+
+    first.send(ClientToServerMsg::PlayPause).await?;
+
+    let state = second.recv_state_matching(|state| !state.is_paused).await?;
+    assert_eq!(state.round, 1);
+    assert!(!state.is_break);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn answers_sync_with_a_snapshot() -> anyhow::Result<()> {
+    let server = TestServer::start(Config {
+        timers: PomodoroTimerConfig {
+            timer: 600,
+            postpone_limit: 2,
+            ..PomodoroTimerConfig::default()
+        },
+        ..test_config()
+    })
+    .await?;
+    let mut first = server.connect().await?;
+
+    // Clients which sync before the timer has produced its first state receive a broadcast
+    first.recv_state_matching(|_| true).await?;
+    first.send(ClientToServerMsg::Skip).await?;
+    first.recv_state_matching(|state| state.is_break).await?;
+
+    // The break waits to be started, therefore nothing is broadcast in the meantime
+    let mut second = server.connect().await?;
+
+    match second.recv().await? {
+        ServerToClientMsg::Snapshot(Snapshot { state, timers }) => {
+            assert!(state.is_break);
+            assert!(state.is_paused);
+            assert_eq!(timers.timer, 600);
+            assert_eq!(timers.postpone_limit, 2);
+        }
+        msg => panic!("Expected a snapshot, but received {:?}", msg),
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn skips_to_break() -> anyhow::Result<()> {
+    let server = TestServer::start(test_config()).await?;
+    let mut client = server.connect().await?;
+
+    client
+        .send_all([ClientToServerMsg::PlayPause, ClientToServerMsg::Skip])
+        .await?;
+
+    let state = client.recv_state_matching(|state| state.is_break).await?;
+    assert_eq!(state.round, 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn counts_the_intervals_of_today() -> anyhow::Result<()> {
+    let config = test_config();
+    let interval = Duration::from_secs(config.timers.timer);
+
+    let server = TestServer::start(config).await?;
+    let mut client = server.connect().await?;
+
+    client.send(ClientToServerMsg::PlayPause).await?;
+    let state = client.recv_state_matching(|state| !state.is_paused).await?;
+    assert_eq!(state.today, Some(DailyStats::default()));
+
+    advance_clock(interval);
+
+    let state = client.recv_state_matching(|state| state.is_break).await?;
+    assert_eq!(
+        state.today,
+        Some(DailyStats {
+            intervals: 1,
+            focused_minutes: u16::try_from(interval.as_secs() / 60)?,
+            goal: None,
+        })
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn notifies_once_the_daily_goal_has_been_reached() -> anyhow::Result<()> {
+    let log = std::env::temp_dir().join(format!("zentime-{}-daily-goal.log", std::process::id()));
+    let config = Config {
+        goals: GoalConfig {
+            daily: Some(1),
+            ..GoalConfig::default()
+        },
+        notifications: NotificationConfig {
+            hooks: vec![format!("echo \"$ZENTIME_EVENT\" >> '{}'", log.display())],
+            ..test_config().notifications
+        },
+        ..test_config()
+    };
+    let interval = Duration::from_secs(config.timers.timer);
+
+    let server = TestServer::start(config).await?;
+    let mut client = server.connect().await?;
+
+    client.send(ClientToServerMsg::PlayPause).await?;
+    let state = client.recv_state_matching(|state| !state.is_paused).await?;
+    assert!(state.today.is_some_and(|today| !today.has_reached_goal()));
+
+    advance_clock(interval);
+
+    let state = client.recv_state_matching(|state| state.is_break).await?;
+    assert_eq!(state.today.and_then(|today| today.goal), Some(1));
+    assert!(state.today.is_some_and(|today| today.has_reached_goal()));
+
+    let mut events = String::new();
+
+    for _ in 0..100 {
+        events = std::fs::read_to_string(&log).unwrap_or_default();
+
+        if events.lines().count() == 2 {
+            break;
+        }
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    std::fs::remove_file(&log).ok();
+
+    let mut events: Vec<_> = events.lines().collect();
+    events.sort();
+    assert_eq!(events, ["daily_goal", "interval_end"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn starts_the_long_break_ahead_of_schedule() -> anyhow::Result<()> {
+    let server = TestServer::start(test_config()).await?;
+    let mut client = server.connect().await?;
+
+    // Second interval
+    client
+        .send_all([ClientToServerMsg::Skip, ClientToServerMsg::Skip])
+        .await?;
+    client.recv_state_matching(|state| state.round == 2).await?;
+
+    client.send(ClientToServerMsg::LongBreakNow).await?;
+    let state = client.recv_state_matching(|state| state.is_break).await?;
+    assert_eq!(state.round, 4);
+    assert_eq!(state.time, "15:00");
+
+    // A full cycle follows the long break
+    client.send(ClientToServerMsg::Skip).await?;
+    let state = client.recv_state_matching(|state| !state.is_break).await?;
+    assert_eq!(state.round, 5);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn adjusts_the_remaining_time() -> anyhow::Result<()> {
+    let server = TestServer::start(test_config()).await?;
+    let mut client = server.connect().await?;
+
+    client.send(ClientToServerMsg::AdjustTime(300)).await?;
+    let state = client
+        .recv_state_matching(|state| state.time == "30:00")
+        .await?;
+    assert!(!state.is_break);
+
+    client.send(ClientToServerMsg::AdjustTime(-600)).await?;
+    client
+        .recv_state_matching(|state| state.time == "20:00")
+        .await?;
+
+    // Removing more than the remaining time ends the interval once the timer runs
+    client
+        .send_all([
+            ClientToServerMsg::AdjustTime(-3600),
+            ClientToServerMsg::PlayPause,
+        ])
+        .await?;
+    let state = client.recv_state_matching(|state| state.is_break).await?;
+    assert_eq!(state.round, 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn goes_back_to_the_previous_phase() -> anyhow::Result<()> {
+    let server = TestServer::start(test_config()).await?;
+    let mut client = server.connect().await?;
+
+    // Nothing precedes the first interval
+    client.send(ClientToServerMsg::Previous).await?;
+    let received = client
+        .recv_until(|msg| matches!(msg, ServerToClientMsg::Error { .. }))
+        .await?;
+    assert!(matches!(
+        received.last(),
+        Some(ServerToClientMsg::Error {
+            code: ErrorCode::ActionRejected,
+            ..
+        })
+    ));
+
+    // Which phase precedes the current one is up to the transition policy of the timer crate
+    client.send(ClientToServerMsg::Skip).await?;
+    client.recv_state_matching(|state| state.is_break).await?;
+
+    client.send(ClientToServerMsg::Previous).await?;
+    let state = client.recv_state_matching(|state| !state.is_break).await?;
+    assert_eq!(state.round, 1);
+
+    // Restarting brings back the full interval of the same round
+    client.send(ClientToServerMsg::AdjustTime(-600)).await?;
+    client
+        .recv_state_matching(|state| state.time == "15:00")
+        .await?;
+
+    client.send(ClientToServerMsg::RestartCurrent).await?;
+    let state = client
+        .recv_state_matching(|state| state.time == "25:00")
+        .await?;
+    assert!(!state.is_break);
+    assert_eq!(state.round, 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn tailors_states_to_client_preferences() -> anyhow::Result<()> {
+    let server = TestServer::start(test_config()).await?;
+    let mut client = server.connect().await?;
+
+    client
+        .send_all([
+            ClientToServerMsg::Hello(ClientPreferences {
+                updates: UpdateMode::Events,
+                precision: TimePrecision::Minutes,
+                ..ClientPreferences::default()
+            }),
+            ClientToServerMsg::Sync,
+            ClientToServerMsg::PlayPause,
+        ])
+        .await?;
+
+    let state = client.recv_state_matching(|state| !state.is_paused).await?;
+    assert_eq!(state.time, "25m");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn interpolates_the_countdown_between_updates() -> anyhow::Result<()> {
+    let server = TestServer::start(test_config()).await?;
+    let mut client = server.connect().await?;
+
+    client
+        .send_all([
+            ClientToServerMsg::Hello(ClientPreferences {
+                resync_seconds: 30,
+                ..ClientPreferences::default()
+            }),
+            ClientToServerMsg::Sync,
+            ClientToServerMsg::PlayPause,
+        ])
+        .await?;
+
+    let state = client.recv_state_matching(|state| !state.is_paused).await?;
+    let progress = state.progress.expect("running states carry their progress");
+    assert_eq!(progress.total_millis, 25 * 60 * 1000);
+
+    let received_at = Instant::now();
+    let mut interpolation = Interpolation::new();
+    interpolation.receive(&state, received_at);
+
+    let later = received_at + progress.remaining() - Duration::from_secs(24 * 60 + 58);
+    let interpolated = interpolation.tick(later).expect("the time has changed");
+    assert_eq!(interpolated.time, "24:58");
+    assert!(interpolation.tick(later).is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn labels_the_current_session() -> anyhow::Result<()> {
+    let server = TestServer::start(test_config()).await?;
+    let mut client = server.connect().await?;
+
+    client
+        .send(ClientToServerMsg::SetTask {
+            task: Some(" write docs ".to_string()),
+        })
+        .await?;
+
+    let state = client
+        .recv_state_matching(|state| state.task.is_some())
+        .await?;
+    assert_eq!(state.task.as_deref(), Some("write docs"));
+
+    client
+        .send_all([
+            ClientToServerMsg::Skip,
+            ClientToServerMsg::SetTask { task: None },
+        ])
+        .await?;
+    client
+        .recv_state_matching(|state| state.is_break && state.task.is_none())
+        .await?;
+
+    // The label is kept for the following sessions, until it is removed
+    client.send(ClientToServerMsg::Skip).await?;
+    client
+        .recv_state_matching(|state| !state.is_break && state.round == 2)
+        .await?;
+
+    // Records are written on a blocking task
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let tasks: Vec<_> = server
+        .history()
+        .records()?
+        .into_iter()
+        .map(|record| record.task)
+        .collect();
+    assert_eq!(tasks, [Some("write docs".to_string()), None]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn switches_timer_profiles() -> anyhow::Result<()> {
+    let deep_work = TimerProfile {
+        timer: Some(HumanDuration::from_secs(50 * 60)),
+        minor_break: Some(HumanDuration::from_secs(10 * 60)),
+        ..TimerProfile::default()
+    };
+    let server = TestServer::start(Config {
+        profiles: [("deep-work".to_string(), deep_work)].into(),
+        profile: Some("deep-work".to_string()),
+        ..test_config()
+    })
+    .await?;
+    let mut client = server.connect().await?;
+
+    client.send(ClientToServerMsg::Sync).await?;
+    let state = client.recv_state_matching(|_| true).await?;
+    assert_eq!(state.profile.as_deref(), Some("deep-work"));
+    assert_eq!(state.time, "50:00");
+
+    // The running interval keeps its length, the break already has the length of the profile
+    client.send(ClientToServerMsg::PlayPause).await?;
+    client.recv_state_matching(|state| !state.is_paused).await?;
+    client
+        .send(ClientToServerMsg::SwitchProfile("default".to_string()))
+        .await?;
+    let state = client
+        .recv_state_matching(|state| state.profile.as_deref() == Some("default"))
+        .await?;
+    assert!(time_to_seconds(&state.time).is_some_and(|seconds| seconds > 25 * 60));
+
+    client.send(ClientToServerMsg::Skip).await?;
+    let state = client.recv_state_matching(|state| state.is_break).await?;
+    assert_eq!(state.time, "05:00");
+
+    client
+        .send(ClientToServerMsg::SwitchProfile("unknown".to_string()))
+        .await?;
+    let received = client
+        .recv_until(|msg| matches!(msg, ServerToClientMsg::Error { .. }))
+        .await?;
+    assert!(matches!(
+        received.last(),
+        Some(ServerToClientMsg::Error {
+            code: ErrorCode::ActionRejected,
+            ..
+        })
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn runs_named_timers_next_to_the_pomodoro_timer() -> anyhow::Result<()> {
+    let server = TestServer::start(test_config()).await?;
+    let mut client = server.connect().await?;
+
+    // A new timer is selected and running right away
+    client
+        .send(ClientToServerMsg::NewTimer {
+            name: "tea".to_string(),
+            seconds: 120,
+        })
+        .await?;
+    let state = client
+        .recv_state_matching(|state| state.selected_timer().is_some())
+        .await?;
+    assert_eq!(state.timers[0].name, "tea");
+    assert!(!state.timers[0].is_paused);
+    assert!(state.is_paused);
+
+    // Actions apply to the selected timer only
+    client.send(ClientToServerMsg::PlayPause).await?;
+    let state = client
+        .recv_state_matching(|state| state.timers.iter().all(|timer| timer.is_paused))
+        .await?;
+    assert!(state.is_paused);
+
+    client
+        .send_all([
+            ClientToServerMsg::SelectTimer(MAIN_TIMER.to_string()),
+            ClientToServerMsg::PlayPause,
+        ])
+        .await?;
+    let state = client.recv_state_matching(|state| !state.is_paused).await?;
+    assert!(state.selected_timer().is_none());
+    assert!(state.timers[0].is_paused);
+
+    // Timers which have run out disappear and the pomodoro timer is selected again
+    client
+        .send_all([
+            ClientToServerMsg::SelectTimer("tea".to_string()),
+            ClientToServerMsg::PlayPause,
+        ])
+        .await?;
+    client
+        .recv_state_matching(|state| state.timers.iter().any(|timer| !timer.is_paused))
+        .await?;
+    advance_clock(Duration::from_secs(121));
+    client
+        .recv_state_matching(|state| state.timers.is_empty())
+        .await?;
+
+    client
+        .send(ClientToServerMsg::SelectTimer("tea".to_string()))
+        .await?;
+    let received = client
+        .recv_until(|msg| matches!(msg, ServerToClientMsg::Error { .. }))
+        .await?;
+    assert!(matches!(
+        received.last(),
+        Some(ServerToClientMsg::Error {
+            code: ErrorCode::ActionRejected,
+            ..
+        })
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn runs_the_countdown_hook_once_a_countdown_is_due() -> anyhow::Result<()> {
+    let log = std::env::temp_dir().join(format!("zentime-{}-countdown.log", std::process::id()));
+    let config = Config {
+        hooks: HooksConfig {
+            on_countdown_end: Some(format!(
+                "echo \"$ZENTIME_EVENT $ZENTIME_REMINDER\" >> '{}'",
+                log.display()
+            )),
+            ..HooksConfig::default()
+        },
+        ..test_config()
+    };
+
+    let server = TestServer::start(config).await?;
+    let mut client = server.connect().await?;
+
+    client
+        .send(ClientToServerMsg::Countdown {
+            seconds: 60,
+            label: Some("standup".to_string()),
+        })
+        .await?;
+    let state = client
+        .recv_state_matching(|state| !state.countdowns.is_empty())
+        .await?;
+    assert_eq!(state.countdowns[0].label.as_deref(), Some("standup"));
+    assert!(state.timers.is_empty());
+    assert!(state.is_paused);
+
+    advance_clock(Duration::from_secs(61));
+    client
+        .recv_state_matching(|state| state.countdowns.is_empty())
+        .await?;
+
+    let mut events = String::new();
+
+    for _ in 0..100 {
+        events = std::fs::read_to_string(&log).unwrap_or_default();
+
+        if !events.is_empty() {
+            break;
+        }
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    std::fs::remove_file(&log).ok();
+    assert_eq!(events.trim(), "countdown_end standup");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn ends_interval_once_the_clock_has_advanced() -> anyhow::Result<()> {
+    let config = test_config();
+    let interval = Duration::from_secs(config.timers.timer);
+
+    let server = TestServer::start(config).await?;
+    let mut client = server.connect().await?;
+
+    client.send(ClientToServerMsg::PlayPause).await?;
+    client.recv_state_matching(|state| !state.is_paused).await?;
+
+    advance_clock(interval);
+
+    let state = client.recv_state_matching(|state| state.is_break).await?;
+    assert_eq!(state.round, 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn pauses_at_the_time_of_a_clock_jump() -> anyhow::Result<()> {
+    let mut config = test_config();
+    config.timers.time_jump_policy = TimeJumpPolicy::Pause;
+
+    let server = TestServer::start(config).await?;
+    let mut client = server.connect().await?;
+
+    client.send(ClientToServerMsg::PlayPause).await?;
+    client.recv_state_matching(|state| !state.is_paused).await?;
+
+    // Simulates a suspend, during which the monotonic clock has kept running
+    advance_clock(Duration::from_secs(10 * 60));
+
+    let state = client.recv_state_matching(|state| state.is_paused).await?;
+    assert!(!state.is_break);
+    // The interval keeps the time it has had right before the jump
+    assert!(
+        matches!(state.time.as_str(), "25:00" | "24:59"),
+        "{}",
+        state.time
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn starts_breaks_and_intervals_automatically() -> anyhow::Result<()> {
+    let config = Config {
+        timers: PomodoroTimerConfig {
+            auto_start_break: true,
+            auto_start_work: true,
+            ..test_config().timers
+        },
+        ..test_config()
+    };
+    let interval = Duration::from_secs(config.timers.timer);
+
+    let server = TestServer::start(config).await?;
+    let mut client = server.connect().await?;
+
+    client.send(ClientToServerMsg::PlayPause).await?;
+    client.recv_state_matching(|state| !state.is_paused).await?;
+
+    advance_clock(interval);
+
+    let state = client.recv_state_matching(|state| state.is_break).await?;
+    assert!(!state.is_paused);
+
+    client.send(ClientToServerMsg::Skip).await?;
+
+    let state = client.recv_state_matching(|state| !state.is_break).await?;
+    assert_eq!(state.round, 2);
+    assert!(!state.is_paused);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn works_through_the_plan() -> anyhow::Result<()> {
+    let config = test_config();
+    let interval = Duration::from_secs(config.timers.timer);
+
+    let server = TestServer::start(config).await?;
+    let mut client = server.connect().await?;
+
+    client
+        .send_all([
+            ClientToServerMsg::AddToPlan {
+                task: "review PRs".to_string(),
+                pomodoros: 1,
+            },
+            ClientToServerMsg::AddToPlan {
+                task: "write docs".to_string(),
+                pomodoros: 2,
+            },
+            ClientToServerMsg::PlayPause,
+        ])
+        .await?;
+    let state = client.recv_state_matching(|state| !state.is_paused).await?;
+    assert_eq!(state.task.as_deref(), Some("review PRs"));
+
+    advance_clock(interval);
+
+    // The next task is worked on as soon as the planned intervals have been completed
+    let state = client.recv_state_matching(|state| state.is_break).await?;
+    assert_eq!(state.task.as_deref(), Some("write docs"));
+
+    let plan = Plan::load(server.plan_path(), WallClock::default().today())?;
+    let progress: Vec<_> = plan.items.iter().map(|item| item.completed).collect();
+    assert_eq!(progress, vec![1, 0]);
+
+    // Explicit labels take precedence over the plan
+    client
+        .send(ClientToServerMsg::SetTask {
+            task: Some("urgent bug".to_string()),
+        })
+        .await?;
+    client
+        .recv_state_matching(|state| state.task.as_deref() == Some("urgent bug"))
+        .await?;
+
+    client
+        .send_all([
+            ClientToServerMsg::SetTask { task: None },
+            ClientToServerMsg::ClearPlan,
+        ])
+        .await?;
+    client
+        .recv_state_matching(|state| state.task.is_none())
+        .await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn records_skipped_breaks() -> anyhow::Result<()> {
+    let server = TestServer::start(test_config()).await?;
+    let mut client = server.connect().await?;
+
+    client
+        .send_all([ClientToServerMsg::Skip, ClientToServerMsg::Skip])
+        .await?;
+    client
+        .recv_state_matching(|state| !state.is_break && state.round == 2)
+        .await?;
+
+    // Records are written on a blocking task
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let records = server.history().records()?;
+    let kinds: Vec<_> = records
+        .iter()
+        .map(|record| (record.kind, record.outcome))
+        .collect();
+
+    assert_eq!(
+        kinds,
+        [
+            (SessionKind::Interval, SessionOutcome::Skipped),
+            (SessionKind::ShortBreak, SessionOutcome::Skipped)
+        ]
+    );
+    assert_eq!(
+        BreakCompliance::from_records(&records).percentage(),
+        Some(0)
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn records_broken_focus_contracts() -> anyhow::Result<()> {
+    let mut config = test_config();
+    config.focus_contract.enabled = true;
+
+    let server = TestServer::start(config).await?;
+    let mut client = server.connect().await?;
+
+    client
+        .send_all([ClientToServerMsg::Skip, ClientToServerMsg::Skip])
+        .await?;
+    client
+        .recv_state_matching(|state| !state.is_break && state.round == 2)
+        .await?;
+
+    // Records are written on a blocking task
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    // Only giving up the interval breaks the contract - skipping a break is up to the user
+    let records = server.history().records()?;
+    let broken: Vec<_> = records
+        .iter()
+        .map(|record| (record.kind, record.broken_contract))
+        .collect();
+
+    assert_eq!(
+        broken,
+        [
+            (SessionKind::Interval, true),
+            (SessionKind::ShortBreak, false)
+        ]
+    );
+    assert_eq!(
+        ContractRecord::from_records(&records),
+        ContractRecord {
+            broken: 1,
+            kept_since_broken: 0
+        }
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn records_pause_reasons() -> anyhow::Result<()> {
+    let server = TestServer::start(test_config()).await?;
+    let mut client = server.connect().await?;
+
+    client.send(ClientToServerMsg::PlayPause).await?;
+    client.recv_state_matching(|state| !state.is_paused).await?;
+
+    // Reasons are only recorded while the timer is paused
+    client
+        .send_all([
+            ClientToServerMsg::PauseReason {
+                reason: "ignored".to_string(),
+            },
+            ClientToServerMsg::PlayPause,
+            ClientToServerMsg::PauseReason {
+                reason: " phone call ".to_string(),
+            },
+            ClientToServerMsg::Skip,
+        ])
+        .await?;
+    client.recv_state_matching(|state| state.is_break).await?;
+
+    // Records are written on a blocking task
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let records = server.history().records()?;
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].pause_reasons, vec!["phone call".to_string()]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn tags_intervals_with_their_context() -> anyhow::Result<()> {
+    let server = TestServer::start(Config {
+        context: ContextConfig {
+            capture: vec![ContextSource::Window],
+            window_command: Some("echo 'main.rs - editor'".to_string()),
+            ..ContextConfig::default()
+        },
+        ..test_config()
+    })
+    .await?;
+    let mut client = server.connect().await?;
+
+    client.send(ClientToServerMsg::PlayPause).await?;
+    client.recv_state_matching(|state| !state.is_paused).await?;
+
+    // The context is captured in the background
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    client
+        .send_all([ClientToServerMsg::Skip, ClientToServerMsg::Skip])
+        .await?;
+    client
+        .recv_state_matching(|state| !state.is_break && state.round == 2)
+        .await?;
+
+    // Records are written on a blocking task
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let records = server.history().records()?;
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].tags, vec!["window:main.rs - editor".to_string()]);
+    assert!(records[1].tags.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn enables_do_not_disturb_while_focusing() -> anyhow::Result<()> {
+    let log = std::env::temp_dir().join(format!("zentime-{}-dnd.log", std::process::id()));
+    let read_switches = || -> Vec<String> {
+        std::fs::read_to_string(&log)
+            .unwrap_or_default()
+            .lines()
+            .map(String::from)
+            .collect()
+    };
+    let wait_for_switches = |count: usize| async move {
+        for _ in 0..200 {
+            if read_switches().len() >= count {
+                break;
+            }
+
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+
+        read_switches()
+    };
+
+    let server = TestServer::start(Config {
+        dnd: DndConfig {
+            enabled: true,
+            backend: DndBackend::Command,
+            enable_command: Some(format!("echo on >> '{}'", log.display())),
+            disable_command: Some(format!("echo off >> '{}'", log.display())),
+        },
+        ..test_config()
+    })
+    .await?;
+    let mut client = server.connect().await?;
+
+    client.send(ClientToServerMsg::PlayPause).await?;
+    client.recv_state_matching(|state| !state.is_paused).await?;
+    assert_eq!(wait_for_switches(1).await, ["on"]);
+
+    client.send(ClientToServerMsg::Skip).await?;
+    client.recv_state_matching(|state| state.is_break).await?;
+    let switches = wait_for_switches(2).await;
+    std::fs::remove_file(&log).ok();
+
+    assert_eq!(switches, ["on", "off"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn pauses_focus_intervals_while_idle() -> anyhow::Result<()> {
+    let server = TestServer::start(Config {
+        idle: IdleConfig {
+            enabled: true,
+            threshold: 60,
+            // Ten minutes without any input
+            command: Some("echo 600000".to_string()),
+            ..IdleConfig::default()
+        },
+        ..test_config()
+    })
+    .await?;
+    let mut client = server.connect().await?;
+
+    client.send(ClientToServerMsg::PlayPause).await?;
+
+    let state = client
+        .recv_state_matching(|state| state.pause_reason.is_some())
+        .await?;
+    assert!(state.is_paused);
+    assert_eq!(state.pause_reason, Some("idle".to_string()));
+
+    // Resuming the interval by hand ends the automatic pause
+    client.send(ClientToServerMsg::PlayPause).await?;
+
+    let state = client.recv_state_matching(|state| !state.is_paused).await?;
+    assert_eq!(state.pause_reason, None);
+
+    Ok(())
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn records_sessions_into_sqlite() -> anyhow::Result<()> {
+    let mut config = test_config();
+    config.history.backend = zentime_rs::config::HistoryBackend::Sqlite;
+
+    let server = TestServer::start(config).await?;
+    let mut client = server.connect().await?;
+
+    client
+        .send_all([
+            ClientToServerMsg::PauseReason {
+                reason: "phone call".to_string(),
+            },
+            ClientToServerMsg::SetTask {
+                task: Some("write docs".to_string()),
+            },
+            ClientToServerMsg::Skip,
+            ClientToServerMsg::Skip,
+        ])
+        .await?;
+    client
+        .recv_state_matching(|state| !state.is_break && state.round == 2)
+        .await?;
+
+    // Records are written on a blocking task
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let records = server.history().records()?;
+    let kinds: Vec<_> = records
+        .iter()
+        .map(|record| (record.kind, record.outcome, record.pause_reasons.clone()))
+        .collect();
+
+    assert_eq!(
+        kinds,
+        [
+            (
+                SessionKind::Interval,
+                SessionOutcome::Skipped,
+                vec!["phone call".to_string()]
+            ),
+            (SessionKind::ShortBreak, SessionOutcome::Skipped, vec![])
+        ]
+    );
+    assert!(records
+        .iter()
+        .all(|record| record.task.as_deref() == Some("write docs")));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn runs_hooks_on_timer_events() -> anyhow::Result<()> {
+    let log = std::env::temp_dir().join(format!("zentime-{}-hooks.log", std::process::id()));
+    let append_event = format!(
+        "echo \"$ZENTIME_EVENT $ZENTIME_PHASE\" >> '{}'",
+        log.display()
+    );
+
+    let server = TestServer::start(Config {
+        hooks: HooksConfig {
+            on_break_start: Some(append_event.clone()),
+            on_pause: Some(append_event.clone()),
+            on_resume: Some(append_event),
+            ..HooksConfig::default()
+        },
+        ..test_config()
+    })
+    .await?;
+    let mut client = server.connect().await?;
+
+    client.send(ClientToServerMsg::PlayPause).await?;
+    client.recv_state_matching(|state| !state.is_paused).await?;
+    client
+        .send_all([ClientToServerMsg::PlayPause, ClientToServerMsg::Skip])
+        .await?;
+    client.recv_state_matching(|state| state.is_break).await?;
+
+    // Hooks are run in the background (and therefore not necessarily in order)
+    let mut events = vec![];
+
+    for _ in 0..100 {
+        events = std::fs::read_to_string(&log)
+            .unwrap_or_default()
+            .lines()
+            .map(String::from)
+            .collect();
+
+        if events.len() >= 3 {
+            break;
+        }
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    std::fs::remove_file(&log).ok();
+    events.sort();
+
+    assert_eq!(events, ["break_start break", "pause focus", "resume focus"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn suggests_what_to_do_during_the_break() -> anyhow::Result<()> {
+    let log = std::env::temp_dir().join(format!("zentime-{}-suggestion.log", std::process::id()));
+    let config = Config {
+        notifications: NotificationConfig {
+            suggestion_provider: Some(
+                "echo '{\"title\": \"Plank\", \"description\": \"Hold it for 30 seconds\"}'"
+                    .to_string(),
+            ),
+            hooks: vec![format!(
+                "echo \"$ZENTIME_EVENT $ZENTIME_SUGGESTION\" >> '{}'",
+                log.display()
+            )],
+            ..test_config().notifications
+        },
+        ..test_config()
+    };
+    let interval = Duration::from_secs(config.timers.timer);
+
+    let server = TestServer::start(config).await?;
+    let mut client = server.connect().await?;
+
+    client.send(ClientToServerMsg::PlayPause).await?;
+    client.recv_state_matching(|state| !state.is_paused).await?;
+
+    advance_clock(interval);
+    client.recv_state_matching(|state| state.is_break).await?;
+
+    let mut suggestion = String::new();
+
+    for _ in 0..100 {
+        suggestion = std::fs::read_to_string(&log).unwrap_or_default();
+
+        if !suggestion.is_empty() {
+            break;
+        }
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    std::fs::remove_file(&log).ok();
+
+    assert_eq!(
+        suggestion.trim_end(),
+        "interval_end Plank: Hold it for 30 seconds"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn rings_the_bell_if_a_notification_fails() -> anyhow::Result<()> {
+    let config = Config {
+        notifications: NotificationConfig {
+            hooks: vec!["exit 1".to_string()],
+            on_failure: NotificationFailurePolicy::Bell,
+            ..test_config().notifications
+        },
+        ..test_config()
+    };
+    let interval = Duration::from_secs(config.timers.timer);
+
+    let server = TestServer::start(config).await?;
+    let mut client = server.connect().await?;
+
+    client.send(ClientToServerMsg::PlayPause).await?;
+    client.recv_state_matching(|state| !state.is_paused).await?;
+
+    advance_clock(interval);
+    let received = client
+        .recv_until(|msg| {
+            matches!(
+                msg,
+                ServerToClientMsg::Bell { .. } | ServerToClientMsg::Error { .. }
+            )
+        })
+        .await?;
+
+    assert!(matches!(
+        received.last(),
+        Some(ServerToClientMsg::Bell { .. })
+    ));
+
+    Ok(())
+}
+
+#[cfg(feature = "notifications")]
+#[tokio::test]
+async fn nudges_while_the_break_is_ignored() -> anyhow::Result<()> {
+    let log = std::env::temp_dir().join(format!("zentime-{}-nudge.log", std::process::id()));
+    let read_levels = || -> Vec<String> {
+        std::fs::read_to_string(&log)
+            .unwrap_or_default()
+            .lines()
+            .map(String::from)
+            .collect()
+    };
+
+    let server = TestServer::start(Config {
+        break_nudge: BreakNudgeConfig {
+            enabled: true,
+            interval: 1,
+            dim_duration: 0,
+            dim_command: Some(format!("echo {{level}} >> '{}'", log.display())),
+            ..BreakNudgeConfig::default()
+        },
+        ..test_config()
+    })
+    .await?;
+    let mut client = server.connect().await?;
+
+    client.send(ClientToServerMsg::Skip).await?;
+    client.recv_state_matching(|state| state.is_break).await?;
+
+    // The first nudge only raises the notification, the second one also dims the screen
+    let mut levels = vec![];
+
+    for _ in 0..200 {
+        levels = read_levels();
+
+        if levels.len() >= 2 {
+            break;
+        }
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    assert_eq!(levels, ["0.85", "1.00"]);
+
+    client.send(ClientToServerMsg::Skip).await?;
+    client.recv_state_matching(|state| !state.is_break).await?;
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+
+    let levels_after_break = read_levels();
+    std::fs::remove_file(&log).ok();
+
+    assert_eq!(levels_after_break, levels);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn summarizes_completed_cycles() -> anyhow::Result<()> {
+    let config = Config {
+        timers: PomodoroTimerConfig {
+            intervals: 1,
+            ..PomodoroTimerConfig::default()
+        },
+        ..test_config()
+    };
+
+    let server = TestServer::start(config).await?;
+    let mut client = server.connect().await?;
+
+    client.send(ClientToServerMsg::PlayPause).await?;
+    client.recv_state_matching(|state| !state.is_paused).await?;
+
+    client
+        .send_all([
+            ClientToServerMsg::PlayPause,
+            ClientToServerMsg::PauseReason {
+                reason: "phone call".to_string(),
+            },
+            ClientToServerMsg::Skip,
+        ])
+        .await?;
+    let state = client.recv_state_matching(|state| state.is_break).await?;
+    assert!(state.banner.is_none());
+
+    // Each break is a long break, therefore skipping it ends the cycle
+    client.send(ClientToServerMsg::Skip).await?;
+    let state = client
+        .recv_state_matching(|state| state.banner.is_some())
+        .await?;
+    let banner = state.banner.unwrap_or_default();
+    assert!(banner.starts_with("Cycle done: 0 intervals, "));
+    assert!(banner.ends_with(" focused, 1 interruption"));
+
+    // Starting the next cycle removes the banner
+    client.send(ClientToServerMsg::PlayPause).await?;
+    client
+        .recv_state_matching(|state| !state.is_paused && state.banner.is_none())
+        .await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn resumes_timer_once_hold_expires() -> anyhow::Result<()> {
+    // Long enough not to expire because of clock advances of other tests
+    let hold_duration = Duration::from_secs(24 * 60 * 60);
+
+    let server = TestServer::start(test_config()).await?;
+    let mut client = server.connect().await?;
+
+    client.send(ClientToServerMsg::PlayPause).await?;
+    client.recv_state_matching(|state| !state.is_paused).await?;
+
+    let until = (clock::system_now() + hold_duration).duration_since(UNIX_EPOCH)?;
+    client
+        .send(ClientToServerMsg::Hold {
+            reason: "meeting".to_string(),
+            until: until.as_secs(),
+        })
+        .await?;
+
+    let state = client
+        .recv_state_matching(|state| state.hold.is_some())
+        .await?;
+    assert!(state.is_paused);
+    assert_eq!(
+        state.hold.map(|hold| hold.reason),
+        Some("meeting".to_string())
+    );
+
+    advance_clock(hold_duration);
+
+    let state = client
+        .recv_state_matching(|state| state.hold.is_none())
+        .await?;
+    assert!(!state.is_paused);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn aligns_interval_starts_to_the_clock() -> anyhow::Result<()> {
+    // Intervals start at midnight - long enough not to be reached because of clock advances of
+    // other tests
+    let mut config = test_config();
+    config.alignment = AlignmentConfig {
+        interval: 24 * 60 * 60,
+    };
+
+    let server = TestServer::start(config).await?;
+    let mut client = server.connect().await?;
+
+    client.send(ClientToServerMsg::PlayPause).await?;
+
+    let state = client
+        .recv_state_matching(|state| state.hold.is_some())
+        .await?;
+    assert!(state.is_paused);
+    assert_eq!(
+        state.hold.map(|hold| hold.reason),
+        Some("waiting for 00:00".to_string())
+    );
+
+    advance_clock(Duration::from_secs(24 * 60 * 60));
+
+    let state = client
+        .recv_state_matching(|state| state.hold.is_none())
+        .await?;
+    assert!(!state.is_paused && !state.is_break);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn follows_the_lengths_of_each_weekday() -> anyhow::Result<()> {
+    let clock = ClockConfig {
+        time_zone: Some(chrono_tz::UTC),
+    };
+    let now = WallClock::from_config(&clock).now();
+    let today = now.weekday();
+
+    // Today has short intervals, all other days have long ones
+    let toml: String = [
+        "monday",
+        "tuesday",
+        "wednesday",
+        "thursday",
+        "friday",
+        "saturday",
+        "sunday",
+    ]
+    .iter()
+    .zip(0..)
+    .map(|(day, index)| {
+        let length = if index == today.num_days_from_monday() {
+            "10m"
+        } else {
+            "40m"
+        };
+        format!("[schedule.{}]\ntimer = \"{}\"\n", day, length)
+    })
+    .collect();
+    let config: Config = Figment::from(Serialized::defaults(test_config()))
+        .merge(Toml::string(&toml))
+        .extract()?;
+
+    let server = TestServer::start(Config { clock, ..config }).await?;
+    let mut client = server.connect().await?;
+
+    client.send(ClientToServerMsg::Sync).await?;
+    let state = client.recv_state_matching(|_| true).await?;
+    assert_eq!(state.time, "10:00");
+
+    // The schedule switches over at midnight, from the next phase on
+    let midnight = (now.date() + chrono::Days::new(1)).and_time(NaiveTime::MIN);
+    advance_clock((midnight - now).to_std()? + Duration::from_secs(60));
+
+    client
+        .send_all([ClientToServerMsg::Skip, ClientToServerMsg::Skip])
+        .await?;
+    let state = client.recv_state_matching(|state| state.round == 2).await?;
+    assert_eq!(state.time, "40:00");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn does_not_start_automatically_during_quiet_hours() -> anyhow::Result<()> {
+    let toml = r#"
+        [timers]
+        auto_start_break = true
+        auto_start_work = true
+
+        [[schedule.quiet_hours]]
+        from = "00:00"
+        to = "00:00"
+    "#;
+    let config: Config = Figment::from(Serialized::defaults(test_config()))
+        .merge(Toml::string(toml))
+        .extract()?;
+    let interval = Duration::from_secs(config.timers.timer);
+
+    let server = TestServer::start(config).await?;
+    let mut client = server.connect().await?;
+
+    client.send(ClientToServerMsg::PlayPause).await?;
+    client.recv_state_matching(|state| !state.is_paused).await?;
+
+    advance_clock(interval);
+
+    let state = client.recv_state_matching(|state| state.is_break).await?;
+    assert!(state.is_paused);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn walks_a_custom_sequence() -> anyhow::Result<()> {
+    let toml = r#"sequence = ["work:50", "break:10", "work:30", "break:20m"]"#;
+    let config: Config = Figment::from(Serialized::defaults(test_config()))
+        .merge(Toml::string(toml))
+        .extract()?;
+
+    let server = TestServer::start(config).await?;
+    let mut client = server.connect().await?;
+
+    let state = client.recv_state_matching(|_| true).await?;
+    assert_eq!(state.time, "50:00");
+
+    // Walking the sequence itself is tested inside the timer crate
+    client.send(ClientToServerMsg::Skip).await?;
+    let state = client.recv_state_matching(|state| state.is_break).await?;
+    assert_eq!(state.time, "10:00");
+
+    let invalid = r#"sequence = ["work:50", "work:50", "break:10"]"#;
+    assert!(Figment::from(Serialized::defaults(Config::default()))
+        .merge(Toml::string(invalid))
+        .extract::<Config>()
+        .is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn reports_timer_events_to_api_clients() -> anyhow::Result<()> {
+    let server = TestServer::start(test_config()).await?;
+    let mut client = ZentimeClient::from(server.connection().await?);
+
+    let snapshot = client.state().await?;
+    assert!(snapshot.state.is_paused);
+    assert_eq!(snapshot.state.round, 1);
+
+    client.play_pause().await?;
+    let (event, state) = timeout(Duration::from_secs(5), client.next_event()).await??;
+    assert_eq!(event, TimerEvent::Resume);
+    assert!(!state.is_paused);
+
+    client.skip().await?;
+    let (event, _) = timeout(Duration::from_secs(5), client.next_event()).await??;
+    assert_eq!(event, TimerEvent::IntervalEnd);
+    let (event, state) = timeout(Duration::from_secs(5), client.next_event()).await??;
+    assert_eq!(event, TimerEvent::BreakStart);
+    assert!(state.is_break);
+
+    client.detach().await
+}
+
+#[tokio::test]
+async fn runs_a_stopwatch() -> anyhow::Result<()> {
+    let server = TestServer::start(Config {
+        mode: TimerMode::Stopwatch,
+        ..test_config()
+    })
+    .await?;
+    let mut client = server.connect().await?;
+
+    let state = client.recv_state_matching(|_| true).await?;
+    assert!(state.stopwatch.is_some());
+    assert_eq!(state.time, "00:00");
+
+    client
+        .send_all([ClientToServerMsg::PlayPause, ClientToServerMsg::Skip])
+        .await?;
+    let state = client.recv_state_matching(|state| state.round == 2).await?;
+    assert!(!state.is_paused);
+    assert_eq!(
+        state.stopwatch.map(|stopwatch| stopwatch.laps.len()),
+        Some(1)
+    );
+
+    // Stopwatches have no breaks to postpone
+    client
+        .send(ClientToServerMsg::PostPone { seconds: None })
+        .await?;
+    let received = client
+        .recv_until(|msg| matches!(msg, ServerToClientMsg::Error { .. }))
+        .await?;
+    assert!(matches!(
+        received.last(),
+        Some(ServerToClientMsg::Error {
+            code: ErrorCode::ActionRejected,
+            ..
+        })
+    ));
+
+    client.send(ClientToServerMsg::Reset).await?;
+    let state = client.recv_state_matching(|state| state.is_paused).await?;
+    assert_eq!(state.round, 1);
+    assert_eq!(state.time, "00:00");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn welcomes_back_once_the_pause_is_over() -> anyhow::Result<()> {
+    // Long enough not to expire because of clock advances of other tests
+    let pause_duration = Duration::from_secs(24 * 60 * 60);
+    let log = std::env::temp_dir().join(format!("zentime-{}-welcome.log", std::process::id()));
+
+    let server = TestServer::start(Config {
+        notifications: NotificationConfig {
+            hooks: vec![format!("echo \"$ZENTIME_EVENT\" >> '{}'", log.display())],
+            ..test_config().notifications
+        },
+        ..test_config()
+    })
+    .await?;
+    let mut client = server.connect().await?;
+
+    // Unlike a hold, the pause resumes the timer even if it hasn't been running before
+    let until = (clock::system_now() + pause_duration).duration_since(UNIX_EPOCH)?;
+    client
+        .send(ClientToServerMsg::PauseUntil {
+            reason: "back at 13:30".to_string(),
+            until: until.as_secs(),
+        })
+        .await?;
+
+    let state = client
+        .recv_state_matching(|state| state.hold.is_some())
+        .await?;
+    assert!(state.is_paused);
+
+    advance_clock(pause_duration);
+
+    let state = client
+        .recv_state_matching(|state| state.hold.is_none())
+        .await?;
+    assert!(!state.is_paused);
+
+    let mut events = String::new();
+
+    for _ in 0..100 {
+        events = std::fs::read_to_string(&log).unwrap_or_default();
+
+        if !events.is_empty() {
+            break;
+        }
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    std::fs::remove_file(&log).ok();
+    assert_eq!(events.trim_end(), "welcome_back");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn resumes_timer_after_a_long_pause() -> anyhow::Result<()> {
+    let config = Config {
+        pause: PauseConfig {
+            max_pause_minutes: 1,
+            on_timeout: PauseTimeoutAction::Resume,
+        },
+        ..test_config()
+    };
+
+    let server = TestServer::start(config).await?;
+    let mut client = server.connect().await?;
+
+    client.send(ClientToServerMsg::PlayPause).await?;
+    client.recv_state_matching(|state| !state.is_paused).await?;
+
+    // Only started timers are resumed
+    advance_clock(Duration::from_secs(1));
+    client
+        .recv_state_matching(|state| state.time != "25:00")
+        .await?;
+
+    client.send(ClientToServerMsg::PlayPause).await?;
+    client.recv_state_matching(|state| state.is_paused).await?;
+
+    advance_clock(Duration::from_secs(60));
+
+    let state = client.recv_state_matching(|state| !state.is_paused).await?;
+    assert!(!state.is_break);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn postpones_breaks_for_the_requested_duration() -> anyhow::Result<()> {
+    let config = Config {
+        timers: PomodoroTimerConfig {
+            postpone_limit: 2,
+            ..PomodoroTimerConfig::default()
+        },
+        ..test_config()
+    };
+
+    let server = TestServer::start(config).await?;
+    let mut client = server.connect().await?;
+
+    client.send(ClientToServerMsg::Skip).await?;
+    client.recv_state_matching(|state| state.is_break).await?;
+
+    client
+        .send(ClientToServerMsg::PostPone { seconds: Some(180) })
+        .await?;
+    let state = client
+        .recv_state_matching(|state| state.is_postponed)
+        .await?;
+    // Postponed breaks start running right away
+    let remaining = time_to_seconds(&state.time).unwrap_or_default();
+    assert!(remaining <= 180 && remaining > 60);
+
+    // Without a duration the configured postpone timer is used
+    client.send(ClientToServerMsg::Skip).await?;
+    client.recv_state_matching(|state| state.is_break).await?;
+
+    client
+        .send(ClientToServerMsg::PostPone { seconds: None })
+        .await?;
+    let state = client
+        .recv_state_matching(|state| state.is_postponed)
+        .await?;
+    assert!(time_to_seconds(&state.time).unwrap_or_default() > 180);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn resumes_the_timer_of_a_previous_server() -> anyhow::Result<()> {
+    let config = Config {
+        timers: PomodoroTimerConfig {
+            postpone_limit: 2,
+            ..PomodoroTimerConfig::default()
+        },
+        ..test_config()
+    };
+
+    let server = TestServer::start(config.clone()).await?;
+    let mut client = server.connect().await?;
+
+    client.send(ClientToServerMsg::Skip).await?;
+    client.recv_state_matching(|state| state.is_break).await?;
+    client
+        .send(ClientToServerMsg::PostPone { seconds: Some(180) })
+        .await?;
+    client
+        .recv_state_matching(|state| state.is_postponed)
+        .await?;
+
+    // The previous server is left running, as if it had crashed
+    let resumed = TestServer::start_resuming(config, &server).await?;
+    let mut client = resumed.connect().await?;
+
+    let state = client.recv_state_matching(|_| true).await?;
+    assert_eq!(state.round, 1);
+    assert!(state.is_postponed);
+    assert_eq!(state.postpone_count, 1);
+    assert!(state.is_paused);
+    let remaining = time_to_seconds(&state.time).unwrap_or_default();
+    assert!(remaining <= 180 && remaining > 60);
+
+    // Ending the postponed break continues with the break it has postponed
+    client.send(ClientToServerMsg::Skip).await?;
+    let state = client
+        .recv_state_matching(|state| !state.is_postponed)
+        .await?;
+    assert!(state.is_break);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn shuts_down_gracefully() -> anyhow::Result<()> {
+    let mut server = TestServer::start(test_config()).await?;
+    let mut client = server.connect().await?;
+    let mut other = server.connect().await?;
+
+    client.send(ClientToServerMsg::Skip).await?;
+    other.recv_state_matching(|state| state.is_break).await?;
+
+    client.send(ClientToServerMsg::Quit).await?;
+    other
+        .recv_until(|msg| matches!(msg, ServerToClientMsg::ShuttingDown))
+        .await?;
+    assert!(other.recv().await.is_err());
+    server.stopped().await?;
+
+    // The timer has been saved on the way out
+    let resumed = TestServer::start_resuming(test_config(), &server).await?;
+    let mut client = resumed.connect().await?;
+    let state = client.recv_state_matching(|_| true).await?;
+    assert!(state.is_break);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn applies_reloaded_timer_lengths_from_the_next_phase() -> anyhow::Result<()> {
+    let reloaded_config = Config {
+        timers: PomodoroTimerConfig {
+            timer: 600,
+            minor_break: 60,
+            ..PomodoroTimerConfig::default()
+        },
+        ..test_config()
+    };
+    let server =
+        TestServer::start_reloadable(test_config(), Arc::new(move || Ok(reloaded_config.clone())))
+            .await?;
+    let mut client = server.connect().await?;
+
+    client
+        .send_all([
+            ClientToServerMsg::PlayPause,
+            ClientToServerMsg::ReloadConfig,
+        ])
+        .await?;
+    client
+        .recv_until(|msg| matches!(msg, ServerToClientMsg::ConfigReloaded))
+        .await?;
+
+    // The running interval keeps its length
+    let state = client.recv_state_matching(|_| true).await?;
+    assert!(time_to_seconds(&state.time).unwrap_or_default() > 600);
+
+    client.send(ClientToServerMsg::Skip).await?;
+    let state = client.recv_state_matching(|state| state.is_break).await?;
+    assert_eq!(time_to_seconds(&state.time), Some(60));
+
+    client.send(ClientToServerMsg::Skip).await?;
+    let state = client.recv_state_matching(|state| !state.is_break).await?;
+    assert_eq!(time_to_seconds(&state.time), Some(600));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn team_participants_share_the_timer_according_to_their_role() -> anyhow::Result<()> {
+    let server = TestServer::start(Config {
+        team: TeamConfig {
+            listen: Some(free_address()?),
+            control_token: Some("control".to_string()),
+            viewer_token: Some("viewer".to_string()),
+        },
+        ..test_config()
+    })
+    .await?;
+
+    assert!(server.join("wrong").await.is_err());
+
+    let mut controller = server.join("control").await?;
+    let mut viewer = server.join("viewer").await?;
+    assert_eq!(controller.role(), Role::Controller);
+    assert_eq!(viewer.role(), Role::Viewer);
+
+    controller.send(ClientToServerMsg::PlayPause).await?;
+    viewer.recv_state_matching(|state| !state.is_paused).await?;
+
+    viewer.send(ClientToServerMsg::Skip).await?;
+    let received = viewer
+        .recv_until(|msg| matches!(msg, ServerToClientMsg::Error { .. }))
+        .await?;
+    assert!(matches!(
+        received.last(),
+        Some(ServerToClientMsg::Error {
+            code: ErrorCode::Unauthorized,
+            ..
+        })
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn team_participants_speak_plain_text_with_their_role() -> anyhow::Result<()> {
+    let address = free_address()?;
+    let server = TestServer::start(Config {
+        team: TeamConfig {
+            listen: Some(address.clone()),
+            control_token: Some("control".to_string()),
+            viewer_token: Some("viewer".to_string()),
+        },
+        ..test_config()
+    })
+    .await?;
+    // Waits until the team listener is ready
+    server.join("control").await?;
+
+    let (reader, mut writer) = TcpStream::connect(&address).await?.into_split();
+    let mut answers = tokio::io::AsyncBufReadExt::lines(tokio::io::BufReader::new(reader));
+
+    writer.write_all(b"JOIN viewer\nSTATUS\n").await?;
+    assert_eq!(answers.next_line().await?.as_deref(), Some("OK viewer"));
+    assert_eq!(
+        answers.next_line().await?.as_deref(),
+        Some("25:00 Focus (paused)")
+    );
+
+    writer.write_all(b"SKIP\n").await?;
+    let answer = answers.next_line().await?.unwrap_or_default();
+    assert!(answer.starts_with("ERROR"), "{}", answer);
+
+    let (reader, mut writer) = TcpStream::connect(&address).await?.into_split();
+    let mut answers = tokio::io::AsyncBufReadExt::lines(tokio::io::BufReader::new(reader));
+
+    writer.write_all(b"JOIN wrong\n").await?;
+    let answer = answers.next_line().await?.unwrap_or_default();
+    assert!(answer.starts_with("ERROR"), "{}", answer);
+
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+#[tokio::test]
+async fn accepts_clients_on_a_socket_passed_by_systemd() -> anyhow::Result<()> {
+    let server = TestServer::start_socket_activated(test_config()).await?;
+    let mut client = server.connect().await?;
+    let _other = server.connect().await?;
+
+    // Clients are listed without the one asking
+    client.send(ClientToServerMsg::ListClients).await?;
+    let received = client
+        .recv_until(|msg| matches!(msg, ServerToClientMsg::Clients(_)))
+        .await?;
+    let Some(ServerToClientMsg::Clients(clients)) = received.last() else {
+        unreachable!("recv_until only returns after the client list");
+    };
+    assert_eq!(clients.len(), 1);
+    assert_eq!(clients[0].pid, Some(std::process::id()));
+
+    client.send(ClientToServerMsg::PlayPause).await?;
+    client.recv_state_matching(|state| !state.is_paused).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn reaps_clients_which_stop_sending_heartbeats() -> anyhow::Result<()> {
+    let server = TestServer::start(Config {
+        connections: ConnectionConfig {
+            heartbeat_timeout: 1,
+        },
+        ..test_config()
+    })
+    .await?;
+    let mut silent = server.connect().await?;
+    let mut watcher = server.connect().await?;
+
+    watcher.send(ClientToServerMsg::ListClients).await?;
+    let received = watcher
+        .recv_until(|msg| matches!(msg, ServerToClientMsg::Clients(_)))
+        .await?;
+    let Some(ServerToClientMsg::Clients(clients)) = received.last() else {
+        unreachable!("recv_until only returns after the client list");
+    };
+    assert_eq!(clients.len(), 1);
+    assert_eq!(clients[0].role, Role::Owner);
+    assert_eq!(clients[0].transport, Transport::Socket);
+
+    // The watcher keeps its connection alive, while the silent client is disconnected long
+    // before receiving would time out
+    let heartbeats = async {
+        for _ in 0..4 {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            watcher.send(ClientToServerMsg::Heartbeat).await?;
+        }
+
+        anyhow::Ok(())
+    };
+    let disconnected = tokio::time::timeout(Duration::from_secs(3), silent.recv_until(|_| false));
+    let (heartbeats, disconnected) = tokio::join!(heartbeats, disconnected);
+    heartbeats?;
+    assert!(matches!(disconnected, Ok(Err(_))));
+
+    watcher.send(ClientToServerMsg::ListClients).await?;
+    let received = watcher
+        .recv_until(|msg| matches!(msg, ServerToClientMsg::Clients(_)))
+        .await?;
+    assert!(
+        matches!(received.last(), Some(ServerToClientMsg::Clients(clients)) if clients.is_empty())
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn rejects_clients_of_another_protocol_version() -> anyhow::Result<()> {
+    let server = TestServer::start(test_config()).await?;
+
+    let connection = server.connection().await?;
+    assert_eq!(connection.server, ServerInfo::current());
+    assert_eq!(connection.server.protocol_version, PROTOCOL_VERSION);
+
+    let (reader, writer) = LocalSocketStream::connect(server.socket_name())
+        .await?
+        .into_split();
+    let mut reader = IpcReader::new(futures::io::BufReader::new(reader));
+    let mut writer = IpcWriter::new(writer);
+
+    writer
+        .send(&ClientToServerMsg::Handshake {
+            version: PROTOCOL_VERSION + 1,
+        })
+        .await?;
+    assert!(matches!(
+        reader.recv::<ServerToClientMsg>().await?,
+        ServerToClientMsg::Error {
+            code: ErrorCode::VersionMismatch,
+            ..
+        }
+    ));
+
+    // Nothing else is sent to a client, which could not decode it anyway
+    let closed = timeout(Duration::from_secs(1), reader.recv::<ServerToClientMsg>()).await?;
+    assert!(closed.is_err());
+
+    Ok(())
+}
+
+#[cfg(feature = "http")]
+#[tokio::test]
+async fn serves_spectator_page_and_view_state_stream() -> anyhow::Result<()> {
+    let address = free_address()?;
+    let server = TestServer::start(Config {
+        http: HttpConfig {
+            listen: Some(address.clone()),
+            ..HttpConfig::default()
+        },
+        ..test_config()
+    })
+    .await?;
+    let mut client = server.connect().await?;
+
+    let response = http_get(&address, "/").await?;
+    assert!(response.starts_with("HTTP/1.1 200 OK"));
+    assert!(response.contains("new WebSocket"));
+
+    let response = http_get(&address, "/overlay.txt").await?;
+    assert!(response.ends_with("\r\n\r\n25:00 Focus (paused)"));
+
+    // The calendar feed exposes the history and therefore has to be enabled
+    let response = http_get(&address, "/focus.ics").await?;
+    assert!(response.starts_with("HTTP/1.1 404 Not Found"));
+
+    let (mut socket, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws", address)).await?;
+    client.send(ClientToServerMsg::PlayPause).await?;
+
+    loop {
+        let Some(Message::Text(json)) = socket.next().await.transpose()? else {
+            continue;
+        };
+
+        let state: ViewState = serde_json::from_str(&json)?;
+        if !state.is_paused {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "http")]
+#[tokio::test]
+async fn controls_the_timer_via_json_api() -> anyhow::Result<()> {
+    let address = free_address()?;
+    let port = address.rsplit(':').next().unwrap_or_default().parse()?;
+    let _server = TestServer::start(Config {
+        api: ApiConfig {
+            enabled: true,
+            port,
+            token: Some("secret".to_string()),
+            allowed_origin: None,
+        },
+        ..test_config()
+    })
+    .await?;
+    let body = |response: &str| -> anyhow::Result<serde_json::Value> {
+        let (_, body) = response.split_once("\r\n\r\n").unwrap_or_default();
+        Ok(serde_json::from_str(body)?)
+    };
+
+    let response = api_request(&address, "GET", "/state", "secret").await?;
+    assert!(response.starts_with("HTTP/1.1 200 OK"));
+    assert_eq!(body(&response)?["is_paused"], true);
+
+    let response = api_request(&address, "POST", "/play-pause", "secret").await?;
+    assert!(response.starts_with("HTTP/1.1 200 OK"));
+    assert_eq!(body(&response)?["is_paused"], false);
+
+    // Only breaks can be postponed
+    let response = api_request(&address, "POST", "/postpone", "secret").await?;
+    assert!(response.starts_with("HTTP/1.1 409 Conflict"));
+    assert!(body(&response)?["error"].is_string());
+
+    // Server-sent events start with the current state
+    let mut events = TcpStream::connect(&address).await?;
+    events
+        .write_all(
+            b"GET /events HTTP/1.1\r\nHost: localhost\r\nAuthorization: Bearer secret\r\n\r\n",
+        )
+        .await?;
+
+    let mut received = String::new();
+    let mut buffer = [0; 1024];
+
+    while !received.contains("\n\n") || !received.contains("data: ") {
+        let length = timeout(Duration::from_secs(5), events.read(&mut buffer)).await??;
+        anyhow::ensure!(length > 0, "Event stream has been closed");
+        received.push_str(&String::from_utf8_lossy(&buffer[..length]));
+    }
+
+    assert!(received.starts_with("HTTP/1.1 200 OK"));
+    assert!(received.contains("Content-Type: text/event-stream"));
+    assert!(received.contains("\"is_paused\":false"));
+
+    Ok(())
+}
+
+#[cfg(feature = "http")]
+#[tokio::test]
+async fn rejects_json_api_requests_of_strangers() -> anyhow::Result<()> {
+    let address = free_address()?;
+    let port = address.rsplit(':').next().unwrap_or_default().parse()?;
+    let _server = TestServer::start(Config {
+        api: ApiConfig {
+            enabled: true,
+            port,
+            token: Some("secret".to_string()),
+            allowed_origin: Some("https://widgets.example.com".to_string()),
+        },
+        ..test_config()
+    })
+    .await?;
+
+    let response = http_request(&address, "POST", "/play-pause").await?;
+    assert!(response.starts_with("HTTP/1.1 401 Unauthorized"));
+
+    let response = api_request(&address, "POST", "/play-pause", "wrong").await?;
+    assert!(response.starts_with("HTTP/1.1 401 Unauthorized"));
+
+    // Pages which have been rebound to localhost via DNS still send their own host
+    let headers = "Host: attacker.example:8080\r\nAuthorization: Bearer secret\r\n";
+    let response = http_request_with_headers(&address, "GET", "/state", headers).await?;
+    assert!(response.starts_with("HTTP/1.1 403 Forbidden"));
+
+    let headers =
+        "Host: localhost\r\nOrigin: https://attacker.example\r\nAuthorization: Bearer secret\r\n";
+    let response = http_request_with_headers(&address, "GET", "/state", headers).await?;
+    assert!(response.starts_with("HTTP/1.1 403 Forbidden"));
+
+    // Only the allowed origin may read responses - and no response allows every origin
+    let headers = "Host: localhost\r\nOrigin: https://widgets.example.com\r\n";
+    let response = http_request_with_headers(&address, "OPTIONS", "/state", headers).await?;
+    assert!(response.starts_with("HTTP/1.1 204 No Content"));
+    assert!(response.contains("Access-Control-Allow-Origin: https://widgets.example.com\r\n"));
+    assert!(response.contains("Access-Control-Allow-Headers: Authorization\r\n"));
+
+    let response = api_request(&address, "GET", "/state", "secret").await?;
+    assert!(response.starts_with("HTTP/1.1 200 OK"));
+    assert!(!response.contains("Access-Control-Allow-Origin"));
+
+    Ok(())
+}
+
+#[cfg(feature = "http")]
+#[tokio::test]
+async fn serves_focus_calendar() -> anyhow::Result<()> {
+    let config = test_config();
+    let interval = Duration::from_secs(config.timers.timer);
+    let address = free_address()?;
+
+    let server = TestServer::start(Config {
+        http: HttpConfig {
+            listen: Some(address.clone()),
+            calendar: true,
+        },
+        ..config
+    })
+    .await?;
+    let mut client = server.connect().await?;
+
+    client.send(ClientToServerMsg::PlayPause).await?;
+    client.recv_state_matching(|state| !state.is_paused).await?;
+
+    advance_clock(interval);
+
+    client.recv_state_matching(|state| state.is_break).await?;
+    client.send(ClientToServerMsg::PlayPause).await?;
+    client
+        .recv_state_matching(|state| state.is_break && !state.is_paused)
+        .await?;
+
+    // Records are written on a blocking task
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    // The completed interval is recorded, the remaining intervals of the cycle are planned
+    let response = http_get(&address, "/focus.ics").await?;
+    assert!(response.starts_with("HTTP/1.1 200 OK"));
+    assert!(response.contains("Content-Type: text/calendar"));
+    assert_eq!(response.matches("SUMMARY:Focus\r\n").count(), 1);
+    assert_eq!(response.matches("SUMMARY:Focus (planned)\r\n").count(), 3);
+    assert!(response.ends_with("END:VCALENDAR\r\n"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn plays_mock_scenarios() -> anyhow::Result<()> {
+    let state = |time: &str, is_break: bool| ViewState {
+        is_break,
+        is_postponed: false,
+        postpone_count: 0,
+        round: 2,
+        time: time.to_string(),
+        is_paused: false,
+        hold: None,
+        reminder: None,
+        task: Some("mock".to_string()),
+        profile: None,
+        banner: None,
+        today: None,
+        progress: None,
+        stopwatch: None,
+        pause_reason: None,
+        timers: vec![],
+        countdowns: vec![],
+    };
+
+    let scenario = Scenario {
+        repeat: false,
+        steps: vec![
+            ScenarioStep {
+                seconds: 3600,
+                state: state("12:34", false),
+            },
+            ScenarioStep {
+                seconds: 3600,
+                state: state("04:59", true),
+            },
+        ],
+    };
+
+    let server = TestServer::start_mock(test_config(), scenario.clone()).await?;
+    let mut client = server.connect().await?;
+
+    client.send(ClientToServerMsg::Sync).await?;
+    let first = client.recv_state_matching(|_| true).await?;
+    assert_eq!(first, scenario.steps[0].state);
+
+    client.send(ClientToServerMsg::Skip).await?;
+    let second = client.recv_state_matching(|state| state.is_break).await?;
+    assert_eq!(second, scenario.steps[1].state);
+
+    // The last state is kept, once the scenario has been played
+    client.send(ClientToServerMsg::Skip).await?;
+    let last = client.recv_state_matching(|_| true).await?;
+    assert_eq!(last, scenario.steps[1].state);
+
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use std::rc::Rc;
+use std::sync::Arc;
 use std::thread;
 use zentime_rs_timer::pomodoro_timer::PomodoroTimer;
 
@@ -7,10 +7,10 @@ fn main() {
     thread::spawn(move || {
         PomodoroTimer::new(
             Default::default(),
-            Rc::new(move |state, msg, _| {
+            Arc::new(move |state, msg, _| {
                 println!("{} {}", state.round, msg.unwrap());
             }),
-            Rc::new(move |view_state| {
+            Arc::new(move |view_state| {
                 println!("{:?}", view_state);
                 None
             }),
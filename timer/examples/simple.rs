@@ -1,6 +1,6 @@
-use std::rc::Rc;
 use std::sync::mpsc::{self, RecvTimeoutError};
 use std::sync::mpsc::{Receiver, Sender};
+use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
 use zentime_rs_timer::config::PomodoroTimerConfig;
@@ -15,18 +15,23 @@ fn main() {
     let (view_sender, view_receiver): (Sender<ViewState>, Receiver<ViewState>) = mpsc::channel();
 
     let config = PomodoroTimerConfig::default();
+    // Callbacks have to be `Sync`, which the receiving end of a channel is not
+    let terminal_input_receiver = Mutex::new(terminal_input_receiver);
 
     // Run timer in its own thread so it does not block the current one
     thread::spawn(move || {
         let timer = PomodoroTimer::new(
             config,
-            Rc::new(move |state, msg, _| {
+            Arc::new(move |state, msg, _| {
                 println!("{} {}", state.round, msg.unwrap());
             }),
-            Rc::new(move |state| -> Option<PomodoroTimerAction> {
+            Arc::new(move |state| -> Option<PomodoroTimerAction> {
                 view_sender.send(state).unwrap();
 
-                let input = terminal_input_receiver.recv_timeout(Duration::from_secs(1));
+                let input = terminal_input_receiver
+                    .lock()
+                    .unwrap()
+                    .recv_timeout(Duration::from_secs(1));
 
                 match input {
                     Ok(action) => Some(action),
@@ -0,0 +1,50 @@
+use std::time::Duration;
+use tokio::select;
+use tokio::sync::mpsc::unbounded_channel;
+use tokio::time::{interval, sleep};
+use zentime_rs_timer::config::PomodoroTimerConfig;
+use zentime_rs_timer::pomodoro_timer_action::PomodoroTimerAction;
+use zentime_rs_timer::ticking_pomodoro_timer::TickingPomodoroTimer;
+
+#[tokio::main]
+async fn main() {
+    let (action_sender, mut action_receiver) = unbounded_channel();
+
+    let config = PomodoroTimerConfig {
+        timer: 3,
+        minor_break: 2,
+        ..Default::default()
+    };
+
+    // Simulate user input
+    tokio::spawn(async move {
+        action_sender.send(PomodoroTimerAction::PlayPause).unwrap();
+        sleep(Duration::from_secs(4)).await;
+        action_sender.send(PomodoroTimerAction::PlayPause).unwrap();
+    });
+
+    let mut timer = TickingPomodoroTimer::new(config);
+    let mut ticks = interval(Duration::from_millis(250));
+
+    loop {
+        select! {
+            _ = ticks.tick() => {
+                if let Some(timer_end) = timer.tick() {
+                    println!("{} {:?}", timer_end.state.round, timer_end.notification);
+                }
+            },
+            Some(action) = action_receiver.recv() => {
+                if let Err(error) = timer.handle_action(action) {
+                    println!("{}", error);
+                }
+            },
+        }
+
+        let view_state = timer.view_state();
+        println!("{:?}", view_state);
+
+        if view_state.round > 1 {
+            break;
+        }
+    }
+}
@@ -0,0 +1,44 @@
+//! Clock used by [TickingTimer](crate::ticking_timer::TickingTimer)s.
+//! Code which has to stay in sync with these timers (e.g. timeouts) should use this clock, too.
+//!
+//! With the `mock-clock` feature enabled, the clock can be advanced manually via [advance],
+//! which makes it possible to test timers without actually waiting for them.
+//! NOTE: The mock clock is shared by the whole process.
+
+use std::time::{Instant, SystemTime};
+
+#[cfg(feature = "mock-clock")]
+use std::sync::atomic::{AtomicU64, Ordering};
+#[cfg(feature = "mock-clock")]
+use std::time::Duration;
+
+/// Time (in milliseconds) the mock clock has been advanced by
+#[cfg(feature = "mock-clock")]
+static OFFSET_MILLIS: AtomicU64 = AtomicU64::new(0);
+
+/// Current monotonic time
+pub fn now() -> Instant {
+    Instant::now() + offset()
+}
+
+/// Current wall clock time
+pub fn system_now() -> SystemTime {
+    SystemTime::now() + offset()
+}
+
+/// Advances the (mock) clock of all timers by the given duration
+#[cfg(feature = "mock-clock")]
+pub fn advance(duration: Duration) {
+    let millis = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
+    OFFSET_MILLIS.fetch_add(millis, Ordering::SeqCst);
+}
+
+#[cfg(feature = "mock-clock")]
+fn offset() -> Duration {
+    Duration::from_millis(OFFSET_MILLIS.load(Ordering::SeqCst))
+}
+
+#[cfg(not(feature = "mock-clock"))]
+fn offset() -> std::time::Duration {
+    std::time::Duration::ZERO
+}
@@ -1,17 +1,24 @@
 //! Configuration of a [Timer]
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::timer::TimeJumpPolicy;
 
 /// Timer configuration which determines certain aspects of the timer,
 /// like the duration of `intervals` and break lengths.
 #[derive(Debug, Deserialize, Serialize, Clone, Copy)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct PomodoroTimerConfig {
-    /// Timer in seconds
+    /// Timer in seconds (config files also accept durations like "25m")
+    #[serde(deserialize_with = "crate::duration::deserialize_seconds")]
     pub timer: u64,
 
     /// Minor break time in seconds
+    #[serde(deserialize_with = "crate::duration::deserialize_seconds")]
     pub minor_break: u64,
 
     /// Major break time in seconds
+    #[serde(deserialize_with = "crate::duration::deserialize_seconds")]
     pub major_break: u64,
 
     /// Intervals before major break
@@ -23,7 +30,43 @@ pub struct PomodoroTimerConfig {
     pub postpone_limit: u16,
 
     /// Determines how long each postpone timer runs (in seconds)
+    #[serde(deserialize_with = "crate::duration::deserialize_seconds")]
     pub postpone_timer: u64,
+
+    /// Determines how a running timer reacts to large wall clock jumps, e.g. when the
+    /// machine has been suspended in the middle of an interval
+    pub time_jump_policy: TimeJumpPolicy,
+
+    /// Gaps between two timer ticks (in seconds) which are at least this long are considered
+    /// to be a time jump
+    #[serde(deserialize_with = "crate::duration::deserialize_seconds")]
+    pub time_jump_threshold: u64,
+
+    /// Starts each break right away once an interval has ended, instead of waiting for
+    /// [PomodoroTimerAction::PlayPause](crate::pomodoro_timer_action::PomodoroTimerAction::PlayPause)
+    #[serde(default)]
+    pub auto_start_break: bool,
+
+    /// Starts the next interval right away once a break has ended
+    #[serde(default)]
+    pub auto_start_work: bool,
+}
+
+impl PomodoroTimerConfig {
+    /// [Self::time_jump_threshold] as [Duration]
+    pub fn time_jump_threshold(&self) -> Duration {
+        Duration::from_secs(self.time_jump_threshold)
+    }
+
+    /// Last round of the cycle the given round belongs to, i.e. the round which is followed
+    /// by a long break
+    pub fn last_round_of_cycle(&self, round: u64) -> u64 {
+        if self.intervals == 0 {
+            return round;
+        }
+
+        round.div_ceil(self.intervals) * self.intervals
+    }
 }
 
 impl Default for PomodoroTimerConfig {
@@ -35,6 +78,10 @@ impl Default for PomodoroTimerConfig {
             intervals: 4,
             postpone_limit: 0,
             postpone_timer: 300,
+            time_jump_policy: TimeJumpPolicy::default(),
+            time_jump_threshold: 5,
+            auto_start_break: false,
+            auto_start_work: false,
         }
     }
 }
@@ -0,0 +1,70 @@
+//! One-off countdowns (e.g. "standup in 15 minutes"), which run independently of the pomodoro
+//! cycle.
+//! Unlike a [TickingTimer](crate::ticking_timer::TickingTimer), a [Countdown] can't be paused
+//! and does not need to be ticked - it only knows when it is due. The due time is a wall clock
+//! time, so a countdown which has run out while the computer has been suspended is due right
+//! after waking up.
+
+use std::time::{Duration, SystemTime};
+
+use crate::clock;
+use crate::pomodoro_timer::CountdownState;
+use crate::util::seconds_to_time;
+
+/// Countdown which is due after a fixed duration
+///
+/// ## Example
+///
+/// ```
+/// use std::time::Duration;
+/// use zentime_rs_timer::countdown::Countdown;
+///
+/// let countdown = Countdown::new(Duration::from_secs(15 * 60), Some("standup".to_string()));
+///
+/// assert!(!countdown.is_due());
+/// assert_eq!(countdown.label(), Some("standup"));
+/// assert!(countdown.remaining_time() <= Duration::from_secs(15 * 60));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Countdown {
+    /// What the countdown is for
+    label: Option<String>,
+
+    /// Wall clock time at which the countdown is due
+    due_at: SystemTime,
+}
+
+impl Countdown {
+    /// Creates a countdown, which is due after the given duration from now on
+    pub fn new(duration: Duration, label: Option<String>) -> Self {
+        Self {
+            label,
+            due_at: clock::system_now() + duration,
+        }
+    }
+
+    /// What the countdown is for - [None] if it has been started without label
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    /// Time until the countdown is due
+    pub fn remaining_time(&self) -> Duration {
+        self.due_at
+            .duration_since(clock::system_now())
+            .unwrap_or_default()
+    }
+
+    /// Denotes if the countdown has run out
+    pub fn is_due(&self) -> bool {
+        self.remaining_time().is_zero()
+    }
+
+    /// State of the countdown, which is shown by clients
+    pub fn state(&self) -> CountdownState {
+        CountdownState {
+            label: self.label.clone(),
+            time: seconds_to_time(self.remaining_time().as_secs()),
+        }
+    }
+}
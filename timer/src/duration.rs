@@ -0,0 +1,245 @@
+//! Durations which are either given as plain number of seconds or as human readable string
+//! (e.g. `25m`, `90s` or `1h15m`), so that config files and command line flags accept the
+//! same notation.
+
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+use std::time::Duration;
+
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Reason why a duration could not be parsed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DurationError {
+    /// The duration contains a unit other than `h`, `m` or `s`
+    UnknownUnit(char),
+
+    /// A unit is not preceded by a number
+    MissingNumber(char),
+
+    /// The duration is empty, lacks a unit at its end or is too long
+    Invalid,
+}
+
+impl Display for DurationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DurationError::UnknownUnit(unit) => write!(f, "Unknown time unit '{}'", unit),
+            DurationError::MissingNumber(unit) => write!(f, "Missing number before '{}'", unit),
+            DurationError::Invalid => write!(f, "Expected a duration like 25m, 1h15m or 90s"),
+        }
+    }
+}
+
+impl Error for DurationError {}
+
+/// Duration with second precision, which is parsed from plain numbers of seconds
+/// (e.g. `1500`) or human readable strings (e.g. `25m`, `90s` or `1h15m`).
+/// It is serialized as number of seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct HumanDuration(Duration);
+
+impl HumanDuration {
+    /// Creates a duration of the given number of seconds
+    pub fn from_secs(seconds: u64) -> Self {
+        Self(Duration::from_secs(seconds))
+    }
+
+    /// Number of whole seconds of this duration
+    pub fn as_secs(&self) -> u64 {
+        self.0.as_secs()
+    }
+}
+
+impl From<HumanDuration> for Duration {
+    fn from(duration: HumanDuration) -> Self {
+        duration.0
+    }
+}
+
+impl FromStr for HumanDuration {
+    type Err = DurationError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+
+        if let Ok(seconds) = value.parse::<u64>() {
+            return Ok(Self::from_secs(seconds));
+        }
+
+        let mut seconds: u64 = 0;
+        let mut number = String::new();
+
+        for character in value.chars().filter(|character| !character.is_whitespace()) {
+            if character.is_ascii_digit() {
+                number.push(character);
+                continue;
+            }
+
+            let unit = match character {
+                'h' => 60 * 60,
+                'm' => 60,
+                's' => 1,
+                _ => return Err(DurationError::UnknownUnit(character)),
+            };
+
+            if number.is_empty() {
+                return Err(DurationError::MissingNumber(character));
+            }
+
+            // Only fails for numbers which are too large
+            let amount: u64 = number.parse().map_err(|_| DurationError::Invalid)?;
+
+            seconds = amount
+                .checked_mul(unit)
+                .and_then(|amount| seconds.checked_add(amount))
+                .ok_or(DurationError::Invalid)?;
+            number.clear();
+        }
+
+        if !number.is_empty() || value.is_empty() {
+            return Err(DurationError::Invalid);
+        }
+
+        Ok(Self::from_secs(seconds))
+    }
+}
+
+impl Display for HumanDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let seconds = self.as_secs();
+
+        if seconds == 0 {
+            return write!(f, "0s");
+        }
+
+        let (hours, minutes, seconds) = (seconds / 3600, seconds % 3600 / 60, seconds % 60);
+
+        if hours > 0 {
+            write!(f, "{}h", hours)?;
+        }
+
+        if minutes > 0 {
+            write!(f, "{}m", minutes)?;
+        }
+
+        if seconds > 0 {
+            write!(f, "{}s", seconds)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Serialize for HumanDuration {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(self.as_secs())
+    }
+}
+
+impl<'de> Deserialize<'de> for HumanDuration {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct HumanDurationVisitor;
+
+        impl Visitor<'_> for HumanDurationVisitor {
+            type Value = HumanDuration;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(
+                    f,
+                    "a number of seconds or a duration like 25m, 1h15m or 90s"
+                )
+            }
+
+            fn visit_u64<E: de::Error>(self, seconds: u64) -> Result<Self::Value, E> {
+                Ok(HumanDuration::from_secs(seconds))
+            }
+
+            fn visit_i64<E: de::Error>(self, seconds: i64) -> Result<Self::Value, E> {
+                u64::try_from(seconds)
+                    .map(HumanDuration::from_secs)
+                    .map_err(|_| E::invalid_value(de::Unexpected::Signed(seconds), &self))
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                value.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_any(HumanDurationVisitor)
+    }
+}
+
+/// Deserializes a number of seconds from a [HumanDuration]
+/// (for use with `#[serde(deserialize_with = "...")]`)
+pub fn deserialize_seconds<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    HumanDuration::deserialize(deserializer).map(|duration| duration.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(value: &str) -> Result<u64, DurationError> {
+        value
+            .parse::<HumanDuration>()
+            .map(|duration| duration.as_secs())
+    }
+
+    #[test]
+    fn parses_each_unit() {
+        assert_eq!(parse("2h"), Ok(2 * 60 * 60));
+        assert_eq!(parse("25m"), Ok(25 * 60));
+        assert_eq!(parse("90s"), Ok(90));
+    }
+
+    #[test]
+    fn parses_plain_numbers_as_seconds() {
+        assert_eq!(parse("25"), Ok(25));
+        assert_eq!(parse(" 0 "), Ok(0));
+    }
+
+    #[test]
+    fn parses_compound_durations() {
+        assert_eq!(parse("1h15m"), Ok(75 * 60));
+        assert_eq!(parse("1h 15m 30s"), Ok(75 * 60 + 30));
+        assert_eq!(parse("90m90s"), Ok(91 * 60 + 30));
+    }
+
+    #[test]
+    fn rejects_invalid_durations() {
+        assert_eq!(parse(""), Err(DurationError::Invalid));
+        assert_eq!(parse("   "), Err(DurationError::Invalid));
+        assert_eq!(parse("25x"), Err(DurationError::UnknownUnit('x')));
+        assert_eq!(parse("-5m"), Err(DurationError::UnknownUnit('-')));
+        assert_eq!(parse("m"), Err(DurationError::MissingNumber('m')));
+        assert_eq!(parse("1h15"), Err(DurationError::Invalid));
+    }
+
+    #[test]
+    fn rejects_durations_which_are_too_long() {
+        assert_eq!(parse("99999999999999999999s"), Err(DurationError::Invalid));
+        assert_eq!(
+            parse(&format!("{}h", u64::MAX / 60)),
+            Err(DurationError::Invalid)
+        );
+        assert_eq!(
+            parse(&format!("{}s1s", u64::MAX)),
+            Err(DurationError::Invalid)
+        );
+    }
+
+    #[test]
+    fn displays_durations_which_can_be_parsed_again() {
+        for seconds in [0, 1, 59, 60, 61, 3599, 3600, 3661, 90_000] {
+            let duration = HumanDuration::from_secs(seconds);
+
+            assert_eq!(duration.to_string().parse(), Ok(duration));
+        }
+
+        assert_eq!(HumanDuration::from_secs(0).to_string(), "0s");
+        assert_eq!(HumanDuration::from_secs(4530).to_string(), "1h15m30s");
+    }
+}
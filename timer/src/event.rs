@@ -0,0 +1,386 @@
+//! State transitions of a [TickingPomodoroTimer], which can be observed by any number of
+//! subscribers.
+//!
+//! Unlike the callbacks of [PomodoroTimer](crate::pomodoro_timer::PomodoroTimer), observers
+//! are `Send`: an [ObservedTimer] can be moved to another thread (or a multi-threaded
+//! runtime) and each subscriber receives the [PomodoroEvent]s on a channel of its own, which
+//! can be read from wherever it is needed.
+//!
+//! ## Example
+//!
+//! ```
+//! use std::thread;
+//! use zentime_rs_timer::config::PomodoroTimerConfig;
+//! use zentime_rs_timer::event::{ObservedTimer, PomodoroEvent};
+//! use zentime_rs_timer::pomodoro_timer_action::PomodoroTimerAction;
+//! use zentime_rs_timer::ticking_pomodoro_timer::TickingPomodoroTimer;
+//!
+//! let mut timer = ObservedTimer::new(TickingPomodoroTimer::new(PomodoroTimerConfig::default()));
+//! let events = timer.subscribe();
+//! let statistics = timer.subscribe();
+//!
+//! let timer_thread = thread::spawn(move || {
+//!     timer.handle_action(PomodoroTimerAction::PlayPause).unwrap();
+//!     timer.handle_action(PomodoroTimerAction::Skip).unwrap();
+//! });
+//!
+//! let statistics_thread = thread::spawn(move || {
+//!     statistics
+//!         .iter()
+//!         .filter(|event| matches!(event, PomodoroEvent::IntervalEnded { .. }))
+//!         .count()
+//! });
+//!
+//! timer_thread.join().unwrap();
+//!
+//! let events: Vec<_> = events.iter().collect();
+//! assert_eq!(
+//!     events,
+//!     vec![
+//!         PomodoroEvent::Resumed,
+//!         PomodoroEvent::IntervalEnded { round: 1, completed: false },
+//!         PomodoroEvent::BreakStarted { round: 1, long: false },
+//!     ]
+//! );
+//! assert_eq!(statistics_thread.join().unwrap(), 1);
+//! ```
+
+use std::fmt::Debug;
+use std::ops::Deref;
+use std::sync::mpsc::{channel, Receiver, Sender};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::PomodoroTimerConfig;
+use crate::pomodoro_timer_action::PomodoroTimerAction;
+use crate::ticking_pomodoro_timer::{ActionError, TickingPomodoroTimer, TimerEnd};
+use crate::transition_policy::{ClassicPomodoro, TransitionPolicy};
+
+/// State transition of a pomodoro timer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PomodoroEvent {
+    /// A focus interval has been entered.
+    /// It waits to be started, unless it is started right away (see [PomodoroEvent::Resumed]).
+    IntervalStarted {
+        /// Round of the interval
+        round: u64,
+    },
+
+    /// A focus interval has been left
+    IntervalEnded {
+        /// Round of the interval
+        round: u64,
+
+        /// Denotes if the interval has run out (instead of being skipped)
+        completed: bool,
+    },
+
+    /// A break has been entered (incl. a break whose postponement is over).
+    /// It waits to be started, unless it is started right away (see [PomodoroEvent::Resumed]).
+    BreakStarted {
+        /// Round the break belongs to
+        round: u64,
+
+        /// Denotes if this is a long break
+        long: bool,
+    },
+
+    /// A break has been left
+    BreakEnded {
+        /// Round the break belongs to
+        round: u64,
+
+        /// Denotes if this has been a long break
+        long: bool,
+
+        /// Denotes if the break has run out (instead of being skipped)
+        completed: bool,
+    },
+
+    /// The current break has been postponed
+    Postponed {
+        /// Time until the break starts in seconds
+        seconds: u64,
+
+        /// How often the break has been postponed by now
+        postponed_count: u16,
+
+        /// Denotes if a long break has been postponed
+        long: bool,
+    },
+
+    /// The timer of the current phase has been paused
+    Paused,
+
+    /// The timer of the current phase has been started or continued
+    Resumed,
+
+    /// The current phase has been given more time
+    Extended {
+        /// Added time in seconds
+        seconds: u64,
+    },
+
+    /// Time has been taken from the current phase
+    Reduced {
+        /// Removed time in seconds
+        seconds: u64,
+    },
+
+    /// The timer has started over with the first round (followed by
+    /// [PomodoroEvent::IntervalStarted])
+    Reset,
+}
+
+/// Receives the [PomodoroEvent]s of an [ObservedTimer]
+pub trait PomodoroObserver: Send {
+    /// Handles an event.
+    /// Returns `false` once the observer is not interested in any further events (e.g. because
+    /// the receiving end of its channel has been dropped), which unsubscribes it.
+    fn notify(&mut self, event: &PomodoroEvent) -> bool;
+}
+
+impl PomodoroObserver for Sender<PomodoroEvent> {
+    fn notify(&mut self, event: &PomodoroEvent) -> bool {
+        self.send(*event).is_ok()
+    }
+}
+
+#[cfg(feature = "async")]
+impl PomodoroObserver for tokio::sync::mpsc::UnboundedSender<PomodoroEvent> {
+    fn notify(&mut self, event: &PomodoroEvent) -> bool {
+        self.send(*event).is_ok()
+    }
+}
+
+impl<F: FnMut(&PomodoroEvent) + Send> PomodoroObserver for F {
+    fn notify(&mut self, event: &PomodoroEvent) -> bool {
+        self(event);
+        true
+    }
+}
+
+/// Observers which are notified of each event in the order they have been added
+#[derive(Default)]
+pub struct Observers {
+    observers: Vec<Box<dyn PomodoroObserver>>,
+}
+
+impl Debug for Observers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Observers")
+            .field("count", &self.observers.len())
+            .finish()
+    }
+}
+
+impl Observers {
+    /// Adds an observer
+    pub fn observe(&mut self, observer: impl PomodoroObserver + 'static) {
+        self.observers.push(Box::new(observer));
+    }
+
+    /// Returns a channel, which receives all events from now on
+    pub fn subscribe(&mut self) -> Receiver<PomodoroEvent> {
+        let (sender, receiver) = channel();
+        self.observe(sender);
+        receiver
+    }
+
+    /// Hands the events over to all observers (and drops those which are not interested
+    /// anymore)
+    pub fn notify(&mut self, events: &[PomodoroEvent]) {
+        for event in events {
+            self.observers.retain_mut(|observer| observer.notify(event));
+        }
+    }
+}
+
+/// [TickingPomodoroTimer] which reports its state transitions to [Observers].
+/// It is driven like the wrapped timer, via [Self::tick()] and [Self::handle_action()], and
+/// dereferences to it for everything which only reads its state.
+#[derive(Debug)]
+pub struct ObservedTimer<P = ClassicPomodoro> {
+    timer: TickingPomodoroTimer<P>,
+    observers: Observers,
+}
+
+impl<P: TransitionPolicy> ObservedTimer<P> {
+    /// Wraps a timer, which has no observers yet
+    pub fn new(timer: TickingPomodoroTimer<P>) -> Self {
+        Self {
+            timer,
+            observers: Observers::default(),
+        }
+    }
+
+    /// Unwraps the timer and drops all observers
+    pub fn into_inner(self) -> TickingPomodoroTimer<P> {
+        self.timer
+    }
+
+    /// Adds an observer, see [Observers::observe()]
+    pub fn observe(&mut self, observer: impl PomodoroObserver + 'static) {
+        self.observers.observe(observer);
+    }
+
+    /// Returns a channel which receives all events from now on, see [Observers::subscribe()]
+    pub fn subscribe(&mut self) -> Receiver<PomodoroEvent> {
+        self.observers.subscribe()
+    }
+
+    /// Advances the timer, see [TickingPomodoroTimer::tick()]
+    pub fn tick(&mut self) -> Option<TimerEnd> {
+        let mut events = vec![];
+        let timer_end = self.timer.tick_with_events(&mut events);
+        self.observers.notify(&events);
+
+        timer_end
+    }
+
+    /// Applies an action to the timer, see [TickingPomodoroTimer::handle_action()]
+    pub fn handle_action(&mut self, action: PomodoroTimerAction) -> Result<(), ActionError> {
+        let mut events = vec![];
+        let result = self.timer.handle_action_with_events(action, &mut events);
+        self.observers.notify(&events);
+
+        result
+    }
+
+    /// Applies multiple actions in the given order, see
+    /// [TickingPomodoroTimer::handle_actions()]
+    pub fn handle_actions(
+        &mut self,
+        actions: impl IntoIterator<Item = PomodoroTimerAction>,
+    ) -> Vec<ActionError> {
+        actions
+            .into_iter()
+            .filter_map(|action| self.handle_action(action).err())
+            .collect()
+    }
+
+    /// Restarts the timer of the current phase, see [TickingPomodoroTimer::restart_phase()]
+    pub fn restart_phase(&mut self) {
+        let mut events = vec![];
+        self.timer.restart_phase_with_events(&mut events);
+        self.observers.notify(&events);
+    }
+
+    /// Updates the configuration, see [TickingPomodoroTimer::set_config()]
+    pub fn set_config(&mut self, config: PomodoroTimerConfig) {
+        self.timer.set_config(config);
+    }
+
+    /// Replaces the transition policy, see [TickingPomodoroTimer::set_policy()]
+    pub fn set_policy(&mut self, policy: P) {
+        self.timer.set_policy(policy);
+    }
+}
+
+impl<P> Deref for ObservedTimer<P> {
+    type Target = TickingPomodoroTimer<P>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.timer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::thread;
+
+    use super::*;
+    use crate::pomodoro_timer::PomodoroTimer;
+
+    #[test]
+    fn callbacks_of_pomodoro_timers_can_be_sent_to_other_threads() {
+        let (sender, receiver) = channel();
+        let timer = PomodoroTimer::new(
+            Default::default(),
+            Arc::new(|_, _, _| {}),
+            Arc::new(move |_| {
+                sender.send(()).ok();
+                None
+            }),
+        );
+
+        thread::spawn(move || drop(timer)).join().unwrap();
+
+        assert!(receiver.recv().is_err());
+    }
+
+    #[test]
+    fn subscribers_on_other_threads_receive_all_events() {
+        let mut timer = ObservedTimer::new(TickingPomodoroTimer::new(Default::default()));
+        let subscribers: Vec<_> = (0..3)
+            .map(|_| {
+                let events = timer.subscribe();
+                thread::spawn(move || events.iter().collect::<Vec<_>>())
+            })
+            .collect();
+
+        let timer_thread = thread::spawn(move || {
+            timer.handle_action(PomodoroTimerAction::PlayPause).unwrap();
+            timer.handle_action(PomodoroTimerAction::Skip).unwrap();
+            timer.handle_action(PomodoroTimerAction::Skip).unwrap();
+        });
+        timer_thread.join().unwrap();
+
+        let expected = vec![
+            PomodoroEvent::Resumed,
+            PomodoroEvent::IntervalEnded {
+                round: 1,
+                completed: false,
+            },
+            PomodoroEvent::BreakStarted {
+                round: 1,
+                long: false,
+            },
+            PomodoroEvent::BreakEnded {
+                round: 1,
+                long: false,
+                completed: false,
+            },
+            PomodoroEvent::IntervalStarted { round: 2 },
+        ];
+        for subscriber in subscribers {
+            assert_eq!(subscriber.join().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn observers_are_called_from_the_thread_of_the_timer() {
+        let (sender, receiver) = channel();
+        let mut timer = ObservedTimer::new(TickingPomodoroTimer::new(Default::default()));
+        timer.observe(move |event: &PomodoroEvent| {
+            sender.send((thread::current().id(), *event)).unwrap();
+        });
+
+        let timer_thread = thread::spawn(move || {
+            timer.handle_action(PomodoroTimerAction::PlayPause).unwrap();
+            thread::current().id()
+        });
+        let timer_thread_id = timer_thread.join().unwrap();
+
+        assert_eq!(
+            receiver.iter().collect::<Vec<_>>(),
+            vec![(timer_thread_id, PomodoroEvent::Resumed)]
+        );
+    }
+
+    #[test]
+    fn drops_subscribers_whose_receiver_is_gone() {
+        let mut timer = ObservedTimer::new(TickingPomodoroTimer::new(Default::default()));
+        let events = timer.subscribe();
+        drop(timer.subscribe());
+
+        timer.handle_action(PomodoroTimerAction::PlayPause).unwrap();
+
+        assert_eq!(timer.observers.observers.len(), 1);
+        assert_eq!(
+            events.try_iter().collect::<Vec<_>>(),
+            vec![PomodoroEvent::Resumed]
+        );
+    }
+}
@@ -7,13 +7,23 @@
 //! Pomodoro/Productivity timer that can transition between various states ([Paused]/[Running]),
 //! tracks intervals and can be configured.
 //!
+//! The example below runs the blocking, callback based [PomodoroTimer](pomodoro_timer::PomodoroTimer)
+//! in a thread of its own. Async code can use the stream of the `timer_stream` module instead
+//! (requires the `async` feature), which runs the timer directly on a tokio runtime.
+//!
+//! The callbacks of [PomodoroTimer](pomodoro_timer::PomodoroTimer) are `Send + Sync`, so the
+//! timer can be created on one thread and run on another. Embedders which want to react to
+//! state transitions from other threads (or with more than one consumer) should subscribe to
+//! the [PomodoroEvent](event::PomodoroEvent)s of an [ObservedTimer](event::ObservedTimer)
+//! instead.
+//!
 //! ## Example
 //!
 //! ```
 //! use std::sync::mpsc::{self, RecvTimeoutError};
 //! use std::sync::mpsc::{Receiver, Sender};
 //! use std::thread;
-//! use std::rc::Rc;
+//! use std::sync::{Arc, Mutex};
 //! use std::time::Duration;
 //! use zentime_rs_timer::config::PomodoroTimerConfig;
 //! use zentime_rs_timer::pomodoro_timer_action::PomodoroTimerAction;
@@ -25,18 +35,23 @@
 //!         mpsc::channel();
 //!
 //!     let config = PomodoroTimerConfig::default();
+//!     // Callbacks have to be `Sync`, which the receiving end of a channel is not
+//!     let terminal_input_receiver = Mutex::new(terminal_input_receiver);
 //!
 //!     // Run timer in its own thread so it does not block the current one
 //!     thread::spawn(move || {
 //!         let timer = PomodoroTimer::new(
 //!             config,
-//!             Rc::new(move |state, msg, _| {
+//!             Arc::new(move |state, msg, _| {
 //!                 println!("{} {}", state.round, msg.unwrap());
 //!             }),
-//!             Rc::new(move |view_state| -> Option<PomodoroTimerAction> {
+//!             Arc::new(move |view_state| -> Option<PomodoroTimerAction> {
 //!                 view_sender.send(view_state).unwrap();
 //!
-//!                 let input = terminal_input_receiver.recv_timeout(Duration::from_millis(100));
+//!                 let input = terminal_input_receiver
+//!                     .lock()
+//!                     .unwrap()
+//!                     .recv_timeout(Duration::from_millis(100));
 //!
 //!                 match input {
 //!                     Ok(action) => Some(action),
@@ -70,9 +85,21 @@
 pub use timer::Timer;
 pub use timer_action::TimerAction;
 
+pub mod clock;
 pub mod config;
+pub mod countdown;
+pub mod duration;
+pub mod event;
 pub mod pomodoro_timer;
 pub mod pomodoro_timer_action;
+pub mod sequence;
+pub mod status_template;
+pub mod stopwatch;
+pub mod ticking_pomodoro_timer;
+pub mod ticking_timer;
 pub mod timer;
 pub mod timer_action;
+#[cfg(feature = "async")]
+pub mod timer_stream;
+pub mod transition_policy;
 pub mod util;
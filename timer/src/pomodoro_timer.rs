@@ -20,4 +20,7 @@ mod short_break;
 mod state;
 
 pub use on_end_handler::TimerKind;
-pub use state::{PomodoroTimer, ViewState};
+pub use state::{
+    CountdownState, DailyStats, HoldState, NamedTimerState, PomodoroTimer, PomodoroTimerState,
+    ReminderState, StopwatchState, TimerProgress, ViewState,
+};
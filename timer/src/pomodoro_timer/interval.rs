@@ -43,6 +43,17 @@ impl TimerTickHandler for IntervalTickHandler {
             round: state.round,
             time: status.current_time.to_string(),
             is_paused: status.is_paused,
+            hold: None,
+            reminder: None,
+            task: None,
+            profile: None,
+            banner: None,
+            today: None,
+            progress: None,
+            stopwatch: None,
+            pause_reason: None,
+            timers: vec![],
+            countdowns: vec![],
         });
 
         if let Some(action) = result {
@@ -80,9 +91,23 @@ impl PomodoroTimer<Interval> {
     /// The internal [Timer] will be initialized in a paused state, waiting for
     /// a [TimerAction:PlayPause]-action (triggered in turn by a [PomodoroTimerAction::PlayPause])
     pub fn init(self) {
-        let is_major_break = self.shared_state.round % self.config.intervals == 0;
+        self.run(false)
+    }
+
+    /// Runs the timer of an interval which follows a break - unlike [Self::init()] the timer
+    /// starts counting right away, if [PomodoroTimerConfig::auto_start_work] is set
+    pub(crate) fn init_after_break(self) {
+        let auto_start = self.config.auto_start_work;
+        self.run(auto_start)
+    }
+
+    fn run(self, auto_start: bool) {
+        let is_major_break = self
+            .shared_state
+            .round
+            .is_multiple_of(self.config.intervals);
 
-        Timer::<Paused>::new(
+        let timer = Timer::<Paused>::new(
             self.config.timer,
             Some(OnEndHandler {
                 on_timer_end: self.callbacks.on_timer_end.clone(),
@@ -94,7 +119,16 @@ impl PomodoroTimer<Interval> {
                 pomodoro_timer: self.clone(),
             }),
         )
-        .init();
+        .with_time_jump_policy(
+            self.config.time_jump_policy,
+            self.config.time_jump_threshold(),
+        );
+
+        if auto_start {
+            timer.start();
+        } else {
+            timer.init();
+        }
 
         Self::next(
             self.config,
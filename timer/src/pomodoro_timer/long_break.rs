@@ -47,13 +47,16 @@ impl PomodoroActionHandler<LongBreak> for LongBreakTickHandler {
         };
 
         match action {
-            PomodoroTimerAction::PostponeBreak if Self::can_postpone(postpone_config) => {
+            PomodoroTimerAction::PostponeBreak { seconds }
+                if Self::can_postpone(postpone_config) && seconds != Some(0) =>
+            {
                 let state = PomodoroTimerState {
                     postponed_count: state.postponed_count + 1,
                     ..state
                 };
 
-                PomodoroTimer::<LongBreak>::postpone(config, callbacks, state);
+                let seconds = seconds.unwrap_or(config.postpone_timer);
+                PomodoroTimer::<LongBreak>::postpone(config, callbacks, state, seconds);
 
                 None
             }
@@ -82,6 +85,17 @@ impl TimerTickHandler for LongBreakTickHandler {
             round: state.round,
             time: status.current_time.to_string(),
             is_paused: status.is_paused,
+            hold: None,
+            reminder: None,
+            task: None,
+            profile: None,
+            banner: None,
+            today: None,
+            progress: None,
+            stopwatch: None,
+            pause_reason: None,
+            timers: vec![],
+            countdowns: vec![],
         });
 
         if let Some(action) = result {
@@ -100,7 +114,7 @@ impl PomodoroTimer<LongBreak> {
             postponed_count: self.shared_state.postponed_count,
         };
 
-        Timer::<Paused>::new(
+        let timer = Timer::<Paused>::new(
             self.config.major_break,
             Some(OnEndHandler {
                 on_timer_end: self.callbacks.on_timer_end.clone(),
@@ -112,7 +126,16 @@ impl PomodoroTimer<LongBreak> {
                 pomodoro_timer: self.clone(),
             }),
         )
-        .init();
+        .with_time_jump_policy(
+            self.config.time_jump_policy,
+            self.config.time_jump_threshold(),
+        );
+
+        if self.config.auto_start_break {
+            timer.start();
+        } else {
+            timer.init();
+        }
 
         Self::next(self.config, self.callbacks, next_shared_state)
     }
@@ -121,6 +144,7 @@ impl PomodoroTimer<LongBreak> {
         config: PomodoroTimerConfig,
         callbacks: Callbacks,
         shared_state: PomodoroTimerState,
+        seconds: u64,
     ) {
         PomodoroTimer {
             shared_state,
@@ -128,7 +152,7 @@ impl PomodoroTimer<LongBreak> {
             callbacks,
             marker: PhantomData::<PostponedLongBreak>,
         }
-        .init();
+        .init(seconds);
     }
 
     fn next(config: PomodoroTimerConfig, callbacks: Callbacks, shared_state: PomodoroTimerState) {
@@ -138,6 +162,6 @@ impl PomodoroTimer<LongBreak> {
             callbacks,
             marker: PhantomData::<Interval>,
         }
-        .init();
+        .init_after_break();
     }
 }
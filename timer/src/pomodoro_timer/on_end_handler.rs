@@ -1,4 +1,4 @@
-use std::rc::Rc;
+use std::sync::Arc;
 
 use crate::timer::TimerEndHandler;
 
@@ -14,7 +14,7 @@ pub enum TimerKind {
     Break,
 }
 
-pub type OnTimerEnd = Rc<dyn Fn(PomodoroTimerState, Option<&str>, TimerKind)>;
+pub type OnTimerEnd = Arc<dyn Fn(PomodoroTimerState, Option<&str>, TimerKind) + Send + Sync>;
 
 /// Handler which is passed to our timer implementation
 pub struct OnEndHandler {
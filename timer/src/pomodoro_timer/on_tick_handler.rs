@@ -1,4 +1,4 @@
-use std::rc::Rc;
+use std::sync::Arc;
 
 use crate::{pomodoro_timer_action::PomodoroTimerAction, TimerAction};
 
@@ -7,7 +7,7 @@ use super::{
     state::{PomodoroState, PomodoroTimer, ViewState},
 };
 
-pub type OnTick = Rc<dyn Fn(ViewState) -> Option<PomodoroTimerAction>>;
+pub type OnTick = Arc<dyn Fn(ViewState) -> Option<PomodoroTimerAction> + Send + Sync>;
 
 pub struct PostponeHandlerConfig {
     pub postpone_limit: u16,
@@ -37,6 +37,16 @@ pub trait PomodoroActionHandler<S: PomodoroState> {
                 None
             }
 
+            PomodoroTimerAction::LongBreakNow => {
+                PomodoroTimer::<Interval>::long_break_now(
+                    timer.config,
+                    timer.callbacks,
+                    timer.shared_state,
+                )
+                .init();
+                None
+            }
+
             _ => None,
         }
     }
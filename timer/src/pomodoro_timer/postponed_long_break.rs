@@ -42,6 +42,17 @@ impl TimerTickHandler for PostponeLongBreakTickHandler {
             round: state.round,
             time: status.current_time.to_string(),
             is_paused: status.is_paused,
+            hold: None,
+            reminder: None,
+            task: None,
+            profile: None,
+            banner: None,
+            today: None,
+            progress: None,
+            stopwatch: None,
+            pause_reason: None,
+            timers: vec![],
+            countdowns: vec![],
         });
 
         if let Some(action) = result {
@@ -53,10 +64,11 @@ impl TimerTickHandler for PostponeLongBreakTickHandler {
 }
 
 impl PomodoroTimer<PostponedLongBreak> {
-    /// Starts the timer loop on a `PomodoroTimer<PostponedLongBreak>`
-    pub fn init(self) {
+    /// Starts the timer loop on a `PomodoroTimer<PostponedLongBreak>`, which runs for the
+    /// given number of seconds
+    pub fn init(self, seconds: u64) {
         Timer::<Running>::new(
-            self.config.postpone_timer,
+            seconds,
             Some(OnEndHandler {
                 on_timer_end: self.callbacks.on_timer_end.clone(),
                 state: self.shared_state,
@@ -67,6 +79,10 @@ impl PomodoroTimer<PostponedLongBreak> {
                 pomodoro_timer: self.clone(),
             }),
         )
+        .with_time_jump_policy(
+            self.config.time_jump_policy,
+            self.config.time_jump_threshold(),
+        )
         .init();
 
         Self::next(self.config, self.callbacks, self.shared_state)
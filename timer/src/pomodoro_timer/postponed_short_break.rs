@@ -42,6 +42,17 @@ impl TimerTickHandler for PostponeShortBreakTickHandler {
             round: state.round,
             time: status.current_time.to_string(),
             is_paused: status.is_paused,
+            hold: None,
+            reminder: None,
+            task: None,
+            profile: None,
+            banner: None,
+            today: None,
+            progress: None,
+            stopwatch: None,
+            pause_reason: None,
+            timers: vec![],
+            countdowns: vec![],
         });
 
         if let Some(action) = result {
@@ -53,9 +64,9 @@ impl TimerTickHandler for PostponeShortBreakTickHandler {
 }
 
 impl PomodoroTimer<PostponedShortBreak> {
-    pub(crate) fn init(self) {
+    pub(crate) fn init(self, seconds: u64) {
         Timer::<Running>::new(
-            self.config.postpone_timer,
+            seconds,
             Some(OnEndHandler {
                 on_timer_end: self.callbacks.on_timer_end.clone(),
                 state: self.shared_state,
@@ -66,6 +77,10 @@ impl PomodoroTimer<PostponedShortBreak> {
                 pomodoro_timer: self.clone(),
             }),
         )
+        .with_time_jump_policy(
+            self.config.time_jump_policy,
+            self.config.time_jump_threshold(),
+        )
         .init();
 
         Self::next(self.config, self.callbacks, self.shared_state)
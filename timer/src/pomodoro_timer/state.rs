@@ -1,14 +1,20 @@
-use super::{interval::Interval, on_end_handler::OnTimerEnd, on_tick_handler::OnTick};
+use super::{
+    interval::Interval, long_break::LongBreak, on_end_handler::OnTimerEnd, on_tick_handler::OnTick,
+};
 use crate::config::PomodoroTimerConfig;
 use serde::{Deserialize, Serialize};
-use std::{fmt::Debug, marker::PhantomData};
+use std::{
+    fmt::{Debug, Display},
+    marker::PhantomData,
+    time::Duration,
+};
 
 /// General trait describing the various states a pomodoro timer can be in
 pub trait PomodoroState {}
 
 /// State which is shared between timers when they transition from one timer state to the
 /// next. Some of its information is also being shared with the [OnTick] closure.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct PomodoroTimerState {
     /// The current pomodoro round.
     /// This is incremented after each break.
@@ -20,7 +26,8 @@ pub struct PomodoroTimerState {
 }
 
 /// Information that will be handed to the [on_tick] closure continously
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ViewState {
     /// Denotes if the current timer is a break timer
     pub is_break: bool,
@@ -39,6 +46,231 @@ pub struct ViewState {
 
     /// Denotes if the timer is currently paused
     pub is_paused: bool,
+
+    /// Set while the pomodoro cycle is on hold (e.g. during a meeting).
+    /// Timers never set this themselves - it is added by the zentime server.
+    #[serde(default)]
+    pub hold: Option<HoldState>,
+
+    /// Set while a reminder (e.g. a micro-break to rest the eyes) is displayed, which is
+    /// independent of intervals and breaks.
+    /// Timers never set this themselves - it is added by the zentime server.
+    #[serde(default)]
+    pub reminder: Option<ReminderState>,
+
+    /// Label of the current session (e.g. the task which is worked on).
+    /// Timers never set this themselves - it is added by the zentime server.
+    #[serde(default)]
+    pub task: Option<String>,
+
+    /// Name of the timer profile the lengths of intervals and breaks are taken from
+    /// (e.g. "deep-work").
+    /// Timers never set this themselves - it is added by the zentime server.
+    #[serde(default)]
+    pub profile: Option<String>,
+
+    /// Message which is shown until the next interval is started (e.g. a summary of the
+    /// completed cycle).
+    /// Timers never set this themselves - it is added by the zentime server.
+    #[serde(default)]
+    pub banner: Option<String>,
+
+    /// Statistics of the current day, which are updated whenever an interval has ended.
+    /// Timers never set this themselves - it is added by the zentime server.
+    #[serde(default)]
+    pub today: Option<DailyStats>,
+
+    /// Exact progress of the current interval/break, so that clients can count down on their
+    /// own between two states.
+    /// Timers never set this themselves (it changes on every tick) - it is added by the zentime
+    /// server to the states it sends.
+    #[serde(default)]
+    pub progress: Option<TimerProgress>,
+
+    /// Set if the state is the one of a [Stopwatch](crate::stopwatch::Stopwatch), whose
+    /// [time](Self::time) is the elapsed instead of the remaining time
+    #[serde(default)]
+    pub stopwatch: Option<StopwatchState>,
+
+    /// Why the timer has been paused, if the server has paused it on its own (e.g. `idle`
+    /// while the user is away).
+    /// Timers never set this themselves - it is added by the zentime server.
+    #[serde(default)]
+    pub pause_reason: Option<String>,
+
+    /// Further timers (e.g. a one-off countdown for the tea), which run next to this one.
+    /// Timers never set this themselves - it is added by the zentime server.
+    #[serde(default)]
+    pub timers: Vec<NamedTimerState>,
+
+    /// One-off countdowns, which are pending (e.g. `zentime countdown 15m standup`). They
+    /// can't be controlled, they only end with a notification.
+    /// Timers never set this themselves - it is added by the zentime server.
+    #[serde(default)]
+    pub countdowns: Vec<CountdownState>,
+}
+
+impl ViewState {
+    /// Denotes if both states only differ in their remaining time (incl. the remaining time
+    /// of holds and reminders), which keeps changing while the timer is running
+    pub fn differs_only_in_time(&self, other: &ViewState) -> bool {
+        self.is_break == other.is_break
+            && self.is_postponed == other.is_postponed
+            && self.postpone_count == other.postpone_count
+            && self.round == other.round
+            && self.is_paused == other.is_paused
+            && self.hold.as_ref().map(|hold| &hold.reason)
+                == other.hold.as_ref().map(|hold| &hold.reason)
+            && self.reminder.as_ref().map(|reminder| &reminder.name)
+                == other.reminder.as_ref().map(|reminder| &reminder.name)
+            && self.task == other.task
+            && self.banner == other.banner
+            && self.today == other.today
+            && self.stopwatch == other.stopwatch
+            && self.pause_reason == other.pause_reason
+            && self.timers.len() == other.timers.len()
+            && self.timers.iter().zip(&other.timers).all(|(timer, other)| {
+                timer.name == other.name
+                    && timer.is_paused == other.is_paused
+                    && timer.is_selected == other.is_selected
+            })
+            && self
+                .countdowns
+                .iter()
+                .map(|countdown| &countdown.label)
+                .eq(other.countdowns.iter().map(|countdown| &countdown.label))
+    }
+
+    /// Named timer which has been selected instead of this one - [None] while this one is
+    /// selected
+    pub fn selected_timer(&self) -> Option<&NamedTimerState> {
+        self.timers.iter().find(|timer| timer.is_selected)
+    }
+}
+
+/// Statistics of the current day (e.g. for status bars showing "5 today")
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct DailyStats {
+    /// Intervals which have run out today
+    pub intervals: u16,
+
+    /// Focus time of all intervals which have ended today (including skipped ones)
+    pub focused_minutes: u16,
+
+    /// Intervals which should be completed per day - [None] if no daily goal has been set
+    #[serde(default)]
+    pub goal: Option<u16>,
+}
+
+impl DailyStats {
+    /// Denotes if today's intervals have reached the daily goal
+    ///
+    /// ```
+    /// use zentime_rs_timer::pomodoro_timer::DailyStats;
+    ///
+    /// let today = DailyStats { intervals: 8, focused_minutes: 200, goal: Some(8) };
+    /// assert!(today.has_reached_goal());
+    /// assert!(!DailyStats { goal: None, ..today }.has_reached_goal());
+    /// ```
+    pub fn has_reached_goal(&self) -> bool {
+        self.goal.is_some_and(|goal| self.intervals >= goal)
+    }
+}
+
+/// Length of the current interval/break and how much of it has elapsed (in milliseconds)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct TimerProgress {
+    /// Length of the timer (including time which has been added or removed)
+    pub total_millis: u64,
+
+    /// Time which has elapsed so far
+    pub elapsed_millis: u64,
+}
+
+impl TimerProgress {
+    /// Time which is left until the timer runs out
+    pub fn remaining(&self) -> Duration {
+        Duration::from_millis(self.total_millis.saturating_sub(self.elapsed_millis))
+    }
+}
+
+/// Information about a hold of the pomodoro cycle
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct HoldState {
+    /// Reason of the hold (e.g. "meeting") - might be empty
+    pub reason: String,
+
+    /// Time until the cycle automatically resumes
+    pub remaining: String,
+}
+
+impl Display for HoldState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.reason.is_empty() {
+            write!(f, "On hold ({})", self.remaining)
+        } else {
+            write!(f, "On hold: {} ({})", self.reason, self.remaining)
+        }
+    }
+}
+
+/// Laps of a running [Stopwatch](crate::stopwatch::Stopwatch)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct StopwatchState {
+    /// Times of all laps which have been taken so far (e.g. `12:30`), the latest one last
+    pub laps: Vec<String>,
+}
+
+/// State of a named timer, which runs next to the pomodoro timer of the zentime server
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct NamedTimerState {
+    /// Name the timer is addressed by (e.g. "tea")
+    pub name: String,
+
+    /// Remaining time of the timer (e.g. `04:12`)
+    pub time: String,
+
+    /// Denotes if the timer is currently paused
+    pub is_paused: bool,
+
+    /// Denotes if clients display and control this timer instead of the pomodoro timer
+    pub is_selected: bool,
+}
+
+/// State of a pending one-off countdown of the zentime server
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct CountdownState {
+    /// What the countdown is for (e.g. "standup") - [None] if it has been started without label
+    pub label: Option<String>,
+
+    /// Remaining time of the countdown (e.g. `14:12`)
+    pub time: String,
+}
+
+/// Information about a displayed reminder
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ReminderState {
+    /// Name of the reminder (e.g. "micro-break")
+    pub name: String,
+
+    /// Text which is shown for the reminder
+    pub message: String,
+
+    /// Time until the reminder disappears
+    pub remaining: String,
+}
+
+impl Display for ReminderState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.message, self.remaining)
+    }
 }
 
 #[derive(Clone)]
@@ -87,4 +319,21 @@ impl<S: PomodoroState> PomodoroTimer<S> {
     pub fn reset(config: PomodoroTimerConfig, callbacks: Callbacks) -> PomodoroTimer<Interval> {
         PomodoroTimer::new(config, callbacks.on_timer_end, callbacks.on_tick)
     }
+
+    /// Ends the current cycle early and returns a timer for its long break
+    pub fn long_break_now(
+        config: PomodoroTimerConfig,
+        callbacks: Callbacks,
+        shared_state: PomodoroTimerState,
+    ) -> PomodoroTimer<LongBreak> {
+        PomodoroTimer {
+            shared_state: PomodoroTimerState {
+                round: config.last_round_of_cycle(shared_state.round),
+                postponed_count: 0,
+            },
+            config,
+            callbacks,
+            marker: PhantomData,
+        }
+    }
 }
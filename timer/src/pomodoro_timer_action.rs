@@ -1,7 +1,7 @@
 //! Action enum that can be passed to the timer on each tick to interact with it
 
 /// Various control actions to transition into new states
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum PomodoroTimerAction {
     /// NoOp
     None,
@@ -15,6 +15,56 @@ pub enum PomodoroTimerAction {
     /// Reset timer
     ResetTimer,
 
+    /// Go back to the phase before the current one (e.g. after skipping an interval by
+    /// accident), which starts from the beginning.
+    ///
+    /// ```
+    /// use zentime_rs_timer::config::PomodoroTimerConfig;
+    /// use zentime_rs_timer::pomodoro_timer_action::PomodoroTimerAction;
+    /// use zentime_rs_timer::ticking_pomodoro_timer::{ActionError, PomodoroPhase, TickingPomodoroTimer};
+    ///
+    /// let mut timer = TickingPomodoroTimer::new(PomodoroTimerConfig::default());
+    /// timer.handle_action(PomodoroTimerAction::Skip).unwrap();
+    /// timer.handle_action(PomodoroTimerAction::Skip).unwrap();
+    /// assert_eq!(timer.shared_state().round, 2);
+    ///
+    /// timer.handle_action(PomodoroTimerAction::Previous).unwrap();
+    /// assert_eq!(timer.phase(), PomodoroPhase::ShortBreak);
+    /// assert_eq!(timer.shared_state().round, 1);
+    ///
+    /// timer.handle_action(PomodoroTimerAction::Previous).unwrap();
+    /// assert_eq!(
+    ///     timer.handle_action(PomodoroTimerAction::Previous),
+    ///     Err(ActionError::NoPreviousPhase)
+    /// );
+    /// ```
+    Previous,
+
+    /// Start the current interval or break over, without changing the round
+    RestartCurrent,
+
+    /// End the current interval or break and start the long break right away.
+    /// Unlike [Self::Skip] this ends the current cycle early, so that a full cycle follows
+    /// the long break.
+    LongBreakNow,
+
     /// Postpone a break
-    PostponeBreak,
+    PostponeBreak {
+        /// Length of the postponed break in seconds - [None] uses
+        /// [PomodoroTimerConfig::postpone_timer](crate::config::PomodoroTimerConfig::postpone_timer)
+        seconds: Option<u64>,
+    },
+
+    /// Add time to the current interval or break (e.g. "just 5 more minutes")
+    ExtendTime {
+        /// Number of seconds which are added to the remaining time
+        seconds: u64,
+    },
+
+    /// Remove time from the current interval or break, without skipping it entirely.
+    /// If less time remains, the timer runs out on its next tick.
+    ReduceTime {
+        /// Number of seconds which are removed from the remaining time
+        seconds: u64,
+    },
 }
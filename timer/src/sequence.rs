@@ -0,0 +1,475 @@
+//! Custom timer sequences, which replace the fixed pomodoro cycle by a schedule of intervals and
+//! breaks of varying lengths, e.g.
+//!
+//! ```text
+//! ["work:50", "break:10", "work:50", "break:30"]
+//! ```
+//!
+//! Each step consists of its kind (`work` or `break`) and its length in minutes (or a duration
+//! like `25m30s`). Work and breaks have to alternate, starting with work. The last break of the
+//! sequence is the long break, after which the sequence starts over.
+//!
+//! ## Example
+//!
+//! ```
+//! use zentime_rs_timer::config::PomodoroTimerConfig;
+//! use zentime_rs_timer::pomodoro_timer_action::PomodoroTimerAction;
+//! use zentime_rs_timer::sequence::{SequenceTimer, TimerSequence};
+//! use zentime_rs_timer::ticking_pomodoro_timer::PomodoroPhase;
+//!
+//! let sequence = TimerSequence::try_from(["work:50", "break:10", "work:50", "break:30"]).unwrap();
+//! let mut timer = SequenceTimer::from_sequence(PomodoroTimerConfig::default(), sequence);
+//! assert_eq!(timer.view_state().time, "50:00");
+//!
+//! timer.handle_action(PomodoroTimerAction::Skip).unwrap();
+//! assert_eq!(timer.phase(), PomodoroPhase::ShortBreak);
+//! assert_eq!(timer.view_state().time, "10:00");
+//!
+//! timer.handle_action(PomodoroTimerAction::Skip).unwrap();
+//! timer.handle_action(PomodoroTimerAction::Skip).unwrap();
+//! assert_eq!(timer.phase(), PomodoroPhase::LongBreak);
+//! assert_eq!(timer.view_state().time, "30:00");
+//! ```
+
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::PomodoroTimerConfig;
+use crate::duration::{DurationError, HumanDuration};
+use crate::pomodoro_timer::PomodoroTimerState;
+use crate::ticking_pomodoro_timer::{PomodoroPhase, TickingPomodoroTimer};
+use crate::transition_policy::{ClassicPomodoro, Transition, TransitionPolicy};
+
+/// Reason why a timer sequence could not be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SequenceError {
+    /// A step is neither `work` nor `break`
+    UnknownStep(String),
+
+    /// The length of a step is missing or invalid
+    InvalidLength(String, DurationError),
+
+    /// A step lasts zero seconds
+    EmptyStep(String),
+
+    /// Work and breaks don't alternate (or the sequence doesn't start with work)
+    NotAlternating(String),
+
+    /// The sequence contains no step at all or ends without a break
+    Incomplete,
+}
+
+impl Display for SequenceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SequenceError::UnknownStep(step) => {
+                write!(
+                    f,
+                    "Unknown step '{}' (expected e.g. work:50 or break:10)",
+                    step
+                )
+            }
+            SequenceError::InvalidLength(step, error) => {
+                write!(f, "Invalid length of step '{}': {}", step, error)
+            }
+            SequenceError::EmptyStep(step) => write!(f, "Step '{}' has no length", step),
+            SequenceError::NotAlternating(step) => write!(
+                f,
+                "Step '{}' is out of order - work and breaks have to alternate, starting with work",
+                step
+            ),
+            SequenceError::Incomplete => write!(f, "A sequence has to end with a break"),
+        }
+    }
+}
+
+impl Error for SequenceError {}
+
+/// Kind of a step of a [TimerSequence]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepKind {
+    /// Focus interval
+    Work,
+
+    /// Break after an interval
+    Break,
+}
+
+/// Single step of a [TimerSequence], e.g. `work:50`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SequenceStep {
+    /// Whether this is an interval or a break
+    pub kind: StepKind,
+
+    /// Length of the step in seconds
+    pub seconds: u64,
+}
+
+impl FromStr for SequenceStep {
+    type Err = SequenceError;
+
+    /// Parses `<kind>:<length>`, whose length is given in minutes unless it has a unit
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let step = value.trim();
+        let (kind, length) = step
+            .split_once(':')
+            .ok_or_else(|| SequenceError::UnknownStep(step.to_string()))?;
+
+        let kind = match kind.trim() {
+            "work" => StepKind::Work,
+            "break" => StepKind::Break,
+            _ => return Err(SequenceError::UnknownStep(step.to_string())),
+        };
+
+        let length = length.trim();
+        let seconds = match length.parse::<u64>() {
+            Ok(minutes) => minutes.saturating_mul(60),
+            Err(_) => length
+                .parse::<HumanDuration>()
+                .map_err(|error| SequenceError::InvalidLength(step.to_string(), error))?
+                .as_secs(),
+        };
+
+        if seconds == 0 {
+            return Err(SequenceError::EmptyStep(step.to_string()));
+        }
+
+        Ok(Self { kind, seconds })
+    }
+}
+
+impl Display for SequenceStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.kind {
+            StepKind::Work => "work",
+            StepKind::Break => "break",
+        };
+
+        if self.seconds.is_multiple_of(60) {
+            write!(f, "{}:{}", kind, self.seconds / 60)
+        } else {
+            write!(f, "{}:{}", kind, HumanDuration::from_secs(self.seconds))
+        }
+    }
+}
+
+/// Schedule of alternating intervals and breaks, which is walked round by round: the n-th
+/// interval of the sequence is followed by its n-th break. Once the last break (the long break)
+/// is over, the sequence starts over.
+///
+/// As a [TransitionPolicy], the sequence decides the lengths of all intervals and breaks
+/// (only postponed breaks keep the length they have been postponed for).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "Vec<String>", into = "Vec<String>")]
+pub struct TimerSequence {
+    /// Steps of the sequence - cheap to clone, because the sequence travels along with
+    /// every copy of the timer
+    steps: Arc<[SequenceStep]>,
+}
+
+impl TimerSequence {
+    /// Creates a sequence from the given steps.
+    /// Fails if work and breaks don't alternate, starting with work and ending with a break.
+    pub fn new(steps: Vec<SequenceStep>) -> Result<Self, SequenceError> {
+        for (index, step) in steps.iter().enumerate() {
+            let expected = if index % 2 == 0 {
+                StepKind::Work
+            } else {
+                StepKind::Break
+            };
+
+            if step.kind != expected {
+                return Err(SequenceError::NotAlternating(step.to_string()));
+            }
+        }
+
+        if steps.is_empty() || !steps.len().is_multiple_of(2) {
+            return Err(SequenceError::Incomplete);
+        }
+
+        Ok(Self {
+            steps: steps.into(),
+        })
+    }
+
+    /// Steps of the sequence
+    pub fn steps(&self) -> &[SequenceStep] {
+        &self.steps
+    }
+
+    /// Number of intervals in the sequence, after which the sequence starts over
+    pub fn intervals(&self) -> u64 {
+        (self.steps.len() / 2) as u64
+    }
+
+    /// Adapts a timer config to the sequence, so that everything which relies on the config
+    /// (e.g. [PomodoroTimerAction::LongBreakNow](crate::pomodoro_timer_action::PomodoroTimerAction::LongBreakNow))
+    /// matches the sequence: the number of intervals is the one of the sequence, the lengths
+    /// are the ones of its first interval and break and of its long break.
+    pub fn apply(&self, config: PomodoroTimerConfig) -> PomodoroTimerConfig {
+        PomodoroTimerConfig {
+            timer: self.work_seconds(1),
+            minor_break: self.break_seconds(1),
+            major_break: self.break_seconds(self.intervals()),
+            intervals: self.intervals(),
+            ..config
+        }
+    }
+
+    /// Position of the given round within the sequence (starting at 0)
+    fn position(&self, round: u64) -> usize {
+        (round.saturating_sub(1) % self.intervals()) as usize
+    }
+
+    /// Length of the interval of the given round
+    fn work_seconds(&self, round: u64) -> u64 {
+        self.steps[self.position(round) * 2].seconds
+    }
+
+    /// Length of the break of the given round
+    fn break_seconds(&self, round: u64) -> u64 {
+        self.steps[self.position(round) * 2 + 1].seconds
+    }
+}
+
+impl<S: AsRef<str>, const N: usize> TryFrom<[S; N]> for TimerSequence {
+    type Error = SequenceError;
+
+    fn try_from(steps: [S; N]) -> Result<Self, Self::Error> {
+        Self::new(
+            steps
+                .iter()
+                .map(|step| step.as_ref().parse())
+                .collect::<Result<_, _>>()?,
+        )
+    }
+}
+
+impl TryFrom<Vec<String>> for TimerSequence {
+    type Error = SequenceError;
+
+    fn try_from(steps: Vec<String>) -> Result<Self, Self::Error> {
+        Self::new(
+            steps
+                .iter()
+                .map(|step| step.parse())
+                .collect::<Result<_, _>>()?,
+        )
+    }
+}
+
+impl From<TimerSequence> for Vec<String> {
+    fn from(sequence: TimerSequence) -> Self {
+        sequence.steps.iter().map(ToString::to_string).collect()
+    }
+}
+
+impl TransitionPolicy for TimerSequence {
+    fn next(
+        &self,
+        config: &PomodoroTimerConfig,
+        phase: PomodoroPhase,
+        state: PomodoroTimerState,
+    ) -> Transition {
+        // The sequence is walked just like a classic cycle with as many intervals
+        let config = PomodoroTimerConfig {
+            intervals: self.intervals(),
+            ..*config
+        };
+
+        ClassicPomodoro.next(&config, phase, state)
+    }
+
+    fn length(
+        &self,
+        _config: &PomodoroTimerConfig,
+        phase: PomodoroPhase,
+        state: PomodoroTimerState,
+    ) -> u64 {
+        match phase {
+            PomodoroPhase::Interval => self.work_seconds(state.round),
+            PomodoroPhase::ShortBreak => self.break_seconds(state.round),
+            PomodoroPhase::LongBreak => self.break_seconds(self.intervals()),
+            PomodoroPhase::PostponedShortBreak { seconds }
+            | PomodoroPhase::PostponedLongBreak { seconds } => seconds,
+        }
+    }
+}
+
+/// Pomodoro timer, which walks a [TimerSequence] instead of the classic cycle
+pub type SequenceTimer = TickingPomodoroTimer<TimerSequence>;
+
+impl SequenceTimer {
+    /// Creates a new (paused) timer starting with the first interval of the sequence.
+    /// The config is adapted to the sequence (see [TimerSequence::apply()]).
+    pub fn from_sequence(config: PomodoroTimerConfig, sequence: TimerSequence) -> Self {
+        Self::with_policy(sequence.apply(config), sequence)
+    }
+}
+
+/// Schedule of a timer, which runs either the classic pomodoro cycle or a custom sequence
+/// (e.g. depending on the config of an application)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Schedule {
+    /// Classic pomodoro cycle (see [ClassicPomodoro])
+    #[default]
+    Classic,
+
+    /// Custom sequence of intervals and breaks
+    Sequence(TimerSequence),
+}
+
+impl Schedule {
+    /// Adapts a timer config to the schedule (see [TimerSequence::apply()])
+    pub fn apply(&self, config: PomodoroTimerConfig) -> PomodoroTimerConfig {
+        match self {
+            Schedule::Classic => config,
+            Schedule::Sequence(sequence) => sequence.apply(config),
+        }
+    }
+}
+
+impl From<Option<TimerSequence>> for Schedule {
+    fn from(sequence: Option<TimerSequence>) -> Self {
+        sequence.map_or(Schedule::Classic, Schedule::Sequence)
+    }
+}
+
+impl TransitionPolicy for Schedule {
+    fn next(
+        &self,
+        config: &PomodoroTimerConfig,
+        phase: PomodoroPhase,
+        state: PomodoroTimerState,
+    ) -> Transition {
+        match self {
+            Schedule::Classic => ClassicPomodoro.next(config, phase, state),
+            Schedule::Sequence(sequence) => sequence.next(config, phase, state),
+        }
+    }
+
+    fn length(
+        &self,
+        config: &PomodoroTimerConfig,
+        phase: PomodoroPhase,
+        state: PomodoroTimerState,
+    ) -> u64 {
+        match self {
+            Schedule::Classic => ClassicPomodoro.length(config, phase, state),
+            Schedule::Sequence(sequence) => sequence.length(config, phase, state),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pomodoro_timer_action::PomodoroTimerAction;
+
+    fn sequence() -> TimerSequence {
+        TimerSequence::try_from(["work:50", "break:10", "work:30", "break:20m"]).unwrap()
+    }
+
+    #[test]
+    fn parses_steps_in_minutes_or_with_a_unit() {
+        assert_eq!(
+            sequence().steps(),
+            [
+                SequenceStep {
+                    kind: StepKind::Work,
+                    seconds: 50 * 60
+                },
+                SequenceStep {
+                    kind: StepKind::Break,
+                    seconds: 10 * 60
+                },
+                SequenceStep {
+                    kind: StepKind::Work,
+                    seconds: 30 * 60
+                },
+                SequenceStep {
+                    kind: StepKind::Break,
+                    seconds: 20 * 60
+                },
+            ]
+        );
+        assert_eq!(
+            Vec::<String>::from(sequence()),
+            ["work:50", "break:10", "work:30", "break:20"]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_sequences() {
+        assert!(matches!(
+            TimerSequence::try_from(["work:50", "work:50", "break:10"]),
+            Err(SequenceError::NotAlternating(_))
+        ));
+        assert_eq!(
+            TimerSequence::try_from(["work:50"]),
+            Err(SequenceError::Incomplete)
+        );
+        assert!(matches!(
+            TimerSequence::try_from(["work:0", "break:10"]),
+            Err(SequenceError::EmptyStep(_))
+        ));
+        assert!(matches!(
+            TimerSequence::try_from(["nap:20", "break:10"]),
+            Err(SequenceError::UnknownStep(_))
+        ));
+    }
+
+    #[test]
+    fn starts_over_after_the_long_break() {
+        let mut timer = SequenceTimer::from_sequence(PomodoroTimerConfig::default(), sequence());
+        let mut walked = vec![];
+
+        for _ in 0..5 {
+            walked.push((timer.phase(), timer.view_state().time));
+            timer.handle_action(PomodoroTimerAction::Skip).unwrap();
+        }
+
+        assert_eq!(
+            walked,
+            [
+                (PomodoroPhase::Interval, "50:00".to_string()),
+                (PomodoroPhase::ShortBreak, "10:00".to_string()),
+                (PomodoroPhase::Interval, "30:00".to_string()),
+                (PomodoroPhase::LongBreak, "20:00".to_string()),
+                (PomodoroPhase::Interval, "50:00".to_string()),
+            ]
+        );
+        assert_eq!(timer.shared_state().round, 3);
+    }
+
+    #[test]
+    fn goes_back_within_the_sequence() {
+        let mut timer = SequenceTimer::from_sequence(PomodoroTimerConfig::default(), sequence());
+
+        for _ in 0..4 {
+            timer.handle_action(PomodoroTimerAction::Skip).unwrap();
+        }
+
+        timer.handle_action(PomodoroTimerAction::Previous).unwrap();
+        assert_eq!(timer.phase(), PomodoroPhase::LongBreak);
+        assert_eq!(timer.view_state().time, "20:00");
+
+        timer.handle_action(PomodoroTimerAction::Previous).unwrap();
+        assert_eq!(timer.phase(), PomodoroPhase::Interval);
+        assert_eq!(timer.view_state().time, "30:00");
+    }
+
+    #[test]
+    fn adapts_the_config_to_the_sequence() {
+        let config = sequence().apply(PomodoroTimerConfig::default());
+
+        assert_eq!(config.intervals, 2);
+        assert_eq!(config.timer, 50 * 60);
+        assert_eq!(config.minor_break, 10 * 60);
+        assert_eq!(config.major_break, 20 * 60);
+    }
+}
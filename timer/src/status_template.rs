@@ -0,0 +1,253 @@
+//! Templates which format the state of the timer as a single line (e.g. for status bars), like
+//! `{round} {time} {state_icon}`.
+//!
+//! The following placeholders are available:
+//!
+//! * `{time}` - remaining time (of the selected timer, see [ViewState::selected_timer])
+//! * `{round}` - current round
+//! * `{phase}` - `focus`, `break` or `postponed`
+//! * `{break}` - `break` during breaks (empty otherwise)
+//! * `{postpone_count}` - how often the current break has been postponed
+//! * `{paused}` - `paused` while the timer is paused (empty otherwise)
+//! * `{task}` - current task (empty if there is none)
+//! * `{state_icon}` - `⏸` while paused, `☕` during breaks and `🍅` during focus intervals
+//!
+//! Literal braces are written as `{{` and `}}`.
+
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::pomodoro_timer::ViewState;
+
+/// Reason why a template could not be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateError {
+    /// The template contains a placeholder which does not exist
+    UnknownPlaceholder(String),
+
+    /// A `{` is not closed or a `}` has not been opened
+    UnbalancedBrace,
+}
+
+impl Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TemplateError::UnknownPlaceholder(name) => write!(
+                f,
+                "Unknown placeholder '{{{}}}' (available: {})",
+                name,
+                Placeholder::ALL
+                    .iter()
+                    .map(|placeholder| format!("{{{}}}", placeholder.name()))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            TemplateError::UnbalancedBrace => {
+                write!(
+                    f,
+                    "Unbalanced brace - literal braces are written as {{{{ and }}}}"
+                )
+            }
+        }
+    }
+}
+
+impl Error for TemplateError {}
+
+/// Value of the timer state, which is inserted into a template
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Placeholder {
+    Time,
+    Round,
+    Phase,
+    Break,
+    PostponeCount,
+    Paused,
+    Task,
+    StateIcon,
+}
+
+impl Placeholder {
+    const ALL: [Placeholder; 8] = [
+        Placeholder::Time,
+        Placeholder::Round,
+        Placeholder::Phase,
+        Placeholder::Break,
+        Placeholder::PostponeCount,
+        Placeholder::Paused,
+        Placeholder::Task,
+        Placeholder::StateIcon,
+    ];
+
+    /// Name of the placeholder inside templates
+    fn name(self) -> &'static str {
+        match self {
+            Placeholder::Time => "time",
+            Placeholder::Round => "round",
+            Placeholder::Phase => "phase",
+            Placeholder::Break => "break",
+            Placeholder::PostponeCount => "postpone_count",
+            Placeholder::Paused => "paused",
+            Placeholder::Task => "task",
+            Placeholder::StateIcon => "state_icon",
+        }
+    }
+
+    fn value(self, state: &ViewState) -> String {
+        match self {
+            Placeholder::Time => state
+                .selected_timer()
+                .map_or(&state.time, |timer| &timer.time)
+                .clone(),
+            Placeholder::Round => state.round.to_string(),
+            Placeholder::Phase if state.is_break => "break".to_string(),
+            Placeholder::Phase if state.is_postponed => "postponed".to_string(),
+            Placeholder::Phase => "focus".to_string(),
+            Placeholder::Break if state.is_break => "break".to_string(),
+            Placeholder::PostponeCount => state.postpone_count.to_string(),
+            Placeholder::Paused if state.is_paused => "paused".to_string(),
+            Placeholder::Task => state.task.clone().unwrap_or_default(),
+            Placeholder::StateIcon if state.is_paused => "⏸".to_string(),
+            Placeholder::StateIcon if state.is_break => "☕".to_string(),
+            Placeholder::StateIcon => "🍅".to_string(),
+            Placeholder::Break | Placeholder::Paused => String::new(),
+        }
+    }
+}
+
+/// Part of a parsed template
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Text(String),
+    Placeholder(Placeholder),
+}
+
+/// Template of a status line, which is parsed from a string like `{round} {time} {state_icon}`
+/// (see the [module docs](self) for all placeholders).
+///
+/// ## Example
+///
+/// ```
+/// use zentime_rs_timer::status_template::StatusTemplate;
+///
+/// let template: StatusTemplate = "#{round} {time} {paused}".parse().unwrap();
+/// assert_eq!(template.to_string(), "#{round} {time} {paused}");
+///
+/// assert!("{remaining}".parse::<StatusTemplate>().is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusTemplate {
+    /// The template as it has been written
+    source: String,
+
+    segments: Vec<Segment>,
+}
+
+impl StatusTemplate {
+    /// Fills the placeholders in with the given state. Leading and trailing whitespace is
+    /// removed, so that empty placeholders at the edges (e.g. `{paused}`) leave no gaps.
+    pub fn render(&self, state: &ViewState) -> String {
+        let line: String = self
+            .segments
+            .iter()
+            .map(|segment| match segment {
+                Segment::Text(text) => text.clone(),
+                Segment::Placeholder(placeholder) => placeholder.value(state),
+            })
+            .collect();
+
+        line.trim().to_string()
+    }
+}
+
+impl FromStr for StatusTemplate {
+    type Err = TemplateError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut segments = vec![];
+        let mut text = String::new();
+        let mut characters = value.chars().peekable();
+
+        while let Some(character) = characters.next() {
+            match character {
+                '{' if characters.peek() == Some(&'{') => {
+                    characters.next();
+                    text.push('{');
+                }
+                '}' if characters.peek() == Some(&'}') => {
+                    characters.next();
+                    text.push('}');
+                }
+                '{' => {
+                    let mut name = String::new();
+
+                    loop {
+                        match characters.next() {
+                            Some('}') => break,
+                            Some('{') | None => return Err(TemplateError::UnbalancedBrace),
+                            Some(character) => name.push(character),
+                        }
+                    }
+
+                    let placeholder = Placeholder::ALL
+                        .into_iter()
+                        .find(|placeholder| placeholder.name() == name.trim())
+                        .ok_or(TemplateError::UnknownPlaceholder(name))?;
+
+                    if !text.is_empty() {
+                        segments.push(Segment::Text(std::mem::take(&mut text)));
+                    }
+
+                    segments.push(Segment::Placeholder(placeholder));
+                }
+                '}' => return Err(TemplateError::UnbalancedBrace),
+                _ => text.push(character),
+            }
+        }
+
+        if !text.is_empty() {
+            segments.push(Segment::Text(text));
+        }
+
+        Ok(Self {
+            source: value.to_string(),
+            segments,
+        })
+    }
+}
+
+impl Display for StatusTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+impl Serialize for StatusTemplate {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.source)
+    }
+}
+
+impl<'de> Deserialize<'de> for StatusTemplate {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct StatusTemplateVisitor;
+
+        impl Visitor<'_> for StatusTemplateVisitor {
+            type Value = StatusTemplate;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "a template like \"{{round}} {{time}} {{state_icon}}\"")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                value.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_str(StatusTemplateVisitor)
+    }
+}
@@ -0,0 +1,136 @@
+//! Stopwatch, which counts the elapsed time upwards instead of counting down a fixed interval,
+//! e.g. to simply track how long one has been working.
+//!
+//! Like a [TickingTimer](crate::ticking_timer::TickingTimer), the stopwatch does nothing on its
+//! own - the elapsed time is derived from the [clock](crate::clock) whenever it is queried.
+//!
+//! ## Example
+//!
+//! ```
+//! use zentime_rs_timer::stopwatch::Stopwatch;
+//!
+//! let mut stopwatch = Stopwatch::new();
+//! assert_eq!(stopwatch.view_state().time, "00:00");
+//!
+//! stopwatch.play_pause();
+//! stopwatch.lap();
+//!
+//! let state = stopwatch.view_state();
+//! assert!(!state.is_paused);
+//! assert_eq!(state.stopwatch.unwrap().laps, vec!["00:00"]);
+//! ```
+
+use std::time::{Duration, Instant};
+
+use crate::clock;
+use crate::pomodoro_timer::{StopwatchState, ViewState};
+use crate::util::seconds_to_time;
+
+/// Internal state of a [Stopwatch]
+#[derive(Clone, Copy, Debug)]
+enum State {
+    Paused { elapsed: Duration },
+    Running { since: Instant, elapsed: Duration },
+}
+
+/// Count-up timer, which can be paused and resumed and splits the elapsed time into laps
+#[derive(Clone, Debug)]
+pub struct Stopwatch {
+    state: State,
+
+    /// Length of all laps taken so far
+    laps: Vec<Duration>,
+}
+
+impl Default for Stopwatch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Stopwatch {
+    /// Creates a new (paused) stopwatch at zero
+    pub fn new() -> Self {
+        Self {
+            state: State::Paused {
+                elapsed: Duration::ZERO,
+            },
+            laps: vec![],
+        }
+    }
+
+    /// Toggles between the paused and the running state
+    pub fn play_pause(&mut self) {
+        self.state = match self.state {
+            State::Paused { elapsed } => State::Running {
+                since: clock::now(),
+                elapsed,
+            },
+            State::Running { .. } => State::Paused {
+                elapsed: self.elapsed_time(),
+            },
+        }
+    }
+
+    /// Denotes if the stopwatch is currently paused
+    pub fn is_paused(&self) -> bool {
+        matches!(self.state, State::Paused { .. })
+    }
+
+    /// Time which has elapsed (excluding pauses)
+    pub fn elapsed_time(&self) -> Duration {
+        match self.state {
+            State::Paused { elapsed } => elapsed,
+            State::Running { since, elapsed } => elapsed + clock::now().duration_since(since),
+        }
+    }
+
+    /// Ends the current lap and returns its length (the time since the previous lap)
+    pub fn lap(&mut self) -> Duration {
+        let previous: Duration = self.laps.iter().sum();
+        let lap = self.elapsed_time().saturating_sub(previous);
+
+        self.laps.push(lap);
+        lap
+    }
+
+    /// Lengths of all laps taken so far
+    pub fn laps(&self) -> &[Duration] {
+        &self.laps
+    }
+
+    /// Stops the stopwatch, sets it back to zero and drops all laps
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Current state of the stopwatch, whose time is the elapsed time and whose round is the
+    /// number of the current lap
+    pub fn view_state(&self) -> ViewState {
+        ViewState {
+            is_break: false,
+            is_postponed: false,
+            postpone_count: 0,
+            round: self.laps.len() as u64 + 1,
+            time: seconds_to_time(self.elapsed_time().as_secs()),
+            is_paused: self.is_paused(),
+            hold: None,
+            reminder: None,
+            task: None,
+            profile: None,
+            banner: None,
+            today: None,
+            progress: None,
+            stopwatch: Some(StopwatchState {
+                laps: self
+                    .laps
+                    .iter()
+                    .map(|lap| seconds_to_time(lap.as_secs()))
+                    .collect(),
+            }),
+            pause_reason: None,
+            timers: vec![],
+            countdowns: vec![],
+        }
+    }
+}
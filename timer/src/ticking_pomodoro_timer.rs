@@ -0,0 +1,610 @@
+//! Non-blocking pomodoro timer implementation.
+//! This is the counterpart of [PomodoroTimer](crate::pomodoro_timer::PomodoroTimer), which
+//! does not block the current thread and does not rely on callbacks.
+//! Instead the caller drives the timer by calling [TickingPomodoroTimer::tick()] (e.g. from a
+//! `tokio::time::interval`) and hands over actions via [TickingPomodoroTimer::handle_action()]
+//! as soon as they arrive. This makes it possible to run the timer as a regular task on an
+//! async runtime.
+//!
+//! The phase which follows an interval or break is decided by a [TransitionPolicy] - the
+//! [ClassicPomodoro] cycle, unless another one is passed to [TickingPomodoroTimer::with_policy()].
+//!
+//! To be notified of state transitions (e.g. from other threads), the timer can be wrapped
+//! into an [ObservedTimer](crate::event::ObservedTimer).
+
+use std::error::Error;
+use std::fmt::Display;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::PomodoroTimerConfig;
+use crate::event::PomodoroEvent;
+use crate::pomodoro_timer::{PomodoroTimerState, TimerKind, TimerProgress, ViewState};
+use crate::pomodoro_timer_action::PomodoroTimerAction;
+use crate::ticking_timer::TickingTimer;
+use crate::transition_policy::{ClassicPomodoro, Transition, TransitionPolicy};
+
+/// Phase the pomodoro timer is currently in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PomodoroPhase {
+    /// Focus interval
+    Interval,
+
+    /// Short break after an interval
+    ShortBreak,
+
+    /// Long break after [PomodoroTimerConfig::intervals] intervals
+    LongBreak,
+
+    /// Short break which has been postponed
+    PostponedShortBreak {
+        /// Length of the postponed break in seconds
+        seconds: u64,
+    },
+
+    /// Long break which has been postponed
+    PostponedLongBreak {
+        /// Length of the postponed break in seconds
+        seconds: u64,
+    },
+}
+
+/// Information about a timer which has run out.
+/// This carries the same information as the arguments of
+/// the `on_timer_end` callback of [PomodoroTimer](crate::pomodoro_timer::PomodoroTimer).
+#[derive(Debug, Clone, Copy)]
+pub struct TimerEnd {
+    /// Shared state of the timer which has ended
+    pub state: PomodoroTimerState,
+
+    /// Notification text describing the end of the timer
+    pub notification: Option<&'static str>,
+
+    /// Kind of the timer which has ended
+    pub kind: TimerKind,
+
+    /// Phase which has ended, which tells apart e.g. short and long breaks
+    pub phase: PomodoroPhase,
+}
+
+/// Everything which is needed to continue a [TickingPomodoroTimer] later on (e.g. after the
+/// process has been restarted), see [TickingPomodoroTimer::save()]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedTimer {
+    /// Phase the timer has been in
+    pub phase: PomodoroPhase,
+
+    /// Round and postpone count
+    pub state: PomodoroTimerState,
+
+    /// Total time of the current phase in seconds (incl. extensions)
+    pub total_seconds: u64,
+
+    /// Time which has been left of the current phase in milliseconds
+    pub remaining_millis: u64,
+}
+
+/// Reason why a [PomodoroTimerAction] could not be applied
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionError {
+    /// Only breaks can be postponed
+    NoBreakToPostpone,
+
+    /// Postponing has been disabled via [PomodoroTimerConfig::postpone_limit]
+    PostponeDisabled,
+
+    /// The current break has already been postponed [PomodoroTimerConfig::postpone_limit] times
+    PostponeLimitReached,
+
+    /// A break can't be postponed for zero seconds
+    EmptyPostpone,
+
+    /// The long break is already running
+    AlreadyInLongBreak,
+
+    /// The first interval has no phase before it to go back to
+    NoPreviousPhase,
+}
+
+impl Display for ActionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ActionError::NoBreakToPostpone => write!(f, "Only breaks can be postponed"),
+            ActionError::PostponeDisabled => write!(f, "Postponing breaks is disabled"),
+            ActionError::PostponeLimitReached => {
+                write!(f, "Break has already been postponed too often")
+            }
+            ActionError::EmptyPostpone => write!(f, "Breaks can't be postponed for zero seconds"),
+            ActionError::AlreadyInLongBreak => write!(f, "The long break is already running"),
+            ActionError::NoPreviousPhase => {
+                write!(f, "There is no phase before the first interval")
+            }
+        }
+    }
+}
+
+impl Error for ActionError {}
+
+/// Pomodoro timer which is advanced by calling [Self::tick()].
+///
+/// ## Example
+///
+/// ```
+/// use zentime_rs_timer::config::PomodoroTimerConfig;
+/// use zentime_rs_timer::pomodoro_timer_action::PomodoroTimerAction;
+/// use zentime_rs_timer::ticking_pomodoro_timer::TickingPomodoroTimer;
+///
+/// let mut timer = TickingPomodoroTimer::new(PomodoroTimerConfig::default());
+/// timer.handle_action(PomodoroTimerAction::PlayPause).unwrap();
+///
+/// if let Some(timer_end) = timer.tick() {
+///     println!("{:?}", timer_end.notification);
+/// }
+///
+/// let view_state = timer.view_state();
+/// assert!(!view_state.is_paused);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct TickingPomodoroTimer<P = ClassicPomodoro> {
+    config: PomodoroTimerConfig,
+    policy: P,
+    shared_state: PomodoroTimerState,
+    phase: PomodoroPhase,
+    timer: TickingTimer,
+}
+
+impl TickingPomodoroTimer {
+    /// Creates a new (paused) pomodoro timer starting with the first interval
+    pub fn new(config: PomodoroTimerConfig) -> Self {
+        Self::with_policy(config, ClassicPomodoro)
+    }
+}
+
+impl<P: TransitionPolicy> TickingPomodoroTimer<P> {
+    /// Creates a new (paused) pomodoro timer starting with the first interval, whose phases
+    /// follow each other as decided by the given policy
+    pub fn with_policy(config: PomodoroTimerConfig, policy: P) -> Self {
+        let shared_state = PomodoroTimerState {
+            round: 1,
+            postponed_count: 0,
+        };
+
+        Self {
+            timer: Self::create_timer(&config, &policy, PomodoroPhase::Interval, shared_state),
+            config,
+            policy,
+            shared_state,
+            phase: PomodoroPhase::Interval,
+        }
+    }
+
+    /// Continues a saved timer (see [Self::save()]) in the phase, round and with the remaining
+    /// time it has been saved with. The timer is paused, even if it has been running before,
+    /// because an unknown amount of time might have passed in the meantime.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use zentime_rs_timer::config::PomodoroTimerConfig;
+    /// use zentime_rs_timer::pomodoro_timer_action::PomodoroTimerAction;
+    /// use zentime_rs_timer::ticking_pomodoro_timer::{PomodoroPhase, TickingPomodoroTimer};
+    /// use zentime_rs_timer::transition_policy::ClassicPomodoro;
+    ///
+    /// let config = PomodoroTimerConfig::default();
+    /// let mut timer = TickingPomodoroTimer::new(config);
+    /// timer.handle_action(PomodoroTimerAction::Skip).unwrap();
+    /// timer.handle_action(PomodoroTimerAction::PlayPause).unwrap();
+    ///
+    /// let restored = TickingPomodoroTimer::restore(config, ClassicPomodoro, timer.save());
+    /// assert_eq!(restored.phase(), PomodoroPhase::ShortBreak);
+    /// assert_eq!(restored.shared_state(), timer.shared_state());
+    /// assert!(restored.view_state().is_paused);
+    /// ```
+    pub fn restore(config: PomodoroTimerConfig, policy: P, saved: SavedTimer) -> Self {
+        let timer = TickingTimer::with_remaining_time(
+            saved.total_seconds,
+            Duration::from_millis(saved.remaining_millis),
+        )
+        .with_time_jump_policy(config.time_jump_policy, config.time_jump_threshold());
+
+        Self {
+            config,
+            policy,
+            shared_state: saved.state,
+            phase: saved.phase,
+            timer,
+        }
+    }
+
+    /// Current phase, round and remaining time, from which the timer can be restored via
+    /// [Self::restore()]
+    pub fn save(&self) -> SavedTimer {
+        SavedTimer {
+            phase: self.phase,
+            state: self.shared_state,
+            total_seconds: self.timer.total_time().as_secs(),
+            remaining_millis: self.timer.remaining_time().as_millis() as u64,
+        }
+    }
+
+    /// Policy deciding which phase follows the current one
+    pub fn policy(&self) -> &P {
+        &self.policy
+    }
+
+    /// Replaces the policy of the timer (e.g. once a custom sequence has been configured).
+    /// Like [Self::set_config()], this takes effect from the next phase on.
+    pub fn set_policy(&mut self, policy: P) {
+        self.policy = policy;
+    }
+
+    /// Phase the timer is currently in
+    pub fn phase(&self) -> PomodoroPhase {
+        self.phase
+    }
+
+    /// State which is shared between phases
+    pub fn shared_state(&self) -> PomodoroTimerState {
+        self.shared_state
+    }
+
+    /// Configuration of the timer
+    pub fn config(&self) -> PomodoroTimerConfig {
+        self.config
+    }
+
+    /// Replaces the configuration of the timer.
+    /// The timer of the current phase keeps its length - the new lengths apply from the next
+    /// phase on, so that a running interval or break is not cut short.
+    pub fn set_config(&mut self, config: PomodoroTimerConfig) {
+        self.config = config;
+    }
+
+    /// Timer of the current phase
+    pub fn timer(&self) -> &TickingTimer {
+        &self.timer
+    }
+
+    /// Current state of the timer, which can be displayed by a client
+    pub fn view_state(&self) -> ViewState {
+        let status = self.timer.status();
+
+        ViewState {
+            is_break: matches!(
+                self.phase,
+                PomodoroPhase::ShortBreak | PomodoroPhase::LongBreak
+            ),
+            is_postponed: matches!(
+                self.phase,
+                PomodoroPhase::PostponedShortBreak { .. }
+                    | PomodoroPhase::PostponedLongBreak { .. }
+            ),
+            postpone_count: self.shared_state.postponed_count,
+            round: self.shared_state.round,
+            time: status.current_time.to_string(),
+            is_paused: status.is_paused,
+            hold: None,
+            reminder: None,
+            task: None,
+            profile: None,
+            banner: None,
+            today: None,
+            progress: None,
+            stopwatch: None,
+            pause_reason: None,
+            timers: vec![],
+            countdowns: vec![],
+        }
+    }
+
+    /// Exact progress of the timer of the current phase (see [ViewState::progress])
+    pub fn progress(&self) -> TimerProgress {
+        let total = self.timer.total_time();
+        let remaining = self.timer.remaining_time().min(total);
+
+        TimerProgress {
+            total_millis: total.as_millis() as u64,
+            elapsed_millis: (total - remaining).as_millis() as u64,
+        }
+    }
+
+    /// Advances the timer.
+    /// If the current timer has run out, the next phase is entered and information
+    /// about the ended timer is returned.
+    pub fn tick(&mut self) -> Option<TimerEnd> {
+        self.tick_with_events(&mut vec![])
+    }
+
+    /// Like [Self::tick()], but also collects the events of the transition
+    pub(crate) fn tick_with_events(&mut self, events: &mut Vec<PomodoroEvent>) -> Option<TimerEnd> {
+        if !self.timer.tick() {
+            return None;
+        }
+
+        let timer_end = TimerEnd {
+            state: self.shared_state,
+            notification: match self.phase {
+                PomodoroPhase::Interval => Some("Good job, take a break!"),
+                PomodoroPhase::ShortBreak | PomodoroPhase::LongBreak => Some("Break is over"),
+                PomodoroPhase::PostponedShortBreak { .. }
+                | PomodoroPhase::PostponedLongBreak { .. } => None,
+            },
+            kind: match self.phase {
+                PomodoroPhase::ShortBreak | PomodoroPhase::LongBreak => TimerKind::Break,
+                _ => TimerKind::Interval,
+            },
+            phase: self.phase,
+        };
+
+        self.leave(true, events);
+        self.next(events);
+
+        Some(timer_end)
+    }
+
+    /// Restarts the timer of the current phase from the beginning, without changing the round
+    pub fn restart_phase(&mut self) {
+        self.restart_phase_with_events(&mut vec![]);
+    }
+
+    /// Like [Self::restart_phase()], but also collects the events of the restart
+    pub(crate) fn restart_phase_with_events(&mut self, events: &mut Vec<PomodoroEvent>) {
+        self.enter(self.phase, events);
+    }
+
+    /// Applies a [PomodoroTimerAction] to the timer.
+    /// Returns an error, if the action can not be applied in the current state.
+    pub fn handle_action(&mut self, action: PomodoroTimerAction) -> Result<(), ActionError> {
+        self.handle_action_with_events(action, &mut vec![])
+    }
+
+    /// Like [Self::handle_action()], but also collects the events caused by the action
+    pub(crate) fn handle_action_with_events(
+        &mut self,
+        action: PomodoroTimerAction,
+        events: &mut Vec<PomodoroEvent>,
+    ) -> Result<(), ActionError> {
+        match action {
+            PomodoroTimerAction::None => {}
+            PomodoroTimerAction::PlayPause => self.play_pause(events),
+            PomodoroTimerAction::Skip => {
+                self.leave(false, events);
+                self.next(events);
+            }
+            PomodoroTimerAction::ResetTimer => self.reset(events),
+            PomodoroTimerAction::Previous => self.previous(events)?,
+            PomodoroTimerAction::RestartCurrent => self.start_over(self.phase, events),
+            PomodoroTimerAction::LongBreakNow => self.long_break_now(events)?,
+            PomodoroTimerAction::PostponeBreak { seconds } => self.postpone(seconds, events)?,
+            PomodoroTimerAction::ExtendTime { seconds } => {
+                self.timer.extend(Duration::from_secs(seconds));
+                events.push(PomodoroEvent::Extended { seconds });
+            }
+            PomodoroTimerAction::ReduceTime { seconds } => {
+                self.timer.reduce(Duration::from_secs(seconds));
+                events.push(PomodoroEvent::Reduced { seconds });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Applies multiple [PomodoroTimerAction]s to the timer in the given order.
+    /// This should be used to apply all actions which have been queued up since the
+    /// last tick at once, so that none of them is delayed until a later tick.
+    /// Actions which could not be applied are skipped and their errors are returned.
+    pub fn handle_actions(
+        &mut self,
+        actions: impl IntoIterator<Item = PomodoroTimerAction>,
+    ) -> Vec<ActionError> {
+        actions
+            .into_iter()
+            .filter_map(|action| self.handle_action(action).err())
+            .collect()
+    }
+
+    /// Postpones the current break for the given number of seconds (or
+    /// [PomodoroTimerConfig::postpone_timer]), if this is allowed by the config
+    fn postpone(
+        &mut self,
+        seconds: Option<u64>,
+        events: &mut Vec<PomodoroEvent>,
+    ) -> Result<(), ActionError> {
+        let seconds = seconds.unwrap_or(self.config.postpone_timer);
+
+        let postponed_phase = match self.phase {
+            PomodoroPhase::ShortBreak => PomodoroPhase::PostponedShortBreak { seconds },
+            PomodoroPhase::LongBreak => PomodoroPhase::PostponedLongBreak { seconds },
+            _ => return Err(ActionError::NoBreakToPostpone),
+        };
+
+        if seconds == 0 {
+            return Err(ActionError::EmptyPostpone);
+        }
+
+        if self.config.postpone_limit == 0 {
+            return Err(ActionError::PostponeDisabled);
+        }
+
+        if self.shared_state.postponed_count >= self.config.postpone_limit {
+            return Err(ActionError::PostponeLimitReached);
+        }
+
+        self.shared_state.postponed_count += 1;
+        self.enter(postponed_phase, events);
+
+        Ok(())
+    }
+
+    /// Starts over with the first interval (and keeps the policy)
+    fn reset(&mut self, events: &mut Vec<PomodoroEvent>) {
+        self.shared_state = PomodoroTimerState {
+            round: 1,
+            postponed_count: 0,
+        };
+        events.push(PomodoroEvent::Reset);
+        self.enter(PomodoroPhase::Interval, events);
+    }
+
+    /// Goes back to the phase before the current one (as decided by the policy).
+    /// A postponed break counts as the break it has postponed, so that the interval before
+    /// it is entered.
+    fn previous(&mut self, events: &mut Vec<PomodoroEvent>) -> Result<(), ActionError> {
+        let phase = match self.phase {
+            PomodoroPhase::PostponedShortBreak { .. } => PomodoroPhase::ShortBreak,
+            PomodoroPhase::PostponedLongBreak { .. } => PomodoroPhase::LongBreak,
+            phase => phase,
+        };
+
+        let Transition { phase, state } = self
+            .policy
+            .previous(&self.config, phase, self.shared_state)
+            .ok_or(ActionError::NoPreviousPhase)?;
+
+        self.shared_state = state;
+        self.start_over(phase, events);
+
+        Ok(())
+    }
+
+    /// Leaves the current phase and enters the given one from its beginning.
+    /// A running timer keeps running, a paused one stays paused.
+    fn start_over(&mut self, phase: PomodoroPhase, events: &mut Vec<PomodoroEvent>) {
+        let was_running = !self.timer.is_paused();
+
+        self.leave(false, events);
+        self.enter(phase, events);
+
+        if was_running && self.timer.is_paused() {
+            self.play_pause(events);
+        }
+    }
+
+    /// Starts the long break, which closes the current cycle early
+    fn long_break_now(&mut self, events: &mut Vec<PomodoroEvent>) -> Result<(), ActionError> {
+        if self.phase == PomodoroPhase::LongBreak {
+            return Err(ActionError::AlreadyInLongBreak);
+        }
+
+        self.shared_state = PomodoroTimerState {
+            round: self.config.last_round_of_cycle(self.shared_state.round),
+            postponed_count: 0,
+        };
+        self.enter(PomodoroPhase::LongBreak, events);
+        self.auto_start(events);
+
+        Ok(())
+    }
+
+    /// Transitions into the phase following the current one (as decided by the policy), which
+    /// starts right away if this has been configured (see [Self::auto_start()])
+    fn next(&mut self, events: &mut Vec<PomodoroEvent>) {
+        match self.phase {
+            PomodoroPhase::PostponedShortBreak { .. } => {
+                self.enter(PomodoroPhase::ShortBreak, events)
+            }
+            PomodoroPhase::PostponedLongBreak { .. } => {
+                self.enter(PomodoroPhase::LongBreak, events)
+            }
+            phase => {
+                let Transition { phase, state } =
+                    self.policy.next(&self.config, phase, self.shared_state);
+
+                self.shared_state = state;
+                self.enter(phase, events);
+            }
+        }
+
+        self.auto_start(events);
+    }
+
+    /// Reports the end of the current phase - postponements end without an event of their own,
+    /// because the break they have postponed starts right away
+    fn leave(&self, completed: bool, events: &mut Vec<PomodoroEvent>) {
+        let round = self.shared_state.round;
+
+        match self.phase {
+            PomodoroPhase::Interval => {
+                events.push(PomodoroEvent::IntervalEnded { round, completed })
+            }
+            PomodoroPhase::ShortBreak | PomodoroPhase::LongBreak => {
+                events.push(PomodoroEvent::BreakEnded {
+                    round,
+                    long: self.phase == PomodoroPhase::LongBreak,
+                    completed,
+                })
+            }
+            PomodoroPhase::PostponedShortBreak { .. }
+            | PomodoroPhase::PostponedLongBreak { .. } => {}
+        }
+    }
+
+    fn play_pause(&mut self, events: &mut Vec<PomodoroEvent>) {
+        self.timer.play_pause();
+
+        events.push(if self.timer.is_paused() {
+            PomodoroEvent::Paused
+        } else {
+            PomodoroEvent::Resumed
+        });
+    }
+
+    /// Starts the timer of a freshly entered phase, if
+    /// [PomodoroTimerConfig::auto_start_break] or [PomodoroTimerConfig::auto_start_work]
+    /// applies to it
+    fn auto_start(&mut self, events: &mut Vec<PomodoroEvent>) {
+        let auto_start = match self.phase {
+            PomodoroPhase::Interval => self.config.auto_start_work,
+            PomodoroPhase::ShortBreak | PomodoroPhase::LongBreak => self.config.auto_start_break,
+            PomodoroPhase::PostponedShortBreak { .. }
+            | PomodoroPhase::PostponedLongBreak { .. } => false,
+        };
+
+        if auto_start && self.timer.is_paused() {
+            self.play_pause(events);
+        }
+    }
+
+    fn enter(&mut self, phase: PomodoroPhase, events: &mut Vec<PomodoroEvent>) {
+        self.phase = phase;
+        self.timer = Self::create_timer(&self.config, &self.policy, phase, self.shared_state);
+
+        let round = self.shared_state.round;
+
+        events.push(match phase {
+            PomodoroPhase::Interval => PomodoroEvent::IntervalStarted { round },
+            PomodoroPhase::ShortBreak => PomodoroEvent::BreakStarted { round, long: false },
+            PomodoroPhase::LongBreak => PomodoroEvent::BreakStarted { round, long: true },
+            PomodoroPhase::PostponedShortBreak { seconds } => PomodoroEvent::Postponed {
+                seconds,
+                postponed_count: self.shared_state.postponed_count,
+                long: false,
+            },
+            PomodoroPhase::PostponedLongBreak { seconds } => PomodoroEvent::Postponed {
+                seconds,
+                postponed_count: self.shared_state.postponed_count,
+                long: true,
+            },
+        });
+    }
+
+    /// Creates the internal timer of a phase, which is as long as the policy decides.
+    /// Postponed breaks start running immediately, all other phases wait for
+    /// [PomodoroTimerAction::PlayPause] (unless they are started via [Self::auto_start()]).
+    fn create_timer(
+        config: &PomodoroTimerConfig,
+        policy: &P,
+        phase: PomodoroPhase,
+        state: PomodoroTimerState,
+    ) -> TickingTimer {
+        let seconds = policy.length(config, phase, state);
+        let timer = match phase {
+            PomodoroPhase::PostponedShortBreak { .. }
+            | PomodoroPhase::PostponedLongBreak { .. } => TickingTimer::new_running(seconds),
+            _ => TickingTimer::new(seconds),
+        };
+
+        timer.with_time_jump_policy(config.time_jump_policy, config.time_jump_threshold())
+    }
+}
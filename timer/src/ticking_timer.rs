@@ -0,0 +1,207 @@
+//! Non-blocking timer implementation.
+//! Unlike [Timer](crate::Timer), which runs a blocking loop and communicates via callbacks,
+//! a [TickingTimer] does nothing on its own. Instead the caller advances it by calling
+//! [TickingTimer::tick()], e.g. from an async interval, and queries its status afterwards.
+
+use std::time::{Duration, Instant};
+
+use crate::clock;
+use crate::timer::{
+    CurrentTime, TickTime, TimeJumpPolicy, TimerStatus, DEFAULT_TIME_JUMP_THRESHOLD,
+};
+use crate::util::seconds_to_time;
+
+/// Internal state of a [TickingTimer]
+#[derive(Clone, Copy, Debug)]
+enum TickingTimerState {
+    Paused { remaining_time: Duration },
+    Running { target_time: Instant },
+}
+
+/// Countdown timer which can be paused and resumed and is advanced by calling [Self::tick()].
+///
+/// ## Example
+///
+/// ```
+/// use zentime_rs_timer::ticking_timer::TickingTimer;
+///
+/// let mut timer = TickingTimer::new(10);
+/// timer.play_pause();
+///
+/// let has_ended = timer.tick();
+/// assert!(!has_ended);
+/// assert_eq!(timer.status().current_time.to_string(), "00:09");
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct TickingTimer {
+    /// Total time of the timer in seconds
+    time: u64,
+
+    state: TickingTimerState,
+
+    /// Determines how the timer reacts to time jumps
+    time_jump_policy: TimeJumpPolicy,
+
+    /// Minimum gap between two ticks which is considered to be a time jump
+    time_jump_threshold: Duration,
+
+    last_tick: TickTime,
+}
+
+impl TickingTimer {
+    /// Creates a new timer in paused state
+    pub fn new(time: u64) -> Self {
+        Self {
+            time,
+            state: TickingTimerState::Paused {
+                remaining_time: Duration::from_secs(time),
+            },
+            time_jump_policy: TimeJumpPolicy::default(),
+            time_jump_threshold: DEFAULT_TIME_JUMP_THRESHOLD,
+            last_tick: TickTime::now(),
+        }
+    }
+
+    /// Creates a paused timer of the given total time, of which only `remaining_time` is left
+    /// (e.g. to continue a timer after the process has been restarted)
+    pub fn with_remaining_time(time: u64, remaining_time: Duration) -> Self {
+        let mut timer = Self::new(time);
+        timer.state = TickingTimerState::Paused { remaining_time };
+        timer
+    }
+
+    /// Creates a new timer, which is already running
+    pub fn new_running(time: u64) -> Self {
+        let mut timer = Self::new(time);
+        timer.play_pause();
+        timer
+    }
+
+    /// Configures how the timer reacts to large wall clock jumps (e.g. after the machine has been
+    /// suspended). Gaps between two ticks of at least `threshold` are considered a time jump.
+    pub fn with_time_jump_policy(mut self, policy: TimeJumpPolicy, threshold: Duration) -> Self {
+        self.time_jump_policy = policy;
+        self.time_jump_threshold = threshold;
+        self
+    }
+
+    /// Toggles between the paused and the running state
+    pub fn play_pause(&mut self) {
+        self.state = match self.state {
+            TickingTimerState::Paused { remaining_time } => {
+                // Gaps while being paused are no time jumps
+                self.last_tick = TickTime::now();
+
+                TickingTimerState::Running {
+                    target_time: self.last_tick.instant + remaining_time,
+                }
+            }
+            TickingTimerState::Running { .. } => TickingTimerState::Paused {
+                remaining_time: self.remaining_time(),
+            },
+        }
+    }
+
+    /// Adds the given duration to the remaining (and total) time
+    pub fn extend(&mut self, duration: Duration) {
+        self.time += duration.as_secs();
+        self.state = match self.state {
+            TickingTimerState::Paused { remaining_time } => TickingTimerState::Paused {
+                remaining_time: remaining_time + duration,
+            },
+            TickingTimerState::Running { target_time } => TickingTimerState::Running {
+                target_time: target_time + duration,
+            },
+        };
+    }
+
+    /// Removes the given duration from the remaining (and total) time.
+    /// The remaining time never drops below zero, so that time which has already elapsed
+    /// stays part of the timer.
+    pub fn reduce(&mut self, duration: Duration) {
+        let duration = duration.min(self.remaining_time());
+
+        self.time = self.time.saturating_sub(duration.as_secs());
+        self.state = match self.state {
+            TickingTimerState::Paused { remaining_time } => TickingTimerState::Paused {
+                remaining_time: remaining_time - duration,
+            },
+            TickingTimerState::Running { target_time } => TickingTimerState::Running {
+                target_time: target_time - duration,
+            },
+        };
+    }
+
+    /// Denotes if the timer is currently paused
+    pub fn is_paused(&self) -> bool {
+        matches!(self.state, TickingTimerState::Paused { .. })
+    }
+
+    /// Time left until the timer runs out
+    pub fn remaining_time(&self) -> Duration {
+        match self.state {
+            TickingTimerState::Paused { remaining_time } => remaining_time,
+            TickingTimerState::Running { target_time } => {
+                target_time.saturating_duration_since(clock::now())
+            }
+        }
+    }
+
+    /// Total time of the timer
+    pub fn total_time(&self) -> Duration {
+        Duration::from_secs(self.time)
+    }
+
+    /// Time which has already elapsed
+    pub fn elapsed_time(&self) -> Duration {
+        self.total_time().saturating_sub(self.remaining_time())
+    }
+
+    /// Current status of the timer
+    pub fn status(&self) -> TimerStatus {
+        TimerStatus {
+            current_time: CurrentTime(seconds_to_time(self.remaining_time().as_secs())),
+            is_paused: self.is_paused(),
+        }
+    }
+
+    /// Advances the timer and handles time jumps (see [TimeJumpPolicy]).
+    /// Returns `true`, if the timer has run out.
+    pub fn tick(&mut self) -> bool {
+        let TickingTimerState::Running { target_time } = self.state else {
+            return false;
+        };
+
+        let tick = TickTime::now();
+        let (elapsed, unaccounted) = tick.since(&self.last_tick);
+        let previous_tick = std::mem::replace(&mut self.last_tick, tick);
+
+        if elapsed >= self.time_jump_threshold {
+            match self.time_jump_policy {
+                // If the target time would lie before the earliest representable instant,
+                // the timer has run out anyway
+                TimeJumpPolicy::Subtract => {
+                    self.state = TickingTimerState::Running {
+                        target_time: target_time.checked_sub(unaccounted).unwrap_or(tick.instant),
+                    };
+                }
+                // The time which has passed since the last tick (e.g. while the machine has been
+                // suspended) does not count, even if the monotonic clock has advanced
+                TimeJumpPolicy::Pause => {
+                    self.state = TickingTimerState::Paused {
+                        remaining_time: target_time
+                            .saturating_duration_since(previous_tick.instant),
+                    };
+                    return false;
+                }
+                TimeJumpPolicy::Restart => {
+                    self.state = TickingTimerState::Running {
+                        target_time: tick.instant + Duration::from_secs(self.time),
+                    };
+                }
+            }
+        }
+
+        self.remaining_time().is_zero()
+    }
+}
@@ -3,20 +3,28 @@
 //! It will call a given `on_tick` closure on every tick update and
 //! an `on_timer_end`-closure, when it's done (either by receiving a [TimerAction::End]) or
 //! when the internal timer is down to 0
+//!
+//! The remaining time is always derived from a deadline on the [clock](crate::clock) instead of
+//! counting ticks, so that delayed ticks don't make the timer drift. Large gaps between two ticks
+//! (e.g. while the machine has been suspended) are handled according to [TimeJumpPolicy].
 
 use serde::{Deserialize, Serialize};
 use std::fmt::{Debug, Display};
 
+use crate::clock;
 use crate::timer_action::TimerAction;
 use crate::util::seconds_to_time;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Gaps between two ticks which are at least this long are treated as time jumps by default
+pub(crate) const DEFAULT_TIME_JUMP_THRESHOLD: Duration = Duration::from_secs(5);
 
 // NOTE: I tried to use the typestate approach, like it's described here:
 // https://cliffle.com/blog/rust-typestate/
 
 /// Information that will be handed to the [on_tick] closure continously
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct CurrentTime(String);
+pub struct CurrentTime(pub(crate) String);
 
 impl Display for CurrentTime {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -34,6 +42,53 @@ pub struct TimerStatus {
     pub is_paused: bool,
 }
 
+/// Determines how a running timer reacts to a large jump of the wall clock, which usually
+/// happens when the machine has been suspended in the middle of an interval.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum TimeJumpPolicy {
+    /// Treat the time the machine was suspended as elapsed timer time
+    #[default]
+    Subtract,
+
+    /// Pause the timer at the time it was suspended
+    Pause,
+
+    /// Restart the current timer from the beginning
+    Restart,
+}
+
+/// Monotonic and wall clock time of a single tick.
+/// Depending on the platform the monotonic clock does not advance while the machine is
+/// suspended, which is why we also need to keep track of the wall clock.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct TickTime {
+    pub(crate) instant: Instant,
+    system_time: SystemTime,
+}
+
+impl TickTime {
+    pub(crate) fn now() -> Self {
+        Self {
+            instant: clock::now(),
+            system_time: clock::system_now(),
+        }
+    }
+
+    /// Returns the total time which has passed between `earlier` and `self` and the part of
+    /// it which has not been accounted for by the monotonic clock.
+    pub(crate) fn since(&self, earlier: &TickTime) -> (Duration, Duration) {
+        let monotonic = self.instant.duration_since(earlier.instant);
+        let wall = self
+            .system_time
+            .duration_since(earlier.system_time)
+            .unwrap_or_default();
+
+        (monotonic.max(wall), wall.saturating_sub(monotonic))
+    }
+}
+
 /// Empty trait implemented by structs (e.g. Paused, Running)
 pub trait TimerState {}
 
@@ -114,6 +169,12 @@ pub struct Timer<S: TimerState> {
 
     /// Internal state data associated with a certain timer state (e.g. [Paused] or [Running])
     internal_state: S,
+
+    /// Determines how the timer reacts to time jumps
+    time_jump_policy: TimeJumpPolicy,
+
+    /// Minimum gap between two ticks which is considered to be a time jump
+    time_jump_threshold: Duration,
 }
 
 impl<S: TimerState + std::fmt::Debug> Debug for Timer<S> {
@@ -123,11 +184,21 @@ impl<S: TimerState + std::fmt::Debug> Debug for Timer<S> {
             .field("on_timer_end", &"[closure] without context")
             .field("internal_state", &self.internal_state)
             .field("on_tick", &"[closure] without context")
+            .field("time_jump_policy", &self.time_jump_policy)
+            .field("time_jump_threshold", &self.time_jump_threshold)
             .finish()
     }
 }
 
-impl<S: TimerState> Timer<S> {}
+impl<S: TimerState> Timer<S> {
+    /// Configures how the timer reacts to large wall clock jumps (e.g. after the machine has been
+    /// suspended). Gaps between two ticks of at least `threshold` are considered a time jump.
+    pub fn with_time_jump_policy(mut self, policy: TimeJumpPolicy, threshold: Duration) -> Self {
+        self.time_jump_policy = policy;
+        self.time_jump_threshold = threshold;
+        self
+    }
+}
 
 /// Implementation of the [Paused] state for [Timer]
 impl Timer<Paused> {
@@ -145,6 +216,8 @@ impl Timer<Paused> {
             on_timer_end: on_timer_end.map(|x| Box::new(x) as Box<dyn TimerEndHandler>),
             on_tick: on_tick.map(|x| Box::new(x) as Box<dyn TimerTickHandler>),
             internal_state: Paused { remaining_time },
+            time_jump_policy: TimeJumpPolicy::default(),
+            time_jump_threshold: DEFAULT_TIME_JUMP_THRESHOLD,
         }
     }
 
@@ -154,7 +227,9 @@ impl Timer<Paused> {
         loop {
             let time = self.internal_state.remaining_time.as_secs();
 
-            let Some(ref mut callback) = self.on_tick else { continue };
+            let Some(ref mut callback) = self.on_tick else {
+                continue;
+            };
             if let Some(action) = callback.call(TimerStatus {
                 is_paused: true,
                 current_time: CurrentTime(seconds_to_time(time)),
@@ -173,6 +248,12 @@ impl Timer<Paused> {
         }
     }
 
+    /// Starts the timer right away, instead of waiting for a [TimerAction::PlayPause] like
+    /// [Self::init()] does
+    pub fn start(self) {
+        self.unpause()
+    }
+
     /// Transitions the paused timer into a running timer
     fn unpause(self) {
         Timer {
@@ -180,8 +261,10 @@ impl Timer<Paused> {
             on_tick: self.on_tick,
             time: self.time,
             internal_state: Running {
-                target_time: Instant::now() + self.internal_state.remaining_time,
+                target_time: clock::now() + self.internal_state.remaining_time,
             },
+            time_jump_policy: self.time_jump_policy,
+            time_jump_threshold: self.time_jump_threshold,
         }
         .init()
     }
@@ -202,21 +285,29 @@ impl Timer<Running> {
             on_timer_end: on_timer_end.map(|x| Box::new(x) as Box<dyn TimerEndHandler>),
             on_tick: on_tick.map(|x| Box::new(x) as Box<dyn TimerTickHandler>),
             internal_state: Running {
-                target_time: Instant::now() + remaining_time,
+                target_time: clock::now() + remaining_time,
             },
+            time_jump_policy: TimeJumpPolicy::default(),
+            time_jump_threshold: DEFAULT_TIME_JUMP_THRESHOLD,
         }
     }
 
     /// Transitions the running timer into a paused timer state and calls `init()` on_interval_end
-    /// it, so that the new timer is ready to receive an [TimerInputAction]
-    fn pause(self) {
+    /// it, so that the new timer is ready to receive an [TimerInputAction].
+    /// The remaining time is the one the timer has had at the given point in time.
+    fn pause(self, at: Instant) {
         Timer {
             time: self.time,
             on_tick: self.on_tick,
             on_timer_end: self.on_timer_end,
             internal_state: Paused {
-                remaining_time: self.internal_state.target_time - Instant::now(),
+                remaining_time: self
+                    .internal_state
+                    .target_time
+                    .saturating_duration_since(at),
             },
+            time_jump_policy: self.time_jump_policy,
+            time_jump_threshold: self.time_jump_threshold,
         }
         .init();
     }
@@ -224,17 +315,52 @@ impl Timer<Running> {
     /// Runs the timer and awaits input.
     /// Depending on the input [TimerInputAction] the timer might transition into a paused state or skip to the next interval.
     pub fn init(mut self) {
-        while self.internal_state.target_time > Instant::now() {
-            let time = (self.internal_state.target_time - Instant::now()).as_secs();
+        let mut last_tick = TickTime::now();
+
+        while self.internal_state.target_time > clock::now() {
+            let tick = TickTime::now();
+            let (elapsed, unaccounted) = tick.since(&last_tick);
+            let previous_tick = std::mem::replace(&mut last_tick, tick);
+
+            if elapsed >= self.time_jump_threshold {
+                match self.time_jump_policy {
+                    // If the target time would lie before the earliest representable instant,
+                    // the timer has run out anyway
+                    TimeJumpPolicy::Subtract => {
+                        self.internal_state.target_time = self
+                            .internal_state
+                            .target_time
+                            .checked_sub(unaccounted)
+                            .unwrap_or(tick.instant);
+                    }
+                    // The time which has passed since the last tick (e.g. while the machine has
+                    // been suspended) does not count, even if the monotonic clock has advanced
+                    TimeJumpPolicy::Pause => return self.pause(previous_tick.instant),
+                    TimeJumpPolicy::Restart => {
+                        self.internal_state.target_time =
+                            tick.instant + Duration::from_secs(self.time);
+                    }
+                }
+
+                continue;
+            }
+
+            let time = self
+                .internal_state
+                .target_time
+                .saturating_duration_since(clock::now())
+                .as_secs();
 
-            let Some(ref mut callback) = self.on_tick else { continue };
+            let Some(ref mut callback) = self.on_tick else {
+                continue;
+            };
             if let Some(action) = callback.call(TimerStatus {
                 is_paused: false,
                 current_time: CurrentTime(seconds_to_time(time)),
             }) {
                 match action {
                     TimerAction::PlayPause => {
-                        return self.pause();
+                        return self.pause(clock::now());
                     }
 
                     // Returns from the blocking loop, so that the calling code
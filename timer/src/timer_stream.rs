@@ -0,0 +1,209 @@
+//! Async API of the timer (requires the `async` feature).
+//!
+//! A [TimerStream] runs a [TickingPomodoroTimer] on a tokio runtime: it ticks the timer by
+//! itself and yields a [TimerUpdate] whenever something has changed. Actions are handed over via
+//! a [TimerHandle], which can be cloned and moved to other tasks. Neither the runtime nor the
+//! current task is ever blocked, so there is no need for a dedicated thread or callbacks.
+//!
+//! ## Example
+//!
+//! ```
+//! use tokio_stream::StreamExt;
+//! use zentime_rs_timer::config::PomodoroTimerConfig;
+//! use zentime_rs_timer::pomodoro_timer_action::PomodoroTimerAction;
+//! use zentime_rs_timer::ticking_pomodoro_timer::TickingPomodoroTimer;
+//! use zentime_rs_timer::timer_stream::TimerUpdate;
+//!
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() {
+//! let (mut updates, handle) = TickingPomodoroTimer::new(PomodoroTimerConfig::default()).stream();
+//! handle.send(PomodoroTimerAction::PlayPause).unwrap();
+//!
+//! while let Some(update) = updates.next().await {
+//!     if let TimerUpdate::State(view_state) = update {
+//!         println!("{}", view_state.time);
+//!
+//!         if !view_state.is_paused {
+//!             break;
+//!         }
+//!     }
+//! }
+//! # }
+//! ```
+
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt::Display;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures_core::Stream;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::time::{interval, Interval, MissedTickBehavior};
+
+use crate::pomodoro_timer::ViewState;
+use crate::pomodoro_timer_action::PomodoroTimerAction;
+use crate::ticking_pomodoro_timer::{ActionError, TickingPomodoroTimer, TimerEnd};
+use crate::transition_policy::TransitionPolicy;
+
+/// Default period in which a [TimerStream] ticks its timer
+pub const DEFAULT_TICK_PERIOD: Duration = Duration::from_millis(250);
+
+/// Item of a [TimerStream]
+#[derive(Debug, Clone)]
+pub enum TimerUpdate {
+    /// The state of the timer has changed (e.g. one second has passed or an action has been
+    /// applied).
+    /// Boxed, because the state is much larger than the other updates.
+    State(Box<ViewState>),
+
+    /// The timer of the current phase has run out.
+    /// This is followed by the state of the next phase.
+    Ended(TimerEnd),
+
+    /// An action which has been sent via the [TimerHandle] could not be applied
+    Rejected(ActionError),
+}
+
+/// Error of [TimerHandle::send()], if the [TimerStream] has been dropped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimerClosed;
+
+impl Display for TimerClosed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "The timer has been dropped")
+    }
+}
+
+impl Error for TimerClosed {}
+
+/// Sends actions to a [TimerStream]
+#[derive(Debug, Clone)]
+pub struct TimerHandle {
+    actions: UnboundedSender<PomodoroTimerAction>,
+}
+
+impl TimerHandle {
+    /// Hands an action over to the timer - it is applied the next time the stream is polled
+    pub fn send(&self, action: PomodoroTimerAction) -> Result<(), TimerClosed> {
+        self.actions.send(action).map_err(|_| TimerClosed)
+    }
+
+    /// Denotes if the timer has been dropped, so that actions can't be sent anymore
+    pub fn is_closed(&self) -> bool {
+        self.actions.is_closed()
+    }
+}
+
+/// Stream of [TimerUpdate]s of a ticking pomodoro timer.
+/// The first item is the initial state of the timer. The stream never ends on its own - the
+/// timer keeps running until the stream is dropped, even if all [TimerHandle]s are gone.
+#[derive(Debug)]
+pub struct TimerStream<P> {
+    timer: TickingPomodoroTimer<P>,
+    actions: Option<UnboundedReceiver<PomodoroTimerAction>>,
+    ticks: Interval,
+    last_state: Option<ViewState>,
+    pending: VecDeque<TimerUpdate>,
+}
+
+impl<P: TransitionPolicy> TickingPomodoroTimer<P> {
+    /// Turns the timer into a [TimerStream], which ticks every [DEFAULT_TICK_PERIOD].
+    /// Has to be called from within a tokio runtime.
+    pub fn stream(self) -> (TimerStream<P>, TimerHandle) {
+        self.stream_with_period(DEFAULT_TICK_PERIOD)
+    }
+
+    /// Turns the timer into a [TimerStream], which ticks in the given period.
+    /// A longer period saves some wakeups at the expense of a less accurate timer.
+    /// Has to be called from within a tokio runtime.
+    pub fn stream_with_period(self, period: Duration) -> (TimerStream<P>, TimerHandle) {
+        let (sender, receiver) = unbounded_channel();
+        let mut ticks = interval(period);
+
+        // Catching up on missed ticks is pointless, because the timer measures the elapsed time
+        // by itself
+        ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        let stream = TimerStream {
+            timer: self,
+            actions: Some(receiver),
+            ticks,
+            last_state: None,
+            pending: VecDeque::new(),
+        };
+
+        (stream, TimerHandle { actions: sender })
+    }
+}
+
+impl<P: TransitionPolicy> TimerStream<P> {
+    /// Timer which is driven by the stream
+    pub fn timer(&self) -> &TickingPomodoroTimer<P> {
+        &self.timer
+    }
+
+    /// Gives mutable access to the timer, e.g. to change its config.
+    /// The stream yields the resulting state the next time it is polled.
+    pub fn timer_mut(&mut self) -> &mut TickingPomodoroTimer<P> {
+        self.last_state = None;
+        &mut self.timer
+    }
+
+    /// Stops the stream and returns the timer in its current state
+    pub fn into_timer(self) -> TickingPomodoroTimer<P> {
+        self.timer
+    }
+
+    /// Applies all actions which have arrived so far
+    fn receive_actions(&mut self, cx: &mut Context<'_>) {
+        while let Some(actions) = &mut self.actions {
+            match actions.poll_recv(cx) {
+                Poll::Ready(Some(action)) => {
+                    if let Err(error) = self.timer.handle_action(action) {
+                        self.pending.push_back(TimerUpdate::Rejected(error));
+                    }
+                }
+                Poll::Ready(None) => self.actions = None,
+                Poll::Pending => break,
+            }
+        }
+    }
+
+    /// Queues up the current state of the timer, unless it has already been yielded
+    fn push_state(&mut self) {
+        let state = self.timer.view_state();
+
+        if self.last_state.as_ref() != Some(&state) {
+            self.last_state = Some(state.clone());
+            self.pending.push_back(TimerUpdate::State(Box::new(state)));
+        }
+    }
+}
+
+impl<P: TransitionPolicy + Unpin> Stream for TimerStream<P> {
+    type Item = TimerUpdate;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let stream = self.get_mut();
+
+        if stream.pending.is_empty() {
+            stream.receive_actions(cx);
+
+            // Polling until the interval is pending again registers the waker for the next tick
+            while stream.ticks.poll_tick(cx).is_ready() {
+                if let Some(timer_end) = stream.timer.tick() {
+                    stream.pending.push_back(TimerUpdate::Ended(timer_end));
+                }
+            }
+
+            stream.push_state();
+        }
+
+        match stream.pending.pop_front() {
+            Some(update) => Poll::Ready(Some(update)),
+            None => Poll::Pending,
+        }
+    }
+}
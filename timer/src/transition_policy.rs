@@ -0,0 +1,302 @@
+//! Policies deciding which phase follows the current one, so that applications can run other
+//! cycles than the classic pomodoro (e.g. a fixed schedule of varying intervals) on top of
+//! [TickingPomodoroTimer](crate::ticking_pomodoro_timer::TickingPomodoroTimer).
+//!
+//! ## Example
+//!
+//! ```
+//! use zentime_rs_timer::config::PomodoroTimerConfig;
+//! use zentime_rs_timer::pomodoro_timer::PomodoroTimerState;
+//! use zentime_rs_timer::pomodoro_timer_action::PomodoroTimerAction;
+//! use zentime_rs_timer::ticking_pomodoro_timer::{PomodoroPhase, TickingPomodoroTimer};
+//! use zentime_rs_timer::transition_policy::{Transition, TransitionPolicy};
+//!
+//! /// Short breaks only - every interval is followed by a short break
+//! #[derive(Debug, Clone, Copy)]
+//! struct ShortBreaksOnly;
+//!
+//! impl TransitionPolicy for ShortBreaksOnly {
+//!     fn next(
+//!         &self,
+//!         _config: &PomodoroTimerConfig,
+//!         phase: PomodoroPhase,
+//!         state: PomodoroTimerState,
+//!     ) -> Transition {
+//!         match phase {
+//!             PomodoroPhase::Interval => Transition {
+//!                 phase: PomodoroPhase::ShortBreak,
+//!                 state: PomodoroTimerState { postponed_count: 0, ..state },
+//!             },
+//!             _ => Transition {
+//!                 phase: PomodoroPhase::Interval,
+//!                 state: PomodoroTimerState { round: state.round + 1, ..state },
+//!             },
+//!         }
+//!     }
+//! }
+//!
+//! // The classic pomodoro would start the long break after the single interval of a cycle
+//! let config = PomodoroTimerConfig { intervals: 1, ..PomodoroTimerConfig::default() };
+//! let mut timer = TickingPomodoroTimer::with_policy(config, ShortBreaksOnly);
+//!
+//! timer.handle_action(PomodoroTimerAction::Skip).unwrap();
+//! assert_eq!(timer.phase(), PomodoroPhase::ShortBreak);
+//! ```
+
+use std::fmt::Debug;
+
+use crate::config::PomodoroTimerConfig;
+use crate::pomodoro_timer::PomodoroTimerState;
+use crate::ticking_pomodoro_timer::PomodoroPhase;
+
+/// Phase a timer transitions into, together with the state shared with that phase
+#[derive(Debug, Clone, Copy)]
+pub struct Transition {
+    /// Phase which follows the current one
+    pub phase: PomodoroPhase,
+
+    /// Shared state of the following phase (e.g. with an incremented round)
+    pub state: PomodoroTimerState,
+}
+
+/// Decides which phase follows the current one and how long each phase lasts.
+///
+/// The policy is asked whenever an interval or a break has run out or has been skipped (and
+/// for the phase before the current one, when the timer goes back).
+/// Postponed breaks always resume the break they have postponed, therefore a policy never
+/// has to deal with them.
+pub trait TransitionPolicy: Debug {
+    /// Phase which follows the given (interval or break) phase
+    fn next(
+        &self,
+        config: &PomodoroTimerConfig,
+        phase: PomodoroPhase,
+        state: PomodoroTimerState,
+    ) -> Transition;
+
+    /// Phase which precedes the given (interval or break) phase - [None] for the very first
+    /// interval.
+    ///
+    /// By default a break goes back to the interval of its round and an interval goes back to
+    /// the phase which [Self::next()] lets follow the interval of the previous round.
+    /// The break is entered without any postponements.
+    fn previous(
+        &self,
+        config: &PomodoroTimerConfig,
+        phase: PomodoroPhase,
+        state: PomodoroTimerState,
+    ) -> Option<Transition> {
+        let state = PomodoroTimerState {
+            postponed_count: 0,
+            ..state
+        };
+
+        match phase {
+            PomodoroPhase::Interval if state.round <= 1 => None,
+            PomodoroPhase::Interval => {
+                let previous_round = PomodoroTimerState {
+                    round: state.round - 1,
+                    ..state
+                };
+
+                Some(self.next(config, PomodoroPhase::Interval, previous_round))
+            }
+            _ => Some(Transition {
+                phase: PomodoroPhase::Interval,
+                state,
+            }),
+        }
+    }
+
+    /// Length of the given phase in seconds - the lengths of the config by default
+    fn length(
+        &self,
+        config: &PomodoroTimerConfig,
+        phase: PomodoroPhase,
+        _state: PomodoroTimerState,
+    ) -> u64 {
+        match phase {
+            PomodoroPhase::Interval => config.timer,
+            PomodoroPhase::ShortBreak => config.minor_break,
+            PomodoroPhase::LongBreak => config.major_break,
+            PomodoroPhase::PostponedShortBreak { seconds }
+            | PomodoroPhase::PostponedLongBreak { seconds } => seconds,
+        }
+    }
+}
+
+/// Classic pomodoro cycle: each interval is followed by a short break, except for every
+/// [PomodoroTimerConfig::intervals]-th one, which is followed by a long break
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ClassicPomodoro;
+
+impl TransitionPolicy for ClassicPomodoro {
+    fn next(
+        &self,
+        config: &PomodoroTimerConfig,
+        phase: PomodoroPhase,
+        state: PomodoroTimerState,
+    ) -> Transition {
+        match phase {
+            PomodoroPhase::Interval => Transition {
+                phase: if state.round.is_multiple_of(config.intervals) {
+                    PomodoroPhase::LongBreak
+                } else {
+                    PomodoroPhase::ShortBreak
+                },
+                state: PomodoroTimerState {
+                    postponed_count: 0,
+                    ..state
+                },
+            },
+            PomodoroPhase::ShortBreak | PomodoroPhase::LongBreak => Transition {
+                phase: PomodoroPhase::Interval,
+                state: PomodoroTimerState {
+                    round: state.round + 1,
+                    ..state
+                },
+            },
+            // Never asked for by the timer, which resumes postponed breaks itself
+            PomodoroPhase::PostponedShortBreak { .. } => Transition {
+                phase: PomodoroPhase::ShortBreak,
+                state,
+            },
+            PomodoroPhase::PostponedLongBreak { .. } => Transition {
+                phase: PomodoroPhase::LongBreak,
+                state,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pomodoro_timer_action::PomodoroTimerAction;
+    use crate::ticking_pomodoro_timer::{ActionError, TickingPomodoroTimer};
+
+    fn state(round: u64, postponed_count: u16) -> PomodoroTimerState {
+        PomodoroTimerState {
+            round,
+            postponed_count,
+        }
+    }
+
+    /// Short breaks only - every interval is followed by a short break
+    #[derive(Debug)]
+    struct ShortBreaksOnly;
+
+    impl TransitionPolicy for ShortBreaksOnly {
+        fn next(
+            &self,
+            _config: &PomodoroTimerConfig,
+            phase: PomodoroPhase,
+            state: PomodoroTimerState,
+        ) -> Transition {
+            match phase {
+                PomodoroPhase::Interval => Transition {
+                    phase: PomodoroPhase::ShortBreak,
+                    state,
+                },
+                _ => Transition {
+                    phase: PomodoroPhase::Interval,
+                    state: PomodoroTimerState {
+                        round: state.round + 1,
+                        ..state
+                    },
+                },
+            }
+        }
+    }
+
+    #[test]
+    fn classic_pomodoro_ends_each_cycle_with_a_long_break() {
+        let config = PomodoroTimerConfig::default();
+
+        let after_first = ClassicPomodoro.next(&config, PomodoroPhase::Interval, state(1, 2));
+        assert_eq!(after_first.phase, PomodoroPhase::ShortBreak);
+        assert_eq!(after_first.state, state(1, 0));
+
+        let after_last =
+            ClassicPomodoro.next(&config, PomodoroPhase::Interval, state(config.intervals, 0));
+        assert_eq!(after_last.phase, PomodoroPhase::LongBreak);
+
+        let after_break = ClassicPomodoro.next(&config, PomodoroPhase::LongBreak, state(4, 0));
+        assert_eq!(after_break.phase, PomodoroPhase::Interval);
+        assert_eq!(after_break.state, state(5, 0));
+    }
+
+    #[test]
+    fn goes_back_to_the_phase_before_the_current_one() {
+        let config = PomodoroTimerConfig::default();
+
+        assert!(ClassicPomodoro
+            .previous(&config, PomodoroPhase::Interval, state(1, 0))
+            .is_none());
+
+        let before_break = ClassicPomodoro
+            .previous(&config, PomodoroPhase::ShortBreak, state(2, 1))
+            .unwrap();
+        assert_eq!(before_break.phase, PomodoroPhase::Interval);
+        assert_eq!(before_break.state, state(2, 0));
+
+        // The interval after a long break goes back to the long break of the previous cycle
+        let before_interval = ClassicPomodoro
+            .previous(&config, PomodoroPhase::Interval, state(5, 0))
+            .unwrap();
+        assert_eq!(before_interval.phase, PomodoroPhase::LongBreak);
+        assert_eq!(before_interval.state, state(4, 0));
+    }
+
+    #[test]
+    fn uses_the_lengths_of_the_config_by_default() {
+        let config = PomodoroTimerConfig::default();
+
+        assert_eq!(
+            ShortBreaksOnly.length(&config, PomodoroPhase::Interval, state(1, 0)),
+            config.timer
+        );
+        assert_eq!(
+            ShortBreaksOnly.length(&config, PomodoroPhase::LongBreak, state(1, 0)),
+            config.major_break
+        );
+        assert_eq!(
+            ShortBreaksOnly.length(
+                &config,
+                PomodoroPhase::PostponedShortBreak { seconds: 90 },
+                state(1, 1)
+            ),
+            90
+        );
+    }
+
+    #[test]
+    fn drives_the_ticking_timer_with_a_custom_policy() {
+        let config = PomodoroTimerConfig {
+            intervals: 1,
+            ..PomodoroTimerConfig::default()
+        };
+        let mut timer = TickingPomodoroTimer::with_policy(config, ShortBreaksOnly);
+
+        assert_eq!(
+            timer.handle_action(PomodoroTimerAction::Previous),
+            Err(ActionError::NoPreviousPhase)
+        );
+
+        for round in 1..=3 {
+            assert_eq!(timer.shared_state().round, round);
+            timer.handle_action(PomodoroTimerAction::Skip).unwrap();
+            assert_eq!(timer.phase(), PomodoroPhase::ShortBreak);
+            timer.handle_action(PomodoroTimerAction::Skip).unwrap();
+        }
+
+        // Going back asks the policy as well, instead of assuming the classic cycle
+        timer.handle_action(PomodoroTimerAction::Previous).unwrap();
+        assert_eq!(timer.phase(), PomodoroPhase::ShortBreak);
+        assert_eq!(timer.shared_state().round, 3);
+
+        timer.handle_action(PomodoroTimerAction::Previous).unwrap();
+        assert_eq!(timer.phase(), PomodoroPhase::Interval);
+        assert_eq!(timer.shared_state().round, 3);
+    }
+}
@@ -6,3 +6,16 @@ pub fn seconds_to_time(duration: u64) -> String {
     let sec = duration % 60;
     format!("{:02}:{:02}", min, sec)
 }
+
+/// Parses a formatted timer string like "29:30" (mm:ss) back into seconds.
+/// Returns [None] if the string is not in this format.
+pub fn time_to_seconds(time: &str) -> Option<u64> {
+    let (min, sec) = time.split_once(':')?;
+    let sec: u64 = sec.parse().ok()?;
+
+    if sec >= 60 {
+        return None;
+    }
+
+    Some(min.parse::<u64>().ok()? * 60 + sec)
+}
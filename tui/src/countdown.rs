@@ -0,0 +1,48 @@
+//! Large digits, which keep the remaining time readable from a distance
+
+/// Number of lines each glyph spans
+pub(crate) const HEIGHT: u16 = 5;
+
+/// Glyphs of the digits and the separator - each column is doubled, because terminal cells are
+/// about twice as high as they are wide
+fn glyph(character: char) -> Option<[&'static str; HEIGHT as usize]> {
+    let glyph = match character {
+        '0' => ["###", "# #", "# #", "# #", "###"],
+        '1' => ["  #", "  #", "  #", "  #", "  #"],
+        '2' => ["###", "  #", "###", "#  ", "###"],
+        '3' => ["###", "  #", "###", "  #", "###"],
+        '4' => ["# #", "# #", "###", "  #", "  #"],
+        '5' => ["###", "#  ", "###", "  #", "###"],
+        '6' => ["###", "#  ", "###", "# #", "###"],
+        '7' => ["###", "  #", "  #", "  #", "  #"],
+        '8' => ["###", "# #", "###", "# #", "###"],
+        '9' => ["###", "# #", "###", "  #", "###"],
+        ':' => [" ", "#", " ", "#", " "],
+        _ => return None,
+    };
+
+    Some(glyph)
+}
+
+/// Lines of the given time in large digits - [None] if it contains anything else than digits
+/// and colons (e.g. `25m`)
+pub(crate) fn large_time(time: &str) -> Option<Vec<String>> {
+    let glyphs = time.chars().map(glyph).collect::<Option<Vec<_>>>()?;
+
+    let lines = (0..HEIGHT as usize)
+        .map(|row| {
+            glyphs
+                .iter()
+                .map(|glyph| {
+                    glyph[row]
+                        .chars()
+                        .map(|cell| if cell == '#' { "██" } else { "  " })
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+                .join("  ")
+        })
+        .collect();
+
+    Some(lines)
+}
@@ -0,0 +1,344 @@
+//! Conversion of terminal input events into [ClientInputAction]s
+
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+
+use crate::status::{Prompt, PromptKind};
+
+/// Number of seconds the `+`/`-` keys add to or remove from the current interval or break
+const TIME_ADJUSTMENT_STEP: i64 = 60;
+
+/// Actions triggered by user terminal input on a client
+#[derive(Clone, Debug)]
+pub enum ClientInputAction {
+    /// Quit Timer and terminate server
+    Quit,
+
+    /// Detach current client without terminating server
+    Detach,
+
+    /// NoOp
+    None,
+
+    /// Either start or pause the current timer
+    PlayPause,
+
+    /// Skip to the next timer (break or focus)
+    Skip,
+
+    /// Resets the timer back to the first interval
+    Reset,
+
+    /// Goes back to the interval or break before the current one
+    Previous,
+
+    /// Starts the current interval or break over
+    RestartCurrent,
+
+    /// Starts the long break right away
+    LongBreakNow,
+
+    /// Postpones the current break, if possible (see [PomodoroTimerConfig])
+    ///
+    /// [PomodoroTimerConfig]: zentime_rs_timer::config::PomodoroTimerConfig
+    PostPone,
+
+    /// Adds (positive) or removes (negative) the given number of seconds to/from the current
+    /// interval or break
+    AdjustTime(i64),
+
+    /// Switches to the next timer profile
+    NextProfile,
+
+    /// Switches to the timer profile with the given name
+    SwitchProfile(String),
+
+    /// Selects the next one of the timers the server runs (see [ViewState::timers])
+    ///
+    /// [ViewState::timers]: zentime_rs_timer::pomodoro_timer::ViewState::timers
+    NextTimer,
+
+    /// Selects the timer with the given name
+    SelectTimer(String),
+
+    /// Pauses the timer (if it is running) and prompts for the reason of the interruption
+    Interrupt,
+
+    /// Prompts for the task which is worked on during the current session
+    LabelTask,
+
+    /// The text of a prompt, which is currently being typed, has changed ([None] once the
+    /// prompt closes)
+    Prompt(Option<Prompt>),
+
+    /// Records why the timer has been paused
+    PauseReason(String),
+
+    /// Labels the current session with the given task ([None] removes the label)
+    SetTask(Option<String>),
+
+    /// Confirms the question of the client (e.g. whether the focus contract should be broken)
+    Confirm,
+
+    /// Restores the terminal and suspends the client (Ctrl-Z)
+    Suspend,
+
+    /// The client has been continued after it had been stopped, therefore the terminal is set
+    /// up again and the current state is requested from the server
+    Resume,
+}
+
+/// Turns terminal input events into [ClientInputAction]s and keeps track of the prompt, which
+/// is currently being typed into (if any)
+#[derive(Debug, Clone, Default)]
+pub struct InputHandler {
+    prompt: Option<Prompt>,
+
+    /// Quitting only closes the popup (see [InterfaceLayout::Popup](crate::InterfaceLayout::Popup))
+    is_popup: bool,
+}
+
+impl InputHandler {
+    /// Creates a handler without an open prompt
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a handler for a popup, which is closed by `q`, `Esc` or Ctrl-C. Closing the
+    /// popup detaches the client instead of shutting down the server.
+    pub fn popup() -> Self {
+        Self {
+            is_popup: true,
+            ..Self::default()
+        }
+    }
+
+    /// Prompt, which is currently being typed into (if any)
+    pub fn prompt(&self) -> Option<&Prompt> {
+        self.prompt.as_ref()
+    }
+
+    /// Converts a terminal input event into [ClientInputAction]s.
+    /// While a prompt is open, key presses are used to type the reason of an interruption or
+    /// the task of the current session.
+    pub fn handle(&mut self, event: Event) -> Vec<ClientInputAction> {
+        if !self.is_popup {
+            return self.actions(event);
+        }
+
+        // Esc closes prompts first and the popup afterwards
+        let is_escape = matches!(event, Event::Key(key) if key.code == KeyCode::Esc);
+
+        if self.prompt.is_none() && is_escape {
+            return vec![ClientInputAction::Detach];
+        }
+
+        self.actions(event)
+            .into_iter()
+            .map(|action| match action {
+                ClientInputAction::Quit => ClientInputAction::Detach,
+                action => action,
+            })
+            .collect()
+    }
+
+    fn actions(&mut self, event: Event) -> Vec<ClientInputAction> {
+        let prompt = &mut self.prompt;
+        let Some(open_prompt) = prompt else {
+            let action = keymap(event);
+
+            let kind = match action {
+                ClientInputAction::Interrupt => PromptKind::Reason,
+                ClientInputAction::LabelTask => PromptKind::Task,
+                action => return vec![action],
+            };
+
+            *prompt = Some(Prompt::new(kind));
+            return vec![action, ClientInputAction::Prompt(prompt.clone())];
+        };
+
+        let Event::Key(KeyEvent {
+            code, modifiers, ..
+        }) = event
+        else {
+            return vec![];
+        };
+
+        match code {
+            KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+                return vec![ClientInputAction::Quit];
+            }
+            KeyCode::Char('z') if modifiers.contains(KeyModifiers::CONTROL) => {
+                return vec![ClientInputAction::Suspend];
+            }
+            KeyCode::Char(character) => open_prompt.text.push(character),
+            KeyCode::Backspace => {
+                open_prompt.text.pop();
+            }
+            KeyCode::Enter => {
+                let Some(Prompt { kind, text }) = prompt.take() else {
+                    return vec![];
+                };
+
+                return match kind {
+                    PromptKind::Reason if text.trim().is_empty() => {
+                        vec![ClientInputAction::Prompt(None)]
+                    }
+                    PromptKind::Reason => vec![
+                        ClientInputAction::PauseReason(text),
+                        ClientInputAction::Prompt(None),
+                    ],
+                    // Confirming an empty task removes the label
+                    PromptKind::Task => vec![
+                        ClientInputAction::SetTask(
+                            Some(text).filter(|text| !text.trim().is_empty()),
+                        ),
+                        ClientInputAction::Prompt(None),
+                    ],
+                    // Confirmations are answered via the keymap (see [ClientInputAction::Confirm])
+                    // and never typed into
+                    PromptKind::FocusContract => vec![ClientInputAction::Prompt(None)],
+                };
+            }
+            KeyCode::Esc => {
+                *prompt = None;
+                return vec![ClientInputAction::Prompt(None)];
+            }
+            _ => return vec![],
+        }
+
+        vec![ClientInputAction::Prompt(Some(open_prompt.clone()))]
+    }
+}
+
+/// Keymap from terminal input events to [ClientInputAction]
+fn keymap(event: Event) -> ClientInputAction {
+    if let Event::Key(key_event) = event {
+        match key_event {
+            KeyEvent {
+                code: KeyCode::Char('q'),
+                ..
+            }
+            | KeyEvent {
+                code: KeyCode::Char('c'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } => {
+                return ClientInputAction::Quit;
+            }
+
+            KeyEvent {
+                code: KeyCode::Char('z'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } => {
+                return ClientInputAction::Suspend;
+            }
+
+            KeyEvent {
+                code: KeyCode::Char('d'),
+                ..
+            } => {
+                return ClientInputAction::Detach;
+            }
+
+            KeyEvent {
+                code: KeyCode::Char(' '),
+                ..
+            } => {
+                return ClientInputAction::PlayPause;
+            }
+
+            KeyEvent {
+                code: KeyCode::Char('s'),
+                ..
+            } => {
+                return ClientInputAction::Skip;
+            }
+
+            KeyEvent {
+                code: KeyCode::Char('p'),
+                ..
+            } => return ClientInputAction::PostPone,
+
+            KeyEvent {
+                code: KeyCode::Char('r'),
+                ..
+            } => {
+                return ClientInputAction::Reset;
+            }
+
+            KeyEvent {
+                code: KeyCode::Char('R'),
+                ..
+            } => {
+                return ClientInputAction::RestartCurrent;
+            }
+
+            KeyEvent {
+                code: KeyCode::Char('b'),
+                ..
+            } => {
+                return ClientInputAction::Previous;
+            }
+
+            KeyEvent {
+                code: KeyCode::Char('l'),
+                ..
+            } => {
+                return ClientInputAction::LongBreakNow;
+            }
+
+            KeyEvent {
+                code: KeyCode::Char('i'),
+                ..
+            } => {
+                return ClientInputAction::Interrupt;
+            }
+
+            KeyEvent {
+                code: KeyCode::Char('t'),
+                ..
+            } => {
+                return ClientInputAction::LabelTask;
+            }
+
+            KeyEvent {
+                code: KeyCode::Char('y'),
+                ..
+            } => {
+                return ClientInputAction::Confirm;
+            }
+
+            KeyEvent {
+                code: KeyCode::Char('m'),
+                ..
+            } => {
+                return ClientInputAction::NextProfile;
+            }
+
+            KeyEvent {
+                code: KeyCode::Tab, ..
+            } => {
+                return ClientInputAction::NextTimer;
+            }
+
+            KeyEvent {
+                code: KeyCode::Char('+'),
+                ..
+            } => {
+                return ClientInputAction::AdjustTime(TIME_ADJUSTMENT_STEP);
+            }
+
+            KeyEvent {
+                code: KeyCode::Char('-'),
+                ..
+            } => {
+                return ClientInputAction::AdjustTime(-TIME_ADJUSTMENT_STEP);
+            }
+
+            _ => {}
+        }
+    }
+
+    ClientInputAction::None
+}
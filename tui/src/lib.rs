@@ -0,0 +1,64 @@
+#![warn(
+    missing_docs,
+    missing_copy_implementations,
+    missing_debug_implementations
+)]
+
+//! Terminal interface of zentime, which is independent of the connection to the server, so that
+//! it can be reused by other frontends and tested without a real terminal:
+//!
+//! * state in - the [ViewState] of the timer and the [ClientStatus] of the client
+//! * frames out - [render::render] draws both into any [tui] backend
+//! * events in - an [InputHandler] turns terminal events into [ClientInputAction]s
+//!
+//! ## Example
+//!
+//! ```
+//! use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+//! use tui::{backend::TestBackend, Terminal};
+//! use zentime_rs_timer::pomodoro_timer::ViewState;
+//! use zentime_tui::{render, ClientInputAction, ClientStatus, InputHandler, ViewOptions};
+//!
+//! let state = ViewState {
+//!     is_break: false,
+//!     is_postponed: false,
+//!     postpone_count: 0,
+//!     round: 1,
+//!     time: "25:00".to_string(),
+//!     is_paused: true,
+//!     hold: None,
+//!     reminder: None,
+//!     task: None,
+//!     profile: None,
+//!     banner: None,
+//!     today: None,
+//!     progress: None,
+//!     stopwatch: None,
+//!     pause_reason: None,
+//!     timers: vec![],
+//!     countdowns: vec![],
+//! };
+//!
+//! let mut terminal = Terminal::new(TestBackend::new(60, 12)).unwrap();
+//! render(&mut terminal, &state, &ClientStatus::default(), &ViewOptions::default()).unwrap();
+//!
+//! let mut input_handler = InputHandler::new();
+//! let space = Event::Key(KeyEvent::new(KeyCode::Char(' '), KeyModifiers::NONE));
+//! assert!(matches!(
+//!     input_handler.handle(space).as_slice(),
+//!     [ClientInputAction::PlayPause]
+//! ));
+//! ```
+//!
+//! [ViewState]: zentime_rs_timer::pomodoro_timer::ViewState
+
+mod countdown;
+pub mod input;
+pub mod render;
+pub mod status;
+pub mod view;
+
+pub use input::{ClientInputAction, InputHandler};
+pub use render::render;
+pub use status::{ClientStatus, ConnectionStatus, Prompt, PromptKind};
+pub use view::{InterfaceLayout, Theme, ViewOptions, POPUP_HEIGHT, POPUP_WIDTH};
@@ -0,0 +1,501 @@
+//! Rendering of the timer state into frames of a [Terminal]
+
+use std::io;
+
+use tui::{
+    backend::Backend,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Paragraph, Tabs},
+    Frame, Terminal,
+};
+use zentime_rs_timer::pomodoro_timer::ViewState;
+
+use crate::countdown::{large_time, HEIGHT as LARGE_TIME_HEIGHT};
+use crate::status::{ClientStatus, ConnectionStatus, Prompt, PromptKind};
+use crate::view::{InterfaceLayout, Theme, ViewOptions, POPUP_HEIGHT, POPUP_WIDTH};
+
+/// Name of the timer profile which is used without any profiles being configured - it is
+/// left out, so that nothing changes without profiles
+const DEFAULT_PROFILE: &str = "default";
+
+/// Draws the next frame to the terminal
+pub fn render<B: Backend>(
+    terminal: &mut Terminal<B>,
+    timer_state: &ViewState,
+    status: &ClientStatus,
+    options: &ViewOptions,
+) -> io::Result<()> {
+    terminal.draw(|frame| draw(frame, timer_state, status, options))?;
+    Ok(())
+}
+
+/// Draws the interface into the given frame, which allows embedding it into another
+/// interface
+pub fn draw<B: Backend>(
+    frame: &mut Frame<'_, B>,
+    timer_state: &ViewState,
+    status: &ClientStatus,
+    options: &ViewOptions,
+) {
+    let rect = frame.size();
+
+    // An enforced break takes over the whole terminal, regardless of the layout
+    if status.enforced_break {
+        draw_enforced_break(frame, timer_state, status, options);
+        return;
+    }
+
+    if options.layout == InterfaceLayout::Compact {
+        let line = compact_line(timer_state, status, options);
+        frame.render_widget(line, compact_layout(rect)[0]);
+        return;
+    }
+
+    if options.layout == InterfaceLayout::Popup {
+        let popup_layout = popup_layout(rect);
+        let line = compact_line(timer_state, status, options);
+        frame.render_widget(line, popup_layout[0]);
+
+        let key_hints = popup_key_hints(timer_state, status.prompt.as_ref(), options);
+        frame.render_widget(key_hints, popup_layout[1]);
+        return;
+    }
+
+    let layout = layout(rect);
+
+    // Rendered at the bottom
+    let key_tabs = key_binding_info(timer_state, status.prompt.as_ref(), &options.theme);
+    frame.render_widget(key_tabs, layout[1]);
+
+    // Top layout
+    let inner_layout = inner_layout(layout[0]);
+
+    // Rendered to the left
+    let timer_info = timer_info(timer_state, status, options);
+    frame.render_widget(timer_info, inner_layout[0]);
+
+    // Rendered to the right
+    let timer = timer(displayed_time(timer_state), &options.theme);
+    frame.render_widget(timer, inner_layout[1])
+}
+
+/// Base layout of the default interface
+/// ┌───────────────────────────────────────────────┐
+/// │                                               │
+/// │                                               │
+/// │                    A                          │
+/// │                                               │
+/// └───────────────────────────────────────────────┘
+/// ┌───────────────────────────────────────────────┐
+/// │                    B                          │
+/// └───────────────────────────────────────────────┘
+fn layout(rect: Rect) -> Vec<Rect> {
+    Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints(
+            [
+                Constraint::Max(4),
+                Constraint::Max(3),
+                Constraint::Length(1),
+            ]
+            .as_ref(),
+        )
+        .split(rect)
+}
+
+/// Compact layout, which only consists of a single line A
+/// ┌───────────────────────────────────────────────┐
+/// │                    A                          │
+/// └───────────────────────────────────────────────┘
+fn compact_layout(rect: Rect) -> Vec<Rect> {
+    Layout::default()
+        .direction(Direction::Vertical)
+        .horizontal_margin(1)
+        .constraints([Constraint::Length(1), Constraint::Min(0)].as_ref())
+        .split(rect)
+}
+
+/// Popup layout of two lines A and B, which stays the same size no matter how large the
+/// terminal is
+/// ┌────────────────────────┐
+/// │ A                      │
+/// │ B                      │
+/// │                        │
+/// └────────────────────────┘
+fn popup_layout(rect: Rect) -> Vec<Rect> {
+    let area = Rect {
+        width: rect.width.min(POPUP_WIDTH),
+        height: rect.height.min(POPUP_HEIGHT),
+        ..rect
+    };
+
+    Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Length(1)].as_ref())
+        .split(area)
+}
+
+/// Inner layout of the default interface rendered into the base layout part A
+/// ┌──────────────────┐ ┌────────────────────────────────┐
+/// │                  │ │                                │
+/// │        A         │ │                B               │
+/// │                  │ │                                │
+/// │                  │ │                                │
+/// └──────────────────┘ └────────────────────────────────┘
+fn inner_layout(rect: Rect) -> Vec<Rect> {
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)].as_ref())
+        .split(rect)
+}
+
+/// Keyboard shortcuts of the default interface
+/// ┌─────────────────────────────────────────────────────────┐
+/// │ [Q]uit │ [D]etach │ [S]kip │ Space: Play/Pause          │
+/// └─────────────────────────────────────────────────────────┘
+fn key_binding_info(state: &ViewState, prompt: Option<&Prompt>, theme: &Theme) -> Tabs<'static> {
+    let keybindings: &[&str] = if let Some(prompt) = prompt {
+        prompt_key_bindings(prompt.kind)
+    } else if state.selected_timer().is_some() {
+        &[
+            "[Q]uit",
+            "[D]etach",
+            "[S]top",
+            "[R]eset",
+            "+/-: 1 min",
+            "Tab: Next timer",
+            "Space: Play/Pause",
+        ]
+    } else if state.stopwatch.is_some() {
+        &[
+            "[Q]uit",
+            "[D]etach",
+            "[S] Lap",
+            "[R]eset",
+            "[T]ask",
+            "Space: Play/Pause",
+        ]
+    } else {
+        &[
+            "[Q]uit",
+            "[D]etach",
+            "[S]kip",
+            if state.is_break { "[P]ostpone" } else { "" },
+            "[I]nterrupt",
+            "[T]ask",
+            "+/-: 1 min",
+            if state.timers.is_empty() {
+                ""
+            } else {
+                "Tab: Next timer"
+            },
+            "Space: Play/Pause",
+        ]
+    };
+
+    let keybinding_spans = keybindings
+        .iter()
+        .map(|key| Spans::from(vec![Span::styled(*key, Style::default().fg(theme.hints))]))
+        .collect();
+
+    Tabs::new(keybinding_spans).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .style(Style::default().fg(theme.hints)),
+    )
+}
+
+/// Single line of key bindings of the popup layout, in which `q` closes the popup
+/// `Space: Play/Pause  [S]kip  [P]ostpone  [Q] Close`
+fn popup_key_hints(
+    state: &ViewState,
+    prompt: Option<&Prompt>,
+    options: &ViewOptions,
+) -> Paragraph<'static> {
+    let keybindings: &[&str] = match prompt {
+        Some(prompt) => prompt_key_bindings(prompt.kind),
+        None if state.selected_timer().is_some() => {
+            &["Space: Play/Pause", "[S]top", "Tab: Next", "[Q] Close"]
+        }
+        None if state.stopwatch.is_some() => &["Space: Play/Pause", "[S] Lap", "[Q] Close"],
+        None if state.is_break => &["Space: Play/Pause", "[S]kip", "[P]ostpone", "[Q] Close"],
+        None => &["Space: Play/Pause", "[S]kip", "[I]nterrupt", "[Q] Close"],
+    };
+
+    Paragraph::new(keybindings.join("  ")).style(Style::default().fg(options.theme.hints))
+}
+
+/// Key bindings while a prompt is open
+fn prompt_key_bindings(kind: PromptKind) -> &'static [&'static str] {
+    match kind {
+        PromptKind::Reason => &["Enter: Save reason", "Esc: Cancel"],
+        PromptKind::Task => &["Enter: Save task", "Esc: Cancel"],
+        PromptKind::FocusContract => &["[Y]es, give up", "Any other key: Keep going"],
+    }
+}
+
+/// Timer information of the default interface (interval/round number, break/focus)
+fn timer_info<'a>(
+    state: &'a ViewState,
+    status: &ClientStatus,
+    options: &ViewOptions,
+) -> Paragraph<'a> {
+    let mut info_text = vec![Spans::from(vec![
+        timer_kind(state, status, &options.theme),
+        postponed_count(state, options),
+    ])];
+
+    if options.show_round {
+        info_text.push(Spans::from(vec![Span::styled(
+            rounds(state),
+            Style::default().fg(options.theme.round),
+        )]));
+    }
+
+    Paragraph::new(info_text)
+        .block(
+            Block::default()
+                .title(title(status, &options.theme))
+                .borders(Borders::ALL),
+        )
+        .style(Style::default().fg(options.theme.text))
+        .alignment(Alignment::Left)
+}
+
+/// Title of the interface, which also shows the connection status and errors
+fn title(status: &ClientStatus, theme: &Theme) -> Span<'static> {
+    match (&status.connection, &status.error) {
+        (ConnectionStatus::Reconnecting { attempt }, _) => Span::styled(
+            format!("zentime - reconnecting ({})...", attempt),
+            Style::default().fg(theme.error),
+        ),
+        (ConnectionStatus::Connected, Some(error)) => Span::styled(
+            format!("zentime - {}", error),
+            Style::default().fg(theme.error),
+        ),
+        (ConnectionStatus::Connected, None) => Span::raw("zentime"),
+    }
+}
+
+/// Enforced break, which fills the whole terminal with a large countdown
+/// ┌───────────────────────────────────────────────┐
+/// │                    Break                      │
+/// │                                               │
+/// │              ██████  ██  ██████               │
+/// │                    ...                        │
+/// │                                               │
+/// │         Step away from the screen ...         │
+/// │                                               │
+/// │          [Q]uit │ [D]etach │ ...              │
+/// └───────────────────────────────────────────────┘
+fn draw_enforced_break<B: Backend>(
+    frame: &mut Frame<'_, B>,
+    state: &ViewState,
+    status: &ClientStatus,
+    options: &ViewOptions,
+) {
+    let theme = &options.theme;
+    let block = Block::default()
+        .title(title(status, theme))
+        .borders(Borders::ALL)
+        .style(Style::default().fg(theme.text));
+    let inner = block.inner(frame.size());
+    frame.render_widget(block, frame.size());
+
+    // Terminals which are too small for the large digits show the plain time instead
+    let time = large_time(&state.time)
+        .filter(|lines| {
+            let width = lines.first().map_or(0, |line| line.chars().count());
+            inner.height > LARGE_TIME_HEIGHT + 4 && inner.width as usize >= width
+        })
+        .unwrap_or_else(|| vec![state.time.clone()]);
+
+    let mut text = vec![
+        Spans::from(timer_kind(state, status, theme)),
+        Spans::default(),
+    ];
+    text.extend(
+        time.into_iter()
+            .map(|line| Spans::from(Span::styled(line, Style::default().fg(theme.timer)))),
+    );
+    text.extend([
+        Spans::default(),
+        Spans::from(Span::raw(
+            "Step away from the screen - this break can't be skipped",
+        )),
+        Spans::default(),
+        Spans::from(Span::styled(
+            "[Q]uit | [D]etach | [I]nterrupt | Space: Play/Pause",
+            Style::default().fg(theme.hints),
+        )),
+    ]);
+
+    // Centered vertically as well
+    let height = (text.len() as u16).min(inner.height);
+    let area = Rect {
+        y: inner.y + (inner.height - height) / 2,
+        height,
+        ..inner
+    };
+
+    frame.render_widget(Paragraph::new(text).alignment(Alignment::Center), area);
+}
+
+/// Timer of the default interface
+fn timer<'a>(time: &'a str, theme: &Theme) -> Paragraph<'a> {
+    Paragraph::new(time)
+        .block(Block::default().borders(Borders::ALL))
+        .style(Style::default().fg(theme.timer))
+        .alignment(Alignment::Center)
+}
+
+/// Single line of the compact layout
+/// ` 24:13  Focus  Round: 2 (deep-work)  reconnecting (3)...`
+fn compact_line<'a>(
+    state: &'a ViewState,
+    status: &ClientStatus,
+    options: &ViewOptions,
+) -> Paragraph<'a> {
+    let separator = || Span::raw("  ");
+
+    // Open prompts take precedence over the configured format, so that they stay visible
+    if let (Some(format), None) = (&options.format, &status.prompt) {
+        return status_line(
+            vec![Span::raw(format.render(state))],
+            status,
+            &options.theme,
+        );
+    }
+
+    let mut line = vec![
+        Span::styled(
+            displayed_time(state),
+            Style::default().fg(options.theme.timer),
+        ),
+        separator(),
+        timer_kind(state, status, &options.theme),
+        postponed_count(state, options),
+    ];
+
+    if options.show_round {
+        line.push(separator());
+        line.push(Span::styled(
+            rounds(state),
+            Style::default().fg(options.theme.round),
+        ));
+    }
+
+    status_line(line, status, &options.theme)
+}
+
+/// Appends the connection status or the most recent error to a line of the compact layout
+fn status_line<'a>(mut line: Vec<Span<'a>>, status: &ClientStatus, theme: &Theme) -> Paragraph<'a> {
+    let status_info = match (&status.connection, &status.error) {
+        (ConnectionStatus::Reconnecting { attempt }, _) => {
+            Some(format!("reconnecting ({})...", attempt))
+        }
+        (ConnectionStatus::Connected, Some(error)) => Some(error.to_string()),
+        (ConnectionStatus::Connected, None) => None,
+    };
+
+    if let Some(status_info) = status_info {
+        line.push(Span::raw("  "));
+        line.push(Span::styled(status_info, Style::default().fg(theme.error)));
+    }
+
+    Paragraph::new(Spans::from(line)).style(Style::default().fg(theme.text))
+}
+
+/// Current phase (or whatever is more important right now, e.g. an open prompt)
+fn timer_kind<'a>(state: &'a ViewState, status: &ClientStatus, theme: &Theme) -> Span<'a> {
+    if let Some(prompt) = &status.prompt {
+        // Only prompts which are typed into show a cursor
+        let cursor = if prompt.kind == PromptKind::FocusContract {
+            ""
+        } else {
+            "_"
+        };
+
+        Span::styled(
+            format!("{}{}", prompt, cursor),
+            Style::default().fg(Color::Yellow),
+        )
+    } else if let Some(timer) = state.selected_timer() {
+        Span::styled(
+            format!("Timer: {}", timer.name),
+            Style::default().fg(theme.focus),
+        )
+    } else if let Some(hold) = &state.hold {
+        Span::styled(hold.to_string(), Style::default().fg(Color::Magenta))
+    } else if let Some(reason) = state.pause_reason.as_ref().filter(|_| state.is_paused) {
+        Span::styled(
+            format!("Paused ({})", reason),
+            Style::default().fg(Color::Magenta),
+        )
+    } else if let Some(reminder) = &state.reminder {
+        Span::styled(reminder.to_string(), Style::default().fg(Color::Cyan))
+    } else if let Some(banner) = &state.banner {
+        Span::styled(banner.as_str(), Style::default().fg(Color::Green))
+    } else if state.stopwatch.is_some() {
+        Span::styled("Stopwatch", Style::default().fg(theme.focus))
+    } else if state.is_break {
+        Span::styled("Break", Style::default().fg(theme.break_phase))
+    } else if state.is_postponed {
+        Span::styled("Postponed", Style::default().fg(theme.postponed))
+    } else {
+        Span::styled("Focus", Style::default().fg(theme.focus))
+    }
+}
+
+/// Remaining time of the selected timer - a named timer is displayed instead of the pomodoro
+/// timer, while it is selected
+fn displayed_time(state: &ViewState) -> &str {
+    state
+        .selected_timer()
+        .map_or(state.time.as_str(), |timer| timer.time.as_str())
+}
+
+/// Number of times the break has been postponed - empty, unless it is postponed right now
+fn postponed_count(state: &ViewState, options: &ViewOptions) -> Span<'static> {
+    if state.is_postponed && options.show_postpone_count {
+        Span::styled(
+            format!(" ({})", state.postpone_count),
+            Style::default().fg(options.theme.hints),
+        )
+    } else {
+        Span::styled("", Style::default())
+    }
+}
+
+/// Round number (or the lap of a stopwatch) incl. the active profile, the progress towards the
+/// daily goal and the task
+fn rounds(state: &ViewState) -> String {
+    let last_lap = state
+        .stopwatch
+        .as_ref()
+        .map(|stopwatch| stopwatch.laps.last());
+    let mut rounds = match last_lap {
+        Some(Some(last_lap)) => format!("Lap: {} (last: {})", state.round, last_lap),
+        Some(None) => format!("Lap: {}", state.round),
+        None => format!("Round: {}", state.round),
+    };
+
+    if let Some(profile) = state.profile.as_ref().filter(|p| *p != DEFAULT_PROFILE) {
+        rounds.push_str(&format!(" ({})", profile));
+    }
+
+    if let Some(today) = state.today {
+        if let Some(goal) = today.goal {
+            rounds.push_str(&format!(" · {}/{} 🍅", today.intervals, goal));
+        }
+    }
+
+    if let Some(task) = &state.task {
+        rounds.push_str(&format!(" - {}", task));
+    }
+
+    rounds
+}
@@ -0,0 +1,79 @@
+//! Status of a client, which is displayed alongside the timer
+
+use std::fmt::Display;
+
+/// Status of the connection between a client and the zentime server
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionStatus {
+    /// The client is connected and receives timer updates
+    #[default]
+    Connected,
+
+    /// The connection has been lost and the client is trying to reconnect
+    Reconnecting {
+        /// Number of the current reconnection attempt
+        attempt: u32,
+    },
+}
+
+/// Status information of a client, which is displayed alongside the timer
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ClientStatus {
+    /// Status of the connection to the server
+    pub connection: ConnectionStatus,
+
+    /// Most recent error reported by the server (if any)
+    pub error: Option<String>,
+
+    /// Prompt, which is currently being typed into (if any)
+    pub prompt: Option<Prompt>,
+
+    /// Set while the client enforces the current break, which takes over the whole interface
+    pub enforced_break: bool,
+}
+
+/// What a prompt asks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromptKind {
+    /// Reason of an interruption
+    Reason,
+
+    /// Task the current session is labeled with
+    Task,
+
+    /// Confirmation before an interval is given up, although the focus contract is in effect.
+    /// Nothing is typed into this prompt - it is confirmed or dismissed by a single key.
+    FocusContract,
+}
+
+/// Text which is being typed into a prompt of the client
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Prompt {
+    /// What the prompt asks for
+    pub kind: PromptKind,
+
+    /// Text which has been typed so far
+    pub text: String,
+}
+
+impl Prompt {
+    /// Empty prompt of the given kind
+    pub fn new(kind: PromptKind) -> Self {
+        Self {
+            kind,
+            text: String::new(),
+        }
+    }
+}
+
+impl Display for Prompt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.kind {
+            PromptKind::Reason => write!(f, "Reason: {}", self.text),
+            PromptKind::Task => write!(f, "Task: {}", self.text),
+            PromptKind::FocusContract => {
+                write!(f, "You promised to stay focused - give up anyway? [y/N]")
+            }
+        }
+    }
+}
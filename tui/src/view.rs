@@ -0,0 +1,99 @@
+//! Options which decide how the timer is laid out and colored
+
+use tui::style::Color;
+use zentime_rs_timer::status_template::StatusTemplate;
+
+/// Number of columns the [InterfaceLayout::Popup] takes up
+pub const POPUP_WIDTH: u16 = 48;
+
+/// Number of lines the [InterfaceLayout::Popup] takes up
+pub const POPUP_HEIGHT: u16 = 2;
+
+/// Layout of the interface
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InterfaceLayout {
+    /// Timer information and the timer side by side inside boxes, with the key bindings below
+    #[default]
+    Default,
+
+    /// A single line with the timer, the phase and the round - without any boxes or key bindings
+    Compact,
+
+    /// The line of the compact layout above a line of key bindings, inside a fixed area of
+    /// [POPUP_WIDTH] x [POPUP_HEIGHT] (e.g. for `tmux display-popup`)
+    Popup,
+}
+
+/// Colors of the interface
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    /// Phase label during focus intervals
+    pub focus: Color,
+
+    /// Phase label during breaks
+    pub break_phase: Color,
+
+    /// Phase label while a break is postponed
+    pub postponed: Color,
+
+    /// Remaining time
+    pub timer: Color,
+
+    /// Plain text (e.g. the title)
+    pub text: Color,
+
+    /// Round counter
+    pub round: Color,
+
+    /// Key bindings and the postpone count
+    pub hints: Color,
+
+    /// Errors and the connection status
+    pub error: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            focus: Color::Blue,
+            break_phase: Color::Yellow,
+            postponed: Color::Red,
+            timer: Color::Cyan,
+            text: Color::White,
+            round: Color::Gray,
+            hints: Color::DarkGray,
+            error: Color::Red,
+        }
+    }
+}
+
+/// Options of the interface, which stay the same while the client is attached
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ViewOptions {
+    /// Layout of the interface
+    pub layout: InterfaceLayout,
+
+    /// Shows the round counter (and the active profile and task)
+    pub show_round: bool,
+
+    /// Shows how often a break has been postponed
+    pub show_postpone_count: bool,
+
+    /// Colors of the interface
+    pub theme: Theme,
+
+    /// Replaces the line of the compact and popup layouts (e.g. `{round} {time} {state_icon}`)
+    pub format: Option<StatusTemplate>,
+}
+
+impl Default for ViewOptions {
+    fn default() -> Self {
+        Self {
+            layout: InterfaceLayout::default(),
+            show_round: true,
+            show_postpone_count: true,
+            theme: Theme::default(),
+            format: None,
+        }
+    }
+}
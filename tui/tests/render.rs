@@ -0,0 +1,248 @@
+//! Headless tests of the terminal interface, which is drawn into a virtual terminal
+
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use tui::{backend::TestBackend, Terminal};
+use zentime_rs_timer::pomodoro_timer::{DailyStats, NamedTimerState, StopwatchState, ViewState};
+use zentime_tui::{
+    render, ClientInputAction, ClientStatus, ConnectionStatus, InputHandler, InterfaceLayout,
+    PromptKind, ViewOptions, POPUP_WIDTH,
+};
+
+fn view_state() -> ViewState {
+    ViewState {
+        is_break: true,
+        is_postponed: true,
+        postpone_count: 2,
+        round: 3,
+        time: "04:59".to_string(),
+        is_paused: false,
+        hold: None,
+        reminder: None,
+        task: Some("Write docs".to_string()),
+        profile: Some("deep-work".to_string()),
+        banner: None,
+        today: None,
+        progress: None,
+        stopwatch: None,
+        pause_reason: None,
+        timers: vec![],
+        countdowns: vec![],
+    }
+}
+
+/// Renders a single frame and returns its lines (without trailing whitespace)
+fn render_lines(state: &ViewState, status: &ClientStatus, options: &ViewOptions) -> Vec<String> {
+    let mut terminal = Terminal::new(TestBackend::new(120, 14)).unwrap();
+    render(&mut terminal, state, status, options).unwrap();
+
+    let buffer = terminal.backend().buffer();
+    (0..buffer.area.height)
+        .map(|y| {
+            let line: String = (0..buffer.area.width)
+                .map(|x| buffer.get(x, y).symbol.as_str())
+                .collect();
+            line.trim_end().to_string()
+        })
+        .collect()
+}
+
+fn key(code: KeyCode) -> Event {
+    Event::Key(KeyEvent::new(code, KeyModifiers::NONE))
+}
+
+#[test]
+fn renders_default_layout() {
+    let screen = render_lines(
+        &view_state(),
+        &ClientStatus::default(),
+        &ViewOptions::default(),
+    )
+    .join("\n");
+
+    assert!(screen.contains("Break (2)"));
+    assert!(screen.contains("Round: 3 (deep-work) - Write docs"));
+    assert!(screen.contains("04:59"));
+    assert!(screen.contains("[P]ostpone"));
+}
+
+#[test]
+fn renders_progress_towards_the_daily_goal() {
+    let state = ViewState {
+        today: Some(DailyStats {
+            intervals: 3,
+            focused_minutes: 75,
+            goal: Some(8),
+        }),
+        ..view_state()
+    };
+
+    let screen = render_lines(&state, &ClientStatus::default(), &ViewOptions::default()).join("\n");
+
+    assert!(screen.contains("Round: 3 (deep-work) · 3/8 🍅"));
+    assert!(screen.contains("- Write docs"));
+}
+
+#[test]
+fn renders_compact_layout() {
+    let options = ViewOptions {
+        layout: InterfaceLayout::Compact,
+        show_postpone_count: false,
+        ..ViewOptions::default()
+    };
+    let status = ClientStatus {
+        connection: ConnectionStatus::Reconnecting { attempt: 2 },
+        ..ClientStatus::default()
+    };
+
+    let lines = render_lines(&view_state(), &status, &options);
+
+    assert_eq!(
+        lines[0].trim(),
+        "04:59  Break  Round: 3 (deep-work) - Write docs  reconnecting (2)..."
+    );
+    assert!(lines[1..].iter().all(|line| line.is_empty()));
+}
+
+#[test]
+fn renders_popup_layout() {
+    let options = ViewOptions {
+        layout: InterfaceLayout::Popup,
+        ..ViewOptions::default()
+    };
+
+    let lines = render_lines(&view_state(), &ClientStatus::default(), &options);
+
+    assert!(lines[0].starts_with("04:59  Break (2)  Round: 3"));
+    assert_eq!(lines[1], "Space: Play/Pause  [S]kip  [P]ostpone  [Q] Close");
+    assert!(lines
+        .iter()
+        .all(|line| line.chars().count() <= POPUP_WIDTH as usize));
+    assert!(lines[2..].iter().all(|line| line.is_empty()));
+
+    // Quitting only closes the popup
+    let mut input_handler = InputHandler::popup();
+    for code in [KeyCode::Char('q'), KeyCode::Esc] {
+        assert!(matches!(
+            input_handler.handle(key(code)).as_slice(),
+            [ClientInputAction::Detach]
+        ));
+    }
+}
+
+#[test]
+fn renders_stopwatch() {
+    let state = ViewState {
+        is_break: false,
+        is_postponed: false,
+        round: 2,
+        time: "42:10".to_string(),
+        profile: None,
+        stopwatch: Some(StopwatchState {
+            laps: vec!["30:05".to_string()],
+        }),
+        ..view_state()
+    };
+
+    let screen = render_lines(&state, &ClientStatus::default(), &ViewOptions::default()).join("\n");
+
+    assert!(screen.contains("Stopwatch"));
+    assert!(screen.contains("Lap: 2 (last: 30:05) - Write docs"));
+    assert!(screen.contains("42:10"));
+    assert!(screen.contains("[S] Lap"));
+}
+
+#[test]
+fn renders_selected_named_timer() {
+    let state = ViewState {
+        timers: vec![NamedTimerState {
+            name: "tea".to_string(),
+            time: "03:12".to_string(),
+            is_paused: false,
+            is_selected: true,
+        }],
+        ..view_state()
+    };
+
+    let screen = render_lines(&state, &ClientStatus::default(), &ViewOptions::default()).join("\n");
+
+    assert!(screen.contains("Timer: tea"));
+    assert!(screen.contains("03:12"));
+    assert!(!screen.contains("04:59"));
+    assert!(screen.contains("Tab: Next timer"));
+}
+
+#[test]
+fn renders_compact_line_from_format() {
+    let options = ViewOptions {
+        layout: InterfaceLayout::Compact,
+        format: Some(
+            "#{round} {time} [{phase}/{postpone_count}] {paused}{task}"
+                .parse()
+                .unwrap(),
+        ),
+        ..ViewOptions::default()
+    };
+
+    let lines = render_lines(&view_state(), &ClientStatus::default(), &options);
+
+    assert_eq!(lines[0].trim(), "#3 04:59 [break/2] Write docs");
+    assert!(!lines.join("\n").contains("Round: 3"));
+}
+
+#[test]
+fn renders_enforced_break_fullscreen() {
+    let status = ClientStatus {
+        enforced_break: true,
+        ..ClientStatus::default()
+    };
+    let options = ViewOptions {
+        layout: InterfaceLayout::Compact,
+        ..ViewOptions::default()
+    };
+
+    let lines = render_lines(&view_state(), &status, &options);
+    let screen = lines.join("\n");
+
+    // The countdown is drawn in large digits (top row of "04:59")
+    assert!(lines
+        .iter()
+        .any(|line| line.contains("██████  ██  ██      ██████  ██████")));
+    assert!(screen.contains("this break can't be skipped"));
+    assert!(!screen.contains("[S]kip"));
+    assert!(!screen.contains("[P]ostpone"));
+}
+
+#[test]
+fn types_into_prompts() {
+    let mut input_handler = InputHandler::new();
+
+    assert!(matches!(
+        input_handler.handle(key(KeyCode::Char('i'))).as_slice(),
+        [
+            ClientInputAction::Interrupt,
+            ClientInputAction::Prompt(Some(_))
+        ]
+    ));
+    assert_eq!(
+        input_handler.prompt().map(|prompt| prompt.kind),
+        Some(PromptKind::Reason)
+    );
+
+    // Keys are typed into the prompt instead of being mapped to actions
+    for character in "call".chars() {
+        input_handler.handle(key(KeyCode::Char(character)));
+    }
+
+    match input_handler.handle(key(KeyCode::Enter)).as_slice() {
+        [ClientInputAction::PauseReason(reason), ClientInputAction::Prompt(None)] => {
+            assert_eq!(reason, "call")
+        }
+        actions => panic!("Unexpected actions {:?}", actions),
+    }
+
+    assert!(input_handler.prompt().is_none());
+    assert!(matches!(
+        input_handler.handle(key(KeyCode::Char('s'))).as_slice(),
+        [ClientInputAction::Skip]
+    ));
+}